@@ -9,7 +9,10 @@ use magicblock_account_dumper::AccountDumperError;
 use magicblock_account_fetcher::AccountFetcherError;
 use magicblock_account_updates::AccountUpdatesError;
 use magicblock_core::magic_program;
-use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
+use solana_sdk::{
+    clock::Slot, precompiles::get_precompiles, pubkey::Pubkey,
+    signature::Signature,
+};
 use thiserror::Error;
 use tokio::sync::oneshot::Sender;
 
@@ -64,6 +67,35 @@ pub enum AccountClonerUnclonableReason {
     /// If an account is delegated to our validator then we should use the latest
     /// state in our own bank since that is more up to date than the on-chain state.
     DelegatedAccountsNotClonedWhileHydrating,
+    /// The account claims to be delegated to our validator, but its delegation
+    /// did not pass verification against the delegation program.
+    InvalidDelegation(InvalidDelegationReason),
+    /// The account was cloned as delegated to our validator, but its delegation
+    /// was revoked on chain without us undelegating it. Its local state is kept
+    /// as is and it cannot be written to until it is delegated to us again.
+    DelegationRevoked(DelegationRevocation),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidDelegationReason {
+    /// The delegation record names another validator as authority
+    DelegatedToOtherValidator { authority: Pubkey },
+    /// The delegation record was created after the slot the account state was
+    /// fetched at, thus the account state cannot be trusted
+    DelegatedAfterFetchedState {
+        delegation_slot: Slot,
+        at_slot: Slot,
+    },
+    /// The delegation metadata account is missing or cannot be parsed
+    InvalidDelegationMetadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DelegationRevocation {
+    /// The account is not delegated anymore
+    Undelegated,
+    /// The account is now delegated to another validator
+    DelegatedToOtherValidator { authority: Pubkey },
 }
 
 #[derive(Debug, Clone)]
@@ -115,6 +147,10 @@ pub fn standard_blacklisted_accounts(
     blacklisted_accounts.insert(solana_sdk::incinerator::ID);
     blacklisted_accounts.insert(solana_sdk::secp256k1_program::ID);
     blacklisted_accounts.insert(solana_sdk::ed25519_program::ID);
+    // Also covers precompiles that are only enabled via feature, i.e. secp256r1
+    for precompile in get_precompiles() {
+        blacklisted_accounts.insert(precompile.program_id);
+    }
     blacklisted_accounts.insert(solana_sdk::address_lookup_table::program::ID);
     blacklisted_accounts.insert(solana_sdk::config::program::ID);
     blacklisted_accounts.insert(solana_sdk::stake::program::ID);
@@ -137,6 +173,8 @@ pub fn standard_blacklisted_accounts(
     blacklisted_accounts.insert(NATIVE_SOL_ID);
     blacklisted_accounts.insert(magic_program::ID);
     blacklisted_accounts.insert(magic_program::MAGIC_CONTEXT_PUBKEY);
+    blacklisted_accounts.insert(magic_program::MAGIC_COMMIT_EVENTS_PUBKEY);
+    blacklisted_accounts.insert(magic_program::MAGIC_SESSION_KEYS_PUBKEY);
     blacklisted_accounts.insert(*validator_id);
     blacklisted_accounts.insert(*faucet_id);
     blacklisted_accounts
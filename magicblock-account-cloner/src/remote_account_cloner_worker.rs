@@ -2,7 +2,7 @@ use std::{
     cell::RefCell,
     collections::{hash_map::Entry, HashMap, HashSet},
     sync::{Arc, RwLock},
-    time::Duration,
+    time::{Duration, Instant},
     vec,
 };
 
@@ -10,18 +10,28 @@ use conjunto_transwise::{
     AccountChainSnapshot, AccountChainSnapshotShared, AccountChainState,
     DelegationRecord,
 };
+use dlp::state::DelegationMetadata;
 use futures_util::{
-    future::join_all,
-    stream::{self, StreamExt, TryStreamExt},
+    future::BoxFuture,
+    stream::{self, FuturesUnordered, StreamExt, TryStreamExt},
 };
 use log::*;
 use lru::LruCache;
 use magicblock_account_dumper::AccountDumper;
-use magicblock_account_fetcher::AccountFetcher;
+use magicblock_account_fetcher::{
+    delegation_metadata_pda, AccountFetcher, AccountFetcherError,
+};
 use magicblock_account_updates::{AccountUpdates, AccountUpdatesResult};
 use magicblock_accounts_api::InternalAccountProvider;
 use magicblock_metrics::metrics;
-use magicblock_mutator::idl::{get_pubkey_anchor_idl, get_pubkey_shank_idl};
+use magicblock_mutator::idl::{
+    get_pubkey_anchor_idl, get_pubkey_program_metadata_idl,
+    get_pubkey_shank_idl,
+};
+use solana_inline_spl::{
+    token::{self, GenericTokenAccount},
+    token_2022,
+};
 use solana_sdk::{
     account::{Account, ReadableAccount},
     bpf_loader_upgradeable::{self, get_program_data_address},
@@ -30,15 +40,20 @@ use solana_sdk::{
     signature::Signature,
 };
 use tokio::{
-    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    sync::{
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+        oneshot::channel,
+    },
     time::sleep,
 };
 use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use crate::{
     AccountClonerError, AccountClonerListeners, AccountClonerOutput,
     AccountClonerPermissions, AccountClonerResult,
-    AccountClonerUnclonableReason, CloneOutputMap,
+    AccountClonerUnclonableReason, CloneOutputMap, DelegationRevocation,
+    InvalidDelegationReason,
 };
 
 pub enum ValidatorStage {
@@ -106,13 +121,31 @@ pub struct RemoteAccountClonerWorker<IAP, AFE, AUP, ADU> {
     payer_init_lamports: Option<u64>,
     validator_charges_fees: ValidatorCollectionMode,
     permissions: AccountClonerPermissions,
+    clone_token_mints: bool,
+    clone_program_idls: bool,
+    max_concurrent_clones: usize,
+    /// Non-delegated accounts cloned longer ago than this are fetched again
+    /// before they are used
+    revalidate_after: Option<Duration>,
+    /// Delegated accounts are only cloned as writable once their delegation
+    /// was verified against the delegation program
+    verify_delegations: bool,
     fetch_retries: u64,
     clone_request_receiver: UnboundedReceiver<Pubkey>,
     clone_request_sender: UnboundedSender<Pubkey>,
     clone_listeners: Arc<RwLock<HashMap<Pubkey, AccountClonerListeners>>>,
+    /// Listeners of the clones currently running, either requested directly
+    /// or as the dependency of another account (i.e. the mint of a token
+    /// account), so that the same account is never cloned twice at once
+    running_clone_listeners: RwLock<HashMap<Pubkey, AccountClonerListeners>>,
     last_clone_output: CloneOutputMap,
+    /// When each account was last cloned, only tracked if
+    /// [Self::revalidate_after] is set
+    last_clone_time: RwLock<HashMap<Pubkey, Instant>>,
     validator_identity: Pubkey,
     monitored_accounts: RefCell<LruCache<Pubkey, ()>>,
+    /// Label of the remote endpoint accounts are cloned from in metrics
+    endpoint_label: String,
 }
 
 // SAFETY:
@@ -148,8 +181,14 @@ where
         payer_init_lamports: Option<u64>,
         validator_charges_fees: ValidatorCollectionMode,
         permissions: AccountClonerPermissions,
+        clone_token_mints: bool,
+        clone_program_idls: bool,
+        max_concurrent_clones: usize,
+        revalidate_after: Option<Duration>,
+        verify_delegations: bool,
         validator_authority: Pubkey,
         max_monitored_accounts: usize,
+        endpoint_label: String,
     ) -> Self {
         let (clone_request_sender, clone_request_receiver) =
             unbounded_channel();
@@ -167,13 +206,21 @@ where
             payer_init_lamports,
             validator_charges_fees,
             permissions,
+            clone_token_mints,
+            clone_program_idls,
+            max_concurrent_clones: max_concurrent_clones.max(1),
+            revalidate_after,
+            verify_delegations,
             fetch_retries,
             clone_request_receiver,
             clone_request_sender,
             clone_listeners: Default::default(),
+            running_clone_listeners: Default::default(),
             last_clone_output: Default::default(),
+            last_clone_time: Default::default(),
             validator_identity: validator_authority,
             monitored_accounts: LruCache::new(max_monitored_accounts).into(),
+            endpoint_label,
         }
     }
 
@@ -191,20 +238,35 @@ where
         self.clone_listeners.clone()
     }
 
+    /// Processes clone requests as they arrive, cloning up to
+    /// `max_concurrent_clones` accounts at once.
+    /// A new request is picked up as soon as any clone in flight completes
+    /// instead of waiting for all requests received with it.
     pub async fn start_clone_request_processing(
         mut self,
         cancellation_token: CancellationToken,
     ) {
-        let mut requests = vec![];
+        // The in-flight clones borrow the worker, thus we take the receiver
+        // out of it in order to keep receiving requests alongside them
+        let (_, closed_receiver) = unbounded_channel();
+        let mut clone_request_receiver = std::mem::replace(
+            &mut self.clone_request_receiver,
+            closed_receiver,
+        );
+        let mut in_flight = FuturesUnordered::new();
         loop {
             tokio::select! {
-                _ = self.clone_request_receiver.recv_many(&mut requests, 100) => {
-                    join_all(
-                        requests
-                            .drain(..)
-                            .map(|request| self.process_clone_request(request))
-                    ).await;
+                request = clone_request_receiver.recv(),
+                    if in_flight.len() < self.max_concurrent_clones =>
+                {
+                    match request {
+                        Some(pubkey) => {
+                            in_flight.push(self.process_clone_request(pubkey));
+                        }
+                        None => return,
+                    }
                 }
+                Some(()) = in_flight.next() => {}
                 _ = cancellation_token.cancelled() => {
                     return;
                 }
@@ -213,8 +275,9 @@ where
     }
 
     async fn process_clone_request(&self, pubkey: Pubkey) {
+        metrics::inc_account_clone_requests(&self.endpoint_label);
         // Actually run the whole cloning process on the bank, yield until done
-        let result = self.do_clone_or_use_cache(&pubkey).await;
+        let result = self.do_clone_or_join_running(&pubkey).await;
         // Collecting the list of listeners awaiting for the clone to be done
         let listeners = match self.clone_listeners
             .write()
@@ -233,9 +296,55 @@ where
         // Notify every listeners of the clone's result
         for listener in listeners {
             if let Err(error) = listener.send(result.clone()) {
-                error!("Could not send clone result: {}: {:?}", pubkey, error);
+                error!(pubkey:% = pubkey; "Could not send clone result: {}: {:?}", pubkey, error);
+            }
+        }
+    }
+
+    /// Clones the account, unless a clone of it is already running in which
+    /// case we wait for that one to complete and share its result.
+    /// Only clones that already started are joined, waiting on a queued
+    /// request could otherwise hold up all clone slots.
+    async fn do_clone_or_join_running(
+        &self,
+        pubkey: &Pubkey,
+    ) -> AccountClonerResult<AccountClonerOutput> {
+        let receiver = match self
+            .running_clone_listeners
+            .write()
+            .expect(
+                "RwLock of RemoteAccountClonerWorker.running_clone_listeners is poisoned",
+            )
+            .entry(*pubkey)
+        {
+            Entry::Vacant(entry) => {
+                entry.insert(vec![]);
+                None
+            }
+            Entry::Occupied(mut entry) => {
+                let (sender, receiver) = channel();
+                entry.get_mut().push(sender);
+                Some(receiver)
+            }
+        };
+        if let Some(receiver) = receiver {
+            return receiver.await?;
+        }
+        let result = self.do_clone_or_use_cache(pubkey).await;
+        let listeners = self
+            .running_clone_listeners
+            .write()
+            .expect(
+                "RwLock of RemoteAccountClonerWorker.running_clone_listeners is poisoned",
+            )
+            .remove(pubkey)
+            .unwrap_or_default();
+        for listener in listeners {
+            if let Err(error) = listener.send(result.clone()) {
+                error!(pubkey:% = pubkey; "Could not send clone result: {}: {:?}", pubkey, error);
             }
         }
+        result
     }
 
     fn can_clone(&self) -> bool {
@@ -316,7 +425,7 @@ where
                         Ok(())
                     }
                     Err(err) => {
-                        error!("Failed to clone {} ('{:?}')", pubkey, err);
+                        error!(pubkey:% = pubkey; "Failed to clone {} ('{:?}')", pubkey, err);
                         // NOTE: the account fetch already has retries built in, so
                         // we don't to retry here
 
@@ -358,9 +467,13 @@ where
                 } => {
                     // If the clone output is recent enough,
                     // or the account is a feepayer, we don't clone again
-                    if snapshot.at_slot >= last_known_update_slot
+                    if (snapshot.at_slot >= last_known_update_slot
+                        && !self.needs_revalidation(pubkey, snapshot))
                         || snapshot.chain_state.is_feepayer()
                     {
+                        metrics::inc_account_clone_cache_hits(
+                            &self.endpoint_label,
+                        );
                         Ok(last_clone_output)
                     }
                     // If the cloned account has been updated since clone, update the cache
@@ -379,6 +492,9 @@ where
                 } => {
                     // If the clone output is recent enough, use that
                     if *until_slot >= last_known_update_slot {
+                        metrics::inc_account_clone_cache_hits(
+                            &self.endpoint_label,
+                        );
                         Ok(last_clone_output)
                     }
                     // If the cloned account has been updated since clone, try to update the cache
@@ -418,7 +534,17 @@ where
         pubkey: &Pubkey,
         stage: ValidatorStage,
     ) -> AccountClonerResult<AccountClonerOutput> {
-        let updated_clone_output = self.do_clone(pubkey, stage).await?;
+        let started = Instant::now();
+        let result = self
+            .do_clone(pubkey, stage)
+            .instrument(tracing::info_span!("clone_account", %pubkey))
+            .await;
+        metrics::observe_account_clone_time(
+            &self.endpoint_label,
+            metrics::Outcome::from_success(result.is_ok()),
+            started.elapsed(),
+        );
+        let updated_clone_output = result?;
         self.last_clone_output
             .write()
             .expect("RwLock of RemoteAccountClonerWorker.last_clone_output is poisoned")
@@ -426,9 +552,50 @@ where
         if let Ok(map) = self.last_clone_output.read() {
             metrics::set_cached_clone_outputs_count(map.len());
         }
+        if self.revalidate_after.is_some() {
+            self.last_clone_time
+                .write()
+                .expect("RwLock of RemoteAccountClonerWorker.last_clone_time is poisoned")
+                .insert(*pubkey, Instant::now());
+        }
         Ok(updated_clone_output)
     }
 
+    /// Determines if a cloned non-delegated account needs to be fetched
+    /// again since it was cloned too long ago.
+    /// Its updates are observed via subscriptions, however a missed update
+    /// would otherwise leave a stale clone around indefinitely.
+    fn needs_revalidation(
+        &self,
+        pubkey: &Pubkey,
+        snapshot: &AccountChainSnapshot,
+    ) -> bool {
+        let Some(revalidate_after) = self.revalidate_after else {
+            return false;
+        };
+        // Programs are expensive to clone and get redeployed rarely, while
+        // delegated accounts are owned by us
+        let is_readonly_data = matches!(
+            &snapshot.chain_state,
+            AccountChainState::Undelegated { account, .. } if !account.executable
+        );
+        let needs_revalidation = is_readonly_data
+            && self
+                .last_clone_time
+                .read()
+                .expect("RwLock of RemoteAccountClonerWorker.last_clone_time is poisoned")
+                .get(pubkey)
+                .is_some_and(|cloned_at| cloned_at.elapsed() > revalidate_after);
+        if needs_revalidation {
+            debug!(
+                "Revalidating account '{}' cloned at slot {}",
+                pubkey, snapshot.at_slot
+            );
+            metrics::inc_revalidated_accounts_count();
+        }
+        needs_revalidation
+    }
+
     /// Put the account's key into cache of monitored accounts, which has a limited capacity.
     /// Once the cache capacity exceeds the preconfigured limit, it will trigger an eviction,
     /// followed by account's removal from AccountsDB and termination of its ws subscription
@@ -446,6 +613,10 @@ where
                 .write()
                 .expect("last accounts clone output map is poisoned")
                 .remove(&evicted);
+            self.last_clone_time
+                .write()
+                .expect("last accounts clone time map is poisoned")
+                .remove(&evicted);
             self.internal_account_provider.remove_account(&evicted);
             self.clone_listeners
                 .write()
@@ -521,6 +692,12 @@ where
                             };
                         }
                     }
+                    // Retrying is pointless while the remote is down
+                    Err(
+                        error @ AccountClonerError::AccountFetcherError(
+                            AccountFetcherError::RemoteUnavailable(_),
+                        ),
+                    ) => return Err(error),
                     Err(error) => {
                         // If we failed to fetch too many time, stop here
                         if fetch_count >= self.fetch_retries {
@@ -534,6 +711,24 @@ where
         } else {
             self.fetch_account_chain_snapshot(pubkey, None).await?
         };
+        // If the delegation to us was revoked on chain, the local state must neither be
+        // overridden nor be written to anymore
+        if let Some(revocation) =
+            self.detect_delegation_revocation(pubkey, &account_chain_snapshot)
+        {
+            warn!(
+                "Delegation of account '{}' was revoked on chain, freezing it: {:?}",
+                pubkey, revocation
+            );
+            metrics::inc_revoked_delegations_count();
+            return Ok(AccountClonerOutput::Unclonable {
+                pubkey: *pubkey,
+                reason: AccountClonerUnclonableReason::DelegationRevoked(
+                    revocation,
+                ),
+                at_slot: account_chain_snapshot.at_slot,
+            });
+        }
         // Generate cloning transactions
         let signature = match &account_chain_snapshot.chain_state {
             // If the account is a fee payer, we clone it assigning the init lamports of
@@ -708,6 +903,32 @@ where
                     });
                 }
 
+                if self.verify_delegations {
+                    if let Some(reason) = self
+                        .verify_delegation(
+                            pubkey,
+                            &account.owner,
+                            delegation_record,
+                            account_chain_snapshot.at_slot,
+                        )
+                        .await?
+                    {
+                        warn!(
+                            "Refusing to clone delegated account '{}': {:?}",
+                            pubkey, reason
+                        );
+                        metrics::inc_invalid_delegations_count();
+                        return Ok(AccountClonerOutput::Unclonable {
+                            pubkey: *pubkey,
+                            reason:
+                                AccountClonerUnclonableReason::InvalidDelegation(
+                                    reason,
+                                ),
+                            at_slot: account_chain_snapshot.at_slot,
+                        });
+                    }
+                }
+
                 self.do_clone_delegated_account(
                     pubkey,
                     // TODO(GabrielePicco): Avoid cloning
@@ -719,6 +940,14 @@ where
                 )?
             }
         };
+        // Programs operating on token accounts commonly need their mint as well
+        // which clients rarely reference directly, thus we clone it alongside
+        if self.clone_token_mints {
+            if let Some(account) = account_chain_snapshot.chain_state.account()
+            {
+                self.clone_token_account_mint(pubkey, account).await;
+            }
+        }
         // Return the result
         Ok(AccountClonerOutput::Cloned {
             account_chain_snapshot,
@@ -726,6 +955,36 @@ where
         })
     }
 
+    async fn clone_token_account_mint(
+        &self,
+        pubkey: &Pubkey,
+        account: &Account,
+    ) {
+        let Some(mint) = token_account_mint(account) else {
+            return;
+        };
+        // The future needs to be boxed since we recurse into the cloning pipeline
+        // Token accounts sharing a mint wait for the same clone of it
+        let clone_mint: BoxFuture<AccountClonerResult<AccountClonerOutput>> =
+            Box::pin(self.do_clone_or_join_running(&mint));
+        match clone_mint.await {
+            Ok(AccountClonerOutput::Cloned { .. }) => {}
+            Ok(AccountClonerOutput::Unclonable { reason, .. }) => {
+                debug!(
+                    "Mint '{}' of token account '{}' is unclonable: {:?}",
+                    mint, pubkey, reason
+                );
+            }
+            // The token account itself is usable, so we don't fail its clone
+            Err(error) => {
+                warn!(
+                    "Failed to clone mint '{}' of token account '{}': {:?}",
+                    mint, pubkey, error
+                );
+            }
+        }
+    }
+
     fn do_clone_feepayer_account(
         &self,
         pubkey: &Pubkey,
@@ -834,31 +1093,36 @@ where
             return Ok(signature);
         }
 
+        // The program data and the IDLs don't depend on each other, thus we
+        // fetch them concurrently
         let program_data_pubkey = &get_program_data_address(program_id_pubkey);
-        let program_data_snapshot = self
-            .fetch_account_chain_snapshot(program_data_pubkey, min_context_slot)
-            .await?;
+        let (program_data_snapshot, idl_accounts) = tokio::join!(
+            self.fetch_account_chain_snapshot(
+                program_data_pubkey,
+                min_context_slot
+            ),
+            async {
+                if self.clone_program_idls {
+                    self.fetch_program_idls(program_id_pubkey, min_context_slot)
+                        .await
+                } else {
+                    Ok(vec![])
+                }
+            }
+        );
+        let program_data_snapshot = program_data_snapshot?;
         let program_data_account = program_data_snapshot
             .chain_state
             .account()
             .ok_or(AccountClonerError::ProgramDataDoesNotExist)?;
-        let idl_account = match self
-            .fetch_program_idl(program_id_pubkey, min_context_slot)
-            .await?
-        {
-            // Only add the IDL account if it exists on chain
-            Some((pubkey, account)) if account.lamports > 0 => {
-                Some((pubkey, account))
-            }
-            _ => None,
-        };
+        let idl_accounts = idl_accounts?;
         self.account_dumper
             .dump_program_accounts(
                 program_id_pubkey,
                 program_id_account,
                 program_data_pubkey,
                 program_data_account,
-                idl_account,
+                idl_accounts,
             )
             .map_err(AccountClonerError::AccountDumperError)
             .inspect(|_| {
@@ -868,6 +1132,42 @@ where
             })
     }
 
+    /// Fetches the IDL accounts of the program that exist on chain, which are
+    /// the anchor (or shank) IDL and the IDL stored via the program metadata
+    /// program
+    async fn fetch_program_idls(
+        &self,
+        program_id_pubkey: &Pubkey,
+        min_context_slot: Option<Slot>,
+    ) -> AccountClonerResult<Vec<(Pubkey, Account)>> {
+        let (idl_account, metadata_idl_account) = tokio::join!(
+            self.fetch_program_idl(program_id_pubkey, min_context_slot),
+            self.try_fetch_program_idl_snapshot(
+                Some(get_pubkey_program_metadata_idl(program_id_pubkey)),
+                min_context_slot,
+            )
+        );
+        let mut idl_accounts = vec![];
+        if let Some(idl_account) = idl_account? {
+            idl_accounts.push(idl_account);
+        }
+        // The metadata IDL is optional, thus we don't fail cloning the
+        // program if we cannot fetch it
+        match metadata_idl_account {
+            Ok(Some(idl_account)) => idl_accounts.push(idl_account),
+            Ok(None) => {}
+            Err(err) => {
+                warn!(
+                    "Failed to fetch metadata IDL of program '{}': {:?}",
+                    program_id_pubkey, err
+                );
+            }
+        }
+        // Only add the IDL accounts that exist on chain
+        idl_accounts.retain(|(_, account)| account.lamports > 0);
+        Ok(idl_accounts)
+    }
+
     async fn fetch_program_idl(
         &self,
         program_id_pubkey: &Pubkey,
@@ -932,6 +1232,109 @@ where
             .map_err(AccountClonerError::AccountFetcherError)
     }
 
+    /// Checks the delegation of an account against the delegation program,
+    /// returns the reason if the delegation is not valid for this validator
+    async fn verify_delegation(
+        &self,
+        pubkey: &Pubkey,
+        delegation_program_id: &Pubkey,
+        delegation_record: &DelegationRecord,
+        at_slot: Slot,
+    ) -> AccountClonerResult<Option<InvalidDelegationReason>> {
+        // TODO(GabrielePicco): remove the Pubkey::default() option once we enforce the authority to be always set
+        if delegation_record.authority != self.validator_identity
+            && delegation_record.authority != Pubkey::default()
+        {
+            return Ok(Some(
+                InvalidDelegationReason::DelegatedToOtherValidator {
+                    authority: delegation_record.authority,
+                },
+            ));
+        }
+        if delegation_record.delegation_slot > at_slot {
+            return Ok(Some(
+                InvalidDelegationReason::DelegatedAfterFetchedState {
+                    delegation_slot: delegation_record.delegation_slot,
+                    at_slot,
+                },
+            ));
+        }
+        let metadata_snapshot = self
+            .fetch_account_chain_snapshot(
+                &delegation_metadata_pda(pubkey, delegation_program_id),
+                Some(at_slot),
+            )
+            .await?;
+        let has_valid_metadata = metadata_snapshot
+            .chain_state
+            .account()
+            .is_some_and(|account| {
+                DelegationMetadata::try_from_bytes_with_discriminator(
+                    &account.data,
+                )
+                .is_ok()
+            });
+        if !has_valid_metadata {
+            return Ok(Some(
+                InvalidDelegationReason::InvalidDelegationMetadata,
+            ));
+        }
+        Ok(None)
+    }
+
+    /// Detects if an account we cloned as delegated to us got undelegated or delegated
+    /// to another validator on chain without us undelegating it.
+    /// Accounts we undelegated ourselves are locked to the delegation program locally.
+    fn detect_delegation_revocation(
+        &self,
+        pubkey: &Pubkey,
+        account_chain_snapshot: &AccountChainSnapshot,
+    ) -> Option<DelegationRevocation> {
+        match self.get_last_clone_output_from_pubkey(pubkey)? {
+            AccountClonerOutput::Cloned {
+                account_chain_snapshot: previous_snapshot,
+                ..
+            } => {
+                let AccountChainState::Delegated { account, .. } =
+                    &previous_snapshot.chain_state
+                else {
+                    return None;
+                };
+                let undelegated_by_us = self
+                    .internal_account_provider
+                    .get_account(pubkey)
+                    .is_some_and(|local| local.owner() == &account.owner);
+                if undelegated_by_us {
+                    return None;
+                }
+            }
+            // Once revoked, the account stays frozen until it is delegated to us again
+            AccountClonerOutput::Unclonable {
+                reason: AccountClonerUnclonableReason::DelegationRevoked(_),
+                ..
+            } => {}
+            AccountClonerOutput::Unclonable { .. } => return None,
+        }
+        match &account_chain_snapshot.chain_state {
+            AccountChainState::Delegated {
+                delegation_record, ..
+            } => {
+                // TODO(GabrielePicco): remove the Pubkey::default() option once we enforce the authority to be always set
+                if self.verify_delegations
+                    && delegation_record.authority != self.validator_identity
+                    && delegation_record.authority != Pubkey::default()
+                {
+                    Some(DelegationRevocation::DelegatedToOtherValidator {
+                        authority: delegation_record.authority,
+                    })
+                } else {
+                    None
+                }
+            }
+            _ => Some(DelegationRevocation::Undelegated),
+        }
+    }
+
     async fn try_fetch_feepayer_chain_snapshot(
         &self,
         feepayer: &Pubkey,
@@ -972,3 +1375,14 @@ where
             .cloned()
     }
 }
+
+/// Returns the mint of an SPL token or token-2022 account
+fn token_account_mint(account: &Account) -> Option<Pubkey> {
+    if account.owner == token::id() {
+        token::Account::unpack_account_mint(&account.data).copied()
+    } else if account.owner == token_2022::id() {
+        token_2022::Account::unpack_account_mint(&account.data).copied()
+    } else {
+        None
+    }
+}
@@ -1,17 +1,23 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
+use futures_util::future::join_all;
 use magicblock_account_cloner::{
     standard_blacklisted_accounts, AccountCloner, AccountClonerError,
     AccountClonerOutput, AccountClonerPermissions,
-    AccountClonerUnclonableReason, RemoteAccountClonerClient,
+    AccountClonerUnclonableReason, DelegationRevocation,
+    InvalidDelegationReason, RemoteAccountClonerClient,
     RemoteAccountClonerWorker, ValidatorCollectionMode,
 };
 use magicblock_account_dumper::AccountDumperStub;
 use magicblock_account_fetcher::AccountFetcherStub;
 use magicblock_account_updates::AccountUpdatesStub;
 use magicblock_accounts_api::InternalAccountProviderStub;
-use magicblock_mutator::idl::{get_pubkey_anchor_idl, get_pubkey_shank_idl};
+use magicblock_mutator::idl::{
+    get_pubkey_anchor_idl, get_pubkey_program_metadata_idl,
+    get_pubkey_shank_idl,
+};
 use solana_sdk::{
+    account::AccountSharedData,
     bpf_loader_upgradeable::get_program_data_address,
     native_token::LAMPORTS_PER_SOL,
     pubkey::Pubkey,
@@ -29,6 +35,9 @@ fn setup_custom(
     allowed_program_ids: Option<HashSet<Pubkey>>,
     blacklisted_accounts: HashSet<Pubkey>,
     permissions: AccountClonerPermissions,
+    clone_program_idls: bool,
+    verify_delegations: bool,
+    max_concurrent_clones: usize,
 ) -> (
     RemoteAccountClonerClient,
     CancellationToken,
@@ -47,8 +56,14 @@ fn setup_custom(
         payer_init_lamports,
         ValidatorCollectionMode::NoFees,
         permissions,
+        true,
+        clone_program_idls,
+        max_concurrent_clones,
+        None,
+        verify_delegations,
         Pubkey::new_unique(),
         1024,
+        "test".to_string(),
     );
     let cloner_client = RemoteAccountClonerClient::new(&cloner_worker);
     // Run the worker in a separate task
@@ -92,6 +107,9 @@ fn setup_replica(
             allow_cloning_delegated_accounts: true,
             allow_cloning_program_accounts: true,
         },
+        true,
+        false,
+        32,
     )
 }
 
@@ -123,6 +141,9 @@ fn setup_programs_replica(
             allow_cloning_delegated_accounts: false,
             allow_cloning_program_accounts: true,
         },
+        true,
+        false,
+        32,
     )
 }
 
@@ -154,6 +175,9 @@ fn setup_ephemeral(
             allow_cloning_delegated_accounts: true,
             allow_cloning_program_accounts: true,
         },
+        true,
+        false,
+        32,
     )
 }
 
@@ -185,6 +209,9 @@ fn setup_offline(
             allow_cloning_delegated_accounts: false,
             allow_cloning_program_accounts: false,
         },
+        true,
+        false,
+        32,
     )
 }
 
@@ -252,6 +279,141 @@ async fn test_clone_allow_undelegated_account_when_ephemeral() {
     assert!(worker_handle.await.is_ok());
 }
 
+#[tokio::test]
+async fn test_clone_token_account_with_its_mint_when_ephemeral() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_ephemeral(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+    );
+    // Account(s) involved
+    let token_account = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    account_updates.set_first_subscribed_slot(token_account, 41);
+    account_updates.set_first_subscribed_slot(mint, 41);
+    account_fetcher.set_undelegated_token_account(token_account, mint, 42);
+    account_fetcher.set_undelegated_account(mint, 42);
+    // Run test
+    let result = cloner.clone_account(&token_account).await;
+    // Check expected result
+    assert!(matches!(result, Ok(AccountClonerOutput::Cloned { .. })));
+    assert!(account_dumper.was_dumped_as_undelegated_account(&token_account));
+    assert_eq!(account_fetcher.get_fetch_count(&mint), 1);
+    assert!(account_updates.has_account_monitoring(&mint));
+    assert!(account_dumper.was_dumped_as_undelegated_account(&mint));
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_clone_token_accounts_sharing_a_mint_clones_it_once() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_ephemeral(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+    );
+    // Account(s) involved, fetches are slow so both mint clones overlap
+    let token_account1 = Pubkey::new_unique();
+    let token_account2 = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    account_updates.set_first_subscribed_slot(token_account1, 41);
+    account_updates.set_first_subscribed_slot(token_account2, 41);
+    account_updates.set_first_subscribed_slot(mint, 41);
+    account_fetcher.set_undelegated_token_account(token_account1, mint, 42);
+    account_fetcher.set_undelegated_token_account(token_account2, mint, 42);
+    account_fetcher.set_undelegated_account(mint, 42);
+    account_fetcher.set_fetch_delay(Duration::from_millis(50));
+    // Run test
+    let (result1, result2) = tokio::join!(
+        cloner.clone_account(&token_account1),
+        cloner.clone_account(&token_account2),
+    );
+    // Check expected result
+    assert!(matches!(result1, Ok(AccountClonerOutput::Cloned { .. })));
+    assert!(matches!(result2, Ok(AccountClonerOutput::Cloned { .. })));
+    assert_eq!(account_fetcher.get_fetch_count(&token_account1), 1);
+    assert_eq!(account_fetcher.get_fetch_count(&token_account2), 1);
+    assert_eq!(account_fetcher.get_fetch_count(&mint), 1);
+    assert!(account_dumper.was_dumped_as_undelegated_account(&mint));
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_clone_runs_at_most_max_concurrent_clones_at_once() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_custom(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+        standard_blacklisted_accounts(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        ),
+        AccountClonerPermissions {
+            allow_cloning_refresh: true,
+            allow_cloning_feepayer_accounts: true,
+            allow_cloning_undelegated_accounts: true,
+            allow_cloning_delegated_accounts: true,
+            allow_cloning_program_accounts: true,
+        },
+        true,
+        false,
+        2,
+    );
+    // Account(s) involved, fetches are slow so the clones overlap
+    let undelegated_accounts =
+        (0..6).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    for undelegated_account in &undelegated_accounts {
+        account_updates.set_first_subscribed_slot(*undelegated_account, 41);
+        account_fetcher.set_undelegated_account(*undelegated_account, 42);
+    }
+    account_fetcher.set_fetch_delay(Duration::from_millis(50));
+    // Run test
+    let results =
+        join_all(undelegated_accounts.iter().map(|undelegated_account| {
+            cloner.clone_account(undelegated_account)
+        }))
+        .await;
+    // Check expected result
+    for result in results {
+        assert!(matches!(result, Ok(AccountClonerOutput::Cloned { .. })));
+    }
+    for undelegated_account in &undelegated_accounts {
+        assert_eq!(account_fetcher.get_fetch_count(undelegated_account), 1);
+    }
+    // The accounts are fetched in parallel, but never more than allowed
+    assert_eq!(account_fetcher.get_max_concurrent_fetches(), 2);
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
 #[tokio::test]
 async fn test_clone_fails_stale_undelegated_account_when_ephemeral() {
     // Stubs
@@ -317,6 +479,58 @@ async fn test_clone_allow_delegated_account_when_ephemeral() {
     assert!(worker_handle.await.is_ok());
 }
 
+#[tokio::test]
+async fn test_clone_refuse_delegated_account_with_invalid_delegation() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_custom(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+        standard_blacklisted_accounts(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        ),
+        AccountClonerPermissions {
+            allow_cloning_refresh: true,
+            allow_cloning_feepayer_accounts: true,
+            allow_cloning_undelegated_accounts: true,
+            allow_cloning_delegated_accounts: true,
+            allow_cloning_program_accounts: true,
+        },
+        true,
+        true,
+        32,
+    );
+    // Account(s) involved, the stub delegates to some other validator
+    let delegated_account = Pubkey::new_unique();
+    account_updates.set_first_subscribed_slot(delegated_account, 41);
+    account_fetcher.set_delegated_account(delegated_account, 42, 11);
+    // Run test
+    let result = cloner.clone_account(&delegated_account).await;
+    // Check expected result
+    assert!(matches!(
+        result,
+        Ok(AccountClonerOutput::Unclonable {
+            reason: AccountClonerUnclonableReason::InvalidDelegation(
+                InvalidDelegationReason::DelegatedToOtherValidator { .. }
+            ),
+            ..
+        })
+    ));
+    assert_eq!(account_fetcher.get_fetch_count(&delegated_account), 1);
+    assert!(account_dumper.was_untouched(&delegated_account));
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
 #[tokio::test]
 async fn test_clone_allow_program_accounts_when_ephemeral() {
     // Stubs
@@ -366,6 +580,100 @@ async fn test_clone_allow_program_accounts_when_ephemeral() {
     assert!(worker_handle.await.is_ok());
 }
 
+#[tokio::test]
+async fn test_clone_program_accounts_with_metadata_idl_when_ephemeral() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_ephemeral(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+    );
+    // Account(s) involved
+    let program_id = Pubkey::new_unique();
+    let program_data = get_program_data_address(&program_id);
+    let program_anchor = get_pubkey_anchor_idl(&program_id).unwrap();
+    let program_metadata = get_pubkey_program_metadata_idl(&program_id);
+    account_updates.set_first_subscribed_slot(program_id, 41);
+    account_fetcher.set_executable_account(program_id, 42);
+    account_fetcher.set_undelegated_account(program_data, 42);
+    account_fetcher.set_undelegated_account(program_anchor, 42);
+    account_fetcher.set_undelegated_account(program_metadata, 42);
+    // Run test
+    let result = cloner.clone_account(&program_id).await;
+    // Check expected result
+    assert!(matches!(result, Ok(AccountClonerOutput::Cloned { .. })));
+    assert!(account_dumper.was_dumped_as_program_id(&program_id));
+    assert!(account_dumper.was_dumped_as_program_data(&program_data));
+    assert!(account_dumper.was_dumped_as_program_idl(&program_anchor));
+    assert_eq!(account_fetcher.get_fetch_count(&program_metadata), 1);
+    assert!(!account_updates.has_account_monitoring(&program_metadata));
+    assert!(account_dumper.was_dumped_as_program_idl(&program_metadata));
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_clone_program_accounts_without_idls() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_custom(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+        standard_blacklisted_accounts(
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        ),
+        AccountClonerPermissions {
+            allow_cloning_refresh: true,
+            allow_cloning_feepayer_accounts: true,
+            allow_cloning_undelegated_accounts: true,
+            allow_cloning_delegated_accounts: true,
+            allow_cloning_program_accounts: true,
+        },
+        false,
+        false,
+        32,
+    );
+    // Account(s) involved
+    let program_id = Pubkey::new_unique();
+    let program_data = get_program_data_address(&program_id);
+    let program_anchor = get_pubkey_anchor_idl(&program_id).unwrap();
+    let program_metadata = get_pubkey_program_metadata_idl(&program_id);
+    account_updates.set_first_subscribed_slot(program_id, 41);
+    account_fetcher.set_executable_account(program_id, 42);
+    account_fetcher.set_undelegated_account(program_data, 42);
+    account_fetcher.set_undelegated_account(program_anchor, 42);
+    account_fetcher.set_undelegated_account(program_metadata, 42);
+    // Run test
+    let result = cloner.clone_account(&program_id).await;
+    // Check expected result
+    assert!(matches!(result, Ok(AccountClonerOutput::Cloned { .. })));
+    assert!(account_dumper.was_dumped_as_program_id(&program_id));
+    assert!(account_dumper.was_dumped_as_program_data(&program_data));
+    assert_eq!(account_fetcher.get_fetch_count(&program_anchor), 0);
+    assert!(account_dumper.was_untouched(&program_anchor));
+    assert_eq!(account_fetcher.get_fetch_count(&program_metadata), 0);
+    assert!(account_dumper.was_untouched(&program_metadata));
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
 #[tokio::test]
 async fn test_clone_program_accounts_when_ephemeral_with_whitelist() {
     // Important pubkeys
@@ -1044,20 +1352,24 @@ async fn test_clone_properly_cached_delegated_account_that_changes_state() {
     assert_eq!(account_fetcher.get_fetch_count(&undelegated_account), 2);
     assert!(account_updates.has_account_monitoring(&undelegated_account));
     assert!(account_dumper.was_untouched(&undelegated_account));
-    // The account is now updated remotely (AND IT BECOMES UNDELEGATED)
+    // The account is now updated remotely (AND IT BECOMES UNDELEGATED without us undelegating it)
     account_updates.set_last_known_update_slot(undelegated_account, 77);
     account_fetcher.set_undelegated_account(undelegated_account, 77);
-    // Run test (now we MUST RE-DUMP as an undelegated account)
+    // Run test (now we MUST NOT RE-DUMP and freeze the account instead)
     let result4 = cloner.clone_account(&undelegated_account).await;
     // Check expected result4
-    assert!(matches!(result4, Ok(AccountClonerOutput::Cloned { .. })));
+    assert!(matches!(
+        result4,
+        Ok(AccountClonerOutput::Unclonable {
+            reason: AccountClonerUnclonableReason::DelegationRevoked(
+                DelegationRevocation::Undelegated
+            ),
+            ..
+        })
+    ));
     assert_eq!(account_fetcher.get_fetch_count(&undelegated_account), 3);
     assert!(account_updates.has_account_monitoring(&undelegated_account));
-    assert!(
-        account_dumper.was_dumped_as_undelegated_account(&undelegated_account)
-    );
-    // Clear dump history
-    account_dumper.clear_history();
+    assert!(account_dumper.was_untouched(&undelegated_account));
     // The account is now updated remotely (AND IT BECOMES RE-DELEGATED)
     account_updates.set_last_known_update_slot(undelegated_account, 88);
     account_fetcher.set_delegated_account(undelegated_account, 88, 88);
@@ -1089,6 +1401,53 @@ async fn test_clone_properly_cached_delegated_account_that_changes_state() {
     assert!(worker_handle.await.is_ok());
 }
 
+#[tokio::test]
+async fn test_clone_properly_cached_delegated_account_undelegated_by_us() {
+    // Stubs
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+    // Create account cloner worker and client
+    let (cloner, cancellation_token, worker_handle) = setup_ephemeral(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+        None,
+    );
+    // Account(s) involved
+    let delegated_account = Pubkey::new_unique();
+    account_updates.set_first_subscribed_slot(delegated_account, 41);
+    account_fetcher.set_delegated_account(delegated_account, 42, 11);
+    // Run test (we clone the account for the first time as delegated)
+    let result1 = cloner.clone_account(&delegated_account).await;
+    // Check expected result1
+    assert!(matches!(result1, Ok(AccountClonerOutput::Cloned { .. })));
+    assert!(account_dumper.was_dumped_as_delegated_account(&delegated_account));
+    // Clear dump history
+    account_dumper.clear_history();
+    // We undelegate the account, which locks it to the delegation program locally
+    internal_account_provider.set(
+        delegated_account,
+        AccountSharedData::new(1000, 0, &Pubkey::default()),
+    );
+    // The undelegation lands on chain
+    account_updates.set_last_known_update_slot(delegated_account, 77);
+    account_fetcher.set_undelegated_account(delegated_account, 77);
+    // Run test (now we MUST RE-DUMP as an undelegated account)
+    let result2 = cloner.clone_account(&delegated_account).await;
+    // Check expected result2
+    assert!(matches!(result2, Ok(AccountClonerOutput::Cloned { .. })));
+    assert_eq!(account_fetcher.get_fetch_count(&delegated_account), 2);
+    assert!(
+        account_dumper.was_dumped_as_undelegated_account(&delegated_account)
+    );
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
 #[tokio::test]
 async fn test_clone_properly_upgrading_downgrading_when_created_and_deleted() {
     // Stubs
@@ -1127,6 +1486,11 @@ async fn test_clone_properly_upgrading_downgrading_when_created_and_deleted() {
     assert_eq!(account_fetcher.get_fetch_count(&undelegated_account), 1);
     assert!(account_updates.has_account_monitoring(&undelegated_account));
     assert!(account_dumper.was_untouched(&undelegated_account));
+    // We undelegate the account, which locks it to the delegation program locally
+    internal_account_provider.set(
+        undelegated_account,
+        AccountSharedData::new(1000, 0, &Pubkey::default()),
+    );
     // The account is now updated remotely, as it becomes an undelegated account
     account_fetcher.set_undelegated_account(undelegated_account, 66);
     account_updates.set_last_known_update_slot(undelegated_account, 66);
@@ -52,7 +52,7 @@ pub trait AccountDumper {
         program_id_account: &Account,
         program_data: &Pubkey,
         program_data_account: &Account,
-        program_idl: Option<(Pubkey, Account)>,
+        program_idls: Vec<(Pubkey, Account)>,
     ) -> AccountDumperResult<Signature>;
 
     /// Edge case handler, when we artificially manufacture 2 accounts for program, which is owned
@@ -120,7 +120,7 @@ impl AccountDumper for AccountDumperBank {
         program_id_account: &Account,
         program_data_pubkey: &Pubkey,
         program_data_account: &Account,
-        program_idl: Option<(Pubkey, Account)>,
+        program_idls: Vec<(Pubkey, Account)>,
     ) -> AccountDumperResult<Signature> {
         let ProgramModifications {
             program_id_modification,
@@ -134,20 +134,22 @@ impl AccountDumper for AccountDumperBank {
             self.bank.slot(),
         )
         .map_err(AccountDumperError::MutatorModificationError)?;
-        let program_idl_modification =
-            program_idl.map(|(program_idl_pubkey, program_idl_account)| {
+        let program_idl_modifications = program_idls
+            .iter()
+            .map(|(program_idl_pubkey, program_idl_account)| {
                 AccountModification::from((
-                    &program_idl_pubkey,
-                    &program_idl_account,
+                    program_idl_pubkey,
+                    program_idl_account,
                 ))
-            });
+            })
+            .collect();
         let needs_upgrade = self.bank.has_account(program_id_pubkey);
         let transaction = transaction_to_clone_program(
             needs_upgrade,
             program_id_modification,
             program_data_modification,
             program_buffer_modification,
-            program_idl_modification,
+            program_idl_modifications,
             self.bank.last_blockhash(),
         );
         self.execute_transaction(transaction)
@@ -196,7 +198,7 @@ impl AccountDumper for AccountDumperBank {
             program_id_modification,
             program_data_modification,
             program_buffer_modification,
-            None,
+            vec![],
             self.bank.last_blockhash(),
         );
         self.execute_transaction(transaction)
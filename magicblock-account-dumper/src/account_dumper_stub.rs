@@ -65,7 +65,7 @@ impl AccountDumper for AccountDumperStub {
         _program_id_account: &Account,
         program_data_pubkey: &Pubkey,
         _program_data_account: &Account,
-        program_idl: Option<(Pubkey, Account)>,
+        program_idls: Vec<(Pubkey, Account)>,
     ) -> AccountDumperResult<Signature> {
         self.program_ids
             .write()
@@ -75,12 +75,10 @@ impl AccountDumper for AccountDumperStub {
             .write()
             .unwrap()
             .insert(*program_data_pubkey);
-        if let Some(program_idl) = program_idl {
-            self.program_idls
-                .write()
-                .expect("RwLock for program_idls is poisoned")
-                .insert(program_idl.0);
-        }
+        self.program_idls
+            .write()
+            .expect("RwLock for program_idls is poisoned")
+            .extend(program_idls.into_iter().map(|(pubkey, _)| pubkey));
         Ok(Signature::new_unique())
     }
 
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use conjunto_transwise::AccountChainSnapshotShared;
 use futures_util::future::BoxFuture;
 use solana_sdk::{clock::Slot, pubkey::Pubkey};
@@ -16,6 +18,14 @@ pub enum AccountFetcherError {
 
     #[error("FailedToFetch '{0}'")]
     FailedToFetch(String),
+
+    #[error(
+        "RemoteUnavailable, all remote endpoints are down, retrying in {0:?}"
+    )]
+    RemoteUnavailable(Duration),
+
+    #[error("FailedToLoadLocalAccounts '{0}'")]
+    FailedToLoadLocalAccounts(String),
 }
 
 pub type AccountFetcherResult<T> = Result<T, AccountFetcherError>;
@@ -1,6 +1,10 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
-    sync::{Arc, RwLock},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -8,17 +12,21 @@ use conjunto_transwise::{
     AccountChainSnapshot, AccountChainSnapshotShared, AccountChainState,
     CommitFrequency, DelegationInconsistency, DelegationRecord,
 };
-use futures_util::future::{ready, BoxFuture};
+use futures_util::future::BoxFuture;
+use solana_inline_spl::token;
 use solana_sdk::{account::Account, clock::Slot, pubkey::Pubkey};
 
 use crate::{AccountFetcher, AccountFetcherResult};
 
 const MIN_ACCOUNT_RENT: u64 = 890880;
+const TOKEN_ACCOUNT_SIZE: usize = 165;
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
 
 #[derive(Debug)]
 enum AccountFetcherStubState {
     FeePayer,
     Undelegated,
+    UndelegatedTokenAccount { mint: Pubkey },
     Delegated { delegation_record: DelegationRecord },
     Executable,
 }
@@ -33,6 +41,9 @@ struct AccountFetcherStubSnapshot {
 pub struct AccountFetcherStub {
     fetched_counters: Arc<RwLock<HashMap<Pubkey, u64>>>,
     known_accounts: Arc<RwLock<HashMap<Pubkey, AccountFetcherStubSnapshot>>>,
+    fetch_delay: Arc<RwLock<Option<Duration>>>,
+    concurrent_fetches: Arc<AtomicUsize>,
+    max_concurrent_fetches: Arc<AtomicUsize>,
 }
 
 impl AccountFetcherStub {
@@ -68,6 +79,23 @@ impl AccountFetcherStub {
                             delegation_inconsistency: DelegationInconsistency::DelegationRecordNotFound,
                         }
                     }
+                    AccountFetcherStubState::UndelegatedTokenAccount {
+                        mint,
+                    } => {
+                        let mut data = vec![0; TOKEN_ACCOUNT_SIZE];
+                        data[..32].copy_from_slice(mint.as_ref());
+                        // Initialized
+                        data[TOKEN_ACCOUNT_STATE_OFFSET] = 1;
+                        AccountChainState::Undelegated {
+                            account: Account {
+                                owner: token::id(),
+                                lamports: MIN_ACCOUNT_RENT,
+                                data,
+                                ..Default::default()
+                            },
+                            delegation_inconsistency: DelegationInconsistency::DelegationRecordNotFound,
+                        }
+                    }
                     AccountFetcherStubState::Delegated {
                         delegation_record,
                     } => AccountChainState::Delegated {
@@ -117,6 +145,22 @@ impl AccountFetcherStub {
             },
         );
     }
+    pub fn set_undelegated_token_account(
+        &self,
+        pubkey: Pubkey,
+        mint: Pubkey,
+        at_slot: Slot,
+    ) {
+        self.insert_known_account(
+            pubkey,
+            AccountFetcherStubSnapshot {
+                slot: at_slot,
+                state: AccountFetcherStubState::UndelegatedTokenAccount {
+                    mint,
+                },
+            },
+        );
+    }
     pub fn set_delegated_account(
         &self,
         pubkey: Pubkey,
@@ -149,6 +193,16 @@ impl AccountFetcherStub {
         );
     }
 
+    /// Makes every fetch take this long, so that concurrent fetches overlap
+    pub fn set_fetch_delay(&self, delay: Duration) {
+        *self.fetch_delay.write().unwrap() = Some(delay);
+    }
+
+    /// The most fetches that were in flight at the same time
+    pub fn get_max_concurrent_fetches(&self) -> usize {
+        self.max_concurrent_fetches.load(Ordering::SeqCst)
+    }
+
     pub fn get_fetch_count(&self, pubkey: &Pubkey) -> u64 {
         self.fetched_counters
             .read()
@@ -174,6 +228,18 @@ impl AccountFetcher for AccountFetcherStub {
                 entry.insert(1);
             }
         };
-        Box::pin(ready(self.generate_account_chain_snapshot(pubkey)))
+        let pubkey = *pubkey;
+        let fetch_delay = *self.fetch_delay.read().unwrap();
+        Box::pin(async move {
+            let concurrent_fetches =
+                self.concurrent_fetches.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_concurrent_fetches
+                .fetch_max(concurrent_fetches, Ordering::SeqCst);
+            if let Some(delay) = fetch_delay {
+                tokio::time::sleep(delay).await;
+            }
+            self.concurrent_fetches.fetch_sub(1, Ordering::SeqCst);
+            self.generate_account_chain_snapshot(&pubkey)
+        })
     }
 }
@@ -0,0 +1,107 @@
+use std::{
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+use log::*;
+use magicblock_metrics::metrics;
+
+/// Short-circuits fetches while the remote cluster is down, so that clones
+/// fail fast instead of stalling transactions until the fetches time out.
+///
+/// The circuit opens once all endpoints are unhealthy. While it is open
+/// fetches are rejected, except for a single one per `cooldown` probing
+/// whether the remote recovered. It closes again once a fetch succeeds or
+/// an endpoint passes its health check.
+pub(crate) struct CircuitBreaker {
+    /// Zero disables the circuit breaker
+    cooldown: Duration,
+    /// When the next fetch is let through, `None` while the circuit is closed
+    retry_at: Mutex<Option<Instant>>,
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(cooldown: Duration) -> Self {
+        metrics::set_remote_fetch_circuit_open(false);
+        Self {
+            cooldown,
+            retry_at: Mutex::new(None),
+        }
+    }
+
+    /// Returns the time left until the next probe if the fetch is rejected
+    pub(crate) fn try_acquire(&self) -> Result<(), Duration> {
+        let mut retry_at = self.retry_at();
+        let Some(at) = *retry_at else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        if now < at {
+            return Err(at - now);
+        }
+        // Any other fetch is rejected until this probe resolved or the
+        // cooldown elapsed again, i.e. if the probe timed out
+        *retry_at = Some(now + self.cooldown);
+        Ok(())
+    }
+
+    pub(crate) fn open(&self) {
+        if self.cooldown.is_zero() {
+            return;
+        }
+        let mut retry_at = self.retry_at();
+        if retry_at.is_none() {
+            warn!(
+                "All remote endpoints are down, failing fetches for {:?} before retrying",
+                self.cooldown
+            );
+            metrics::set_remote_fetch_circuit_open(true);
+        }
+        *retry_at = Some(Instant::now() + self.cooldown);
+    }
+
+    pub(crate) fn close(&self) {
+        if self.retry_at().take().is_some() {
+            info!("Remote endpoints recovered, resuming fetches");
+            metrics::set_remote_fetch_circuit_open(false);
+        }
+    }
+
+    fn retry_at(&self) -> MutexGuard<Option<Instant>> {
+        self.retry_at
+            .lock()
+            .expect("Mutex of CircuitBreaker.retry_at is poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let cooldown = Duration::from_millis(50);
+        let circuit_breaker = CircuitBreaker::new(cooldown);
+        assert!(circuit_breaker.try_acquire().is_ok());
+
+        // Rejects fetches until the cooldown elapsed
+        circuit_breaker.open();
+        assert!(circuit_breaker.try_acquire().is_err());
+
+        // Lets a single probe through once it elapsed
+        std::thread::sleep(cooldown);
+        assert!(circuit_breaker.try_acquire().is_ok());
+        assert!(circuit_breaker.try_acquire().is_err());
+
+        circuit_breaker.close();
+        assert!(circuit_breaker.try_acquire().is_ok());
+        assert!(circuit_breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_circuit_breaker_disabled() {
+        let circuit_breaker = CircuitBreaker::new(Duration::ZERO);
+        circuit_breaker.open();
+        assert!(circuit_breaker.try_acquire().is_ok());
+    }
+}
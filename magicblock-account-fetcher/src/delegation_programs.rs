@@ -0,0 +1,44 @@
+use conjunto_transwise::{CommitFrequency, DelegationRecord};
+use solana_sdk::pubkey::Pubkey;
+
+// Additional delegation programs are expected to derive their PDAs and lay
+// out their delegation records the same way as the default one
+const DELEGATION_RECORD_SEED: &[u8] = b"delegation";
+const DELEGATION_METADATA_SEED: &[u8] = b"delegation-metadata";
+
+/// The delegation record of an account delegated through the given program
+pub fn delegation_record_pda(
+    delegated_account: &Pubkey,
+    delegation_program_id: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[DELEGATION_RECORD_SEED, delegated_account.as_ref()],
+        delegation_program_id,
+    )
+    .0
+}
+
+/// The delegation metadata of an account delegated through the given program
+pub fn delegation_metadata_pda(
+    delegated_account: &Pubkey,
+    delegation_program_id: &Pubkey,
+) -> Pubkey {
+    Pubkey::find_program_address(
+        &[DELEGATION_METADATA_SEED, delegated_account.as_ref()],
+        delegation_program_id,
+    )
+    .0
+}
+
+pub(crate) fn parse_delegation_record(data: &[u8]) -> Option<DelegationRecord> {
+    let record =
+        dlp::state::DelegationRecord::try_from_bytes_with_discriminator(data)
+            .ok()?;
+    Some(DelegationRecord {
+        authority: record.authority,
+        owner: record.owner,
+        delegation_slot: record.delegation_slot,
+        lamports: record.lamports,
+        commit_frequency: CommitFrequency::Millis(record.commit_frequency_ms),
+    })
+}
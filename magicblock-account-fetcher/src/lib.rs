@@ -1,9 +1,16 @@
 mod account_fetcher;
 mod account_fetcher_stub;
+mod circuit_breaker;
+mod delegation_programs;
+mod local_accounts_source;
 mod remote_account_fetcher_client;
 mod remote_account_fetcher_worker;
+mod remote_endpoint_pool;
 
 pub use account_fetcher::*;
 pub use account_fetcher_stub::*;
+pub use delegation_programs::{delegation_metadata_pda, delegation_record_pda};
+pub use local_accounts_source::LocalAccountsSource;
 pub use remote_account_fetcher_client::*;
 pub use remote_account_fetcher_worker::*;
+pub use remote_endpoint_pool::*;
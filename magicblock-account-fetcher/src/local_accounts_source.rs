@@ -0,0 +1,216 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    str::FromStr,
+};
+
+use conjunto_transwise::{
+    AccountChainSnapshot, AccountChainState, DelegationInconsistency,
+};
+use log::*;
+use magicblock_accounts_db::{config::AccountsDbConfig, AccountsDb};
+use serde::Deserialize;
+use solana_account_decoder::UiAccount;
+use solana_sdk::{
+    account::Account, clock::Slot, pubkey::Pubkey, system_program,
+};
+
+use crate::{
+    delegation_programs::parse_delegation_record, delegation_record_pda,
+    AccountFetcherError, AccountFetcherResult,
+};
+
+/// An account as written by `solana account <pubkey> --output json`
+#[derive(Deserialize)]
+struct JsonAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
+enum LocalAccounts {
+    /// The accounts database of another validator
+    AccountsDb(AccountsDb),
+    /// Accounts loaded from JSON files, they have no slot
+    Json(HashMap<Pubkey, Account>),
+}
+
+// -----------------
+// LocalAccountsSource
+// -----------------
+/// Accounts that are cloned from a local snapshot of the remote state instead
+/// of being fetched from the remote RPC, i.e. for air-gapped testing or to
+/// spin up validators against heavy state.
+///
+/// Accounts missing from it are fetched from the remote only if
+/// [Self::fallback_to_remote] is set, otherwise they are considered to not
+/// exist.
+pub struct LocalAccountsSource {
+    accounts: LocalAccounts,
+    fallback_to_remote: bool,
+}
+
+impl LocalAccountsSource {
+    /// Opens the accounts database found in the provided ledger directory
+    pub fn from_accountsdb(
+        ledger_path: &Path,
+        fallback_to_remote: bool,
+    ) -> AccountFetcherResult<Self> {
+        // Opening the database creates it if missing, which would silently
+        // treat all accounts as missing
+        if !ledger_path.join("accountsdb").is_dir() {
+            return Err(AccountFetcherError::FailedToLoadLocalAccounts(
+                format!(
+                    "'{}' does not contain an accounts database",
+                    ledger_path.display()
+                ),
+            ));
+        }
+        let config = AccountsDbConfig {
+            snapshot_frequency: u64::MAX,
+            ..Default::default()
+        };
+        let accountsdb =
+            AccountsDb::new(&config, ledger_path, Default::default()).map_err(
+                |err| {
+                    AccountFetcherError::FailedToLoadLocalAccounts(format!(
+                        "Failed to open accounts database at '{}': {}",
+                        ledger_path.display(),
+                        err
+                    ))
+                },
+            )?;
+        info!(
+            "Cloning accounts from the accounts database at '{}' ({} accounts at slot {})",
+            ledger_path.display(),
+            accountsdb.get_accounts_count(),
+            accountsdb.slot()
+        );
+        Ok(Self {
+            accounts: LocalAccounts::AccountsDb(accountsdb),
+            fallback_to_remote,
+        })
+    }
+
+    /// Loads all `*.json` account files of the provided directory
+    pub fn from_json_accounts(
+        dir: &Path,
+        fallback_to_remote: bool,
+    ) -> AccountFetcherResult<Self> {
+        let load_error = |path: &Path, err: String| {
+            AccountFetcherError::FailedToLoadLocalAccounts(format!(
+                "'{}': {}",
+                path.display(),
+                err
+            ))
+        };
+        let mut accounts = HashMap::new();
+        let entries = fs::read_dir(dir)
+            .map_err(|err| load_error(dir, err.to_string()))?;
+        for entry in entries {
+            let path = entry
+                .map_err(|err| load_error(dir, err.to_string()))?
+                .path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path)
+                .map_err(|err| load_error(&path, err.to_string()))?;
+            let json_account = serde_json::from_str::<JsonAccount>(&content)
+                .map_err(|err| load_error(&path, err.to_string()))?;
+            let pubkey = Pubkey::from_str(&json_account.pubkey)
+                .map_err(|err| load_error(&path, err.to_string()))?;
+            let account =
+                json_account.account.decode::<Account>().ok_or_else(|| {
+                    load_error(&path, "Invalid account data".to_string())
+                })?;
+            accounts.insert(pubkey, account);
+        }
+        info!(
+            "Cloning accounts from {} JSON account files in '{}'",
+            accounts.len(),
+            dir.display()
+        );
+        Ok(Self {
+            accounts: LocalAccounts::Json(accounts),
+            fallback_to_remote,
+        })
+    }
+
+    pub fn fallback_to_remote(&self) -> bool {
+        self.fallback_to_remote
+    }
+
+    /// Builds the chain snapshot of the account the same way as if it was
+    /// fetched from the remote, `None` if the source doesn't contain it
+    pub(crate) fn get_chain_snapshot(
+        &self,
+        pubkey: &Pubkey,
+        delegation_program_ids: &HashSet<Pubkey>,
+    ) -> Option<AccountChainSnapshot> {
+        let account = self.get_account(pubkey)?;
+        let delegation_record = delegation_program_ids
+            .contains(&account.owner)
+            .then(|| {
+                self.get_account(&delegation_record_pda(pubkey, &account.owner))
+            })
+            .flatten()
+            .and_then(|record| parse_delegation_record(&record.data));
+        let chain_state = match delegation_record {
+            Some(delegation_record) => AccountChainState::Delegated {
+                account,
+                delegation_record,
+            },
+            None if account.owner == system_program::id()
+                && account.data.is_empty() =>
+            {
+                AccountChainState::FeePayer {
+                    lamports: account.lamports,
+                    owner: account.owner,
+                }
+            }
+            None => AccountChainState::Undelegated {
+                account,
+                delegation_inconsistency:
+                    DelegationInconsistency::DelegationRecordNotFound,
+            },
+        };
+        Some(AccountChainSnapshot {
+            pubkey: *pubkey,
+            at_slot: self.slot(),
+            chain_state,
+        })
+    }
+
+    /// The snapshot of an account missing from the source, which like on the
+    /// remote is an empty fee payer
+    pub(crate) fn missing_chain_snapshot(
+        &self,
+        pubkey: &Pubkey,
+    ) -> AccountChainSnapshot {
+        AccountChainSnapshot {
+            pubkey: *pubkey,
+            at_slot: self.slot(),
+            chain_state: AccountChainState::FeePayer {
+                lamports: 0,
+                owner: system_program::id(),
+            },
+        }
+    }
+
+    fn slot(&self) -> Slot {
+        match &self.accounts {
+            LocalAccounts::AccountsDb(accountsdb) => accountsdb.slot(),
+            LocalAccounts::Json(_) => 0,
+        }
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Option<Account> {
+        match &self.accounts {
+            LocalAccounts::AccountsDb(accountsdb) => {
+                accountsdb.get_account(pubkey).ok().map(Account::from)
+            }
+            LocalAccounts::Json(accounts) => accounts.get(pubkey).cloned(),
+        }
+    }
+}
@@ -4,10 +4,7 @@ use std::{
 };
 
 use conjunto_transwise::AccountChainSnapshotShared;
-use futures_util::{
-    future::{ready, BoxFuture},
-    FutureExt,
-};
+use futures_util::{future::BoxFuture, FutureExt};
 use solana_sdk::{clock::Slot, pubkey::Pubkey};
 use tokio::sync::{mpsc::UnboundedSender, oneshot::channel};
 
@@ -28,6 +25,35 @@ impl RemoteAccountFetcherClient {
             fetch_listeners: worker.get_fetch_listeners(),
         }
     }
+
+    fn fail_listeners(&self, pubkey: &Pubkey, error: AccountFetcherError) {
+        let listeners = self
+            .fetch_listeners
+            .lock()
+            .expect("RwLock of RemoteAccountFetcherClient.fetch_listeners is poisoned")
+            .remove(pubkey)
+            .unwrap_or_default();
+        for listener in listeners {
+            let _ = listener.send(Err(error.clone()));
+        }
+    }
+}
+
+/// Counts a fetch as pending until its result was received or the caller
+/// stopped waiting for it
+struct PendingFetchGuard;
+
+impl PendingFetchGuard {
+    fn new() -> Self {
+        magicblock_metrics::metrics::inc_pending_clone_requests();
+        Self
+    }
+}
+
+impl Drop for PendingFetchGuard {
+    fn drop(&mut self) {
+        magicblock_metrics::metrics::dec_pending_clone_requests();
+    }
 }
 
 impl AccountFetcher for RemoteAccountFetcherClient {
@@ -54,18 +80,21 @@ impl AccountFetcher for RemoteAccountFetcherClient {
             }
         };
         // track the number of pending clones, might be helpful to detect memory leaks
-        magicblock_metrics::metrics::inc_pending_clone_requests();
+        let pending_guard = PendingFetchGuard::new();
         if should_request_fetch {
             if let Err(error) =
                 self.fetch_request_sender.send((*pubkey, min_context_slot))
             {
-                return Box::pin(ready(Err(AccountFetcherError::SendError(
-                    error,
-                ))));
+                // Nobody will ever resolve the listeners, including the ones
+                // that joined in the meantime, thus fail all of them
+                self.fail_listeners(
+                    pubkey,
+                    AccountFetcherError::SendError(error),
+                );
             }
         }
-        Box::pin(receiver.map(|received| {
-            magicblock_metrics::metrics::dec_pending_clone_requests();
+        Box::pin(receiver.map(move |received| {
+            drop(pending_guard);
             match received {
                 Ok(result) => result,
                 Err(error) => Err(AccountFetcherError::RecvError(error)),
@@ -1,43 +1,76 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     sync::{Arc, Mutex},
+    time::Duration,
     vec,
 };
 
-use conjunto_transwise::{
-    AccountChainSnapshotProvider, AccountChainSnapshotShared,
-    DelegationRecordParserImpl, RpcAccountProvider, RpcProviderConfig,
-};
+use conjunto_transwise::{AccountChainSnapshotShared, RpcProviderConfig};
 use futures_util::future::join_all;
 use log::*;
+use rate_limiter::RateLimiterConfig;
 use solana_sdk::{clock::Slot, pubkey::Pubkey};
-use tokio::sync::mpsc::{
-    unbounded_channel, UnboundedReceiver, UnboundedSender,
+use tokio::{
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+    time::{interval, timeout, MissedTickBehavior},
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::{AccountFetcherError, AccountFetcherListeners};
+use crate::{
+    AccountFetcherError, AccountFetcherListeners, LocalAccountsSource,
+    RemoteEndpointPool,
+};
+
+pub const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct RemoteAccountFetcherWorker {
-    account_chain_snapshot_provider: AccountChainSnapshotProvider<
-        RpcAccountProvider,
-        DelegationRecordParserImpl,
-    >,
+    endpoint_pool: Arc<RemoteEndpointPool>,
+    local_accounts_source: Option<LocalAccountsSource>,
+    /// All delegation programs, including the default one
+    delegation_program_ids: HashSet<Pubkey>,
+    health_check_interval: Duration,
+    fetch_timeout: Duration,
     fetch_request_receiver: UnboundedReceiver<(Pubkey, Option<Slot>)>,
     fetch_request_sender: UnboundedSender<(Pubkey, Option<Slot>)>,
     fetch_listeners: Arc<Mutex<HashMap<Pubkey, AccountFetcherListeners>>>,
 }
 
 impl RemoteAccountFetcherWorker {
-    pub fn new(config: RpcProviderConfig) -> Self {
-        let account_chain_snapshot_provider = AccountChainSnapshotProvider::new(
-            RpcAccountProvider::new(config),
-            DelegationRecordParserImpl,
-        );
+    /// Creates a worker fetching from the endpoints of the provided configs,
+    /// the first one being preferred while the others are only used when
+    /// it is unhealthy, see [RemoteEndpointPool]. Once all of them are down
+    /// fetches fail fast for `circuit_breaker_cooldown` at a time.
+    /// Accounts owned by any of the `delegation_program_ids` are recognized
+    /// as delegated in addition to the ones of the default delegation program.
+    /// Concurrent fetches of the same account are resolved by a single
+    /// request, which fails for all of them after `fetch_timeout`.
+    /// Accounts found in the `local_accounts_source` are never fetched from
+    /// the remote, see [LocalAccountsSource].
+    pub fn new(
+        configs: Vec<(RpcProviderConfig, RateLimiterConfig)>,
+        health_check_interval: Duration,
+        max_consecutive_failures: u32,
+        circuit_breaker_cooldown: Duration,
+        fetch_timeout: Duration,
+        delegation_program_ids: HashSet<Pubkey>,
+        local_accounts_source: Option<LocalAccountsSource>,
+    ) -> Self {
+        let endpoint_pool = Arc::new(RemoteEndpointPool::new(
+            configs,
+            max_consecutive_failures,
+            circuit_breaker_cooldown,
+            delegation_program_ids.clone(),
+        ));
+        let mut delegation_program_ids = delegation_program_ids;
+        delegation_program_ids.insert(dlp::id());
         let (fetch_request_sender, fetch_request_receiver) =
             unbounded_channel();
         Self {
-            account_chain_snapshot_provider,
+            endpoint_pool,
+            local_accounts_source,
+            delegation_program_ids,
+            health_check_interval,
+            fetch_timeout,
             fetch_request_receiver,
             fetch_request_sender,
             fetch_listeners: Default::default(),
@@ -60,9 +93,20 @@ impl RemoteAccountFetcherWorker {
         &mut self,
         cancellation_token: CancellationToken,
     ) {
+        let mut health_check_interval = interval(self.health_check_interval);
+        health_check_interval
+            .set_missed_tick_behavior(MissedTickBehavior::Delay);
         loop {
             let mut requests = vec![];
             tokio::select! {
+                _ = health_check_interval.tick() => {
+                    // Checked on a separate task in order to not delay fetches
+                    // when endpoints are slow to respond
+                    let endpoint_pool = self.endpoint_pool.clone();
+                    tokio::spawn(async move {
+                        endpoint_pool.check_health().await;
+                    });
+                }
                 _ = self.fetch_request_receiver.recv_many(&mut requests, 100) => {
                     join_all(
                         requests
@@ -80,20 +124,37 @@ impl RemoteAccountFetcherWorker {
     async fn process_fetch_request(&self, request: (Pubkey, Option<Slot>)) {
         let pubkey = request.0;
         let min_context_slot = request.1;
-        // Actually fetch the account asynchronously
-        let result = match self
-            .account_chain_snapshot_provider
-            .try_fetch_chain_snapshot_of_pubkey(&pubkey, min_context_slot)
+        // Prefer the local accounts, only fetching the remote for the ones
+        // that are missing locally if allowed to
+        let local_snapshot =
+            self.local_accounts_source.as_ref().and_then(|source| {
+                source
+                    .get_chain_snapshot(&pubkey, &self.delegation_program_ids)
+                    .or_else(|| {
+                        (!source.fallback_to_remote())
+                            .then(|| source.missing_chain_snapshot(&pubkey))
+                    })
+            });
+        let result = match local_snapshot {
+            Some(snapshot) => Ok(AccountChainSnapshotShared::from(snapshot)),
+            // Actually fetch the account asynchronously, the pool downgrades
+            // errors to a clonable version since the result is shared.
+            // The fetch is bounded so that the listeners are always resolved.
+            None => timeout(
+                self.fetch_timeout,
+                self.endpoint_pool.try_fetch_chain_snapshot_of_pubkey(
+                    &pubkey,
+                    min_context_slot,
+                ),
+            )
             .await
-        {
-            Ok(snapshot) => Ok(AccountChainSnapshotShared::from(snapshot)),
-            // LockboxError is unclonable, so we have to downgrade it to a clonable error type
-            Err(error) => {
-                // Log the error now, since we're going to lose the stacktrace after string conversion
-                warn!("Failed to fetch account: {} :{:?}", pubkey, error);
-                // Lose the error full stack trace and create a simplified clonable string version
-                Err(AccountFetcherError::FailedToFetch(error.to_string()))
-            }
+            .unwrap_or_else(|_| {
+                Err(AccountFetcherError::FailedToFetch(format!(
+                    "Timed out after {:?}",
+                    self.fetch_timeout
+                )))
+            })
+            .map(AccountChainSnapshotShared::from),
         };
         // Log the result for debugging purposes
         debug!(
@@ -116,10 +177,14 @@ impl RemoteAccountFetcherWorker {
             // If the entry exists, we want to consume the list of listeners
             Entry::Occupied(entry) => entry.remove(),
         };
-        // Notify the listeners of the arrival of the result
+        // Notify the listeners of the arrival of the result, the ones that
+        // are gone stopped waiting for it which is fine
         for listener in listeners {
-            if let Err(error) = listener.send(result.clone()) {
-                error!("Could not send fetch result: {}: {:?}", pubkey, error);
+            if listener.send(result.clone()).is_err() {
+                debug!(
+                    "Fetch listener was dropped before the result: {}",
+                    pubkey
+                );
             }
         }
     }
@@ -0,0 +1,304 @@
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+use conjunto_transwise::{
+    AccountChainSnapshot, AccountChainSnapshotProvider, AccountChainState,
+    DelegationRecordParserImpl, RpcAccountProvider, RpcProviderConfig,
+};
+use futures_util::future::join_all;
+use log::*;
+pub use magicblock_metrics::metrics::endpoint_label;
+use magicblock_metrics::metrics::{self, Outcome};
+use rate_limiter::{is_throttling_error, RateLimiter, RateLimiterConfig};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{clock::Slot, pubkey::Pubkey};
+
+use crate::{
+    circuit_breaker::CircuitBreaker,
+    delegation_programs::parse_delegation_record, delegation_record_pda,
+    AccountFetcherError, AccountFetcherResult,
+};
+
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_CONSECUTIVE_FAILURES: u32 = 3;
+pub const DEFAULT_CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(5);
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Distinguishes the endpoints of the pool in metrics from the ones commits
+/// are sent through
+const ENDPOINT_ROLE: &str = "clone";
+
+struct RemoteEndpoint {
+    /// Identifies the endpoint in logs and metrics without leaking
+    /// credentials that are part of its path or query
+    label: String,
+    account_chain_snapshot_provider: AccountChainSnapshotProvider<
+        RpcAccountProvider,
+        DelegationRecordParserImpl,
+    >,
+    health_client: RpcClient,
+    rate_limiter: RateLimiter,
+    consecutive_failures: AtomicU32,
+    healthy: AtomicBool,
+}
+
+impl RemoteEndpoint {
+    fn new(
+        config: RpcProviderConfig,
+        rate_limiter_config: RateLimiterConfig,
+    ) -> Self {
+        let label = endpoint_label(config.url());
+        let health_client = RpcClient::new_with_timeout(
+            config.url().to_string(),
+            HEALTH_CHECK_TIMEOUT,
+        );
+        metrics::set_remote_endpoint_healthy(ENDPOINT_ROLE, &label, true);
+        Self {
+            label,
+            account_chain_snapshot_provider: AccountChainSnapshotProvider::new(
+                RpcAccountProvider::new(config),
+                DelegationRecordParserImpl,
+            ),
+            health_client,
+            rate_limiter: RateLimiter::new(rate_limiter_config),
+            consecutive_failures: AtomicU32::new(0),
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn set_healthy(&self, healthy: bool) {
+        if self.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+            if healthy {
+                info!("Remote endpoint '{}' is healthy again", self.label);
+            } else {
+                warn!("Remote endpoint '{}' became unhealthy", self.label);
+            }
+            metrics::set_remote_endpoint_healthy(
+                ENDPOINT_ROLE,
+                &self.label,
+                healthy,
+            );
+        }
+    }
+}
+
+/// Pool of RPC endpoints of the same remote cluster that accounts are
+/// fetched from.
+///
+/// Endpoints are tried in the order they were provided, skipping the ones
+/// that are unhealthy, i.e. which failed too many fetches in a row or their
+/// last health check. If no endpoint is healthy fetches fail fast, only
+/// probing all endpoints once per circuit breaker cooldown.
+pub struct RemoteEndpointPool {
+    endpoints: Vec<RemoteEndpoint>,
+    max_consecutive_failures: u32,
+    circuit_breaker: CircuitBreaker,
+    /// Delegation programs recognized in addition to the default one
+    delegation_program_ids: HashSet<Pubkey>,
+}
+
+impl RemoteEndpointPool {
+    /// Creates a pool where the first config is the preferred endpoint,
+    /// requests to each endpoint are limited by its own [RateLimiter]
+    pub fn new(
+        configs: Vec<(RpcProviderConfig, RateLimiterConfig)>,
+        max_consecutive_failures: u32,
+        circuit_breaker_cooldown: Duration,
+        delegation_program_ids: HashSet<Pubkey>,
+    ) -> Self {
+        assert!(
+            !configs.is_empty(),
+            "RemoteEndpointPool needs at least one endpoint"
+        );
+        Self {
+            endpoints: configs
+                .into_iter()
+                .map(|(config, rate_limiter_config)| {
+                    RemoteEndpoint::new(config, rate_limiter_config)
+                })
+                .collect(),
+            max_consecutive_failures,
+            circuit_breaker: CircuitBreaker::new(circuit_breaker_cooldown),
+            delegation_program_ids,
+        }
+    }
+
+    pub async fn try_fetch_chain_snapshot_of_pubkey(
+        &self,
+        pubkey: &Pubkey,
+        min_context_slot: Option<Slot>,
+    ) -> AccountFetcherResult<AccountChainSnapshot> {
+        if let Err(retry_in) = self.circuit_breaker.try_acquire() {
+            metrics::inc_remote_fetch_short_circuited();
+            return Err(AccountFetcherError::RemoteUnavailable(retry_in));
+        }
+        let result = self.fetch_from_candidates(pubkey, min_context_slot).await;
+        match &result {
+            Ok(_) => self.circuit_breaker.close(),
+            Err(_)
+                if !self.endpoints.iter().any(RemoteEndpoint::is_healthy) =>
+            {
+                self.circuit_breaker.open()
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    async fn fetch_from_candidates(
+        &self,
+        pubkey: &Pubkey,
+        min_context_slot: Option<Slot>,
+    ) -> AccountFetcherResult<AccountChainSnapshot> {
+        let mut last_error = None;
+        for endpoint in self.candidates() {
+            if let Some((failed_endpoint, _)) = &last_error {
+                debug!(
+                    "Failing over fetch of {} from '{}' to '{}'",
+                    pubkey, failed_endpoint, endpoint.label
+                );
+                metrics::inc_remote_endpoint_failovers(ENDPOINT_ROLE);
+                metrics::inc_remote_fetch_failover(failed_endpoint);
+            }
+
+            endpoint.rate_limiter.acquire().await;
+            let start = Instant::now();
+            let result = endpoint
+                .account_chain_snapshot_provider
+                .try_fetch_chain_snapshot_of_pubkey(pubkey, min_context_slot)
+                .await;
+            metrics::observe_remote_fetch_time(
+                &endpoint.label,
+                Outcome::from_success(result.is_ok()),
+                start.elapsed(),
+            );
+
+            match result {
+                Ok(snapshot) => {
+                    endpoint.rate_limiter.on_success();
+                    endpoint.consecutive_failures.store(0, Ordering::Relaxed);
+                    return self.resolve_delegation(endpoint, snapshot).await;
+                }
+                Err(error) => {
+                    // Log the error now, since only its string version is kept
+                    warn!(
+                        "Failed to fetch account {} from '{}': {:?}",
+                        pubkey, endpoint.label, error
+                    );
+                    let error = error.to_string();
+                    if is_throttling_error(&error) {
+                        let backoff = endpoint.rate_limiter.on_throttled();
+                        debug!(
+                            "Remote endpoint '{}' is throttling, backing off for {:?}",
+                            endpoint.label, backoff
+                        );
+                        metrics::inc_remote_fetch_throttled(&endpoint.label);
+                    }
+                    let failures = endpoint
+                        .consecutive_failures
+                        .fetch_add(1, Ordering::Relaxed)
+                        + 1;
+                    if failures >= self.max_consecutive_failures {
+                        endpoint.set_healthy(false);
+                    }
+                    last_error = Some((endpoint.label.as_str(), error));
+                }
+            }
+        }
+        let (_, error) =
+            last_error.expect("RemoteEndpointPool has at least one endpoint");
+        Err(AccountFetcherError::FailedToFetch(error))
+    }
+
+    /// The snapshot provider only knows the default delegation program, thus
+    /// accounts delegated through any of the additional ones appear to be
+    /// undelegated until we resolve their delegation record ourselves
+    async fn resolve_delegation(
+        &self,
+        endpoint: &RemoteEndpoint,
+        snapshot: AccountChainSnapshot,
+    ) -> AccountFetcherResult<AccountChainSnapshot> {
+        let AccountChainState::Undelegated { account, .. } =
+            &snapshot.chain_state
+        else {
+            return Ok(snapshot);
+        };
+        if !self.delegation_program_ids.contains(&account.owner) {
+            return Ok(snapshot);
+        }
+        let record_snapshot = endpoint
+            .account_chain_snapshot_provider
+            .try_fetch_chain_snapshot_of_pubkey(
+                &delegation_record_pda(&snapshot.pubkey, &account.owner),
+                Some(snapshot.at_slot),
+            )
+            .await
+            .map_err(|err| {
+                AccountFetcherError::FailedToFetch(err.to_string())
+            })?;
+        let Some(delegation_record) = record_snapshot
+            .chain_state
+            .account()
+            .and_then(|record| parse_delegation_record(&record.data))
+        else {
+            debug!(
+                "Account {} is owned by delegation program '{}' but has no valid delegation record",
+                snapshot.pubkey, account.owner
+            );
+            return Ok(snapshot);
+        };
+        Ok(AccountChainSnapshot {
+            pubkey: snapshot.pubkey,
+            at_slot: snapshot.at_slot,
+            chain_state: AccountChainState::Delegated {
+                account: account.clone(),
+                delegation_record,
+            },
+        })
+    }
+
+    /// Checks the health of all endpoints, which is how unhealthy endpoints
+    /// become available again and fetches resume if all of them were down
+    pub async fn check_health(&self) {
+        join_all(self.endpoints.iter().map(|endpoint| async move {
+            match endpoint.health_client.get_health().await {
+                Ok(_) => {
+                    endpoint.consecutive_failures.store(0, Ordering::Relaxed);
+                    endpoint.set_healthy(true);
+                }
+                Err(error) => {
+                    debug!(
+                        "Health check of remote endpoint '{}' failed: {:?}",
+                        endpoint.label, error
+                    );
+                    endpoint.set_healthy(false);
+                }
+            }
+        }))
+        .await;
+        if self.endpoints.iter().any(RemoteEndpoint::is_healthy) {
+            self.circuit_breaker.close();
+        }
+    }
+
+    fn candidates(&self) -> Vec<&RemoteEndpoint> {
+        let healthy = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.is_healthy())
+            .collect::<Vec<_>>();
+        if healthy.is_empty() {
+            self.endpoints.iter().collect()
+        } else {
+            healthy
+        }
+    }
+}
@@ -1,29 +1,41 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 use conjunto_transwise::RpcProviderConfig;
 use magicblock_account_fetcher::{
-    AccountFetcher, RemoteAccountFetcherClient, RemoteAccountFetcherWorker,
+    AccountFetcher, AccountFetcherError, LocalAccountsSource,
+    RemoteAccountFetcherClient, RemoteAccountFetcherWorker,
+    DEFAULT_CIRCUIT_BREAKER_COOLDOWN, DEFAULT_FETCH_TIMEOUT,
+    DEFAULT_HEALTH_CHECK_INTERVAL, DEFAULT_MAX_CONSECUTIVE_FAILURES,
 };
+use rate_limiter::RateLimiterConfig;
 use solana_sdk::{
     signature::Keypair,
     signer::Signer,
     system_program,
     sysvar::{clock, recent_blockhashes, rent},
 };
+use tempfile::TempDir;
 use test_tools::skip_if_devnet_down;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 use tokio_util::sync::CancellationToken;
 
-fn setup() -> (
-    RemoteAccountFetcherClient,
-    CancellationToken,
-    tokio::task::JoinHandle<()>,
-) {
-    // Create account fetcher worker and client
-    let mut worker =
-        RemoteAccountFetcherWorker::new(RpcProviderConfig::devnet());
-    let client = RemoteAccountFetcherClient::new(&worker);
-    // Run the worker in a separate task
+fn create_worker(
+    local_accounts_source: Option<LocalAccountsSource>,
+) -> RemoteAccountFetcherWorker {
+    RemoteAccountFetcherWorker::new(
+        vec![(RpcProviderConfig::devnet(), RateLimiterConfig::default())],
+        DEFAULT_HEALTH_CHECK_INTERVAL,
+        DEFAULT_MAX_CONSECUTIVE_FAILURES,
+        DEFAULT_CIRCUIT_BREAKER_COOLDOWN,
+        DEFAULT_FETCH_TIMEOUT,
+        HashSet::new(),
+        local_accounts_source,
+    )
+}
+
+fn start_worker(
+    mut worker: RemoteAccountFetcherWorker,
+) -> (CancellationToken, tokio::task::JoinHandle<()>) {
     let cancellation_token = CancellationToken::new();
     let worker_handle = {
         let cancellation_token = cancellation_token.clone();
@@ -33,10 +45,31 @@ fn setup() -> (
                 .await
         })
     };
+    (cancellation_token, worker_handle)
+}
+
+fn setup() -> (
+    RemoteAccountFetcherClient,
+    CancellationToken,
+    tokio::task::JoinHandle<()>,
+) {
+    // Create account fetcher worker and client
+    let worker = create_worker(None);
+    let client = RemoteAccountFetcherClient::new(&worker);
+    // Run the worker in a separate task
+    let (cancellation_token, worker_handle) = start_worker(worker);
     // Ready to run
     (client, cancellation_token, worker_handle)
 }
 
+/// Worker that never reaches the remote, all accounts are missing locally
+fn create_offline_worker(accounts_dir: &TempDir) -> RemoteAccountFetcherWorker {
+    create_worker(Some(
+        LocalAccountsSource::from_json_accounts(accounts_dir.path(), false)
+            .unwrap(),
+    ))
+}
+
 #[tokio::test]
 async fn test_devnet_fetch_clock_multiple_times() {
     skip_if_devnet_down!();
@@ -137,3 +170,91 @@ async fn test_devnet_fetch_multiple_accounts_same_time() {
     cancellation_token.cancel();
     assert!(worker_handle.await.is_ok());
 }
+
+#[tokio::test]
+async fn test_concurrent_fetches_are_coalesced_across_clients() {
+    let accounts_dir = TempDir::new().unwrap();
+    let worker = create_offline_worker(&accounts_dir);
+    let fetch_listeners = worker.get_fetch_listeners();
+    let client1 = RemoteAccountFetcherClient::new(&worker);
+    let client2 = RemoteAccountFetcherClient::new(&worker);
+    let key_new_account = Keypair::new().pubkey();
+    // Request the same account from both clients before the worker runs
+    let futures = (0..4)
+        .map(|idx| {
+            let client = if idx % 2 == 0 { &client1 } else { &client2 };
+            client.fetch_account_chain_snapshot(&key_new_account, None)
+        })
+        .collect::<Vec<_>>();
+    // All of them wait for the same in-flight request
+    assert_eq!(fetch_listeners.lock().unwrap().len(), 1);
+    assert_eq!(fetch_listeners.lock().unwrap()[&key_new_account].len(), 4);
+    // Resolve them all at once
+    let (cancellation_token, worker_handle) = start_worker(worker);
+    let snapshots = futures_util::future::join_all(futures)
+        .await
+        .into_iter()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    assert!(snapshots.iter().all(|snapshot| snapshot == &snapshots[0]));
+    assert!(snapshots[0].chain_state.is_feepayer());
+    assert!(fetch_listeners.lock().unwrap().is_empty());
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_cancelled_fetches_do_not_affect_other_waiters() {
+    let accounts_dir = TempDir::new().unwrap();
+    let worker = create_offline_worker(&accounts_dir);
+    let fetch_listeners = worker.get_fetch_listeners();
+    let client = RemoteAccountFetcherClient::new(&worker);
+    let key_new_account = Keypair::new().pubkey();
+    // Some waiters give up before the in-flight request completes
+    let future_cancelled1 =
+        client.fetch_account_chain_snapshot(&key_new_account, None);
+    let future_kept =
+        client.fetch_account_chain_snapshot(&key_new_account, None);
+    let future_cancelled2 =
+        client.fetch_account_chain_snapshot(&key_new_account, None);
+    drop(future_cancelled1);
+    drop(future_cancelled2);
+    let (cancellation_token, worker_handle) = start_worker(worker);
+    // The remaining waiter still gets the result
+    assert!(future_kept.await.unwrap().chain_state.is_feepayer());
+    assert!(fetch_listeners.lock().unwrap().is_empty());
+    // Even if all waiters gave up the account can be fetched again
+    drop(client.fetch_account_chain_snapshot(&key_new_account, None));
+    let result = timeout(
+        Duration::from_secs(5),
+        client.fetch_account_chain_snapshot(&key_new_account, None),
+    )
+    .await
+    .expect("fetch after cancellation should not hang");
+    assert!(result.unwrap().chain_state.is_feepayer());
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_fetches_fail_when_worker_is_gone() {
+    let accounts_dir = TempDir::new().unwrap();
+    let worker = create_offline_worker(&accounts_dir);
+    let fetch_listeners = worker.get_fetch_listeners();
+    let client = RemoteAccountFetcherClient::new(&worker);
+    drop(worker);
+    let key_new_account = Keypair::new().pubkey();
+    // Neither the first request nor the following ones may wait forever
+    for _ in 0..2 {
+        let result = timeout(
+            Duration::from_secs(5),
+            client.fetch_account_chain_snapshot(&key_new_account, None),
+        )
+        .await
+        .expect("fetch without worker should not hang");
+        assert!(matches!(result, Err(AccountFetcherError::SendError(_))));
+    }
+    assert!(fetch_listeners.lock().unwrap().is_empty());
+}
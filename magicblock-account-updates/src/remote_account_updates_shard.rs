@@ -1,16 +1,20 @@
 use std::{
     cmp::{max, min},
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     future::Future,
     pin::Pin,
     sync::{Arc, RwLock},
+    time::{Duration, Instant},
 };
 
 use futures_util::{stream::FuturesUnordered, Stream, StreamExt};
 use log::*;
-use magicblock_metrics::metrics;
+use magicblock_metrics::metrics::{self, Outcome};
+use rate_limiter::{is_throttling_error, RateLimiter};
 use solana_account_decoder::{UiAccount, UiAccountEncoding, UiDataSliceConfig};
-use solana_pubsub_client::nonblocking::pubsub_client::PubsubClient;
+use solana_pubsub_client::nonblocking::pubsub_client::{
+    PubsubClient, PubsubClientError,
+};
 use solana_rpc_client_api::{config::RpcAccountInfoConfig, response::Response};
 use solana_sdk::{
     clock::{Clock, Slot},
@@ -19,10 +23,21 @@ use solana_sdk::{
     sysvar::clock,
 };
 use thiserror::Error;
-use tokio::sync::mpsc::Receiver;
+use tokio::{
+    sync::mpsc::Receiver,
+    time::{interval, MissedTickBehavior},
+};
 use tokio_stream::StreamMap;
 use tokio_util::sync::CancellationToken;
 
+/// Used as flush interval when coalescing is disabled, in which case no
+/// updates are ever pending and the flush never runs
+const MIN_COALESCE_INTERVAL: Duration = Duration::from_millis(1);
+
+/// How often a throttled connection or subscription is retried before the
+/// shard fails
+const MAX_THROTTLED_RETRIES: usize = 5;
+
 type BoxFn = Box<
     dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send + 'static>> + Send,
 >;
@@ -33,37 +48,58 @@ type SubscriptionStream =
 #[derive(Debug, Error)]
 pub enum RemoteAccountUpdatesShardError {
     #[error(transparent)]
-    PubsubClientError(
-        #[from]
-        solana_pubsub_client::nonblocking::pubsub_client::PubsubClientError,
-    ),
+    PubsubClientError(#[from] PubsubClientError),
 }
 
 pub struct RemoteAccountUpdatesShard {
     shard_id: String,
     url: String,
+    rate_limiter: Arc<RateLimiter>,
     commitment: Option<CommitmentLevel>,
+    /// Updates of the same account received within this interval are
+    /// collapsed into the latest one, zero applies each update immediately.
+    /// NOTE: a pending update only becomes visible as last known update slot
+    /// once it is flushed, until then a clone of the account is considered
+    /// up to date even though it is stale
+    coalesce_interval: Duration,
+    /// High frequency accounts, i.e. oracles, which are subscribed to via a
+    /// dedicated connection and whose updates are never coalesced
+    fast_lane_accounts: Arc<HashSet<Pubkey>>,
     monitoring_request_receiver: Receiver<(Pubkey, bool)>,
     first_subscribed_slots: Arc<RwLock<HashMap<Pubkey, Slot>>>,
     last_known_update_slots: Arc<RwLock<HashMap<Pubkey, Slot>>>,
+    /// Latest clock slot observed by each running shard
+    shard_slots: Arc<RwLock<HashMap<String, Slot>>>,
+    /// Latest clock observed by any running shard
+    remote_clock: Arc<RwLock<Option<Clock>>>,
 }
 
 impl RemoteAccountUpdatesShard {
     pub fn new(
         shard_id: String,
         url: String,
+        rate_limiter: Arc<RateLimiter>,
         commitment: Option<CommitmentLevel>,
+        coalesce_interval: Duration,
+        fast_lane_accounts: Arc<HashSet<Pubkey>>,
         monitoring_request_receiver: Receiver<(Pubkey, bool)>,
         first_subscribed_slots: Arc<RwLock<HashMap<Pubkey, Slot>>>,
         last_known_update_slots: Arc<RwLock<HashMap<Pubkey, Slot>>>,
+        shard_slots: Arc<RwLock<HashMap<String, Slot>>>,
+        remote_clock: Arc<RwLock<Option<Clock>>>,
     ) -> Self {
         Self {
             shard_id,
             url,
+            rate_limiter,
             commitment,
+            coalesce_interval,
+            fast_lane_accounts,
             monitoring_request_receiver,
             first_subscribed_slots,
             last_known_update_slots,
+            shard_slots,
+            remote_clock,
         }
     }
 
@@ -86,14 +122,24 @@ impl RemoteAccountUpdatesShard {
             }),
             min_context_slot: None,
         };
-        let mut pool = PubsubPool::new(ws_url, config).await?;
+        let mut pool = PubsubPool::new(
+            ws_url,
+            config,
+            self.rate_limiter.clone(),
+            !self.fast_lane_accounts.is_empty(),
+        )
+        .await?;
         // Subscribe to the clock from the RPC (to figure out the latest slot)
-        let mut clock_stream = pool.subscribe(clock::ID).await?;
+        let mut clock_stream = pool.subscribe(clock::ID, false).await?;
         let mut clock_slot = 0;
         // We'll store useful maps for each of the account subscriptions
         let mut account_streams = StreamMap::new();
         const LOG_CLOCK_FREQ: u64 = 100;
         let mut log_clock_count = 0;
+        let mut pending_updates = PendingUpdates::default();
+        let mut flush_interval =
+            interval(self.coalesce_interval.max(MIN_COALESCE_INTERVAL));
+        flush_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
 
         // Loop forever until we stop the worker
         loop {
@@ -109,6 +155,7 @@ impl RemoteAccountUpdatesShard {
                         }
                         if let Ok(clock_value) = clock_value {
                             clock_slot = clock_value.slot;
+                            self.update_remote_clock(clock_value);
                         } else {
                             warn!("Shard {}: Failed to deserialize clock data: {:?}", self.shard_id, clock_data);
                         }
@@ -116,6 +163,7 @@ impl RemoteAccountUpdatesShard {
                         warn!("Shard {}: Received empty clock data", self.shard_id);
                     }
                     self.try_to_override_last_known_update_slot(clock::ID, clock_slot);
+                    self.update_shard_slot(clock_slot);
                 }
                 // When we receive a message to start monitoring an account
                 Some((pubkey, unsub)) = self.monitoring_request_receiver.recv() => {
@@ -134,8 +182,9 @@ impl RemoteAccountUpdatesShard {
                         pubkey,
                         clock_slot
                     );
+                    let fast_lane = self.fast_lane_accounts.contains(&pubkey);
                     let stream = pool
-                        .subscribe(pubkey)
+                        .subscribe(pubkey, fast_lane)
                         .await?;
                     account_streams.insert(pubkey, stream);
                     metrics::set_subscriptions_count(account_streams.len(), &self.shard_id);
@@ -148,7 +197,18 @@ impl RemoteAccountUpdatesShard {
                         "Shard {}: Account update: {:?}, current_update_slot: {}, data: {:?}",
                         self.shard_id, pubkey, current_update_slot, update.value.data.decode(),
                     );
-                    self.try_to_override_last_known_update_slot(pubkey, current_update_slot);
+                    metrics::inc_account_updates();
+                    if self.coalesce_interval.is_zero()
+                        || self.fast_lane_accounts.contains(&pubkey)
+                    {
+                        self.try_to_override_last_known_update_slot(pubkey, current_update_slot);
+                        continue;
+                    }
+                    pending_updates.insert(pubkey, current_update_slot);
+                }
+                // Periodically apply the latest update of each account
+                _ = flush_interval.tick(), if !pending_updates.is_empty() => {
+                    pending_updates.flush(&self.last_known_update_slots);
                 }
                 // When we want to stop the worker (it was cancelled)
                 _ = cancellation_token.cancelled() => {
@@ -157,6 +217,7 @@ impl RemoteAccountUpdatesShard {
             }
         }
         // Cleanup all subscriptions and wait for proper shutdown
+        pending_updates.flush(&self.last_known_update_slots);
         drop(account_streams);
         drop(clock_stream);
         pool.shutdown().await;
@@ -165,6 +226,36 @@ impl RemoteAccountUpdatesShard {
         Ok(())
     }
 
+    /// Records the latest clock slot observed by this shard and how far it
+    /// is behind the latest slot observed by any shard
+    fn update_shard_slot(&self, clock_slot: Slot) {
+        let remote_slot = {
+            let mut shard_slots = self.shard_slots.write().expect(
+                "RwLock of RemoteAccountUpdatesShard.shard_slots poisoned",
+            );
+            shard_slots.insert(self.shard_id.clone(), clock_slot);
+            shard_slots.values().copied().max().unwrap_or(clock_slot)
+        };
+        metrics::set_remote_slot(remote_slot);
+        metrics::set_subscription_lag(
+            &self.shard_id,
+            remote_slot.saturating_sub(clock_slot),
+        );
+    }
+
+    /// Keeps the clock of the most recent slot observed by any shard
+    fn update_remote_clock(&self, clock: Clock) {
+        let mut remote_clock = self.remote_clock.write().expect(
+            "RwLock of RemoteAccountUpdatesShard.remote_clock poisoned",
+        );
+        if remote_clock
+            .as_ref()
+            .map_or(true, |remote_clock| remote_clock.slot < clock.slot)
+        {
+            *remote_clock = Some(clock);
+        }
+    }
+
     fn try_to_override_first_subscribed_slot(
         &self,
         pubkey: Pubkey,
@@ -222,50 +313,126 @@ impl RemoteAccountUpdatesShard {
     }
 }
 
+/// Latest update slot of each account received since the last flush, all
+/// updates of an account within one flush interval are merged into one
+#[derive(Default)]
+struct PendingUpdates {
+    slots: HashMap<Pubkey, Slot>,
+}
+
+impl PendingUpdates {
+    fn insert(&mut self, pubkey: Pubkey, update_slot: Slot) {
+        match self.slots.entry(pubkey) {
+            Entry::Vacant(entry) => {
+                entry.insert(update_slot);
+            }
+            Entry::Occupied(mut entry) => {
+                metrics::inc_account_updates_coalesced();
+                *entry.get_mut() = max(*entry.get(), update_slot);
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Applies the pending updates to the last known update slots while
+    /// only acquiring the write lock once
+    fn flush(
+        &mut self,
+        last_known_update_slots: &RwLock<HashMap<Pubkey, Slot>>,
+    ) {
+        if self.slots.is_empty() {
+            return;
+        }
+        let mut last_known_update_slots = last_known_update_slots
+            .write()
+            .expect("RwLock of RemoteAccountUpdatesShard.last_known_update_slots poisoned");
+        for (pubkey, update_slot) in self.slots.drain() {
+            match last_known_update_slots.entry(pubkey) {
+                Entry::Vacant(entry) => {
+                    entry.insert(update_slot);
+                }
+                Entry::Occupied(mut entry) => {
+                    *entry.get_mut() = max(*entry.get(), update_slot);
+                }
+            }
+        }
+    }
+}
+
 struct PubsubPool {
+    /// Identifies the websocket endpoint in metrics
+    endpoint: String,
     clients: Vec<PubSubConnection>,
+    /// Index of the connection reserved for fast lane subscriptions
+    fast_lane_index: Option<usize>,
     unsubscribes: HashMap<Pubkey, (usize, BoxFn)>,
     config: RpcAccountInfoConfig,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl PubsubPool {
     async fn new(
         url: &str,
         config: RpcAccountInfoConfig,
+        rate_limiter: Arc<RateLimiter>,
+        with_fast_lane: bool,
     ) -> Result<Self, RemoteAccountUpdatesShardError> {
         // 8 is pretty much arbitrary, but a sane value for the number
         // of connections per RPC upstream, we don't overcomplicate things
         // here, as the whole cloning pipeline will be rewritten quite soon
         const CONNECTIONS_PER_POOL: usize = 8;
-        let mut clients = Vec::with_capacity(CONNECTIONS_PER_POOL);
-        let mut connections: FuturesUnordered<_> = (0..CONNECTIONS_PER_POOL)
-            .map(|_| PubSubConnection::new(url))
+        let connections_count = CONNECTIONS_PER_POOL + with_fast_lane as usize;
+        let endpoint = metrics::endpoint_label(url);
+        let mut clients = Vec::with_capacity(connections_count);
+        let mut connections: FuturesUnordered<_> = (0..connections_count)
+            .map(|_| PubSubConnection::new(url, &endpoint, &rate_limiter))
             .collect();
         while let Some(c) = connections.next().await {
             clients.push(c?);
         }
+        // The fast lane has its own connection so that its updates are not
+        // queued behind the updates of all other accounts
+        let fast_lane_index = with_fast_lane.then_some(CONNECTIONS_PER_POOL);
         Ok(Self {
+            endpoint,
             clients,
+            fast_lane_index,
             unsubscribes: HashMap::new(),
             config,
+            rate_limiter,
         })
     }
 
     async fn subscribe(
         &mut self,
         pubkey: Pubkey,
+        fast_lane: bool,
     ) -> Result<SubscriptionStream, RemoteAccountUpdatesShardError> {
+        let fast_lane_index = self.fast_lane_index;
         let (index, client) = self
             .clients
             .iter_mut()
             .enumerate()
+            .filter(|(index, _)| match fast_lane_index {
+                Some(fast_lane_index) => {
+                    (*index == fast_lane_index) == fast_lane
+                }
+                None => true,
+            })
             .min_by(|a, b| a.1.subs.cmp(&b.1.subs))
             .expect("clients vec is always greater than 0");
-        let (stream, unsubscribe) = client
-            .inner
-            .account_subscribe(&pubkey, Some(self.config.clone()))
-            .await
-            .map_err(RemoteAccountUpdatesShardError::PubsubClientError)?;
+        let inner = &client.inner;
+        let (stream, unsubscribe) = with_backoff(
+            &self.rate_limiter,
+            &self.endpoint,
+            "subscribe",
+            || inner.account_subscribe(&pubkey, Some(self.config.clone())),
+        )
+        .await
+        .map_err(RemoteAccountUpdatesShardError::PubsubClientError)?;
         client.subs += 1;
         // SAFETY:
         // we never drop the PubsubPool before the returned subscription stream
@@ -309,10 +476,103 @@ struct PubSubConnection {
 }
 
 impl PubSubConnection {
-    async fn new(url: &str) -> Result<Self, RemoteAccountUpdatesShardError> {
-        let inner = PubsubClient::new(url)
-            .await
-            .map_err(RemoteAccountUpdatesShardError::PubsubClientError)?;
+    async fn new(
+        url: &str,
+        endpoint: &str,
+        rate_limiter: &RateLimiter,
+    ) -> Result<Self, RemoteAccountUpdatesShardError> {
+        let inner = with_backoff(rate_limiter, endpoint, "connect", || {
+            PubsubClient::new(url)
+        })
+        .await
+        .map_err(RemoteAccountUpdatesShardError::PubsubClientError)?;
         Ok(Self { inner, subs: 0 })
     }
 }
+
+/// Sends the request once the rate limiter allows it and retries it with
+/// exponential backoff while the remote is throttling us, the time and
+/// outcome of each attempt is recorded by endpoint and `kind` of request
+async fn with_backoff<T, F, Fut>(
+    rate_limiter: &RateLimiter,
+    endpoint: &str,
+    kind: &str,
+    mut request: F,
+) -> Result<T, PubsubClientError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, PubsubClientError>>,
+{
+    let mut retries = 0;
+    loop {
+        rate_limiter.acquire().await;
+        let start = Instant::now();
+        let result = request().await;
+        metrics::observe_remote_pubsub_request_time(
+            endpoint,
+            kind,
+            Outcome::from_success(result.is_ok()),
+            start.elapsed(),
+        );
+        match result {
+            Ok(value) => {
+                rate_limiter.on_success();
+                return Ok(value);
+            }
+            Err(error)
+                if retries < MAX_THROTTLED_RETRIES
+                    && is_throttling_error(&error.to_string()) =>
+            {
+                retries += 1;
+                let backoff = rate_limiter.on_throttled();
+                warn!(
+                    "Pubsub request throttled, retrying in {:?}: {:?}",
+                    backoff, error
+                );
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_updates_merge_bursts_until_flushed() {
+        let last_known_update_slots = RwLock::new(HashMap::new());
+        let mut pending_updates = PendingUpdates::default();
+        let pubkey = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        for slot in [10, 13, 11, 12] {
+            pending_updates.insert(pubkey, slot);
+        }
+        pending_updates.insert(other, 5);
+        assert_eq!(pending_updates.slots.len(), 2);
+        assert!(last_known_update_slots.read().unwrap().is_empty());
+
+        pending_updates.flush(&last_known_update_slots);
+        assert!(pending_updates.is_empty());
+        let last_known_update_slots = last_known_update_slots.read().unwrap();
+        assert_eq!(last_known_update_slots.get(&pubkey), Some(&13));
+        assert_eq!(last_known_update_slots.get(&other), Some(&5));
+    }
+
+    #[test]
+    fn test_pending_updates_never_lower_last_known_update_slot() {
+        let pubkey = Pubkey::new_unique();
+        let last_known_update_slots =
+            RwLock::new(HashMap::from([(pubkey, 20)]));
+        let mut pending_updates = PendingUpdates::default();
+
+        pending_updates.insert(pubkey, 15);
+        pending_updates.flush(&last_known_update_slots);
+
+        assert_eq!(
+            last_known_update_slots.read().unwrap().get(&pubkey),
+            Some(&20)
+        );
+    }
+}
@@ -8,8 +8,12 @@ use std::{
 };
 
 use log::*;
+use magicblock_metrics::metrics;
+use rate_limiter::{RateLimiter, RateLimiterConfig};
 use solana_sdk::{
-    clock::Slot, commitment_config::CommitmentLevel, pubkey::Pubkey,
+    clock::{Clock, Slot},
+    commitment_config::CommitmentLevel,
+    pubkey::Pubkey,
 };
 use thiserror::Error;
 use tokio::{
@@ -43,31 +47,48 @@ struct RemoteAccountUpdatesWorkerRunner {
 }
 
 pub struct RemoteAccountUpdatesWorker {
-    ws_urls: Vec<String>,
+    /// Each websocket url with the rate limiter of requests sent to it,
+    /// which is shared by all runners created for that url
+    ws_urls: Vec<(String, Arc<RateLimiter>)>,
     commitment: Option<CommitmentLevel>,
     refresh_interval: Duration,
+    coalesce_interval: Duration,
+    fast_lane_accounts: Arc<HashSet<Pubkey>>,
     monitoring_request_receiver: Receiver<(Pubkey, bool)>,
     monitoring_request_sender: Sender<(Pubkey, bool)>,
     first_subscribed_slots: Arc<RwLock<HashMap<Pubkey, Slot>>>,
     last_known_update_slots: Arc<RwLock<HashMap<Pubkey, Slot>>>,
+    shard_slots: Arc<RwLock<HashMap<String, Slot>>>,
+    remote_clock: Arc<RwLock<Option<Clock>>>,
 }
 
 impl RemoteAccountUpdatesWorker {
     pub fn new(
-        ws_urls: Vec<String>,
+        ws_urls: Vec<(String, RateLimiterConfig)>,
         commitment: Option<CommitmentLevel>,
         refresh_interval: Duration,
+        coalesce_interval: Duration,
+        fast_lane_accounts: HashSet<Pubkey>,
     ) -> Self {
         let (monitoring_request_sender, monitoring_request_receiver) =
             channel(INFLIGHT_ACCOUNT_FETCHES_LIMIT);
         Self {
-            ws_urls,
+            ws_urls: ws_urls
+                .into_iter()
+                .map(|(url, rate_limiter_config)| {
+                    (url, Arc::new(RateLimiter::new(rate_limiter_config)))
+                })
+                .collect(),
             commitment,
             refresh_interval,
+            coalesce_interval,
+            fast_lane_accounts: Arc::new(fast_lane_accounts),
             monitoring_request_receiver,
             monitoring_request_sender,
             first_subscribed_slots: Default::default(),
             last_known_update_slots: Default::default(),
+            shard_slots: Default::default(),
+            remote_clock: Default::default(),
         }
     }
 
@@ -87,19 +108,31 @@ impl RemoteAccountUpdatesWorker {
         self.last_known_update_slots.clone()
     }
 
+    /// Latest remote slot observed by each running shard, keyed by shard id
+    pub fn get_shard_slots(&self) -> Arc<RwLock<HashMap<String, Slot>>> {
+        self.shard_slots.clone()
+    }
+
+    /// Clock of the most recent remote slot observed by any shard
+    pub fn get_remote_clock(&self) -> Arc<RwLock<Option<Clock>>> {
+        self.remote_clock.clone()
+    }
+
     pub async fn start_monitoring_request_processing(
         &mut self,
         cancellation_token: CancellationToken,
     ) {
         // Maintain a runner for each config passed as parameter
         let mut runners = vec![];
-        let mut monitored_accounts = HashSet::new();
+        // Fast lane accounts are monitored for the whole lifetime of the worker
+        let mut monitored_accounts = HashSet::clone(&self.fast_lane_accounts);
         // Initialize all the runners for all configs
-        for (index, url) in self.ws_urls.iter().enumerate() {
+        for (index, (url, rate_limiter)) in self.ws_urls.iter().enumerate() {
             runners.push(
                 self.create_runner_from_config(
                     index,
                     url.clone(),
+                    rate_limiter.clone(),
                     self.commitment,
                     &monitored_accounts,
                 )
@@ -118,6 +151,9 @@ impl RemoteAccountUpdatesWorker {
                     if monitored_accounts.contains(&pubkey) && !unsubscribe {
                         continue;
                     }
+                    if unsubscribe && self.fast_lane_accounts.contains(&pubkey) {
+                        continue;
+                    }
                     if !unsubscribe {
                         monitored_accounts.insert(pubkey);
                     } else {
@@ -131,13 +167,14 @@ impl RemoteAccountUpdatesWorker {
                 // Periodically we refresh runners to keep them fresh
                 _ = refresh_interval.tick() => {
                     current_refresh_index = (current_refresh_index + 1) % self.ws_urls.len();
-                    let url = self.ws_urls
+                    let (url, rate_limiter) = self.ws_urls
                         .get(current_refresh_index)
                         .unwrap()
                         .clone();
                     let new_runner = self.create_runner_from_config(
                         current_refresh_index,
                         url,
+                        rate_limiter,
                         self.commitment,
                         &monitored_accounts
                     ).await;
@@ -162,6 +199,7 @@ impl RemoteAccountUpdatesWorker {
         &self,
         index: usize,
         url: String,
+        rate_limiter: Arc<RateLimiter>,
         commitment: Option<CommitmentLevel>,
         monitored_accounts: &HashSet<Pubkey>,
     ) -> RemoteAccountUpdatesWorkerRunner {
@@ -169,7 +207,11 @@ impl RemoteAccountUpdatesWorker {
             channel(INFLIGHT_ACCOUNT_FETCHES_LIMIT);
         let first_subscribed_slots = self.first_subscribed_slots.clone();
         let last_known_update_slots = self.last_known_update_slots.clone();
+        let shard_slots = self.shard_slots.clone();
+        let remote_clock = self.remote_clock.clone();
         let runner_id = format!("[{}:{:06}]", index, self.generate_runner_id());
+        let coalesce_interval = self.coalesce_interval;
+        let fast_lane_accounts = self.fast_lane_accounts.clone();
         let cancellation_token = CancellationToken::new();
         let shard_id = runner_id.clone();
         let shard_cancellation_token = cancellation_token.clone();
@@ -177,10 +219,15 @@ impl RemoteAccountUpdatesWorker {
             let mut shard = RemoteAccountUpdatesShard::new(
                 shard_id.clone(),
                 url,
+                rate_limiter,
                 commitment,
+                coalesce_interval,
+                fast_lane_accounts,
                 monitoring_request_receiver,
                 first_subscribed_slots,
                 last_known_update_slots,
+                shard_slots.clone(),
+                remote_clock,
             );
             if let Err(error) = shard
                 .start_monitoring_request_processing(shard_cancellation_token)
@@ -192,6 +239,14 @@ impl RemoteAccountUpdatesWorker {
                 #[cfg(test)]
                 panic!("Runner shard has failed: {}: {:?}", shard_id, error);
             }
+            // A stopped shard no longer lags behind
+            shard_slots
+                .write()
+                .expect(
+                    "RwLock of RemoteAccountUpdatesWorker.shard_slots poisoned",
+                )
+                .remove(&shard_id);
+            metrics::remove_subscription_lag(&shard_id);
         });
         let runner = RemoteAccountUpdatesWorkerRunner {
             id: runner_id,
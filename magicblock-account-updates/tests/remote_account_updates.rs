@@ -1,10 +1,12 @@
-use std::time::Duration;
+use std::{collections::HashSet, time::Duration};
 
 use conjunto_transwise::RpcProviderConfig;
 use magicblock_account_updates::{
     AccountUpdates, RemoteAccountUpdatesClient, RemoteAccountUpdatesWorker,
 };
+use rate_limiter::RateLimiterConfig;
 use solana_sdk::{
+    pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
     system_program,
@@ -18,13 +20,31 @@ async fn setup() -> (
     RemoteAccountUpdatesClient,
     CancellationToken,
     tokio::task::JoinHandle<()>,
+) {
+    setup_with_fast_lane(HashSet::new()).await
+}
+
+async fn setup_with_fast_lane(
+    fast_lane_accounts: HashSet<Pubkey>,
+) -> (
+    RemoteAccountUpdatesClient,
+    CancellationToken,
+    tokio::task::JoinHandle<()>,
 ) {
     let _ = env_logger::builder().is_test(true).try_init();
     // Create account updates worker and client
     let mut worker = RemoteAccountUpdatesWorker::new(
-        vec![RpcProviderConfig::devnet().ws_url().into(); 1],
+        vec![
+            (
+                RpcProviderConfig::devnet().ws_url().into(),
+                RateLimiterConfig::default()
+            );
+            1
+        ],
         Some(solana_sdk::commitment_config::CommitmentLevel::Confirmed),
         Duration::from_secs(50 * 60),
+        Duration::from_millis(50),
+        fast_lane_accounts,
     );
     let client = RemoteAccountUpdatesClient::new(&worker);
     // Run the worker in a separate task
@@ -158,3 +178,21 @@ async fn test_devnet_monitoring_invalid_and_immutable_and_program_account() {
     cancellation_token.cancel();
     assert!(worker_handle.await.is_ok());
 }
+
+#[tokio::test]
+async fn test_devnet_monitoring_fast_lane_accounts_without_request() {
+    skip_if_devnet_down!();
+    // Create account updates worker and client
+    let sysvar_sh = slot_hashes::ID;
+    let sysvar_rent = rent::ID;
+    let (client, cancellation_token, worker_handle) =
+        setup_with_fast_lane(HashSet::from([sysvar_sh])).await;
+    // Wait for a few slots to happen on-chain
+    sleep(Duration::from_millis(2_000)).await;
+    // Fast lane accounts are monitored without being requested
+    assert!(client.get_last_known_update_slot(&sysvar_sh).is_some());
+    assert!(client.get_last_known_update_slot(&sysvar_rent).is_none());
+    // Cleanup everything correctly
+    cancellation_token.cancel();
+    assert!(worker_handle.await.is_ok());
+}
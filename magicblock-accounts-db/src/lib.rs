@@ -4,7 +4,7 @@ use config::AccountsDbConfig;
 use error::AccountsDbError;
 use index::AccountsDbIndex;
 use log::{error, warn};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use snapshot::SnapshotEngine;
 use solana_account::{
     cow::AccountBorrowed, AccountSharedData, ReadableAccount,
@@ -18,6 +18,8 @@ pub type AdbResult<T> = Result<T, AccountsDbError>;
 /// Stop the World Lock, used to halt all writes to adb while
 /// some critical operation is in action, e.g. snapshotting
 pub type StWLock = Arc<RwLock<()>>;
+/// Checksum of the accounts storage, identifies the exact database state
+pub type AdbChecksum = [u8; 32];
 
 const ACCOUNTSDB_SUB_DIR: &str = "accountsdb/main";
 
@@ -32,6 +34,10 @@ pub struct AccountsDb {
     lock: StWLock,
     /// Slot wise frequency at which snapshots should be taken
     snapshot_frequency: u64,
+    /// Slot and database checksum of the latest snapshot taken by this instance
+    latest_snapshot_checksum: Mutex<Option<(u64, AdbChecksum)>>,
+    /// Database checksum captured at startup, see [AccountsDb::capture_startup_checksum]
+    startup_checksum: Option<AdbChecksum>,
 }
 
 impl AccountsDb {
@@ -62,6 +68,8 @@ impl AccountsDb {
             snapshot_engine,
             lock,
             snapshot_frequency,
+            latest_snapshot_checksum: Mutex::default(),
+            startup_checksum: None,
         })
     }
 
@@ -257,14 +265,7 @@ impl AccountsDb {
         if remainder != 0 {
             return;
         }
-        // acquire the lock, effectively stopping the world, nothing should be able
-        // to modify underlying accounts database while this lock is active
-        let _locked = self.lock.write();
-        // flush everything before taking the snapshot, in order to ensure consistent state
-        self.flush(true);
-
-        let used_storage = self.storage.utilized_mmap();
-        if let Err(err) = self.snapshot_engine.snapshot(slot, used_storage) {
+        if let Err(err) = self.take_snapshot(slot) {
             warn!(
                 "failed to take snapshot at {}, slot {slot}: {err}",
                 self.snapshot_engine.database_path().display()
@@ -272,6 +273,65 @@ impl AccountsDb {
         }
     }
 
+    /// Takes a snapshot at the latest observed slot unless there is one
+    /// already, so that the validator restarts from the final state after a
+    /// shutdown instead of replaying the ledger from an earlier snapshot
+    pub fn take_final_snapshot(&self) -> AdbResult<u64> {
+        let slot = self.slot();
+        if self.get_latest_snapshot_slot() != Some(slot) {
+            self.take_snapshot(slot)?;
+        }
+        Ok(slot)
+    }
+
+    fn take_snapshot(&self, slot: u64) -> AdbResult<()> {
+        let (snapshot, used_len) = {
+            // acquire the lock, effectively stopping the world, nothing should be able
+            // to modify underlying accounts database while this lock is active
+            let _locked = self.lock.write();
+            // flush everything before taking the snapshot, in order to ensure consistent state
+            self.flush(true);
+
+            let used_storage = self.storage.utilized_mmap();
+            let snapshot = self.snapshot_engine.snapshot(slot, used_storage)?;
+            (snapshot, used_storage.len())
+        };
+        // hash the snapshot once the lock is released, so that
+        // writers don't have to wait for the entire storage to be hashed
+        let checksum = SnapshotEngine::checksum(&snapshot, used_len)?;
+        *self.latest_snapshot_checksum.lock() = Some((slot, checksum));
+        Ok(())
+    }
+
+    /// Returns the slot and checksum of the latest snapshot taken since
+    /// the database was opened, snapshots found on disk at startup are
+    /// not included
+    pub fn latest_snapshot_checksum(&self) -> Option<(u64, AdbChecksum)> {
+        *self.latest_snapshot_checksum.lock()
+    }
+
+    /// Computes the checksum of the current database state, which matches
+    /// the checksum of a snapshot if no writes happened after it was taken
+    pub fn checksum(&self) -> AdbChecksum {
+        // make sure that no one is writing to the database
+        let _locked = self.lock.write();
+        blake3::hash(self.storage.utilized_mmap()).into()
+    }
+
+    /// Captures the checksum of the database if its current slot matches the
+    /// latest snapshot, this has to happen before any other writes in order to
+    /// verify that the state didn't diverge from the snapshot
+    pub fn capture_startup_checksum(&mut self) {
+        self.startup_checksum = (self.get_latest_snapshot_slot()
+            == Some(self.slot()))
+        .then(|| self.checksum());
+    }
+
+    /// Returns the checksum captured via [AccountsDb::capture_startup_checksum]
+    pub fn startup_checksum(&self) -> Option<AdbChecksum> {
+        self.startup_checksum
+    }
+
     /// Returns slot of latest snapshot or None
     /// Parses path to extract slot
     pub fn get_latest_snapshot_slot(&self) -> Option<u64> {
@@ -342,6 +402,17 @@ impl AccountsDb {
         self.storage.size()
     }
 
+    /// Get the total number of bytes the snapshots take up on disk
+    pub fn snapshots_storage_size(&self) -> u64 {
+        self.snapshot_engine.storage_size()
+    }
+
+    /// Removes all but the `keep` most recent snapshots in order to free up
+    /// disk space, returns the number of snapshots removed
+    pub fn prune_snapshots(&self, keep: usize) -> usize {
+        self.snapshot_engine.prune(keep)
+    }
+
     /// Returns an iterator over all accounts in the database,
     pub fn iter_all(
         &self,
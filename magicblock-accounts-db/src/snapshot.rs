@@ -4,7 +4,7 @@ use std::{
     fs,
     fs::File,
     io,
-    io::Write,
+    io::{Read, Write},
     path::{Path, PathBuf},
 };
 
@@ -13,7 +13,9 @@ use memmap2::MmapMut;
 use parking_lot::Mutex;
 use reflink::reflink;
 
-use crate::{error::AccountsDbError, log_err, storage::ADB_FILE, AdbResult};
+use crate::{
+    error::AccountsDbError, log_err, storage::ADB_FILE, AdbChecksum, AdbResult,
+};
 
 pub struct SnapshotEngine {
     /// directory path where database files are kept
@@ -48,7 +50,13 @@ impl SnapshotEngine {
 
     /// Take snapshot of database directory, this operation
     /// assumes that no writers are currently active
-    pub(crate) fn snapshot(&self, slot: u64, mmap: &[u8]) -> AdbResult<()> {
+    ///
+    /// Returns the path of the new snapshot
+    pub(crate) fn snapshot(
+        &self,
+        slot: u64,
+        mmap: &[u8],
+    ) -> AdbResult<PathBuf> {
         let slot = SnapSlot(slot);
         // this lock is always free, as we take StWLock higher up in the call stack and
         // only one thread can take snapshots, namely the one that advances the slot
@@ -66,8 +74,21 @@ impl SnapshotEngine {
         } else {
             rcopy_dir(&self.dbpath, &snapout, mmap)?;
         }
-        snapshots.push_back(snapout);
-        Ok(())
+        snapshots.push_back(snapout.clone());
+        Ok(snapout)
+    }
+
+    /// Computes the checksum of the first `len` bytes of the main accounts db
+    /// file in the snapshot, which is immutable once taken, thus this doesn't
+    /// require holding any lock on the primary database
+    pub(crate) fn checksum(
+        snapshot: &Path,
+        len: usize,
+    ) -> io::Result<AdbChecksum> {
+        let file = File::open(snapshot.join(ADB_FILE))?;
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(file.take(len as u64))?;
+        Ok(hasher.finalize().into())
     }
 
     /// Provides read-only access to the internal snapshots queue.
@@ -145,6 +166,30 @@ impl SnapshotEngine {
         &self.dbpath
     }
 
+    /// Removes the oldest snapshots until at most `keep` are left, returns
+    /// the number of snapshots removed
+    pub(crate) fn prune(&self, keep: usize) -> usize {
+        let mut snapshots = self.snapshots.lock();
+        let mut removed = 0;
+        while snapshots.len() > keep {
+            let Some(old) = snapshots.pop_front() else {
+                break;
+            };
+            info!("pruning snapshot at {}", old.display());
+            let _ = fs::remove_dir_all(&old)
+                .inspect_err(log_err!("error during snapshot pruning"));
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Returns the number of bytes the snapshots take up on disk
+    pub(crate) fn storage_size(&self) -> u64 {
+        self.with_snapshots(|snapshots| {
+            snapshots.iter().map(|path| dir_size(path)).sum()
+        })
+    }
+
     /// Perform test to find out whether file system
     /// supports CoW operations (btrfs, xfs, zfs, apfs)
     fn supports_cow(dir: &Path) -> io::Result<bool> {
@@ -239,6 +284,22 @@ impl SnapSlot {
     }
 }
 
+/// Sums up the size of all files in the directory, files that can't be
+/// read are skipped
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
 /// Conventional byte to byte recursive directory copy,
 /// works on all filesystems. Ideally this should only
 /// be used for development purposes, and performance
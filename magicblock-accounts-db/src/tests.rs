@@ -258,6 +258,50 @@ fn test_take_snapshot() {
     );
 }
 
+#[test]
+fn test_prune_snapshots() {
+    let tenv = init_test_env();
+    tenv.account();
+
+    for i in 1..=3 {
+        tenv.set_slot(i * SNAPSHOT_FREQUENCY);
+    }
+    assert!(tenv.snapshots_storage_size() > 0);
+
+    assert_eq!(tenv.prune_snapshots(1), 2);
+    assert!(!tenv.snapshot_exists(SNAPSHOT_FREQUENCY));
+    assert!(!tenv.snapshot_exists(2 * SNAPSHOT_FREQUENCY));
+    assert!(tenv.snapshot_exists(3 * SNAPSHOT_FREQUENCY));
+    assert_eq!(
+        tenv.get_oldest_snapshot_slot(),
+        Some(3 * SNAPSHOT_FREQUENCY)
+    );
+    assert_eq!(tenv.prune_snapshots(1), 0);
+}
+
+#[test]
+fn test_take_final_snapshot() {
+    let tenv = init_test_env();
+    tenv.account();
+
+    tenv.set_slot(SNAPSHOT_FREQUENCY + 1);
+    assert!(matches!(
+        tenv.take_final_snapshot(),
+        Ok(slot) if slot == SNAPSHOT_FREQUENCY + 1
+    ));
+    assert!(tenv.snapshot_exists(SNAPSHOT_FREQUENCY + 1));
+    assert_eq!(
+        tenv.get_latest_snapshot_slot(),
+        Some(SNAPSHOT_FREQUENCY + 1)
+    );
+
+    // No second snapshot is taken at the same slot
+    assert!(matches!(
+        tenv.take_final_snapshot(),
+        Ok(slot) if slot == SNAPSHOT_FREQUENCY + 1
+    ));
+}
+
 #[test]
 fn test_restore_from_snapshot() {
     let mut tenv = init_test_env();
@@ -298,6 +342,46 @@ fn test_restore_from_snapshot() {
     assert_eq!(tenv.slot(), SNAPSHOT_FREQUENCY);
 }
 
+#[test]
+fn test_snapshot_checksum() {
+    let mut tenv = init_test_env();
+    let mut acc = tenv.account();
+
+    assert!(tenv.latest_snapshot_checksum().is_none());
+    tenv.set_slot(SNAPSHOT_FREQUENCY); // trigger snapshot
+    let (slot, checksum) = tenv
+        .latest_snapshot_checksum()
+        .expect("snapshot checksum should have been recorded");
+    assert_eq!(slot, SNAPSHOT_FREQUENCY);
+    assert_eq!(
+        tenv.checksum(),
+        checksum,
+        "unmodified database should match the snapshot checksum"
+    );
+
+    acc.account.set_lamports(42);
+    tenv.insert_account(&acc.pubkey, &acc.account);
+    assert_ne!(
+        tenv.checksum(),
+        checksum,
+        "modified database should not match the snapshot checksum"
+    );
+
+    tenv.set_slot(SNAPSHOT_FREQUENCY * 2 + 1);
+    assert!(
+        matches!(
+            tenv.ensure_at_most(SNAPSHOT_FREQUENCY),
+            Ok(SNAPSHOT_FREQUENCY)
+        ),
+        "failed to rollback to snapshot"
+    );
+    assert_eq!(
+        tenv.checksum(),
+        checksum,
+        "restored database should match the snapshot checksum"
+    );
+}
+
 #[test]
 fn test_get_all_accounts_after_rollback() {
     let mut tenv = init_test_env();
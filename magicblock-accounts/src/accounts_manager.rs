@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{iter, sync::Arc};
 
 use conjunto_transwise::{
     transaction_accounts_extractor::TransactionAccountsExtractorImpl,
@@ -7,13 +7,16 @@ use conjunto_transwise::{
 use magicblock_account_cloner::{CloneOutputMap, RemoteAccountClonerClient};
 use magicblock_accounts_api::BankAccountProvider;
 use magicblock_bank::bank::Bank;
+use magicblock_core::traits::PersistsCommitRecords;
 use magicblock_transaction_status::TransactionStatusSender;
 use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{commitment_config::CommitmentConfig, signature::Keypair};
+use url::Url;
 
 use crate::{
     config::AccountsConfig, errors::AccountsResult,
     remote_account_committer::RemoteAccountCommitter,
+    remote_program_accounts_provider::RemoteProgramAccountsProvider,
     remote_scheduled_commits_processor::RemoteScheduledCommitsProcessor,
     utils::try_rpc_cluster_from_cluster, ExternalAccountsManager,
 };
@@ -25,6 +28,7 @@ pub type AccountsManager = ExternalAccountsManager<
     TransactionAccountsExtractorImpl,
     TransactionAccountsValidatorImpl,
     RemoteScheduledCommitsProcessor,
+    RemoteProgramAccountsProvider,
 >;
 
 impl AccountsManager {
@@ -35,18 +39,41 @@ impl AccountsManager {
         transaction_status_sender: Option<TransactionStatusSender>,
         validator_keypair: Keypair,
         config: AccountsConfig,
+        commit_record_persister: Option<Arc<dyn PersistsCommitRecords>>,
     ) -> AccountsResult<Self> {
         let remote_cluster = config.remote_cluster;
         let internal_account_provider = BankAccountProvider::new(bank.clone());
         let rpc_cluster = try_rpc_cluster_from_cluster(&remote_cluster)?;
-        let rpc_client = RpcClient::new_with_commitment(
-            rpc_cluster.url().to_string(),
-            CommitmentConfig::confirmed(),
-        );
+        let commit_cluster = config
+            .commit_remote_cluster
+            .unwrap_or_else(|| remote_cluster.clone());
+        let commit_rpc_cluster = try_rpc_cluster_from_cluster(&commit_cluster)?;
+        let commit_rpc_clients =
+            iter::once(commit_rpc_cluster.url().to_string())
+                .chain(
+                    config.commit_fallback_remotes.iter().map(Url::to_string),
+                )
+                .map(|url| {
+                    RpcClient::new_with_commitment(
+                        url,
+                        CommitmentConfig::confirmed(),
+                    )
+                })
+                .collect();
+        let program_accounts_provider =
+            RemoteProgramAccountsProvider::new(RpcClient::new_with_commitment(
+                rpc_cluster.url().to_string(),
+                CommitmentConfig::confirmed(),
+            ));
         let account_committer = RemoteAccountCommitter::new(
-            rpc_client,
+            commit_rpc_clients,
+            commit_cluster.ws_urls().into_iter().next(),
             validator_keypair,
             config.commit_compute_unit_price,
+            config.commit_priority_fee,
+            config.commit_max_requested_compute_unit_price,
+            config.commit_durable_nonce,
+            config.commit_jito,
         );
 
         let scheduled_commits_processor = RemoteScheduledCommitsProcessor::new(
@@ -54,6 +81,10 @@ impl AccountsManager {
             bank.clone(),
             cloned_accounts.clone(),
             transaction_status_sender.clone(),
+            commit_record_persister,
+            config.commit_changed_only,
+            config.commit_simulate,
+            config.commit_webhook,
         );
 
         Ok(Self {
@@ -64,6 +95,7 @@ impl AccountsManager {
             transaction_accounts_validator: TransactionAccountsValidatorImpl,
             lifecycle: config.lifecycle,
             scheduled_commits_processor,
+            program_accounts_provider,
             external_commitable_accounts: Default::default(),
         })
     }
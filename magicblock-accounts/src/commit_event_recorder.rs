@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use log::*;
+use magicblock_bank::bank::Bank;
+use magicblock_core::commit_record::CommitRecord;
+use magicblock_processor::execute_transaction::execute_legacy_transaction;
+use magicblock_program::magicblock_instruction::record_commit_event;
+use magicblock_transaction_status::TransactionStatusSender;
+
+// -----------------
+// CommitEventRecorder
+// -----------------
+/// Records the outcome of scheduled commits in the commit events account of
+/// the ephemeral rollup.
+///
+/// Each event is recorded by a transaction of the validator authority, thus
+/// clients learn about the outcome of their commits via a plain
+/// `accountSubscribe` to the commit events account or a `logsSubscribe`
+/// mentioning it.
+#[derive(Clone)]
+pub(crate) struct CommitEventRecorder {
+    bank: Arc<Bank>,
+    transaction_status_sender: Option<TransactionStatusSender>,
+}
+
+impl CommitEventRecorder {
+    pub(crate) fn new(
+        bank: Arc<Bank>,
+        transaction_status_sender: Option<TransactionStatusSender>,
+    ) -> Self {
+        Self {
+            bank,
+            transaction_status_sender,
+        }
+    }
+
+    pub(crate) fn record(&self, record: &CommitRecord) {
+        let transaction =
+            record_commit_event(record.clone(), self.bank.last_blockhash());
+        if let Err(err) = execute_legacy_transaction(
+            transaction,
+            &self.bank,
+            self.transaction_status_sender.as_ref(),
+        ) {
+            error!(
+                "Failed to record event of commit {}: {:?}",
+                record.commit_id, err
+            );
+        }
+    }
+}
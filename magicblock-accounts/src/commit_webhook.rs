@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use log::*;
+use magicblock_core::commit_record::{CommitOutcome, CommitRecord};
+use serde_json::{json, Value};
+
+use crate::CommitWebhookConfig;
+
+/// How long a single delivery attempt may take
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Delay before the first retry, doubled with each following one
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+// -----------------
+// CommitWebhook
+// -----------------
+/// Posts JSON events about the outcome of scheduled commits to a webhook, so
+/// that backends can react to them without polling the base layer.
+///
+/// Events are delivered in the background and dropped after the configured
+/// number of retries, thus they must not be relied upon for correctness.
+pub(crate) struct CommitWebhook {
+    client: reqwest::Client,
+    config: CommitWebhookConfig,
+}
+
+impl CommitWebhook {
+    pub(crate) fn new(config: CommitWebhookConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    /// Sends the events for the current outcome of the commit, only final
+    /// outcomes are of interest
+    pub(crate) fn notify(&self, record: &CommitRecord) {
+        let mut events = vec![];
+        match &record.outcome {
+            // Nothing needed to be sent since the base layer is up to date
+            CommitOutcome::NotNeeded | CommitOutcome::Confirmed => {
+                events.push(commit_event("commit_succeeded", record, None));
+                if record.requested_undelegation {
+                    events.push(commit_event(
+                        "undelegation_completed",
+                        record,
+                        None,
+                    ));
+                }
+            }
+            CommitOutcome::Failed(err) => {
+                events.push(commit_event("commit_failed", record, Some(err)));
+            }
+            CommitOutcome::Pending | CommitOutcome::Finalized => {}
+        }
+        for event in events {
+            let client = self.client.clone();
+            let config = self.config.clone();
+            tokio::spawn(async move {
+                deliver(&client, &config, event).await;
+            });
+        }
+    }
+}
+
+fn commit_event(
+    event: &str,
+    record: &CommitRecord,
+    error: Option<&String>,
+) -> Value {
+    json!({
+        "event": event,
+        "commit_id": record.commit_id,
+        "slot": record.slot,
+        "payer": record.payer.to_string(),
+        "accounts": record
+            .included_pubkeys
+            .iter()
+            .map(|pubkey| pubkey.to_string())
+            .collect::<Vec<_>>(),
+        "excluded_accounts": record
+            .excluded_pubkeys
+            .iter()
+            .map(|pubkey| pubkey.to_string())
+            .collect::<Vec<_>>(),
+        "requested_undelegation": record.requested_undelegation,
+        "signatures": record
+            .chain_signatures
+            .iter()
+            .map(|signature| signature.to_string())
+            .collect::<Vec<_>>(),
+        "error": error,
+    })
+}
+
+async fn deliver(
+    client: &reqwest::Client,
+    config: &CommitWebhookConfig,
+    event: Value,
+) {
+    let mut retry_delay = INITIAL_RETRY_DELAY;
+    for attempt in 0..=config.max_retries {
+        if attempt > 0 {
+            tokio::time::sleep(retry_delay).await;
+            retry_delay *= 2;
+        }
+        let mut request = client
+            .post(config.url.clone())
+            .timeout(DELIVERY_TIMEOUT)
+            .json(&event);
+        if let Some(auth_header) = &config.auth_header {
+            request =
+                request.header(reqwest::header::AUTHORIZATION, auth_header);
+        }
+        match request.send().await.and_then(|res| res.error_for_status()) {
+            Ok(_) => return,
+            Err(err) => debug!(
+                "Failed to deliver webhook event (attempt {}): {:?}",
+                attempt + 1,
+                err
+            ),
+        }
+    }
+    warn!(
+        "Dropped webhook event {} for commit {} after {} retries",
+        event["event"], event["commit_id"], config.max_retries
+    );
+}
@@ -1,18 +1,90 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
 use magicblock_account_cloner::AccountClonerPermissions;
 use magicblock_mutator::Cluster;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair};
+use url::Url;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct AccountsConfig {
     pub remote_cluster: Cluster,
+    /// The cluster commits are sent to, `None` if it is the [Self::remote_cluster]
+    pub commit_remote_cluster: Option<Cluster>,
+    /// Endpoints commits are sent through when sending them to the commit
+    /// remote fails
+    pub commit_fallback_remotes: Vec<Url>,
     pub lifecycle: LifecycleMode,
     pub commit_compute_unit_price: u64,
+    /// Skip committing accounts of scheduled commits that didn't change
+    pub commit_changed_only: bool,
+    /// Simulate commit transactions on the remote cluster before sending them
+    pub commit_simulate: bool,
+    pub commit_priority_fee: Option<PriorityFeeConfig>,
+    /// Cap of the compute unit price scheduled commits may request, `0`
+    /// ignores requested prices
+    pub commit_max_requested_compute_unit_price: u64,
+    pub commit_durable_nonce: Option<DurableNonceConfig>,
+    pub commit_jito: Option<JitoConfig>,
+    pub commit_webhook: Option<CommitWebhookConfig>,
     pub payer_init_lamports: Option<u64>,
     pub allowed_program_ids: Option<HashSet<Pubkey>>,
 }
 
+/// Bounds within which the compute unit price of commits is chosen from the
+/// recent prioritization fees of the remote cluster
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriorityFeeConfig {
+    pub min_compute_unit_price: u64,
+    pub max_compute_unit_price: u64,
+    /// Percentile (0-100) of the recent fees that is offered
+    pub percentile: u8,
+}
+
+/// Durable nonce that commit transactions are signed with so that they can be
+/// retried until they land, regardless of blockhash expiry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DurableNonceConfig {
+    /// The nonce account whose authority is the validator identity
+    pub account: Pubkey,
+    /// How long to wait for a commit transaction to land before resending it
+    pub retry_interval: Duration,
+    /// How often a commit transaction is sent before giving up, `None` retries
+    /// until it lands
+    pub max_retries: Option<u32>,
+}
+
+/// Jito block engine that commit transactions are submitted to as bundles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JitoConfig {
+    /// The JSON RPC endpoint of the block engine
+    pub url: String,
+    /// The tip in lamports paid with each bundle
+    pub tip_lamports: u64,
+}
+
+/// Webhook that the outcomes of scheduled commits are posted to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitWebhookConfig {
+    pub url: Url,
+    /// Value of the `Authorization` header sent with each event
+    pub auth_header: Option<String>,
+    /// How often delivering an event is retried before it is dropped
+    pub max_retries: u32,
+}
+
+/// Monitoring of the balance of the payer of commits on the remote cluster
+#[derive(Debug)]
+pub struct PayerMonitorConfig {
+    /// How often the balance of the payer is checked
+    pub check_interval: Duration,
+    /// Below this balance an alert is raised and the payer is topped up
+    pub min_lamports: u64,
+    /// The treasury the payer is topped up from, `None` only monitors it
+    pub treasury: Option<Keypair>,
+    /// The lamports transferred from the treasury with each top-up
+    pub top_up_lamports: u64,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum LifecycleMode {
     Replica,
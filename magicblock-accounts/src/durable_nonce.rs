@@ -0,0 +1,252 @@
+use std::time::{Duration, Instant};
+
+use log::*;
+use magicblock_metrics::metrics;
+use solana_rpc_client::{
+    nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction,
+};
+use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    nonce::state::{State, Versions},
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    system_instruction, system_program,
+    transaction::Transaction,
+};
+use tokio::sync::Mutex;
+
+use crate::{
+    errors::{AccountsError, AccountsResult},
+    DurableNonceConfig,
+};
+
+/// How often we check if a sent commit transaction landed
+const LANDED_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+// -----------------
+// DurableNonce
+// -----------------
+/// Durable nonce that commit transactions use instead of a recent blockhash.
+///
+/// Since such a transaction doesn't expire it can be sent again until it
+/// lands, even across long outages of the remote cluster.
+/// Once it lands the nonce advances which invalidates any duplicate of it,
+/// thus each commit is delivered exactly once.
+pub(crate) struct DurableNonce {
+    account: Pubkey,
+    retry_interval: Duration,
+    max_retries: Option<u32>,
+    /// Only one transaction may use the nonce at a time, otherwise all but
+    /// the first one to land are invalidated when it advances the nonce
+    in_flight: Mutex<()>,
+}
+
+/// Tracks the delivery attempts of a single commit transaction
+#[derive(Debug, Default)]
+struct CommitRetryState {
+    /// How often the transaction was sent again after not landing in time
+    retries: u32,
+    /// How often the transaction was signed again with an advanced nonce
+    resigned: u32,
+    last_error: Option<String>,
+}
+
+impl DurableNonce {
+    pub(crate) fn new(config: DurableNonceConfig) -> Self {
+        Self {
+            account: config.account,
+            retry_interval: config.retry_interval,
+            max_retries: config.max_retries,
+            in_flight: Mutex::default(),
+        }
+    }
+
+    /// The instruction advancing the nonce, it has to be the first
+    /// instruction of each transaction using the nonce
+    pub(crate) fn advance_instruction(
+        &self,
+        authority: &Pubkey,
+    ) -> Instruction {
+        system_instruction::advance_nonce_account(&self.account, authority)
+    }
+
+    /// Fetches the current value of the nonce which replaces the recent
+    /// blockhash of transactions using it
+    pub(crate) async fn fetch_nonce(
+        &self,
+        rpc_client: &RpcClient,
+    ) -> AccountsResult<Hash> {
+        let account = rpc_client
+            .get_account_with_commitment(
+                &self.account,
+                CommitmentConfig::confirmed(),
+            )
+            .await
+            .map_err(|err| {
+                AccountsError::FailedToGetDurableNonce(err.to_string())
+            })?
+            .value
+            .ok_or_else(|| {
+                AccountsError::FailedToGetDurableNonce(format!(
+                    "Nonce account {} not found",
+                    self.account
+                ))
+            })?;
+        nonce_from_account(&self.account, &account)
+    }
+
+    /// Sends the transaction until it lands on the remote cluster, signing it
+    /// again whenever the nonce it uses was advanced without it landing.
+    /// Returns the signature of the transaction that landed, which differs
+    /// from the signature it was created with if it had to be signed again.
+    pub(crate) async fn deliver(
+        &self,
+        rpc_client: &RpcClient,
+        authority: &Keypair,
+        mut transaction: Transaction,
+    ) -> Result<Signature, String> {
+        let _in_flight = self.in_flight.lock().await;
+        let mut state = CommitRetryState::default();
+        loop {
+            match self
+                .try_deliver(
+                    rpc_client,
+                    authority,
+                    &mut transaction,
+                    &mut state,
+                )
+                .await
+            {
+                Ok(Some(signature)) => {
+                    if state.retries > 0 {
+                        info!(
+                            "Commit transaction '{}' landed after {} retries",
+                            signature, state.retries
+                        );
+                    }
+                    return Ok(signature);
+                }
+                Ok(None) => {
+                    state.last_error = Some(format!(
+                        "Transaction did not land within {:?}",
+                        self.retry_interval
+                    ));
+                }
+                Err(err) => {
+                    state.last_error = Some(err);
+                    tokio::time::sleep(self.retry_interval).await;
+                }
+            }
+
+            if self
+                .max_retries
+                .is_some_and(|max_retries| state.retries >= max_retries)
+            {
+                return Err(format!(
+                    "Gave up delivering commit transaction '{}': {:?}",
+                    transaction.get_signature(),
+                    state
+                ));
+            }
+            state.retries += 1;
+            metrics::inc_account_commit_retries();
+            warn!(
+                "Retrying commit transaction '{}': {:?}",
+                transaction.get_signature(),
+                state
+            );
+        }
+    }
+
+    /// Sends the transaction once and waits for it to land.
+    /// Returns `None` if it didn't land within the retry interval.
+    async fn try_deliver(
+        &self,
+        rpc_client: &RpcClient,
+        authority: &Keypair,
+        transaction: &mut Transaction,
+        state: &mut CommitRetryState,
+    ) -> Result<Option<Signature>, String> {
+        // The nonce has to be fetched before checking if the transaction
+        // landed, otherwise it could land in between and we'd sign it again
+        let nonce = self
+            .fetch_nonce(rpc_client)
+            .await
+            .map_err(|err| err.to_string())?;
+        if transaction.message.recent_blockhash != nonce {
+            // The nonce advanced, either because our transaction landed or
+            // because it was created with an outdated nonce
+            if landed(rpc_client, transaction.get_signature()).await? {
+                return Ok(Some(*transaction.get_signature()));
+            }
+            transaction
+                .try_sign(&[authority], nonce)
+                .map_err(|err| err.to_string())?;
+            state.resigned += 1;
+        }
+
+        // Sending the same transaction again is a noop if it landed already
+        let signature = rpc_client
+            .send_transaction_with_config(
+                &*transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let deadline = Instant::now() + self.retry_interval;
+        while Instant::now() < deadline {
+            tokio::time::sleep(LANDED_POLL_INTERVAL).await;
+            if landed(rpc_client, &signature).await? {
+                return Ok(Some(signature));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Determines if the transaction was included in a block, regardless of
+/// whether it succeeded
+async fn landed(
+    rpc_client: &RpcClient,
+    signature: &Signature,
+) -> Result<bool, String> {
+    rpc_client
+        .get_signature_status_with_commitment_and_history(
+            signature,
+            CommitmentConfig::confirmed(),
+            true,
+        )
+        .await
+        .map(|status| status.is_some())
+        .map_err(|err| err.to_string())
+}
+
+fn nonce_from_account(
+    pubkey: &Pubkey,
+    account: &Account,
+) -> AccountsResult<Hash> {
+    if account.owner != system_program::id() {
+        return Err(AccountsError::FailedToGetDurableNonce(format!(
+            "Account {} is not a nonce account, it is owned by {}",
+            pubkey, account.owner
+        )));
+    }
+    let versions =
+        bincode::deserialize::<Versions>(&account.data).map_err(|err| {
+            AccountsError::FailedToGetDurableNonce(err.to_string())
+        })?;
+    match versions.state() {
+        State::Initialized(data) => Ok(data.blockhash()),
+        State::Uninitialized => Err(AccountsError::FailedToGetDurableNonce(
+            format!("Nonce account {} is not initialized", pubkey),
+        )),
+    }
+}
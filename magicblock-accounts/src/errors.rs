@@ -1,7 +1,8 @@
 use std::collections::HashSet;
 
 use magicblock_account_cloner::{
-    AccountClonerError, AccountClonerUnclonableReason,
+    AccountClonerError, AccountClonerUnclonableReason, DelegationRevocation,
+    InvalidDelegationReason,
 };
 use solana_sdk::pubkey::Pubkey;
 use thiserror::Error;
@@ -28,6 +29,17 @@ pub enum AccountsError {
         AccountClonerUnclonableReason,
     ),
 
+    #[error("InvalidDelegation '{0}' ('{1:?}')")]
+    InvalidDelegation(Pubkey, InvalidDelegationReason),
+
+    #[error("DelegationRevoked '{0}' ('{1:?}')")]
+    DelegationRevoked(Pubkey, DelegationRevocation),
+
+    /// Nonce accounts of the base layer can't be delegated, hence only those
+    /// created on the ephemeral can be advanced by durable nonce transactions
+    #[error("NonceAccountNotLocalToEphemeral '{0}'")]
+    NonceAccountNotLocalToEphemeral(Pubkey),
+
     #[error("InvalidRpcUrl '{0}'")]
     InvalidRpcUrl(String),
 
@@ -46,6 +58,24 @@ pub enum AccountsError {
     #[error("FailedToSendCommitTransaction '{0}'")]
     FailedToSendCommitTransaction(String, HashSet<Pubkey>, HashSet<Pubkey>),
 
+    #[error("FailedToGetProgramAccounts '{0}'")]
+    FailedToGetProgramAccounts(String),
+
+    #[error("FailedToGetDurableNonce '{0}'")]
+    FailedToGetDurableNonce(String),
+
+    #[error("FailedToGetJitoTipAccounts '{0}'")]
+    FailedToGetJitoTipAccounts(String),
+
+    #[error("FailedToGetPayerBalance '{0}'")]
+    FailedToGetPayerBalance(String),
+
+    #[error("FailedToTopUpPayer '{0}'")]
+    FailedToTopUpPayer(String),
+
+    #[error("FailedToSerializeCommitTransaction '{0}'")]
+    FailedToSerializeCommitTransaction(String),
+
     #[error("Too many committees: {0}")]
     TooManyCommittees(usize),
 }
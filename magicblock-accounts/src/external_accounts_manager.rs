@@ -1,5 +1,5 @@
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    collections::{hash_map::Entry, HashMap, HashSet},
     sync::{Arc, RwLock},
     time::Duration,
     vec,
@@ -12,28 +12,42 @@ use conjunto_transwise::{
     transaction_accounts_validator::TransactionAccountsValidator,
     AccountChainSnapshotShared, AccountChainState, CommitFrequency,
 };
-use futures_util::future::{try_join, try_join_all};
+use futures_util::{
+    future::{try_join, try_join_all},
+    stream, StreamExt,
+};
 use log::*;
-use magicblock_account_cloner::{AccountCloner, AccountClonerOutput};
+use magicblock_account_cloner::{
+    AccountCloner, AccountClonerOutput, AccountClonerUnclonableReason,
+};
 use magicblock_accounts_api::InternalAccountProvider;
 use magicblock_core::magic_program;
+use magicblock_program::magicblock_instruction::CommitPriority;
+use solana_rpc_client_api::filter::RpcFilterType;
 use solana_sdk::{
     account::{AccountSharedData, ReadableAccount},
+    address_lookup_table,
     hash::Hash,
     pubkey::Pubkey,
     signature::Signature,
-    transaction::SanitizedTransaction,
+    transaction::{SanitizedTransaction, VersionedTransaction},
 };
+use tracing::Instrument;
 
 use crate::{
     errors::{AccountsError, AccountsResult},
     traits::AccountCommitter,
     utils::get_epoch,
-    AccountCommittee, CommitAccountsPayload, LifecycleMode,
-    PendingCommitTransaction, ScheduledCommitsProcessor,
-    SendableCommitAccountsPayload,
+    AccountCommittee, ClonedProgramAccounts, CommitAccountsPayload,
+    LifecycleMode, PendingCommitTransaction, ProgramAccountsProvider,
+    ScheduledCommitsProcessor, SendableCommitAccountsPayload,
 };
 
+/// The max number of program accounts that are cloned at the same time.
+/// Kept in line with the concurrency used when hydrating accounts on startup
+/// in order to not get rate limited by the remote RPC provider.
+const CLONE_PROGRAM_ACCOUNTS_CONCURRENCY: usize = 30;
+
 #[derive(Debug)]
 pub struct ExternalCommitableAccount {
     pubkey: Pubkey,
@@ -79,7 +93,7 @@ impl ExternalCommitableAccount {
 }
 
 #[derive(Debug)]
-pub struct ExternalAccountsManager<IAP, ACL, ACM, TAE, TAV, SCP>
+pub struct ExternalAccountsManager<IAP, ACL, ACM, TAE, TAV, SCP, PAP>
 where
     IAP: InternalAccountProvider,
     ACL: AccountCloner,
@@ -87,6 +101,7 @@ where
     TAE: TransactionAccountsExtractor,
     TAV: TransactionAccountsValidator,
     SCP: ScheduledCommitsProcessor,
+    PAP: ProgramAccountsProvider,
 {
     pub internal_account_provider: IAP,
     pub account_cloner: ACL,
@@ -94,13 +109,14 @@ where
     pub transaction_accounts_extractor: TAE,
     pub transaction_accounts_validator: TAV,
     pub scheduled_commits_processor: SCP,
+    pub program_accounts_provider: PAP,
     pub lifecycle: LifecycleMode,
     pub external_commitable_accounts:
         RwLock<HashMap<Pubkey, ExternalCommitableAccount>>,
 }
 
-impl<IAP, ACL, ACM, TAE, TAV, SCP>
-    ExternalAccountsManager<IAP, ACL, ACM, TAE, TAV, SCP>
+impl<IAP, ACL, ACM, TAE, TAV, SCP, PAP>
+    ExternalAccountsManager<IAP, ACL, ACM, TAE, TAV, SCP, PAP>
 where
     IAP: InternalAccountProvider,
     ACL: AccountCloner,
@@ -108,16 +124,34 @@ where
     TAE: TransactionAccountsExtractor,
     TAV: TransactionAccountsValidator,
     SCP: ScheduledCommitsProcessor,
+    PAP: ProgramAccountsProvider,
 {
     pub async fn ensure_accounts(
         &self,
         tx: &SanitizedTransaction,
     ) -> AccountsResult<Vec<Signature>> {
         // Extract all acounts from the transaction
-        let accounts_holder = self
+        let mut accounts_holder = self
             .transaction_accounts_extractor
             .try_accounts_from_sanitized_transaction(tx)
             .map_err(Box::new)?;
+        // Lookup tables created and modified on the ER are local to it and
+        // thus neither cloned nor required to be delegated
+        let local_lookup_tables = lookup_tables_modified_by_transaction(tx);
+        accounts_holder
+            .writable
+            .retain(|pubkey| !local_lookup_tables.contains(pubkey));
+        // The nonce account of a durable nonce transaction has to be local
+        // as well, it is checked separately since it is advanced and thus
+        // written by the transaction
+        if self.lifecycle.requires_ephemeral_validation() {
+            if let Some(nonce_pubkey) = tx.get_durable_nonce() {
+                self.ensure_local_nonce_account(nonce_pubkey).await?;
+                accounts_holder
+                    .writable
+                    .retain(|pubkey| pubkey != nonce_pubkey);
+            }
+        }
         // Make sure all accounts used by the transaction are cloned properly if needed
         self.ensure_accounts_from_holder(
             accounts_holder,
@@ -126,7 +160,136 @@ where
         .await
     }
 
-    // Direct use for tests only
+    /// Nonce accounts of the base layer are owned by the system program and
+    /// can't be delegated, they are cloned as undelegated accounts which can't
+    /// be written to. Hence only nonce accounts created on the ephemeral can
+    /// be used by durable nonce transactions.
+    async fn ensure_local_nonce_account(
+        &self,
+        pubkey: &Pubkey,
+    ) -> AccountsResult<()> {
+        match self
+            .account_cloner
+            .clone_account(pubkey)
+            .await
+            .map_err(AccountsError::AccountClonerError)?
+        {
+            AccountClonerOutput::Unclonable {
+                reason: AccountClonerUnclonableReason::AlreadyLocallyOverriden,
+                ..
+            } => Ok(()),
+            _ => Err(AccountsError::NonceAccountNotLocalToEphemeral(*pubkey)),
+        }
+    }
+
+    /// Clones the address lookup tables referenced by a versioned transaction.
+    /// They need to be present before the transaction can be sanitized since
+    /// that resolves the addresses it loads from them.
+    /// The tables are cloned as readonly accounts and thus kept up to date
+    /// when they are extended on chain.
+    pub async fn ensure_address_lookup_tables(
+        &self,
+        tx: &VersionedTransaction,
+    ) -> AccountsResult<Vec<Signature>> {
+        let Some(address_table_lookups) = tx.message.address_table_lookups()
+        else {
+            return Ok(vec![]);
+        };
+        let lookup_tables = address_table_lookups
+            .iter()
+            .map(|lookup| lookup.account_key)
+            .collect::<HashSet<_>>();
+        let clone_outputs = try_join_all(
+            lookup_tables
+                .iter()
+                .map(|pubkey| self.account_cloner.clone_account(pubkey)),
+        )
+        .await
+        .map_err(AccountsError::AccountClonerError)?;
+
+        let mut signatures = vec![];
+        for clone_output in clone_outputs {
+            match clone_output {
+                AccountClonerOutput::Cloned { signature, .. } => {
+                    signatures.push(signature)
+                }
+                AccountClonerOutput::Unclonable { pubkey, reason, .. } => {
+                    // Sanitizing the transaction will fail with a proper error
+                    debug!(
+                        "Address lookup table '{}' is unclonable: {:?}",
+                        pubkey, reason
+                    );
+                }
+            }
+        }
+        Ok(signatures)
+    }
+
+    /// Clones all accounts of the program on the remote cluster that match
+    /// the provided filters.
+    /// This allows hydrating the accounts of a program upfront instead of
+    /// lazily cloning each of them when it is first used by a transaction.
+    /// Failing to clone some of the accounts does not fail the whole
+    /// operation, instead they are counted in the returned summary.
+    pub async fn clone_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> AccountsResult<ClonedProgramAccounts> {
+        let pubkeys = self
+            .program_accounts_provider
+            .get_program_account_keys(program_id, filters)
+            .await?;
+        debug!(
+            "Cloning {} accounts of program '{}'",
+            pubkeys.len(),
+            program_id
+        );
+
+        let mut summary = ClonedProgramAccounts {
+            total: pubkeys.len(),
+            ..Default::default()
+        };
+        let mut clone_results =
+            stream::iter(pubkeys.into_iter().filter(should_clone_account))
+                .map(|pubkey| async move {
+                    (pubkey, self.account_cloner.clone_account(&pubkey).await)
+                })
+                .buffer_unordered(CLONE_PROGRAM_ACCOUNTS_CONCURRENCY);
+        while let Some((pubkey, result)) = clone_results.next().await {
+            match result {
+                Ok(clone_output) => {
+                    self.start_commit_frequency_counters_if_needed(
+                        &clone_output,
+                    );
+                    match clone_output {
+                        AccountClonerOutput::Cloned { .. } => {
+                            summary.cloned += 1
+                        }
+                        AccountClonerOutput::Unclonable { reason, .. } => {
+                            trace!(
+                                "Program account '{}' is unclonable: {:?}",
+                                pubkey,
+                                reason
+                            );
+                            summary.unclonable += 1;
+                        }
+                    }
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to clone program account '{}': {:?}",
+                        pubkey, err
+                    );
+                    summary.failed += 1;
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    // Used directly to clone accounts which are not referenced by a
+    // transaction, i.e. pinned programs, and in tests
     pub async fn ensure_accounts_from_holder(
         &self,
         accounts_holder: TransactionAccountsHolder,
@@ -193,6 +356,12 @@ where
             let writable_snapshots = writable_clone_outputs.into_iter()
                 .map(|clone_output| match clone_output {
                     AccountClonerOutput::Cloned{account_chain_snapshot, ..} => Ok(account_chain_snapshot),
+                    AccountClonerOutput::Unclonable{ pubkey, reason: AccountClonerUnclonableReason::InvalidDelegation(reason), ..} => {
+                        Err(AccountsError::InvalidDelegation(pubkey, reason))
+                    }
+                    AccountClonerOutput::Unclonable{ pubkey, reason: AccountClonerUnclonableReason::DelegationRevoked(revocation), ..} => {
+                        Err(AccountsError::DelegationRevoked(pubkey, revocation))
+                    }
                     AccountClonerOutput::Unclonable{ pubkey, reason, ..} => {
                         Err(AccountsError::UnclonableAccountUsedAsWritableInEphemeral(pubkey, reason))
                     }
@@ -217,6 +386,20 @@ where
         &self,
         clone_output: &AccountClonerOutput,
     ) {
+        // Accounts whose delegation was revoked on chain cannot be committed anymore
+        if let AccountClonerOutput::Unclonable {
+            pubkey,
+            reason: AccountClonerUnclonableReason::DelegationRevoked(_),
+            ..
+        } = clone_output
+        {
+            self.external_commitable_accounts
+                .write()
+                .expect(
+                    "RwLock of ExternalAccountsManager.external_commitable_accounts is poisoned",
+                )
+                .remove(pubkey);
+        }
         if let AccountClonerOutput::Cloned {
             account_chain_snapshot,
             ..
@@ -326,6 +509,10 @@ where
                         account_data: acc,
                         slot,
                         undelegation_requested: undelegation_request,
+                        base_layer_action: None,
+                        compute_unit_price: None,
+                        memo: None,
+                        priority: CommitPriority::Normal,
                     });
                 }
             } else {
@@ -336,16 +523,11 @@ where
             }
         }
 
-        // NOTE: Once we run into issues that the data to be committed in a single
-        // transaction is too large, we can split these into multiple batches
-        // That is why we return a Vec of CreateCommitAccountsTransactionResult
-        let txs = try_join_all(committees.into_iter().map(|commitee| {
-            self.account_committer
-                .create_commit_accounts_transaction(vec![commitee])
-        }))
-        .await?;
-
-        Ok(txs)
+        // The committer packs the committees into as few transactions as the
+        // size and compute limits of a transaction allow
+        self.account_committer
+            .create_commit_accounts_transactions(committees)
+            .await
     }
 
     pub async fn run_transactions_to_commit_specific_accounts(
@@ -366,6 +548,7 @@ where
         let pending_commits = self
             .account_committer
             .send_commit_transactions(payloads)
+            .instrument(tracing::info_span!("commit_accounts"))
             .await?;
 
         // Mark committed accounts
@@ -412,6 +595,14 @@ where
         self.scheduled_commits_processor.scheduled_commits_len()
     }
 
+    pub fn unresolved_commits_len(&self) -> usize {
+        self.scheduled_commits_processor.unresolved_commits_len()
+    }
+
+    pub fn failed_commits_len(&self) -> usize {
+        self.scheduled_commits_processor.failed_commits_len()
+    }
+
     pub fn clear_scheduled_commits(&self) {
         self.scheduled_commits_processor.clear_scheduled_commits()
     }
@@ -419,12 +610,35 @@ where
 
 fn should_clone_account(pubkey: &Pubkey) -> bool {
     pubkey != &magic_program::MAGIC_CONTEXT_PUBKEY
+        && pubkey != &magic_program::MAGIC_COMMIT_EVENTS_PUBKEY
+        && pubkey != &magic_program::MAGIC_SESSION_KEYS_PUBKEY
+}
+
+/// Returns the lookup tables the transaction creates, extends, deactivates,
+/// freezes or closes via the address lookup table program
+fn lookup_tables_modified_by_transaction(
+    tx: &SanitizedTransaction,
+) -> HashSet<Pubkey> {
+    const LOOKUP_TABLE_IDX: usize = 0;
+    let account_keys = tx.message().account_keys();
+    tx.message()
+        .program_instructions_iter()
+        .filter(|(program_id, _)| {
+            *program_id == &address_lookup_table::program::id()
+        })
+        .filter_map(|(_, ix)| {
+            ix.accounts
+                .get(LOOKUP_TABLE_IDX)
+                .and_then(|idx| account_keys.get(*idx as usize))
+                .copied()
+        })
+        .collect()
 }
 
 /// Creates deterministic hashes from account lamports, owner and data
 /// NOTE: We don't expect an account that we commit to ever change executable status, hence the
 /// executable flag is not included in the hash
-fn hash_account(account: &AccountSharedData) -> Hash {
+pub(crate) fn hash_account(account: &AccountSharedData) -> Hash {
     let lamports_bytes = account.lamports().to_le_bytes();
     let owner_bytes = account.owner().to_bytes();
     let data_bytes = account.data();
@@ -0,0 +1,137 @@
+use std::{
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde_json::json;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::request::RpcRequest;
+use solana_sdk::{
+    pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+    transaction::Transaction,
+};
+use tokio::sync::OnceCell;
+
+use crate::{
+    errors::{AccountsError, AccountsResult},
+    JitoConfig,
+};
+
+/// Max number of transactions the block engine accepts in a single bundle
+pub(crate) const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+// -----------------
+// JitoBundleSender
+// -----------------
+/// Submits transactions as bundles to a Jito block engine.
+///
+/// All transactions of a bundle land in the same slot in the order they were
+/// provided or none of them lands. Each bundle needs to tip one of the tip
+/// accounts of the block engine, which we do with a separate transaction at
+/// the end of the bundle.
+pub(crate) struct JitoBundleSender {
+    block_engine: RpcClient,
+    tip_lamports: u64,
+    /// Fetched from the block engine once they are needed
+    tip_accounts: OnceCell<Vec<Pubkey>>,
+    /// Spreads the tips across all tip accounts to reduce write lock
+    /// contention on them
+    next_tip_account: AtomicUsize,
+}
+
+impl JitoBundleSender {
+    pub(crate) fn new(config: JitoConfig) -> Self {
+        Self {
+            block_engine: RpcClient::new(config.url),
+            tip_lamports: config.tip_lamports,
+            tip_accounts: OnceCell::new(),
+            next_tip_account: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn url(&self) -> String {
+        self.block_engine.url()
+    }
+
+    /// Creates the transaction paying the tip for a bundle, it needs to use
+    /// the same blockhash as the other transactions of the bundle
+    pub(crate) async fn tip_transaction(
+        &self,
+        payer: &Keypair,
+        transaction: &Transaction,
+    ) -> AccountsResult<Transaction> {
+        let tip_account = self.tip_account().await?;
+        let tip_ix = system_instruction::transfer(
+            &payer.pubkey(),
+            &tip_account,
+            self.tip_lamports,
+        );
+        Ok(Transaction::new_signed_with_payer(
+            &[tip_ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            transaction.message.recent_blockhash,
+        ))
+    }
+
+    /// Sends the transactions as a single bundle and returns its id
+    pub(crate) async fn send_bundle(
+        &self,
+        transactions: &[Transaction],
+    ) -> Result<String, String> {
+        let encoded = transactions
+            .iter()
+            .map(|tx| {
+                bincode::serialize(tx)
+                    .map(|bytes| BASE64_STANDARD.encode(bytes))
+                    .map_err(|err| err.to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.block_engine
+            .send::<String>(
+                RpcRequest::Custom {
+                    method: "sendBundle",
+                },
+                json!([encoded, { "encoding": "base64" }]),
+            )
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    async fn tip_account(&self) -> AccountsResult<Pubkey> {
+        let tip_accounts = self
+            .tip_accounts
+            .get_or_try_init(|| self.fetch_tip_accounts())
+            .await?;
+        let idx = self.next_tip_account.fetch_add(1, Ordering::Relaxed);
+        Ok(tip_accounts[idx % tip_accounts.len()])
+    }
+
+    async fn fetch_tip_accounts(&self) -> AccountsResult<Vec<Pubkey>> {
+        let tip_accounts = self
+            .block_engine
+            .send::<Vec<String>>(
+                RpcRequest::Custom {
+                    method: "getTipAccounts",
+                },
+                json!([]),
+            )
+            .await
+            .map_err(|err| {
+                AccountsError::FailedToGetJitoTipAccounts(err.to_string())
+            })?
+            .iter()
+            .map(|pubkey| Pubkey::from_str(pubkey))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| {
+                AccountsError::FailedToGetJitoTipAccounts(err.to_string())
+            })?;
+        if tip_accounts.is_empty() {
+            return Err(AccountsError::FailedToGetJitoTipAccounts(
+                "Block engine provided no tip accounts".to_string(),
+            ));
+        }
+        Ok(tip_accounts)
+    }
+}
@@ -1,8 +1,14 @@
 mod accounts_manager;
+mod commit_event_recorder;
+mod commit_webhook;
 mod config;
+mod durable_nonce;
 pub mod errors;
 mod external_accounts_manager;
+mod jito_bundle_sender;
+mod payer_monitor;
 mod remote_account_committer;
+mod remote_program_accounts_provider;
 mod remote_scheduled_commits_processor;
 mod traits;
 pub mod utils;
@@ -11,5 +17,7 @@ pub use accounts_manager::AccountsManager;
 pub use config::*;
 pub use external_accounts_manager::ExternalAccountsManager;
 pub use magicblock_mutator::Cluster;
+pub use payer_monitor::PayerMonitor;
+pub use remote_program_accounts_provider::RemoteProgramAccountsProvider;
 pub use traits::*;
 pub use utils::*;
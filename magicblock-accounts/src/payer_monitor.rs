@@ -0,0 +1,131 @@
+use log::*;
+use magicblock_account_fetcher::endpoint_label;
+use magicblock_metrics::{health, metrics};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_instruction,
+    transaction::Transaction,
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    errors::{AccountsError, AccountsResult},
+    PayerMonitorConfig,
+};
+
+// -----------------
+// PayerMonitor
+// -----------------
+/// Periodically checks the balance of the payer of commits on the remote
+/// cluster, so that commits don't silently start failing once it runs dry.
+///
+/// Whenever the balance is below the configured minimum an alert is raised
+/// and the payer is topped up from the treasury if one is configured.
+pub struct PayerMonitor {
+    rpc_client: RpcClient,
+    payer: Pubkey,
+    config: PayerMonitorConfig,
+}
+
+impl PayerMonitor {
+    pub fn new(
+        rpc_client: RpcClient,
+        payer: Pubkey,
+        config: PayerMonitorConfig,
+    ) -> Self {
+        Self {
+            rpc_client,
+            payer,
+            config,
+        }
+    }
+
+    pub async fn start_monitoring(self, cancellation_token: CancellationToken) {
+        loop {
+            if let Err(err) = self.check_balance().await {
+                warn!(
+                    "Failed to check balance of commit payer '{}': {:?}",
+                    self.payer, err
+                );
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(self.config.check_interval) => {}
+                _ = cancellation_token.cancelled() => {
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn check_balance(&self) -> AccountsResult<()> {
+        let balance =
+            self.rpc_client
+                .get_balance(&self.payer)
+                .await
+                .map_err(|err| {
+                    AccountsError::FailedToGetPayerBalance(err.to_string())
+                })?;
+        let is_low = balance < self.config.min_lamports;
+        let endpoint = endpoint_label(&self.rpc_client.url());
+        metrics::set_committer_payer_balance(&endpoint, balance);
+        metrics::set_committer_payer_low_balance(&endpoint, is_low);
+        if !is_low {
+            health::set_readiness("committer-payer", Ok(()));
+            return Ok(());
+        }
+        health::set_readiness(
+            "committer-payer",
+            Err(format!(
+                "balance of {} lamports is below the minimum of {} lamports",
+                balance, self.config.min_lamports
+            )),
+        );
+
+        error!(
+            "Balance of commit payer '{}' is {} lamports which is below the minimum of {} lamports, commits will fail once it runs dry",
+            self.payer, balance, self.config.min_lamports
+        );
+        if let Some(treasury) = &self.config.treasury {
+            let signature = self.top_up(treasury).await?;
+            metrics::inc_committer_payer_top_ups();
+            info!(
+                "Topped up commit payer '{}' with {} lamports from treasury '{}': {}",
+                self.payer,
+                self.config.top_up_lamports,
+                treasury.pubkey(),
+                signature
+            );
+        }
+        Ok(())
+    }
+
+    async fn top_up(&self, treasury: &Keypair) -> AccountsResult<Signature> {
+        let blockhash =
+            self.rpc_client
+                .get_latest_blockhash()
+                .await
+                .map_err(|err| {
+                    AccountsError::FailedToGetLatestBlockhash(err.to_string())
+                })?;
+        let transfer_ix = system_instruction::transfer(
+            &treasury.pubkey(),
+            &self.payer,
+            self.config.top_up_lamports,
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[transfer_ix],
+            Some(&treasury.pubkey()),
+            &[treasury],
+            blockhash,
+        );
+        // Confirming the transfer ensures the next check sees the new
+        // balance and doesn't top up the payer a second time
+        self.rpc_client
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(|err| AccountsError::FailedToTopUpPayer(err.to_string()))
+    }
+}
@@ -1,4 +1,8 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
 
 use async_trait::async_trait;
 use dlp::{
@@ -7,26 +11,47 @@ use dlp::{
     pda::delegation_metadata_pda_from_delegated_account,
     state::DelegationMetadata,
 };
-use futures_util::future::join_all;
+use futures_util::{future::join_all, StreamExt};
 use log::*;
+use magicblock_account_fetcher::endpoint_label;
 use magicblock_metrics::metrics;
-use magicblock_program::{validator, Pubkey};
+use magicblock_program::{
+    magicblock_instruction::{commit_memo_instruction, CommitPriority},
+    validator, Pubkey,
+};
+use solana_pubsub_client::nonblocking::pubsub_client::PubsubClient;
 use solana_rpc_client::{
     nonblocking::rpc_client::RpcClient, rpc_client::SerializableTransaction,
 };
-use solana_rpc_client_api::config::RpcSendTransactionConfig;
+use solana_rpc_client_api::{
+    config::{
+        RpcSendTransactionConfig, RpcSignatureSubscribeConfig,
+        RpcSimulateTransactionConfig,
+    },
+    response::{
+        ProcessedSignatureResult, Response as RpcResponse, RpcSignatureResult,
+    },
+};
 use solana_sdk::{
-    account::ReadableAccount, clock::MAX_HASH_AGE_IN_SECONDS,
+    account::ReadableAccount,
+    clock::MAX_HASH_AGE_IN_SECONDS,
     commitment_config::CommitmentConfig,
-    compute_budget::ComputeBudgetInstruction, instruction::Instruction,
-    signature::Keypair, signer::Signer, transaction::Transaction,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    packet::PACKET_DATA_SIZE,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::Transaction,
 };
 
 use crate::{
+    durable_nonce::DurableNonce,
     errors::{AccountsError, AccountsResult},
+    jito_bundle_sender::{JitoBundleSender, MAX_BUNDLE_TRANSACTIONS},
     AccountCommittee, AccountCommitter, CommitAccountsPayload,
-    CommitAccountsTransaction, PendingCommitTransaction,
-    SendableCommitAccountsPayload,
+    CommitAccountsTransaction, DurableNonceConfig, JitoConfig,
+    PendingCommitTransaction, PriorityFeeConfig, SendableCommitAccountsPayload,
 };
 
 // [solana_sdk::clock::MAX_HASH_AGE_IN_SECONDS] (120secs) is the max time window at which
@@ -35,143 +60,307 @@ use crate::{
 const MAX_TRANSACTION_CONFIRMATION_SECS: u64 =
     MAX_HASH_AGE_IN_SECONDS as u64 / 4;
 
+// Finalization takes about 32 slots longer than confirmation
+const MAX_TRANSACTION_FINALIZATION_SECS: u64 = MAX_HASH_AGE_IN_SECONDS as u64;
+
+/// Max compute units a single transaction may request on the remote cluster
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Distinguishes the endpoints commits are sent through in metrics from the
+/// ones accounts are cloned from
+const ENDPOINT_ROLE: &str = "commit";
+
+/// Reasons commit transactions are counted as failed for in metrics
+const COMMIT_FAILURE_SEND: &str = "send";
+const COMMIT_FAILURE_NOT_CONFIRMED: &str = "not_confirmed";
+const COMMIT_FAILURE_CONFIRMATION_ERROR: &str = "confirmation_error";
+
+/// Max number of accounts `getRecentPrioritizationFees` accepts
+const MAX_PRIORITIZATION_FEE_ADDRESSES: usize = 128;
+
+/// Number of trailing logs of a failed simulation that are reported, they
+/// usually explain the failure
+const MAX_SIMULATION_LOGS: usize = 5;
+
 // -----------------
 // RemoteAccountCommitter
 // -----------------
 pub struct RemoteAccountCommitter {
-    rpc_client: RpcClient,
+    /// Endpoints of the cluster commits are sent to, the first one being
+    /// preferred while the others are only used when sending through it fails
+    rpc_clients: Vec<RpcClient>,
+    /// Index of the endpoint commits were last sent through successfully
+    active_rpc_client: AtomicUsize,
     committer_authority: Keypair,
+    /// Static compute unit price, used when no [PriorityFeeOracle] is
+    /// configured or it fails to obtain recent fees
     compute_unit_price: u64,
+    priority_fee_oracle: Option<PriorityFeeOracle>,
+    /// Cap of the compute unit price requested by scheduled commits, `0`
+    /// ignores requested prices
+    max_requested_compute_unit_price: u64,
+    /// When set commit transactions use this nonce instead of a recent
+    /// blockhash and are sent until they land
+    durable_nonce: Option<DurableNonce>,
+    /// When set commit transactions are submitted as Jito bundles
+    jito_bundle_sender: Option<JitoBundleSender>,
+    /// Websocket of the remote used to track commit transactions
+    ws_url: Option<String>,
 }
 
 impl RemoteAccountCommitter {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        rpc_client: RpcClient,
+        rpc_clients: Vec<RpcClient>,
+        ws_url: Option<String>,
         committer_authority: Keypair,
         compute_unit_price: u64,
+        priority_fee: Option<PriorityFeeConfig>,
+        max_requested_compute_unit_price: u64,
+        durable_nonce: Option<DurableNonceConfig>,
+        jito: Option<JitoConfig>,
     ) -> Self {
+        assert!(
+            !rpc_clients.is_empty(),
+            "RemoteAccountCommitter needs at least one endpoint"
+        );
+        for rpc_client in &rpc_clients {
+            metrics::set_remote_endpoint_healthy(
+                ENDPOINT_ROLE,
+                &endpoint_label(&rpc_client.url()),
+                true,
+            );
+        }
         Self {
-            rpc_client,
+            rpc_clients,
+            active_rpc_client: AtomicUsize::new(0),
             committer_authority,
             compute_unit_price,
+            priority_fee_oracle: priority_fee.map(PriorityFeeOracle::new),
+            max_requested_compute_unit_price,
+            durable_nonce: durable_nonce.map(DurableNonce::new),
+            jito_bundle_sender: jito.map(JitoBundleSender::new),
+            ws_url,
+        }
+    }
+}
+
+impl RemoteAccountCommitter {
+    fn rpc_client(&self) -> &RpcClient {
+        &self.rpc_clients[self.active_rpc_client.load(Ordering::Relaxed)]
+    }
+
+    /// Sends the transaction through the active endpoint, failing over to
+    /// the next ones in order if that fails.
+    /// The first endpoint that accepts it becomes the active one.
+    async fn send_transaction_with_failover(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, String> {
+        let active = self.active_rpc_client.load(Ordering::Relaxed);
+        let mut last_error = String::new();
+        for offset in 0..self.rpc_clients.len() {
+            let idx = (active + offset) % self.rpc_clients.len();
+            let rpc_client = &self.rpc_clients[idx];
+            let label = endpoint_label(&rpc_client.url());
+            if offset > 0 {
+                warn!(
+                    "Failing over commit transaction '{:?}' to '{}': {}",
+                    transaction.get_signature(),
+                    label,
+                    last_error
+                );
+                metrics::inc_remote_endpoint_failovers(ENDPOINT_ROLE);
+            }
+            match rpc_client
+                .send_transaction_with_config(
+                    transaction,
+                    RpcSendTransactionConfig {
+                        skip_preflight: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+            {
+                Ok(signature) => {
+                    metrics::set_remote_endpoint_healthy(
+                        ENDPOINT_ROLE,
+                        &label,
+                        true,
+                    );
+                    self.active_rpc_client.store(idx, Ordering::Relaxed);
+                    return Ok(signature);
+                }
+                Err(err) => {
+                    metrics::set_remote_endpoint_healthy(
+                        ENDPOINT_ROLE,
+                        &label,
+                        false,
+                    );
+                    last_error = err.to_string();
+                }
+            }
         }
+        Err(last_error)
+    }
+}
+
+// -----------------
+// PriorityFeeOracle
+// -----------------
+/// Chooses the compute unit price of a commit from the fees that were recently
+/// paid on the remote cluster to write lock the committed accounts
+struct PriorityFeeOracle {
+    config: PriorityFeeConfig,
+}
+
+impl PriorityFeeOracle {
+    fn new(config: PriorityFeeConfig) -> Self {
+        Self { config }
+    }
+
+    async fn compute_unit_price(
+        &self,
+        rpc_client: &RpcClient,
+        pubkeys: &[Pubkey],
+        fallback: u64,
+    ) -> u64 {
+        let pubkeys =
+            &pubkeys[..pubkeys.len().min(MAX_PRIORITIZATION_FEE_ADDRESSES)];
+        match rpc_client.get_recent_prioritization_fees(pubkeys).await {
+            Ok(fees) => self.select_compute_unit_price(
+                fees.into_iter().map(|fee| fee.prioritization_fee).collect(),
+            ),
+            Err(err) => {
+                warn!(
+                    "Failed to get recent prioritization fees, using compute unit price {}: {:?}",
+                    fallback, err
+                );
+                fallback
+            }
+        }
+    }
+
+    /// Picks the configured percentile of the fees and clamps it to the
+    /// configured bounds where the max takes precedence
+    fn select_compute_unit_price(&self, mut fees: Vec<u64>) -> u64 {
+        fees.sort_unstable();
+        let fee = if fees.is_empty() {
+            0
+        } else {
+            let percentile = self.config.percentile.min(100) as usize;
+            fees[(fees.len() - 1) * percentile / 100]
+        };
+        fee.max(self.config.min_compute_unit_price)
+            .min(self.config.max_compute_unit_price)
     }
 }
 
 #[async_trait]
 impl AccountCommitter for RemoteAccountCommitter {
-    async fn create_commit_accounts_transaction(
+    async fn create_commit_accounts_transactions(
         &self,
         committees: Vec<AccountCommittee>,
-    ) -> AccountsResult<CommitAccountsPayload> {
+    ) -> AccountsResult<Vec<CommitAccountsPayload>> {
+        if committees.is_empty() {
+            return Ok(vec![]);
+        }
         // Get blockhash once since this is a slow operation
-        let latest_blockhash = self
-            .rpc_client
-            .get_latest_blockhash()
-            .await
-            .map_err(|err| {
-                AccountsError::FailedToGetLatestBlockhash(err.to_string())
-            })?;
-
-        let committee_count: u32 = committees
-            .len()
-            .try_into()
-            .map_err(|_| AccountsError::TooManyCommittees(committees.len()))?;
-        let undelegation_count: u32 = committees
-            .iter()
-            .filter(|c| c.undelegation_requested)
-            .count()
-            .try_into()
-            .map_err(|_| AccountsError::TooManyCommittees(committees.len()))?;
-        let (compute_budget_ix, compute_unit_price_ix) =
-            self.compute_instructions(committee_count, undelegation_count);
-
-        let mut undelegated_accounts = HashSet::new();
-        let mut committed_only_accounts = HashSet::new();
-        let mut ixs = vec![compute_budget_ix, compute_unit_price_ix];
+        let latest_blockhash = match &self.durable_nonce {
+            Some(durable_nonce) => {
+                durable_nonce.fetch_nonce(self.rpc_client()).await?
+            }
+            None => self.rpc_client().get_latest_blockhash().await.map_err(
+                |err| {
+                    AccountsError::FailedToGetLatestBlockhash(err.to_string())
+                },
+            )?,
+        };
 
-        for AccountCommittee {
-            pubkey,
-            owner,
-            account_data,
-            slot,
-            undelegation_requested: undelegation_request,
-        } in committees.iter()
-        {
-            let committer = self.committer_authority.pubkey();
-            let commit_args = CommitStateArgs {
-                slot: *slot,
-                allow_undelegation: *undelegation_request,
-                data: account_data.data().to_vec(),
-                lamports: account_data.lamports(),
-            };
-            let commit_ix =
-                commit_state(committer, *pubkey, *owner, commit_args);
-
-            let finalize_ix = finalize(committer, *pubkey);
-            ixs.extend(vec![commit_ix, finalize_ix]);
-            if *undelegation_request {
-                let metadata_account = self
-                    .rpc_client
-                    .get_account(
-                        &delegation_metadata_pda_from_delegated_account(pubkey),
-                    )
-                    .await
-                    .map_err(|err| {
-                        AccountsError::FailedToGetReimbursementAddress(
-                            err.to_string(),
-                        )
-                    })?;
-                let metadata =
-                    DelegationMetadata::try_from_bytes_with_discriminator(
-                        &metadata_account.data,
-                    )
-                    .map_err(|err| {
-                        AccountsError::FailedToGetReimbursementAddress(
-                            err.to_string(),
-                        )
-                    })?;
-                let undelegate_ix = undelegate(
-                    validator::validator_authority_id(),
-                    *pubkey,
-                    *owner,
-                    metadata.rent_payer,
-                );
-                ixs.push(undelegate_ix);
-                undelegated_accounts.insert(*pubkey);
-            } else {
-                committed_only_accounts.insert(*pubkey);
+        // Pack as many committees into each transaction as its size and
+        // compute limits allow, preserving the order of the committees
+        let mut batches = vec![];
+        let mut batch = CommitBatch::default();
+        for committee in committees {
+            let committee_ixs = self.committee_instructions(&committee).await?;
+            if !batch.is_empty()
+                && !self.fits_into_transaction(
+                    &batch,
+                    &committee,
+                    &committee_ixs,
+                )?
+            {
+                batches.push(std::mem::take(&mut batch));
             }
+            batch.push(committee, committee_ixs);
         }
+        batches.push(batch);
 
-        // For now we always commit all accounts in one transaction, but
-        // in the future we may split them up into batches to avoid running
-        // over the max instruction args size
-        let tx = Transaction::new_signed_with_payer(
-            &ixs,
-            Some(&self.committer_authority.pubkey()),
-            &[&self.committer_authority],
-            latest_blockhash,
-        );
-        let committees = committees
-            .into_iter()
-            .map(|c| (c.pubkey, c.account_data))
-            .collect();
+        let mut payloads = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let compute_unit_price =
+                self.batch_compute_unit_price(&batch).await;
+            payloads.push(self.payload_from_batch(
+                batch,
+                latest_blockhash,
+                compute_unit_price,
+            )?);
+        }
+        if payloads.len() > 1 {
+            debug!("Packed commit into {} transactions", payloads.len());
+        }
+        Ok(payloads)
+    }
 
-        Ok(CommitAccountsPayload {
-            transaction: Some(CommitAccountsTransaction {
-                transaction: tx,
-                undelegated_accounts,
-                committed_only_accounts,
-            }),
-            committees,
-        })
+    async fn simulate_commit_transactions(
+        &self,
+        payloads: &[SendableCommitAccountsPayload],
+    ) -> Vec<Option<String>> {
+        join_all(payloads.iter().map(|payload| async move {
+            let signature = payload.get_signature();
+            let result = match self
+                .rpc_client()
+                .simulate_transaction_with_config(
+                    &payload.transaction.transaction,
+                    RpcSimulateTransactionConfig {
+                        sig_verify: false,
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        ..Default::default()
+                    },
+                )
+                .await
+            {
+                Ok(response) => response.value,
+                Err(err) => {
+                    warn!(
+                        "Failed to simulate commit transaction '{:?}': {:?}",
+                        signature, err
+                    );
+                    return None;
+                }
+            };
+            let err = result.err?;
+            let logs = result.logs.unwrap_or_default();
+            let logs = &logs[logs.len().saturating_sub(MAX_SIMULATION_LOGS)..];
+            warn!(
+                "Simulation of commit transaction '{:?}' failed: {:?}",
+                signature, err
+            );
+            Some(format!("{} ({})", err, logs.join(" | ")))
+        }))
+        .await
     }
 
     async fn send_commit_transactions(
         &self,
         payloads: Vec<SendableCommitAccountsPayload>,
     ) -> AccountsResult<Vec<PendingCommitTransaction>> {
+        if let Some(jito_bundle_sender) = &self.jito_bundle_sender {
+            return self
+                .send_commit_bundles(jito_bundle_sender, payloads)
+                .await;
+        }
+
         let mut pending_commits = Vec::new();
         for SendableCommitAccountsPayload {
             transaction:
@@ -199,7 +388,7 @@ impl AccountCommitter for RemoteAccountCommitter {
                     "Committing accounts [{}] sig: {:?} to {}",
                     pubkeys_display,
                     tx_sig,
-                    self.rpc_client.url()
+                    self.rpc_client().url()
                 );
                 Some(pubkeys_display)
             } else {
@@ -220,30 +409,59 @@ impl AccountCommitter for RemoteAccountCommitter {
             }
 
             let timer = metrics::account_commit_start();
-            let signature = self
-                .rpc_client
-                .send_transaction_with_config(
-                    &transaction,
-                    RpcSendTransactionConfig {
-                        skip_preflight: true,
-                        ..Default::default()
-                    },
-                )
-                .await
-                .map_err(|err| {
-                    AccountsError::FailedToSendCommitTransaction(
-                        err.to_string(),
-                        undelegated_accounts.clone(),
-                        committed_only_accounts.clone(),
-                    )
-                })?;
-
-            if &signature != tx_sig {
-                error!(
-                    "Transaction Signature mismatch: {:?} != {:?}",
-                    signature, tx_sig
-                );
-            }
+            let initial_signature = *tx_sig;
+            let signature = match &self.durable_nonce {
+                Some(durable_nonce) => {
+                    let signature = durable_nonce
+                        .deliver(
+                            self.rpc_client(),
+                            &self.committer_authority,
+                            transaction,
+                        )
+                        .await
+                        .map_err(|err| {
+                            metrics::inc_account_commit_failures(
+                                &endpoint_label(&self.rpc_client().url()),
+                                COMMIT_FAILURE_SEND,
+                            );
+                            AccountsError::FailedToSendCommitTransaction(
+                                err,
+                                undelegated_accounts.clone(),
+                                committed_only_accounts.clone(),
+                            )
+                        })?;
+                    if signature != initial_signature {
+                        debug!(
+                            "Commit transaction '{:?}' landed as '{:?}' after signing it with an advanced nonce",
+                            initial_signature, signature
+                        );
+                    }
+                    signature
+                }
+                None => {
+                    let signature = self
+                        .send_transaction_with_failover(&transaction)
+                        .await
+                        .map_err(|err| {
+                            metrics::inc_account_commit_failures(
+                                &endpoint_label(&self.rpc_client().url()),
+                                COMMIT_FAILURE_SEND,
+                            );
+                            AccountsError::FailedToSendCommitTransaction(
+                                err.to_string(),
+                                undelegated_accounts.clone(),
+                                committed_only_accounts.clone(),
+                            )
+                        })?;
+                    if signature != initial_signature {
+                        error!(
+                            "Transaction Signature mismatch: {:?} != {:?}",
+                            signature, initial_signature
+                        );
+                    }
+                    signature
+                }
+            };
             debug!(
                 "Sent commit for [{}] | signature: '{:?}'",
                 pubkeys_display.unwrap_or_default(),
@@ -251,6 +469,7 @@ impl AccountCommitter for RemoteAccountCommitter {
             );
             pending_commits.push(PendingCommitTransaction {
                 signature,
+                initial_signature,
                 undelegated_accounts,
                 committed_only_accounts,
                 timer,
@@ -262,73 +481,67 @@ impl AccountCommitter for RemoteAccountCommitter {
     async fn confirm_pending_commits(
         &self,
         pending_commits: Vec<PendingCommitTransaction>,
-    ) {
+    ) -> HashSet<Signature> {
+        let pubsub_client = self.connect_pubsub_client().await;
+        let pubsub_client = pubsub_client.as_ref();
+        let endpoint = &endpoint_label(&self.rpc_client().url());
         let mut futures = Vec::new();
         for pc in pending_commits.into_iter() {
             let fut = async move {
                 let now = std::time::Instant::now();
-                loop {
-                    match self
-                        .rpc_client
-                        .confirm_transaction_with_commitment(
-                            &pc.signature,
-                            CommitmentConfig::confirmed(),
-                        )
-                        .await
-                    {
-                        Ok(res) => {
-                            // The RPC `confirm_transaction_with_commitment` doesn't provide
-                            // the info to distinguish between a not yet confirmed or
-                            // failed transaction.
-                            // Failed transactions should be rare, so it's ok to check
-                            // them over and over until the timeout is reached.
-                            // If we see that happen a lot we can write our custom confirm method
-                            // that makes this more straightforward.
-                            let confirmed_and_succeeded = res.value;
-                            if confirmed_and_succeeded {
-                                update_account_commit_metrics(
-                                    &pc.undelegated_accounts,
-                                    &pc.committed_only_accounts,
-                                    metrics::Outcome::from_success(res.value),
-                                    Some(pc.timer),
-                                );
-                                break;
-                            } else if now.elapsed().as_secs()
-                                > MAX_TRANSACTION_CONFIRMATION_SECS
-                            {
-                                error!(
-                                    "Timed out confirming commit-transaction success '{:?}': {:?}. This means that the transaction failed or failed to confirm in time.",
-                                    pc.signature, res
-                                );
-                                update_account_commit_metrics(
-                                    &pc.undelegated_accounts,
-                                    &pc.committed_only_accounts,
-                                    metrics::Outcome::Error,
-                                    None,
-                                );
-                                break;
-                            } else {
-                                tokio::time::sleep(
-                                    std::time::Duration::from_millis(50),
-                                )
-                                .await;
-                            }
-                        }
-                        Err(err) => {
-                            error!(
-                                "Failed to confirm commit transaction '{:?}': {:?}",
-                                pc.signature, err
-                            );
-                            update_account_commit_metrics(
-                                &pc.undelegated_accounts,
-                                &pc.committed_only_accounts,
-                                metrics::Outcome::Error,
-                                None,
-                            );
-                            break;
-                        }
+                let failure = match self
+                    .await_signature(
+                        pubsub_client,
+                        &pc.signature,
+                        CommitmentConfig::confirmed(),
+                        Duration::from_secs(MAX_TRANSACTION_CONFIRMATION_SECS),
+                    )
+                    .await
+                {
+                    Ok(true) => {
+                        update_account_commit_metrics(
+                            &pc.undelegated_accounts,
+                            &pc.committed_only_accounts,
+                            metrics::Outcome::Success,
+                            Some(pc.timer),
+                        );
+                        None
+                    }
+                    Ok(false) => {
+                        error!(
+                            "Timed out confirming commit-transaction success '{:?}'. This means that the transaction failed or failed to confirm in time.",
+                            pc.signature
+                        );
+                        update_account_commit_metrics(
+                            &pc.undelegated_accounts,
+                            &pc.committed_only_accounts,
+                            metrics::Outcome::Error,
+                            None,
+                        );
+                        Some(COMMIT_FAILURE_NOT_CONFIRMED)
+                    }
+                    Err(err) => {
+                        error!(
+                            "Failed to confirm commit transaction '{:?}': {:?}",
+                            pc.signature, err
+                        );
+                        update_account_commit_metrics(
+                            &pc.undelegated_accounts,
+                            &pc.committed_only_accounts,
+                            metrics::Outcome::Error,
+                            None,
+                        );
+                        Some(COMMIT_FAILURE_CONFIRMATION_ERROR)
                     }
+                };
+                if let Some(reason) = failure {
+                    metrics::inc_account_commit_failures(endpoint, reason);
                 }
+                metrics::observe_account_commit_confirmation_time(
+                    endpoint,
+                    metrics::Outcome::from_success(failure.is_none()),
+                    now.elapsed(),
+                );
 
                 if log_enabled!(log::Level::Trace) {
                     trace!(
@@ -337,10 +550,151 @@ impl AccountCommitter for RemoteAccountCommitter {
                         now.elapsed()
                     );
                 }
+                failure.is_none().then_some(pc.signature)
             };
             futures.push(fut);
         }
-        join_all(futures).await;
+        join_all(futures).await.into_iter().flatten().collect()
+    }
+
+    async fn finalize_confirmed_commits(
+        &self,
+        signatures: Vec<Signature>,
+    ) -> HashSet<Signature> {
+        let pubsub_client = self.connect_pubsub_client().await;
+        let pubsub_client = pubsub_client.as_ref();
+        let futures = signatures.into_iter().map(|signature| async move {
+            match self
+                .await_signature(
+                    pubsub_client,
+                    &signature,
+                    CommitmentConfig::finalized(),
+                    Duration::from_secs(MAX_TRANSACTION_FINALIZATION_SECS),
+                )
+                .await
+            {
+                Ok(finalized) => finalized.then_some(signature),
+                Err(err) => {
+                    warn!(
+                        "Failed to await finalization of commit transaction '{:?}': {:?}",
+                        signature, err
+                    );
+                    None
+                }
+            }
+        });
+        join_all(futures).await.into_iter().flatten().collect()
+    }
+}
+
+impl RemoteAccountCommitter {
+    /// Connects to the websocket of the remote in order to subscribe to the
+    /// signatures of commit transactions instead of polling their status
+    async fn connect_pubsub_client(&self) -> Option<PubsubClient> {
+        let ws_url = self.ws_url.as_ref()?;
+        match PubsubClient::new(ws_url).await {
+            Ok(client) => Some(client),
+            Err(err) => {
+                warn!(
+                    "Failed to connect to '{}', polling commit transactions instead: {:?}",
+                    ws_url, err
+                );
+                None
+            }
+        }
+    }
+
+    /// Waits until the transaction reaches the commitment level.
+    /// Returns `false` if it failed or didn't reach it in time.
+    async fn await_signature(
+        &self,
+        pubsub_client: Option<&PubsubClient>,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<bool, String> {
+        if let Some(pubsub_client) = pubsub_client {
+            match self
+                .subscribe_signature(
+                    pubsub_client,
+                    signature,
+                    commitment,
+                    timeout,
+                )
+                .await
+            {
+                Ok(succeeded) => return Ok(succeeded),
+                Err(err) => debug!(
+                    "Failed to subscribe to signature '{:?}', polling it instead: {:?}",
+                    signature, err
+                ),
+            }
+        }
+        self.poll_signature(signature, commitment, timeout).await
+    }
+
+    async fn subscribe_signature(
+        &self,
+        pubsub_client: &PubsubClient,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<bool, String> {
+        let (mut notifications, unsubscribe) = pubsub_client
+            .signature_subscribe(
+                signature,
+                Some(RpcSignatureSubscribeConfig {
+                    commitment: Some(commitment),
+                    enable_received_notification: Some(false),
+                }),
+            )
+            .await
+            .map_err(|err| err.to_string())?;
+
+        // The transaction may have reached the commitment before we subscribed
+        let succeeded = match self
+            .rpc_client()
+            .get_signature_status_with_commitment(signature, commitment)
+            .await
+        {
+            Ok(Some(result)) => result.is_ok(),
+            _ => matches!(
+                tokio::time::timeout(timeout, notifications.next()).await,
+                Ok(Some(RpcResponse {
+                    value: RpcSignatureResult::ProcessedSignature(
+                        ProcessedSignatureResult { err: None }
+                    ),
+                    ..
+                }))
+            ),
+        };
+        drop(notifications);
+        unsubscribe().await;
+        Ok(succeeded)
+    }
+
+    async fn poll_signature(
+        &self,
+        signature: &Signature,
+        commitment: CommitmentConfig,
+        timeout: Duration,
+    ) -> Result<bool, String> {
+        let now = std::time::Instant::now();
+        loop {
+            match self
+                .rpc_client()
+                .get_signature_status_with_commitment(signature, commitment)
+                .await
+                .map_err(|err| err.to_string())?
+            {
+                Some(result) => return Ok(result.is_ok()),
+                None if now.elapsed() > timeout => return Ok(false),
+                None => {
+                    tokio::time::sleep(std::time::Duration::from_millis(50))
+                        .await
+                }
+            }
+        }
     }
 }
 
@@ -371,28 +725,471 @@ pub(crate) fn update_account_commit_metrics(
     }
 }
 
+/// Committees that are committed together in a single transaction
+#[derive(Default)]
+struct CommitBatch {
+    committees: Vec<AccountCommittee>,
+    /// Commit, finalize and possibly undelegate instructions of all committees
+    ixs: Vec<Instruction>,
+    undelegation_count: u32,
+    /// Number of base layer actions appended to the commits
+    action_count: u32,
+    /// Memos of the commits the committees belong to, each is included once
+    memos: Vec<String>,
+}
+
+impl CommitBatch {
+    fn is_empty(&self) -> bool {
+        self.committees.is_empty()
+    }
+
+    fn committee_count(&self) -> u32 {
+        self.committees.len() as u32
+    }
+
+    fn memo_count(&self) -> u32 {
+        self.memos.len() as u32
+    }
+
+    /// The memo of the committee unless the batch includes it already
+    fn new_memo<'a>(&self, committee: &'a AccountCommittee) -> Option<&'a str> {
+        committee
+            .memo
+            .as_deref()
+            .filter(|memo| !self.memos.iter().any(|m| m == memo))
+    }
+
+    fn push(
+        &mut self,
+        committee: AccountCommittee,
+        committee_ixs: Vec<Instruction>,
+    ) {
+        if committee.undelegation_requested {
+            self.undelegation_count += 1;
+        }
+        if committee.base_layer_action.is_some() {
+            self.action_count += 1;
+        }
+        if let Some(memo) = self.new_memo(&committee).map(str::to_string) {
+            self.ixs.push(commit_memo_instruction(&memo));
+            self.memos.push(memo);
+        }
+        self.committees.push(committee);
+        self.ixs.extend(committee_ixs);
+    }
+}
+
 impl RemoteAccountCommitter {
-    fn compute_instructions(
+    /// Creates the instructions committing a single account and undelegating
+    /// it if requested
+    async fn committee_instructions(
         &self,
+        committee: &AccountCommittee,
+    ) -> AccountsResult<Vec<Instruction>> {
+        let AccountCommittee {
+            pubkey,
+            owner,
+            account_data,
+            slot,
+            undelegation_requested: undelegation_request,
+            base_layer_action,
+            compute_unit_price: _,
+            memo: _,
+            priority: _,
+        } = committee;
+        let committer = self.committer_authority.pubkey();
+        let commit_args = CommitStateArgs {
+            slot: *slot,
+            allow_undelegation: *undelegation_request,
+            data: account_data.data().to_vec(),
+            lamports: account_data.lamports(),
+        };
+        let commit_ix = commit_state(committer, *pubkey, *owner, commit_args);
+        let finalize_ix = finalize(committer, *pubkey);
+        let mut ixs = vec![commit_ix, finalize_ix];
+        if *undelegation_request {
+            let metadata_account = self
+                .rpc_client()
+                .get_account(&delegation_metadata_pda_from_delegated_account(
+                    pubkey,
+                ))
+                .await
+                .map_err(|err| {
+                    AccountsError::FailedToGetReimbursementAddress(
+                        err.to_string(),
+                    )
+                })?;
+            let metadata =
+                DelegationMetadata::try_from_bytes_with_discriminator(
+                    &metadata_account.data,
+                )
+                .map_err(|err| {
+                    AccountsError::FailedToGetReimbursementAddress(
+                        err.to_string(),
+                    )
+                })?;
+            let undelegate_ix = undelegate(
+                validator::validator_authority_id(),
+                *pubkey,
+                *owner,
+                metadata.rent_payer,
+            );
+            ixs.push(undelegate_ix);
+        }
+        ixs.extend(base_layer_action.iter().cloned());
+        Ok(ixs)
+    }
+
+    /// Determines if the committee can be added to the batch without the
+    /// transaction exceeding the max transaction size or compute units
+    fn fits_into_transaction(
+        &self,
+        batch: &CommitBatch,
+        committee: &AccountCommittee,
+        committee_ixs: &[Instruction],
+    ) -> AccountsResult<bool> {
+        let committee_count = batch.committee_count() + 1;
+        let undelegation_count =
+            batch.undelegation_count + committee.undelegation_requested as u32;
+        let action_count =
+            batch.action_count + committee.base_layer_action.is_some() as u32;
+        let new_memo = batch.new_memo(committee);
+        let memo_count = batch.memo_count() + new_memo.is_some() as u32;
+        if Self::compute_budget(
+            committee_count,
+            undelegation_count,
+            action_count,
+            memo_count,
+        ) > MAX_COMPUTE_UNIT_LIMIT
+        {
+            return Ok(false);
+        }
+
+        // The price does not affect the size of the transaction
+        let ixs = self
+            .leading_instructions(
+                committee_count,
+                undelegation_count,
+                action_count,
+                memo_count,
+                self.compute_unit_price,
+            )
+            .into_iter()
+            .chain(batch.ixs.iter().cloned())
+            .chain(new_memo.map(commit_memo_instruction))
+            .chain(committee_ixs.iter().cloned())
+            .collect::<Vec<_>>();
+        // An unsigned transaction includes placeholders for all signatures
+        // and thus has the same size as once it is signed
+        let tx = Transaction::new_with_payer(
+            &ixs,
+            Some(&self.committer_authority.pubkey()),
+        );
+        let tx_size = bincode::serialized_size(&tx).map_err(|err| {
+            AccountsError::FailedToSerializeCommitTransaction(err.to_string())
+        })?;
+        Ok(tx_size <= PACKET_DATA_SIZE as u64)
+    }
+
+    /// Submits the commit transactions in bundles, each of them is followed
+    /// by the transaction paying the tip for the bundle.
+    /// Transactions that are part of the same bundle land atomically.
+    async fn send_commit_bundles(
+        &self,
+        jito_bundle_sender: &JitoBundleSender,
+        payloads: Vec<SendableCommitAccountsPayload>,
+    ) -> AccountsResult<Vec<PendingCommitTransaction>> {
+        let mut pending_commits = Vec::new();
+        let mut payloads = payloads.into_iter().peekable();
+        while payloads.peek().is_some() {
+            let bundle = payloads
+                .by_ref()
+                .take(MAX_BUNDLE_TRANSACTIONS - 1)
+                .map(|payload| payload.transaction)
+                .collect::<Vec<_>>();
+            let undelegated_accounts = bundle
+                .iter()
+                .flat_map(|tx| tx.undelegated_accounts.iter().copied())
+                .collect::<HashSet<_>>();
+            let committed_only_accounts = bundle
+                .iter()
+                .flat_map(|tx| tx.committed_only_accounts.iter().copied())
+                .collect::<HashSet<_>>();
+            let send_failed = |err: String| {
+                AccountsError::FailedToSendCommitTransaction(
+                    err,
+                    undelegated_accounts.clone(),
+                    committed_only_accounts.clone(),
+                )
+            };
+
+            let tip_tx = jito_bundle_sender
+                .tip_transaction(
+                    &self.committer_authority,
+                    &bundle[0].transaction,
+                )
+                .await
+                .map_err(|err| send_failed(err.to_string()))?;
+            let transactions = bundle
+                .iter()
+                .map(|tx| tx.transaction.clone())
+                .chain([tip_tx])
+                .collect::<Vec<_>>();
+
+            let timer = metrics::account_commit_start();
+            let bundle_id = jito_bundle_sender
+                .send_bundle(&transactions)
+                .await
+                .map_err(send_failed)?;
+            debug!(
+                "Sent bundle '{}' with {} commit transactions to {}",
+                bundle_id,
+                bundle.len(),
+                jito_bundle_sender.url()
+            );
+
+            // All transactions of the bundle land together, thus they share
+            // the timer of the first one
+            let mut timer = Some(timer);
+            for tx in bundle {
+                let signature = tx.get_signature();
+                pending_commits.push(PendingCommitTransaction {
+                    signature,
+                    initial_signature: signature,
+                    undelegated_accounts: tx.undelegated_accounts,
+                    committed_only_accounts: tx.committed_only_accounts,
+                    timer: timer
+                        .take()
+                        .unwrap_or_else(metrics::account_commit_start),
+                });
+            }
+        }
+        Ok(pending_commits)
+    }
+
+    /// The price chosen for the batch, raised to the highest price requested
+    /// by any of its committees within the configured cap.
+    /// Batches of low priority commits only use the static price, while high
+    /// priority ones offer the cap right away.
+    async fn batch_compute_unit_price(&self, batch: &CommitBatch) -> u64 {
+        let priority = batch
+            .committees
+            .iter()
+            .map(|committee| committee.priority)
+            .max()
+            .unwrap_or_default();
+        let compute_unit_price = match priority {
+            CommitPriority::Low => self.compute_unit_price,
+            CommitPriority::Normal => self.base_compute_unit_price(batch).await,
+            CommitPriority::High => self
+                .base_compute_unit_price(batch)
+                .await
+                .max(self.max_requested_compute_unit_price),
+        };
+        batch
+            .committees
+            .iter()
+            .filter_map(|committee| committee.compute_unit_price)
+            .max()
+            .map(|requested| {
+                requested
+                    .min(self.max_requested_compute_unit_price)
+                    .max(compute_unit_price)
+            })
+            .unwrap_or(compute_unit_price)
+    }
+
+    async fn base_compute_unit_price(&self, batch: &CommitBatch) -> u64 {
+        match &self.priority_fee_oracle {
+            Some(oracle) => {
+                let pubkeys = batch
+                    .committees
+                    .iter()
+                    .map(|committee| committee.pubkey)
+                    .collect::<Vec<_>>();
+                oracle
+                    .compute_unit_price(
+                        self.rpc_client(),
+                        &pubkeys,
+                        self.compute_unit_price,
+                    )
+                    .await
+            }
+            None => self.compute_unit_price,
+        }
+    }
+
+    fn payload_from_batch(
+        &self,
+        batch: CommitBatch,
+        latest_blockhash: Hash,
+        compute_unit_price: u64,
+    ) -> AccountsResult<CommitAccountsPayload> {
+        let memo_count = batch.memo_count();
+        let CommitBatch {
+            committees,
+            ixs: committee_ixs,
+            undelegation_count,
+            action_count,
+            memos: _,
+        } = batch;
+        let committee_count: u32 = committees
+            .len()
+            .try_into()
+            .map_err(|_| AccountsError::TooManyCommittees(committees.len()))?;
+        let mut ixs = self.leading_instructions(
+            committee_count,
+            undelegation_count,
+            action_count,
+            memo_count,
+            compute_unit_price,
+        );
+        ixs.extend(committee_ixs);
+
+        let mut undelegated_accounts = HashSet::new();
+        let mut committed_only_accounts = HashSet::new();
+        for committee in committees.iter() {
+            if committee.undelegation_requested {
+                undelegated_accounts.insert(committee.pubkey);
+            } else {
+                committed_only_accounts.insert(committee.pubkey);
+            }
+        }
+
+        let tx = Transaction::new_signed_with_payer(
+            &ixs,
+            Some(&self.committer_authority.pubkey()),
+            &[&self.committer_authority],
+            latest_blockhash,
+        );
+        let committees = committees
+            .into_iter()
+            .map(|c| (c.pubkey, c.account_data))
+            .collect();
+
+        Ok(CommitAccountsPayload {
+            transaction: Some(CommitAccountsTransaction {
+                transaction: tx,
+                undelegated_accounts,
+                committed_only_accounts,
+            }),
+            committees,
+        })
+    }
+
+    fn compute_budget(
         committee_count: u32,
         undelegation_count: u32,
-    ) -> (Instruction, Instruction) {
+        action_count: u32,
+        memo_count: u32,
+    ) -> u32 {
         // TODO(thlorenz): We may need to consider account size as well since
         // the account is copied which could affect CUs
         const BASE_COMPUTE_BUDGET: u32 = 80_000;
         const COMPUTE_BUDGET_PER_COMMITTEE: u32 = 45_000;
         const COMPUTE_BUDGET_PER_UNDELEGATION: u32 = 70_000;
+        const COMPUTE_BUDGET_PER_ACTION: u32 = 200_000;
+        const COMPUTE_BUDGET_PER_MEMO: u32 = 15_000;
 
-        let compute_budget = BASE_COMPUTE_BUDGET
+        BASE_COMPUTE_BUDGET
             + (COMPUTE_BUDGET_PER_COMMITTEE * committee_count)
-            + (COMPUTE_BUDGET_PER_UNDELEGATION * undelegation_count);
+            + (COMPUTE_BUDGET_PER_UNDELEGATION * undelegation_count)
+            + (COMPUTE_BUDGET_PER_ACTION * action_count)
+            + (COMPUTE_BUDGET_PER_MEMO * memo_count)
+    }
+
+    /// Instructions preceding the commit instructions of a transaction,
+    /// advancing the durable nonce needs to be the very first one
+    fn leading_instructions(
+        &self,
+        committee_count: u32,
+        undelegation_count: u32,
+        action_count: u32,
+        memo_count: u32,
+        compute_unit_price: u64,
+    ) -> Vec<Instruction> {
+        let advance_nonce_ix =
+            self.durable_nonce.as_ref().map(|durable_nonce| {
+                durable_nonce
+                    .advance_instruction(&self.committer_authority.pubkey())
+            });
+        let (compute_budget_ix, compute_unit_price_ix) = self
+            .compute_instructions(
+                committee_count,
+                undelegation_count,
+                action_count,
+                memo_count,
+                compute_unit_price,
+            );
+        advance_nonce_ix
+            .into_iter()
+            .chain([compute_budget_ix, compute_unit_price_ix])
+            .collect()
+    }
 
+    fn compute_instructions(
+        &self,
+        committee_count: u32,
+        undelegation_count: u32,
+        action_count: u32,
+        memo_count: u32,
+        compute_unit_price: u64,
+    ) -> (Instruction, Instruction) {
+        let compute_budget = Self::compute_budget(
+            committee_count,
+            undelegation_count,
+            action_count,
+            memo_count,
+        );
         let compute_budget_ix =
             ComputeBudgetInstruction::set_compute_unit_limit(compute_budget);
         let compute_unit_price_ix =
             ComputeBudgetInstruction::set_compute_unit_price(
-                self.compute_unit_price,
+                compute_unit_price,
             );
         (compute_budget_ix, compute_unit_price_ix)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oracle(min: u64, max: u64, percentile: u8) -> PriorityFeeOracle {
+        PriorityFeeOracle::new(PriorityFeeConfig {
+            min_compute_unit_price: min,
+            max_compute_unit_price: max,
+            percentile,
+        })
+    }
+
+    #[test]
+    fn test_select_compute_unit_price_percentile() {
+        let fees = (1..=10).rev().map(|x| x * 100).collect::<Vec<_>>();
+        assert_eq!(
+            oracle(0, u64::MAX, 0).select_compute_unit_price(fees.clone()),
+            100
+        );
+        assert_eq!(
+            oracle(0, u64::MAX, 50).select_compute_unit_price(fees.clone()),
+            500
+        );
+        assert_eq!(
+            oracle(0, u64::MAX, 100).select_compute_unit_price(fees),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_select_compute_unit_price_bounds() {
+        let fees = vec![100, 200, 300];
+        assert_eq!(
+            oracle(250, 1_000, 0).select_compute_unit_price(fees.clone()),
+            250
+        );
+        assert_eq!(oracle(0, 150, 100).select_compute_unit_price(fees), 150);
+        assert_eq!(oracle(50, 1_000, 75).select_compute_unit_price(vec![]), 50);
+        // The max takes precedence over misconfigured bounds
+        assert_eq!(oracle(500, 400, 50).select_compute_unit_price(vec![]), 400);
+    }
+}
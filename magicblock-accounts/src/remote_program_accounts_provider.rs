@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::{
+    config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    filter::RpcFilterType,
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+
+use crate::{
+    errors::{AccountsError, AccountsResult},
+    ProgramAccountsProvider,
+};
+
+pub struct RemoteProgramAccountsProvider {
+    rpc_client: RpcClient,
+}
+
+impl RemoteProgramAccountsProvider {
+    pub fn new(rpc_client: RpcClient) -> Self {
+        Self { rpc_client }
+    }
+}
+
+#[async_trait]
+impl ProgramAccountsProvider for RemoteProgramAccountsProvider {
+    async fn get_program_account_keys(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> AccountsResult<Vec<Pubkey>> {
+        // We only need the pubkeys here since each account is fetched again
+        // together with its delegation state when it is cloned
+        let config = RpcProgramAccountsConfig {
+            filters: (!filters.is_empty()).then_some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                commitment: Some(CommitmentConfig::confirmed()),
+                min_context_slot: None,
+            },
+            ..Default::default()
+        };
+        let accounts = self
+            .rpc_client
+            .get_program_accounts_with_config(program_id, config)
+            .await
+            .map_err(|err| {
+                AccountsError::FailedToGetProgramAccounts(format!(
+                    "{}: {:?}",
+                    program_id, err
+                ))
+            })?;
+        Ok(accounts.into_iter().map(|(pubkey, _)| pubkey).collect())
+    }
+}
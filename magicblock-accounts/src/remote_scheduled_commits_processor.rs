@@ -1,31 +1,63 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    },
+};
 
 use async_trait::async_trait;
-use conjunto_transwise::AccountChainSnapshot;
+use conjunto_transwise::{AccountChainSnapshot, AccountChainState};
 use log::*;
 use magicblock_account_cloner::{
     AccountClonerOutput, AccountClonerOutput::Cloned, CloneOutputMap,
 };
 use magicblock_accounts_api::InternalAccountProvider;
 use magicblock_bank::bank::Bank;
-use magicblock_core::debug_panic;
-use magicblock_metrics::metrics;
+use magicblock_core::{
+    commit_record::{CommitOutcome, CommitRecord},
+    debug_panic,
+    mutation_audit::MutationReason,
+    traits::PersistsCommitRecords,
+};
+use magicblock_metrics::{health, metrics};
 use magicblock_mutator::Cluster;
 use magicblock_processor::execute_transaction::execute_legacy_transaction;
 use magicblock_program::{
+    magicblock_instruction::{
+        modify_accounts, scheduled_commit_sent, settle_transfer_intent,
+        AccountModification,
+    },
     register_scheduled_commit_sent, FeePayerAccount, SentCommit,
     TransactionScheduler,
 };
 use magicblock_transaction_status::TransactionStatusSender;
-use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_sdk::{
+    account::AccountSharedData, hash::Hash, pubkey::Pubkey,
+    signature::Signature, system_program,
+};
+use tracing::Instrument;
 
 use crate::{
+    commit_event_recorder::CommitEventRecorder,
+    commit_webhook::CommitWebhook,
     errors::{AccountsError, AccountsResult},
+    external_accounts_manager::hash_account,
     remote_account_committer::update_account_commit_metrics,
-    AccountCommittee, AccountCommitter, ScheduledCommitsProcessor,
-    SendableCommitAccountsPayload,
+    AccountCommittee, AccountCommitter, CommitWebhookConfig,
+    ScheduledCommitsProcessor, SendableCommitAccountsPayload,
 };
 
+const UNCONFIRMED_COMMIT_FAILURE: &str =
+    "Commit transaction failed or was not confirmed in time";
+
+/// Component the outcome of sending commits is reported as via `/readyz`
+const COMMITTER_HEALTH: &str = "committer";
+
+/// Hashes of delegated accounts at their last confirmed commit
+type CommittedHashes = Arc<RwLock<HashMap<Pubkey, Hash>>>;
+
 pub struct RemoteScheduledCommitsProcessor {
     #[allow(unused)]
     cluster: Cluster,
@@ -33,6 +65,23 @@ pub struct RemoteScheduledCommitsProcessor {
     transaction_status_sender: Option<TransactionStatusSender>,
     transaction_scheduler: TransactionScheduler,
     cloned_accounts: CloneOutputMap,
+    commit_record_persister: Option<Arc<dyn PersistsCommitRecords>>,
+    /// If set, accounts that didn't change since their last commit are not
+    /// committed again unless they are undelegated
+    changed_only: bool,
+    /// If set, commit transactions are simulated before they are sent and
+    /// the ones whose simulation fails are not sent
+    simulate: bool,
+    committed_hashes: CommittedHashes,
+    /// Commits that were sent to chain, but are not confirmed yet
+    pending_commits: Arc<AtomicUsize>,
+    /// Commits whose outcome is not final yet
+    unresolved_commits: Arc<AtomicUsize>,
+    /// Commits that failed to land on chain since the validator started
+    failed_commits: Arc<AtomicUsize>,
+    webhook: Option<Arc<CommitWebhook>>,
+    /// Makes the outcomes of commits observable on the ephemeral rollup
+    commit_events: CommitEventRecorder,
 }
 
 #[async_trait]
@@ -46,18 +95,47 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
         AC: AccountCommitter,
         IAP: InternalAccountProvider,
     {
-        let scheduled_commits =
-            self.transaction_scheduler.take_scheduled_commits();
+        let mut scheduled_commits = self
+            .transaction_scheduler
+            .take_due_scheduled_commits(self.bank.slot());
 
         if scheduled_commits.is_empty() {
             return Ok(());
         }
+        // Counted from the moment they are taken off the schedule until they
+        // were sent and their outcome is final
+        let unresolved = UnresolvedCommits::new(
+            self.unresolved_commits.clone(),
+            scheduled_commits.len(),
+        );
+        // Commits with a higher priority are sent first, otherwise they are
+        // sent in the order they were scheduled
+        scheduled_commits.sort_by_key(|commit| Reverse(commit.priority));
 
         let mut sendable_payloads_queue = vec![];
-        for commit in scheduled_commits {
+        let mut pending_commit_records = vec![];
+        // Accounts to close once the commit undelegating them is confirmed
+        let mut accounts_to_close = HashMap::new();
+        // Escrows of the transfer intents that are settled once the outcome
+        // of their commit is known
+        let mut transfer_intent_escrows = HashMap::new();
+        for mut commit in scheduled_commits {
+            // Commits scheduled in advance commit the state of the slot they are due
+            // at, the blockhash they were scheduled with may have expired by then
+            if commit.execute_at_slot.is_some() {
+                commit.slot = self.bank.slot();
+                commit.blockhash = self.bank.last_blockhash();
+                commit.commit_sent_transaction =
+                    scheduled_commit_sent(commit.id, commit.blockhash);
+                info!(
+                    "Commit {} scheduled in advance is due, ScheduledCommitSent signature: {}",
+                    commit.id, commit.commit_sent_transaction.signatures[0]
+                );
+            }
             info!("Processing commit: {:?}", commit);
 
             // Determine which accounts are available and can be committed
+            let account_count = commit.accounts.len();
             let mut committees = vec![];
             let all_pubkeys: HashSet<Pubkey> = HashSet::from_iter(
                 commit
@@ -67,6 +145,24 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
                     .collect::<Vec<_>>(),
             );
             let mut feepayers = HashSet::new();
+            // Accounts whose last commit already has their current state
+            let mut unchanged_pubkeys = HashSet::new();
+
+            // Only a delegated escrow pays out on the base layer, otherwise
+            // the intent is refunded without committing the escrow
+            let transfer_intent_escrow = commit
+                .transfer_intent
+                .as_ref()
+                .and(commit.accounts.first())
+                .map(|account| account.pubkey);
+            let intent_error = transfer_intent_escrow
+                .filter(|escrow| !self.is_delegated(escrow))
+                .map(|escrow| {
+                    format!(
+                        "escrow {} of the transfer intent is not delegated",
+                        escrow
+                    )
+                });
 
             for committed_account in commit.accounts {
                 let mut commitment_pubkey = committed_account.pubkey;
@@ -100,12 +196,33 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
 
                 match account_provider.get_account(&committed_account.pubkey) {
                     Some(account_data) => {
+                        if commit.request_undelegation {
+                            self.forget_committed_hash(&commitment_pubkey);
+                        } else if commit.transfer_intent.is_none()
+                            && !commit.atomic
+                            && self.is_unchanged_since_last_commit(
+                                &commitment_pubkey,
+                                &account_data,
+                            )
+                        {
+                            debug!(
+                                "Skipping commit of unchanged account '{}'",
+                                commitment_pubkey
+                            );
+                            metrics::inc_account_commits_skipped();
+                            unchanged_pubkeys.insert(commitment_pubkey);
+                            continue;
+                        }
                         committees.push(AccountCommittee {
                             pubkey: commitment_pubkey,
                             owner: commitment_pubkey_owner,
                             account_data,
                             slot: commit.slot,
                             undelegation_requested: commit.request_undelegation,
+                            base_layer_action: None,
+                            compute_unit_price: commit.compute_unit_price,
+                            memo: commit.memo.clone(),
+                            priority: commit.priority,
                         });
                     }
                     None => {
@@ -117,16 +234,57 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
                 }
             }
 
-            let payloads = vec![
-                committer
-                    .create_commit_accounts_transaction(committees)
-                    .await?,
-            ];
+            // The action runs once all accounts of the commit are committed,
+            // a transfer intent is paid out once its escrow is committed
+            if let Some(last_committee) = committees.last_mut() {
+                last_committee.base_layer_action = commit
+                    .base_layer_action
+                    .as_ref()
+                    .map(|action| action.to_instruction())
+                    .or_else(|| {
+                        commit
+                            .transfer_intent
+                            .as_ref()
+                            .map(|intent| intent.to_instruction())
+                    });
+            }
+
+            // An atomic commit is only sent if a single transaction commits all
+            // of its accounts, otherwise none of them is committed
+            let mut atomic_error = None;
+            if commit.atomic && committees.len() != account_count {
+                atomic_error = Some(format!(
+                    "{} of {} accounts are not available",
+                    account_count - committees.len(),
+                    account_count
+                ));
+                committees.clear();
+            }
+            if let Some(err) = &intent_error {
+                warn!(
+                    "Transfer intent of commit {} is not paid out: {}",
+                    commit.id, err
+                );
+                committees.clear();
+            }
+            let mut payloads = committer
+                .create_commit_accounts_transactions(committees)
+                .await?;
+            if commit.atomic && payloads.len() > 1 {
+                atomic_error = Some(format!(
+                    "{} accounts do not fit into a single transaction",
+                    account_count
+                ));
+                payloads.clear();
+            }
+            if let Some(err) = &atomic_error {
+                warn!("Atomic commit {} is not sent: {}", commit.id, err);
+            }
 
             // Determine which payloads are a noop since all accounts are up to date
             // and which require a commit to chain
             let mut included_pubkeys = HashSet::new();
-            let sendable_payloads = payloads
+            let mut sendable_payloads = payloads
                 .into_iter()
                 .filter_map(|payload| {
                     if let Some(transaction) = payload.transaction {
@@ -146,6 +304,32 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
                 })
                 .collect::<Vec<SendableCommitAccountsPayload>>();
 
+            // Transactions that would fail on chain are not sent, the accounts
+            // they commit are excluded instead
+            let mut simulation_errors = vec![];
+            if self.simulate && !sendable_payloads.is_empty() {
+                let results = committer
+                    .simulate_commit_transactions(&sendable_payloads)
+                    .await;
+                sendable_payloads = sendable_payloads
+                    .into_iter()
+                    .zip(results)
+                    .filter_map(|(payload, error)| match error {
+                        Some(error) => {
+                            for (pubkey, _) in &payload.committees {
+                                included_pubkeys.remove(pubkey);
+                            }
+                            metrics::inc_account_commit_simulation_failures();
+                            simulation_errors.push(error);
+                            None
+                        }
+                        None => Some(payload),
+                    })
+                    .collect();
+            }
+            // Skipped accounts are included since chain has their state already
+            included_pubkeys.extend(unchanged_pubkeys);
+
             // Tally up the pubkeys that will not be committed since the account
             // was not available as determined when creating sendable payloads
             let excluded_pubkeys = all_pubkeys
@@ -167,6 +351,49 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
                 .map(|payload| payload.get_signature())
                 .collect::<Vec<Signature>>();
 
+            // Keep an audit trail of the commit in the ledger, the outcome is
+            // updated once the commit transactions are confirmed
+            let commit_record = CommitRecord {
+                commit_id: commit.id,
+                slot: commit.slot,
+                payer: commit.payer,
+                included_pubkeys: included_pubkeys.iter().copied().collect(),
+                excluded_pubkeys: excluded_pubkeys.clone(),
+                requested_undelegation: commit.request_undelegation,
+                chain_signatures: signatures.clone(),
+                outcome: if !sendable_payloads.is_empty() {
+                    CommitOutcome::Pending
+                } else if let Some(err) = &atomic_error {
+                    CommitOutcome::Failed(err.clone())
+                } else if let Some(err) = &intent_error {
+                    CommitOutcome::Failed(err.clone())
+                } else if !simulation_errors.is_empty() {
+                    CommitOutcome::Failed(simulation_errors.join("; "))
+                } else {
+                    CommitOutcome::NotNeeded
+                },
+            };
+            persist_commit_record(
+                self.commit_record_persister.as_deref(),
+                &commit_record,
+            );
+            count_failed_commit(&self.failed_commits, &commit_record);
+            self.commit_events.record(&commit_record);
+            // Fee payers are never closed, only their escrow is committed
+            if commit.close_accounts {
+                let closable_pubkeys = commit_record
+                    .included_pubkeys
+                    .iter()
+                    .filter(|pubkey| {
+                        !feepayers
+                            .iter()
+                            .any(|feepayer| feepayer.delegated_pda == **pubkey)
+                    })
+                    .copied()
+                    .collect::<Vec<_>>();
+                accounts_to_close.insert(commit.id, closable_pubkeys);
+            }
+
             // Record that we are about to send the commit to chain including all
             // information (mainly signatures) needed to track its outcome on chain
             let sent_commit = SentCommit {
@@ -179,6 +406,9 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
                 excluded_pubkeys,
                 feepayers,
                 requested_undelegation: commit.request_undelegation,
+                simulation_errors,
+                atomic: commit.atomic,
+                atomic_error,
             };
             register_scheduled_commit_sent(sent_commit);
             let signature = execute_legacy_transaction(
@@ -188,9 +418,20 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
             )
             .map_err(Box::new)?;
 
-            // In the case that no account needs to be committed we record that in
-            // our ledger and are done
+            // In the case that no account needs to be committed or all commit
+            // transactions would fail we record that in our ledger and are done
             if sendable_payloads.is_empty() {
+                if let Some(webhook) = &self.webhook {
+                    webhook.notify(&commit_record);
+                }
+                if let Some(escrow) = &transfer_intent_escrow {
+                    settle_transfer_intents(
+                        &self.bank,
+                        self.transaction_status_sender.as_ref(),
+                        &HashMap::from([(commit.id, *escrow)]),
+                        std::slice::from_ref(&commit_record),
+                    );
+                }
                 debug!(
                     "Signaled no commit needed with internal signature: {:?}",
                     signature
@@ -204,12 +445,20 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
             }
 
             // Queue up the actual commit
+            if let Some(escrow) = transfer_intent_escrow {
+                transfer_intent_escrows.insert(commit.id, escrow);
+            }
             sendable_payloads_queue.extend(sendable_payloads);
+            pending_commit_records.push(commit_record);
         }
 
         self.process_accounts_commits_in_background(
             committer,
+            unresolved,
             sendable_payloads_queue,
+            pending_commit_records,
+            accounts_to_close,
+            transfer_intent_escrows,
         );
 
         Ok(())
@@ -219,6 +468,18 @@ impl ScheduledCommitsProcessor for RemoteScheduledCommitsProcessor {
         self.transaction_scheduler.scheduled_commits_len()
     }
 
+    fn pending_commits_len(&self) -> usize {
+        self.pending_commits.load(Ordering::Relaxed)
+    }
+
+    fn unresolved_commits_len(&self) -> usize {
+        self.unresolved_commits.load(Ordering::Relaxed)
+    }
+
+    fn failed_commits_len(&self) -> usize {
+        self.failed_commits.load(Ordering::Relaxed)
+    }
+
     fn clear_scheduled_commits(&self) {
         self.transaction_scheduler.clear_scheduled_commits();
     }
@@ -230,45 +491,152 @@ impl RemoteScheduledCommitsProcessor {
         bank: Arc<Bank>,
         cloned_accounts: CloneOutputMap,
         transaction_status_sender: Option<TransactionStatusSender>,
+        commit_record_persister: Option<Arc<dyn PersistsCommitRecords>>,
+        changed_only: bool,
+        simulate: bool,
+        webhook_config: Option<CommitWebhookConfig>,
     ) -> Self {
         Self {
             cluster,
+            commit_events: CommitEventRecorder::new(
+                bank.clone(),
+                transaction_status_sender.clone(),
+            ),
             bank,
             transaction_status_sender,
             cloned_accounts,
+            commit_record_persister,
+            changed_only,
+            simulate,
+            committed_hashes: Default::default(),
+            pending_commits: Default::default(),
+            unresolved_commits: Default::default(),
+            failed_commits: Default::default(),
+            webhook: webhook_config
+                .map(|config| Arc::new(CommitWebhook::new(config))),
             transaction_scheduler: TransactionScheduler::default(),
         }
     }
 
+    fn is_unchanged_since_last_commit(
+        &self,
+        pubkey: &Pubkey,
+        account: &AccountSharedData,
+    ) -> bool {
+        self.changed_only
+            && self
+                .committed_hashes
+                .read()
+                .expect("RwLock of committed_hashes is poisoned")
+                .get(pubkey)
+                .is_some_and(|hash| *hash == hash_account(account))
+    }
+
+    fn forget_committed_hash(&self, pubkey: &Pubkey) {
+        if self.changed_only {
+            self.committed_hashes
+                .write()
+                .expect("RwLock of committed_hashes is poisoned")
+                .remove(pubkey);
+        }
+    }
+
     fn process_accounts_commits_in_background<AC: AccountCommitter>(
         &self,
         committer: &Arc<AC>,
+        unresolved: UnresolvedCommits,
         sendable_payloads_queue: Vec<SendableCommitAccountsPayload>,
+        mut pending_commit_records: Vec<CommitRecord>,
+        mut accounts_to_close: HashMap<u64, Vec<Pubkey>>,
+        transfer_intent_escrows: HashMap<u64, Pubkey>,
     ) {
         // We process the queue on a separate task in order to not block
-        // the validator (slot advance) itself
-        // NOTE: @@ we have to be careful here and ensure that the validator does not
-        // shutdown before this task is done
-        // We will need some tracking machinery which is overkill until we get to the
-        // point where we do allow validator shutdown
+        // the validator (slot advance) itself, the commits are tracked as
+        // unresolved until it is done so that shutdown can wait for them
         let committer = committer.clone();
+        let persister = self.commit_record_persister.clone();
+        let webhook = self.webhook.clone();
+        let commit_events = self.commit_events.clone();
+        let committed_hashes = self.committed_hashes.clone();
+        let bank = self.bank.clone();
+        let transaction_status_sender = self.transaction_status_sender.clone();
+        let cloned_accounts = self.cloned_accounts.clone();
+        let pending_commits_count = self.pending_commits.clone();
+        let pending_count = pending_commit_records.len();
+        track_pending_commits(&pending_commits_count, pending_count as isize);
+        let failed_commits = self.failed_commits.clone();
+        // Hashes of committed accounts by the signature of the transaction
+        // committing them, undelegated accounts are no longer tracked
+        let mut hashes_by_signature = if self.changed_only {
+            sendable_payloads_queue
+                .iter()
+                .map(|payload| {
+                    let hashes = payload
+                        .committees
+                        .iter()
+                        .filter(|(pubkey, _)| {
+                            !payload
+                                .transaction
+                                .undelegated_accounts
+                                .contains(pubkey)
+                        })
+                        .map(|(pubkey, account)| {
+                            (*pubkey, hash_account(account))
+                        })
+                        .collect::<Vec<_>>();
+                    (payload.get_signature(), hashes)
+                })
+                .collect::<HashMap<_, _>>()
+        } else {
+            HashMap::new()
+        };
         tokio::task::spawn(async move {
+            let _unresolved = unresolved;
             let pending_commits = match committer
                 .send_commit_transactions(sendable_payloads_queue)
+                .instrument(tracing::info_span!("commit_accounts"))
                 .await
             {
-                Ok(pending) => pending,
+                Ok(pending) => {
+                    health::set_readiness(COMMITTER_HEALTH, Ok(()));
+                    pending
+                }
                 Err(AccountsError::FailedToSendCommitTransaction(
                     err,
                     commit_and_undelegate_accounts,
                     commit_only_accounts,
                 )) => {
+                    health::set_readiness(
+                        COMMITTER_HEALTH,
+                        Err(format!(
+                            "failed to send commit transactions: {}",
+                            err
+                        )),
+                    );
                     update_account_commit_metrics(
                         &commit_and_undelegate_accounts,
                         &commit_only_accounts,
                         metrics::Outcome::Error,
                         None,
                     );
+                    let records = record_commit_outcomes(
+                        persister.as_deref(),
+                        webhook.as_deref(),
+                        &commit_events,
+                        &failed_commits,
+                        pending_commit_records,
+                        |_| CommitOutcome::Failed(format!("{:?}", err)),
+                    );
+                    settle_transfer_intents(
+                        &bank,
+                        transaction_status_sender.as_ref(),
+                        &transfer_intent_escrows,
+                        &records,
+                    );
+                    track_pending_commits(
+                        &pending_commits_count,
+                        -(pending_count as isize),
+                    );
                     debug_panic!(
                         "Failed to send commit transactions: {:?}",
                         err
@@ -276,6 +644,31 @@ impl RemoteScheduledCommitsProcessor {
                     return;
                 }
                 Err(err) => {
+                    health::set_readiness(
+                        COMMITTER_HEALTH,
+                        Err(format!(
+                            "failed to send commit transactions: {}",
+                            err
+                        )),
+                    );
+                    let records = record_commit_outcomes(
+                        persister.as_deref(),
+                        webhook.as_deref(),
+                        &commit_events,
+                        &failed_commits,
+                        pending_commit_records,
+                        |_| CommitOutcome::Failed(format!("{:?}", err)),
+                    );
+                    settle_transfer_intents(
+                        &bank,
+                        transaction_status_sender.as_ref(),
+                        &transfer_intent_escrows,
+                        &records,
+                    );
+                    track_pending_commits(
+                        &pending_commits_count,
+                        -(pending_count as isize),
+                    );
                     debug_panic!(
                         "Failed to send commit transactions, received invalid err: {:?}",
                         err
@@ -284,10 +677,140 @@ impl RemoteScheduledCommitsProcessor {
                 }
             };
 
-            committer.confirm_pending_commits(pending_commits).await;
+            // Transactions that had to be signed again with an advanced durable
+            // nonce landed with a different signature than the one recorded
+            let landed_signatures = pending_commits
+                .iter()
+                .map(|pc| (pc.initial_signature, pc.signature))
+                .collect::<Vec<_>>();
+            let resigned = landed_signatures
+                .iter()
+                .filter(|(initial, landed)| initial != landed)
+                .copied()
+                .collect::<HashMap<_, _>>();
+            if !resigned.is_empty() {
+                for record in pending_commit_records.iter_mut() {
+                    for signature in record.chain_signatures.iter_mut() {
+                        if let Some(landed) = resigned.get(signature) {
+                            *signature = *landed;
+                        }
+                    }
+                }
+            }
+
+            let confirmed_signatures =
+                committer.confirm_pending_commits(pending_commits).await;
+            track_pending_commits(
+                &pending_commits_count,
+                -(pending_count as isize),
+            );
+            if !hashes_by_signature.is_empty() {
+                let mut committed_hashes = committed_hashes
+                    .write()
+                    .expect("RwLock of committed_hashes is poisoned");
+                for (initial, landed) in landed_signatures {
+                    if !confirmed_signatures.contains(&landed) {
+                        continue;
+                    }
+                    if let Some(hashes) = hashes_by_signature.remove(&initial) {
+                        committed_hashes.extend(hashes);
+                    }
+                }
+            }
+            let records = record_commit_outcomes(
+                persister.as_deref(),
+                webhook.as_deref(),
+                &commit_events,
+                &failed_commits,
+                pending_commit_records,
+                |record| {
+                    if record.chain_signatures.iter().all(|signature| {
+                        confirmed_signatures.contains(signature)
+                    }) {
+                        CommitOutcome::Confirmed
+                    } else {
+                        CommitOutcome::Failed(
+                            UNCONFIRMED_COMMIT_FAILURE.to_string(),
+                        )
+                    }
+                },
+            );
+
+            settle_transfer_intents(
+                &bank,
+                transaction_status_sender.as_ref(),
+                &transfer_intent_escrows,
+                &records,
+            );
+
+            // Keep tracking the confirmed commits until they are finalized
+            let confirmed_records = records
+                .into_iter()
+                .filter(|record| record.outcome == CommitOutcome::Confirmed)
+                .collect::<Vec<_>>();
+            if confirmed_records.is_empty() {
+                return;
+            }
+            let closed_pubkeys = confirmed_records
+                .iter()
+                .flat_map(|record| {
+                    accounts_to_close
+                        .remove(&record.commit_id)
+                        .unwrap_or_default()
+                })
+                .collect::<Vec<_>>();
+            if !closed_pubkeys.is_empty() {
+                close_undelegated_accounts(
+                    &bank,
+                    transaction_status_sender.as_ref(),
+                    &cloned_accounts,
+                    closed_pubkeys,
+                );
+            }
+            let finalized_signatures = committer
+                .finalize_confirmed_commits(
+                    confirmed_records
+                        .iter()
+                        .flat_map(|record| record.chain_signatures.clone())
+                        .collect(),
+                )
+                .await;
+            let (finalized_records, unfinalized_records): (Vec<_>, Vec<_>) =
+                confirmed_records.into_iter().partition(|record| {
+                    record.chain_signatures.iter().all(|signature| {
+                        finalized_signatures.contains(signature)
+                    })
+                });
+            for record in unfinalized_records {
+                warn!(
+                    "Commit {} was confirmed, but not finalized in time",
+                    record.commit_id
+                );
+            }
+            record_commit_outcomes(
+                persister.as_deref(),
+                webhook.as_deref(),
+                &commit_events,
+                &failed_commits,
+                finalized_records,
+                |_| CommitOutcome::Finalized,
+            );
         });
     }
 
+    fn is_delegated(&self, pubkey: &Pubkey) -> bool {
+        matches!(
+            Self::fetch_cloned_account(pubkey, &self.cloned_accounts),
+            Some(Cloned {
+                account_chain_snapshot,
+                ..
+            }) if matches!(
+                account_chain_snapshot.chain_state,
+                AccountChainState::Delegated { .. }
+            )
+        )
+    }
+
     fn fetch_cloned_account(
         pubkey: &Pubkey,
         cloned_accounts: &CloneOutputMap,
@@ -298,3 +821,182 @@ impl RemoteScheduledCommitsProcessor {
             .get(pubkey).cloned()
     }
 }
+
+/// Closes accounts on the ephemeral rollup once their undelegation was
+/// confirmed, their lamports are reclaimed by the validator authority.
+/// They are also forgotten by the cloner so that they are cloned again
+/// if they are used afterwards.
+fn close_undelegated_accounts(
+    bank: &Bank,
+    transaction_status_sender: Option<&TransactionStatusSender>,
+    cloned_accounts: &CloneOutputMap,
+    pubkeys: Vec<Pubkey>,
+) {
+    let modifications = pubkeys
+        .iter()
+        .map(|pubkey| AccountModification {
+            pubkey: *pubkey,
+            lamports: Some(0),
+            owner: Some(system_program::id()),
+            executable: Some(false),
+            data: Some(vec![]),
+            rent_epoch: None,
+        })
+        .collect();
+    let transaction = modify_accounts(
+        modifications,
+        MutationReason::CloseUndelegatedAccounts,
+        bank.last_blockhash(),
+    );
+    match execute_legacy_transaction(
+        transaction,
+        bank,
+        transaction_status_sender,
+    ) {
+        Ok(signature) => {
+            debug!(
+                "Closed {} undelegated account(s) with signature: {}",
+                pubkeys.len(),
+                signature
+            );
+        }
+        Err(err) => {
+            error!(
+                "Failed to close undelegated accounts {:?}: {:?}",
+                pubkeys, err
+            );
+            return;
+        }
+    }
+    let mut cloned_accounts = cloned_accounts.write().expect(
+        "RwLock of RemoteAccountClonerWorker.last_clone_output is poisoned",
+    );
+    for pubkey in &pubkeys {
+        cloned_accounts.remove(pubkey);
+    }
+}
+
+/// Settles the transfer intents of the commits once it is known whether their
+/// recipient was paid on the base layer. Intents of commits that could not be
+/// confirmed in time stay held since they may have been paid out after all.
+fn settle_transfer_intents(
+    bank: &Bank,
+    transaction_status_sender: Option<&TransactionStatusSender>,
+    escrows: &HashMap<u64, Pubkey>,
+    records: &[CommitRecord],
+) {
+    for record in records {
+        let Some(escrow) = escrows.get(&record.commit_id) else {
+            continue;
+        };
+        let paid_out = match &record.outcome {
+            CommitOutcome::Pending => continue,
+            CommitOutcome::Failed(err) if err == UNCONFIRMED_COMMIT_FAILURE => {
+                error!(
+                    "Transfer intent of commit {} stays held since its payout could not be confirmed",
+                    record.commit_id
+                );
+                continue;
+            }
+            CommitOutcome::Confirmed | CommitOutcome::Finalized => true,
+            CommitOutcome::Failed(_) | CommitOutcome::NotNeeded => false,
+        };
+        let transaction = settle_transfer_intent(
+            record.commit_id,
+            *escrow,
+            paid_out,
+            bank.last_blockhash(),
+        );
+        match execute_legacy_transaction(
+            transaction,
+            bank,
+            transaction_status_sender,
+        ) {
+            Ok(signature) => debug!(
+                "Settled transfer intent of commit {} with signature: {}",
+                record.commit_id, signature
+            ),
+            Err(err) => error!(
+                "Failed to settle transfer intent of commit {}: {:?}",
+                record.commit_id, err
+            ),
+        }
+    }
+}
+
+/// Adjusts the number of commits that are not confirmed yet
+fn track_pending_commits(pending_commits: &AtomicUsize, delta: isize) {
+    let count = if delta >= 0 {
+        pending_commits.fetch_add(delta as usize, Ordering::Relaxed)
+            + delta as usize
+    } else {
+        pending_commits.fetch_sub(delta.unsigned_abs(), Ordering::Relaxed)
+            - delta.unsigned_abs()
+    };
+    metrics::set_pending_commits_count(count);
+}
+
+/// Commits counted as unresolved until they are dropped, which happens once
+/// the task processing them is done, including when it returns early
+struct UnresolvedCommits {
+    count: Arc<AtomicUsize>,
+    len: usize,
+}
+
+impl UnresolvedCommits {
+    fn new(count: Arc<AtomicUsize>, len: usize) -> Self {
+        count.fetch_add(len, Ordering::Relaxed);
+        Self { count, len }
+    }
+}
+
+impl Drop for UnresolvedCommits {
+    fn drop(&mut self) {
+        self.count.fetch_sub(self.len, Ordering::Relaxed);
+    }
+}
+
+fn count_failed_commit(failed_commits: &AtomicUsize, record: &CommitRecord) {
+    if matches!(record.outcome, CommitOutcome::Failed(_)) {
+        failed_commits.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Persists the outcome of each record, notifies the webhook of it, records
+/// it in the commit events account and returns the updated records
+fn record_commit_outcomes(
+    persister: Option<&dyn PersistsCommitRecords>,
+    webhook: Option<&CommitWebhook>,
+    commit_events: &CommitEventRecorder,
+    failed_commits: &AtomicUsize,
+    records: Vec<CommitRecord>,
+    outcome_of: impl Fn(&CommitRecord) -> CommitOutcome,
+) -> Vec<CommitRecord> {
+    records
+        .into_iter()
+        .map(|mut record| {
+            record.outcome = outcome_of(&record);
+            persist_commit_record(persister, &record);
+            count_failed_commit(failed_commits, &record);
+            if let Some(webhook) = webhook {
+                webhook.notify(&record);
+            }
+            commit_events.record(&record);
+            record
+        })
+        .collect()
+}
+
+fn persist_commit_record(
+    persister: Option<&dyn PersistsCommitRecords>,
+    record: &CommitRecord,
+) {
+    if let Some(persister) = persister {
+        if let Err(err) = persister.persist_commit_record(record) {
+            error!(
+                "Failed to persist record of commit {}: {:?}",
+                record.commit_id, err
+            );
+        }
+    }
+}
@@ -3,10 +3,12 @@ use std::{collections::HashSet, sync::Arc};
 use async_trait::async_trait;
 use magicblock_accounts_api::InternalAccountProvider;
 use magicblock_metrics::metrics::HistogramTimer;
+use magicblock_program::magicblock_instruction::CommitPriority;
 use solana_rpc_client::rpc_client::SerializableTransaction;
+use solana_rpc_client_api::filter::RpcFilterType;
 use solana_sdk::{
-    account::AccountSharedData, pubkey::Pubkey, signature::Signature,
-    transaction::Transaction,
+    account::AccountSharedData, instruction::Instruction, pubkey::Pubkey,
+    signature::Signature, transaction::Transaction,
 };
 
 use crate::errors::AccountsResult;
@@ -22,6 +24,15 @@ pub trait ScheduledCommitsProcessor {
 
     /// Returns the number of commits that were scheduled and accepted
     fn scheduled_commits_len(&self) -> usize;
+    /// Returns the number of commits that were sent to chain, but are not
+    /// confirmed yet
+    fn pending_commits_len(&self) -> usize;
+    /// Returns the number of commits that are still being sent, confirmed
+    /// or finalized
+    fn unresolved_commits_len(&self) -> usize;
+    /// Returns the number of commits that failed to land on chain since the
+    /// validator started
+    fn failed_commits_len(&self) -> usize;
     /// Clears all scheduled commits
     fn clear_scheduled_commits(&self);
 }
@@ -39,6 +50,14 @@ pub struct AccountCommittee {
     pub slot: u64,
     /// Only present if undelegation was requested.
     pub undelegation_requested: bool,
+    /// Instruction to run on chain right after the account was committed.
+    pub base_layer_action: Option<Instruction>,
+    /// Compute unit price requested by the program that scheduled the commit.
+    pub compute_unit_price: Option<u64>,
+    /// Memo included in the transaction committing the account.
+    pub memo: Option<String>,
+    /// Priority of the commit, determines the compute unit price offered.
+    pub priority: CommitPriority,
 }
 
 #[derive(Debug)]
@@ -87,6 +106,10 @@ impl SendableCommitAccountsPayload {
 pub struct PendingCommitTransaction {
     /// The signature of the transaction that was sent to chain.
     pub signature: Signature,
+    /// The signature the transaction was created with, it differs from
+    /// [Self::signature] if it had to be signed again with an advanced
+    /// durable nonce before it landed.
+    pub initial_signature: Signature,
     /// The accounts that are undelegated on chain as part of this transaction.
     pub undelegated_accounts: HashSet<Pubkey>,
     /// Accounts that are only committed and not undelegated as part of the transaction.
@@ -98,15 +121,25 @@ pub struct PendingCommitTransaction {
 
 #[async_trait]
 pub trait AccountCommitter: Send + Sync + 'static {
-    /// Creates a transaction to commit each provided account unless it determines
+    /// Creates transactions to commit each provided account unless it determines
     /// that it isn't necessary, i.e. when the previously committed state is the same
     /// as the [commit_state_data].
-    /// Returns the transaction committing the accounts and the pubkeys of accounts
-    /// it did commit
-    async fn create_commit_accounts_transaction(
+    /// As many accounts as fit within the transaction size and compute limits
+    /// are packed into each transaction, preserving the order of the committees.
+    /// Returns the transactions committing the accounts together with the pubkeys
+    /// of accounts each of them commits
+    async fn create_commit_accounts_transactions(
         &self,
         committees: Vec<AccountCommittee>,
-    ) -> AccountsResult<CommitAccountsPayload>;
+    ) -> AccountsResult<Vec<CommitAccountsPayload>>;
+
+    /// Simulates the commit transactions on the remote cluster.
+    /// Returns for each payload the reason its simulation failed, `None` if it
+    /// succeeded or the simulation itself could not be performed.
+    async fn simulate_commit_transactions(
+        &self,
+        payloads: &[SendableCommitAccountsPayload],
+    ) -> Vec<Option<String>>;
 
     /// Returns the main-chain signatures of the commit transactions
     /// This will only fail due to network issues, not if the transaction failed.
@@ -121,8 +154,43 @@ pub trait AccountCommitter: Send + Sync + 'static {
     /// commitment level.
     /// Updates the metrics for each transaction in order to record the time it took
     /// to fully confirm it on chain.
+    /// Returns the signatures of the transactions that were confirmed to have
+    /// succeeded.
     async fn confirm_pending_commits(
         &self,
         pending_commits: Vec<PendingCommitTransaction>,
-    );
+    ) -> HashSet<Signature>;
+
+    /// Waits for the confirmed commit transactions with the given [signatures]
+    /// to be finalized.
+    /// Returns the signatures of the transactions that were finalized in time.
+    async fn finalize_confirmed_commits(
+        &self,
+        signatures: Vec<Signature>,
+    ) -> HashSet<Signature>;
+}
+
+#[async_trait]
+pub trait ProgramAccountsProvider: Send + Sync + 'static {
+    /// Returns the pubkeys of all accounts owned by the [program_id] on the
+    /// remote cluster which match all of the provided [filters].
+    async fn get_program_account_keys(
+        &self,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+    ) -> AccountsResult<Vec<Pubkey>>;
+}
+
+/// Outcome of cloning all accounts of a program
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ClonedProgramAccounts {
+    /// The number of accounts of the program found on the remote cluster
+    pub total: usize,
+    /// The number of accounts that were cloned or already up to date
+    pub cloned: usize,
+    /// The number of accounts that cannot be cloned, i.e. because they are
+    /// blacklisted or delegated to another validator
+    pub unclonable: usize,
+    /// The number of accounts that failed to be fetched or dumped
+    pub failed: usize,
 }
@@ -18,6 +18,7 @@ use solana_sdk::{
 };
 use stubs::{
     account_committer_stub::AccountCommitterStub,
+    program_accounts_provider_stub::ProgramAccountsProviderStub,
     scheduled_commits_processor_stub::ScheduledCommitsProcessorStub,
 };
 use test_tools_core::init_logger;
@@ -31,6 +32,7 @@ type StubbedAccountsManager = ExternalAccountsManager<
     TransactionAccountsExtractorImpl,
     TransactionAccountsValidatorImpl,
     ScheduledCommitsProcessorStub,
+    ProgramAccountsProviderStub,
 >;
 
 fn setup(
@@ -45,6 +47,7 @@ fn setup(
         transaction_accounts_extractor: TransactionAccountsExtractorImpl,
         transaction_accounts_validator: TransactionAccountsValidatorImpl,
         scheduled_commits_processor: ScheduledCommitsProcessorStub::default(),
+        program_accounts_provider: ProgramAccountsProviderStub::default(),
         lifecycle: LifecycleMode::Ephemeral,
         external_commitable_accounts: Default::default(),
     }
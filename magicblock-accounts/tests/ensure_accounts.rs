@@ -13,12 +13,25 @@ use magicblock_account_dumper::AccountDumperStub;
 use magicblock_account_fetcher::AccountFetcherStub;
 use magicblock_account_updates::AccountUpdatesStub;
 use magicblock_accounts::{
-    errors::AccountsError, ExternalAccountsManager, LifecycleMode,
+    errors::AccountsError, ClonedProgramAccounts, ExternalAccountsManager,
+    LifecycleMode,
 };
 use magicblock_accounts_api::InternalAccountProviderStub;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{
+    address_lookup_table,
+    hash::Hash,
+    message::{
+        v0::{self, MessageAddressTableLookup},
+        Message, MessageHeader, VersionedMessage,
+    },
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction, system_program, sysvar,
+    transaction::{SanitizedTransaction, Transaction, VersionedTransaction},
+};
 use stubs::{
     account_committer_stub::AccountCommitterStub,
+    program_accounts_provider_stub::ProgramAccountsProviderStub,
     scheduled_commits_processor_stub::ScheduledCommitsProcessorStub,
 };
 use test_tools_core::init_logger;
@@ -34,6 +47,7 @@ type StubbedAccountsManager = ExternalAccountsManager<
     TransactionAccountsExtractorImpl,
     TransactionAccountsValidatorImpl,
     ScheduledCommitsProcessorStub,
+    ProgramAccountsProviderStub,
 >;
 
 fn setup_with_lifecycle(
@@ -55,8 +69,14 @@ fn setup_with_lifecycle(
         Some(1_000_000_000),
         ValidatorCollectionMode::NoFees,
         lifecycle.to_account_cloner_permissions(),
+        true,
+        true,
+        32,
+        None,
+        false,
         Pubkey::new_unique(),
         1024,
+        "test".to_string(),
     );
     let remote_account_cloner_client =
         RemoteAccountClonerClient::new(&remote_account_cloner_worker);
@@ -75,6 +95,7 @@ fn setup_with_lifecycle(
         transaction_accounts_extractor: TransactionAccountsExtractorImpl,
         transaction_accounts_validator: TransactionAccountsValidatorImpl,
         scheduled_commits_processor: ScheduledCommitsProcessorStub::default(),
+        program_accounts_provider: ProgramAccountsProviderStub::default(),
         lifecycle,
         external_commitable_accounts: Default::default(),
     };
@@ -928,3 +949,275 @@ async fn test_ensure_accounts_already_cloned_can_be_reused_without_updates() {
     cancel.cancel();
     assert!(handle.await.is_ok());
 }
+
+#[tokio::test]
+async fn test_ensure_address_lookup_tables_of_versioned_transaction() {
+    init_logger!();
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+
+    let (manager, cancel, handle) = setup_ephem(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+    );
+
+    // Lookup table exists on chain, but not in our validator
+    let lookup_table = Pubkey::new_unique();
+    account_updates.set_first_subscribed_slot(lookup_table, 41);
+    account_fetcher.set_undelegated_account(lookup_table, 42);
+
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 0,
+            },
+            account_keys: vec![Pubkey::new_unique()],
+            recent_blockhash: Hash::default(),
+            instructions: vec![],
+            address_table_lookups: vec![MessageAddressTableLookup {
+                account_key: lookup_table,
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            }],
+        }),
+    };
+
+    // Ensure lookup tables
+    let result = manager.ensure_address_lookup_tables(&transaction).await;
+    assert!(result.is_ok());
+
+    // Check proper behaviour
+    assert!(account_dumper.was_dumped_as_undelegated_account(&lookup_table));
+
+    // Cleanup
+    cancel.cancel();
+    assert!(handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_ensure_accounts_of_transaction_creating_lookup_table() {
+    init_logger!();
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+
+    let (manager, cancel, handle) = setup_ephem(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+    );
+
+    let authority = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let (create_ix, lookup_table) =
+        address_lookup_table::instruction::create_lookup_table(
+            authority, payer, 0,
+        );
+    account_updates.set_first_subscribed_slot(payer, 41);
+    account_fetcher.set_feepayer_account(payer, 42);
+    for readonly in [
+        authority,
+        system_program::id(),
+        address_lookup_table::program::id(),
+    ] {
+        account_updates.set_first_subscribed_slot(readonly, 41);
+        account_fetcher.set_undelegated_account(readonly, 42);
+    }
+
+    let transaction = SanitizedTransaction::try_from_legacy_transaction(
+        Transaction::new_unsigned(Message::new(&[create_ix], Some(&payer))),
+        &Default::default(),
+    )
+    .unwrap();
+
+    // Ensure accounts
+    let result = manager.ensure_accounts(&transaction).await;
+    assert!(result.is_ok());
+
+    // Check proper behaviour
+    assert!(account_dumper.was_untouched(&lookup_table));
+    assert!(account_dumper.was_dumped_as_feepayer_account(&payer));
+
+    // Cleanup
+    cancel.cancel();
+    assert!(handle.await.is_ok());
+}
+
+fn durable_nonce_transfer(
+    payer: Pubkey,
+    nonce: Pubkey,
+    account_fetcher: &AccountFetcherStub,
+    account_updates: &AccountUpdatesStub,
+) -> SanitizedTransaction {
+    // Only the payer and the nonce account are writable
+    let transfer_ix = system_instruction::transfer(&payer, &payer, 0);
+    let message = Message::new_with_nonce(
+        vec![transfer_ix],
+        Some(&payer),
+        &nonce,
+        &payer,
+    );
+    account_updates.set_first_subscribed_slot(payer, 41);
+    account_fetcher.set_feepayer_account(payer, 42);
+    for readonly in [system_program::id(), sysvar::recent_blockhashes::id()] {
+        account_updates.set_first_subscribed_slot(readonly, 41);
+        account_fetcher.set_undelegated_account(readonly, 42);
+    }
+    SanitizedTransaction::try_from_legacy_transaction(
+        Transaction::new_unsigned(message),
+        &Default::default(),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_ensure_accounts_of_durable_nonce_transaction_with_local_nonce() {
+    init_logger!();
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+
+    let (manager, cancel, handle) = setup_ephem(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+    );
+
+    // Nonce account created on the ephemeral
+    let nonce = Pubkey::new_unique();
+    internal_account_provider.set(nonce, Default::default());
+    let payer = Pubkey::new_unique();
+    let transaction = durable_nonce_transfer(
+        payer,
+        nonce,
+        &account_fetcher,
+        &account_updates,
+    );
+
+    // Ensure accounts
+    let result = manager.ensure_accounts(&transaction).await;
+    assert!(result.is_ok());
+
+    // Check proper behaviour
+    assert!(account_dumper.was_untouched(&nonce));
+    assert!(account_dumper.was_dumped_as_feepayer_account(&payer));
+
+    // Cleanup
+    cancel.cancel();
+    assert!(handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_ensure_accounts_of_durable_nonce_transaction_with_chain_nonce() {
+    init_logger!();
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+
+    let (manager, cancel, handle) = setup_ephem(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+    );
+
+    // Nonce account of the base layer, which can't be delegated
+    let nonce = Pubkey::new_unique();
+    account_updates.set_first_subscribed_slot(nonce, 41);
+    account_fetcher.set_undelegated_account(nonce, 42);
+    let transaction = durable_nonce_transfer(
+        Pubkey::new_unique(),
+        nonce,
+        &account_fetcher,
+        &account_updates,
+    );
+
+    // Ensure accounts
+    let result = manager.ensure_accounts(&transaction).await;
+
+    // Check proper behaviour
+    assert!(matches!(
+        result,
+        Err(AccountsError::NonceAccountNotLocalToEphemeral(pubkey)) if pubkey == nonce
+    ));
+
+    // Cleanup
+    cancel.cancel();
+    assert!(handle.await.is_ok());
+}
+
+#[tokio::test]
+async fn test_clone_program_accounts() {
+    init_logger!();
+    let internal_account_provider = InternalAccountProviderStub::default();
+    let account_fetcher = AccountFetcherStub::default();
+    let account_updates = AccountUpdatesStub::default();
+    let account_dumper = AccountDumperStub::default();
+
+    let (manager, cancel, handle) = setup_ephem(
+        internal_account_provider.clone(),
+        account_fetcher.clone(),
+        account_updates.clone(),
+        account_dumper.clone(),
+    );
+
+    // The program owns three accounts on chain, one of which cannot be fetched
+    let program_id = Pubkey::new_unique();
+    let undelegated_account = Pubkey::new_unique();
+    let delegated_account = Pubkey::new_unique();
+    let unfetchable_account = Pubkey::new_unique();
+    manager.program_accounts_provider.set(
+        &program_id,
+        vec![undelegated_account, delegated_account, unfetchable_account],
+    );
+    account_updates.set_first_subscribed_slot(undelegated_account, 41);
+    account_updates.set_first_subscribed_slot(delegated_account, 41);
+    account_updates.set_first_subscribed_slot(unfetchable_account, 41);
+    account_fetcher.set_undelegated_account(undelegated_account, 42);
+    account_fetcher.set_delegated_account(delegated_account, 42, 11);
+
+    // Clone program accounts
+    let result = manager.clone_program_accounts(&program_id, vec![]).await;
+
+    // Check proper behaviour
+    assert_eq!(
+        result.unwrap(),
+        ClonedProgramAccounts {
+            total: 3,
+            cloned: 2,
+            unclonable: 0,
+            failed: 1,
+        }
+    );
+    assert!(
+        account_dumper.was_dumped_as_undelegated_account(&undelegated_account)
+    );
+    assert!(account_dumper.was_dumped_as_delegated_account(&delegated_account));
+    assert!(account_dumper.was_untouched(&unfetchable_account));
+    assert!(manager.last_commit(&delegated_account).is_some());
+
+    // Unknown programs fail as a whole
+    let result = manager
+        .clone_program_accounts(&Pubkey::new_unique(), vec![])
+        .await;
+    assert!(matches!(
+        result,
+        Err(AccountsError::FailedToGetProgramAccounts(_))
+    ));
+
+    // Cleanup
+    cancel.cancel();
+    assert!(handle.await.is_ok());
+}
@@ -39,10 +39,13 @@ impl AccountCommitterStub {
 
 #[async_trait]
 impl AccountCommitter for AccountCommitterStub {
-    async fn create_commit_accounts_transaction(
+    async fn create_commit_accounts_transactions(
         &self,
         committees: Vec<AccountCommittee>,
-    ) -> AccountsResult<CommitAccountsPayload> {
+    ) -> AccountsResult<Vec<CommitAccountsPayload>> {
+        if committees.is_empty() {
+            return Ok(vec![]);
+        }
         let transaction = Transaction::default();
         let payload = CommitAccountsPayload {
             transaction: Some(CommitAccountsTransaction {
@@ -55,7 +58,14 @@ impl AccountCommitter for AccountCommitterStub {
                 .map(|x| (x.pubkey, x.account_data.clone()))
                 .collect(),
         };
-        Ok(payload)
+        Ok(vec![payload])
+    }
+
+    async fn simulate_commit_transactions(
+        &self,
+        payloads: &[SendableCommitAccountsPayload],
+    ) -> Vec<Option<String>> {
+        vec![None; payloads.len()]
     }
 
     async fn send_commit_transactions(
@@ -64,11 +74,15 @@ impl AccountCommitter for AccountCommitterStub {
     ) -> AccountsResult<Vec<PendingCommitTransaction>> {
         let signatures = payloads
             .iter()
-            .map(|_| PendingCommitTransaction {
-                signature: Signature::new_unique(),
-                undelegated_accounts: HashSet::new(),
-                committed_only_accounts: HashSet::new(),
-                timer: metrics::account_commit_start(),
+            .map(|_| {
+                let signature = Signature::new_unique();
+                PendingCommitTransaction {
+                    signature,
+                    initial_signature: signature,
+                    undelegated_accounts: HashSet::new(),
+                    committed_only_accounts: HashSet::new(),
+                    timer: metrics::account_commit_start(),
+                }
             })
             .collect();
         for payload in payloads {
@@ -85,12 +99,22 @@ impl AccountCommitter for AccountCommitterStub {
     async fn confirm_pending_commits(
         &self,
         pending_commits: Vec<PendingCommitTransaction>,
-    ) {
+    ) -> HashSet<Signature> {
+        let mut confirmed = HashSet::new();
         for commit in pending_commits {
             self.confirmed_transactions
                 .write()
                 .unwrap()
                 .insert(commit.signature);
+            confirmed.insert(commit.signature);
         }
+        confirmed
+    }
+
+    async fn finalize_confirmed_commits(
+        &self,
+        signatures: Vec<Signature>,
+    ) -> HashSet<Signature> {
+        signatures.into_iter().collect()
     }
 }
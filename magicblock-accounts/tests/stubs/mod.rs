@@ -1,2 +1,3 @@
 pub mod account_committer_stub;
+pub mod program_accounts_provider_stub;
 pub mod scheduled_commits_processor_stub;
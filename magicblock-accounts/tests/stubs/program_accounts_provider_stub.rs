@@ -0,0 +1,48 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use magicblock_accounts::{
+    errors::{AccountsError, AccountsResult},
+    ProgramAccountsProvider,
+};
+use solana_rpc_client_api::filter::RpcFilterType;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Default, Clone)]
+pub struct ProgramAccountsProviderStub {
+    program_accounts: Arc<RwLock<HashMap<Pubkey, Vec<Pubkey>>>>,
+}
+
+#[allow(unused)] // used in tests
+impl ProgramAccountsProviderStub {
+    pub fn set(&self, program_id: &Pubkey, pubkeys: Vec<Pubkey>) {
+        self.program_accounts
+            .write()
+            .unwrap()
+            .insert(*program_id, pubkeys);
+    }
+}
+
+#[async_trait]
+impl ProgramAccountsProvider for ProgramAccountsProviderStub {
+    async fn get_program_account_keys(
+        &self,
+        program_id: &Pubkey,
+        _filters: Vec<RpcFilterType>,
+    ) -> AccountsResult<Vec<Pubkey>> {
+        self.program_accounts
+            .read()
+            .unwrap()
+            .get(program_id)
+            .cloned()
+            .ok_or_else(|| {
+                AccountsError::FailedToGetProgramAccounts(format!(
+                    "Program not set in ProgramAccountsProviderStub: {}",
+                    program_id
+                ))
+            })
+    }
+}
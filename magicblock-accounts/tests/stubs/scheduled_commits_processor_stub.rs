@@ -21,5 +21,14 @@ impl ScheduledCommitsProcessor for ScheduledCommitsProcessorStub {
     fn scheduled_commits_len(&self) -> usize {
         0
     }
+    fn pending_commits_len(&self) -> usize {
+        0
+    }
+    fn unresolved_commits_len(&self) -> usize {
+        0
+    }
+    fn failed_commits_len(&self) -> usize {
+        0
+    }
     fn clear_scheduled_commits(&self) {}
 }
@@ -0,0 +1,332 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    io::{self, Write},
+    panic,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, Once, PoisonError, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use log::{error, Log, Metadata, Record};
+use magicblock_bank::bank::Bank;
+use magicblock_config::EphemeralConfig;
+use magicblock_core::{
+    magic_program::MAGIC_CONTEXT_PUBKEY, traits::ProvidesRemoteSyncStatus,
+};
+use magicblock_program::{MagicContext, ScheduledCommit, TransactionScheduler};
+use serde_json::{json, Value};
+use solana_sdk::account::ReadableAccount;
+
+/// The number of log lines included in a diagnostics bundle
+const MAX_RECENT_LOG_LINES: usize = 1_000;
+
+static RECENT_LOG_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static CONTEXT: RwLock<Option<DiagnosticsContext>> = RwLock::new(None);
+static PANIC_HOOK: Once = Once::new();
+
+/// State of the validator included in a diagnostics bundle
+struct DiagnosticsContext {
+    /// The directory bundles are written to
+    dir: PathBuf,
+    bank: Arc<Bank>,
+    remote_sync_status: Arc<dyn ProvidesRemoteSyncStatus>,
+    /// Snapshot of the config with secrets redacted
+    config: String,
+}
+
+// -----------------
+// Recent Logs
+// -----------------
+/// Wraps the logger of the validator in order to keep the most recent log
+/// lines around to include them in a diagnostics bundle
+pub struct RecordingLogger<L> {
+    inner: L,
+}
+
+impl<L: Log> RecordingLogger<L> {
+    pub fn new(inner: L) -> Self {
+        Self { inner }
+    }
+}
+
+impl<L: Log> Log for RecordingLogger<L> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.inner.enabled(record.metadata()) {
+            return;
+        }
+        // Formatted before taking the lock, so a panic while formatting
+        // doesn't leave it locked for the panic hook
+        let line = format!(
+            "{} {} {}: {}",
+            unix_timestamp_millis(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        {
+            let mut lines = RECENT_LOG_LINES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if lines.len() >= MAX_RECENT_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(line);
+        }
+        self.inner.log(record);
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+// -----------------
+// Panic Hook
+// -----------------
+/// Registers the state included in diagnostics bundles and installs a panic
+/// hook that writes one to the ledger directory before the default hook runs
+pub(crate) fn init(
+    ledger_dir: &Path,
+    bank: Arc<Bank>,
+    remote_sync_status: Arc<dyn ProvidesRemoteSyncStatus>,
+    config: &EphemeralConfig,
+) {
+    *CONTEXT.write().unwrap_or_else(PoisonError::into_inner) =
+        Some(DiagnosticsContext {
+            dir: ledger_dir.to_path_buf(),
+            bank,
+            remote_sync_status,
+            config: config.to_redacted_string(),
+        });
+
+    PANIC_HOOK.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            // Not logged since the logger could be what panicked
+            match write_diagnostics(&format!("panic: {info}")) {
+                Ok(Some(path)) => {
+                    eprintln!("Wrote diagnostics to '{}'", path.display())
+                }
+                Ok(None) => {}
+                Err(err) => eprintln!("Failed to write diagnostics: {err}"),
+            }
+            default_hook(info);
+        }));
+    });
+}
+
+/// Writes a diagnostics bundle and aborts the process, used when the
+/// validator cannot continue running
+pub fn abort_with_diagnostics(reason: &str) -> ! {
+    error!("Fatal error: {reason}");
+    match write_diagnostics(reason) {
+        Ok(Some(path)) => {
+            error!("Wrote diagnostics to '{}'", path.display())
+        }
+        Ok(None) => {}
+        Err(err) => error!("Failed to write diagnostics: {err}"),
+    }
+    log::logger().flush();
+    std::process::abort();
+}
+
+/// Writes a diagnostics bundle to the ledger directory, returns its path or
+/// `None` if the validator wasn't initialized yet
+pub fn write_diagnostics(reason: &str) -> io::Result<Option<PathBuf>> {
+    let context = CONTEXT.read().unwrap_or_else(PoisonError::into_inner);
+    let Some(context) = context.as_ref() else {
+        return Ok(None);
+    };
+
+    let timestamp = unix_timestamp_millis();
+    let recent_logs = RECENT_LOG_LINES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+    let bundle = json!({
+        "reason": reason,
+        "timestamp": timestamp,
+        "version": env!("CARGO_PKG_VERSION"),
+        "slot": context.bank.slot(),
+        "accountsdb": {
+            "accounts_count": context.bank.accounts_db.get_accounts_count(),
+            "storage_size": context.bank.accounts_db_storage_size(),
+        },
+        "remote_sync_status": context.remote_sync_status.remote_sync_status(),
+        "scheduled_commits": scheduled_commits(&context.bank),
+        "config": context.config,
+        "recent_logs": recent_logs,
+    });
+
+    let path = context.dir.join(format!("diagnostics-{timestamp}.json"));
+    let mut file = fs::File::create(&path)?;
+    serde_json::to_writer_pretty(&mut file, &bundle)?;
+    file.write_all(b"\n")?;
+    file.sync_all()?;
+    Ok(Some(path))
+}
+
+/// The commits still waiting in the MagicContext to be accepted and the
+/// accepted ones that were not sent to the base layer yet
+fn scheduled_commits(bank: &Bank) -> Value {
+    let not_accepted = bank
+        .get_account(&MAGIC_CONTEXT_PUBKEY)
+        .and_then(|account| MagicContext::try_from_data(account.data()).ok())
+        .map(|context| context.scheduled_commits)
+        .unwrap_or_default();
+    let accepted = TransactionScheduler::default().get_scheduled_commits();
+    json!({
+        "not_accepted": not_accepted
+            .iter()
+            .map(scheduled_commit)
+            .collect::<Vec<_>>(),
+        "accepted": accepted.iter().map(scheduled_commit).collect::<Vec<_>>(),
+    })
+}
+
+fn scheduled_commit(commit: &ScheduledCommit) -> Value {
+    json!({
+        "id": commit.id,
+        "slot": commit.slot,
+        "payer": commit.payer.to_string(),
+        "accounts": commit
+            .accounts
+            .iter()
+            .map(|account| account.pubkey.to_string())
+            .collect::<Vec<_>>(),
+        "request_undelegation": commit.request_undelegation,
+        "execute_at_slot": commit.execute_at_slot,
+        "priority": format!("{:?}", commit.priority),
+    })
+}
+
+fn unix_timestamp_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use magicblock_core::remote_sync_status::RemoteSyncStatus;
+    use magicblock_program::{
+        magicblock_instruction::CommitPriority, CommittedAccount,
+    };
+    use solana_sdk::{
+        genesis_config::create_genesis_config, hash::Hash, pubkey::Pubkey,
+        transaction::Transaction,
+    };
+
+    use super::*;
+
+    struct StaticRemoteSyncStatus;
+
+    impl ProvidesRemoteSyncStatus for StaticRemoteSyncStatus {
+        fn remote_sync_status(&self) -> RemoteSyncStatus {
+            RemoteSyncStatus {
+                remote_slot: Some(42),
+                scheduled_commits: 1,
+                ..Default::default()
+            }
+        }
+    }
+
+    struct NoopLogger;
+
+    impl Log for NoopLogger {
+        fn enabled(&self, _metadata: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, _record: &Record) {}
+
+        fn flush(&self) {}
+    }
+
+    fn scheduled_commit(id: u64) -> ScheduledCommit {
+        ScheduledCommit {
+            id,
+            slot: 7,
+            blockhash: Hash::default(),
+            accounts: vec![CommittedAccount {
+                pubkey: Pubkey::new_unique(),
+                owner: Pubkey::new_unique(),
+            }],
+            payer: Pubkey::new_unique(),
+            commit_sent_transaction: Transaction::default(),
+            request_undelegation: true,
+            execute_at_slot: None,
+            base_layer_action: None,
+            compute_unit_price: None,
+            close_accounts: false,
+            transfer_intent: None,
+            atomic: false,
+            memo: None,
+            priority: CommitPriority::High,
+        }
+    }
+
+    #[test]
+    fn test_write_diagnostics_bundle() {
+        let dir = tempfile::tempdir().unwrap();
+        let (genesis_config, _) = create_genesis_config(u64::MAX);
+        let bank =
+            Arc::new(Bank::new_for_tests(&genesis_config, None, None).unwrap());
+        init(
+            dir.path(),
+            bank.clone(),
+            Arc::new(StaticRemoteSyncStatus),
+            &EphemeralConfig::default(),
+        );
+        RecordingLogger::new(NoopLogger).log(
+            &Record::builder()
+                .args(format_args!("about to fail"))
+                .level(log::Level::Error)
+                .target("diagnostics")
+                .build(),
+        );
+        let commit = scheduled_commit(1234);
+        TransactionScheduler::default()
+            .accept_scheduled_commits(vec![commit.clone()]);
+
+        let path = write_diagnostics("test failure").unwrap().unwrap();
+        assert!(path.starts_with(dir.path()));
+        let bundle: Value =
+            serde_json::from_reader(fs::File::open(&path).unwrap()).unwrap();
+
+        assert_eq!(bundle["reason"], "test failure");
+        assert_eq!(bundle["slot"], bank.slot());
+        assert_eq!(bundle["remote_sync_status"]["remote_slot"], 42);
+        let accepted =
+            bundle["scheduled_commits"]["accepted"].as_array().unwrap();
+        let accepted = accepted
+            .iter()
+            .find(|accepted| accepted["id"] == commit.id)
+            .expect("accepted commit should be included");
+        assert_eq!(accepted["payer"], commit.payer.to_string());
+        assert_eq!(
+            accepted["accounts"][0],
+            commit.accounts[0].pubkey.to_string()
+        );
+        assert_eq!(accepted["request_undelegation"], true);
+        assert_eq!(accepted["priority"], "High");
+        assert!(bundle["recent_logs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|line| line
+                .as_str()
+                .unwrap()
+                .ends_with("ERROR diagnostics: about to fail")));
+
+        TransactionScheduler::default().remove_scheduled_commit(commit.id);
+    }
+}
@@ -0,0 +1,410 @@
+use std::{
+    collections::HashSet, ffi::CString, io, mem::MaybeUninit,
+    os::unix::ffi::OsStrExt, path::Path, sync::Arc, time::Duration,
+};
+
+use log::*;
+use magicblock_bank::bank::Bank;
+use magicblock_config::DiskConfig;
+use magicblock_ledger::{ledger_truncator::EmergencyTruncation, Ledger};
+use magicblock_metrics::{health, metrics};
+use magicblock_program::validator;
+use tokio_util::sync::CancellationToken;
+
+const LEDGER: &str = "ledger";
+const ACCOUNTSDB: &str = "accountsdb";
+const SNAPSHOTS: &str = "snapshots";
+const DISK_HEALTH: &str = "disk";
+
+/// Usage above this percentage of a budget is alerted on
+const BUDGET_WARNING_PERCENTAGE: u64 = 90;
+/// Snapshots kept when pruning them, the ledger is replayed from the
+/// oldest one on restart
+const SNAPSHOTS_KEPT_WHEN_PRUNING: usize = 1;
+
+struct DiskUsage {
+    ledger: u64,
+    accountsdb: u64,
+    snapshots: u64,
+    /// Free space on the disk of the ledger, if it could be determined
+    free: Option<u64>,
+}
+
+/// Emergency actions to take for the current disk usage
+#[derive(Debug, Default, PartialEq, Eq)]
+struct EmergencyActions {
+    prune_snapshots: bool,
+    truncate_ledger: bool,
+    read_only: bool,
+}
+
+struct DiskBudgets {
+    config: DiskConfig,
+    /// Components currently near or above their budget, alerts are only
+    /// logged once they cross it
+    alerted: HashSet<&'static str>,
+}
+
+struct DiskWatcher {
+    budgets: DiskBudgets,
+    ledger: Arc<Ledger>,
+    bank: Arc<Bank>,
+    emergency_truncation: EmergencyTruncation,
+}
+
+/// Checks the disk usage of the validator against the configured budgets
+/// and takes emergency actions as it is running out of disk space:
+/// pruning accountsdb snapshots, then truncating the ledger and finally
+/// switching to read-only mode.
+pub fn init_disk_watcher(
+    config: &DiskConfig,
+    ledger: &Arc<Ledger>,
+    bank: &Arc<Bank>,
+    emergency_truncation: EmergencyTruncation,
+    token: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    for (component, budget) in [
+        (LEDGER, config.ledger_budget_bytes),
+        (ACCOUNTSDB, config.accountsdb_budget_bytes),
+        (SNAPSHOTS, config.snapshots_budget_bytes),
+    ] {
+        if let Some(budget) = budget {
+            metrics::set_disk_budget(component, budget);
+        }
+    }
+
+    let mut watcher = DiskWatcher {
+        budgets: DiskBudgets {
+            config: config.clone(),
+            alerted: HashSet::new(),
+        },
+        ledger: ledger.clone(),
+        bank: bank.clone(),
+        emergency_truncation,
+    };
+    let check_interval = Duration::from_millis(config.check_interval_millis);
+    tokio::task::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(check_interval) => {
+                    // Measuring the usage walks the storage directories and
+                    // pruning removes snapshots, neither may block the runtime
+                    let checked = tokio::task::spawn_blocking(move || {
+                        watcher.check();
+                        watcher
+                    })
+                    .await;
+                    match checked {
+                        Ok(checked) => watcher = checked,
+                        Err(err) => {
+                            error!("Disk watcher stopped: {:?}", err);
+                            break;
+                        }
+                    }
+                },
+                _ = token.cancelled() => {
+                    break;
+                }
+            }
+        }
+    })
+}
+
+impl DiskWatcher {
+    fn check(&mut self) {
+        let usage = self.usage();
+        metrics::set_disk_usage(LEDGER, usage.ledger);
+        metrics::set_disk_usage(ACCOUNTSDB, usage.accountsdb);
+        metrics::set_disk_usage(SNAPSHOTS, usage.snapshots);
+        if let Some(free) = usage.free {
+            metrics::set_disk_free(free);
+        }
+
+        let EmergencyActions {
+            prune_snapshots,
+            truncate_ledger,
+            read_only,
+        } = self.budgets.emergency_actions(&usage);
+
+        // Pruning snapshots first allows the truncation to remove all slots
+        // before the remaining one
+        if prune_snapshots {
+            let pruned = self
+                .bank
+                .accounts_db
+                .prune_snapshots(SNAPSHOTS_KEPT_WHEN_PRUNING);
+            if pruned > 0 {
+                warn!("Pruned {pruned} accountsdb snapshots to free up disk space");
+                metrics::inc_disk_emergency_actions("snapshot_pruning");
+            }
+        }
+        if truncate_ledger {
+            warn!("Truncating the ledger to free up disk space");
+            self.emergency_truncation.trigger();
+            metrics::inc_disk_emergency_actions("ledger_truncation");
+        }
+
+        if validator::set_read_only(read_only) {
+            if read_only {
+                error!("Running out of disk space, switching to read-only mode and rejecting transactions");
+                metrics::inc_disk_emergency_actions("read_only");
+            } else {
+                info!("Enough disk space was freed up, leaving read-only mode");
+            }
+        }
+        metrics::set_read_only(read_only);
+        health::set_readiness(
+            DISK_HEALTH,
+            if read_only {
+                Err("read-only since the disk is running full".to_string())
+            } else {
+                Ok(())
+            },
+        );
+    }
+
+    fn usage(&self) -> DiskUsage {
+        let ledger = self.ledger.storage_size().unwrap_or_else(|err| {
+            warn!("Failed to get ledger storage size: {:?}", err);
+            0
+        });
+        let free = available_space(self.ledger.ledger_path())
+            .inspect_err(|err| {
+                warn!("Failed to get available disk space: {:?}", err)
+            })
+            .ok();
+        DiskUsage {
+            ledger,
+            accountsdb: self.bank.accounts_db_storage_size(),
+            snapshots: self.bank.accounts_db.snapshots_storage_size(),
+            free,
+        }
+    }
+}
+
+impl DiskBudgets {
+    fn emergency_actions(&mut self, usage: &DiskUsage) -> EmergencyActions {
+        let ledger_over_budget = self.over_budget(
+            LEDGER,
+            usage.ledger,
+            self.config.ledger_budget_bytes,
+        );
+        let accountsdb_over_budget = self.over_budget(
+            ACCOUNTSDB,
+            usage.accountsdb,
+            self.config.accountsdb_budget_bytes,
+        );
+        let snapshots_over_budget = self.over_budget(
+            SNAPSHOTS,
+            usage.snapshots,
+            self.config.snapshots_budget_bytes,
+        );
+        let below_free = |threshold: Option<u64>| {
+            usage
+                .free
+                .zip(threshold)
+                .is_some_and(|(free, threshold)| free < threshold)
+        };
+        let low_on_space = below_free(self.config.min_free_bytes);
+
+        EmergencyActions {
+            prune_snapshots: snapshots_over_budget || low_on_space,
+            truncate_ledger: ledger_over_budget || low_on_space,
+            read_only: accountsdb_over_budget
+                || below_free(self.config.read_only_free_bytes),
+        }
+    }
+
+    /// Returns `true` if the component exceeds its budget, alerts once it
+    /// gets close to it
+    fn over_budget(
+        &mut self,
+        component: &'static str,
+        usage: u64,
+        budget: Option<u64>,
+    ) -> bool {
+        let Some(budget) = budget else {
+            return false;
+        };
+        let warning_level = budget / 100 * BUDGET_WARNING_PERCENTAGE;
+        if usage < warning_level {
+            self.alerted.remove(component);
+        } else if self.alerted.insert(component) {
+            warn!(
+                "Disk usage of the {component} is {usage} bytes, close to its budget of {budget} bytes"
+            );
+        }
+        usage > budget
+    }
+}
+
+/// Returns the bytes available to the validator on the file system
+/// containing the path
+fn available_space(path: &Path) -> io::Result<u64> {
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: the path is a valid nul terminated string and the stats are
+    // only read if they were filled in
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        stat.assume_init()
+    };
+    Ok(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GB: u64 = 1024 * 1024 * 1024;
+
+    fn budgets() -> DiskBudgets {
+        DiskBudgets {
+            config: DiskConfig {
+                ledger_budget_bytes: Some(10 * GB),
+                accountsdb_budget_bytes: Some(20 * GB),
+                snapshots_budget_bytes: Some(30 * GB),
+                min_free_bytes: Some(5 * GB),
+                read_only_free_bytes: Some(GB),
+                ..Default::default()
+            },
+            alerted: HashSet::new(),
+        }
+    }
+
+    fn usage() -> DiskUsage {
+        DiskUsage {
+            ledger: 5 * GB,
+            accountsdb: 5 * GB,
+            snapshots: 5 * GB,
+            free: Some(100 * GB),
+        }
+    }
+
+    #[test]
+    fn test_no_emergency_actions_within_budgets() {
+        let mut budgets = budgets();
+        let usage = DiskUsage {
+            // Exactly at the budget is still within it
+            ledger: 10 * GB,
+            free: Some(5 * GB),
+            ..usage()
+        };
+        assert_eq!(
+            budgets.emergency_actions(&usage),
+            EmergencyActions::default()
+        );
+        assert!(budgets.alerted.contains(LEDGER));
+    }
+
+    #[test]
+    fn test_no_emergency_actions_without_budgets() {
+        let mut budgets = DiskBudgets {
+            config: DiskConfig::default(),
+            alerted: HashSet::new(),
+        };
+        let usage = DiskUsage {
+            ledger: u64::MAX,
+            accountsdb: u64::MAX,
+            snapshots: u64::MAX,
+            free: Some(0),
+        };
+        assert_eq!(
+            budgets.emergency_actions(&usage),
+            EmergencyActions::default()
+        );
+        assert!(budgets.alerted.is_empty());
+    }
+
+    #[test]
+    fn test_snapshots_over_budget_prunes_them() {
+        let usage = DiskUsage {
+            snapshots: 30 * GB + 1,
+            ..usage()
+        };
+        assert_eq!(
+            budgets().emergency_actions(&usage),
+            EmergencyActions {
+                prune_snapshots: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_ledger_over_budget_truncates_it() {
+        let usage = DiskUsage {
+            ledger: 10 * GB + 1,
+            ..usage()
+        };
+        assert_eq!(
+            budgets().emergency_actions(&usage),
+            EmergencyActions {
+                truncate_ledger: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_accountsdb_over_budget_switches_to_read_only() {
+        let usage = DiskUsage {
+            accountsdb: 20 * GB + 1,
+            ..usage()
+        };
+        assert_eq!(
+            budgets().emergency_actions(&usage),
+            EmergencyActions {
+                read_only: true,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_low_free_space_prunes_and_truncates() {
+        let usage = DiskUsage {
+            free: Some(5 * GB - 1),
+            ..usage()
+        };
+        assert_eq!(
+            budgets().emergency_actions(&usage),
+            EmergencyActions {
+                prune_snapshots: true,
+                truncate_ledger: true,
+                read_only: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_very_low_free_space_switches_to_read_only() {
+        let usage = DiskUsage {
+            free: Some(GB - 1),
+            ..usage()
+        };
+        assert_eq!(
+            budgets().emergency_actions(&usage),
+            EmergencyActions {
+                prune_snapshots: true,
+                truncate_ledger: true,
+                read_only: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_free_space_triggers_no_actions() {
+        let usage = DiskUsage {
+            free: None,
+            ..usage()
+        };
+        assert_eq!(
+            budgets().emergency_actions(&usage),
+            EmergencyActions::default()
+        );
+    }
+}
@@ -23,6 +23,9 @@ pub enum ApiError {
     #[error("AccountCloner error: {0}")]
     AccountClonerError(#[from] magicblock_account_cloner::AccountClonerError),
 
+    #[error("AccountFetcher error: {0}")]
+    AccountFetcherError(#[from] magicblock_account_fetcher::AccountFetcherError),
+
     #[error("Ledger error: {0}")]
     LedgerError(#[from] magicblock_ledger::errors::LedgerError),
 
@@ -47,6 +50,9 @@ pub enum ApiError {
     #[error("Failed to start metrics service: {0}")]
     FailedToStartMetricsService(std::io::Error),
 
+    #[error("Failed to initialize tracing: {0}")]
+    FailedToInitTracing(String),
+
     #[error("Ledger Path is missing a parent directory: {0}")]
     LedgerPathIsMissingParent(String),
 
@@ -68,9 +74,18 @@ pub enum ApiError {
     #[error("Ledger could not write validator keypair file: {0} ({1})")]
     LedgerCouldNotWriteValidatorKeypair(String, String),
 
+    #[error("Invalid treasury keypair file: {0} ({1})")]
+    InvalidTreasuryKeypair(String, String),
+
     #[error("Ledger validator keypair '{0}' needs to match the provided one '{1}'")]
     LedgerValidatorKeypairNotMatchingProvidedKeypair(String, String),
 
+    #[error("Additional builtin '{0}' uses the program id {1} which is already taken")]
+    AdditionalBuiltinProgramIdTaken(String, String),
+
+    #[error("Ledger export to {0} requires the validator to be built with the '{1}' feature")]
+    LedgerExportSinkNotEnabled(String, String),
+
     #[error("The slot at which we should continue after processing the ledger ({0}) does not match the bank slot ({1})"
     )]
     NextSlotAfterLedgerProcessingNotMatchingBankSlot(u64, u64),
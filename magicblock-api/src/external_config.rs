@@ -1,7 +1,14 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, time::Duration};
 
-use magicblock_accounts::{AccountsConfig, Cluster, LifecycleMode};
+use magicblock_accounts::{
+    AccountsConfig, Cluster, CommitWebhookConfig, DurableNonceConfig,
+    JitoConfig, LifecycleMode, PriorityFeeConfig,
+};
 use magicblock_config::errors::ConfigResult;
+use rate_limiter::RateLimiterConfig;
+use solana_rpc_client_api::filter::{
+    Memcmp, MemcmpEncodedBytes, RpcFilterType,
+};
 use solana_sdk::{genesis_config::ClusterType, pubkey::Pubkey};
 
 pub(crate) fn try_convert_accounts_config(
@@ -9,8 +16,46 @@ pub(crate) fn try_convert_accounts_config(
 ) -> ConfigResult<AccountsConfig> {
     Ok(AccountsConfig {
         remote_cluster: cluster_from_remote(&conf.remote),
+        commit_remote_cluster: conf
+            .commit
+            .remote
+            .as_ref()
+            .map(cluster_from_remote),
+        commit_fallback_remotes: conf.commit.fallback_remotes.clone(),
         lifecycle: lifecycle_mode_from_lifecycle_mode(&conf.lifecycle),
         commit_compute_unit_price: conf.commit.compute_unit_price,
+        commit_changed_only: conf.commit.changed_only,
+        commit_simulate: conf.commit.simulate,
+        commit_priority_fee: conf.commit.priority_fee.as_ref().map(
+            |priority_fee| PriorityFeeConfig {
+                min_compute_unit_price: priority_fee.min_compute_unit_price,
+                max_compute_unit_price: priority_fee.max_compute_unit_price,
+                percentile: priority_fee.percentile,
+            },
+        ),
+        commit_max_requested_compute_unit_price: conf
+            .commit
+            .max_requested_compute_unit_price,
+        commit_durable_nonce: conf.commit.durable_nonce.as_ref().map(
+            |durable_nonce| DurableNonceConfig {
+                account: durable_nonce.account,
+                retry_interval: Duration::from_millis(
+                    durable_nonce.retry_interval_millis,
+                ),
+                max_retries: durable_nonce.max_retries,
+            },
+        ),
+        commit_jito: conf.commit.try_jito()?.map(|jito| JitoConfig {
+            url: jito.url.clone(),
+            tip_lamports: jito.tip_lamports,
+        }),
+        commit_webhook: conf.commit.webhook.as_ref().map(|webhook| {
+            CommitWebhookConfig {
+                url: webhook.url.clone(),
+                auth_header: webhook.auth_header.clone(),
+                max_retries: webhook.max_retries,
+            }
+        }),
         payer_init_lamports: conf.payer.try_init_lamports()?,
         allowed_program_ids: allowed_program_ids_from_allowed_programs(
             &conf.allowed_programs,
@@ -37,6 +82,35 @@ pub(crate) fn cluster_from_remote(
     }
 }
 
+pub(crate) fn rate_limiter_config_for_remote(
+    conf: &magicblock_config::RateLimitConfig,
+    url: &str,
+) -> RateLimiterConfig {
+    let conf = conf.for_remote(url);
+    RateLimiterConfig {
+        requests_per_second: conf.requests_per_second,
+        burst: conf.burst,
+        initial_backoff: Duration::from_millis(conf.initial_backoff_millis),
+        max_backoff: Duration::from_millis(conf.max_backoff_millis),
+    }
+}
+
+pub(crate) fn rpc_filters_from_prefetch_program(
+    program: &magicblock_config::PrefetchProgram,
+) -> Vec<RpcFilterType> {
+    program
+        .data_size
+        .map(RpcFilterType::DataSize)
+        .into_iter()
+        .chain(program.memcmp.iter().map(|memcmp| {
+            RpcFilterType::Memcmp(Memcmp::new(
+                memcmp.offset,
+                MemcmpEncodedBytes::Base58(memcmp.bytes.clone()),
+            ))
+        }))
+        .collect()
+}
+
 fn lifecycle_mode_from_lifecycle_mode(
     clone: &magicblock_config::LifecycleMode,
 ) -> LifecycleMode {
@@ -71,10 +71,37 @@ pub(crate) fn funded_faucet(
 }
 
 pub(crate) fn fund_magic_context(bank: &Bank) {
+    // Leaves room for the fees charged for scheduling commits that the
+    // context holds until the validator authority claims them
     fund_account_with_data(
         bank,
         &magic_program::MAGIC_CONTEXT_PUBKEY,
-        u64::MAX,
+        u64::MAX / 2,
         vec![0; magic_program::MAGIC_CONTEXT_SIZE],
     );
 }
+
+pub(crate) fn fund_magic_commit_events(bank: &Bank) {
+    fund_account_with_data(
+        bank,
+        &magic_program::MAGIC_COMMIT_EVENTS_PUBKEY,
+        u64::MAX,
+        vec![0; magic_program::MAGIC_COMMIT_EVENTS_SIZE],
+    );
+}
+
+pub(crate) fn fund_magic_session_keys(bank: &Bank) {
+    // Keeps the session keys registered before the validator restarted
+    if bank
+        .get_account(&magic_program::MAGIC_SESSION_KEYS_PUBKEY)
+        .is_some()
+    {
+        return;
+    }
+    fund_account_with_data(
+        bank,
+        &magic_program::MAGIC_SESSION_KEYS_PUBKEY,
+        u64::MAX,
+        vec![0; magic_program::MAGIC_SESSION_KEYS_SIZE],
+    );
+}
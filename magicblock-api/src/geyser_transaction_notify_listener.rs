@@ -1,17 +1,28 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use crossbeam_channel::Receiver;
 use itertools::izip;
-use magicblock_bank::{bank::Bank, geyser::TransactionNotifier};
-use magicblock_ledger::Ledger;
+use log::*;
+use magicblock_bank::{
+    get_compute_budget_details::GetComputeBudgetDetails,
+    geyser::TransactionNotifier,
+};
+use magicblock_ledger::{Ledger, TransactionToWrite};
 use magicblock_metrics::metrics;
 use magicblock_transaction_status::{
-    extract_and_fmt_memos, map_inner_instructions, TransactionStatusBatch,
-    TransactionStatusMessage, TransactionStatusMeta,
+    extract_and_fmt_memos, map_inner_instructions, Reward,
+    TransactionStatusBatch, TransactionStatusMessage, TransactionStatusMeta,
 };
 use solana_rpc::transaction_notifier_interface::TransactionNotifier as _;
+use solana_sdk::{
+    clock::Slot, pubkey::Pubkey, transaction::SanitizedTransaction,
+};
 use solana_svm::transaction_commit_result::CommittedTransaction;
 
+/// Max number of transactions buffered before they are written to the ledger
+/// even though their slot didn't complete yet
+const MAX_BUFFERED_TRANSACTIONS: usize = 1_024;
+
 pub struct GeyserTransactionNotifyListener {
     transaction_notifier: Option<TransactionNotifier>,
     transaction_recvr: Receiver<TransactionStatusMessage>,
@@ -31,11 +42,7 @@ impl GeyserTransactionNotifyListener {
         }
     }
 
-    pub fn run(
-        &mut self,
-        enable_rpc_transaction_history: bool,
-        bank: Arc<Bank>,
-    ) {
+    pub fn run(&mut self, enable_rpc_transaction_history: bool) {
         let transaction_notifier = match self.transaction_notifier.take() {
             Some(notifier) => notifier,
             None => return,
@@ -44,6 +51,13 @@ impl GeyserTransactionNotifyListener {
         let ledger = self.ledger.clone();
         // TODO(thlorenz): need to be able to cancel this
         std::thread::spawn(move || {
+            // Transactions are written to the ledger in batches grouped by
+            // slot which are flushed once the slot completes
+            let mut buffered_slot = None;
+            let mut buffered_transactions = Vec::new();
+            // Spans of the buffered transactions, their `ledger_write` spans
+            // are only opened once the transactions are written
+            let mut buffered_spans = Vec::new();
             while let Ok(message) = transaction_recvr.recv() {
                 // Mostly from: rpc/src/transaction_status_service.rs
                 match message {
@@ -55,6 +69,7 @@ impl GeyserTransactionNotifyListener {
                             balances,
                             token_balances,
                             transaction_indexes,
+                            span,
                         },
                     ) => {
                         for (
@@ -81,15 +96,15 @@ impl GeyserTransactionNotifyListener {
                                     inner_instructions,
                                     return_data,
                                     executed_units,
+                                    loaded_account_stats,
+                                    fee_details,
+                                    rent_debits,
                                     ..
                                 } = details;
 
-                                let lamports_per_signature =
-                                    bank.get_lamports_per_signature();
-                                let fee = bank.get_fee_for_message_with_lamports_per_signature(
-                                    transaction.message(),
-                                    lamports_per_signature,
-                                );
+                                // Same as agave, the fee actually charged
+                                // including the prioritization fee
+                                let fee = fee_details.total_fee();
 
                                 let fee_payer = transaction
                                     .message()
@@ -100,6 +115,12 @@ impl GeyserTransactionNotifyListener {
                                     &fee_payer,
                                 );
                                 metrics::inc_executed_units(executed_units);
+                                record_compute_metering(
+                                    &transaction,
+                                    executed_units,
+                                    loaded_account_stats
+                                        .loaded_accounts_data_size,
+                                );
                                 metrics::inc_fee(fee);
 
                                 let inner_instructions = inner_instructions
@@ -113,8 +134,23 @@ impl GeyserTransactionNotifyListener {
                                     Some(pre_token_balances);
                                 let post_token_balances =
                                     Some(post_token_balances);
-                                // NOTE: we don't charge rent and rewards are based on rent_debits
-                                let rewards = None;
+                                // NOTE: we don't charge rent, but provide the
+                                // rewards like agave does for plugins decoding them
+                                let rewards = Some(
+                                    rent_debits
+                                        .into_unordered_rewards_iter()
+                                        .map(|(pubkey, reward_info)| Reward {
+                                            pubkey: pubkey.to_string(),
+                                            lamports: reward_info.lamports,
+                                            post_balance: reward_info
+                                                .post_balance,
+                                            reward_type: Some(
+                                                reward_info.reward_type,
+                                            ),
+                                            commission: reward_info.commission,
+                                        })
+                                        .collect(),
+                                );
                                 let loaded_addresses =
                                     transaction.get_loaded_addresses();
                                 let transaction_status_meta =
@@ -135,36 +171,141 @@ impl GeyserTransactionNotifyListener {
                                         ),
                                     };
 
-                                transaction_notifier.notify_transaction(
-                                    slot,
-                                    transaction_index,
-                                    transaction.signature(),
-                                    &transaction_status_meta,
-                                    &transaction,
+                                tracing::info_span!(
+                                    parent: &span,
+                                    "geyser_notify"
+                                )
+                                .in_scope(
+                                    || {
+                                        transaction_notifier.notify_transaction(
+                                            slot,
+                                            transaction_index,
+                                            transaction.signature(),
+                                            &transaction_status_meta,
+                                            &transaction,
+                                        )
+                                    },
                                 );
                                 if enable_rpc_transaction_history {
-                                    if let Some(memos) = extract_and_fmt_memos(
+                                    if buffered_slot != Some(slot) {
+                                        flush_transactions(
+                                            &ledger,
+                                            buffered_slot,
+                                            &mut buffered_transactions,
+                                            &mut buffered_spans,
+                                        );
+                                        buffered_slot = Some(slot);
+                                    }
+                                    let memos = extract_and_fmt_memos(
                                         transaction.message(),
-                                    ) {
-                                        ledger
-                                            .write_transaction_memos(transaction.signature(), slot, memos)
-                                            .expect("Expect database write to succeed: TransactionMemos");
+                                    );
+                                    buffered_spans.push(span.clone());
+                                    buffered_transactions.push(
+                                        TransactionToWrite {
+                                            signature: *transaction.signature(),
+                                            slot,
+                                            transaction,
+                                            status: transaction_status_meta,
+                                            transaction_slot_index:
+                                                transaction_index,
+                                            memos,
+                                        },
+                                    );
+                                    if buffered_transactions.len()
+                                        >= MAX_BUFFERED_TRANSACTIONS
+                                    {
+                                        flush_transactions(
+                                            &ledger,
+                                            buffered_slot,
+                                            &mut buffered_transactions,
+                                            &mut buffered_spans,
+                                        );
                                     }
-                                    ledger.write_transaction(
-                                        *transaction.signature(),
-                                        slot,
-                                        transaction,
-                                        transaction_status_meta,
-                                        transaction_index,
-                                    )
-                                        .expect("Expect database write to succeed: TransactionStatus");
                                 }
                             }
                         }
                     }
-                    TransactionStatusMessage::Freeze(_slot) => {}
+                    TransactionStatusMessage::Freeze(slot, flushed) => {
+                        if buffered_slot
+                            .is_some_and(|buffered_slot| buffered_slot <= slot)
+                        {
+                            flush_transactions(
+                                &ledger,
+                                buffered_slot,
+                                &mut buffered_transactions,
+                                &mut buffered_spans,
+                            );
+                        }
+                        let _ = flushed.send(());
+                    }
                 }
             }
+            flush_transactions(
+                &ledger,
+                buffered_slot,
+                &mut buffered_transactions,
+                &mut buffered_spans,
+            );
         });
     }
 }
+
+fn flush_transactions(
+    ledger: &Ledger,
+    slot: Option<Slot>,
+    transactions: &mut Vec<TransactionToWrite>,
+    spans: &mut Vec<tracing::Span>,
+) {
+    if transactions.is_empty() {
+        return;
+    }
+    trace!(
+        "Writing {} transactions of slot {:?}",
+        transactions.len(),
+        slot
+    );
+    let _write_spans = spans
+        .drain(..)
+        .map(|parent| tracing::info_span!(parent: &parent, "ledger_write"))
+        .collect::<Vec<_>>();
+    ledger
+        .write_transactions(std::mem::take(transactions))
+        .expect("Expect database write to succeed: TransactionStatus");
+}
+
+/// Records the compute resources used by the transaction, the executed units
+/// are aggregated per program the transaction invokes
+fn record_compute_metering(
+    transaction: &SanitizedTransaction,
+    executed_units: u64,
+    loaded_accounts_data_size: u32,
+) {
+    let heap_size = transaction
+        .get_compute_budget_details(false)
+        .map(|details| details.heap_size);
+    debug!(
+        "Transaction '{}' consumed {} CUs, heap size: {:?} bytes, loaded accounts data size: {} bytes",
+        transaction.signature(),
+        executed_units,
+        heap_size,
+        loaded_accounts_data_size
+    );
+
+    let program_ids = transaction
+        .message()
+        .program_instructions_iter()
+        .map(|(program_id, _)| program_id)
+        .collect::<HashSet<&Pubkey>>();
+    for program_id in program_ids {
+        metrics::observe_program_executed_units(
+            &program_id.to_string(),
+            executed_units,
+        );
+    }
+    if let Some(heap_size) = heap_size {
+        metrics::observe_transaction_heap_size(heap_size);
+    }
+    metrics::observe_transaction_loaded_accounts_data_size(
+        loaded_accounts_data_size,
+    );
+}
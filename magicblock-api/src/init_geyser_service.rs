@@ -1,15 +1,26 @@
-use std::sync::Arc;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use libloading::Library;
+use libloading::{Library, Symbol};
 use log::*;
-use magicblock_config::GeyserGrpcConfig;
+use magicblock_config::{
+    GeyserGrpcConfig, GeyserGrpcSlowClientPolicy, GeyserPluginConfig,
+    GeyserSinkBus, GeyserSinkConfig, GeyserSinkFormat,
+};
 use magicblock_geyser_plugin::{
     config::{
-        Config as GeyserPluginConfig, ConfigGrpc as GeyserPluginConfigGrpc,
+        Config as GrpcPluginConfig, ConfigGrpc as GrpcPluginConfigGrpc,
+        ConfigGrpcServerTls as GrpcPluginConfigGrpcServerTls,
+        ConfigGrpcSlowClientPolicy, ConfigSink, ConfigSinkBus,
+        ConfigSinkFormat,
     },
     plugin::GrpcGeyserPlugin,
     rpc::GeyserRpcService,
 };
+use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
 use solana_geyser_plugin_manager::{
     geyser_plugin_manager::{GeyserPluginManager, LoadedGeyserPlugin},
     geyser_plugin_service::GeyserPluginServiceError,
@@ -44,6 +55,8 @@ impl Default for InitGeyserServiceConfig {
 // -----------------
 pub fn init_geyser_service(
     config: InitGeyserServiceConfig,
+    geyser_plugins: &[GeyserPluginConfig],
+    geyser_sink: Option<&GeyserSinkConfig>,
 ) -> Result<
     (GeyserPluginManager, Arc<GeyserRpcService>),
     GeyserPluginServiceError,
@@ -56,14 +69,43 @@ pub fn init_geyser_service(
         geyser_grpc,
     } = config;
 
-    let config = GeyserPluginConfig {
+    let mut grpc =
+        GrpcPluginConfigGrpc::default_with_addr(geyser_grpc.socket_addr());
+    grpc.filters.accounts.any = geyser_grpc.accounts_filter_any;
+    grpc.filters.accounts.account_max = geyser_grpc.accounts_filter_max_pubkeys;
+    grpc.filters.accounts.owner_max = geyser_grpc.accounts_filter_max_pubkeys;
+    let config = GrpcPluginConfig {
         cache_accounts,
         cache_transactions,
         enable_account_notifications,
         enable_transaction_notifications,
-        grpc: GeyserPluginConfigGrpc::default_with_addr(
-            geyser_grpc.socket_addr(),
-        ),
+        enable_entry_notifications: geyser_grpc.entry_notifications,
+        grpc: GrpcPluginConfigGrpc {
+            tls_config: geyser_grpc.tls.as_ref().map(|tls| {
+                GrpcPluginConfigGrpcServerTls {
+                    cert_path: PathBuf::from(&tls.cert_path),
+                    key_path: PathBuf::from(&tls.key_path),
+                    client_ca_cert_path: tls
+                        .client_ca_cert_path
+                        .as_ref()
+                        .map(PathBuf::from),
+                }
+            }),
+            x_tokens: geyser_grpc.x_tokens.iter().cloned().collect(),
+            replay_stored_slots: geyser_grpc.replay_stored_slots,
+            replay_max_bytes: geyser_grpc.replay_max_bytes,
+            channel_capacity: geyser_grpc.client_queue_capacity,
+            slow_client_policy: match geyser_grpc.slow_client_policy {
+                GeyserGrpcSlowClientPolicy::Disconnect => {
+                    ConfigGrpcSlowClientPolicy::Disconnect
+                }
+                GeyserGrpcSlowClientPolicy::DropOldest => {
+                    ConfigGrpcSlowClientPolicy::DropOldest
+                }
+            },
+            ..grpc
+        },
+        sink: geyser_sink.map(sink_config),
         ..Default::default()
     };
     let mut manager = GeyserPluginManager::new();
@@ -95,5 +137,79 @@ pub fn init_geyser_service(
     };
     manager.plugins.push(grpc_plugin);
 
+    for plugin in geyser_plugins {
+        let plugin =
+            load_geyser_plugin(Path::new(&plugin.config)).map_err(|err| {
+                error!(
+                    "Failed to load geyser plugin from '{}': {:?}",
+                    plugin.config, err
+                );
+                GeyserPluginServiceError::FailedToLoadPlugin(err)
+            })?;
+        info!("Loaded geyser plugin: {}", plugin.name());
+        manager.plugins.push(plugin);
+    }
+
     Ok((manager, rpc_service))
 }
+
+fn sink_config(config: &GeyserSinkConfig) -> ConfigSink {
+    ConfigSink {
+        bus: match &config.bus {
+            GeyserSinkBus::Kafka { brokers } => ConfigSinkBus::Kafka {
+                brokers: brokers.clone(),
+            },
+            GeyserSinkBus::Nats { url } => {
+                ConfigSinkBus::Nats { url: url.clone() }
+            }
+        },
+        format: match config.format {
+            GeyserSinkFormat::Protobuf => ConfigSinkFormat::Protobuf,
+            GeyserSinkFormat::Json => ConfigSinkFormat::Json,
+        },
+        accounts_topic: config.accounts_topic.clone(),
+        transactions_topic: config.transactions_topic.clone(),
+    }
+}
+
+// -----------------
+// load_geyser_plugin
+// -----------------
+type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
+
+/// Loads a geyser plugin library the same way the Solana validator does,
+/// the JSON config file provides the `libpath` of the library, relative to
+/// the config file unless absolute, and optionally the `name` of the plugin.
+fn load_geyser_plugin(
+    config_file: &Path,
+) -> Result<LoadedGeyserPlugin, Box<dyn std::error::Error>> {
+    let config = fs::read_to_string(config_file)?;
+    let config: serde_json::Value = serde_json::from_str(&config)?;
+    let libpath = config["libpath"]
+        .as_str()
+        .ok_or("the config file is missing the 'libpath' of the plugin")?;
+    let mut libpath = PathBuf::from(libpath);
+    if libpath.is_relative() {
+        let config_dir = config_file
+            .parent()
+            .ok_or("the config file is missing a parent dir")?;
+        libpath = config_dir.join(libpath);
+    }
+    let name = config["name"].as_str().map(|name| name.to_string());
+
+    // SAFETY: the library is trusted by the operator that configured it and
+    // has to export the plugin constructor expected by all geyser plugins
+    let (mut plugin, lib) = unsafe {
+        let lib = Library::new(libpath)?;
+        let constructor: Symbol<PluginConstructor> =
+            lib.get(b"_create_plugin")?;
+        let plugin_raw = constructor();
+        (Box::from_raw(plugin_raw), lib)
+    };
+    let config_file = config_file
+        .to_str()
+        .ok_or("the config file path is not valid unicode")?;
+    plugin.on_load(config_file, false)?;
+
+    Ok(LoadedGeyserPlugin::new(lib, plugin, name))
+}
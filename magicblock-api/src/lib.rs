@@ -1,3 +1,5 @@
+pub mod diagnostics;
+mod disk_watcher;
 pub mod domain_registry_manager;
 pub mod errors;
 pub mod external_config;
@@ -6,6 +8,7 @@ mod geyser_transaction_notify_listener;
 mod init_geyser_service;
 pub mod ledger;
 pub mod magic_validator;
+mod remote_sync_status;
 mod slot;
 mod tickers;
 mod utils;
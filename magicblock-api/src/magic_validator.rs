@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     net::SocketAddr,
     path::{Path, PathBuf},
     process,
@@ -7,10 +8,12 @@ use std::{
         Arc, RwLock,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-use conjunto_transwise::RpcProviderConfig;
+use conjunto_transwise::{
+    transaction_accounts_holder::TransactionAccountsHolder, RpcProviderConfig,
+};
 use log::*;
 use magicblock_account_cloner::{
     standard_blacklisted_accounts, CloneOutputMap, RemoteAccountClonerClient,
@@ -18,43 +21,63 @@ use magicblock_account_cloner::{
 };
 use magicblock_account_dumper::AccountDumperBank;
 use magicblock_account_fetcher::{
-    RemoteAccountFetcherClient, RemoteAccountFetcherWorker,
+    endpoint_label, LocalAccountsSource, RemoteAccountFetcherClient,
+    RemoteAccountFetcherWorker,
 };
 use magicblock_account_updates::{
     RemoteAccountUpdatesClient, RemoteAccountUpdatesWorker,
 };
 use magicblock_accounts::{
-    utils::try_rpc_cluster_from_cluster, AccountsManager,
+    utils::try_rpc_cluster_from_cluster, AccountsManager, Cluster,
+    PayerMonitor, PayerMonitorConfig,
 };
 use magicblock_accounts_api::BankAccountProvider;
-use magicblock_accounts_db::{
-    config::AccountsDbConfig, error::AccountsDbError,
-};
+use magicblock_accounts_db::config::AccountsDbConfig;
 use magicblock_bank::{
-    bank::Bank,
+    bank::{AccountsDataGrowthLimits, Bank, FeatureOverrides},
+    builtins::{is_reserved_program_id, BuiltinPrototype},
     genesis_utils::create_genesis_config_with_leader,
-    geyser::{AccountsUpdateNotifier, TransactionNotifier},
+    geyser::{
+        AccountsUpdateNotifier, BlockMetadataNotifier, TransactionNotifier,
+    },
     program_loader::load_programs_into_bank,
     transaction_logs::TransactionLogCollectorFilter,
+    transaction_logs::{TransactionLogFilter, TransactionLogLevel},
 };
-use magicblock_config::{EphemeralConfig, LifecycleMode, ProgramConfig};
+use magicblock_config::{
+    AccountDataGrowthConfig, ClockSource, EphemeralConfig, FeaturesConfig,
+    LedgerExportConfig, LedgerExportSink, LifecycleMode, PinnedProgram,
+    ProgramConfig, TransactionLimitsConfig, TransactionLogsConfig,
+};
+use magicblock_core::magic_program;
 use magicblock_geyser_plugin::rpc::GeyserRpcService;
 use magicblock_ledger::{
     blockstore_processor::process_ledger,
-    ledger_truncator::{LedgerTruncator, DEFAULT_TRUNCATION_TIME_INTERVAL},
+    ledger_archiver::{FileArchiveSink, LedgerArchiver},
+    ledger_tailer::{FileTailSink, LedgerTailer, TailSink, UnixSocketTailSink},
+    ledger_truncator::{
+        LedgerRetention, LedgerTruncator, DEFAULT_TRUNCATION_TIME_INTERVAL,
+    },
     Ledger,
 };
-use magicblock_metrics::MetricsService;
+use magicblock_metrics::{health, MetricsService, OtlpTracing};
 use magicblock_perf_service::SamplePerformanceService;
-use magicblock_processor::execute_transaction::TRANSACTION_INDEX_LOCK;
+use magicblock_processor::{
+    execute_transaction::TRANSACTION_INDEX_LOCK,
+    scheduler::{TransactionOrdering, TRANSACTION_SCHEDULER},
+};
 use magicblock_program::{
-    init_persister, validator, validator::validator_authority,
+    init_persister,
+    validator::{self, validator_authority, ScheduleCommitFees},
+    MagicContext,
 };
 use magicblock_pubsub::pubsub_service::{
     PubsubConfig, PubsubService, PubsubServiceCloseHandle,
 };
 use magicblock_rpc::{
-    json_rpc_request_processor::JsonRpcConfig, json_rpc_service::JsonRpcService,
+    json_rpc_request_processor::JsonRpcConfig,
+    json_rpc_service::JsonRpcService,
+    transaction_admission::TransactionAdmission,
 };
 use magicblock_transaction_status::{
     TransactionStatusMessage, TransactionStatusSender,
@@ -69,20 +92,33 @@ use solana_geyser_plugin_manager::{
     geyser_plugin_manager::GeyserPluginManager,
     slot_status_notifier::SlotStatusNotifierImpl,
 };
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
-    clock::Slot, commitment_config::CommitmentLevel,
-    genesis_config::GenesisConfig, pubkey::Pubkey, signature::Keypair,
-    signer::Signer,
+    account::ReadableAccount,
+    clock::{Clock, Slot},
+    commitment_config::{CommitmentConfig, CommitmentLevel},
+    genesis_config::GenesisConfig,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::{EncodableKey, Signer},
 };
+use solana_svm::runtime_config::RuntimeConfig;
 use tempfile::TempDir;
+use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
 use crate::{
+    diagnostics,
+    disk_watcher::init_disk_watcher,
     domain_registry_manager::DomainRegistryManager,
     errors::{ApiError, ApiResult},
-    external_config::{cluster_from_remote, try_convert_accounts_config},
+    external_config::{
+        cluster_from_remote, rate_limiter_config_for_remote,
+        rpc_filters_from_prefetch_program, try_convert_accounts_config,
+    },
     fund_account::{
-        fund_magic_context, fund_validator_identity, funded_faucet,
+        fund_magic_commit_events, fund_magic_context, fund_magic_session_keys,
+        fund_validator_identity, funded_faucet,
     },
     geyser_transaction_notify_listener::GeyserTransactionNotifyListener,
     init_geyser_service::{init_geyser_service, InitGeyserServiceConfig},
@@ -90,7 +126,10 @@ use crate::{
         self, read_validator_keypair_from_ledger,
         write_validator_keypair_to_ledger,
     },
-    slot::advance_slot_and_update_ledger,
+    remote_sync_status::RemoteSyncStatusProvider,
+    slot::{
+        advance_slot_and_take_final_snapshot, advance_slot_and_update_ledger,
+    },
     tickers::{
         init_commit_accounts_ticker, init_slot_ticker,
         init_system_metrics_ticker,
@@ -104,6 +143,8 @@ use crate::{
 pub struct MagicValidatorConfig {
     pub validator_config: EphemeralConfig,
     pub init_geyser_service_config: InitGeyserServiceConfig,
+    /// Native programs to add to the bank in addition to the standard ones
+    pub additional_builtins: Vec<BuiltinPrototype>,
 }
 
 impl std::fmt::Debug for MagicValidatorConfig {
@@ -114,6 +155,7 @@ impl std::fmt::Debug for MagicValidatorConfig {
                 "init_geyser_service_config",
                 &self.init_geyser_service_config,
             )
+            .field("additional_builtins", &self.additional_builtins)
             .finish()
     }
 }
@@ -128,15 +170,21 @@ pub struct MagicValidator {
     bank: Arc<Bank>,
     ledger: Arc<Ledger>,
     ledger_truncator: LedgerTruncator<Bank>,
+    ledger_tailer: Option<LedgerTailer>,
     slot_ticker: Option<tokio::task::JoinHandle<()>>,
+    disk_watcher: Option<tokio::task::JoinHandle<()>>,
     pubsub_handle: RwLock<Option<thread::JoinHandle<()>>>,
     pubsub_close_handle: PubsubServiceCloseHandle,
     sample_performance_service: Option<SamplePerformanceService>,
     commit_accounts_ticker: Option<tokio::task::JoinHandle<()>>,
+    payer_monitor: Option<PayerMonitor>,
+    payer_monitor_handle: Option<tokio::task::JoinHandle<()>>,
     remote_account_fetcher_worker: Option<RemoteAccountFetcherWorker>,
     remote_account_fetcher_handle: Option<tokio::task::JoinHandle<()>>,
     remote_account_updates_worker: Option<RemoteAccountUpdatesWorker>,
     remote_account_updates_handle: Option<tokio::task::JoinHandle<()>>,
+    /// Latest base layer clock, only set if the Clock sysvar is anchored to it
+    remote_clock: Option<Arc<RwLock<Option<Clock>>>>,
     remote_account_cloner_worker: Option<
         RemoteAccountClonerWorker<
             BankAccountProvider,
@@ -149,7 +197,10 @@ pub struct MagicValidator {
     accounts_manager: Arc<AccountsManager>,
     transaction_listener: GeyserTransactionNotifyListener,
     rpc_service: JsonRpcService,
+    /// Notified when a shutdown is requested via the admin RPC
+    shutdown_request: Arc<Notify>,
     _metrics: Option<(MetricsService, tokio::task::JoinHandle<()>)>,
+    _tracing: Option<OtlpTracing>,
     geyser_rpc_service: Arc<GeyserRpcService>,
     pubsub_config: PubsubConfig,
     pub transaction_status_sender: TransactionStatusSender,
@@ -166,8 +217,23 @@ impl MagicValidator {
         // TODO(thlorenz): @@ this will need to be recreated on each start
         let token = CancellationToken::new();
 
-        let (geyser_manager, geyser_rpc_service) =
-            init_geyser_service(config.init_geyser_service_config)?;
+        let tracing = config
+            .validator_config
+            .tracing
+            .as_ref()
+            .map(|tracing| {
+                OtlpTracing::try_init(&tracing.endpoint, &tracing.service_name)
+                    .map_err(|err| {
+                        ApiError::FailedToInitTracing(err.to_string())
+                    })
+            })
+            .transpose()?;
+
+        let (geyser_manager, geyser_rpc_service) = init_geyser_service(
+            config.init_geyser_service_config,
+            &config.validator_config.geyser_plugins,
+            config.validator_config.geyser_sink.as_ref(),
+        )?;
         let geyser_manager = Arc::new(RwLock::new(geyser_manager));
 
         let validator_pubkey = identity_keypair.pubkey();
@@ -204,17 +270,57 @@ impl MagicValidator {
             validator_pubkey,
             adb_path,
             ledger.get_max_blockhash().map(|(slot, _)| slot)?,
+            &config.validator_config.validator.features,
+            &config.validator_config.validator.account_data_growth,
+            &config.validator_config.validator.transaction_logs,
+            config.validator_config.validator.sponsor_session_key_fees,
+            &config.validator_config.validator.transaction_limits,
+            &config.validator_config.validator.pinned_programs,
+            &config.additional_builtins,
         )?;
-
+        geyser_rpc_service.set_accounts_snapshot_provider(bank.clone());
+
+        let ledger_archiver = config
+            .validator_config
+            .ledger
+            .archive
+            .as_ref()
+            .map(|archive| {
+                FileArchiveSink::new(&archive.path).map(|sink| {
+                    Arc::new(LedgerArchiver::new(
+                        Arc::new(sink),
+                        archive.compression_level,
+                    ))
+                })
+            })
+            .transpose()?;
         let ledger_truncator = LedgerTruncator::new(
             ledger.clone(),
             bank.clone(),
             DEFAULT_TRUNCATION_TIME_INTERVAL,
             config.validator_config.ledger.size,
+            LedgerRetention {
+                max_slots: config.validator_config.ledger.retention_slots,
+                max_age: config
+                    .validator_config
+                    .ledger
+                    .retention_secs
+                    .map(Duration::from_secs),
+            },
+            ledger_archiver,
         );
+        let ledger_tailer = config
+            .validator_config
+            .ledger
+            .export
+            .as_ref()
+            .map(|export| Self::init_ledger_tailer(&ledger, export))
+            .transpose()?;
 
         fund_validator_identity(&bank, &validator_pubkey);
         fund_magic_context(&bank);
+        fund_magic_commit_events(&bank);
+        fund_magic_session_keys(&bank);
         let faucet_keypair = funded_faucet(
             &bank,
             ledger.ledger_path().as_path(),
@@ -267,19 +373,103 @@ impl MagicValidator {
             Some(CommitmentLevel::Confirmed),
         );
 
-        let remote_account_fetcher_worker =
-            RemoteAccountFetcherWorker::new(remote_rpc_config.clone());
+        // The payer is monitored on the cluster commits are sent to
+        let commit_rpc_cluster = try_rpc_cluster_from_cluster(
+            accounts_config
+                .commit_remote_cluster
+                .as_ref()
+                .unwrap_or(&accounts_config.remote_cluster),
+        )?;
+        let payer_monitor = config
+            .validator_config
+            .accounts
+            .commit
+            .payer_monitor
+            .as_ref()
+            .map(|payer_monitor_config| {
+                Self::init_payer_monitor(
+                    payer_monitor_config,
+                    commit_rpc_cluster.url(),
+                    identity_keypair.pubkey(),
+                )
+            })
+            .transpose()?;
+
+        let bootstrap_config =
+            config.validator_config.accounts.cloning.try_bootstrap()?;
+        let local_accounts_source = bootstrap_config
+            .map(Self::init_local_accounts_source)
+            .transpose()?;
+
+        let fetcher_config = &config.validator_config.accounts.fetcher;
+        let rate_limit_config = &config.validator_config.accounts.rate_limit;
+        let mut fetcher_rpc_configs = vec![remote_rpc_config.clone()];
+        for url in &fetcher_config.fallback_remotes {
+            fetcher_rpc_configs.push(RpcProviderConfig::new(
+                try_rpc_cluster_from_cluster(&Cluster::Custom(url.clone()))?,
+                remote_rpc_config.commitment(),
+            ));
+        }
+        let remote_account_fetcher_worker = RemoteAccountFetcherWorker::new(
+            fetcher_rpc_configs
+                .into_iter()
+                .map(|rpc_config| {
+                    let rate_limiter_config = rate_limiter_config_for_remote(
+                        rate_limit_config,
+                        rpc_config.url(),
+                    );
+                    (rpc_config, rate_limiter_config)
+                })
+                .collect(),
+            Duration::from_millis(fetcher_config.health_check_interval_millis),
+            fetcher_config.max_consecutive_failures,
+            Duration::from_millis(
+                fetcher_config.circuit_breaker_cooldown_millis,
+            ),
+            Duration::from_millis(fetcher_config.fetch_timeout_millis),
+            config
+                .validator_config
+                .accounts
+                .delegation_programs
+                .iter()
+                .map(|program| program.id)
+                .collect(),
+            local_accounts_source,
+        );
 
         let remote_account_updates_worker = RemoteAccountUpdatesWorker::new(
-            accounts_config.remote_cluster.ws_urls(),
+            accounts_config
+                .remote_cluster
+                .ws_urls()
+                .into_iter()
+                .map(|url| {
+                    let rate_limiter_config =
+                        rate_limiter_config_for_remote(rate_limit_config, &url);
+                    (url, rate_limiter_config)
+                })
+                .collect(),
             remote_rpc_config.commitment(),
             // We'll kill/refresh one connection every 50 minutes
             Duration::from_secs(60 * 50),
+            Duration::from_millis(
+                config
+                    .validator_config
+                    .accounts
+                    .updates
+                    .coalesce_interval_millis,
+            ),
+            config
+                .validator_config
+                .accounts
+                .updates
+                .fast_lane
+                .iter()
+                .map(|account| account.id)
+                .collect(),
         );
 
-        let transaction_status_sender = TransactionStatusSender {
-            sender: transaction_sndr,
-        };
+        let transaction_status_sender =
+            TransactionStatusSender::new(transaction_sndr);
 
         let bank_account_provider = BankAccountProvider::new(bank.clone());
         let remote_account_fetcher_client =
@@ -290,11 +480,28 @@ impl MagicValidator {
             bank.clone(),
             Some(transaction_status_sender.clone()),
         );
-        let blacklisted_accounts = standard_blacklisted_accounts(
+        let mut blacklisted_accounts = standard_blacklisted_accounts(
             &identity_keypair.pubkey(),
             &faucet_keypair.pubkey(),
         );
+        // Cloning an account into the place of a builtin would replace it
+        blacklisted_accounts.extend(
+            config
+                .additional_builtins
+                .iter()
+                .map(|builtin| builtin.program_id),
+        );
 
+        let cloning_config = &config.validator_config.accounts.cloning;
+        let mut cloner_permissions =
+            accounts_config.lifecycle.to_account_cloner_permissions();
+        // Accounts cloned from a local copy of the remote never change,
+        // thus there is nothing to monitor
+        if bootstrap_config
+            .is_some_and(|bootstrap| !bootstrap.fallback_to_remote)
+        {
+            cloner_permissions.allow_cloning_refresh = false;
+        }
         let remote_account_cloner_worker = RemoteAccountClonerWorker::new(
             bank_account_provider,
             remote_account_fetcher_client,
@@ -308,9 +515,17 @@ impl MagicValidator {
             } else {
                 ValidatorCollectionMode::Fees
             },
-            accounts_config.lifecycle.to_account_cloner_permissions(),
+            cloner_permissions,
+            cloning_config.token_mints,
+            cloning_config.program_idls,
+            cloning_config.max_concurrent_clones,
+            cloning_config
+                .revalidate_after_millis
+                .map(Duration::from_millis),
+            cloning_config.verify_delegations,
             identity_keypair.pubkey(),
             config.validator_config.accounts.max_monitored_accounts,
+            endpoint_label(remote_rpc_config.url()),
         );
 
         let accounts_manager = Self::init_accounts_manager(
@@ -320,25 +535,96 @@ impl MagicValidator {
             transaction_status_sender.clone(),
             &identity_keypair,
             &config.validator_config,
+            ledger.clone(),
+        );
+        let remote_clock = match config.validator_config.validator.clock_source
+        {
+            ClockSource::Local => None,
+            ClockSource::Remote => {
+                Some(remote_account_updates_worker.get_remote_clock())
+            }
+        };
+        let remote_sync_status = Arc::new(RemoteSyncStatusProvider::new(
+            remote_account_updates_worker.get_shard_slots(),
+            remote_account_cloner_worker.get_clone_listeners(),
+            accounts_manager.clone(),
+        ));
+        register_readiness_probes(
+            &config.validator_config.accounts.lifecycle,
+            remote_sync_status.clone(),
+        );
+        diagnostics::init(
+            adb_path,
+            bank.clone(),
+            remote_sync_status.clone(),
+            &config.validator_config,
         );
 
         let pubsub_config = PubsubConfig::from_rpc(
             config.validator_config.rpc.addr,
             config.validator_config.rpc.port,
             config.validator_config.rpc.max_ws_connections,
+            config.validator_config.rpc.cors_allowed_origins.clone(),
         );
         validator::init_validator_authority(identity_keypair);
+        let magic_program_allowlist =
+            &config.validator_config.validator.magic_program_allowlist;
+        if !magic_program_allowlist.is_empty() {
+            validator::init_magic_program_allowlist(
+                magic_program_allowlist
+                    .iter()
+                    .map(|program| program.id)
+                    .collect(),
+            );
+        }
+        validator::init_session_key_sponsor_programs(
+            config
+                .validator_config
+                .validator
+                .session_key_sponsor_programs
+                .iter()
+                .map(|program| program.id)
+                .collect(),
+        );
+        if let Some(schedule_fee) =
+            &config.validator_config.accounts.commit.schedule_fee
+        {
+            validator::init_schedule_commit_fees(ScheduleCommitFees {
+                flat: schedule_fee.flat_lamports,
+                per_account: schedule_fee.per_account_lamports,
+            });
+        }
+        TRANSACTION_SCHEDULER.set_ordering(
+            match config.validator_config.validator.transaction_ordering {
+                magicblock_config::TransactionOrdering::Fifo => {
+                    TransactionOrdering::Fifo
+                }
+                magicblock_config::TransactionOrdering::FeePriority => {
+                    TransactionOrdering::FeePriority
+                }
+            },
+        );
+        TRANSACTION_SCHEDULER.set_slow_transaction_threshold(
+            config
+                .validator_config
+                .logging
+                .slow_transaction_threshold_millis
+                .map(Duration::from_millis),
+        );
 
         // Make sure we process the ledger before we're open to handle
         // transactions via RPC
+        let shutdown_request = Arc::new(Notify::new());
         let rpc_service = Self::init_json_rpc_service(
             bank.clone(),
             ledger.clone(),
             faucet_keypair,
             &genesis_config,
             accounts_manager.clone(),
+            remote_sync_status,
             transaction_status_sender.clone(),
             &pubsub_config,
+            shutdown_request.clone(),
             &config.validator_config,
         )?;
 
@@ -346,14 +632,20 @@ impl MagicValidator {
             config: config.validator_config,
             exit,
             rpc_service,
+            shutdown_request,
             _metrics: metrics,
+            _tracing: tracing,
             geyser_rpc_service,
             slot_ticker: None,
+            disk_watcher: None,
             commit_accounts_ticker: None,
+            payer_monitor,
+            payer_monitor_handle: None,
             remote_account_fetcher_worker: Some(remote_account_fetcher_worker),
             remote_account_fetcher_handle: None,
             remote_account_updates_worker: Some(remote_account_updates_worker),
             remote_account_updates_handle: None,
+            remote_clock,
             remote_account_cloner_worker: Some(remote_account_cloner_worker),
             remote_account_cloner_handle: None,
             pubsub_handle: Default::default(),
@@ -364,12 +656,14 @@ impl MagicValidator {
             bank,
             ledger,
             ledger_truncator,
+            ledger_tailer,
             accounts_manager,
             transaction_listener,
             transaction_status_sender,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn init_bank(
         geyser_manager: Option<Arc<RwLock<GeyserPluginManager>>>,
         genesis_config: &GenesisConfig,
@@ -378,24 +672,78 @@ impl MagicValidator {
         validator_pubkey: Pubkey,
         adb_path: &Path,
         adb_init_slot: Slot,
-    ) -> Result<Arc<Bank>, AccountsDbError> {
-        let runtime_config = Default::default();
+        features_config: &FeaturesConfig,
+        account_data_growth_config: &AccountDataGrowthConfig,
+        transaction_logs_config: &TransactionLogsConfig,
+        sponsor_session_key_fees: bool,
+        transaction_limits_config: &TransactionLimitsConfig,
+        pinned_programs: &[PinnedProgram],
+        additional_builtins: &[BuiltinPrototype],
+    ) -> ApiResult<Arc<Bank>> {
+        Self::validate_additional_builtins(additional_builtins)?;
+        let runtime_config = Arc::new(RuntimeConfig {
+            transaction_account_lock_limit: Some(
+                transaction_limits_config.max_account_locks,
+            ),
+            ..Default::default()
+        });
+        let feature_overrides = FeatureOverrides {
+            activate: features_config
+                .activate
+                .iter()
+                .map(|feature| feature.id)
+                .collect(),
+            deactivate: features_config
+                .deactivate
+                .iter()
+                .map(|feature| feature.id)
+                .collect(),
+        };
         let lock = TRANSACTION_INDEX_LOCK.clone();
-        let bank = Bank::new(
+        let mut bank = Bank::new(
             genesis_config,
             runtime_config,
             accountsdb_config,
             None,
-            None,
+            Some(additional_builtins),
             false,
             geyser_manager.clone().map(AccountsUpdateNotifier::new),
-            geyser_manager.map(SlotStatusNotifierImpl::new),
+            geyser_manager.clone().map(SlotStatusNotifierImpl::new),
             millis_per_slot,
             validator_pubkey,
             lock,
             adb_path,
             adb_init_slot,
+            &feature_overrides,
         )?;
+        bank.set_accounts_data_growth_limits(AccountsDataGrowthLimits {
+            max_per_transaction: account_data_growth_config.max_per_transaction,
+            max_per_slot: account_data_growth_config.max_per_slot,
+        });
+        bank.set_transaction_log_filter(TransactionLogFilter {
+            default_level: Self::transaction_log_level(
+                transaction_logs_config.level,
+            ),
+            program_levels: transaction_logs_config
+                .programs
+                .iter()
+                .map(|program| {
+                    (program.id, Self::transaction_log_level(program.level))
+                })
+                .collect(),
+            max_bytes_per_transaction: transaction_logs_config
+                .max_bytes_per_transaction,
+        });
+        if let Some(geyser_manager) = geyser_manager {
+            bank.set_block_metadata_notifier(BlockMetadataNotifier::new(
+                geyser_manager,
+            ));
+        }
+        bank.set_sponsor_session_key_fees(sponsor_session_key_fees);
+        bank.set_max_transaction_size(transaction_limits_config.max_size);
+        bank.set_pinned_programs(
+            pinned_programs.iter().map(|program| program.id).collect(),
+        );
         bank.transaction_log_collector_config
             .write()
             .unwrap()
@@ -403,6 +751,39 @@ impl MagicValidator {
         Ok(Arc::new(bank))
     }
 
+    fn transaction_log_level(
+        level: magicblock_config::TransactionLogLevel,
+    ) -> TransactionLogLevel {
+        match level {
+            magicblock_config::TransactionLogLevel::All => {
+                TransactionLogLevel::All
+            }
+            magicblock_config::TransactionLogLevel::Invocations => {
+                TransactionLogLevel::Invocations
+            }
+            magicblock_config::TransactionLogLevel::None => {
+                TransactionLogLevel::None
+            }
+        }
+    }
+
+    fn validate_additional_builtins(
+        additional_builtins: &[BuiltinPrototype],
+    ) -> ApiResult<()> {
+        let mut program_ids = HashSet::new();
+        for builtin in additional_builtins {
+            if is_reserved_program_id(&builtin.program_id)
+                || !program_ids.insert(builtin.program_id)
+            {
+                return Err(ApiError::AdditionalBuiltinProgramIdTaken(
+                    builtin.name.to_string(),
+                    builtin.program_id.to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn init_accounts_manager(
         bank: &Arc<Bank>,
         cloned_accounts: &CloneOutputMap,
@@ -410,6 +791,7 @@ impl MagicValidator {
         transaction_status_sender: TransactionStatusSender,
         validator_keypair: &Keypair,
         config: &EphemeralConfig,
+        ledger: Arc<Ledger>,
     ) -> Arc<AccountsManager> {
         let accounts_config = try_convert_accounts_config(&config.accounts)
             .expect(
@@ -427,12 +809,66 @@ impl MagicValidator {
             // places only temporarily
             validator_keypair.insecure_clone(),
             accounts_config,
+            Some(ledger),
         )
         .expect("Failed to create accounts manager");
 
         Arc::new(accounts_manager)
     }
 
+    fn init_local_accounts_source(
+        config: &magicblock_config::BootstrapConfig,
+    ) -> ApiResult<LocalAccountsSource> {
+        let source = match (&config.accountsdb_path, &config.accounts_dir) {
+            (Some(accountsdb_path), _) => LocalAccountsSource::from_accountsdb(
+                Path::new(accountsdb_path),
+                config.fallback_to_remote,
+            )?,
+            (None, Some(accounts_dir)) => {
+                LocalAccountsSource::from_json_accounts(
+                    Path::new(accounts_dir),
+                    config.fallback_to_remote,
+                )?
+            }
+            (None, None) => unreachable!("validated by try_bootstrap"),
+        };
+        Ok(source)
+    }
+
+    fn init_payer_monitor(
+        config: &magicblock_config::PayerMonitorConfig,
+        remote_url: &str,
+        payer: Pubkey,
+    ) -> ApiResult<PayerMonitor> {
+        let treasury = config
+            .treasury_keypair
+            .as_ref()
+            .map(|keypair_path| {
+                Keypair::read_from_file(keypair_path).map_err(|err| {
+                    ApiError::InvalidTreasuryKeypair(
+                        keypair_path.clone(),
+                        err.to_string(),
+                    )
+                })
+            })
+            .transpose()?;
+        Ok(PayerMonitor::new(
+            RpcClient::new_with_commitment(
+                remote_url.to_string(),
+                CommitmentConfig::confirmed(),
+            ),
+            payer,
+            PayerMonitorConfig {
+                check_interval: Duration::from_millis(
+                    config.check_interval_millis,
+                ),
+                min_lamports: config.min_lamports,
+                treasury,
+                top_up_lamports: config.top_up_lamports,
+            },
+        ))
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn init_json_rpc_service(
         bank: Arc<Bank>,
@@ -440,8 +876,10 @@ impl MagicValidator {
         faucet_keypair: Keypair,
         genesis_config: &GenesisConfig,
         accounts_manager: Arc<AccountsManager>,
+        remote_sync_status: Arc<RemoteSyncStatusProvider>,
         transaction_status_sender: TransactionStatusSender,
         pubsub_config: &PubsubConfig,
+        shutdown_request: Arc<Notify>,
         config: &EphemeralConfig,
     ) -> ApiResult<JsonRpcService> {
         let rpc_socket_addr = SocketAddr::new(config.rpc.addr, config.rpc.port);
@@ -455,6 +893,37 @@ impl MagicValidator {
             pubsub_socket_addr: Some(*pubsub_config.socket()),
             enable_rpc_transaction_history: true,
             disable_sigverify: !config.validator.sigverify,
+            cors_allowed_origins: config.rpc.cors_allowed_origins.clone(),
+            admin_socket_addr: config.rpc.admin_socket_addr(),
+            slow_request_threshold: config
+                .logging
+                .slow_rpc_request_threshold_millis
+                .map(Duration::from_millis),
+            transaction_admission: TransactionAdmission {
+                min_compute_unit_price: config
+                    .validator
+                    .fee_floor
+                    .min_compute_unit_price,
+                min_fee_per_signature: config
+                    .validator
+                    .fee_floor
+                    .min_fee_per_signature,
+                exempt_fee_payers: config
+                    .validator
+                    .fee_floor
+                    .exempt_fee_payers
+                    .iter()
+                    .map(|fee_payer| fee_payer.id)
+                    .collect(),
+                exempt_programs: config
+                    .validator
+                    .fee_floor
+                    .exempt_programs
+                    .iter()
+                    .map(|program| program.id)
+                    .collect(),
+            },
+            shutdown_request,
 
             ..Default::default()
         };
@@ -465,6 +934,7 @@ impl MagicValidator {
             faucet_keypair,
             genesis_config.hash(),
             accounts_manager,
+            remote_sync_status,
             rpc_json_config,
         )
         .map_err(|err| {
@@ -489,6 +959,39 @@ impl MagicValidator {
         Ok(ledger_shared)
     }
 
+    fn init_ledger_tailer(
+        ledger: &Arc<Ledger>,
+        export: &LedgerExportConfig,
+    ) -> ApiResult<LedgerTailer> {
+        let sink: Arc<dyn TailSink> = match &export.sink {
+            LedgerExportSink::File { path } => {
+                Arc::new(FileTailSink::new(path)?)
+            }
+            LedgerExportSink::UnixSocket { path } => {
+                Arc::new(UnixSocketTailSink::new(path))
+            }
+            #[cfg(feature = "kafka")]
+            LedgerExportSink::Kafka { brokers, topic } => {
+                Arc::new(magicblock_ledger::ledger_tailer::KafkaTailSink::new(
+                    brokers,
+                    topic.clone(),
+                )?)
+            }
+            #[cfg(not(feature = "kafka"))]
+            LedgerExportSink::Kafka { .. } => {
+                return Err(ApiError::LedgerExportSinkNotEnabled(
+                    "Kafka".to_string(),
+                    "kafka".to_string(),
+                ))
+            }
+        };
+        Ok(LedgerTailer::new(
+            ledger.clone(),
+            sink,
+            Duration::from_millis(export.poll_interval_millis),
+        ))
+    }
+
     fn sync_validator_keypair_with_ledger(
         ledger_path: &Path,
         validator_keypair: &Keypair,
@@ -555,7 +1058,7 @@ impl MagicValidator {
         // user request to be processed in the next slot such that it doesn't become
         // part of the last block found in the existing ledger which would be incorrect.
         let (update_ledger_result, _) =
-            advance_slot_and_update_ledger(&self.bank, &self.ledger);
+            advance_slot_and_update_ledger(&self.bank, &self.ledger, None);
         if let Err(err) = update_ledger_result {
             return Err(err.into());
         }
@@ -568,6 +1071,17 @@ impl MagicValidator {
             );
         }
 
+        // Transactions that ran before the snapshot aren't replayed, thus we
+        // restore their statuses in order to reject them if they are resent
+        let slot_statuses = self.ledger.read_status_cache(
+            slot_to_continue_at.saturating_sub(self.bank.max_age),
+        )?;
+        debug!(
+            "Restoring transaction statuses of {} slots",
+            slot_statuses.len()
+        );
+        self.bank.restore_transaction_statuses(slot_statuses);
+
         info!(
             "Processed ledger, validator continues at slot {}",
             slot_to_continue_at
@@ -630,7 +1144,7 @@ impl MagicValidator {
 
         self.maybe_process_ledger()?;
 
-        self.transaction_listener.run(true, self.bank.clone());
+        self.transaction_listener.run(true);
 
         self.slot_ticker = Some(init_slot_ticker(
             &self.bank,
@@ -638,6 +1152,12 @@ impl MagicValidator {
             Some(self.transaction_status_sender.clone()),
             self.ledger.clone(),
             Duration::from_millis(self.config.validator.millis_per_slot),
+            self.config
+                .validator
+                .on_demand_slots
+                .as_ref()
+                .map(|config| Duration::from_millis(config.max_idle_millis)),
+            self.remote_clock.clone(),
             self.exit.clone(),
         ));
 
@@ -647,11 +1167,29 @@ impl MagicValidator {
             self.token.clone(),
         ));
 
+        if let Some(payer_monitor) = self.payer_monitor.take() {
+            self.payer_monitor_handle = Some(tokio::spawn(
+                payer_monitor.start_monitoring(self.token.clone()),
+            ));
+        }
+
         self.start_remote_account_fetcher_worker();
         self.start_remote_account_updates_worker();
         self.start_remote_account_cloner_worker().await?;
+        self.prefetch_program_accounts().await;
+        self.warm_up_pinned_programs().await;
 
         self.ledger_truncator.start();
+        self.disk_watcher = Some(init_disk_watcher(
+            &self.config.disk,
+            &self.ledger,
+            &self.bank,
+            self.ledger_truncator.emergency_truncation(),
+            self.token.clone(),
+        ));
+        if let Some(ledger_tailer) = self.ledger_tailer.as_mut() {
+            ledger_tailer.start(self.exit.clone())?;
+        }
 
         self.rpc_service.start().map_err(|err| {
             ApiError::FailedToStartJsonRpcService(format!("{:?}", err))
@@ -735,12 +1273,87 @@ impl MagicValidator {
         Ok(())
     }
 
-    pub fn stop(&mut self) {
+    /// Clones all accounts of the programs configured to be prefetched, which
+    /// requires the cloner worker to be running already
+    async fn prefetch_program_accounts(&self) {
+        for program in &self.config.accounts.cloning.prefetch {
+            let filters = rpc_filters_from_prefetch_program(program);
+            match self
+                .accounts_manager
+                .clone_program_accounts(&program.id, filters)
+                .await
+            {
+                Ok(cloned) => info!(
+                    "Prefetched accounts of program '{}': {:?}",
+                    program.id, cloned
+                ),
+                Err(err) => error!(
+                    "Failed to prefetch accounts of program '{}': {:?}",
+                    program.id, err
+                ),
+            }
+        }
+    }
+
+    /// Clones the pinned programs and loads them into the program cache,
+    /// afterwards the bank reloads them whenever they are evicted
+    async fn warm_up_pinned_programs(&self) {
+        let pinned_programs = self
+            .bank
+            .pinned_programs()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        if pinned_programs.is_empty() {
+            return;
+        }
+        if let Err(err) = self
+            .accounts_manager
+            .ensure_accounts_from_holder(
+                TransactionAccountsHolder {
+                    readonly: pinned_programs.clone(),
+                    writable: vec![],
+                    payer: self.bank.get_identity(),
+                },
+                "pinned-programs".to_string(),
+            )
+            .await
+        {
+            error!("Failed to clone pinned programs: {:?}", err);
+        }
+        let loaded = self.bank.warm_up_program_cache(&pinned_programs);
+        info!(
+            "Loaded pinned programs into the program cache: {:?}",
+            loaded
+        );
+    }
+
+    /// Resolves once a shutdown was requested via the admin RPC
+    pub async fn shutdown_requested(&self) {
+        self.shutdown_request.notified().await
+    }
+
+    /// Stops the validator after the scheduled commits were sent to the base
+    /// layer, returns `false` if any of them did not land or the shutdown
+    /// timed out before they did
+    pub fn stop(&mut self) -> bool {
+        // Transactions are rejected from here on, while the slot ticker keeps
+        // accepting and processing the commits that were already scheduled
+        validator::start_shutting_down();
+        health::set_readiness("shutdown", Err("shutting down".to_string()));
+        let commits_landed = self.drain_scheduled_commits();
+
         self.exit.store(true, Ordering::Relaxed);
+        if let Some(slot_ticker) = self.slot_ticker.take() {
+            slot_ticker.abort();
+        }
         self.rpc_service.close();
         PubsubService::close(&self.pubsub_close_handle);
         self.token.cancel();
         self.ledger_truncator.stop();
+        if let Some(ledger_tailer) = self.ledger_tailer.as_mut() {
+            ledger_tailer.join();
+        }
 
         // wait a bit for services to stop
         thread::sleep(Duration::from_secs(1));
@@ -756,11 +1369,69 @@ impl MagicValidator {
             }
         }
 
-        // we have two memory mapped databases, flush them to disk before exitting
-        self.bank.flush();
+        if let Err(err) = self
+            .ledger
+            .write_status_cache(self.bank.recent_transaction_statuses())
+        {
+            error!("Failed to persist status cache: {:?}", err);
+        }
+
+        // The final snapshot flushes the accountsdb and allows to restart
+        // without replaying the ledger since the last periodic snapshot
+        match advance_slot_and_take_final_snapshot(
+            &self.bank,
+            &self.ledger,
+            Some(&self.transaction_status_sender),
+        ) {
+            Ok(slot) => info!("Took final accountsdb snapshot at slot {slot}"),
+            Err(err) => {
+                error!("Failed to take final accountsdb snapshot: {:?}", err);
+                self.bank.flush();
+            }
+        }
         if let Err(err) = self.ledger.shutdown(false) {
             error!("Failed to shutdown ledger: {:?}", err);
         }
+        commits_landed
+    }
+
+    /// Waits until all scheduled commits were sent and their outcome is
+    /// final, or the shutdown timeout elapsed
+    fn drain_scheduled_commits(&self) -> bool {
+        let timeout = Duration::from_millis(
+            self.config.validator.shutdown_timeout_millis,
+        );
+        let deadline = Instant::now() + timeout;
+        let failed_before = self.accounts_manager.failed_commits_len();
+        loop {
+            let pending = self.accounts_manager.scheduled_commits_len()
+                + self.accounts_manager.unresolved_commits_len();
+            let scheduled_in_context = self
+                .bank
+                .get_account(&magic_program::MAGIC_CONTEXT_PUBKEY)
+                .is_some_and(|acc| {
+                    MagicContext::has_scheduled_commits(acc.data())
+                });
+            if pending == 0 && !scheduled_in_context {
+                break;
+            }
+            if Instant::now() >= deadline {
+                error!(
+                    "Shutdown timed out after {:?} with {} commits pending",
+                    timeout, pending
+                );
+                return false;
+            }
+            thread::sleep(Duration::from_millis(100));
+        }
+
+        let failed = self.accounts_manager.failed_commits_len() - failed_before;
+        if failed > 0 {
+            error!("{failed} commits failed while shutting down");
+            return false;
+        }
+        info!("All scheduled commits landed");
+        true
     }
 
     pub fn join(self) {
@@ -789,3 +1460,28 @@ fn programs_to_load(programs: &[ProgramConfig]) -> Vec<(Pubkey, String)> {
         .map(|program| (program.id, program.path.clone()))
         .collect()
 }
+
+/// Registers the checks reported via `/readyz` of the metrics service
+fn register_readiness_probes(
+    lifecycle: &LifecycleMode,
+    remote_sync_status: Arc<RemoteSyncStatusProvider>,
+) {
+    // Includes hydrating the cloned accounts after the ledger was replayed
+    health::register_readiness_probe("ledger", || {
+        if validator::is_starting_up() {
+            Err("replaying the ledger".to_string())
+        } else {
+            Ok(())
+        }
+    });
+    if !matches!(lifecycle, LifecycleMode::Offline) {
+        // Shards are removed once their subscription fails
+        health::register_readiness_probe("remote", move || {
+            if remote_sync_status.remote_sync_status().shards.is_empty() {
+                Err("not receiving slots from the remote cluster".to_string())
+            } else {
+                Ok(())
+            }
+        });
+    }
+}
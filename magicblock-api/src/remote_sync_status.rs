@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+use magicblock_account_cloner::AccountClonerListeners;
+use magicblock_accounts::{AccountsManager, ScheduledCommitsProcessor};
+use magicblock_core::{
+    remote_sync_status::{RemoteSyncStatus, ShardSyncStatus},
+    traits::ProvidesRemoteSyncStatus,
+};
+use solana_sdk::{clock::Slot, pubkey::Pubkey};
+
+/// Collects the sync status from the workers that track the remote cluster
+pub(crate) struct RemoteSyncStatusProvider {
+    shard_slots: Arc<RwLock<HashMap<String, Slot>>>,
+    clone_listeners: Arc<RwLock<HashMap<Pubkey, AccountClonerListeners>>>,
+    accounts_manager: Arc<AccountsManager>,
+}
+
+impl RemoteSyncStatusProvider {
+    pub(crate) fn new(
+        shard_slots: Arc<RwLock<HashMap<String, Slot>>>,
+        clone_listeners: Arc<RwLock<HashMap<Pubkey, AccountClonerListeners>>>,
+        accounts_manager: Arc<AccountsManager>,
+    ) -> Self {
+        Self {
+            shard_slots,
+            clone_listeners,
+            accounts_manager,
+        }
+    }
+}
+
+impl ProvidesRemoteSyncStatus for RemoteSyncStatusProvider {
+    fn remote_sync_status(&self) -> RemoteSyncStatus {
+        let shard_slots = self
+            .shard_slots
+            .read()
+            .expect("RwLock of shard slots is poisoned")
+            .clone();
+        let remote_slot = shard_slots.values().copied().max();
+        let mut shards = shard_slots
+            .into_iter()
+            .map(|(shard_id, slot)| ShardSyncStatus {
+                shard_id,
+                slot,
+                lag: remote_slot.unwrap_or(slot).saturating_sub(slot),
+            })
+            .collect::<Vec<_>>();
+        shards.sort_by(|a, b| a.shard_id.cmp(&b.shard_id));
+
+        // Each clone in progress has listeners until it completes
+        let pending_clones = self
+            .clone_listeners
+            .read()
+            .expect("RwLock of clone listeners is poisoned")
+            .len();
+        let scheduled_commits_processor =
+            &self.accounts_manager.scheduled_commits_processor;
+        RemoteSyncStatus {
+            remote_slot,
+            shards,
+            pending_clones,
+            scheduled_commits: scheduled_commits_processor
+                .scheduled_commits_len(),
+            pending_commits: scheduled_commits_processor.pending_commits_len(),
+        }
+    }
+}
@@ -1,12 +1,33 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use log::*;
 use magicblock_bank::bank::Bank;
 use magicblock_ledger::{errors::LedgerResult, Ledger};
-use solana_sdk::clock::Slot;
+use magicblock_transaction_status::{FreezeOutcome, TransactionStatusSender};
+use solana_sdk::{clock::Slot, hash::Hash};
 
+use crate::errors::ApiResult;
+
+/// How long to wait for the transactions of the completed slot to be written
+/// before writing its block
+const FREEZE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Advances the slot and writes the block of the completed one, the buffered
+/// transactions of that slot are written to the ledger first if the sender
+/// of their statuses is provided.
+///
+/// Executed transactions are only buffered in memory until their slot
+/// completes or enough of them accumulated, if the validator crashes before
+/// they were written they are missing from the ledger although their effects
+/// may already be part of the accountsdb. Buffering trades this durability
+/// for fewer ledger writes.
+/// If the ledger writer stalls the slots keep advancing without waiting for
+/// it, the blocks written in the meantime may then be missing transactions
+/// which are written once the writer caught up.
 pub fn advance_slot_and_update_ledger(
     bank: &Bank,
     ledger: &Ledger,
+    transaction_status_sender: Option<&TransactionStatusSender>,
 ) -> (LedgerResult<()>, Slot) {
     let prev_slot = bank.slot();
     let prev_blockhash = bank.last_blockhash();
@@ -21,15 +42,63 @@ pub fn advance_slot_and_update_ledger(
     // should not exceed a few milliseconds.
     let next_slot = bank.advance_slot();
 
+    // The transactions of the completed slot are written before its block,
+    // so that a block in the ledger doesn't miss any of its transactions
+    // unless the ledger writer stalled
+    if let Some(transaction_status_sender) = transaction_status_sender {
+        match transaction_status_sender
+            .freeze_transaction_statuses(prev_slot, FREEZE_TIMEOUT)
+        {
+            FreezeOutcome::Flushed => {}
+            FreezeOutcome::TimedOut => warn!(
+                "Transactions of slot {} were not written within {:?}, the ledger writer stalled. Writing blocks without waiting for it until it caught up",
+                prev_slot, FREEZE_TIMEOUT
+            ),
+            FreezeOutcome::Stalled => debug!(
+                "Ledger writer still stalled, writing block of slot {} without waiting for its transactions",
+                prev_slot
+            ),
+        }
+    }
+
     // Update ledger with previous block's metas
-    let ledger_result = ledger.write_block(
-        prev_slot,
-        timestamp_in_secs() as i64,
-        prev_blockhash,
-    );
+    let ledger_result = ledger
+        .write_block(prev_slot, timestamp_in_secs() as i64, prev_blockhash)
+        .and_then(|_| write_snapshot_accounts_hash(bank, ledger, next_slot));
     (ledger_result, next_slot)
 }
 
+/// Advances the slot one last time before shutting down and snapshots the
+/// accountsdb at the new slot, so that none of the transactions of the
+/// completed slot are replayed from the ledger on restart
+pub fn advance_slot_and_take_final_snapshot(
+    bank: &Bank,
+    ledger: &Ledger,
+    transaction_status_sender: Option<&TransactionStatusSender>,
+) -> ApiResult<Slot> {
+    let (ledger_result, next_slot) =
+        advance_slot_and_update_ledger(bank, ledger, transaction_status_sender);
+    ledger_result?;
+    let snapshot_slot = bank.accounts_db.take_final_snapshot()?;
+    write_snapshot_accounts_hash(bank, ledger, snapshot_slot)?;
+    Ok(snapshot_slot)
+}
+
+/// If a snapshot was taken when advancing to `slot` its checksum is recorded
+/// in the ledger, which allows to verify the snapshot on restart
+fn write_snapshot_accounts_hash(
+    bank: &Bank,
+    ledger: &Ledger,
+    slot: Slot,
+) -> LedgerResult<()> {
+    match bank.accounts_db.latest_snapshot_checksum() {
+        Some((snapshot_slot, checksum)) if snapshot_slot == slot => {
+            ledger.write_accounts_hash(slot, &Hash::new_from_array(checksum))
+        }
+        _ => Ok(()),
+    }
+}
+
 fn timestamp_in_secs() -> u64 {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -1,9 +1,9 @@
 use std::{
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc,
+        Arc, RwLock,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use log::*;
@@ -17,10 +17,12 @@ use magicblock_program::{
     magicblock_instruction::accept_scheduled_commits, MagicContext,
 };
 use magicblock_transaction_status::TransactionStatusSender;
-use solana_sdk::account::ReadableAccount;
+use solana_sdk::{account::ReadableAccount, clock::Clock};
 use tokio_util::sync::CancellationToken;
 
-use crate::slot::advance_slot_and_update_ledger;
+use crate::{
+    diagnostics::abort_with_diagnostics, slot::advance_slot_and_update_ledger,
+};
 
 pub fn init_slot_ticker(
     bank: &Arc<Bank>,
@@ -28,27 +30,74 @@ pub fn init_slot_ticker(
     transaction_status_sender: Option<TransactionStatusSender>,
     ledger: Arc<Ledger>,
     tick_duration: Duration,
+    max_idle_duration: Option<Duration>,
+    remote_clock: Option<Arc<RwLock<Option<Clock>>>>,
     exit: Arc<AtomicBool>,
 ) -> tokio::task::JoinHandle<()> {
     let bank = bank.clone();
     let accounts_manager = accounts_manager.clone();
     let log = tick_duration >= Duration::from_secs(5);
     tokio::task::spawn(async move {
+        let mut last_slot_at = Instant::now();
+        let mut last_transaction_count = bank.transaction_count();
+        let mut last_remote_clock_slot = None;
         while !exit.load(Ordering::Relaxed) {
             tokio::time::sleep(tick_duration).await;
 
+            // When producing slots on demand we skip empty ones until the
+            // validator was idle for too long
+            if let Some(max_idle_duration) = max_idle_duration {
+                if bank.transaction_count() == last_transaction_count
+                    && last_slot_at.elapsed() < max_idle_duration
+                {
+                    continue;
+                }
+                last_slot_at = Instant::now();
+                last_transaction_count = bank.transaction_count();
+            }
+
+            // Re-anchor the clock of the next slot whenever the base layer
+            // advanced, a stale sample would hold back the time
+            if let Some(remote_clock) = &remote_clock {
+                let remote_clock = remote_clock
+                    .read()
+                    .expect("RwLock of remote clock poisoned")
+                    .clone();
+                if let Some(remote_clock) = remote_clock.filter(|clock| {
+                    last_remote_clock_slot
+                        .map_or(true, |slot| clock.slot > slot)
+                }) {
+                    bank.set_remote_clock_anchor(&remote_clock);
+                    last_remote_clock_slot = Some(remote_clock.slot);
+                }
+            }
+
             let (update_ledger_result, next_slot) =
-                advance_slot_and_update_ledger(&bank, &ledger);
+                advance_slot_and_update_ledger(
+                    &bank,
+                    &ledger,
+                    transaction_status_sender.as_ref(),
+                );
             if let Err(err) = update_ledger_result {
                 error!("Failed to write block: {:?}", err);
             }
 
             // If accounts were scheduled to be committed, we accept them here
             // and processs the commits
-            let magic_context_acc = bank.get_account(&magic_program::MAGIC_CONTEXT_PUBKEY)
-                .expect("Validator found to be running without MagicContext account!");
+            let Some(magic_context_acc) =
+                bank.get_account(&magic_program::MAGIC_CONTEXT_PUBKEY)
+            else {
+                abort_with_diagnostics(
+                    "Validator found to be running without MagicContext account",
+                );
+            };
 
-            if MagicContext::has_scheduled_commits(magic_context_acc.data()) {
+            if MagicContext::has_scheduled_commits(magic_context_acc.data())
+                || MagicContext::has_due_recurring_commits(
+                    magic_context_acc.data(),
+                    bank.slot(),
+                )
+            {
                 // 1. Send the transaction to move the scheduled commits from the MagicContext
                 //    to the global ScheduledCommit store
                 let tx = accept_scheduled_commits(bank.last_blockhash());
@@ -58,24 +107,31 @@ pub fn init_slot_ticker(
                     transaction_status_sender.as_ref(),
                 ) {
                     error!("Failed to accept scheduled commits: {:?}", err);
-                } else {
-                    // 2. Process those scheduled commits
-                    // TODO: fix the possible delay here
-                    // https://github.com/magicblock-labs/magicblock-validator/issues/104
-                    if let Err(err) =
-                        accounts_manager.process_scheduled_commits().await
-                    {
-                        error!(
-                            "Failed to process scheduled commits: {:?}",
-                            err
-                        );
-                    }
+                }
+            }
+            // 2. Process the scheduled commits that are due, which includes commits
+            //    accepted in previous slots that were scheduled in advance
+            // TODO: fix the possible delay here
+            // https://github.com/magicblock-labs/magicblock-validator/issues/104
+            let scheduled_commits = accounts_manager.scheduled_commits_len();
+            metrics::set_scheduled_commits_count(scheduled_commits);
+            if scheduled_commits > 0 {
+                if let Err(err) =
+                    accounts_manager.process_scheduled_commits().await
+                {
+                    error!("Failed to process scheduled commits: {:?}", err);
                 }
             }
             if log {
-                info!("Advanced to slot {}", next_slot);
+                info!(slot = next_slot; "Advanced to slot {}", next_slot);
             }
             metrics::inc_slot();
+            let program_cache = bank.take_program_cache_stats();
+            metrics::inc_program_cache_activity(
+                program_cache.hits,
+                program_cache.misses,
+                program_cache.evictions,
+            );
         }
     })
 }
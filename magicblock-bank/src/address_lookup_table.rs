@@ -50,6 +50,15 @@ impl Bank {
                 table_account.data(),
             )
             .map_err(|_ix_err| AddressLoaderError::InvalidAccountData)?;
+            // Tables cloned from the remote cluster were extended at slots of
+            // that cluster which are ahead of ours. We treat all of their
+            // addresses as active since the extension already happened.
+            let last_extended_slot = lookup_table.meta.last_extended_slot;
+            let current_slot = if last_extended_slot > current_slot {
+                last_extended_slot.saturating_add(1)
+            } else {
+                current_slot
+            };
 
             Ok(LoadedAddresses {
                 writable: lookup_table
@@ -13,11 +13,15 @@ use std::{
     time::Duration,
 };
 
-use log::{debug, info, trace};
+use log::{debug, info, trace, warn};
 use magicblock_accounts_db::{
     config::AccountsDbConfig, error::AccountsDbError, AccountsDb, StWLock,
 };
-use magicblock_core::traits::FinalityProvider;
+use magicblock_core::{
+    magic_program,
+    traits::{FinalityProvider, ProvidesAccountsSnapshot},
+};
+use magicblock_program::SessionKeyRegistry;
 use solana_accounts_db::{
     accounts_update_notifier_interface::AccountsUpdateNotifierInterface,
     blockhash_queue::BlockhashQueue,
@@ -51,7 +55,8 @@ use solana_sdk::{
     feature,
     feature_set::{
         self, curve25519_restrict_msm_length, curve25519_syscall_enabled,
-        disable_rent_fees_collection, FeatureSet,
+        disable_rent_fees_collection, move_precompile_verification_to_svm,
+        FeatureSet, FEATURE_NAMES,
     },
     fee::{FeeBudgetLimits, FeeDetails, FeeStructure},
     fee_calculator::FeeRateGovernor,
@@ -64,6 +69,7 @@ use solana_sdk::{
     packet::PACKET_DATA_SIZE,
     precompiles::get_precompiles,
     pubkey::Pubkey,
+    recent_blockhashes_account,
     rent_collector::RentCollector,
     rent_debits::RentDebits,
     signature::Signature,
@@ -83,6 +89,7 @@ use solana_svm::{
     },
     account_overrides::AccountOverrides,
     nonce_info::NonceInfo,
+    program_loader::load_program_with_pubkey,
     rollback_accounts::RollbackAccounts,
     runtime_config::RuntimeConfig,
     transaction_commit_result::{
@@ -111,18 +118,20 @@ use crate::{
         inherit_specially_retained_account_fields, update_sysvar_data,
     },
     builtins::{BuiltinPrototype, BUILTINS},
-    geyser::AccountsUpdateNotifier,
+    geyser::{AccountsUpdateNotifier, BlockMetadataNotifier},
     status_cache::StatusCache,
     transaction_batch::TransactionBatch,
     transaction_logs::{
         TransactionLogCollector, TransactionLogCollectorConfig,
+        TransactionLogFilter,
     },
     transaction_results::{
         LoadAndExecuteTransactionsOutput, ProcessedTransactionCounts,
         TransactionBalances, TransactionBalancesSet,
     },
     transaction_simulation::TransactionSimulationResult,
-    LAMPORTS_PER_SIGNATURE,
+    LAMPORTS_PER_SIGNATURE, MAX_ACCOUNTS_DATA_GROWTH_PER_SLOT,
+    MAX_ACCOUNTS_DATA_GROWTH_PER_TRANSACTION,
 };
 
 pub type BankStatusCache = StatusCache<Result<()>>;
@@ -187,6 +196,29 @@ pub struct Bank {
     // `transaction_log_collector_config`
     pub transaction_log_collector: Arc<RwLock<TransactionLogCollector>>,
 
+    /// Applied to the logs of executed and simulated transactions
+    transaction_log_filter: TransactionLogFilter,
+
+    /// If set, transactions paid by a registered session key which only
+    /// invoke the programs it was registered for are not charged any fees
+    sponsor_session_key_fees: bool,
+
+    /// Decoded registry of the session keys account, cleared whenever the
+    /// account is stored so that it is only decoded again once it changed
+    session_key_registry: RwLock<Option<Arc<SessionKeyRegistry>>>,
+
+    /// Max serialized size of transactions the bank accepts, larger than a
+    /// packet only if transactions are not received via UDP
+    max_transaction_size: usize,
+
+    /// Programs kept compiled in the program cache, they are reloaded once
+    /// the next slot starts if they were evicted
+    pinned_programs: HashSet<Pubkey>,
+
+    /// If set, the Clock sysvar follows the time and epoch of the base layer
+    /// instead of being derived from the local system time only
+    remote_clock_anchor: RwLock<Option<RemoteClockAnchor>>,
+
     transaction_debug_keys: Option<Arc<HashSet<Pubkey>>>,
 
     /// A cache of signature statuses
@@ -221,6 +253,13 @@ pub struct Bank {
     /// The number of signatures from valid transactions in this slot
     signature_count: AtomicU64,
 
+    /// Limits on how much transactions may grow the accounts data
+    accounts_data_growth_limits: AccountsDataGrowthLimits,
+
+    /// The growth of the accounts data due to transactions executed in the
+    /// current slot, checked against [AccountsDataGrowthLimits::max_per_slot]
+    accounts_data_growth_in_slot: AtomicU64,
+
     // -----------------
     // Genesis related
     // -----------------
@@ -289,6 +328,7 @@ pub struct Bank {
     // -----------------
     slot_status_notifier: Option<SlotStatusNotifierImpl>,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
+    block_metadata_notifier: Option<BlockMetadataNotifier>,
     // for compatibility, some RPC code needs that flag, which we set to true immediately
     accounts_verified: Arc<AtomicBool>,
 }
@@ -382,6 +422,68 @@ impl TransactionProcessingCallback for Bank {
     }
 }
 
+/// Max number of bytes by which executed transactions may grow the accounts
+/// data. Transactions exceeding a limit fail and their account changes are
+/// rolled back.
+/// This protects the accounts db which is backed by a memory mapped file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountsDataGrowthLimits {
+    pub max_per_transaction: u64,
+    pub max_per_slot: u64,
+}
+
+impl Default for AccountsDataGrowthLimits {
+    fn default() -> Self {
+        Self {
+            max_per_transaction: MAX_ACCOUNTS_DATA_GROWTH_PER_TRANSACTION,
+            max_per_slot: MAX_ACCOUNTS_DATA_GROWTH_PER_SLOT,
+        }
+    }
+}
+
+/// Runtime features to toggle on top of the ones the bank activates by
+/// default. Deactivations are applied last.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeatureOverrides {
+    pub activate: Vec<Pubkey>,
+    pub deactivate: Vec<Pubkey>,
+}
+
+impl FeatureOverrides {
+    fn apply(&self, feature_set: &mut FeatureSet) {
+        for feature_id in self.activate.iter().chain(&self.deactivate) {
+            if !FEATURE_NAMES.contains_key(feature_id) {
+                warn!("Overriding unknown runtime feature {}", feature_id);
+            }
+        }
+        for feature_id in &self.activate {
+            feature_set.activate(feature_id, 0);
+        }
+        for feature_id in &self.deactivate {
+            feature_set.deactivate(feature_id);
+        }
+    }
+}
+
+/// Activity of the program cache, a miss means a program had to be loaded
+/// and compiled before it could be executed
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramCacheActivity {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+/// The base layer clock the Clock sysvar is aligned with
+#[derive(Debug, Clone, Copy)]
+struct RemoteClockAnchor {
+    /// Seconds the base layer time is ahead of the local system time
+    unix_timestamp_offset: UnixTimestamp,
+    epoch_start_timestamp: UnixTimestamp,
+    epoch: Epoch,
+    leader_schedule_epoch: Epoch,
+}
+
 #[derive(Default)]
 pub struct TransactionExecutionRecordingOpts {
     pub enable_cpi_recording: bool,
@@ -431,6 +533,7 @@ impl Bank {
         lock: StWLock,
         adb_path: &Path,
         adb_init_slot: Slot,
+        feature_overrides: &FeatureOverrides,
     ) -> std::result::Result<Self, AccountsDbError> {
         // TODO(bmuddha): When we transition to multi-threaded mode with multiple SVM workers,
         // every transaction should acquire the read guard on this lock before executing.
@@ -441,11 +544,15 @@ impl Bank {
         // this is the only place where we have a mutable access to the AccountsDb
         // before it's wrapped in Arc, and thus becomes immutable
         accounts_db.ensure_at_most(adb_init_slot)?;
+        // capture the state before the bank initialization writes to it, this
+        // is used to verify snapshots against the ledger when processing it
+        accounts_db.capture_startup_checksum();
 
         let mut bank = Self::default_with_accounts(
             accounts_db,
             accounts_update_notifier,
             millis_per_slot,
+            feature_overrides,
         );
         bank.fee_rate_governor.lamports_per_signature = LAMPORTS_PER_SIGNATURE;
 
@@ -465,6 +572,7 @@ impl Bank {
         bank.update_clock(genesis_config.creation_time, None);
         bank.update_rent();
         bank.update_fees();
+        bank.update_recent_blockhashes();
         bank.update_epoch_schedule();
         bank.update_last_restart_slot();
 
@@ -482,6 +590,7 @@ impl Bank {
         adb: AccountsDb,
         accounts_update_notifier: Option<AccountsUpdateNotifier>,
         millis_per_slot: u64,
+        feature_overrides: &FeatureOverrides,
     ) -> Self {
         // NOTE: this was not part of the original implementation
 
@@ -505,6 +614,11 @@ impl Bank {
         feature_set.activate(&disable_rent_fees_collection::ID, 0);
         feature_set.activate(&curve25519_syscall_enabled::ID, 0);
         feature_set.activate(&curve25519_restrict_msm_length::ID, 0);
+        // Precompile instructions are otherwise only verified during sigverify
+        // which we skip for precompiles when sending transactions
+        feature_set.activate(&move_precompile_verification_to_svm::ID, 0);
+        // Operators may adjust the above to their needs
+        feature_overrides.apply(&mut feature_set);
 
         let mut bank = Self {
             accounts_db: adb,
@@ -518,6 +632,12 @@ impl Bank {
             >::default(),
             transaction_log_collector:
                 Arc::<RwLock<TransactionLogCollector>>::default(),
+            transaction_log_filter: TransactionLogFilter::default(),
+            sponsor_session_key_fees: false,
+            session_key_registry: RwLock::default(),
+            max_transaction_size: PACKET_DATA_SIZE,
+            pinned_programs: HashSet::default(),
+            remote_clock_anchor: RwLock::default(),
             fee_structure: FeeStructure::default(),
             transaction_processor: Default::default(),
             fork_graph: Arc::<RwLock<SimpleForkGraph>>::default(),
@@ -534,6 +654,8 @@ impl Bank {
             transactions_per_entry_max: AtomicU64::default(),
             accounts_data_size_delta_on_chain: AtomicI64::default(),
             accounts_data_size_delta_off_chain: AtomicI64::default(),
+            accounts_data_growth_limits: AccountsDataGrowthLimits::default(),
+            accounts_data_growth_in_slot: AtomicU64::default(),
             signature_count: AtomicU64::default(),
 
             // Genesis related
@@ -561,6 +683,7 @@ impl Bank {
             // Geyser
             slot_status_notifier: Option::<SlotStatusNotifierImpl>::default(),
             accounts_update_notifier,
+            block_metadata_notifier: None,
             accounts_verified: Arc::default(),
         };
 
@@ -596,7 +719,7 @@ impl Bank {
                 .iter()
                 .chain(additional_builtins.unwrap_or(&[]).iter())
             {
-                if builtin.feature_id.is_none() {
+                if self.is_feature_enabled(builtin.feature_id) {
                     self.transaction_processor.read().unwrap().add_builtin(
                         self,
                         builtin.program_id,
@@ -610,7 +733,7 @@ impl Bank {
                 }
             }
             for precompile in get_precompiles() {
-                if precompile.feature.is_none() {
+                if self.is_feature_enabled(precompile.feature) {
                     self.add_precompile(&precompile.program_id);
                 }
             }
@@ -638,6 +761,13 @@ impl Bank {
         self.sync_loaded_programs_cache_to_slot();
     }
 
+    /// Programs gated by a feature are only added if it is active, i.e.
+    /// the secp256r1 precompile
+    fn is_feature_enabled(&self, feature_id: Option<Pubkey>) -> bool {
+        feature_id
+            .is_none_or(|feature_id| self.feature_set.is_active(&feature_id))
+    }
+
     fn sync_loaded_programs_cache_to_slot(&self) {
         let txp = self.transaction_processor.read().unwrap();
         let mut loaded_programs_cache = txp.program_cache.write().unwrap();
@@ -645,6 +775,90 @@ impl Bank {
         loaded_programs_cache.latest_root_epoch = self.epoch();
     }
 
+    /// Compiles the programs and adds them to the program cache unless they
+    /// are cached already, which avoids the latency of loading them when they
+    /// are first invoked.
+    /// Returns the programs that were loaded, programs which don't exist or
+    /// fail verification are skipped.
+    pub fn warm_up_program_cache(&self, program_ids: &[Pubkey]) -> Vec<Pubkey> {
+        let txp = self.transaction_processor.read().unwrap();
+        let (cached, environments) = {
+            let program_cache = txp.program_cache.read().unwrap();
+            let cached = program_cache
+                .get_flattened_entries(true, true)
+                .into_iter()
+                .map(|(program_id, _)| program_id)
+                .collect::<HashSet<_>>();
+            let environments = program_cache
+                .get_environments_for_epoch(self.epoch())
+                .clone();
+            (cached, environments)
+        };
+
+        let mut loaded = vec![];
+        for program_id in program_ids {
+            if cached.contains(program_id) {
+                continue;
+            }
+            let Some(entry) = load_program_with_pubkey(
+                self,
+                &environments,
+                program_id,
+                self.slot(),
+                &mut ExecuteTimings::default(),
+                false,
+            ) else {
+                continue;
+            };
+            if entry.is_tombstone() {
+                warn!("Failed to warm up program cache for {}", program_id);
+                continue;
+            }
+            txp.program_cache
+                .write()
+                .unwrap()
+                .assign_program(*program_id, entry);
+            loaded.push(*program_id);
+        }
+        loaded
+    }
+
+    pub fn pinned_programs(&self) -> &HashSet<Pubkey> {
+        &self.pinned_programs
+    }
+
+    pub fn set_pinned_programs(&mut self, program_ids: HashSet<Pubkey>) {
+        self.pinned_programs = program_ids;
+    }
+
+    /// Reloads pinned programs that were evicted from the program cache or
+    /// were not cloned yet when they were last checked
+    fn reload_pinned_programs(&self) {
+        if self.pinned_programs.is_empty() {
+            return;
+        }
+        let program_ids =
+            self.pinned_programs.iter().copied().collect::<Vec<_>>();
+        for program_id in self.warm_up_program_cache(&program_ids) {
+            debug!("Reloaded pinned program {}", program_id);
+        }
+    }
+
+    /// Takes the hits, misses and evictions of the program cache since they
+    /// were last taken
+    pub fn take_program_cache_stats(&self) -> ProgramCacheActivity {
+        let txp = self.transaction_processor.read().unwrap();
+        let mut program_cache = txp.program_cache.write().unwrap();
+        let stats = &program_cache.stats;
+        let activity = ProgramCacheActivity {
+            hits: stats.hits.load(Ordering::Relaxed),
+            misses: stats.misses.load(Ordering::Relaxed),
+            evictions: stats.evictions.values().sum(),
+        };
+        program_cache.stats.reset();
+        activity
+    }
+
     // -----------------
     // Genesis
     // -----------------
@@ -735,6 +949,8 @@ impl Bank {
         // Determine next slot and set it
         let prev_slot = self.slot();
         let next_slot = prev_slot + 1;
+        // Time of the block being frozen, before the clock moves on
+        let prev_block_time = self.clock().unix_timestamp;
         self.set_next_slot(next_slot);
         self.update_sysvars(self.genesis_creation_time, None);
 
@@ -757,15 +973,17 @@ impl Bank {
         };
 
         // Register the new blockhash with the blockhash queue
-        {
-            let mut blockhash_queue = self.blockhash_queue.write().unwrap();
-            blockhash_queue.register_hash(
-                &blockhash,
-                self.fee_rate_governor.lamports_per_signature,
-            );
-        }
+        self.register_hash(&blockhash);
 
         // Notify Geyser Service
+        if let Some(block_metadata_notifier) = &self.block_metadata_notifier {
+            block_metadata_notifier.notify_block_frozen(
+                prev_slot,
+                &current_hash,
+                prev_block_time,
+                self.transaction_count(),
+            );
+        }
         if let Some(slot_status_notifier) = &self.slot_status_notifier {
             slot_status_notifier
                 .notify_slot_rooted(next_slot, Some(next_slot - 1));
@@ -773,6 +991,7 @@ impl Bank {
 
         // Update loaded programs cache as otherwise we cannot deploy new programs
         self.sync_loaded_programs_cache_to_slot();
+        self.reload_pinned_programs();
 
         self.update_slot_hashes_and_slot_history(prev_slot, current_hash);
 
@@ -870,6 +1089,7 @@ impl Bank {
     /// fn store the single `account` with `pubkey`.
     pub fn store_account(&self, pubkey: Pubkey, account: AccountSharedData) {
         self.accounts_db.insert_account(&pubkey, &account);
+        self.clear_session_key_registry_if_stored(&pubkey);
         if let Some(notifier) = &self.accounts_update_notifier {
             let slot = self.slot();
             notifier.notify_account_update(slot, &account, &None, &pubkey, 0);
@@ -888,6 +1108,7 @@ impl Bank {
         let slot = self.slot();
         for (pubkey, acc) in accounts {
             self.accounts_db.insert_account(&pubkey, &acc);
+            self.clear_session_key_registry_if_stored(&pubkey);
             if let Some(notifier) = &self.accounts_update_notifier {
                 notifier.notify_account_update(slot, &acc, &None, &pubkey, 0);
             }
@@ -1056,7 +1277,7 @@ impl Bank {
         // and confirmed that the timestamps match
 
         let slot = self.slot();
-        let clock = sysvar::clock::Clock {
+        let mut clock = sysvar::clock::Clock {
             slot,
             epoch_start_timestamp,
             epoch: self.epoch_schedule().get_epoch(slot),
@@ -1065,6 +1286,21 @@ impl Bank {
                 .get_leader_schedule_epoch(slot),
             unix_timestamp,
         };
+        // When replaying the ledger the recorded timestamp is used as is
+        let remote_clock_anchor = *self
+            .remote_clock_anchor
+            .read()
+            .expect("RwLock of remote clock anchor poisoned");
+        if let (Some(anchor), None) = (remote_clock_anchor, timestamp) {
+            clock.epoch_start_timestamp = anchor.epoch_start_timestamp;
+            clock.epoch = anchor.epoch;
+            clock.leader_schedule_epoch = anchor.leader_schedule_epoch;
+            // Sampling a base layer clock which is slightly behind the
+            // previous sample must not move time backwards
+            clock.unix_timestamp = unix_timestamp
+                .saturating_add(anchor.unix_timestamp_offset)
+                .max(self.clock().unix_timestamp);
+        }
         self.update_sysvar_account(&sysvar::clock::id(), |account| {
             update_sysvar_data(&clock, account)
         });
@@ -1094,6 +1330,29 @@ impl Bank {
         }
     }
 
+    fn update_recent_blockhashes(&self) {
+        let blockhash_queue = self.blockhash_queue.read().unwrap();
+        self.update_recent_blockhashes_locked(&blockhash_queue);
+    }
+
+    fn update_recent_blockhashes_locked(
+        &self,
+        locked_blockhash_queue: &BlockhashQueue,
+    ) {
+        #[allow(deprecated)]
+        self.update_sysvar_account(
+            &sysvar::recent_blockhashes::id(),
+            |account| {
+                let recent_blockhash_iter =
+                    locked_blockhash_queue.get_recent_blockhashes();
+                recent_blockhashes_account::create_account_with_data_and_fields(
+                    recent_blockhash_iter,
+                    self.inherit_specially_retained_account_fields(&account),
+                )
+            },
+        );
+    }
+
     fn update_epoch_schedule(&self) {
         self.update_sysvar_account(&sysvar::epoch_schedule::id(), |account| {
             update_sysvar_data(self.epoch_schedule(), account)
@@ -1237,6 +1496,59 @@ impl Bank {
         }
     }
 
+    pub fn set_accounts_data_growth_limits(
+        &mut self,
+        limits: AccountsDataGrowthLimits,
+    ) {
+        self.accounts_data_growth_limits = limits;
+    }
+
+    /// Notifies geyser plugins of each block as its slot is frozen
+    pub fn set_block_metadata_notifier(
+        &mut self,
+        notifier: BlockMetadataNotifier,
+    ) {
+        notifier.set_transaction_count(self.transaction_count());
+        self.block_metadata_notifier = Some(notifier);
+    }
+
+    pub fn set_transaction_log_filter(&mut self, filter: TransactionLogFilter) {
+        self.transaction_log_filter = filter;
+    }
+
+    pub fn set_sponsor_session_key_fees(&mut self, sponsor: bool) {
+        self.sponsor_session_key_fees = sponsor;
+    }
+
+    pub fn max_transaction_size(&self) -> usize {
+        self.max_transaction_size
+    }
+
+    pub fn set_max_transaction_size(&mut self, max_transaction_size: usize) {
+        self.max_transaction_size = max_transaction_size;
+    }
+
+    /// Aligns the time and epoch of the Clock sysvar with the clock sampled
+    /// from the base layer, starting with the next slot.
+    /// Until the next sample the time keeps advancing with the local system
+    /// time, so the base layer should be sampled while it is current.
+    pub fn set_remote_clock_anchor(&self, remote_clock: &sysvar::clock::Clock) {
+        let local_timestamp =
+            i64::try_from(get_epoch_secs()).expect("get_epoch_secs overflow");
+        *self
+            .remote_clock_anchor
+            .write()
+            .expect("RwLock of remote clock anchor poisoned") =
+            Some(RemoteClockAnchor {
+                unix_timestamp_offset: remote_clock
+                    .unix_timestamp
+                    .saturating_sub(local_timestamp),
+                epoch_start_timestamp: remote_clock.epoch_start_timestamp,
+                epoch: remote_clock.epoch,
+                leader_schedule_epoch: remote_clock.leader_schedule_epoch,
+            });
+    }
+
     // Looks like this is only used in tests since add_precompiled_account_with_owner is as well
     // However `finish_init` is calling this method, so we keep it here
     pub fn add_precompile(&mut self, program_id: &Pubkey) {
@@ -1352,6 +1664,9 @@ impl Bank {
         let next_lamports_per_signature = hash_queue
             .get_lamports_per_signature(&last_blockhash)
             .unwrap();
+        let session_keys = self
+            .sponsor_session_key_fees
+            .then(|| self.session_key_registry());
 
         sanitized_txs
             .iter()
@@ -1362,6 +1677,7 @@ impl Bank {
                     &next_durable_nonce,
                     &hash_queue,
                     next_lamports_per_signature,
+                    session_keys.as_deref(),
                     error_counters,
                 ),
                 Err(e) => Err(e.clone()),
@@ -1375,6 +1691,7 @@ impl Bank {
         next_durable_nonce: &DurableNonce,
         hash_queue: &BlockhashQueue,
         next_lamports_per_signature: u64,
+        session_keys: Option<&SessionKeyRegistry>,
         error_counters: &mut TransactionErrorMetrics,
     ) -> TransactionCheckResult {
         let max_age = self.max_age as usize;
@@ -1382,10 +1699,17 @@ impl Bank {
         if let Some(hash_info) =
             hash_queue.get_hash_info_if_valid(recent_blockhash, max_age)
         {
-            Ok(CheckedTransactionDetails::new(
-                None,
-                hash_info.lamports_per_signature(),
-            ))
+            // The SVM charges no fees if the lamports per signature are zero.
+            // Durable nonce transactions are never sponsored.
+            let lamports_per_signature =
+                if session_keys.is_some_and(|session_keys| {
+                    self.is_sponsored_by_session_key(tx.message(), session_keys)
+                }) {
+                    0
+                } else {
+                    hash_info.lamports_per_signature()
+                };
+            Ok(CheckedTransactionDetails::new(None, lamports_per_signature))
         } else if let Some((nonce, previous_lamports_per_signature)) = self
             .check_load_and_advance_message_nonce_account(
                 tx.message(),
@@ -1402,6 +1726,70 @@ impl Bank {
             Err(TransactionError::BlockhashNotFound)
         }
     }
+
+    /// The registry of the session keys account, only decoded if the
+    /// account changed since it was decoded last
+    fn session_key_registry(&self) -> Arc<SessionKeyRegistry> {
+        if let Some(registry) = self
+            .session_key_registry
+            .read()
+            .expect("RwLock of Bank.session_key_registry is poisoned")
+            .as_ref()
+        {
+            return registry.clone();
+        }
+        // Decoded while holding the write lock, the account is thus either
+        // decoded after it was stored or cleared once the decoding finished
+        let mut cached = self
+            .session_key_registry
+            .write()
+            .expect("RwLock of Bank.session_key_registry is poisoned");
+        cached
+            .get_or_insert_with(|| {
+                let registry = self
+                    .get_account(&magic_program::MAGIC_SESSION_KEYS_PUBKEY)
+                    .and_then(|account| {
+                        SessionKeyRegistry::try_from_data(account.data())
+                            .inspect_err(|err| {
+                                warn!(
+                                    "Failed to deserialize SessionKeyRegistry: {:?}",
+                                    err
+                                )
+                            })
+                            .ok()
+                    })
+                    .unwrap_or_default();
+                Arc::new(registry)
+            })
+            .clone()
+    }
+
+    fn clear_session_key_registry_if_stored(&self, pubkey: &Pubkey) {
+        if pubkey == &magic_program::MAGIC_SESSION_KEYS_PUBKEY {
+            self.session_key_registry
+                .write()
+                .expect("RwLock of Bank.session_key_registry is poisoned")
+                .take();
+        }
+    }
+
+    /// Whether the transaction is paid by a session key that did not expire
+    /// and only invokes the programs the session key was registered for
+    fn is_sponsored_by_session_key(
+        &self,
+        message: &SanitizedMessage,
+        session_keys: &SessionKeyRegistry,
+    ) -> bool {
+        let Some(session_key) =
+            session_keys.get_valid(message.fee_payer(), self.slot())
+        else {
+            return false;
+        };
+        message
+            .program_instructions_iter()
+            .all(|(program_id, _)| session_key.allows_program(program_id))
+    }
+
     pub(super) fn check_load_and_advance_message_nonce_account(
         &self,
         message: &SanitizedMessage,
@@ -1461,9 +1849,17 @@ impl Bank {
         status_cache: &BankStatusCache,
     ) -> bool {
         let signature = sanitized_tx.signature();
+        // The message hash prevents processing the same message again with
+        // a different signature
         status_cache
             .get_recent_transaction_status(signature, Some(self.max_age))
             .is_some()
+            || status_cache
+                .get_status(
+                    sanitized_tx.message_hash(),
+                    sanitized_tx.message().recent_blockhash(),
+                )
+                .is_some()
     }
 
     fn check_status_cache(
@@ -1619,29 +2015,42 @@ impl Bank {
         };
 
         let LoadAndExecuteTransactionsOutput {
-            processing_results,
-            processed_counts,
-        } = self.load_and_execute_transactions(
-            batch,
-            timings,
-            &mut TransactionErrorMetrics::default(),
-            TransactionProcessingConfig {
-                account_overrides: None,
-                check_program_modification_slot: false,
-                compute_budget: None,
-                log_messages_bytes_limit,
-                limit_to_load_programs: false,
-                recording_config,
-                transaction_account_lock_limit: None,
-            },
-        );
-
-        let commit_results = self.commit_transactions(
+            mut processing_results,
+            mut processed_counts,
+        } = tracing::info_span!("execute").in_scope(|| {
+            self.load_and_execute_transactions(
+                batch,
+                timings,
+                &mut TransactionErrorMetrics::default(),
+                TransactionProcessingConfig {
+                    account_overrides: None,
+                    check_program_modification_slot: false,
+                    compute_budget: None,
+                    log_messages_bytes_limit,
+                    limit_to_load_programs: false,
+                    recording_config,
+                    transaction_account_lock_limit: None,
+                },
+            )
+        });
+        let failed_count = self.enforce_accounts_data_growth_limits(
             batch.sanitized_transactions(),
-            processing_results,
-            &processed_counts,
-            timings,
+            &mut processing_results,
         );
+        processed_counts.processed_with_successful_result_count =
+            processed_counts
+                .processed_with_successful_result_count
+                .saturating_sub(failed_count);
+        self.filter_transaction_logs(&mut processing_results);
+
+        let commit_results = tracing::info_span!("commit").in_scope(|| {
+            self.commit_transactions(
+                batch.sanitized_transactions(),
+                processing_results,
+                &processed_counts,
+                timings,
+            )
+        });
         let post_balances = if collect_balances {
             self.collect_balances(batch)
         } else {
@@ -1653,6 +2062,84 @@ impl Bank {
         )
     }
 
+    /// Fails the successfully executed transactions which grew the accounts
+    /// data by more than allowed per transaction or per slot, which results
+    /// in only their fees being committed.
+    /// Since the growth is only known once all instructions executed, the
+    /// per transaction limit is reported as
+    /// [TransactionError::WouldExceedAccountDataTotalLimit] instead of an
+    /// instruction error.
+    /// Transactions paid for by the validator, i.e. the ones cloning
+    /// accounts, are not limited.
+    /// Returns the number of transactions that were failed.
+    fn enforce_accounts_data_growth_limits(
+        &self,
+        sanitized_txs: &[SanitizedTransaction],
+        processing_results: &mut [TransactionProcessingResult],
+    ) -> u64 {
+        let AccountsDataGrowthLimits {
+            max_per_transaction,
+            max_per_slot,
+        } = self.accounts_data_growth_limits;
+        let mut failed_count = 0;
+        for (tx, processing_result) in
+            sanitized_txs.iter().zip(processing_results.iter_mut())
+        {
+            if tx.message().fee_payer().eq(&self.identity_id) {
+                continue;
+            }
+            let Ok(ProcessedTransaction::Executed(executed_tx)) =
+                processing_result
+            else {
+                continue;
+            };
+            let details = &mut executed_tx.execution_details;
+            if details.status.is_err() || details.accounts_data_len_delta <= 0 {
+                continue;
+            }
+            let growth = details.accounts_data_len_delta as u64;
+            let err = if growth > max_per_transaction {
+                TransactionError::WouldExceedAccountDataTotalLimit
+            } else if self
+                .accounts_data_growth_in_slot
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |in_slot| {
+                    in_slot
+                        .checked_add(growth)
+                        .filter(|in_slot| *in_slot <= max_per_slot)
+                })
+                .is_err()
+            {
+                TransactionError::WouldExceedAccountDataBlockLimit
+            } else {
+                continue;
+            };
+            debug!(
+                "Transaction growing accounts data by {} bytes failed: {}",
+                growth, err
+            );
+            details.status = Err(err);
+            failed_count += 1;
+        }
+        failed_count
+    }
+
+    fn filter_transaction_logs(
+        &self,
+        processing_results: &mut [TransactionProcessingResult],
+    ) {
+        for processing_result in processing_results.iter_mut() {
+            if let Ok(ProcessedTransaction::Executed(executed_tx)) =
+                processing_result
+            {
+                if let Some(log_messages) =
+                    executed_tx.execution_details.log_messages.as_mut()
+                {
+                    self.transaction_log_filter.apply(log_messages);
+                }
+            }
+        }
+    }
+
     fn collect_accounts_to_store<'a, T: SVMMessage>(
         txs: &'a [T],
         processing_results: &'a [TransactionProcessingResult],
@@ -1927,7 +2414,7 @@ impl Bank {
         let sanitized_tx = {
             let size = bincode::serialized_size(&tx)
                 .map_err(|_| TransactionError::SanitizeFailure)?;
-            if size > PACKET_DATA_SIZE as u64 {
+            if size > self.max_transaction_size as u64 {
                 return Err(TransactionError::SanitizeFailure);
             }
             let message_hash = if verification_mode
@@ -2011,9 +2498,7 @@ impl Bank {
             lamports_per_signature == 0,
             self.fee_structure.lamports_per_signature,
             fee_budget_limits.prioritization_fee,
-            FeeFeatures {
-                enable_secp256r1_precompile: false,
-            },
+            FeeFeatures::from(self.feature_set.as_ref()),
         )
     }
 
@@ -2104,7 +2589,8 @@ impl Bank {
             },
             Err(error) => (vec![], Err(error), None, None, None),
         };
-        let logs = logs.unwrap_or_default();
+        let mut logs = logs.unwrap_or_default();
+        self.transaction_log_filter.apply(&mut logs);
 
         TransactionSimulationResult {
             result,
@@ -2190,6 +2676,41 @@ impl Bank {
             .get_recent_transaction_status(signature, lookback_slots)
     }
 
+    /// The result of the transaction if it was processed recently enough to
+    /// be rejected as [TransactionError::AlreadyProcessed] when sent again.
+    /// This lets clients retrying to send a transaction learn its original
+    /// result instead.
+    pub fn get_processed_transaction_result(
+        &self,
+        signature: &Signature,
+    ) -> Option<Result<()>> {
+        self.get_recent_signature_status(signature, Some(self.max_age))
+            .map(|(_, result)| result)
+    }
+
+    /// Returns the statuses of the recently executed transactions grouped by
+    /// slot, used to persist the status cache across restarts
+    pub fn recent_transaction_statuses(
+        &self,
+    ) -> Vec<(Slot, Vec<(Signature, Result<()>)>)> {
+        self.status_cache
+            .read()
+            .expect("RwLock status_cache poisoned")
+            .transaction_statuses_by_slot()
+    }
+
+    /// Restores transaction statuses persisted before a restart such that
+    /// transactions that executed before are rejected as already processed
+    pub fn restore_transaction_statuses(
+        &self,
+        slot_statuses: Vec<(Slot, Vec<(Signature, Result<()>)>)>,
+    ) {
+        self.status_cache
+            .write()
+            .expect("RwLock status_cache poisoned")
+            .restore_transaction_statuses(slot_statuses)
+    }
+
     // -----------------
     // Counters
     // -----------------
@@ -2388,6 +2909,9 @@ impl Bank {
         let mut blockhash_queue = self.blockhash_queue.write().unwrap();
         blockhash_queue
             .register_hash(hash, self.fee_rate_governor.lamports_per_signature);
+        // Durable nonce instructions of the system program require the
+        // recent blockhashes sysvar to be present
+        self.update_recent_blockhashes_locked(&blockhash_queue);
     }
 
     // -----------------
@@ -2395,6 +2919,8 @@ impl Bank {
     // -----------------
     fn set_next_slot(&self, next_slot: Slot) {
         self.set_slot(next_slot);
+        self.accounts_data_growth_in_slot
+            .store(0, Ordering::Release);
 
         let tx_processor = self.transaction_processor.write().unwrap();
         // Update transaction processor with new slot
@@ -2565,3 +3091,20 @@ impl FinalityProvider for Bank {
         self.accounts_db.get_oldest_snapshot_slot().unwrap_or(0)
     }
 }
+
+impl ProvidesAccountsSnapshot for Bank {
+    fn snapshot_slot(&self) -> Slot {
+        self.slot()
+    }
+
+    fn snapshot_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.get_account(pubkey)
+    }
+
+    fn snapshot_program_accounts(
+        &self,
+        program_id: &Pubkey,
+    ) -> Vec<(Pubkey, AccountSharedData)> {
+        self.get_filtered_program_accounts(program_id, |_| true)
+    }
+}
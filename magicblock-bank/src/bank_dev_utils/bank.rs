@@ -20,7 +20,9 @@ use solana_svm::{
 use solana_timings::ExecuteTimings;
 
 use crate::{
-    bank::Bank, geyser::AccountsUpdateNotifier,
+    bank::{Bank, FeatureOverrides},
+    builtins::BuiltinPrototype,
+    geyser::AccountsUpdateNotifier,
     transaction_batch::TransactionBatch,
     transaction_logs::TransactionLogCollectorFilter,
     EPHEM_DEFAULT_MILLIS_PER_SLOT,
@@ -49,6 +51,24 @@ impl Bank {
         millis_per_slot: u64,
     ) -> std::result::Result<Bank, magicblock_accounts_db::error::AccountsDbError>
     {
+        Self::new_with_builtins_for_tests(
+            genesis_config,
+            runtime_config,
+            accounts_update_notifier,
+            slot_status_notifier,
+            millis_per_slot,
+            &[],
+        )
+    }
+
+    pub fn new_with_builtins_for_tests(
+        genesis_config: &GenesisConfig,
+        runtime_config: Arc<RuntimeConfig>,
+        accounts_update_notifier: Option<AccountsUpdateNotifier>,
+        slot_status_notifier: Option<SlotStatusNotifierImpl>,
+        millis_per_slot: u64,
+        additional_builtins: &[BuiltinPrototype],
+    ) -> std::result::Result<Bank, AccountsDbError> {
         let accountsdb_config = AccountsDbConfig::temp_for_tests(500);
         let adb_path = tempfile::tempdir()
             .expect("failed to create temp dir for test bank")
@@ -60,7 +80,7 @@ impl Bank {
             runtime_config,
             &accountsdb_config,
             None,
-            None,
+            Some(additional_builtins),
             false,
             accounts_update_notifier,
             slot_status_notifier,
@@ -71,6 +91,7 @@ impl Bank {
             StWLock::default(),
             &adb_path,
             adb_init_slot,
+            &FeatureOverrides::default(),
         )?;
         bank.transaction_log_collector_config
             .write()
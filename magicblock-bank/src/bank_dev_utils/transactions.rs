@@ -6,11 +6,16 @@ use rayon::{
     },
 };
 use solana_sdk::{
-    account::Account,
+    account::{Account, AccountSharedData},
+    ed25519_program,
     hash::Hash,
     instruction::{AccountMeta, Instruction},
     message::{v0::LoadedAddresses, Message},
     native_token::LAMPORTS_PER_SOL,
+    nonce::{
+        self,
+        state::{DurableNonce, Versions},
+    },
     pubkey::Pubkey,
     rent::Rent,
     signature::Keypair,
@@ -163,6 +168,54 @@ pub fn create_system_allocate_transaction(
     )
 }
 
+// Durable Nonce
+/// Creates a nonce account holding the durable nonce derived from the current
+/// blockhash, returns its address and that durable nonce
+pub fn create_nonce_account(bank: &Bank, authority: &Pubkey) -> (Pubkey, Hash) {
+    let nonce_pubkey = Pubkey::new_unique();
+    let durable_nonce = DurableNonce::from_blockhash(&bank.last_blockhash());
+    let nonce_state = Versions::new(nonce::state::State::new_initialized(
+        authority,
+        durable_nonce,
+        LAMPORTS_PER_SIGNATURE,
+    ));
+    let lamports = Rent::default().minimum_balance(nonce::state::State::size());
+    let nonce_account = AccountSharedData::new_data(
+        lamports,
+        &nonce_state,
+        &system_program::id(),
+    )
+    .unwrap();
+    bank.store_account(nonce_pubkey, nonce_account);
+    (nonce_pubkey, *durable_nonce.as_hash())
+}
+
+/// Creates a transfer which uses a durable nonce instead of a recent blockhash,
+/// returns the transaction, the receiver, the nonce account and its nonce
+pub fn create_durable_nonce_transfer_transaction(
+    bank: &Bank,
+    fund_lamports: u64,
+    send_lamports: u64,
+) -> (SanitizedTransaction, Pubkey, Pubkey, Hash) {
+    let from = create_funded_account(bank, Some(fund_lamports));
+    let to = Pubkey::new_unique();
+    let (nonce_pubkey, nonce_hash) = create_nonce_account(bank, &from.pubkey());
+    let tx = system_transaction::nonced_transfer(
+        &from,
+        &to,
+        send_lamports,
+        &nonce_pubkey,
+        &from,
+        nonce_hash,
+    );
+    (
+        SanitizedTransaction::from_transaction_for_tests(tx),
+        to,
+        nonce_pubkey,
+        nonce_hash,
+    )
+}
+
 // Noop
 pub fn create_noop_transaction(
     bank: &Bank,
@@ -252,6 +305,64 @@ fn create_solx_send_post_instruction(
     )
 }
 
+// Precompiles
+/// Creates a transaction with a single instruction of the precompile holding
+/// the provided data
+pub fn create_precompile_transaction(
+    bank: &Bank,
+    program_id: Pubkey,
+    data: Vec<u8>,
+) -> SanitizedTransaction {
+    let payer = create_funded_account(bank, Some(LAMPORTS_PER_SOL));
+    let instruction = Instruction::new_with_bytes(program_id, &data, vec![]);
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        bank.last_blockhash(),
+    );
+    SanitizedTransaction::from_transaction_for_tests(tx)
+}
+
+/// Creates a transaction verifying the ed25519 signature of the message by
+/// the signer, if `valid` is `false` the signature is corrupted
+pub fn create_ed25519_verify_transaction(
+    bank: &Bank,
+    signer: &Keypair,
+    message: &[u8],
+    valid: bool,
+) -> SanitizedTransaction {
+    // The offsets of the signature, public key and message all refer to the
+    // instruction itself which is denoted by u16::MAX
+    const CURRENT_INSTRUCTION: u16 = u16::MAX;
+    const PUBKEY_OFFSET: u16 = 16;
+    const SIGNATURE_OFFSET: u16 = PUBKEY_OFFSET + 32;
+    const MESSAGE_OFFSET: u16 = SIGNATURE_OFFSET + 64;
+
+    let mut signature = signer.sign_message(message).as_ref().to_vec();
+    if !valid {
+        signature[0] ^= 0xff;
+    }
+
+    let mut data = vec![1, 0];
+    for offset in [
+        SIGNATURE_OFFSET,
+        CURRENT_INSTRUCTION,
+        PUBKEY_OFFSET,
+        CURRENT_INSTRUCTION,
+        MESSAGE_OFFSET,
+        message.len() as u16,
+        CURRENT_INSTRUCTION,
+    ] {
+        data.extend_from_slice(&offset.to_le_bytes());
+    }
+    data.extend_from_slice(signer.pubkey().as_ref());
+    data.extend_from_slice(&signature);
+    data.extend_from_slice(message);
+
+    create_precompile_transaction(bank, ed25519_program::id(), data)
+}
+
 // Sysvars
 pub fn create_sysvars_get_transaction(bank: &Bank) -> SanitizedTransaction {
     let funded_accounts = create_funded_accounts(bank, 2, None);
@@ -2,9 +2,14 @@
 use solana_program_runtime::invoke_context::BuiltinFunctionWithContext;
 use solana_sdk::{
     address_lookup_table, bpf_loader_upgradeable, compute_budget,
-    pubkey::Pubkey,
+    precompiles::get_precompiles, pubkey::Pubkey,
 };
 
+/// A native program added to the bank at startup.
+///
+/// Embedders can provide additional builtins to [crate::bank::Bank::new] in
+/// order to ship custom native programs with their validator.
+/// Builtins with a `feature_id` are only added if that feature is active.
 pub struct BuiltinPrototype {
     pub feature_id: Option<Pubkey>,
     pub program_id: Pubkey,
@@ -51,6 +56,10 @@ impl solana_frozen_abi::abi_example::AbiExample for BuiltinPrototype {
 /// - `address_lookup_table_program`
 /// - `magicblock_program` which supports account mutations, etc.
 ///
+/// Additionally the precompiles `ed25519_program` and `secp256k1_program` are
+/// added at startup, as well as `secp256r1_program` once its feature is
+/// activated. Their instructions are verified when executing the transaction.
+///
 /// We don't support the following builtin programs:
 ///
 /// - `vote_program` since we have no votes
@@ -98,3 +107,14 @@ pub static BUILTINS: &[BuiltinPrototype] = &[
             solana_address_lookup_table_program::processor::Entrypoint::vm,
     },
 ];
+
+/// Returns `true` if the program id is taken by one of the [BUILTINS] or
+/// precompiles which the bank adds at startup
+pub fn is_reserved_program_id(program_id: &Pubkey) -> bool {
+    BUILTINS
+        .iter()
+        .any(|builtin| builtin.program_id.eq(program_id))
+        || get_precompiles()
+            .iter()
+            .any(|precompile| precompile.program_id.eq(program_id))
+}
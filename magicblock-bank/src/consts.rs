@@ -1,2 +1,6 @@
 pub const LAMPORTS_PER_SIGNATURE: u64 = 5000;
 pub const EPHEM_DEFAULT_MILLIS_PER_SLOT: u64 = 50;
+/// Matches the max the runtime allows a single transaction to allocate
+pub const MAX_ACCOUNTS_DATA_GROWTH_PER_TRANSACTION: u64 = 20 * 1024 * 1024;
+/// Matches the max a block on the base layer may grow the accounts data
+pub const MAX_ACCOUNTS_DATA_GROWTH_PER_SLOT: u64 = 100_000_000;
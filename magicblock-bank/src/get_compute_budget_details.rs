@@ -10,6 +10,8 @@ use solana_sdk::{
 pub struct ComputeBudgetDetails {
     pub compute_unit_price: u64,
     pub compute_unit_limit: u64,
+    /// Size of the heap frame available to the transaction's programs
+    pub heap_size: u32,
 }
 
 pub trait GetComputeBudgetDetails {
@@ -33,6 +35,7 @@ pub trait GetComputeBudgetDetails {
             compute_unit_limit: u64::from(
                 compute_budget_limits.compute_unit_limit,
             ),
+            heap_size: compute_budget_limits.updated_heap_bytes,
         })
     }
 }
@@ -63,7 +66,9 @@ impl GetComputeBudgetDetails for SanitizedTransaction {
 
 #[cfg(test)]
 mod tests {
-    use solana_compute_budget::compute_budget_limits::DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT;
+    use solana_compute_budget::compute_budget_limits::{
+        DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT, MIN_HEAP_FRAME_BYTES,
+    };
     use solana_sdk::{
         compute_budget::ComputeBudgetInstruction,
         message::Message,
@@ -102,6 +107,7 @@ mod tests {
                 compute_unit_price: 0,
                 compute_unit_limit: DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
                     as u64,
+                heap_size: 32 * 1024,
             })
         );
 
@@ -118,6 +124,7 @@ mod tests {
                 compute_unit_price: 0,
                 compute_unit_limit: DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
                     as u64,
+                heap_size: 32 * 1024,
             })
         );
     }
@@ -149,6 +156,7 @@ mod tests {
             Some(ComputeBudgetDetails {
                 compute_unit_price: 0,
                 compute_unit_limit: requested_cu as u64,
+                heap_size: MIN_HEAP_FRAME_BYTES,
             })
         );
 
@@ -164,6 +172,7 @@ mod tests {
             Some(ComputeBudgetDetails {
                 compute_unit_price: 0,
                 compute_unit_limit: requested_cu as u64,
+                heap_size: MIN_HEAP_FRAME_BYTES,
             })
         );
     }
@@ -198,6 +207,7 @@ mod tests {
                 compute_unit_price: requested_price,
                 compute_unit_limit: DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
                     as u64,
+                heap_size: MIN_HEAP_FRAME_BYTES,
             })
         );
 
@@ -214,6 +224,7 @@ mod tests {
                 compute_unit_price: requested_price,
                 compute_unit_limit: DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
                     as u64,
+                heap_size: MIN_HEAP_FRAME_BYTES,
             })
         );
     }
@@ -9,19 +9,21 @@ use {
         accounts_update_notifier_interface::AccountsUpdateNotifierInterface,
     },
     solana_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
+        ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaBlockInfoV3,
+        ReplicaBlockInfoVersions, ReplicaEntryInfoV2, ReplicaEntryInfoVersions,
         ReplicaTransactionInfoV2, ReplicaTransactionInfoVersions,
     },
     solana_geyser_plugin_manager::geyser_plugin_manager::GeyserPluginManager,
     solana_rpc::transaction_notifier_interface::TransactionNotifier as TransactionNotifierInterface,
     solana_sdk::{
         account::{AccountSharedData, ReadableAccount},
-        clock::Slot,
+        clock::{Slot, UnixTimestamp},
+        hash::Hash,
         signature::Signature,
         transaction::SanitizedTransaction,
     },
     solana_transaction_status::TransactionStatusMeta,
-    std::sync::{Arc, RwLock},
+    std::sync::{Arc, Mutex, RwLock},
 };
 
 /// This implementation of TransactionNotifier is passed to the rpc's TransactionStatusService
@@ -208,3 +210,91 @@ impl AccountsUpdateNotifier {
         }
     }
 }
+
+/// Notifies plugins of each block once its slot is frozen. Since the
+/// ephemeral validator doesn't produce PoH entries, a block consists of a
+/// single entry spanning all of its transactions.
+#[derive(Debug)]
+pub struct BlockMetadataNotifier {
+    plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    /// Blockhash and accumulated transaction count of the last frozen block
+    last_block: Mutex<(Hash, u64)>,
+}
+
+impl BlockMetadataNotifier {
+    pub fn new(plugin_manager: Arc<RwLock<GeyserPluginManager>>) -> Self {
+        Self {
+            plugin_manager,
+            last_block: Mutex::new((Hash::default(), 0)),
+        }
+    }
+
+    /// Transactions counted up to this point are not attributed to the
+    /// next frozen block
+    pub(crate) fn set_transaction_count(&self, transaction_count: u64) {
+        self.last_block.lock().unwrap().1 = transaction_count;
+    }
+
+    pub fn notify_block_frozen(
+        &self,
+        slot: Slot,
+        blockhash: &Hash,
+        block_time: UnixTimestamp,
+        transaction_count: u64,
+    ) {
+        let (parent_blockhash, executed_transaction_count) = {
+            let mut last_block = self.last_block.lock().unwrap();
+            let (parent_blockhash, parent_transaction_count) = *last_block;
+            *last_block = (*blockhash, transaction_count);
+            (
+                parent_blockhash,
+                transaction_count.saturating_sub(parent_transaction_count),
+            )
+        };
+
+        let plugin_manager = self.plugin_manager.read().unwrap();
+        if plugin_manager.plugins.is_empty() {
+            return;
+        }
+
+        let entry = ReplicaEntryInfoV2 {
+            slot,
+            index: 0,
+            num_hashes: 0,
+            hash: blockhash.as_ref(),
+            executed_transaction_count,
+            starting_transaction_index: 0,
+        };
+        let parent_blockhash = parent_blockhash.to_string();
+        let blockhash = blockhash.to_string();
+        let block_info = ReplicaBlockInfoV3 {
+            parent_slot: slot.saturating_sub(1),
+            parent_blockhash: &parent_blockhash,
+            slot,
+            blockhash: &blockhash,
+            rewards: &[],
+            block_time: Some(block_time),
+            block_height: Some(slot),
+            executed_transaction_count,
+            entry_count: 1,
+        };
+        for plugin in plugin_manager.plugins.iter() {
+            if plugin.entry_notifications_enabled() {
+                let _ = plugin
+                    .notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry))
+                    .inspect_err(|err| {
+                        log::error!("failed to notify plugin of entry: {err}")
+                    });
+            }
+            let _ = plugin
+                .notify_block_metadata(ReplicaBlockInfoVersions::V0_0_3(
+                    &block_info,
+                ))
+                .inspect_err(|err| {
+                    log::error!(
+                        "failed to notify plugin of block metadata: {err}"
+                    )
+                });
+        }
+    }
+}
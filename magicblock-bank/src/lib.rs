@@ -1,7 +1,7 @@
 pub mod address_lookup_table;
 pub mod bank;
 mod bank_helpers;
-mod builtins;
+pub mod builtins;
 mod consts;
 pub mod genesis_utils;
 pub mod get_compute_budget_details;
@@ -94,6 +94,45 @@ impl<T: Clone> StatusCache<T> {
         }
     }
 
+    /// Finds the status of the key, i.e. a message hash or signature,
+    /// recorded for a transaction using the blockhash
+    pub fn get_status<K: AsRef<[u8]>>(
+        &self,
+        key: K,
+        transaction_blockhash: &Hash,
+    ) -> Option<(Slot, T)> {
+        let (_, key_index, key_map) =
+            self.cache_by_blockhash.get(transaction_blockhash)?;
+        let max_key_index =
+            key.as_ref().len().saturating_sub(CACHED_KEY_SIZE + 1);
+        let key_index = (*key_index).min(max_key_index);
+        let mut key_slice = [0u8; CACHED_KEY_SIZE];
+        key_slice.clone_from_slice(
+            &key.as_ref()[key_index..key_index + CACHED_KEY_SIZE],
+        );
+        key_map.get(&key_slice)?.last().cloned()
+    }
+
+    /// Returns the cached transaction statuses grouped by the slot in which
+    /// the transactions executed, ordered by slot
+    pub fn transaction_statuses_by_slot(
+        &self,
+    ) -> Vec<(Slot, Vec<(Signature, T)>)> {
+        let mut by_slot: Vec<(Slot, Vec<(Signature, T)>)> = vec![];
+        for (slot, map) in &self.transaction_status_cache {
+            let statuses = map
+                .iter()
+                .map(|(signature, status)| (*signature, status.clone()));
+            match by_slot.last_mut() {
+                Some((last_slot, last)) if last_slot == slot => {
+                    last.extend(statuses)
+                }
+                _ => by_slot.push((*slot, statuses.collect())),
+            }
+        }
+        by_slot
+    }
+
     // -----------------
     // Inserts
     // -----------------
@@ -114,6 +153,28 @@ impl<T: Clone> StatusCache<T> {
         map.insert(*signature, status);
     }
 
+    /// Restores transaction statuses that were persisted before a restart.
+    /// Statuses of slots that are already cached, i.e. since their
+    /// transactions were replayed, are ignored.
+    pub fn restore_transaction_statuses(
+        &mut self,
+        slot_statuses: Vec<(Slot, Vec<(Signature, T)>)>,
+    ) {
+        let cached_slots = self
+            .transaction_status_cache
+            .iter()
+            .map(|(slot, _)| *slot)
+            .collect::<HashSet<_>>();
+        for (slot, statuses) in slot_statuses {
+            if cached_slots.contains(&slot) {
+                continue;
+            }
+            self.transaction_status_cache
+                .push((slot, statuses.into_iter().collect()));
+        }
+        self.transaction_status_cache.sort_by_key(|(slot, _)| *slot);
+    }
+
     /// Insert a new key for a specific slot.
     pub fn insert<K: AsRef<[u8]>>(
         &mut self,
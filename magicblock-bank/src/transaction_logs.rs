@@ -1,5 +1,8 @@
 // NOTE: copied from bank.rs:335
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
 
 use serde::{Deserialize, Serialize};
 use solana_frozen_abi_macro::{AbiEnumVisitor, AbiExample};
@@ -65,3 +68,223 @@ impl TransactionLogCollector {
         }
     }
 }
+
+// -----------------
+// Log Filtering
+// -----------------
+const LOG_TRUNCATED: &str = "Log truncated";
+
+/// Which logs are recorded for the instructions executed by a program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionLogLevel {
+    #[default]
+    All,
+    /// Only the logs the runtime emits for the invocations of the program,
+    /// i.e. without the messages and data logged by the program itself
+    Invocations,
+    None,
+}
+
+/// Filters the log messages of executed transactions before they are
+/// recorded in the ledger or returned from a simulation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionLogFilter {
+    /// Level of programs not found in [Self::program_levels]
+    pub default_level: TransactionLogLevel,
+    pub program_levels: HashMap<Pubkey, TransactionLogLevel>,
+    /// Logs exceeding this are replaced with [LOG_TRUNCATED]
+    pub max_bytes_per_transaction: Option<usize>,
+}
+
+impl TransactionLogFilter {
+    fn is_noop(&self) -> bool {
+        self.default_level == TransactionLogLevel::All
+            && self.program_levels.is_empty()
+            && self.max_bytes_per_transaction.is_none()
+    }
+
+    fn level_of(&self, program_id: Option<&Pubkey>) -> TransactionLogLevel {
+        program_id
+            .and_then(|program_id| self.program_levels.get(program_id))
+            .copied()
+            .unwrap_or(self.default_level)
+    }
+
+    /// Removes the log messages excluded by the level of the program that
+    /// was executing when they were logged and truncates the remaining ones.
+    pub fn apply(&self, log_messages: &mut TransactionLogMessages) {
+        if self.is_noop() {
+            return;
+        }
+        let mut invocations = Vec::<Pubkey>::new();
+        let mut bytes = 0;
+        let mut filtered = Vec::with_capacity(log_messages.len());
+        for log in log_messages.drain(..) {
+            let (program_id, logged_by_runtime) = match RuntimeLog::parse(&log)
+            {
+                Some(RuntimeLog::Invoke(program_id)) => {
+                    invocations.push(program_id);
+                    (Some(program_id), true)
+                }
+                Some(RuntimeLog::Exit(program_id)) => {
+                    invocations.pop();
+                    (Some(program_id), true)
+                }
+                Some(RuntimeLog::Other(program_id)) => (Some(program_id), true),
+                None => (invocations.last().copied(), log == LOG_TRUNCATED),
+            };
+            let keep = match self.level_of(program_id.as_ref()) {
+                TransactionLogLevel::All => true,
+                TransactionLogLevel::Invocations => logged_by_runtime,
+                TransactionLogLevel::None => false,
+            };
+            if !keep {
+                continue;
+            }
+            if let Some(max_bytes) = self.max_bytes_per_transaction {
+                bytes += log.len();
+                if bytes > max_bytes {
+                    filtered.push(LOG_TRUNCATED.to_string());
+                    break;
+                }
+            }
+            filtered.push(log);
+        }
+        *log_messages = filtered;
+    }
+}
+
+/// Log messages the runtime emits around the invocation of a program, see
+/// solana_program_runtime::stable_log
+enum RuntimeLog {
+    /// `Program <id> invoke [<depth>]`
+    Invoke(Pubkey),
+    /// `Program <id> success` or `Program <id> failed: <err>`
+    Exit(Pubkey),
+    /// `Program <id> consumed <units> of <limit> compute units` or
+    /// `Program return: <id> <data>`
+    Other(Pubkey),
+}
+
+impl RuntimeLog {
+    fn parse(log: &str) -> Option<Self> {
+        let (first, rest) = log.strip_prefix("Program ")?.split_once(' ')?;
+        if first == "return:" {
+            let (program_id, _) = rest.split_once(' ')?;
+            return Some(Self::Other(Pubkey::from_str(program_id).ok()?));
+        }
+        let program_id = Pubkey::from_str(first).ok()?;
+        if rest.starts_with("invoke [") {
+            Some(Self::Invoke(program_id))
+        } else if rest == "success" || rest.starts_with("failed: ") {
+            Some(Self::Exit(program_id))
+        } else if rest.starts_with("consumed ") {
+            Some(Self::Other(program_id))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(program: &Pubkey, cpi_program: &Pubkey) -> Vec<String> {
+        vec![
+            format!("Program {} invoke [1]", program),
+            "Program log: outer".to_string(),
+            format!("Program {} invoke [2]", cpi_program),
+            "Program log: inner".to_string(),
+            "Program data: aW5uZXI=".to_string(),
+            format!(
+                "Program {} consumed 100 of 1000 compute units",
+                cpi_program
+            ),
+            format!("Program return: {} aW5uZXI=", cpi_program),
+            format!("Program {} success", cpi_program),
+            "Program log: outer again".to_string(),
+            format!("Program {} consumed 300 of 1000 compute units", program),
+            format!("Program {} success", program),
+        ]
+    }
+
+    #[test]
+    fn test_default_filter_keeps_all_logs() {
+        let (program, cpi_program) =
+            (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut log_messages = logs(&program, &cpi_program);
+        TransactionLogFilter::default().apply(&mut log_messages);
+        assert_eq!(log_messages, logs(&program, &cpi_program));
+    }
+
+    #[test]
+    fn test_filter_by_program_level() {
+        let (program, cpi_program) =
+            (Pubkey::new_unique(), Pubkey::new_unique());
+        let filter = TransactionLogFilter {
+            program_levels: HashMap::from([(
+                cpi_program,
+                TransactionLogLevel::Invocations,
+            )]),
+            ..Default::default()
+        };
+        let mut log_messages = logs(&program, &cpi_program);
+        filter.apply(&mut log_messages);
+        assert_eq!(
+            log_messages,
+            vec![
+                format!("Program {} invoke [1]", program),
+                "Program log: outer".to_string(),
+                format!("Program {} invoke [2]", cpi_program),
+                format!(
+                    "Program {} consumed 100 of 1000 compute units",
+                    cpi_program
+                ),
+                format!("Program return: {} aW5uZXI=", cpi_program),
+                format!("Program {} success", cpi_program),
+                "Program log: outer again".to_string(),
+                format!(
+                    "Program {} consumed 300 of 1000 compute units",
+                    program
+                ),
+                format!("Program {} success", program),
+            ]
+        );
+
+        let filter = TransactionLogFilter {
+            default_level: TransactionLogLevel::None,
+            program_levels: HashMap::from([(
+                cpi_program,
+                TransactionLogLevel::All,
+            )]),
+            ..Default::default()
+        };
+        let mut log_messages = logs(&program, &cpi_program);
+        filter.apply(&mut log_messages);
+        assert_eq!(log_messages, logs(&program, &cpi_program)[2..8]);
+    }
+
+    #[test]
+    fn test_filter_truncates_logs() {
+        let (program, cpi_program) =
+            (Pubkey::new_unique(), Pubkey::new_unique());
+        let all_logs = logs(&program, &cpi_program);
+        let filter = TransactionLogFilter {
+            max_bytes_per_transaction: Some(
+                all_logs[0].len() + all_logs[1].len(),
+            ),
+            ..Default::default()
+        };
+        let mut log_messages = all_logs.clone();
+        filter.apply(&mut log_messages);
+        assert_eq!(
+            log_messages,
+            vec![
+                all_logs[0].clone(),
+                all_logs[1].clone(),
+                LOG_TRUNCATED.to_string()
+            ]
+        );
+    }
+}
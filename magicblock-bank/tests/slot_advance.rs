@@ -5,6 +5,7 @@ use log::*;
 use magicblock_bank::bank::Bank;
 use solana_sdk::{
     account::{accounts_equal, Account},
+    clock::Clock,
     genesis_config::create_genesis_config,
     pubkey::Pubkey,
     system_program,
@@ -120,3 +121,44 @@ fn test_bank_advances_slot_in_clock_sysvar() {
     bank.advance_slot();
     assert_eq!(bank.clock().slot, 5);
 }
+
+#[test]
+fn test_bank_anchors_clock_sysvar_to_remote_clock() {
+    init_logger!();
+
+    let (genesis_config, _) = create_genesis_config(u64::MAX);
+    let bank = Bank::new_for_tests(&genesis_config, None, None).unwrap();
+
+    bank.advance_slot();
+    let local_clock = bank.clock();
+
+    // The base layer is an hour ahead and in a later epoch
+    let remote_clock = Clock {
+        slot: 1_000_000,
+        epoch_start_timestamp: local_clock.unix_timestamp - 7_200,
+        epoch: 42,
+        leader_schedule_epoch: 43,
+        unix_timestamp: local_clock.unix_timestamp + 3_600,
+    };
+    bank.set_remote_clock_anchor(&remote_clock);
+    bank.advance_slot();
+
+    let clock = bank.clock();
+    assert_eq!(clock.slot, 2);
+    assert_eq!(clock.epoch, 42);
+    assert_eq!(clock.leader_schedule_epoch, 43);
+    assert_eq!(
+        clock.epoch_start_timestamp,
+        remote_clock.epoch_start_timestamp
+    );
+    assert!(clock.unix_timestamp >= remote_clock.unix_timestamp);
+    assert!(clock.unix_timestamp < remote_clock.unix_timestamp + 60);
+
+    // A sample lagging behind does not move time backwards
+    bank.set_remote_clock_anchor(&Clock {
+        unix_timestamp: remote_clock.unix_timestamp - 10,
+        ..remote_clock.clone()
+    });
+    bank.advance_slot();
+    assert!(bank.clock().unix_timestamp >= clock.unix_timestamp);
+}
@@ -2,11 +2,14 @@
 
 use assert_matches::assert_matches;
 use magicblock_bank::{
-    bank::Bank,
+    bank::{AccountsDataGrowthLimits, Bank, BankStatusCache},
     bank_dev_utils::{
         elfs::{self, add_elf_program},
         transactions::{
-            create_noop_transaction, create_solx_send_post_transaction,
+            create_durable_nonce_transfer_transaction,
+            create_ed25519_verify_transaction, create_funded_account,
+            create_noop_transaction, create_precompile_transaction,
+            create_solx_send_post_transaction,
             create_system_allocate_transaction,
             create_system_transfer_transaction,
             create_sysvars_from_account_transaction,
@@ -14,14 +17,39 @@ use magicblock_bank::{
             SolanaxPostAccounts,
         },
     },
+    builtins::BuiltinPrototype,
     genesis_utils::create_genesis_config_with_leader_and_fees,
     transaction_results::TransactionBalancesSet,
-    LAMPORTS_PER_SIGNATURE,
+    EPHEM_DEFAULT_MILLIS_PER_SLOT, LAMPORTS_PER_SIGNATURE,
 };
+use magicblock_program::{
+    magicblock_instruction::register_session_key,
+    validator::{
+        generate_validator_authority_if_needed, validator_authority_id,
+    },
+    SessionKey, SessionKeyRegistry, MAGIC_SESSION_KEYS_PUBKEY,
+};
+use solana_program_runtime::declare_process_instruction;
 use solana_sdk::{
-    account::ReadableAccount, genesis_config::create_genesis_config,
-    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, rent::Rent,
-    transaction::SanitizedTransaction,
+    account::{AccountSharedData, ReadableAccount, WritableAccount},
+    address_lookup_table::{self, state::AddressLookupTable},
+    ed25519_program,
+    genesis_config::create_genesis_config,
+    instruction::{AccountMeta, Instruction},
+    message::v0::MessageAddressTableLookup,
+    native_token::LAMPORTS_PER_SOL,
+    nonce::state::DurableNonce,
+    nonce_account,
+    pubkey::Pubkey,
+    rent::Rent,
+    secp256k1_program,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    system_program, system_transaction,
+    transaction::{
+        AddressLoader, SanitizedTransaction, Transaction, TransactionError,
+        TransactionVerificationMode, VersionedTransaction,
+    },
 };
 use test_tools_core::init_logger;
 
@@ -213,6 +241,106 @@ fn test_bank_solx_instructions() {
     assert_matches!(sig_status.as_ref().unwrap(), Ok(()));
 }
 
+#[test]
+fn test_bank_durable_nonce_transaction() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+
+    let (tx, to, nonce, nonce_hash) = create_durable_nonce_transfer_transaction(
+        &bank,
+        LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL / 5,
+    );
+    // A nonce can only be advanced once the blockhash it was derived from
+    // changed
+    bank.advance_slot();
+    let (results, _) = execute_transactions(&bank, vec![tx.clone()]);
+    assert_matches!(results[0], Ok(_));
+    assert_eq!(bank.get_balance(&to), LAMPORTS_PER_SOL / 5);
+
+    // The nonce was advanced to the one derived from the current blockhash
+    let nonce_acc = bank.get_account(&nonce).unwrap();
+    assert!(
+        nonce_account::verify_nonce_account(&nonce_acc, &nonce_hash).is_none()
+    );
+    let next_durable_nonce =
+        DurableNonce::from_blockhash(&bank.last_blockhash());
+    assert!(nonce_account::verify_nonce_account(
+        &nonce_acc,
+        next_durable_nonce.as_hash()
+    )
+    .is_some());
+
+    // Which prevents the transaction from being replayed
+    bank.advance_slot();
+    let (results, _) = execute_transactions(&bank, vec![tx]);
+    assert!(results[0].is_err());
+}
+
+#[test]
+fn test_bank_create_and_use_address_lookup_table() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+    // The table is derived from a slot which needs to be in the slot hashes
+    // which the sysvar cache picks up when the slot after it is advanced
+    let recent_slot = bank.slot();
+    bank.advance_slot();
+    bank.advance_slot();
+
+    let authority = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let address = Pubkey::new_unique();
+    let (create_ix, lookup_table) =
+        address_lookup_table::instruction::create_lookup_table(
+            authority.pubkey(),
+            authority.pubkey(),
+            recent_slot,
+        );
+    let extend_ix = address_lookup_table::instruction::extend_lookup_table(
+        lookup_table,
+        authority.pubkey(),
+        Some(authority.pubkey()),
+        vec![address],
+    );
+    let tx = Transaction::new_signed_with_payer(
+        &[create_ix, extend_ix],
+        Some(&authority.pubkey()),
+        &[&authority],
+        bank.last_blockhash(),
+    );
+    let tx = SanitizedTransaction::from_transaction_for_tests(tx);
+    execute_and_check_results(&bank, tx);
+
+    let table_acc = bank.get_account(&lookup_table).unwrap();
+    assert_eq!(table_acc.owner(), &address_lookup_table::program::id());
+    let table = AddressLookupTable::deserialize(table_acc.data()).unwrap();
+    assert_eq!(table.addresses.as_ref(), &[address]);
+
+    // Extended addresses can be loaded starting with the next slot
+    bank.advance_slot();
+    let loaded = (&bank)
+        .load_addresses(&[MessageAddressTableLookup {
+            account_key: lookup_table,
+            writable_indexes: vec![0],
+            readonly_indexes: vec![],
+        }])
+        .unwrap();
+    assert_eq!(loaded.writable, vec![address]);
+}
+
 fn execute_and_check_results(bank: &Bank, tx: SanitizedTransaction) {
     let (results, _) = execute_transactions(bank, vec![tx]);
     let failures = results.iter().filter(|r| r.is_err()).collect::<Vec<_>>();
@@ -244,3 +372,544 @@ fn test_bank_sysvars_from_account() {
     bank.advance_slot();
     execute_and_check_results(&bank, tx);
 }
+
+#[test]
+fn test_bank_restored_status_cache_rejects_processed_transaction() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+
+    let (tx, _, _) = create_system_transfer_transaction(
+        &bank,
+        LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL / 5,
+    );
+    let signature = *tx.signature();
+    let (results, _) = execute_transactions(&bank, vec![tx.clone()]);
+    assert_matches!(results[0], Ok(_));
+
+    let slot_statuses = bank.recent_transaction_statuses();
+    assert_eq!(
+        slot_statuses,
+        vec![(bank.slot(), vec![(signature, Ok(()))])]
+    );
+
+    // Simulate a restart which starts out with an empty status cache
+    *bank.status_cache.write().unwrap() = BankStatusCache::new(bank.max_age);
+    assert!(bank.get_signature_status(&signature).is_none());
+
+    bank.restore_transaction_statuses(slot_statuses);
+    assert_eq!(bank.get_signature_status(&signature), Some(Ok(())));
+
+    let (results, _) = execute_transactions(&bank, vec![tx]);
+    assert_matches!(results[0], Err(TransactionError::AlreadyProcessed));
+}
+
+#[test]
+fn test_bank_processed_transaction_result() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+
+    // Transfers more than the payer holds and thus fails
+    let (failing_tx, _, _) = create_system_transfer_transaction(
+        &bank,
+        LAMPORTS_PER_SOL,
+        2 * LAMPORTS_PER_SOL,
+    );
+    let (tx, _, _) = create_system_transfer_transaction(
+        &bank,
+        LAMPORTS_PER_SOL,
+        LAMPORTS_PER_SOL / 5,
+    );
+    assert!(bank
+        .get_processed_transaction_result(tx.signature())
+        .is_none());
+
+    execute_transactions(&bank, vec![tx.clone(), failing_tx.clone()]);
+    assert_eq!(
+        bank.get_processed_transaction_result(tx.signature()),
+        Some(Ok(()))
+    );
+    assert_matches!(
+        bank.get_processed_transaction_result(failing_tx.signature()),
+        Some(Err(TransactionError::InstructionError(0, _)))
+    );
+
+    // Resending does not change the result
+    let (results, _) = execute_transactions(&bank, vec![tx.clone()]);
+    assert_matches!(results[0], Err(TransactionError::AlreadyProcessed));
+    assert_eq!(
+        bank.get_processed_transaction_result(tx.signature()),
+        Some(Ok(()))
+    );
+}
+
+#[test]
+fn test_bank_rejects_processed_message_with_other_signature() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+
+    let payer = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let tx = system_transaction::transfer(
+        &payer,
+        &Pubkey::new_unique(),
+        LAMPORTS_PER_SOL / 2,
+        bank.last_blockhash(),
+    );
+    // The bank does not verify signatures, so any other signature stands in
+    // for one malleated from the valid signature
+    let mut other_signature_tx = tx.clone();
+    other_signature_tx.signatures[0] = Signature::from([7; 64]);
+
+    let (results, _) = execute_transactions(
+        &bank,
+        vec![SanitizedTransaction::from_transaction_for_tests(tx)],
+    );
+    assert_matches!(results[0], Ok(_));
+
+    let (results, _) = execute_transactions(
+        &bank,
+        vec![SanitizedTransaction::from_transaction_for_tests(
+            other_signature_tx,
+        )],
+    );
+    assert_matches!(results[0], Err(TransactionError::AlreadyProcessed));
+}
+
+#[test]
+fn test_bank_ed25519_precompile_verifies_signature() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+    assert!(bank
+        .get_account(&ed25519_program::id())
+        .unwrap()
+        .executable());
+
+    let signer = Keypair::new();
+    let message = b"session key authorization";
+
+    let tx = create_ed25519_verify_transaction(&bank, &signer, message, true);
+    let sig = *tx.signature();
+    let (results, _) = execute_transactions(&bank, vec![tx]);
+    assert_matches!(results[0], Ok(_));
+    assert_eq!(bank.get_signature_status(&sig), Some(Ok(())));
+
+    let tx = create_ed25519_verify_transaction(&bank, &signer, message, false);
+    let sig = *tx.signature();
+    let (results, _) = execute_transactions(&bank, vec![tx]);
+    assert_matches!(results[0], Ok(_));
+    assert_matches!(
+        bank.get_signature_status(&sig),
+        Some(Err(TransactionError::InstructionError(0, _)))
+    );
+}
+
+#[test]
+fn test_bank_secp256k1_precompile_verifies_instruction() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+    assert!(bank
+        .get_account(&secp256k1_program::id())
+        .unwrap()
+        .executable());
+
+    // No signatures to verify
+    let tx =
+        create_precompile_transaction(&bank, secp256k1_program::id(), vec![0]);
+    let sig = *tx.signature();
+    let (results, _) = execute_transactions(&bank, vec![tx]);
+    assert_matches!(results[0], Ok(_));
+    assert_eq!(bank.get_signature_status(&sig), Some(Ok(())));
+
+    // Claims a signature without providing its offsets
+    let tx =
+        create_precompile_transaction(&bank, secp256k1_program::id(), vec![1]);
+    let sig = *tx.signature();
+    let (results, _) = execute_transactions(&bank, vec![tx]);
+    assert_matches!(results[0], Ok(_));
+    assert_matches!(
+        bank.get_signature_status(&sig),
+        Some(Err(TransactionError::InstructionError(0, _)))
+    );
+}
+
+#[test]
+fn test_bank_additional_builtin() {
+    init_logger!();
+
+    const CUSTOM_BUILTIN_ID: Pubkey = Pubkey::new_from_array([7; 32]);
+    // Echoes the instruction data as return data
+    declare_process_instruction!(CustomBuiltin, 150, |invoke_context| {
+        let data = invoke_context
+            .transaction_context
+            .get_current_instruction_context()?
+            .get_instruction_data()
+            .to_vec();
+        invoke_context
+            .transaction_context
+            .set_return_data(CUSTOM_BUILTIN_ID, data)
+    });
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let bank = Bank::new_with_builtins_for_tests(
+        &genesis_config_info.genesis_config,
+        Default::default(),
+        None,
+        None,
+        EPHEM_DEFAULT_MILLIS_PER_SLOT,
+        &[BuiltinPrototype {
+            feature_id: None,
+            program_id: CUSTOM_BUILTIN_ID,
+            name: "custom_builtin",
+            entrypoint: CustomBuiltin::vm,
+        }],
+    )
+    .unwrap();
+    assert!(bank.get_account(&CUSTOM_BUILTIN_ID).unwrap().executable());
+
+    let payer = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let tx = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            CUSTOM_BUILTIN_ID,
+            b"echo",
+            vec![],
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        bank.last_blockhash(),
+    );
+    let (results, _) = execute_transactions(
+        &bank,
+        vec![SanitizedTransaction::from_transaction_for_tests(tx)],
+    );
+    let meta = results[0]
+        .as_ref()
+        .unwrap()
+        .tx_with_meta
+        .get_status_meta()
+        .unwrap();
+    assert_matches!(meta.status, Ok(()));
+    let return_data = meta.return_data.unwrap();
+    assert_eq!(return_data.program_id, CUSTOM_BUILTIN_ID);
+    assert_eq!(return_data.data, b"echo");
+}
+
+#[test]
+fn test_bank_accounts_data_growth_limits() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let mut bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+    bank.set_accounts_data_growth_limits(AccountsDataGrowthLimits {
+        max_per_transaction: 1_000,
+        max_per_slot: 1_500,
+    });
+
+    let status_of = |bank: &Bank, tx: SanitizedTransaction| {
+        let (results, _) = execute_transactions(bank, vec![tx]);
+        results[0]
+            .as_ref()
+            .unwrap()
+            .tx_with_meta
+            .get_status_meta()
+            .unwrap()
+            .status
+    };
+
+    // Exceeds the per transaction limit, only the fees are charged
+    let (tx, payer, account) =
+        create_system_allocate_transaction(&bank, LAMPORTS_PER_SOL, 1_001);
+    assert_matches!(
+        status_of(&bank, tx),
+        Err(TransactionError::WouldExceedAccountDataTotalLimit)
+    );
+    assert_eq!(
+        bank.get_account(&payer).unwrap().lamports(),
+        LAMPORTS_PER_SOL - 2 * LAMPORTS_PER_SIGNATURE
+    );
+    assert!(bank.get_account(&account).unwrap().data().is_empty());
+
+    // The second transaction exceeds the per slot limit
+    let (tx, _, _) =
+        create_system_allocate_transaction(&bank, LAMPORTS_PER_SOL, 1_000);
+    assert_matches!(status_of(&bank, tx), Ok(()));
+    let (tx, _, account) =
+        create_system_allocate_transaction(&bank, LAMPORTS_PER_SOL, 1_000);
+    assert_matches!(
+        status_of(&bank, tx),
+        Err(TransactionError::WouldExceedAccountDataBlockLimit)
+    );
+    assert!(bank.get_account(&account).unwrap().data().is_empty());
+
+    // The growth is tracked per slot
+    bank.advance_slot();
+    let (tx, _, account) =
+        create_system_allocate_transaction(&bank, LAMPORTS_PER_SOL, 1_000);
+    assert_matches!(status_of(&bank, tx), Ok(()));
+    assert_eq!(bank.get_account(&account).unwrap().data().len(), 1_000);
+}
+
+#[test]
+fn test_bank_sponsors_session_key_fees() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let mut bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+
+    let session_key = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let out_of_scope_key = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let registry = SessionKeyRegistry {
+        keys: vec![
+            SessionKey {
+                session_key: session_key.pubkey(),
+                authority: Pubkey::new_unique(),
+                expires_at_slot: bank.slot(),
+                programs: vec![system_program::id()],
+            },
+            SessionKey {
+                session_key: out_of_scope_key.pubkey(),
+                authority: Pubkey::new_unique(),
+                expires_at_slot: bank.slot(),
+                programs: vec![Pubkey::new_unique()],
+            },
+        ],
+    };
+    let mut session_keys = AccountSharedData::new(
+        u64::MAX,
+        SessionKeyRegistry::SIZE,
+        &magicblock_program::id(),
+    );
+    session_keys.serialize_data(&registry).unwrap();
+    bank.store_account(MAGIC_SESSION_KEYS_PUBKEY, session_keys);
+
+    // Returns the lamports the payer spent on the transfer
+    let transfer = |bank: &Bank, payer: &Keypair| {
+        let before = bank.get_account(&payer.pubkey()).unwrap().lamports();
+        let tx = system_transaction::transfer(
+            payer,
+            &Pubkey::new_unique(),
+            LAMPORTS_PER_SOL / 10,
+            bank.last_blockhash(),
+        );
+        execute_and_check_results(
+            bank,
+            SanitizedTransaction::from_transaction_for_tests(tx),
+        );
+        before - bank.get_account(&payer.pubkey()).unwrap().lamports()
+    };
+
+    // Session keys are only sponsored once enabled
+    assert_eq!(
+        transfer(&bank, &session_key),
+        LAMPORTS_PER_SOL / 10 + LAMPORTS_PER_SIGNATURE
+    );
+    bank.set_sponsor_session_key_fees(true);
+    assert_eq!(transfer(&bank, &session_key), LAMPORTS_PER_SOL / 10);
+
+    // Transactions invoking programs outside of the scope are charged
+    assert_eq!(
+        transfer(&bank, &out_of_scope_key),
+        LAMPORTS_PER_SOL / 10 + LAMPORTS_PER_SIGNATURE
+    );
+
+    // Expired session keys are charged
+    bank.advance_slot();
+    assert_eq!(
+        transfer(&bank, &session_key),
+        LAMPORTS_PER_SOL / 10 + LAMPORTS_PER_SIGNATURE
+    );
+
+    // Changes to the registry are picked up once it is stored
+    let registry = SessionKeyRegistry {
+        keys: vec![SessionKey {
+            expires_at_slot: bank.slot(),
+            ..registry.keys[0].clone()
+        }],
+    };
+    let mut session_keys =
+        bank.get_account(&MAGIC_SESSION_KEYS_PUBKEY).unwrap();
+    session_keys.data_as_mut_slice().fill(0);
+    session_keys.serialize_data(&registry).unwrap();
+    bank.store_account(MAGIC_SESSION_KEYS_PUBKEY, session_keys);
+    assert_eq!(transfer(&bank, &session_key), LAMPORTS_PER_SOL / 10);
+}
+
+#[test]
+fn test_bank_sponsors_unfunded_session_key() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let mut bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+    bank.set_sponsor_session_key_fees(true);
+    add_elf_program(&bank, &elfs::noop::ID);
+
+    generate_validator_authority_if_needed();
+    bank.store_account(
+        validator_authority_id(),
+        AccountSharedData::new(LAMPORTS_PER_SOL, 0, &system_program::id()),
+    );
+    bank.store_account(
+        MAGIC_SESSION_KEYS_PUBKEY,
+        AccountSharedData::new(
+            u64::MAX,
+            SessionKeyRegistry::SIZE,
+            &magicblock_program::id(),
+        ),
+    );
+
+    // The session key does not hold any lamports before it is registered
+    let authority = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let session_key = Keypair::new();
+    assert!(bank.get_account(&session_key.pubkey()).is_none());
+    let tx = register_session_key(
+        &authority,
+        &session_key,
+        bank.slot() + 10,
+        vec![elfs::noop::id()],
+        bank.last_blockhash(),
+    );
+    execute_and_check_results(
+        &bank,
+        SanitizedTransaction::from_transaction_for_tests(tx),
+    );
+    let rent_exempt_minimum = bank.get_minimum_balance_for_rent_exemption(0);
+    assert_eq!(
+        bank.get_account(&session_key.pubkey()).unwrap().lamports(),
+        rent_exempt_minimum
+    );
+
+    // The registered session key pays for transactions without being charged
+    let tx = Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            elfs::noop::id(),
+            &[],
+            vec![AccountMeta::new(session_key.pubkey(), true)],
+        )],
+        Some(&session_key.pubkey()),
+        &[&session_key],
+        bank.last_blockhash(),
+    );
+    execute_and_check_results(
+        &bank,
+        SanitizedTransaction::from_transaction_for_tests(tx),
+    );
+    assert_eq!(
+        bank.get_account(&session_key.pubkey()).unwrap().lamports(),
+        rent_exempt_minimum
+    );
+}
+
+#[test]
+fn test_bank_verifies_transaction_size() {
+    init_logger!();
+
+    let genesis_config_info = create_genesis_config_with_leader_and_fees(
+        u64::MAX,
+        &Pubkey::new_unique(),
+    );
+    let mut bank =
+        Bank::new_for_tests(&genesis_config_info.genesis_config, None, None)
+            .unwrap();
+
+    // Does not fit into a packet
+    let payer = create_funded_account(&bank, Some(LAMPORTS_PER_SOL));
+    let tx = VersionedTransaction::from(Transaction::new_signed_with_payer(
+        &[Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[0; 2_000],
+            vec![],
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        bank.last_blockhash(),
+    ));
+    assert_matches!(
+        bank.verify_transaction(
+            tx.clone(),
+            TransactionVerificationMode::FullVerification
+        ),
+        Err(TransactionError::SanitizeFailure)
+    );
+
+    bank.set_max_transaction_size(4 * 1024);
+    assert!(bank
+        .verify_transaction(tx, TransactionVerificationMode::FullVerification)
+        .is_ok());
+}
+
+#[test]
+fn test_bank_warms_up_program_cache() {
+    init_logger!();
+
+    let (genesis_config, _) = create_genesis_config(u64::MAX);
+    let bank = Bank::new_for_tests(&genesis_config, None, None).unwrap();
+    add_elf_program(&bank, &elfs::noop::ID);
+    bank.advance_slot();
+
+    // Programs that don't exist are skipped
+    assert_eq!(
+        bank.warm_up_program_cache(&[elfs::noop::ID, Pubkey::new_unique()]),
+        vec![elfs::noop::ID]
+    );
+    assert!(bank.warm_up_program_cache(&[elfs::noop::ID]).is_empty());
+
+    // Executing the warmed up program does not need to compile it
+    bank.take_program_cache_stats();
+    let tx = create_noop_transaction(&bank, bank.last_blockhash());
+    execute_and_check_results(&bank, tx);
+    let stats = bank.take_program_cache_stats();
+    assert_eq!(stats.misses, 0);
+    assert!(stats.hits > 0);
+}
@@ -17,6 +17,14 @@ pub struct AccountsConfig {
     #[serde(default)]
     pub remote: RemoteConfig,
     #[serde(default)]
+    pub fetcher: FetcherConfig,
+    #[serde(default)]
+    pub updates: UpdatesConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub cloning: CloningConfig,
+    #[serde(default)]
     pub lifecycle: LifecycleMode,
     #[serde(default)]
     pub commit: CommitStrategy,
@@ -24,6 +32,11 @@ pub struct AccountsConfig {
     pub payer: Payer,
     #[serde(default)]
     pub allowed_programs: Vec<AllowedProgram>,
+    /// Delegation programs whose delegated accounts are recognized in
+    /// addition to the ones of the default delegation program, i.e. during
+    /// its migration or for third-party delegation programs
+    #[serde(default)]
+    pub delegation_programs: Vec<DelegationProgram>,
 
     #[serde(default)]
     pub db: AccountsDbConfig,
@@ -36,10 +49,15 @@ impl Default for AccountsConfig {
     fn default() -> Self {
         Self {
             remote: Default::default(),
+            fetcher: Default::default(),
+            updates: Default::default(),
+            rate_limit: Default::default(),
+            cloning: Default::default(),
             lifecycle: Default::default(),
             commit: Default::default(),
             payer: Default::default(),
             allowed_programs: Default::default(),
+            delegation_programs: Default::default(),
             db: Default::default(),
             max_monitored_accounts: default_max_monitored_accounts(),
         }
@@ -70,6 +88,321 @@ pub enum RemoteConfig {
     },
 }
 
+// -----------------
+// FetcherConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct FetcherConfig {
+    /// Additional RPC endpoints of the remote cluster which accounts are
+    /// fetched from when the main remote is unhealthy
+    #[serde(default)]
+    pub fallback_remotes: Vec<Url>,
+    /// How often the health of each remote endpoint is checked
+    #[serde(default = "default_health_check_interval_millis")]
+    pub health_check_interval_millis: u64,
+    /// Number of consecutive failed fetches after which an endpoint is
+    /// considered unhealthy until its next successful health check
+    #[serde(default = "default_max_consecutive_failures")]
+    pub max_consecutive_failures: u32,
+    /// Once all endpoints are unhealthy fetches fail immediately for this
+    /// long instead of waiting for the remote to time out, after which a
+    /// single fetch probes it again. `0` disables the circuit breaker
+    #[serde(default = "default_circuit_breaker_cooldown_millis")]
+    pub circuit_breaker_cooldown_millis: u64,
+    /// Fetches that didn't complete within this time fail, including the
+    /// ones that were waiting for the same account
+    #[serde(default = "default_fetch_timeout_millis")]
+    pub fetch_timeout_millis: u64,
+}
+
+fn default_health_check_interval_millis() -> u64 {
+    10_000
+}
+
+fn default_max_consecutive_failures() -> u32 {
+    3
+}
+
+fn default_circuit_breaker_cooldown_millis() -> u64 {
+    5_000
+}
+
+fn default_fetch_timeout_millis() -> u64 {
+    30_000
+}
+
+impl Default for FetcherConfig {
+    fn default() -> Self {
+        Self {
+            fallback_remotes: Default::default(),
+            health_check_interval_millis: default_health_check_interval_millis(
+            ),
+            max_consecutive_failures: default_max_consecutive_failures(),
+            circuit_breaker_cooldown_millis:
+                default_circuit_breaker_cooldown_millis(),
+            fetch_timeout_millis: default_fetch_timeout_millis(),
+        }
+    }
+}
+
+// -----------------
+// UpdatesConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct UpdatesConfig {
+    /// Updates of the same account received via websocket within this
+    /// interval are collapsed into the latest one before being applied,
+    /// `0` applies each update as soon as it arrives.
+    /// Coalescing delays noticing that a cloned account became stale by up
+    /// to the interval, transactions may run against its outdated state
+    /// meanwhile, hence it is disabled by default
+    #[serde(default = "default_coalesce_interval_millis")]
+    pub coalesce_interval_millis: u64,
+    /// High frequency accounts, i.e. oracle price feeds, which are always
+    /// monitored via a dedicated connection and whose updates are applied
+    /// without coalescing
+    #[serde(default)]
+    pub fast_lane: Vec<FastLaneAccount>,
+}
+
+fn default_coalesce_interval_millis() -> u64 {
+    0
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            coalesce_interval_millis: default_coalesce_interval_millis(),
+            fast_lane: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FastLaneAccount {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+}
+
+// -----------------
+// RateLimitConfig
+// -----------------
+/// Limits the requests sent to each remote endpoint, both account fetches
+/// and subscriptions, in order to not get banned by RPC providers.
+/// Whenever an endpoint throttles us (429 or timeouts) we back off
+/// exponentially and temporarily lower the rate for that endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RateLimitConfig {
+    /// Maximum sustained requests per second to a single endpoint,
+    /// `0` disables rate limiting
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: u32,
+    /// Maximum number of requests sent at once after an idle period
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+    /// Backoff applied after the first throttled request, doubled for
+    /// each further one
+    #[serde(default = "default_initial_backoff_millis")]
+    pub initial_backoff_millis: u64,
+    #[serde(default = "default_max_backoff_millis")]
+    pub max_backoff_millis: u64,
+    /// Overrides of the above limits for specific remotes
+    #[serde(default)]
+    pub remotes: Vec<RemoteRateLimitConfig>,
+}
+
+fn default_requests_per_second() -> u32 {
+    50
+}
+
+fn default_burst() -> u32 {
+    100
+}
+
+fn default_initial_backoff_millis() -> u64 {
+    500
+}
+
+fn default_max_backoff_millis() -> u64 {
+    30_000
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: default_requests_per_second(),
+            burst: default_burst(),
+            initial_backoff_millis: default_initial_backoff_millis(),
+            max_backoff_millis: default_max_backoff_millis(),
+            remotes: Default::default(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Returns the limits that apply to the remote with the given url,
+    /// remotes are matched by host so that the same limits apply to the
+    /// RPC and websocket endpoints of a provider
+    pub fn for_remote(&self, url: &str) -> RateLimitConfig {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(ToString::to_string));
+        let remote = self.remotes.iter().find(|remote| {
+            host.as_deref()
+                .is_some_and(|host| remote.host.eq_ignore_ascii_case(host))
+        });
+        RateLimitConfig {
+            requests_per_second: remote
+                .and_then(|remote| remote.requests_per_second)
+                .unwrap_or(self.requests_per_second),
+            burst: remote.and_then(|remote| remote.burst).unwrap_or(self.burst),
+            initial_backoff_millis: self.initial_backoff_millis,
+            max_backoff_millis: self.max_backoff_millis,
+            remotes: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct RemoteRateLimitConfig {
+    /// Host of the remote, i.e. `api.devnet.solana.com`
+    pub host: String,
+    pub requests_per_second: Option<u32>,
+    pub burst: Option<u32>,
+}
+
+// -----------------
+// CloningConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CloningConfig {
+    /// Whether the mint of a cloned token account is cloned alongside it
+    #[serde(default = "default_clone_token_mints")]
+    pub token_mints: bool,
+    /// Whether the IDL accounts of a cloned program are cloned alongside it
+    #[serde(default = "default_clone_program_idls")]
+    pub program_idls: bool,
+    /// Max number of accounts that are cloned concurrently, including the
+    /// accounts they depend on
+    #[serde(default = "default_max_concurrent_clones")]
+    pub max_concurrent_clones: usize,
+    /// Cloned non-delegated accounts are fetched again before they are used
+    /// once they were cloned longer ago than this, even if no update was
+    /// observed for them. By default they are only updated when observed.
+    #[serde(default)]
+    pub revalidate_after_millis: Option<u64>,
+    /// Whether the delegation of accounts delegated to this validator is
+    /// verified against the delegation program before they are cloned
+    #[serde(default = "default_verify_delegations")]
+    pub verify_delegations: bool,
+    /// Programs whose accounts are all cloned on startup instead of lazily
+    /// when each of them is first used
+    #[serde(default)]
+    pub prefetch: Vec<PrefetchProgram>,
+    /// Clones accounts from a local copy of the remote state instead of
+    /// fetching them from the remote
+    #[serde(default)]
+    pub bootstrap: Option<BootstrapConfig>,
+}
+
+fn default_clone_token_mints() -> bool {
+    true
+}
+
+fn default_clone_program_idls() -> bool {
+    true
+}
+
+fn default_max_concurrent_clones() -> usize {
+    32
+}
+
+fn default_verify_delegations() -> bool {
+    true
+}
+
+impl Default for CloningConfig {
+    fn default() -> Self {
+        Self {
+            token_mints: default_clone_token_mints(),
+            program_idls: default_clone_program_idls(),
+            max_concurrent_clones: default_max_concurrent_clones(),
+            revalidate_after_millis: None,
+            verify_delegations: default_verify_delegations(),
+            prefetch: Default::default(),
+            bootstrap: None,
+        }
+    }
+}
+
+impl CloningConfig {
+    pub fn try_bootstrap(&self) -> ConfigResult<Option<&BootstrapConfig>> {
+        match &self.bootstrap {
+            Some(bootstrap)
+                if bootstrap.accountsdb_path.is_some()
+                    == bootstrap.accounts_dir.is_some() =>
+            {
+                Err(ConfigError::BootstrapRequiresExactlyOneSource)
+            }
+            bootstrap => Ok(bootstrap.as_ref()),
+        }
+    }
+}
+
+// -----------------
+// BootstrapConfig
+// -----------------
+/// Exactly one of the sources has to be provided
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BootstrapConfig {
+    /// Ledger directory of a validator whose accounts database is used
+    #[serde(default)]
+    pub accountsdb_path: Option<String>,
+    /// Directory with account files as written by
+    /// `solana account <pubkey> --output json`
+    #[serde(default)]
+    pub accounts_dir: Option<String>,
+    /// Whether accounts missing locally are fetched from the remote,
+    /// otherwise they are considered to not exist
+    #[serde(default)]
+    pub fallback_to_remote: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PrefetchProgram {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+    /// Only prefetch accounts with exactly this data size
+    #[serde(default)]
+    pub data_size: Option<u64>,
+    /// Only prefetch accounts whose data matches all of these
+    #[serde(default)]
+    pub memcmp: Vec<PrefetchMemcmp>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PrefetchMemcmp {
+    /// Offset into the account data
+    pub offset: usize,
+    /// Base58 encoded bytes expected at the offset
+    pub bytes: String,
+}
+
 // -----------------
 // LifecycleMode
 // -----------------
@@ -98,6 +431,55 @@ pub struct CommitStrategy {
     /// This is in micro lamports and defaults to `1_000_000` (1 Lamport)
     #[serde(default = "default_compute_unit_price")]
     pub compute_unit_price: u64,
+    /// Skips committing accounts of scheduled commits whose state didn't
+    /// change since they were last committed, unless they are undelegated
+    #[serde(default)]
+    pub changed_only: bool,
+    /// When provided the compute unit price is chosen for each commit from the
+    /// recent prioritization fees of the remote cluster within these bounds.
+    /// The [Self::compute_unit_price] is then only used when those fees cannot
+    /// be obtained.
+    #[serde(default)]
+    pub priority_fee: Option<PriorityFeeConfig>,
+    /// The highest compute unit price in micro lamports offered for a commit
+    /// whose scheduling program requested a specific price. Requested prices
+    /// are ignored when this is `0` which is the default.
+    #[serde(default)]
+    pub max_requested_compute_unit_price: u64,
+    /// When provided commit transactions use this durable nonce instead of a
+    /// recent blockhash and are retried until they land on the remote cluster
+    #[serde(default)]
+    pub durable_nonce: Option<DurableNonceConfig>,
+    /// When provided commit transactions are submitted as Jito bundles.
+    /// Read it via [Self::try_jito].
+    #[serde(default)]
+    pub jito: Option<JitoConfig>,
+    /// When provided the balance of the validator identity paying for the
+    /// commits is monitored on the remote cluster
+    #[serde(default)]
+    pub payer_monitor: Option<PayerMonitorConfig>,
+    /// When provided the outcomes of scheduled commits and undelegations are
+    /// posted to this webhook
+    #[serde(default)]
+    pub webhook: Option<CommitWebhookConfig>,
+    /// Whether commit transactions are simulated on the remote cluster
+    /// before they are sent. The ones whose simulation fails are not sent and
+    /// the failure is logged by the transaction signaling the sent commit.
+    #[serde(default)]
+    pub simulate: bool,
+    /// The cluster commits are sent to, by default the same one accounts are
+    /// cloned from. Both need to be the same chain, but can be served by
+    /// different endpoints, i.e. a private transaction sender.
+    #[serde(default)]
+    pub remote: Option<RemoteConfig>,
+    /// Additional RPC endpoints commits are sent through when sending them
+    /// through the main commit remote fails
+    #[serde(default)]
+    pub fallback_remotes: Vec<Url>,
+    /// When provided payers are charged these fees for scheduling commits,
+    /// which offsets the fees the validator pays for committing on their behalf
+    #[serde(default)]
+    pub schedule_fee: Option<ScheduleCommitFeeConfig>,
 }
 
 fn default_frequency_millis() -> u64 {
@@ -119,10 +501,208 @@ impl Default for CommitStrategy {
         Self {
             frequency_millis: default_frequency_millis(),
             compute_unit_price: default_compute_unit_price(),
+            changed_only: false,
+            priority_fee: None,
+            max_requested_compute_unit_price: 0,
+            durable_nonce: None,
+            jito: None,
+            payer_monitor: None,
+            webhook: None,
+            simulate: false,
+            remote: None,
+            fallback_remotes: Default::default(),
+            schedule_fee: None,
+        }
+    }
+}
+
+impl CommitStrategy {
+    pub fn try_jito(&self) -> ConfigResult<Option<&JitoConfig>> {
+        if self.jito.is_some() && self.durable_nonce.is_some() {
+            return Err(ConfigError::CannotSpecifyBothJitoAndDurableNonce);
+        }
+        Ok(self.jito.as_ref())
+    }
+}
+
+// -----------------
+// PriorityFeeConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PriorityFeeConfig {
+    /// The lowest compute unit price in micro lamports offered for a commit
+    #[serde(default = "default_min_compute_unit_price")]
+    pub min_compute_unit_price: u64,
+    /// The highest compute unit price in micro lamports offered for a commit,
+    /// even if the base layer is more congested than that
+    #[serde(default = "default_max_compute_unit_price")]
+    pub max_compute_unit_price: u64,
+    /// The percentile (0-100) of the recent prioritization fees paid for the
+    /// committed accounts that we offer
+    #[serde(default = "default_priority_fee_percentile")]
+    pub percentile: u8,
+}
+
+fn default_min_compute_unit_price() -> u64 {
+    0
+}
+
+fn default_max_compute_unit_price() -> u64 {
+    10_000_000 // 10 Lamports
+}
+
+fn default_priority_fee_percentile() -> u8 {
+    75
+}
+
+impl Default for PriorityFeeConfig {
+    fn default() -> Self {
+        Self {
+            min_compute_unit_price: default_min_compute_unit_price(),
+            max_compute_unit_price: default_max_compute_unit_price(),
+            percentile: default_priority_fee_percentile(),
         }
     }
 }
 
+// -----------------
+// ScheduleCommitFeeConfig
+// -----------------
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleCommitFeeConfig {
+    /// Lamports charged for each scheduled commit
+    #[serde(default)]
+    pub flat_lamports: u64,
+    /// Lamports charged for each account of a scheduled commit
+    #[serde(default)]
+    pub per_account_lamports: u64,
+}
+
+// -----------------
+// DurableNonceConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DurableNonceConfig {
+    /// The nonce account on the remote cluster, its authority needs to be the
+    /// validator identity which signs the commits
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub account: Pubkey,
+    /// How long to wait for a commit transaction to land before sending it
+    /// again
+    #[serde(default = "default_nonce_retry_interval_millis")]
+    pub retry_interval_millis: u64,
+    /// How often a commit transaction is sent before giving up on it, by
+    /// default it is retried until it lands
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+}
+
+fn default_nonce_retry_interval_millis() -> u64 {
+    2_000
+}
+
+// -----------------
+// JitoConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct JitoConfig {
+    /// The JSON RPC endpoint of the Jito block engine bundles are sent to
+    #[serde(default = "default_jito_url")]
+    pub url: String,
+    /// The tip in lamports paid to Jito with each bundle
+    #[serde(default = "default_jito_tip_lamports")]
+    pub tip_lamports: u64,
+}
+
+fn default_jito_url() -> String {
+    "https://mainnet.block-engine.jito.wtf/api/v1/bundles".to_string()
+}
+
+fn default_jito_tip_lamports() -> u64 {
+    10_000
+}
+
+impl Default for JitoConfig {
+    fn default() -> Self {
+        Self {
+            url: default_jito_url(),
+            tip_lamports: default_jito_tip_lamports(),
+        }
+    }
+}
+
+// -----------------
+// PayerMonitorConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PayerMonitorConfig {
+    /// How often the balance of the payer is checked
+    #[serde(default = "default_payer_check_interval_millis")]
+    pub check_interval_millis: u64,
+    /// Below this balance in lamports an alert is raised and the payer is
+    /// topped up if a treasury is configured
+    #[serde(default = "default_payer_min_lamports")]
+    pub min_lamports: u64,
+    /// Path to the keypair file of the treasury the payer is topped up from,
+    /// without it the payer is only monitored
+    #[serde(default)]
+    pub treasury_keypair: Option<String>,
+    /// The lamports transferred from the treasury with each top-up
+    #[serde(default = "default_payer_top_up_lamports")]
+    pub top_up_lamports: u64,
+}
+
+fn default_payer_check_interval_millis() -> u64 {
+    60_000
+}
+
+fn default_payer_min_lamports() -> u64 {
+    LAMPORTS_PER_SOL
+}
+
+fn default_payer_top_up_lamports() -> u64 {
+    LAMPORTS_PER_SOL
+}
+
+impl Default for PayerMonitorConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_millis: default_payer_check_interval_millis(),
+            min_lamports: default_payer_min_lamports(),
+            treasury_keypair: None,
+            top_up_lamports: default_payer_top_up_lamports(),
+        }
+    }
+}
+
+// -----------------
+// CommitWebhookConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CommitWebhookConfig {
+    /// The endpoint JSON events are posted to
+    pub url: Url,
+    /// Value of the `Authorization` header sent with each event
+    #[serde(default)]
+    pub auth_header: Option<String>,
+    /// How often delivering an event is retried before it is dropped
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_webhook_max_retries() -> u32 {
+    3
+}
+
 // -----------------
 // Payer
 // -----------------
@@ -170,6 +750,16 @@ pub struct AllowedProgram {
     pub id: Pubkey,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct DelegationProgram {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+}
+
 fn pubkey_deserialize<'de, D>(deserializer: D) -> Result<Pubkey, D::Error>
 where
     D: serde::Deserializer<'de>,
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_DISK_CHECK_INTERVAL_MILLIS: u64 = 10_000;
+
+/// Budgets the disk usage of the validator is checked against.
+/// Exceeding them triggers emergency actions before the disk fills up and
+/// corrupts the ledger, nothing is enforced for the ones that are not set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DiskConfig {
+    /// How often the disk usage is checked
+    #[serde(default = "default_disk_check_interval_millis")]
+    pub check_interval_millis: u64,
+    /// Above this size of the ledger all slots that aren't needed to replay
+    /// it are truncated right away
+    #[serde(default)]
+    pub ledger_budget_bytes: Option<u64>,
+    /// Above this size of the accounts database the validator switches to
+    /// read-only mode, since it cannot be shrunk
+    #[serde(default)]
+    pub accountsdb_budget_bytes: Option<u64>,
+    /// Above this size of the accounts database snapshots all but the
+    /// latest one are removed
+    #[serde(default)]
+    pub snapshots_budget_bytes: Option<u64>,
+    /// Below this free space on the disk of the ledger the ledger is
+    /// truncated and snapshots are pruned
+    #[serde(default)]
+    pub min_free_bytes: Option<u64>,
+    /// Below this free space on the disk of the ledger the validator
+    /// switches to read-only mode, rejecting transactions until enough
+    /// space was freed up again
+    #[serde(default)]
+    pub read_only_free_bytes: Option<u64>,
+}
+
+const fn default_disk_check_interval_millis() -> u64 {
+    DEFAULT_DISK_CHECK_INTERVAL_MILLIS
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_millis: default_disk_check_interval_millis(),
+            ledger_budget_bytes: Default::default(),
+            accountsdb_budget_bytes: Default::default(),
+            snapshots_budget_bytes: Default::default(),
+            min_free_bytes: Default::default(),
+            read_only_free_bytes: Default::default(),
+        }
+    }
+}
@@ -15,6 +15,19 @@ pub enum ConfigError {
     #[error("Program with id '{0}' has invalid path '{1}'")]
     ProgramPathInvalidUnicode(String, String),
 
+    #[error("Geyser plugin has invalid config path '{0}'")]
+    GeyserPluginConfigPathInvalidUnicode(String),
+
     #[error("Cannot specify both init_lamports and init_sol")]
     CannotSpecifyBothInitLamportAndInitSol,
+
+    #[error(
+        "Cannot submit commits as Jito bundles when using a durable nonce"
+    )]
+    CannotSpecifyBothJitoAndDurableNonce,
+
+    #[error(
+        "Bootstrapping requires exactly one of accountsdb-path or accounts-dir"
+    )]
+    BootstrapRequiresExactlyOneSource,
 }
@@ -1,7 +1,146 @@
-use crate::helpers;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
-helpers::socket_addr_config! {
-    GeyserGrpcConfig,
-    10_000,
-    "geyser_grpc"
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GeyserGrpcConfig {
+    /// Only local clients can connect by default, set it to `0.0.0.0` in
+    /// order to expose the gRPC endpoint.
+    #[serde(
+        default = "default_addr",
+        deserialize_with = "deserialize_addr",
+        serialize_with = "serialize_addr"
+    )]
+    pub addr: IpAddr,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Serves the gRPC endpoint via TLS, it is unencrypted if not provided.
+    #[serde(default)]
+    pub tls: Option<GeyserGrpcTlsConfig>,
+    /// Clients need to provide one of these tokens via the `x-token` header.
+    /// If empty any client may connect.
+    #[serde(default)]
+    pub x_tokens: Vec<String>,
+    /// Number of recent slots whose updates are buffered so that clients can
+    /// replay them after reconnecting. Replay is disabled if `0`.
+    #[serde(default)]
+    pub replay_stored_slots: u64,
+    /// Max size of the buffered updates, older slots are dropped first.
+    #[serde(default = "default_replay_max_bytes")]
+    pub replay_max_bytes: usize,
+    /// Allows account subscriptions that don't select any accounts or
+    /// owners. Disable it to require consumers to filter server-side instead
+    /// of receiving all account updates.
+    #[serde(default = "default_accounts_filter_any")]
+    pub accounts_filter_any: bool,
+    /// Max number of accounts as well as owners a single accounts filter
+    /// may select.
+    #[serde(default = "default_accounts_filter_max_pubkeys")]
+    pub accounts_filter_max_pubkeys: usize,
+    /// Max number of updates queued for a single subscriber that doesn't
+    /// receive them fast enough.
+    #[serde(default = "default_client_queue_capacity")]
+    pub client_queue_capacity: usize,
+    /// What happens to a subscriber once its queue is full.
+    #[serde(default)]
+    pub slow_client_policy: GeyserGrpcSlowClientPolicy,
+    /// Emits an entry spanning all transactions of each block in addition to
+    /// the block metadata.
+    #[serde(default)]
+    pub entry_notifications: bool,
+}
+
+impl Default for GeyserGrpcConfig {
+    fn default() -> Self {
+        Self {
+            addr: default_addr(),
+            port: default_port(),
+            tls: None,
+            x_tokens: Default::default(),
+            replay_stored_slots: 0,
+            replay_max_bytes: default_replay_max_bytes(),
+            accounts_filter_any: default_accounts_filter_any(),
+            accounts_filter_max_pubkeys: default_accounts_filter_max_pubkeys(),
+            client_queue_capacity: default_client_queue_capacity(),
+            slow_client_policy: Default::default(),
+            entry_notifications: false,
+        }
+    }
+}
+
+impl GeyserGrpcConfig {
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.addr, self.port)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GeyserGrpcTlsConfig {
+    /// PEM encoded certificate (chain) of the server
+    pub cert_path: String,
+    /// PEM encoded private key of the server
+    pub key_path: String,
+    /// PEM encoded CA certificate that client certificates need to be
+    /// signed by, enables mutual TLS if provided.
+    #[serde(default)]
+    pub client_ca_cert_path: Option<String>,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum GeyserGrpcSlowClientPolicy {
+    /// Closes the stream of the subscriber with a `lagged` error
+    #[default]
+    Disconnect,
+    /// Drops the oldest queued updates of the subscriber to make room for
+    /// new ones
+    DropOldest,
+}
+
+fn deserialize_addr<'de, D>(deserializer: D) -> Result<IpAddr, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(|err| {
+        // The error returned here by serde is a bit unhelpful so we help out
+        // by logging a bit more information.
+        eprintln!("The [geyser_grpc] field 'addr' is invalid ({:?}).", err);
+        serde::de::Error::custom(err)
+    })
+}
+
+fn serialize_addr<S>(addr: &IpAddr, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_str(addr.to_string().as_ref())
+}
+
+fn default_addr() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+fn default_port() -> u16 {
+    10_000
+}
+
+fn default_replay_max_bytes() -> usize {
+    256 * 1024 * 1024
+}
+
+fn default_accounts_filter_any() -> bool {
+    true
+}
+
+fn default_accounts_filter_max_pubkeys() -> usize {
+    10_000
+}
+
+fn default_client_queue_capacity() -> usize {
+    1024
 }
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// A standard Solana geyser plugin loaded from a shared library when the
+/// validator starts up.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GeyserPluginConfig {
+    /// The JSON config file of the plugin, it is passed to the plugin as is
+    /// and needs to provide the `libpath` of the plugin library.
+    pub config: String,
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct GeyserSinkConfig {
+    /// The message bus the updates are published to
+    pub bus: GeyserSinkBus,
+    /// How each update is serialized into a message
+    #[serde(default)]
+    pub format: GeyserSinkFormat,
+    /// Topic (Kafka) or subject (NATS) account updates are published to,
+    /// account updates are not published if not provided.
+    #[serde(default)]
+    pub accounts_topic: Option<String>,
+    /// Topic (Kafka) or subject (NATS) transaction notifications are
+    /// published to, transactions are not published if not provided.
+    #[serde(default)]
+    pub transactions_topic: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, tag = "type", rename_all = "kebab-case")]
+pub enum GeyserSinkBus {
+    // Produces each update as a message keyed by the account pubkey or
+    // transaction signature.
+    // Requires the validator to be built with the `kafka` feature.
+    Kafka { brokers: String },
+    // Publishes each update to the NATS server at the url.
+    // Requires the validator to be built with the `nats` feature.
+    Nats { url: String },
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum GeyserSinkFormat {
+    /// `SubscribeUpdate` of the geyser gRPC protocol, encoded as protobuf
+    #[default]
+    Protobuf,
+    /// JSON object with base58 encoded keys and base64 encoded account data
+    Json,
+}
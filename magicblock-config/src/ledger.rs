@@ -4,6 +4,8 @@ use crate::helpers::serde_defaults::bool_true;
 
 // Default desired ledger size 100 GiB
 pub const DEFAULT_LEDGER_SIZE_BYTES: u64 = 100 * 1024 * 1024 * 1024;
+pub const DEFAULT_ARCHIVE_COMPRESSION_LEVEL: i32 = 3;
+pub const DEFAULT_EXPORT_POLL_INTERVAL_MILLIS: u64 = 50;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields, rename_all = "kebab-case")]
@@ -19,6 +21,22 @@ pub struct LedgerConfig {
     // The size under which it's desired to keep ledger in bytes.
     #[serde(default = "default_ledger_size")]
     pub size: u64,
+    // The number of most recent slots to keep in the ledger.
+    // Older slots are truncated even if the ledger is below its size.
+    #[serde(default)]
+    pub retention_slots: Option<u64>,
+    // The maximum age of slots kept in the ledger in seconds.
+    // Older slots are truncated even if the ledger is below its size.
+    #[serde(default)]
+    pub retention_secs: Option<u64>,
+    // If set, slots are exported to compressed archives before they are
+    // truncated, so the full history remains reconstructable.
+    #[serde(default)]
+    pub archive: Option<LedgerArchiveConfig>,
+    // If set, newly completed blocks are streamed to the configured sink
+    // as they are written to the ledger.
+    #[serde(default)]
+    pub export: Option<LedgerExportConfig>,
 }
 
 const fn default_ledger_size() -> u64 {
@@ -31,6 +49,70 @@ impl Default for LedgerConfig {
             reset: bool_true(),
             path: Default::default(),
             size: DEFAULT_LEDGER_SIZE_BYTES,
+            retention_slots: Default::default(),
+            retention_secs: Default::default(),
+            archive: Default::default(),
+            export: Default::default(),
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LedgerArchiveConfig {
+    // The directory into which archives of truncated slots are written.
+    // Point it at a mounted object store bucket to keep archives off the host.
+    pub path: String,
+    // The zstd compression level used for the archives
+    #[serde(default = "default_archive_compression_level")]
+    pub compression_level: i32,
+}
+
+impl LedgerArchiveConfig {
+    pub fn new(path: String) -> Self {
+        Self {
+            path,
+            compression_level: default_archive_compression_level(),
+        }
+    }
+}
+
+const fn default_archive_compression_level() -> i32 {
+    DEFAULT_ARCHIVE_COMPRESSION_LEVEL
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LedgerExportConfig {
+    // The destination the block records are streamed to
+    pub sink: LedgerExportSink,
+    // How often the ledger is checked for new blocks once the exporter
+    // caught up with it
+    #[serde(default = "default_export_poll_interval_millis")]
+    pub poll_interval_millis: u64,
+}
+
+impl LedgerExportConfig {
+    pub fn new(sink: LedgerExportSink) -> Self {
+        Self {
+            sink,
+            poll_interval_millis: default_export_poll_interval_millis(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, tag = "type", rename_all = "kebab-case")]
+pub enum LedgerExportSink {
+    // Appends the records to the file at the path
+    File { path: String },
+    // Writes the records to the Unix socket at the path
+    UnixSocket { path: String },
+    // Produces each record as a message to the topic.
+    // Requires the validator to be built with the `kafka` feature.
+    Kafka { brokers: String, topic: String },
+}
+
+const fn default_export_poll_interval_millis() -> u64 {
+    DEFAULT_EXPORT_POLL_INTERVAL_MILLIS
+}
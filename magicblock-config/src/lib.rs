@@ -11,20 +11,30 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 mod accounts;
+mod disk;
 pub mod errors;
 mod geyser_grpc;
+mod geyser_plugin;
+mod geyser_sink;
 mod helpers;
 mod ledger;
+mod logging;
 mod metrics;
 mod program;
 mod rpc;
+mod tracing;
 mod validator;
 pub use accounts::*;
+pub use disk::*;
 pub use geyser_grpc::*;
+pub use geyser_plugin::*;
+pub use geyser_sink::*;
 pub use ledger::*;
+pub use logging::*;
 pub use metrics::*;
 pub use program::*;
 pub use rpc::*;
+pub use tracing::*;
 pub use validator::*;
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
@@ -45,6 +55,19 @@ pub struct EphemeralConfig {
     pub programs: Vec<ProgramConfig>,
     #[serde(default)]
     pub metrics: MetricsConfig,
+    #[serde(default)]
+    #[serde(rename = "geyser_plugin")]
+    pub geyser_plugins: Vec<GeyserPluginConfig>,
+    /// Publishes geyser updates to a message bus if provided
+    #[serde(default)]
+    pub geyser_sink: Option<GeyserSinkConfig>,
+    /// Exports traces of the transaction lifecycle via OTLP if provided
+    #[serde(default)]
+    pub tracing: Option<TracingConfig>,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
 }
 
 impl EphemeralConfig {
@@ -84,6 +107,27 @@ impl EphemeralConfig {
                     .to_string()
             }
         }
+        // Geyser plugin configs are resolved the same way as program paths
+        for plugin in &mut config.geyser_plugins {
+            if let Some(config_path) = config_path {
+                plugin.config = config_path
+                    .parent()
+                    .ok_or_else(|| {
+                        ConfigError::ConfigPathInvalid(format!(
+                            "Config path: '{}' is missing parent dir",
+                            config_path.display()
+                        ))
+                    })?
+                    .join(&plugin.config)
+                    .to_str()
+                    .ok_or_else(|| {
+                        ConfigError::GeyserPluginConfigPathInvalidUnicode(
+                            plugin.config.to_string(),
+                        )
+                    })?
+                    .to_string()
+            }
+        }
         Ok(config)
     }
 
@@ -171,6 +215,24 @@ impl EphemeralConfig {
             });
         }
 
+        if let Ok(origins) = env::var("RPC_CORS_ALLOWED_ORIGINS") {
+            config.rpc.cors_allowed_origins = origins
+                .split(',')
+                .map(|origin| origin.trim().to_string())
+                .filter(|origin| !origin.is_empty())
+                .collect();
+        }
+
+        if let Ok(enable_admin) = env::var("RPC_ENABLE_ADMIN") {
+            config.rpc.enable_admin = bool::from_str(&enable_admin)
+                .unwrap_or_else(|err| {
+                    panic!(
+                        "Failed to parse 'RPC_ENABLE_ADMIN' as bool: {:?}",
+                        err
+                    )
+                });
+        }
+
         // -----------------
         // Geyser GRPC
         // -----------------
@@ -194,6 +256,14 @@ impl EphemeralConfig {
                 });
         }
 
+        if let Ok(x_tokens) = env::var("GEYSER_GRPC_X_TOKENS") {
+            config.geyser_grpc.x_tokens = x_tokens
+                .split(',')
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty())
+                .collect();
+        }
+
         // -----------------
         // Validator
         // -----------------
@@ -235,6 +305,24 @@ impl EphemeralConfig {
             config.validator.fdqn = Some(fdqn)
         }
 
+        if let Ok(ordering) = env::var("VALIDATOR_TRANSACTION_ORDERING") {
+            config.validator.transaction_ordering = ordering.parse().unwrap_or_else(|err| {
+                panic!(
+                    "Failed to parse 'VALIDATOR_TRANSACTION_ORDERING' as TransactionOrdering: {}: {:?}",
+                    ordering, err
+                )
+            })
+        }
+
+        if let Ok(clock_source) = env::var("VALIDATOR_CLOCK_SOURCE") {
+            config.validator.clock_source = clock_source.parse().unwrap_or_else(|err| {
+                panic!(
+                    "Failed to parse 'VALIDATOR_CLOCK_SOURCE' as ClockSource: {}: {:?}",
+                    clock_source, err
+                )
+            })
+        }
+
         // -----------------
         // Ledger
         // -----------------
@@ -252,6 +340,42 @@ impl EphemeralConfig {
                 panic!("Failed to parse 'LEDGER_SIZE' as u64: {:?}", err)
             });
         }
+        if let Ok(retention_slots) = env::var("LEDGER_RETENTION_SLOTS") {
+            config.ledger.retention_slots =
+                Some(u64::from_str(&retention_slots).unwrap_or_else(|err| {
+                    panic!(
+                        "Failed to parse 'LEDGER_RETENTION_SLOTS' as u64: {:?}",
+                        err
+                    )
+                }));
+        }
+        if let Ok(retention_secs) = env::var("LEDGER_RETENTION_SECS") {
+            config.ledger.retention_secs =
+                Some(u64::from_str(&retention_secs).unwrap_or_else(|err| {
+                    panic!(
+                        "Failed to parse 'LEDGER_RETENTION_SECS' as u64: {:?}",
+                        err
+                    )
+                }));
+        }
+        if let Ok(archive_path) = env::var("LEDGER_ARCHIVE_PATH") {
+            match config.ledger.archive.as_mut() {
+                Some(archive) => archive.path = archive_path,
+                None => {
+                    config.ledger.archive =
+                        Some(LedgerArchiveConfig::new(archive_path))
+                }
+            }
+        }
+        if let Ok(export_path) = env::var("LEDGER_EXPORT_FILE_PATH") {
+            let sink = LedgerExportSink::File { path: export_path };
+            match config.ledger.export.as_mut() {
+                Some(export) => export.sink = sink,
+                None => {
+                    config.ledger.export = Some(LedgerExportConfig::new(sink))
+                }
+            }
+        }
 
         // -----------------
         // Metrics
@@ -295,6 +419,74 @@ impl EphemeralConfig {
     }
 }
 
+impl EphemeralConfig {
+    /// Renders the config as TOML with secrets removed so it can be shared,
+    /// i.e. as part of a diagnostics bundle.
+    /// Values of keys that hold credentials are replaced and URLs are reduced
+    /// to their scheme and host since providers often embed API keys in the
+    /// path or query.
+    pub fn to_redacted_string(&self) -> String {
+        match toml::Value::try_from(self) {
+            Ok(mut value) => {
+                redact_toml_value(None, &mut value);
+                toml::to_string_pretty(&value)
+                    .unwrap_or("Invalid Config".to_string())
+            }
+            Err(_) => "Invalid Config".to_string(),
+        }
+    }
+}
+
+const REDACTED: &str = "<redacted>";
+const SECRET_KEY_PARTS: [&str; 5] =
+    ["auth", "token", "secret", "password", "keypair"];
+
+fn redact_toml_value(key: Option<&str>, value: &mut toml::Value) {
+    let is_secret_key = key.is_some_and(|key| {
+        let key = key.to_lowercase();
+        SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+    });
+    match value {
+        toml::Value::String(string) => {
+            if is_secret_key {
+                *string = REDACTED.to_string();
+            } else if let Some(redacted) = redact_url(string) {
+                *string = redacted;
+            }
+        }
+        toml::Value::Array(values) => {
+            for value in values {
+                redact_toml_value(key, value);
+            }
+        }
+        toml::Value::Table(table) => {
+            for (key, value) in table.iter_mut() {
+                redact_toml_value(Some(key), value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Strips credentials, path and query from the URL, returns `None` if the
+/// string is not a URL or has nothing to strip
+fn redact_url(string: &str) -> Option<String> {
+    let url = Url::parse(string).ok()?;
+    let host = url.host_str()?;
+    let has_secrets = !url.username().is_empty()
+        || url.password().is_some()
+        || url.path() != "/"
+        || url.query().is_some()
+        || url.fragment().is_some();
+    if !has_secrets {
+        return None;
+    }
+    Some(match url.port() {
+        Some(port) => format!("{}://{}:{}", url.scheme(), host, port),
+        None => format!("{}://{}", url.scheme(), host),
+    })
+}
+
 impl fmt::Display for EphemeralConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let toml = toml::to_string_pretty(self)
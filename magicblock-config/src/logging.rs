@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct LoggingConfig {
+    /// How log records are written to stderr
+    /// default: "plain"
+    #[serde(default)]
+    pub format: LogFormat,
+    /// Transactions taking longer than this to execute are logged with
+    /// their timings and account counts, none are logged if not set
+    #[serde(default)]
+    pub slow_transaction_threshold_millis: Option<u64>,
+    /// RPC requests taking longer than this to be handled are logged with
+    /// their method and payload sizes, none are logged if not set
+    #[serde(default)]
+    pub slow_rpc_request_threshold_millis: Option<u64>,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum LogFormat {
+    /// Human readable lines, the style can be picked via `RUST_LOG_STYLE`
+    #[default]
+    Plain,
+    /// One JSON object per record, structured fields like the `slot`,
+    /// `signature` or `pubkey` a record refers to are included as keys
+    Json,
+}
@@ -15,6 +15,25 @@ pub struct RpcConfig {
     pub port: u16,
     #[serde(default = "default_max_ws_connections")]
     pub max_ws_connections: usize,
+    /// Origins that browsers are allowed to issue requests from, honored by
+    /// the HTTP service (CORS) and the WebSocket handshake.
+    /// If empty or containing `"*"` any origin is allowed.
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+    /// Enables admin methods (i.e. `createLedgerBackup`), they are served on
+    /// a separate endpoint at `admin-addr` and `admin-port`.
+    #[serde(default)]
+    pub enable_admin: bool,
+    /// Only local clients can use the admin methods by default, never expose
+    /// this address to untrusted clients.
+    #[serde(
+        default = "default_admin_addr",
+        deserialize_with = "deserialize_addr",
+        serialize_with = "serialize_addr"
+    )]
+    pub admin_addr: IpAddr,
+    #[serde(default = "default_admin_port")]
+    pub admin_port: u16,
 }
 
 impl Default for RpcConfig {
@@ -23,6 +42,10 @@ impl Default for RpcConfig {
             addr: default_addr(),
             port: default_port(),
             max_ws_connections: default_max_ws_connections(),
+            cors_allowed_origins: Default::default(),
+            enable_admin: false,
+            admin_addr: default_admin_addr(),
+            admin_port: default_admin_port(),
         }
     }
 }
@@ -31,6 +54,12 @@ impl RpcConfig {
     pub fn socket_addr(&self) -> SocketAddr {
         SocketAddr::new(self.addr, self.port)
     }
+
+    /// The address admin methods are served at if they are enabled
+    pub fn admin_socket_addr(&self) -> Option<SocketAddr> {
+        self.enable_admin
+            .then(|| SocketAddr::new(self.admin_addr, self.admin_port))
+    }
 }
 
 fn deserialize_addr<'de, D>(deserializer: D) -> Result<IpAddr, D::Error>
@@ -61,6 +90,14 @@ fn default_port() -> u16 {
     8899
 }
 
+fn default_admin_addr() -> IpAddr {
+    IpAddr::V4(Ipv4Addr::LOCALHOST)
+}
+
+fn default_admin_port() -> u16 {
+    8898
+}
+
 fn default_max_ws_connections() -> usize {
     16384
 }
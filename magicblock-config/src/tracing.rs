@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct TracingConfig {
+    /// OTLP gRPC endpoint of the collector spans are exported to, i.e.
+    /// `http://localhost:4317` for a local Jaeger instance.
+    /// Requires the validator to be built with the `otlp` feature.
+    pub endpoint: String,
+    /// Name the validator is reported as in the traces
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+fn default_service_name() -> String {
+    "magicblock-validator".to_string()
+}
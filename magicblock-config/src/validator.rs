@@ -1,5 +1,11 @@
+use std::str::FromStr;
+
 use isocountry::CountryCode;
 use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use strum_macros::EnumString;
+
+use crate::AllowedProgram;
 
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -8,7 +14,9 @@ pub struct ValidatorConfig {
     pub millis_per_slot: u64,
 
     /// By default the validator will verify transaction signature.
-    /// This can be disabled by setting [Self::sigverify] to `false`.
+    /// This can be disabled by setting [Self::sigverify] to `false`, which
+    /// saves a considerable amount of CPU when only trusted clients are
+    /// able to send transactions, i.e. during local development.
     #[serde(default = "default_sigverify")]
     pub sigverify: bool,
 
@@ -24,6 +32,348 @@ pub struct ValidatorConfig {
     /// default: "US"
     #[serde(default = "default_country_code")]
     pub country_code: CountryCode,
+
+    /// Programs that may invoke the magic program to schedule commits.
+    /// If empty any program may do so, which is fine for private ephemeral
+    /// rollups, shared ones should restrict it to prevent scheduling spam.
+    /// NOTE: mutating accounts via the magic program is always restricted to
+    /// the validator authority.
+    #[serde(default)]
+    pub magic_program_allowlist: Vec<AllowedProgram>,
+
+    /// Runtime features to activate or deactivate on top of the ones the
+    /// validator activates by default.
+    #[serde(default)]
+    pub features: FeaturesConfig,
+
+    /// Order in which transactions competing for execution are processed.
+    /// default: "fifo"
+    #[serde(default)]
+    pub transaction_ordering: TransactionOrdering,
+
+    /// If set, slots are only produced when transactions were executed
+    /// during the current slot, instead of every [Self::millis_per_slot].
+    /// This keeps the ledger of rarely used ephemeral rollups small.
+    #[serde(default)]
+    pub on_demand_slots: Option<OnDemandSlotsConfig>,
+
+    /// Limits how much transactions may grow the accounts data, which keeps
+    /// a single user from filling up the accounts db.
+    #[serde(default)]
+    pub account_data_growth: AccountDataGrowthConfig,
+
+    /// Which program logs are recorded in the ledger and returned to
+    /// subscribers and simulations.
+    #[serde(default)]
+    pub transaction_logs: TransactionLogsConfig,
+
+    /// If set, the validator pays the fees of transactions signed by session
+    /// keys registered via the magic program, as long as they only invoke
+    /// the programs the session key was registered for.
+    /// This enables gasless games without each of them running a relayer.
+    #[serde(default)]
+    pub sponsor_session_key_fees: bool,
+
+    /// Programs that may register session keys in their own scope via CPI
+    /// without the validator authority signing, in which case the validator
+    /// authority still funds them.
+    /// Only list programs that restrict who may register session keys.
+    #[serde(default)]
+    pub session_key_sponsor_programs: Vec<AllowedProgram>,
+
+    /// Where the time and epoch of the Clock sysvar come from.
+    /// default: "local"
+    #[serde(default)]
+    pub clock_source: ClockSource,
+
+    /// Limits of the transactions the validator accepts, raising them lets
+    /// rollups reached via private networking accept transactions that would
+    /// not fit on the base layer.
+    #[serde(default)]
+    pub transaction_limits: TransactionLimitsConfig,
+
+    /// Min fees transactions sent via RPC need to pay, which protects public
+    /// validators against spam that fills up slots for free.
+    #[serde(default)]
+    pub fee_floor: FeeFloorConfig,
+
+    /// Programs which are cloned and compiled at startup and kept in the
+    /// program cache, which avoids latency spikes when big programs are
+    /// invoked after they were not used for a while.
+    #[serde(default)]
+    pub pinned_programs: Vec<PinnedProgram>,
+
+    /// Max time a graceful shutdown waits for the scheduled commits to land
+    /// on the base layer before the validator exits anyway.
+    /// default: 60 seconds
+    #[serde(default = "default_shutdown_timeout_millis")]
+    pub shutdown_timeout_millis: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct OnDemandSlotsConfig {
+    /// Max time without a new slot, even if no transactions were executed,
+    /// which keeps the blockhash fresh and time based features like recurring
+    /// commits working.
+    #[serde(default = "default_max_idle_millis")]
+    pub max_idle_millis: u64,
+}
+
+fn default_max_idle_millis() -> u64 {
+    1_000
+}
+
+impl Default for OnDemandSlotsConfig {
+    fn default() -> Self {
+        Self {
+            max_idle_millis: default_max_idle_millis(),
+        }
+    }
+}
+
+/// Accounts data growth limits in bytes. Transactions exceeding them fail,
+/// only their fees are charged.
+/// Transactions paid for by the validator, i.e. account clones, are not
+/// limited.
+/// NOTE: changing these for an existing ledger may change the outcome of
+/// replayed transactions.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountDataGrowthConfig {
+    /// default: 20 MiB, same as on the base layer
+    #[serde(default = "default_max_growth_per_transaction")]
+    pub max_per_transaction: u64,
+    /// Across all transactions of a slot
+    /// default: 100 MB, same as on the base layer
+    #[serde(default = "default_max_growth_per_slot")]
+    pub max_per_slot: u64,
+}
+
+fn default_max_growth_per_transaction() -> u64 {
+    20 * 1024 * 1024
+}
+
+fn default_max_growth_per_slot() -> u64 {
+    100_000_000
+}
+
+impl Default for AccountDataGrowthConfig {
+    fn default() -> Self {
+        Self {
+            max_per_transaction: default_max_growth_per_transaction(),
+            max_per_slot: default_max_growth_per_slot(),
+        }
+    }
+}
+
+/// NOTE: lowering these for an existing ledger may fail replaying
+/// transactions that were accepted before.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionLimitsConfig {
+    /// Max serialized size of a transaction in bytes
+    /// default: 1232, the packet size of the base layer
+    #[serde(default = "default_max_transaction_size")]
+    pub max_size: usize,
+    /// Max number of accounts a transaction may reference, including the ones
+    /// loaded from address lookup tables
+    /// default: 64, same as on the base layer
+    #[serde(default = "default_max_transaction_account_locks")]
+    pub max_account_locks: usize,
+}
+
+fn default_max_transaction_size() -> usize {
+    1232
+}
+
+fn default_max_transaction_account_locks() -> usize {
+    64
+}
+
+impl Default for TransactionLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_size: default_max_transaction_size(),
+            max_account_locks: default_max_transaction_account_locks(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct PinnedProgram {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+}
+
+/// Transactions paying less are rejected before they are executed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeFloorConfig {
+    /// Min price in micro-lamports per compute unit
+    /// default: 0
+    #[serde(default)]
+    pub min_compute_unit_price: u64,
+    /// Min fee in lamports paid for each signature, including the
+    /// prioritization fee, only enforceable if [ValidatorConfig::base_fees]
+    /// or a compute unit price are charged
+    /// default: 0
+    #[serde(default)]
+    pub min_fee_per_signature: u64,
+    /// Transactions paid by these accounts are exempt, i.e. the ones of
+    /// trusted relayers
+    #[serde(default)]
+    pub exempt_fee_payers: Vec<ExemptFeePayer>,
+    /// Transactions invoking only these programs are exempt
+    #[serde(default)]
+    pub exempt_programs: Vec<AllowedProgram>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExemptFeePayer {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+}
+
+/// Allows keeping noisy programs from bloating the ledger and suppressing
+/// debug logs which should not be public.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionLogsConfig {
+    /// Level of programs not listed in [Self::programs]
+    /// default: "all"
+    #[serde(default)]
+    pub level: TransactionLogLevel,
+    #[serde(default)]
+    pub programs: Vec<ProgramTransactionLogs>,
+    /// Logs of a transaction exceeding this are truncated
+    #[serde(default)]
+    pub max_bytes_per_transaction: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProgramTransactionLogs {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+    pub level: TransactionLogLevel,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum TransactionLogLevel {
+    #[default]
+    All,
+    /// Only the invocations of the program logged by the runtime, without
+    /// the messages and data the program logs itself
+    Invocations,
+    None,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum TransactionOrdering {
+    /// In the order they were received in
+    #[default]
+    Fifo,
+    /// By compute unit price, highest first, which lets users pay for
+    /// faster processing during contention like on the base layer
+    FeePriority,
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+    EnumString,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case")]
+pub enum ClockSource {
+    /// Derived from the system time of the validator
+    #[default]
+    Local,
+    /// Anchored to the clock of the base layer which is sampled by the
+    /// account updates subscriptions, so programs doing time based logic
+    /// observe the same time and epoch as on chain
+    Remote,
+}
+
+/// Allows matching the feature activations of the base layer or trying out
+/// upcoming features early.
+/// A feature listed in both lists ends up deactivated.
+/// NOTE: changing these for an existing ledger may change the outcome of
+/// replayed transactions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeaturesConfig {
+    #[serde(default)]
+    pub activate: Vec<FeatureGate>,
+    #[serde(default)]
+    pub deactivate: Vec<FeatureGate>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeatureGate {
+    #[serde(
+        deserialize_with = "pubkey_deserialize",
+        serialize_with = "pubkey_serialize"
+    )]
+    pub id: Pubkey,
+}
+
+fn pubkey_deserialize<'de, D>(deserializer: D) -> Result<Pubkey, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+}
+
+fn pubkey_serialize<S>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    key.to_string().serialize(serializer)
 }
 
 fn default_millis_per_slot() -> u64 {
@@ -42,6 +392,10 @@ fn default_base_fees() -> Option<u64> {
     None
 }
 
+fn default_shutdown_timeout_millis() -> u64 {
+    60_000
+}
+
 fn default_country_code() -> CountryCode {
     CountryCode::for_alpha2("US").unwrap()
 }
@@ -54,6 +408,19 @@ impl Default for ValidatorConfig {
             fdqn: default_fdqn(),
             base_fees: default_base_fees(),
             country_code: default_country_code(),
+            magic_program_allowlist: Default::default(),
+            features: Default::default(),
+            transaction_ordering: Default::default(),
+            on_demand_slots: Default::default(),
+            account_data_growth: Default::default(),
+            transaction_logs: Default::default(),
+            sponsor_session_key_fees: false,
+            session_key_sponsor_programs: Default::default(),
+            clock_source: Default::default(),
+            transaction_limits: Default::default(),
+            fee_floor: Default::default(),
+            pinned_programs: Default::default(),
+            shutdown_timeout_millis: default_shutdown_timeout_millis(),
         }
     }
 }
@@ -1,11 +1,22 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
 use isocountry::CountryCode;
 use magicblock_config::{
-    AccountsConfig, AllowedProgram, CommitStrategy, EphemeralConfig,
-    GeyserGrpcConfig, LedgerConfig, LifecycleMode, MetricsConfig,
-    MetricsServiceConfig, Payer, PayerParams, ProgramConfig, RemoteConfig,
-    RpcConfig, ValidatorConfig,
+    AccountDataGrowthConfig, AccountsConfig, AllowedProgram, BootstrapConfig,
+    ClockSource, CloningConfig, CommitStrategy, CommitWebhookConfig,
+    DelegationProgram, DiskConfig, DurableNonceConfig, EphemeralConfig,
+    ExemptFeePayer, FastLaneAccount, FeatureGate, FeaturesConfig,
+    FeeFloorConfig, FetcherConfig, GeyserGrpcConfig,
+    GeyserGrpcSlowClientPolicy, GeyserGrpcTlsConfig, GeyserPluginConfig,
+    GeyserSinkBus, GeyserSinkConfig, GeyserSinkFormat, JitoConfig,
+    LedgerArchiveConfig, LedgerConfig, LedgerExportConfig, LedgerExportSink,
+    LifecycleMode, LogFormat, LoggingConfig, MetricsConfig,
+    MetricsServiceConfig, OnDemandSlotsConfig, Payer, PayerMonitorConfig,
+    PayerParams, PinnedProgram, PrefetchMemcmp, PrefetchProgram,
+    PriorityFeeConfig, ProgramConfig, ProgramTransactionLogs, RateLimitConfig,
+    RemoteConfig, RemoteRateLimitConfig, RpcConfig, ScheduleCommitFeeConfig,
+    TracingConfig, TransactionLimitsConfig, TransactionLogLevel,
+    TransactionLogsConfig, TransactionOrdering, UpdatesConfig, ValidatorConfig,
 };
 use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey};
 use url::Url;
@@ -87,6 +98,17 @@ fn test_local_dev_with_programs_toml() {
                 commit: CommitStrategy {
                     frequency_millis: 600_000,
                     compute_unit_price: 0,
+                    changed_only: false,
+                    priority_fee: None,
+                    max_requested_compute_unit_price: 0,
+                    durable_nonce: None,
+                    jito: None,
+                    payer_monitor: None,
+                    webhook: None,
+                    simulate: false,
+                    remote: None,
+                    fallback_remotes: vec![],
+                    schedule_fee: None,
                 },
                 ..Default::default()
             },
@@ -98,7 +120,8 @@ fn test_local_dev_with_programs_toml() {
             rpc: RpcConfig {
                 addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
                 port: 7799,
-                max_ws_connections: 16384
+                max_ws_connections: 16384,
+                ..Default::default()
             },
             validator: ValidatorConfig {
                 millis_per_slot: 14,
@@ -109,7 +132,8 @@ fn test_local_dev_with_programs_toml() {
             },
             geyser_grpc: GeyserGrpcConfig {
                 addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-                port: 11_000
+                port: 11_000,
+                ..Default::default()
             },
             metrics: MetricsConfig {
                 enabled: true,
@@ -119,47 +143,916 @@ fn test_local_dev_with_programs_toml() {
                 },
                 ..Default::default()
             },
+            geyser_plugins: vec![GeyserPluginConfig {
+                config: "geyser-plugin.json".to_string(),
+            }],
+            geyser_sink: None,
+            tracing: None,
+            logging: LoggingConfig::default(),
+            disk: DiskConfig::default(),
+        }
+    )
+}
+
+#[test]
+fn test_custom_remote_toml() {
+    let toml = include_str!("fixtures/07_custom-remote.toml");
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+
+    assert_eq!(
+        config,
+        EphemeralConfig {
+            accounts: AccountsConfig {
+                remote: RemoteConfig::Custom(
+                    Url::parse("http://localhost:8899").unwrap()
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_custom_ws_remote_toml() {
+    let toml = include_str!("fixtures/09_custom-ws-remote.toml");
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+
+    assert_eq!(
+        config,
+        EphemeralConfig {
+            accounts: AccountsConfig {
+                remote: RemoteConfig::CustomWithWs(
+                    Url::parse("http://localhost:8899").unwrap(),
+                    Url::parse("ws://localhost:9001").unwrap()
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_fetcher_fallback_remotes() {
+    let toml = r#"
+[accounts]
+remote = "http://localhost:8899"
+
+[accounts.fetcher]
+fallback-remotes = ["http://localhost:8898", "http://localhost:8897"]
+max-consecutive-failures = 5
+circuit-breaker-cooldown-millis = 10000
+fetch-timeout-millis = 5000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts,
+        AccountsConfig {
+            remote: RemoteConfig::Custom(
+                Url::parse("http://localhost:8899").unwrap()
+            ),
+            fetcher: FetcherConfig {
+                fallback_remotes: vec![
+                    Url::parse("http://localhost:8898").unwrap(),
+                    Url::parse("http://localhost:8897").unwrap(),
+                ],
+                max_consecutive_failures: 5,
+                circuit_breaker_cooldown_millis: 10_000,
+                fetch_timeout_millis: 5_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_rate_limit_per_remote() {
+    let toml = r#"
+[accounts.rate-limit]
+requests-per-second = 20
+max-backoff-millis = 10000
+
+[[accounts.rate-limit.remotes]]
+host = "api.devnet.solana.com"
+requests-per-second = 5
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    let rate_limit = config.accounts.rate_limit;
+    assert_eq!(
+        rate_limit,
+        RateLimitConfig {
+            requests_per_second: 20,
+            max_backoff_millis: 10_000,
+            remotes: vec![RemoteRateLimitConfig {
+                host: "api.devnet.solana.com".to_string(),
+                requests_per_second: Some(5),
+                burst: None,
+            }],
+            ..Default::default()
+        }
+    );
+
+    let devnet = rate_limit.for_remote("wss://api.devnet.solana.com/");
+    assert_eq!(devnet.requests_per_second, 5);
+    assert_eq!(devnet.burst, rate_limit.burst);
+    assert_eq!(devnet.max_backoff_millis, 10_000);
+
+    let other = rate_limit.for_remote("http://localhost:8899");
+    assert_eq!(other.requests_per_second, 20);
+}
+
+#[test]
+fn test_updates_coalesce_interval() {
+    let toml = r#"
+[accounts.updates]
+coalesce-interval-millis = 50
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.updates,
+        UpdatesConfig {
+            coalesce_interval_millis: 50,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        AccountsConfig::default().updates.coalesce_interval_millis,
+        0
+    );
+}
+
+#[test]
+fn test_updates_fast_lane() {
+    let toml = r#"
+[[accounts.updates.fast-lane]]
+id = "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.updates.fast_lane,
+        vec![FastLaneAccount {
+            id: pubkey!("H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG"),
+        }]
+    );
+    assert!(AccountsConfig::default().updates.fast_lane.is_empty());
+}
+
+#[test]
+fn test_commit_priority_fee() {
+    let toml = r#"
+[accounts.commit]
+compute_unit_price = 500_000
+priority_fee = { max_compute_unit_price = 2_000_000, percentile = 90 }
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit,
+        CommitStrategy {
+            compute_unit_price: 500_000,
+            priority_fee: Some(PriorityFeeConfig {
+                min_compute_unit_price: 0,
+                max_compute_unit_price: 2_000_000,
+                percentile: 90,
+            }),
+            ..Default::default()
+        }
+    );
+    assert!(AccountsConfig::default().commit.priority_fee.is_none());
+}
+
+#[test]
+fn test_commit_durable_nonce() {
+    let toml = r#"
+[accounts.commit]
+durable_nonce = { account = "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG", max_retries = 100 }
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit.durable_nonce,
+        Some(DurableNonceConfig {
+            account: pubkey!("H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG"),
+            retry_interval_millis: 2_000,
+            max_retries: Some(100),
+        })
+    );
+    assert!(AccountsConfig::default().commit.durable_nonce.is_none());
+}
+
+#[test]
+fn test_commit_jito() {
+    let toml = r#"
+[accounts.commit]
+jito = { tip_lamports = 50_000 }
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit.try_jito().unwrap(),
+        Some(&JitoConfig {
+            url: "https://mainnet.block-engine.jito.wtf/api/v1/bundles"
+                .to_string(),
+            tip_lamports: 50_000,
+        })
+    );
+    assert!(AccountsConfig::default().commit.jito.is_none());
+
+    let toml = r#"
+[accounts.commit]
+jito = {}
+durable_nonce = { account = "H6ARHf6YXhGYeQfUzQNGk6rDNnLBQKrenN712K4AQJEG" }
+"#;
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert!(config.accounts.commit.try_jito().is_err());
+}
+
+#[test]
+fn test_commit_payer_monitor() {
+    let toml = r#"
+[accounts.commit]
+payer_monitor = { min_lamports = 5_000_000_000, treasury_keypair = "treasury.json" }
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit.payer_monitor,
+        Some(PayerMonitorConfig {
+            check_interval_millis: 60_000,
+            min_lamports: 5 * LAMPORTS_PER_SOL,
+            treasury_keypair: Some("treasury.json".to_string()),
+            top_up_lamports: LAMPORTS_PER_SOL,
+        })
+    );
+    assert!(AccountsConfig::default().commit.payer_monitor.is_none());
+}
+
+#[test]
+fn test_commit_webhook() {
+    let toml = r#"
+[accounts.commit]
+webhook = { url = "https://game.example.com/hooks/commits", auth_header = "Bearer secret" }
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit.webhook,
+        Some(CommitWebhookConfig {
+            url: Url::parse("https://game.example.com/hooks/commits").unwrap(),
+            auth_header: Some("Bearer secret".to_string()),
+            max_retries: 3,
+        })
+    );
+    assert!(AccountsConfig::default().commit.webhook.is_none());
+}
+
+#[test]
+fn test_commit_simulate() {
+    let toml = r#"
+[accounts.commit]
+simulate = true
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert!(config.accounts.commit.simulate);
+    assert!(!AccountsConfig::default().commit.simulate);
+}
+
+#[test]
+fn test_commit_remote() {
+    let toml = r#"
+[accounts]
+remote = "http://read-replica.example.com"
+
+[accounts.commit]
+remote = "http://sender.example.com"
+fallback_remotes = ["http://sender-backup.example.com"]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.remote,
+        RemoteConfig::Custom(
+            Url::parse("http://read-replica.example.com").unwrap()
+        )
+    );
+    assert_eq!(
+        config.accounts.commit.remote,
+        Some(RemoteConfig::Custom(
+            Url::parse("http://sender.example.com").unwrap()
+        ))
+    );
+    assert_eq!(
+        config.accounts.commit.fallback_remotes,
+        vec![Url::parse("http://sender-backup.example.com").unwrap()]
+    );
+    assert!(AccountsConfig::default().commit.remote.is_none());
+}
+
+#[test]
+fn test_commit_changed_only() {
+    let toml = r#"
+[accounts.commit]
+changed_only = true
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert!(config.accounts.commit.changed_only);
+    assert!(!AccountsConfig::default().commit.changed_only);
+}
+
+#[test]
+fn test_commit_max_requested_compute_unit_price() {
+    let toml = r#"
+[accounts.commit]
+max_requested_compute_unit_price = 50_000_000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit.max_requested_compute_unit_price,
+        50_000_000
+    );
+    assert_eq!(
+        AccountsConfig::default()
+            .commit
+            .max_requested_compute_unit_price,
+        0
+    );
+}
+
+#[test]
+fn test_commit_schedule_fee() {
+    let toml = r#"
+[accounts.commit.schedule_fee]
+flat_lamports = 5_000
+per_account_lamports = 1_000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.commit.schedule_fee,
+        Some(ScheduleCommitFeeConfig {
+            flat_lamports: 5_000,
+            per_account_lamports: 1_000,
+        })
+    );
+}
+
+#[test]
+fn test_magic_program_allowlist() {
+    let toml = r#"
+[validator]
+magic_program_allowlist = [
+    { id = "wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4" },
+]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.magic_program_allowlist,
+        vec![AllowedProgram {
+            id: pubkey!("wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4")
+        }]
+    );
+    assert!(ValidatorConfig::default()
+        .magic_program_allowlist
+        .is_empty());
+}
+
+#[test]
+fn test_validator_features() {
+    let toml = r#"
+[validator.features]
+activate = [
+    { id = "EenyoWx9UMXYKpR8mW5Jmfmy2fRjzUtM7NduYMY8bx33" },
+]
+deactivate = [
+    { id = "7Vced912WrRnfjaiKRiNBcbuFw7RrnLv3E3z95Y4GTNc" },
+]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.features,
+        FeaturesConfig {
+            activate: vec![FeatureGate {
+                id: pubkey!("EenyoWx9UMXYKpR8mW5Jmfmy2fRjzUtM7NduYMY8bx33")
+            }],
+            deactivate: vec![FeatureGate {
+                id: pubkey!("7Vced912WrRnfjaiKRiNBcbuFw7RrnLv3E3z95Y4GTNc")
+            }],
+        }
+    );
+    assert_eq!(
+        ValidatorConfig::default().features,
+        FeaturesConfig::default()
+    );
+}
+
+#[test]
+fn test_validator_transaction_ordering() {
+    let toml = r#"
+[validator]
+transaction_ordering = "fee-priority"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.transaction_ordering,
+        TransactionOrdering::FeePriority
+    );
+    assert_eq!(
+        ValidatorConfig::default().transaction_ordering,
+        TransactionOrdering::Fifo
+    );
+}
+
+#[test]
+fn test_validator_on_demand_slots() {
+    let toml = r#"
+[validator.on_demand_slots]
+max_idle_millis = 5000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.on_demand_slots,
+        Some(OnDemandSlotsConfig {
+            max_idle_millis: 5_000
+        })
+    );
+    assert!(ValidatorConfig::default().on_demand_slots.is_none());
+
+    let toml = r#"
+[validator.on_demand_slots]
+"#;
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.on_demand_slots,
+        Some(OnDemandSlotsConfig::default())
+    );
+}
+
+#[test]
+fn test_validator_account_data_growth() {
+    let toml = r#"
+[validator.account_data_growth]
+max_per_transaction = 1024
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.account_data_growth,
+        AccountDataGrowthConfig {
+            max_per_transaction: 1_024,
+            max_per_slot: 100_000_000,
+        }
+    );
+    assert_eq!(
+        ValidatorConfig::default().account_data_growth,
+        AccountDataGrowthConfig {
+            max_per_transaction: 20 * 1024 * 1024,
+            max_per_slot: 100_000_000,
+        }
+    );
+}
+
+#[test]
+fn test_validator_transaction_logs() {
+    let toml = r#"
+[validator.transaction_logs]
+level = "invocations"
+max_bytes_per_transaction = 10000
+programs = [
+    { id = "wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4", level = "none" },
+    { id = "9hgprgZiRWmy8KkfvUuaVkDGrqo9GzeXMohwq6BazgUY", level = "all" },
+]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.transaction_logs,
+        TransactionLogsConfig {
+            level: TransactionLogLevel::Invocations,
+            programs: vec![
+                ProgramTransactionLogs {
+                    id: pubkey!("wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4"),
+                    level: TransactionLogLevel::None,
+                },
+                ProgramTransactionLogs {
+                    id: pubkey!("9hgprgZiRWmy8KkfvUuaVkDGrqo9GzeXMohwq6BazgUY"),
+                    level: TransactionLogLevel::All,
+                },
+            ],
+            max_bytes_per_transaction: Some(10_000),
+        }
+    );
+    assert_eq!(
+        ValidatorConfig::default().transaction_logs.level,
+        TransactionLogLevel::All
+    );
+}
+
+#[test]
+fn test_validator_sponsor_session_key_fees() {
+    let toml = r#"
+[validator]
+sponsor_session_key_fees = true
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert!(config.validator.sponsor_session_key_fees);
+    assert!(!ValidatorConfig::default().sponsor_session_key_fees);
+}
+
+#[test]
+fn test_validator_session_key_sponsor_programs() {
+    let toml = r#"
+[validator]
+session_key_sponsor_programs = [
+    { id = "wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4" },
+]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.session_key_sponsor_programs,
+        vec![AllowedProgram {
+            id: pubkey!("wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4")
+        }]
+    );
+    assert!(ValidatorConfig::default()
+        .session_key_sponsor_programs
+        .is_empty());
+}
+
+#[test]
+fn test_validator_remote_clock_source() {
+    let toml = r#"
+[validator]
+clock_source = "remote"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(config.validator.clock_source, ClockSource::Remote);
+    assert_eq!(ValidatorConfig::default().clock_source, ClockSource::Local);
+}
+
+#[test]
+fn test_validator_transaction_limits() {
+    let toml = r#"
+[validator.transaction_limits]
+max_size = 65536
+max_account_locks = 256
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.transaction_limits,
+        TransactionLimitsConfig {
+            max_size: 65536,
+            max_account_locks: 256,
+        }
+    );
+    assert_eq!(
+        ValidatorConfig::default().transaction_limits,
+        TransactionLimitsConfig {
+            max_size: 1232,
+            max_account_locks: 64,
+        }
+    );
+}
+
+#[test]
+fn test_validator_fee_floor() {
+    let toml = r#"
+[validator.fee_floor]
+min_compute_unit_price = 1000
+min_fee_per_signature = 5000
+exempt_fee_payers = [
+    { id = "9yXjZTevvMp1XgZSZEaziPRgFiXtAQChpnP2oX9eCpvt" },
+]
+exempt_programs = [
+    { id = "wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4" },
+]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.fee_floor,
+        FeeFloorConfig {
+            min_compute_unit_price: 1000,
+            min_fee_per_signature: 5000,
+            exempt_fee_payers: vec![ExemptFeePayer {
+                id: pubkey!("9yXjZTevvMp1XgZSZEaziPRgFiXtAQChpnP2oX9eCpvt"),
+            }],
+            exempt_programs: vec![AllowedProgram {
+                id: pubkey!("wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4"),
+            }],
+        }
+    );
+    assert_eq!(
+        ValidatorConfig::default().fee_floor,
+        FeeFloorConfig::default()
+    );
+}
+
+#[test]
+fn test_validator_pinned_programs() {
+    let toml = r#"
+[validator]
+pinned_programs = [
+    { id = "wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4" },
+]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.validator.pinned_programs,
+        vec![PinnedProgram {
+            id: pubkey!("wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4"),
+        }]
+    );
+    assert!(ValidatorConfig::default().pinned_programs.is_empty());
+}
+
+#[test]
+fn test_validator_shutdown_timeout() {
+    let toml = r#"
+[validator]
+shutdown_timeout_millis = 5000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(config.validator.shutdown_timeout_millis, 5_000);
+    assert_eq!(ValidatorConfig::default().shutdown_timeout_millis, 60_000);
+}
+
+#[test]
+fn test_geyser_grpc_tls_and_x_tokens() {
+    let toml = r#"
+[geyser_grpc]
+port = 10001
+x-tokens = ["secret-a", "secret-b"]
+tls = { cert-path = "/certs/server.pem", key-path = "/certs/server.key", client-ca-cert-path = "/certs/ca.pem" }
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.geyser_grpc,
+        GeyserGrpcConfig {
+            port: 10001,
+            tls: Some(GeyserGrpcTlsConfig {
+                cert_path: "/certs/server.pem".to_string(),
+                key_path: "/certs/server.key".to_string(),
+                client_ca_cert_path: Some("/certs/ca.pem".to_string()),
+            }),
+            x_tokens: vec!["secret-a".to_string(), "secret-b".to_string()],
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        GeyserGrpcConfig::default().addr,
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    );
+    assert!(GeyserGrpcConfig::default().tls.is_none());
+    assert!(GeyserGrpcConfig::default().x_tokens.is_empty());
+}
+
+#[test]
+fn test_geyser_grpc_replay() {
+    let toml = r#"
+[geyser_grpc]
+replay-stored-slots = 400
+replay-max-bytes = 1048576
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.geyser_grpc,
+        GeyserGrpcConfig {
+            replay_stored_slots: 400,
+            replay_max_bytes: 1_048_576,
+            ..Default::default()
+        }
+    );
+    assert_eq!(GeyserGrpcConfig::default().replay_stored_slots, 0);
+}
+
+#[test]
+fn test_geyser_grpc_accounts_filter_limits() {
+    let toml = r#"
+[geyser_grpc]
+accounts-filter-any = false
+accounts-filter-max-pubkeys = 100
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.geyser_grpc,
+        GeyserGrpcConfig {
+            accounts_filter_any: false,
+            accounts_filter_max_pubkeys: 100,
+            ..Default::default()
+        }
+    );
+    assert!(GeyserGrpcConfig::default().accounts_filter_any);
+}
+
+#[test]
+fn test_geyser_grpc_entry_notifications() {
+    let toml = r#"
+[geyser_grpc]
+entry-notifications = true
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert!(config.geyser_grpc.entry_notifications);
+    assert!(!GeyserGrpcConfig::default().entry_notifications);
+}
+
+#[test]
+fn test_geyser_grpc_slow_client_policy() {
+    let toml = r#"
+[geyser_grpc]
+client-queue-capacity = 64
+slow-client-policy = "drop-oldest"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.geyser_grpc,
+        GeyserGrpcConfig {
+            client_queue_capacity: 64,
+            slow_client_policy: GeyserGrpcSlowClientPolicy::DropOldest,
+            ..Default::default()
         }
-    )
+    );
+    assert_eq!(
+        GeyserGrpcConfig::default().slow_client_policy,
+        GeyserGrpcSlowClientPolicy::Disconnect
+    );
 }
 
 #[test]
-fn test_custom_remote_toml() {
-    let toml = include_str!("fixtures/07_custom-remote.toml");
+fn test_cloning_without_token_mints() {
+    let toml = r#"
+[accounts.cloning]
+token-mints = false
+"#;
+
     let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.cloning,
+        CloningConfig {
+            token_mints: false,
+            ..Default::default()
+        }
+    );
+    assert!(AccountsConfig::default().cloning.token_mints);
+}
+
+#[test]
+fn test_cloning_without_program_idls() {
+    let toml = r#"
+[accounts.cloning]
+program-idls = false
+"#;
 
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
     assert_eq!(
-        config,
-        EphemeralConfig {
-            accounts: AccountsConfig {
-                remote: RemoteConfig::Custom(
-                    Url::parse("http://localhost:8899").unwrap()
-                ),
-                ..Default::default()
-            },
+        config.accounts.cloning,
+        CloningConfig {
+            program_idls: false,
             ..Default::default()
         }
     );
+    assert!(AccountsConfig::default().cloning.program_idls);
 }
 
 #[test]
-fn test_custom_ws_remote_toml() {
-    let toml = include_str!("fixtures/09_custom-ws-remote.toml");
+fn test_cloning_max_concurrent_clones() {
+    let toml = r#"
+[accounts.cloning]
+max-concurrent-clones = 8
+"#;
+
     let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.cloning,
+        CloningConfig {
+            max_concurrent_clones: 8,
+            ..Default::default()
+        }
+    );
+    assert_eq!(AccountsConfig::default().cloning.max_concurrent_clones, 32);
+}
 
+#[test]
+fn test_cloning_revalidate_after_millis() {
+    let toml = r#"
+[accounts.cloning]
+revalidate-after-millis = 60000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
     assert_eq!(
-        config,
-        EphemeralConfig {
-            accounts: AccountsConfig {
-                remote: RemoteConfig::CustomWithWs(
-                    Url::parse("http://localhost:8899").unwrap(),
-                    Url::parse("ws://localhost:9001").unwrap()
-                ),
-                ..Default::default()
-            },
+        config.accounts.cloning,
+        CloningConfig {
+            revalidate_after_millis: Some(60_000),
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        AccountsConfig::default().cloning.revalidate_after_millis,
+        None
+    );
+}
+
+#[test]
+fn test_cloning_verify_delegations() {
+    let toml = r#"
+[accounts.cloning]
+verify-delegations = false
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.cloning,
+        CloningConfig {
+            verify_delegations: false,
             ..Default::default()
         }
     );
+    assert!(AccountsConfig::default().cloning.verify_delegations);
+}
+
+#[test]
+fn test_cloning_bootstrap() {
+    let toml = r#"
+[accounts.cloning.bootstrap]
+accounts-dir = "fixtures/accounts"
+fallback-to-remote = true
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.cloning.try_bootstrap().unwrap(),
+        Some(&BootstrapConfig {
+            accounts_dir: Some("fixtures/accounts".to_string()),
+            fallback_to_remote: true,
+            ..Default::default()
+        })
+    );
+
+    let toml = r#"
+[accounts.cloning.bootstrap]
+accountsdb-path = "ledger"
+accounts-dir = "fixtures/accounts"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert!(config.accounts.cloning.try_bootstrap().is_err());
+}
+
+#[test]
+fn test_cloning_prefetch_programs() {
+    let toml = r#"
+[[accounts.cloning.prefetch]]
+id = "wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4"
+
+[[accounts.cloning.prefetch]]
+id = "DmnRGfyyftzacFb1XadYhWF6vWqXwtQk5tbr6XgR3BA1"
+data-size = 128
+memcmp = [{ offset = 8, bytes = "3Mc6vR" }]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.cloning.prefetch,
+        vec![
+            PrefetchProgram {
+                id: pubkey!("wormH7q6y9EBUUL6EyptYhryxs6HoJg8sPK3LMfoNf4"),
+                ..Default::default()
+            },
+            PrefetchProgram {
+                id: pubkey!("DmnRGfyyftzacFb1XadYhWF6vWqXwtQk5tbr6XgR3BA1"),
+                data_size: Some(128),
+                memcmp: vec![PrefetchMemcmp {
+                    offset: 8,
+                    bytes: "3Mc6vR".to_string(),
+                }],
+            },
+        ]
+    );
+    assert!(AccountsConfig::default().cloning.prefetch.is_empty());
 }
 
 #[test]
@@ -257,3 +1150,316 @@ remote = { http = "http://localhost:8899", ws = ["ws://awesomews1.com:933", "wss
     println!("{res:?}");
     assert!(res.is_ok());
 }
+
+#[test]
+fn test_rpc_cors_allowed_origins() {
+    let toml = r#"
+[rpc]
+cors-allowed-origins = ["https://app.magicblock.xyz", "http://localhost:3000"]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.rpc,
+        RpcConfig {
+            cors_allowed_origins: vec![
+                "https://app.magicblock.xyz".to_string(),
+                "http://localhost:3000".to_string(),
+            ],
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_delegation_programs() {
+    let toml = r#"
+[[accounts.delegation_programs]]
+id = "DELeGGvXpWV2fqJUhqcF5ZSYMS4JTLjteaAMARRSaeSh"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.accounts.delegation_programs,
+        vec![DelegationProgram {
+            id: pubkey!("DELeGGvXpWV2fqJUhqcF5ZSYMS4JTLjteaAMARRSaeSh"),
+        }]
+    );
+    assert!(AccountsConfig::default().delegation_programs.is_empty());
+}
+
+#[test]
+fn test_ledger_retention() {
+    let toml = r#"
+[ledger]
+retention-slots = 500000
+retention-secs = 86400
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.ledger,
+        LedgerConfig {
+            retention_slots: Some(500_000),
+            retention_secs: Some(86_400),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_ledger_archive() {
+    let toml = r#"
+[ledger]
+size = 1000000
+
+[ledger.archive]
+path = "/var/magicblock/archive"
+compression-level = 9
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.ledger,
+        LedgerConfig {
+            size: 1_000_000,
+            archive: Some(LedgerArchiveConfig {
+                path: "/var/magicblock/archive".to_string(),
+                compression_level: 9,
+            }),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_ledger_export() {
+    let toml = r#"
+[ledger.export]
+poll-interval-millis = 10
+
+[ledger.export.sink]
+type = "kafka"
+brokers = "localhost:9092"
+topic = "er-blocks"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.ledger,
+        LedgerConfig {
+            export: Some(LedgerExportConfig {
+                sink: LedgerExportSink::Kafka {
+                    brokers: "localhost:9092".to_string(),
+                    topic: "er-blocks".to_string(),
+                },
+                poll_interval_millis: 10,
+            }),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn test_geyser_sink() {
+    let toml = r#"
+[geyser_sink]
+format = "json"
+accounts-topic = "er-accounts"
+transactions-topic = "er-transactions"
+
+[geyser_sink.bus]
+type = "nats"
+url = "nats://localhost:4222"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.geyser_sink,
+        Some(GeyserSinkConfig {
+            bus: GeyserSinkBus::Nats {
+                url: "nats://localhost:4222".to_string(),
+            },
+            format: GeyserSinkFormat::Json,
+            accounts_topic: Some("er-accounts".to_string()),
+            transactions_topic: Some("er-transactions".to_string()),
+        })
+    );
+    assert!(EphemeralConfig::default().geyser_sink.is_none());
+}
+
+#[test]
+fn test_geyser_sink_defaults_to_protobuf() {
+    let toml = r#"
+[geyser_sink]
+accounts-topic = "er-accounts"
+
+[geyser_sink.bus]
+type = "kafka"
+brokers = "localhost:9092"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.geyser_sink,
+        Some(GeyserSinkConfig {
+            bus: GeyserSinkBus::Kafka {
+                brokers: "localhost:9092".to_string(),
+            },
+            format: GeyserSinkFormat::Protobuf,
+            accounts_topic: Some("er-accounts".to_string()),
+            transactions_topic: None,
+        })
+    );
+}
+
+#[test]
+fn test_rpc_enable_admin() {
+    let toml = r#"
+[rpc]
+enable-admin = true
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.rpc,
+        RpcConfig {
+            enable_admin: true,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        config.rpc.admin_socket_addr(),
+        Some(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8898))
+    );
+    assert!(!RpcConfig::default().enable_admin);
+    assert_eq!(RpcConfig::default().admin_socket_addr(), None);
+}
+
+#[test]
+fn test_rpc_admin_addr() {
+    let toml = r#"
+[rpc]
+enable-admin = true
+admin-addr = "10.0.0.1"
+admin-port = 9000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.rpc.admin_socket_addr(),
+        Some(SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+            9000
+        ))
+    );
+}
+
+#[test]
+fn test_tracing() {
+    let toml = r#"
+[tracing]
+endpoint = "http://localhost:4317"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.tracing,
+        Some(TracingConfig {
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "magicblock-validator".to_string(),
+        })
+    );
+    assert!(EphemeralConfig::default().tracing.is_none());
+}
+
+#[test]
+fn test_logging_json() {
+    let toml = r#"
+[logging]
+format = "json"
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.logging,
+        LoggingConfig {
+            format: LogFormat::Json,
+            ..Default::default()
+        }
+    );
+    assert_eq!(EphemeralConfig::default().logging.format, LogFormat::Plain);
+}
+
+#[test]
+fn test_logging_slow_thresholds() {
+    let toml = r#"
+[logging]
+slow-transaction-threshold-millis = 50
+slow-rpc-request-threshold-millis = 200
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.logging,
+        LoggingConfig {
+            format: LogFormat::Plain,
+            slow_transaction_threshold_millis: Some(50),
+            slow_rpc_request_threshold_millis: Some(200),
+        }
+    );
+    let default = LoggingConfig::default();
+    assert!(default.slow_transaction_threshold_millis.is_none());
+    assert!(default.slow_rpc_request_threshold_millis.is_none());
+}
+
+#[test]
+fn test_redacted_config() {
+    let toml = r#"
+[accounts]
+remote = "https://rpc.provider.com/secret-api-key"
+
+[accounts.commit]
+webhook = { url = "https://hooks.example.com/commits?token=secret", auth_header = "Bearer secret" }
+
+[geyser-grpc]
+x-tokens = ["secret"]
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    let redacted = config.to_redacted_string();
+    assert!(!redacted.contains("secret"));
+    assert!(redacted.contains("https://rpc.provider.com"));
+    assert!(redacted.contains("https://hooks.example.com"));
+    assert!(redacted.contains("<redacted>"));
+
+    // Parsing the redacted config keeps everything that isn't a secret
+    let redacted = toml::from_str::<EphemeralConfig>(&redacted).unwrap();
+    assert_eq!(redacted.validator, config.validator);
+    assert_eq!(redacted.ledger, config.ledger);
+}
+
+#[test]
+fn test_disk_config() {
+    let toml = r#"
+[disk]
+check-interval-millis = 5_000
+ledger-budget-bytes = 50_000_000_000
+snapshots-budget-bytes = 10_000_000_000
+min-free-bytes = 5_000_000_000
+read-only-free-bytes = 1_000_000_000
+"#;
+
+    let config = toml::from_str::<EphemeralConfig>(toml).unwrap();
+    assert_eq!(
+        config.disk,
+        DiskConfig {
+            check_interval_millis: 5_000,
+            ledger_budget_bytes: Some(50_000_000_000),
+            accountsdb_budget_bytes: None,
+            snapshots_budget_bytes: Some(10_000_000_000),
+            min_free_bytes: Some(5_000_000_000),
+            read_only_free_bytes: Some(1_000_000_000),
+        }
+    );
+}
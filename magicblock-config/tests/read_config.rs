@@ -5,9 +5,11 @@ use std::{
 
 use isocountry::CountryCode;
 use magicblock_config::{
-    AccountsConfig, CommitStrategy, EphemeralConfig, GeyserGrpcConfig,
-    LedgerConfig, LifecycleMode, MetricsConfig, MetricsServiceConfig,
-    ProgramConfig, RemoteConfig, RpcConfig, ValidatorConfig,
+    AccountsConfig, CommitStrategy, DiskConfig, EphemeralConfig,
+    GeyserGrpcConfig, GeyserPluginConfig, LedgerArchiveConfig, LedgerConfig,
+    LedgerExportConfig, LedgerExportSink, LifecycleMode, LoggingConfig,
+    MetricsConfig, MetricsServiceConfig, ProgramConfig, RemoteConfig,
+    RpcConfig, ValidatorConfig,
 };
 use solana_sdk::pubkey;
 use test_tools_core::paths::cargo_workspace_dir;
@@ -32,6 +34,17 @@ fn test_load_local_dev_with_programs_toml() {
                 commit: CommitStrategy {
                     frequency_millis: 600_000,
                     compute_unit_price: 0,
+                    changed_only: false,
+                    priority_fee: None,
+                    max_requested_compute_unit_price: 0,
+                    durable_nonce: None,
+                    jito: None,
+                    payer_monitor: None,
+                    webhook: None,
+                    simulate: false,
+                    remote: None,
+                    fallback_remotes: vec![],
+                    schedule_fee: None,
                 },
                 ..Default::default()
             },
@@ -45,11 +58,13 @@ fn test_load_local_dev_with_programs_toml() {
             rpc: RpcConfig {
                 addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
                 port: 7799,
-                max_ws_connections: 16384
+                max_ws_connections: 16384,
+                ..Default::default()
             },
             geyser_grpc: GeyserGrpcConfig {
                 addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
                 port: 11000,
+                ..Default::default()
             },
             validator: ValidatorConfig {
                 millis_per_slot: 14,
@@ -66,6 +81,16 @@ fn test_load_local_dev_with_programs_toml() {
                 },
                 ..Default::default()
             },
+            geyser_plugins: vec![GeyserPluginConfig {
+                config: format!(
+                    "{}/geyser-plugin.json",
+                    config_file_dir.parent().unwrap().to_str().unwrap()
+                ),
+            }],
+            geyser_sink: None,
+            tracing: None,
+            logging: LoggingConfig::default(),
+            disk: DiskConfig::default(),
         }
     )
 }
@@ -90,6 +115,7 @@ fn test_load_local_dev_with_programs_toml_envs_override() {
     env::set_var("ACCOUNTS_COMMIT_COMPUTE_UNIT_PRICE", "1");
     env::set_var("RPC_ADDR", "0.1.0.1");
     env::set_var("RPC_PORT", "123");
+    env::set_var("RPC_ENABLE_ADMIN", "true");
     env::set_var("GEYSER_GRPC_ADDR", "0.1.0.1");
     env::set_var("GEYSER_GRPC_PORT", "123");
     env::set_var("VALIDATOR_MILLIS_PER_SLOT", "100");
@@ -101,6 +127,10 @@ fn test_load_local_dev_with_programs_toml_envs_override() {
     env::set_var("METRICS_PORT", "1234");
     env::set_var("METRICS_SYSTEM_METRICS_TICK_INTERVAL_SECS", "10");
     env::set_var("LEDGER_SIZE", "123123");
+    env::set_var("LEDGER_RETENTION_SLOTS", "1000");
+    env::set_var("LEDGER_RETENTION_SECS", "3600");
+    env::set_var("LEDGER_ARCHIVE_PATH", "/hello/archive");
+    env::set_var("LEDGER_EXPORT_FILE_PATH", "/hello/blocks");
 
     let config =
         EphemeralConfig::try_load_from_file(config_file_dir.to_str().unwrap())
@@ -115,6 +145,17 @@ fn test_load_local_dev_with_programs_toml_envs_override() {
                 commit: CommitStrategy {
                     frequency_millis: 123,
                     compute_unit_price: 1,
+                    changed_only: false,
+                    priority_fee: None,
+                    max_requested_compute_unit_price: 0,
+                    durable_nonce: None,
+                    jito: None,
+                    payer_monitor: None,
+                    webhook: None,
+                    simulate: false,
+                    remote: None,
+                    fallback_remotes: vec![],
+                    schedule_fee: None,
                 },
                 remote: RemoteConfig::Custom(Url::parse(base_cluster).unwrap()),
                 ..Default::default()
@@ -129,11 +170,14 @@ fn test_load_local_dev_with_programs_toml_envs_override() {
             rpc: RpcConfig {
                 addr: IpAddr::V4(Ipv4Addr::new(0, 1, 0, 1)),
                 port: 123,
-                max_ws_connections: 16384
+                max_ws_connections: 16384,
+                enable_admin: true,
+                ..Default::default()
             },
             geyser_grpc: GeyserGrpcConfig {
                 addr: IpAddr::V4(Ipv4Addr::new(0, 1, 0, 1)),
                 port: 123,
+                ..Default::default()
             },
             validator: ValidatorConfig {
                 millis_per_slot: 100,
@@ -144,7 +188,15 @@ fn test_load_local_dev_with_programs_toml_envs_override() {
             ledger: LedgerConfig {
                 reset: false,
                 path: Some("/hello/world".to_string()),
-                size: 123123
+                size: 123123,
+                retention_slots: Some(1000),
+                retention_secs: Some(3600),
+                archive: Some(LedgerArchiveConfig::new(
+                    "/hello/archive".to_string()
+                )),
+                export: Some(LedgerExportConfig::new(LedgerExportSink::File {
+                    path: "/hello/blocks".to_string()
+                })),
             },
             metrics: MetricsConfig {
                 enabled: false,
@@ -154,6 +206,16 @@ fn test_load_local_dev_with_programs_toml_envs_override() {
                 },
                 system_metrics_tick_interval_secs: 10,
             },
+            geyser_plugins: vec![GeyserPluginConfig {
+                config: format!(
+                    "{}/geyser-plugin.json",
+                    config_file_dir.parent().unwrap().to_str().unwrap()
+                ),
+            }],
+            geyser_sink: None,
+            tracing: None,
+            logging: LoggingConfig::default(),
+            disk: DiskConfig::default(),
         }
     );
     env::set_var("ACCOUNTS_REMOTE_WS", base_cluster_ws);
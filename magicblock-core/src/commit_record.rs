@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
+
+/// Outcome of a scheduled commit on the base layer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CommitOutcome {
+    /// None of the accounts changed since they were last committed, thus
+    /// no transaction was sent
+    NotNeeded,
+    /// The commit transactions were sent, but not confirmed yet
+    Pending,
+    /// All commit transactions were confirmed on the base layer
+    Confirmed,
+    /// Sending or confirming the commit transactions failed
+    Failed(String),
+    /// All commit transactions were finalized on the base layer
+    Finalized,
+}
+
+/// Record of a scheduled commit and its outcome, persisted in the ledger
+/// keyed by the slot at which the commit was scheduled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitRecord {
+    pub commit_id: u64,
+    /// The slot at which the commit was scheduled
+    pub slot: Slot,
+    pub payer: Pubkey,
+    /// Accounts committed to the base layer
+    pub included_pubkeys: Vec<Pubkey>,
+    /// Accounts that were scheduled, but could not be committed
+    pub excluded_pubkeys: Vec<Pubkey>,
+    pub requested_undelegation: bool,
+    /// Signatures of the commit transactions sent to the base layer
+    pub chain_signatures: Vec<Signature>,
+    pub outcome: CommitOutcome,
+}
+
+/// Status of the undelegation of an account on the base layer, derived from
+/// the outcomes of the commits requesting it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UndelegationStatus {
+    /// No undelegation was requested for the account recently
+    Unknown,
+    /// The undelegation transactions were sent, but not confirmed yet
+    Pending,
+    /// The undelegation was confirmed on the base layer, but could still be
+    /// rolled back
+    Confirmed,
+    /// The undelegation was finalized on the base layer, the account is owned
+    /// by its program again
+    Finalized,
+    /// Undelegating the account failed, it is still delegated
+    Failed,
+}
+
+impl UndelegationStatus {
+    /// The undelegation status of the accounts included in a commit that
+    /// requested their undelegation, `None` if no transaction was sent
+    pub fn from_outcome(outcome: &CommitOutcome) -> Option<Self> {
+        match outcome {
+            CommitOutcome::NotNeeded => None,
+            CommitOutcome::Pending => Some(Self::Pending),
+            CommitOutcome::Confirmed => Some(Self::Confirmed),
+            CommitOutcome::Failed(_) => Some(Self::Failed),
+            CommitOutcome::Finalized => Some(Self::Finalized),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+pub mod commit_record;
+pub mod mutation_audit;
+pub mod remote_sync_status;
 pub mod traits;
 
 pub mod magic_program {
@@ -15,6 +18,30 @@ pub mod magic_program {
     /// NOTE: the default max accumulated account size per transaction is 64MB.
     /// See: MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES inside program-runtime/src/compute_budget_processor.rs
     pub const MAGIC_CONTEXT_SIZE: usize = 1024 * 1024 * 5; // 5 MB
+
+    /// The magic context grows up to this size if more commits are scheduled
+    /// within a slot than fit into [MAGIC_CONTEXT_SIZE] and shrinks back
+    /// once they are accepted.
+    /// It matches the max data length of any account on chain.
+    pub const MAX_MAGIC_CONTEXT_SIZE: usize = 1024 * 1024 * 10; // 10 MB
+
+    /// Account holding the outcomes of the most recent scheduled commits,
+    /// clients subscribe to it to learn about the outcome of their commits.
+    pub const MAGIC_COMMIT_EVENTS_PUBKEY: Pubkey =
+        pubkey!("MagicCommitEvents11111111111111111111111111");
+
+    /// The oldest commit events are dropped once they don't fit into the
+    /// commit events account anymore.
+    pub const MAGIC_COMMIT_EVENTS_SIZE: usize = 1024 * 1024; // 1 MB
+
+    /// Account holding the session keys registered via the magic program,
+    /// transactions paid by them may be sponsored by the validator.
+    pub const MAGIC_SESSION_KEYS_PUBKEY: Pubkey =
+        pubkey!("MagicSessionKeys111111111111111111111111111");
+
+    /// Expired session keys are pruned whenever a new one is registered,
+    /// registering fails once the unexpired ones fill up the account.
+    pub const MAGIC_SESSION_KEYS_SIZE: usize = 1024 * 1024; // 1 MB
 }
 
 /// A macro that panics when running a debug build and logs the panic message
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::{clock::Slot, pubkey::Pubkey};
+
+/// Why the validator authority modified accounts outside of regular
+/// transaction execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MutationReason {
+    /// The accounts were modified via a layout of the instruction that did
+    /// not include the reason
+    Unspecified,
+    /// Cloned an account from the remote cluster
+    CloneAccount,
+    /// Cloned a program together with its program data and IDL accounts
+    CloneProgram,
+    /// Closed accounts once their undelegation was confirmed on the base layer
+    CloseUndelegatedAccounts,
+}
+
+/// Audit entry persisted in the ledger each time the validator authority
+/// modifies accounts, keyed by the slot at which they were modified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MutationAuditEntry {
+    /// Increases with each entry recorded since the validator started
+    pub seq: u64,
+    pub slot: Slot,
+    /// The authority that signed the modification
+    pub authority: Pubkey,
+    pub reason: MutationReason,
+    /// The modified accounts
+    pub accounts: Vec<Pubkey>,
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::clock::Slot;
+
+/// How far the validator is behind the remote cluster it clones accounts
+/// from and commits accounts to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteSyncStatus {
+    /// Latest slot of the remote cluster observed via any subscription
+    pub remote_slot: Option<Slot>,
+    /// Status of each shard of account subscriptions
+    pub shards: Vec<ShardSyncStatus>,
+    /// Accounts requested to be cloned whose clone did not complete yet
+    pub pending_clones: usize,
+    /// Commits that were scheduled, but not sent to the remote cluster yet
+    pub scheduled_commits: usize,
+    /// Commits sent to the remote cluster that are not confirmed yet
+    pub pending_commits: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShardSyncStatus {
+    pub shard_id: String,
+    /// Latest slot of the remote cluster observed by this shard
+    pub slot: Slot,
+    /// Slots this shard is behind [RemoteSyncStatus::remote_slot]
+    pub lag: Slot,
+}
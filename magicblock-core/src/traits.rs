@@ -1,7 +1,20 @@
 use std::{error::Error, fmt};
+
+use solana_sdk::{account::AccountSharedData, clock::Slot, pubkey::Pubkey};
+
+use crate::{
+    commit_record::CommitRecord, mutation_audit::MutationAuditEntry,
+    remote_sync_status::RemoteSyncStatus,
+};
+
 pub trait PersistsAccountModData: Sync + Send + fmt::Display + 'static {
     fn persist(&self, id: u64, data: Vec<u8>) -> Result<(), Box<dyn Error>>;
     fn load(&self, id: u64) -> Result<Option<Vec<u8>>, Box<dyn Error>>;
+    /// Appends the entry to the audit trail of account modifications
+    fn persist_mutation_audit_entry(
+        &self,
+        entry: &MutationAuditEntry,
+    ) -> Result<(), Box<dyn Error>>;
 }
 
 /// Provides slot after which it is safe to purge slots
@@ -10,3 +23,30 @@ pub trait PersistsAccountModData: Sync + Send + fmt::Display + 'static {
 pub trait FinalityProvider: Send + Sync + 'static {
     fn get_latest_final_slot(&self) -> u64;
 }
+
+/// Persists the outcome of scheduled commits in order to keep an audit trail
+/// of the commits to the base layer
+pub trait PersistsCommitRecords: Sync + Send + 'static {
+    /// Stores the record, replacing an earlier record of the same commit
+    fn persist_commit_record(
+        &self,
+        record: &CommitRecord,
+    ) -> Result<(), Box<dyn Error>>;
+}
+
+/// Reports how far the validator is behind the remote cluster
+pub trait ProvidesRemoteSyncStatus: Sync + Send + 'static {
+    fn remote_sync_status(&self) -> RemoteSyncStatus;
+}
+
+/// Provides the current state of accounts, i.e. to send it to subscribers
+/// before streaming the updates of the accounts
+pub trait ProvidesAccountsSnapshot: Sync + Send + 'static {
+    /// The slot the accounts are current at
+    fn snapshot_slot(&self) -> Slot;
+    fn snapshot_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData>;
+    fn snapshot_program_accounts(
+        &self,
+        program_id: &Pubkey,
+    ) -> Vec<(Pubkey, AccountSharedData)>;
+}
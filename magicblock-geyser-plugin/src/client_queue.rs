@@ -0,0 +1,269 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use geyser_grpc_proto::prelude::SubscribeUpdate;
+use tokio::sync::Notify;
+use tokio_stream::Stream;
+use tonic::{Result as TonicResult, Status};
+
+pub(crate) type QueueItem = TonicResult<SubscribeUpdate>;
+
+/// Bounded queue of the updates sent to a single gRPC subscriber, unlike a
+/// channel it allows dropping the oldest updates once it is full
+pub(crate) fn client_queue(
+    capacity: usize,
+) -> (ClientQueueSender, ClientQueueStream) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            waker: None,
+            sender_dropped: false,
+            stream_dropped: false,
+        }),
+        consumed: Notify::new(),
+    });
+    (
+        ClientQueueSender {
+            shared: shared.clone(),
+        },
+        ClientQueueStream { shared },
+    )
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Notified whenever the stream took an update or was dropped
+    consumed: Notify,
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("client queue poisoned")
+    }
+}
+
+struct State {
+    items: VecDeque<QueueItem>,
+    capacity: usize,
+    waker: Option<Waker>,
+    sender_dropped: bool,
+    stream_dropped: bool,
+}
+
+impl State {
+    fn push(&mut self, item: QueueItem) {
+        self.items.push_back(item);
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum TryPushError {
+    /// The queue is full, the update was not queued
+    Full,
+    /// The client went away
+    Closed,
+}
+
+/// The client went away
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct QueueClosed;
+
+pub(crate) struct ClientQueueSender {
+    shared: Arc<Shared>,
+}
+
+impl ClientQueueSender {
+    pub(crate) fn len(&self) -> usize {
+        self.shared.lock().items.len()
+    }
+
+    pub(crate) fn try_push(&self, item: QueueItem) -> Result<(), TryPushError> {
+        let mut state = self.shared.lock();
+        if state.stream_dropped {
+            return Err(TryPushError::Closed);
+        }
+        if state.items.len() >= state.capacity {
+            return Err(TryPushError::Full);
+        }
+        state.push(item);
+        Ok(())
+    }
+
+    /// Queues the update, dropping the oldest one if the queue is full.
+    /// Returns the number of updates dropped.
+    pub(crate) fn push_dropping_oldest(
+        &self,
+        item: QueueItem,
+    ) -> Result<u64, QueueClosed> {
+        let mut state = self.shared.lock();
+        if state.stream_dropped {
+            return Err(QueueClosed);
+        }
+        let mut dropped = 0;
+        while state.items.len() >= state.capacity {
+            state.items.pop_front();
+            dropped += 1;
+        }
+        state.push(item);
+        Ok(dropped)
+    }
+
+    /// Queues the update once there is room for it
+    pub(crate) async fn send(
+        &self,
+        item: QueueItem,
+    ) -> Result<(), QueueClosed> {
+        loop {
+            // Registered before checking the state to not miss a wakeup
+            let consumed = self.shared.consumed.notified();
+            {
+                let mut state = self.shared.lock();
+                if state.stream_dropped {
+                    return Err(QueueClosed);
+                }
+                if state.items.len() < state.capacity {
+                    state.push(item);
+                    return Ok(());
+                }
+            }
+            consumed.await;
+        }
+    }
+
+    /// Resolves once the client went away
+    pub(crate) async fn closed(&self) {
+        loop {
+            let consumed = self.shared.consumed.notified();
+            if self.shared.lock().stream_dropped {
+                return;
+            }
+            consumed.await;
+        }
+    }
+}
+
+impl Drop for ClientQueueSender {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.sender_dropped = true;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The stream of updates tonic sends to the client
+pub struct ClientQueueStream {
+    shared: Arc<Shared>,
+}
+
+impl Stream for ClientQueueStream {
+    type Item = Result<SubscribeUpdate, Status>;
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut state = self.shared.lock();
+        if let Some(item) = state.items.pop_front() {
+            drop(state);
+            self.shared.consumed.notify_waiters();
+            return Poll::Ready(Some(item));
+        }
+        if state.sender_dropped {
+            return Poll::Ready(None);
+        }
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for ClientQueueStream {
+    fn drop(&mut self) {
+        self.shared.lock().stream_dropped = true;
+        self.shared.consumed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geyser_grpc_proto::prelude::{
+        subscribe_update::UpdateOneof, SubscribeUpdatePong,
+    };
+    use tokio_stream::StreamExt;
+
+    use super::*;
+
+    fn pong(id: i32) -> QueueItem {
+        Ok(SubscribeUpdate {
+            filters: vec![],
+            update_oneof: Some(UpdateOneof::Pong(SubscribeUpdatePong { id })),
+        })
+    }
+
+    fn pong_id(item: QueueItem) -> i32 {
+        match item.unwrap().update_oneof {
+            Some(UpdateOneof::Pong(pong)) => pong.id,
+            _ => panic!("expected pong"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_try_push_fails_when_full() {
+        let (tx, mut rx) = client_queue(2);
+        assert_eq!(tx.try_push(pong(1)), Ok(()));
+        assert_eq!(tx.try_push(pong(2)), Ok(()));
+        assert_eq!(tx.try_push(pong(3)), Err(TryPushError::Full));
+        assert_eq!(tx.len(), 2);
+
+        assert_eq!(pong_id(rx.next().await.unwrap()), 1);
+        assert_eq!(tx.try_push(pong(3)), Ok(()));
+        drop(tx);
+        assert_eq!(pong_id(rx.next().await.unwrap()), 2);
+        assert_eq!(pong_id(rx.next().await.unwrap()), 3);
+        assert!(rx.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_push_dropping_oldest() {
+        let (tx, mut rx) = client_queue(2);
+        assert_eq!(tx.push_dropping_oldest(pong(1)), Ok(0));
+        assert_eq!(tx.push_dropping_oldest(pong(2)), Ok(0));
+        assert_eq!(tx.push_dropping_oldest(pong(3)), Ok(1));
+        drop(tx);
+
+        let ids = rx.by_ref().map(pong_id).collect::<Vec<_>>().await;
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_send_waits_for_room() {
+        let (tx, mut rx) = client_queue(1);
+        tx.send(pong(1)).await.unwrap();
+        let sender = tokio::spawn(async move {
+            tx.send(pong(2)).await.unwrap();
+        });
+        assert_eq!(pong_id(rx.next().await.unwrap()), 1);
+        sender.await.unwrap();
+        assert_eq!(pong_id(rx.next().await.unwrap()), 2);
+        assert!(rx.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_closed_once_stream_dropped() {
+        let (tx, rx) = client_queue(1);
+        drop(rx);
+        tx.closed().await;
+        assert_eq!(tx.try_push(pong(1)), Err(TryPushError::Closed));
+        assert_eq!(tx.push_dropping_oldest(pong(1)), Err(QueueClosed));
+        assert_eq!(tx.send(pong(1)).await, Err(QueueClosed));
+    }
+}
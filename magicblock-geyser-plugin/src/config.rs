@@ -2,6 +2,7 @@
 use std::{
     collections::HashSet,
     net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
 };
 
 use solana_sdk::pubkey::Pubkey;
@@ -29,6 +30,11 @@ pub struct Config {
     pub enable_account_notifications: bool,
     /// If we should register to receive tranaction notifications, (default: true)
     pub enable_transaction_notifications: bool,
+    /// If we should register to receive entry notifications, (default: false)
+    pub enable_entry_notifications: bool,
+
+    /// Publishes updates to a message bus if provided (default: None)
+    pub sink: Option<ConfigSink>,
 }
 
 impl Default for Config {
@@ -46,18 +52,23 @@ impl Default for Config {
 
             enable_account_notifications: true,
             enable_transaction_notifications: true,
+            enable_entry_notifications: false,
+
+            sink: None,
         }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ConfigGrpc {
-    /// Address of Grpc service.
+    /// Address of Grpc service, only reachable locally by default
     pub address: SocketAddr,
     /// Limits the maximum size of a decoded message, default is 4MiB
     pub max_decoding_message_size: usize,
-    /// Capacity of the channel per connection
+    /// Capacity of the queue of updates per connection
     pub channel_capacity: usize,
+    /// What happens to a client whose queue of updates is full
+    pub slow_client_policy: ConfigGrpcSlowClientPolicy,
     /// Concurrency limit for unary requests
     pub unary_concurrency_limit: usize,
     /// Enable/disable unary methods
@@ -67,21 +78,29 @@ pub struct ConfigGrpc {
     /// Normalizes filter commitment levels to 'processed' no matter
     /// what actual commitment level was passed by the user
     pub normalize_commitment_level: bool,
+    /// TLS config, the service is unencrypted if not provided
+    pub tls_config: Option<ConfigGrpcServerTls>,
+    /// Tokens accepted via the `x-token` header, any client is served if empty
+    pub x_tokens: HashSet<String>,
+    /// Number of recent slots whose updates are buffered so that clients
+    /// can replay them, replay is disabled if `0`
+    pub replay_stored_slots: u64,
+    /// Limits the memory held by the buffered updates
+    pub replay_max_bytes: usize,
 }
 
 const MAX_DECODING_MESSAGE_SIZE_DEFAULT: usize = 4 * 1024 * 1024;
 const CHANNEL_CAPACITY_DEFAULT: usize = 1024;
 const UNARY_CONCURRENCY_LIMIT_DEFAULT: usize = Semaphore::MAX_PERMITS;
+const REPLAY_MAX_BYTES_DEFAULT: usize = 256 * 1024 * 1024;
 
 impl Default for ConfigGrpc {
     fn default() -> Self {
         Self {
-            address: SocketAddr::new(
-                IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)),
-                10_000,
-            ),
+            address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 10_000),
             max_decoding_message_size: MAX_DECODING_MESSAGE_SIZE_DEFAULT,
             channel_capacity: CHANNEL_CAPACITY_DEFAULT,
+            slow_client_policy: Default::default(),
             unary_concurrency_limit: UNARY_CONCURRENCY_LIMIT_DEFAULT,
             unary_disabled: Default::default(),
             filters: ConfigGrpcFilters {
@@ -92,6 +111,10 @@ impl Default for ConfigGrpc {
                 ..Default::default()
             },
             normalize_commitment_level: true,
+            tls_config: None,
+            x_tokens: HashSet::new(),
+            replay_stored_slots: 0,
+            replay_max_bytes: REPLAY_MAX_BYTES_DEFAULT,
         }
     }
 }
@@ -105,6 +128,45 @@ impl ConfigGrpc {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConfigGrpcSlowClientPolicy {
+    /// Closes the stream with a `lagged` error
+    #[default]
+    Disconnect,
+    /// Drops the oldest queued updates to make room for new ones
+    DropOldest,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigGrpcServerTls {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// Requires clients to authenticate with a certificate signed by this CA
+    pub client_ca_cert_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigSink {
+    pub bus: ConfigSinkBus,
+    pub format: ConfigSinkFormat,
+    /// Topic account updates are published to, not published if `None`
+    pub accounts_topic: Option<String>,
+    /// Topic transaction updates are published to, not published if `None`
+    pub transactions_topic: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ConfigSinkBus {
+    Kafka { brokers: String },
+    Nats { url: String },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ConfigSinkFormat {
+    Protobuf,
+    Json,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct ConfigGrpcFilters {
     pub accounts: ConfigGrpcFiltersAccounts,
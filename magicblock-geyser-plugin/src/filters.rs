@@ -44,6 +44,8 @@ pub struct Filter {
     commitment: CommitmentLevel,
     accounts_data_slice: Vec<FilterAccountsDataSlice>,
     ping: Option<i32>,
+    accounts_snapshot: bool,
+    from_slot: Option<u64>,
 }
 
 impl Filter {
@@ -61,6 +63,11 @@ impl Filter {
         } else {
             Self::decode_commitment(config.commitment)?
         };
+        let accounts_snapshot = config.accounts_snapshot.unwrap_or_default();
+        anyhow::ensure!(
+            !accounts_snapshot || config.from_slot.is_none(),
+            "accounts_snapshot and from_slot can not be combined"
+        );
         Ok(Self {
             accounts: FilterAccounts::new(&config.accounts, &limit.accounts)?,
             slots: FilterSlots::new(&config.slots, &limit.slots)?,
@@ -79,6 +86,8 @@ impl Filter {
                 &config.accounts_data_slice,
             )?,
             ping: config.ping.as_ref().map(|msg| msg.id),
+            accounts_snapshot,
+            from_slot: config.from_slot,
         })
     }
 
@@ -123,6 +132,23 @@ impl Filter {
         self.commitment
     }
 
+    pub const fn is_accounts_snapshot_requested(&self) -> bool {
+        self.accounts_snapshot
+    }
+
+    pub const fn get_from_slot(&self) -> Option<u64> {
+        self.from_slot
+    }
+
+    /// Accounts and owners of accounts that the accounts filters select,
+    /// filters that don't select any of them (`any`) are not included
+    pub fn get_accounts_snapshot_keys(&self) -> (Vec<Pubkey>, Vec<Pubkey>) {
+        (
+            self.accounts.account.keys().copied().collect(),
+            self.accounts.owner.keys().copied().collect(),
+        )
+    }
+
     pub fn get_filters<'a>(
         &self,
         message: &'a GeyserMessage,
@@ -908,11 +934,16 @@ mod tests {
     use std::{collections::HashMap, sync::Arc};
 
     use geyser_grpc_proto::geyser::{
+        subscribe_request_filter_accounts_filter::Filter as AccountsFilterDataOneof,
+        subscribe_request_filter_accounts_filter_memcmp::Data as AccountsFilterMemcmpOneof,
         SubscribeRequest, SubscribeRequestFilterAccounts,
+        SubscribeRequestFilterAccountsFilter,
+        SubscribeRequestFilterAccountsFilterMemcmp,
         SubscribeRequestFilterTransactions,
     };
     use magicblock_transaction_status::TransactionStatusMeta;
     use solana_sdk::{
+        account::AccountSharedData,
         hash::Hash,
         message::{v0::LoadedAddresses, Message as SolMessage, MessageHeader},
         pubkey::Pubkey,
@@ -921,9 +952,11 @@ mod tests {
     };
 
     use crate::{
-        config::ConfigGrpcFilters,
+        config::{ConfigGrpcFilters, ConfigGrpcFiltersAccounts},
         filters::Filter,
-        grpc_messages::{Message, MessageTransaction, MessageTransactionInfo},
+        grpc_messages::{
+            Message, MessageAccount, MessageTransaction, MessageTransactionInfo,
+        },
     };
 
     const NORMALIZE_COMMITMENT: bool = false;
@@ -987,12 +1020,34 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let limit = ConfigGrpcFilters::default();
         let filter = Filter::new(&config, &limit, NORMALIZE_COMMITMENT);
         assert!(filter.is_ok());
     }
 
+    #[test]
+    fn test_filters_snapshot_and_replay_exclusive() {
+        let config = SubscribeRequest {
+            accounts: HashMap::new(),
+            slots: HashMap::new(),
+            transactions: HashMap::new(),
+            blocks: HashMap::new(),
+            blocks_meta: HashMap::new(),
+            entry: HashMap::new(),
+            commitment: None,
+            accounts_data_slice: Vec::new(),
+            ping: None,
+            accounts_snapshot: Some(true),
+            from_slot: Some(1),
+        };
+        let limit = ConfigGrpcFilters::default();
+        let filter = Filter::new(&config, &limit, NORMALIZE_COMMITMENT);
+        assert!(filter.is_err());
+    }
+
     #[test]
     fn test_filters_account_empty() {
         let mut accounts = HashMap::new();
@@ -1016,6 +1071,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let mut limit = ConfigGrpcFilters::default();
         limit.accounts.any = false;
@@ -1024,6 +1081,118 @@ mod tests {
         assert!(filter.is_err());
     }
 
+    fn accounts_subscribe_request(
+        filter: SubscribeRequestFilterAccounts,
+    ) -> SubscribeRequest {
+        SubscribeRequest {
+            accounts: HashMap::from([("program".to_owned(), filter)]),
+            slots: HashMap::new(),
+            transactions: HashMap::new(),
+            blocks: HashMap::new(),
+            blocks_meta: HashMap::new(),
+            entry: HashMap::new(),
+            commitment: None,
+            accounts_data_slice: Vec::new(),
+            ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
+        }
+    }
+
+    fn account_message(owner: &Pubkey, data: Vec<u8>) -> Arc<Message> {
+        let mut account = AccountSharedData::new(1, 0, owner);
+        account.set_data_from_slice(&data);
+        Arc::new(Message::Account(MessageAccount::from_snapshot(
+            Pubkey::new_unique(),
+            &account,
+            100,
+        )))
+    }
+
+    fn matched_filters(filter: &Filter, message: &Arc<Message>) -> Vec<String> {
+        filter
+            .get_filters(message, None)
+            .into_iter()
+            .flat_map(|(filters, _)| filters)
+            .collect()
+    }
+
+    #[test]
+    fn test_filters_account_any_rejected() {
+        let config =
+            accounts_subscribe_request(SubscribeRequestFilterAccounts {
+                account: vec![],
+                owner: vec![],
+                filters: vec![],
+            });
+        let limit = ConfigGrpcFilters {
+            accounts: ConfigGrpcFiltersAccounts {
+                any: false,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let filter = Filter::new(&config, &limit, NORMALIZE_COMMITMENT);
+        assert!(filter.is_err());
+    }
+
+    #[test]
+    fn test_filters_account_owner() {
+        let program_id = Pubkey::new_unique();
+        let config =
+            accounts_subscribe_request(SubscribeRequestFilterAccounts {
+                account: vec![],
+                owner: vec![program_id.to_string()],
+                filters: vec![],
+            });
+        let limit = ConfigGrpcFilters::default();
+        let filter =
+            Filter::new(&config, &limit, NORMALIZE_COMMITMENT).unwrap();
+
+        let owned = account_message(&program_id, vec![]);
+        assert_eq!(matched_filters(&filter, &owned), vec!["program"]);
+        let other = account_message(&Pubkey::new_unique(), vec![]);
+        assert!(matched_filters(&filter, &other).is_empty());
+    }
+
+    #[test]
+    fn test_filters_account_owner_datasize_and_memcmp() {
+        let program_id = Pubkey::new_unique();
+        let config =
+            accounts_subscribe_request(SubscribeRequestFilterAccounts {
+                account: vec![],
+                owner: vec![program_id.to_string()],
+                filters: vec![
+                    SubscribeRequestFilterAccountsFilter {
+                        filter: Some(AccountsFilterDataOneof::Datasize(4)),
+                    },
+                    SubscribeRequestFilterAccountsFilter {
+                        filter: Some(AccountsFilterDataOneof::Memcmp(
+                            SubscribeRequestFilterAccountsFilterMemcmp {
+                                offset: 1,
+                                data: Some(AccountsFilterMemcmpOneof::Bytes(
+                                    vec![7, 8],
+                                )),
+                            },
+                        )),
+                    },
+                ],
+            });
+        let limit = ConfigGrpcFilters::default();
+        let filter =
+            Filter::new(&config, &limit, NORMALIZE_COMMITMENT).unwrap();
+
+        let matching = account_message(&program_id, vec![0, 7, 8, 0]);
+        assert_eq!(matched_filters(&filter, &matching), vec!["program"]);
+        for message in [
+            account_message(&program_id, vec![0, 7, 9, 0]),
+            account_message(&program_id, vec![0, 7, 8]),
+            account_message(&Pubkey::new_unique(), vec![0, 7, 8, 0]),
+        ] {
+            assert!(matched_filters(&filter, &message).is_empty());
+        }
+    }
+
     #[test]
     fn test_filters_transaction_empty() {
         let mut transactions = HashMap::new();
@@ -1050,6 +1219,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let mut limit = ConfigGrpcFilters::default();
         limit.transactions.any = false;
@@ -1083,6 +1254,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let mut limit = ConfigGrpcFilters::default();
         limit.transactions.any = false;
@@ -1123,6 +1296,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let limit = ConfigGrpcFilters::default();
         let filter =
@@ -1170,6 +1345,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let limit = ConfigGrpcFilters::default();
         let filter =
@@ -1217,6 +1394,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let limit = ConfigGrpcFilters::default();
         let filter =
@@ -1270,6 +1449,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let limit = ConfigGrpcFilters::default();
         let filter =
@@ -1323,6 +1504,8 @@ mod tests {
             commitment: None,
             accounts_data_slice: Vec::new(),
             ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
         };
         let limit = ConfigGrpcFilters::default();
         let filter =
@@ -1,21 +1,183 @@
 // Adapted yellowstone-grpc/yellowstone-grpc-geyser/src/grpc.rs
 
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use geyser_grpc_proto::prelude::{
+    geyser_server::{Geyser, GeyserServer},
+    subscribe_update::UpdateOneof,
+    CommitmentLevel, GetBlockHeightRequest, GetBlockHeightResponse,
+    GetLatestBlockhashRequest, GetLatestBlockhashResponse, GetSlotRequest,
+    GetSlotResponse, GetVersionRequest, GetVersionResponse,
+    IsBlockhashValidRequest, IsBlockhashValidResponse, PingRequest,
+    PongResponse, SubscribeRequest, SubscribeUpdate,
+    SubscribeUpdateAccountsSnapshotEnd,
+};
+use log::*;
+use magicblock_core::traits::ProvidesAccountsSnapshot;
+use magicblock_metrics::metrics;
+use tokio::sync::{broadcast, mpsc, Notify};
+use tonic::{
+    codec::CompressionEncoding,
+    service::interceptor::InterceptedService,
+    transport::{
+        server::{Server, TcpIncoming},
+        Certificate, Identity, ServerTlsConfig,
+    },
+    Request, Response, Result as TonicResult, Status, Streaming,
+};
+
 use crate::{
+    client_queue::{
+        client_queue, ClientQueueSender, ClientQueueStream, TryPushError,
+    },
+    config::{ConfigGrpc, ConfigGrpcServerTls, ConfigGrpcSlowClientPolicy},
+    filters::Filter,
     grpc_messages::*,
-    types::{GeyserMessageReceiver, SubscriptionsDb},
+    replay::MessagesBroadcast,
+    types::{
+        AccountsSnapshotProvider, GeyserMessage, GeyserMessageReceiver,
+        SubscriptionsDb,
+    },
+    version::GrpcVersionInfo,
 };
 
-#[derive(Debug)]
-pub struct GrpcService {}
+/// Slots are final as soon as they are processed on the ephemeral
+/// validator, so each slot is reported with all commitment levels at once
+const SLOT_COMMITMENT_LEVELS: [CommitmentLevel; 3] = [
+    CommitmentLevel::Processed,
+    CommitmentLevel::Confirmed,
+    CommitmentLevel::Finalized,
+];
+
+/// Implements the Yellowstone (Dragon's Mouth) gRPC protocol on top of the
+/// messages the geyser plugin receives
+pub struct GrpcService {
+    config: ConfigGrpc,
+    subscribe_id: AtomicUsize,
+    broadcast: Arc<MessagesBroadcast>,
+    latest_slot: Arc<AtomicU64>,
+    accounts_snapshot: AccountsSnapshotProvider,
+}
+
+impl std::fmt::Debug for GrpcService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrpcService")
+            .field("config", &self.config)
+            .field("subscribe_id", &self.subscribe_id)
+            .field("latest_slot", &self.latest_slot)
+            .field("accounts_snapshot", &self.accounts_snapshot.get().is_some())
+            .finish()
+    }
+}
 
 impl GrpcService {
+    /// Binds the gRPC server to the configured address and serves it until
+    /// the shutdown is notified
+    pub(crate) fn create(
+        config: ConfigGrpc,
+        broadcast: Arc<MessagesBroadcast>,
+        latest_slot: Arc<AtomicU64>,
+        accounts_snapshot: AccountsSnapshotProvider,
+        shutdown: Arc<Notify>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut server_builder = Server::builder();
+        if let Some(tls_config) = config.tls_config.as_ref() {
+            server_builder = server_builder
+                .tls_config(Self::server_tls_config(tls_config)?)?;
+        }
+        let incoming = TcpIncoming::new(config.address, true, None)?;
+
+        let max_decoding_message_size = config.max_decoding_message_size;
+        let x_tokens = Arc::new(config.x_tokens.clone());
+        let service = GeyserServer::new(Self {
+            config,
+            subscribe_id: AtomicUsize::new(0),
+            broadcast,
+            latest_slot,
+            accounts_snapshot,
+        })
+        .max_decoding_message_size(max_decoding_message_size)
+        .accept_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Gzip);
+        // The health service stays reachable without a token
+        let service =
+            InterceptedService::new(service, move |request: Request<()>| {
+                Self::check_x_token(&x_tokens, request)
+            });
+
+        tokio::spawn(async move {
+            let (mut health_reporter, health_service) =
+                tonic_health::server::health_reporter();
+            health_reporter.set_serving::<GeyserServer<Self>>().await;
+
+            if let Err(err) = server_builder
+                .add_service(health_service)
+                .add_service(service)
+                .serve_with_incoming_shutdown(incoming, shutdown.notified())
+                .await
+            {
+                error!("Geyser gRPC server failed: {:?}", err);
+            }
+        });
+        Ok(())
+    }
+
+    fn server_tls_config(
+        config: &ConfigGrpcServerTls,
+    ) -> Result<ServerTlsConfig, Box<dyn std::error::Error + Send + Sync>> {
+        let read = |path: &Path| {
+            fs::read(path).map_err(|err| {
+                format!("Failed to read '{}': {}", path.display(), err)
+            })
+        };
+        let identity = Identity::from_pem(
+            read(&config.cert_path)?,
+            read(&config.key_path)?,
+        );
+        let mut tls_config = ServerTlsConfig::new().identity(identity);
+        if let Some(path) = config.client_ca_cert_path.as_ref() {
+            tls_config =
+                tls_config.client_ca_root(Certificate::from_pem(read(path)?));
+        }
+        Ok(tls_config)
+    }
+
+    fn check_x_token(
+        x_tokens: &HashSet<String>,
+        request: Request<()>,
+    ) -> TonicResult<Request<()>> {
+        if x_tokens.is_empty() {
+            return Ok(request);
+        }
+        match request
+            .metadata()
+            .get("x-token")
+            .and_then(|token| token.to_str().ok())
+        {
+            Some(token) if x_tokens.contains(token) => Ok(request),
+            _ => Err(Status::unauthenticated("No valid auth token")),
+        }
+    }
+
     pub(crate) async fn geyser_loop(
         messages_rx: GeyserMessageReceiver,
         subscriptions_db: SubscriptionsDb,
+        broadcast: Arc<MessagesBroadcast>,
+        latest_slot: Arc<AtomicU64>,
     ) {
         while let Ok(message) = messages_rx.recv_async().await {
+            broadcast.send(message.clone());
             match *message {
-                Message::Slot(_) => {
+                Message::Slot(ref slot) => {
+                    latest_slot.fetch_max(slot.slot, Ordering::Relaxed);
                     subscriptions_db.send_slot(message).await;
                 }
                 Message::Account(ref account) => {
@@ -38,4 +200,665 @@ impl GrpcService {
             }
         }
     }
+
+    fn empty_subscribe_request() -> SubscribeRequest {
+        SubscribeRequest {
+            accounts: HashMap::new(),
+            slots: HashMap::new(),
+            transactions: HashMap::new(),
+            blocks: HashMap::new(),
+            blocks_meta: HashMap::new(),
+            entry: HashMap::new(),
+            commitment: None,
+            accounts_data_slice: Vec::new(),
+            ping: None,
+            accounts_snapshot: None,
+            from_slot: None,
+        }
+    }
+
+    fn get_updates(
+        filter: &Filter,
+        message: &GeyserMessage,
+    ) -> Vec<SubscribeUpdate> {
+        let commitment = Some(filter.get_commitment_level());
+        match message.as_ref() {
+            Message::Slot(slot) => SLOT_COMMITMENT_LEVELS
+                .into_iter()
+                .flat_map(|status| {
+                    let message = Arc::new(Message::Slot(MessageSlot {
+                        status,
+                        ..*slot
+                    }));
+                    filter.get_update(&message, commitment)
+                })
+                .collect(),
+            _ => filter.get_update(message, commitment),
+        }
+    }
+
+    /// Current state of the accounts matching the accounts filters,
+    /// followed by the end of snapshot marker
+    fn get_accounts_snapshot_updates(
+        filter: &Filter,
+        provider: &dyn ProvidesAccountsSnapshot,
+    ) -> Vec<SubscribeUpdate> {
+        let slot = provider.snapshot_slot();
+        let (accounts, owners) = filter.get_accounts_snapshot_keys();
+        let mut snapshot = HashMap::new();
+        for pubkey in accounts {
+            if let Some(account) = provider.snapshot_account(&pubkey) {
+                snapshot.insert(pubkey, account);
+            }
+        }
+        for owner in owners {
+            snapshot.extend(provider.snapshot_program_accounts(&owner));
+        }
+
+        let mut updates = snapshot
+            .into_iter()
+            .flat_map(|(pubkey, account)| {
+                let message = Arc::new(Message::Account(
+                    MessageAccount::from_snapshot(pubkey, &account, slot),
+                ));
+                Self::get_updates(filter, &message)
+            })
+            .collect::<Vec<_>>();
+        updates.push(SubscribeUpdate {
+            filters: vec![],
+            update_oneof: Some(UpdateOneof::AccountsSnapshotEnd(
+                SubscribeUpdateAccountsSnapshotEnd { slot },
+            )),
+        });
+        updates
+    }
+
+    async fn client_loop(
+        id: usize,
+        mut filter: Filter,
+        stream_tx: ClientQueueSender,
+        mut filter_rx: mpsc::UnboundedReceiver<TonicResult<Filter>>,
+        broadcast: Arc<MessagesBroadcast>,
+        accounts_snapshot: AccountsSnapshotProvider,
+        slow_client_policy: ConfigGrpcSlowClientPolicy,
+    ) {
+        debug!("gRPC client #{id}: new");
+        let client = id.to_string();
+        let mut messages_rx = broadcast.subscribe();
+        let mut filter_rx_closed = false;
+        'outer: loop {
+            tokio::select! {
+                _ = stream_tx.closed() => break 'outer,
+                new_filter = filter_rx.recv(), if !filter_rx_closed => {
+                    let new_filter = match new_filter {
+                        Some(Ok(new_filter)) => new_filter,
+                        // Invalid requests are reported without closing
+                        // the stream
+                        Some(Err(status)) => {
+                            if stream_tx.send(Err(status)).await.is_err() {
+                                break 'outer;
+                            }
+                            continue;
+                        }
+                        // The client stopped sending requests, but keeps
+                        // receiving updates for the current filter
+                        None => {
+                            filter_rx_closed = true;
+                            continue;
+                        }
+                    };
+                    if let Some(pong) = new_filter.get_pong_msg() {
+                        if stream_tx.send(Ok(pong)).await.is_err() {
+                            break 'outer;
+                        }
+                        continue;
+                    }
+                    filter = new_filter;
+
+                    // Continues with the messages sent after the replayed
+                    // ones, instead of the ones received so far
+                    if let Some(from_slot) = filter.get_from_slot() {
+                        let (messages, rx) =
+                            match broadcast.subscribe_from_slot(from_slot) {
+                                Ok(replay) => replay,
+                                Err(err) => {
+                                    let _ = stream_tx
+                                        .send(Err(Status::invalid_argument(err)))
+                                        .await;
+                                    break 'outer;
+                                }
+                            };
+                        messages_rx = rx;
+                        for message in messages {
+                            for update in Self::get_updates(&filter, &message) {
+                                if stream_tx.send(Ok(update)).await.is_err() {
+                                    break 'outer;
+                                }
+                            }
+                        }
+                    }
+
+                    // Updates received while the snapshot is taken are
+                    // queued and sent afterwards, so none are missed
+                    if filter.is_accounts_snapshot_requested() {
+                        let Some(provider) = accounts_snapshot.get().cloned()
+                        else {
+                            let _ = stream_tx
+                                .send(Err(Status::unavailable(
+                                    "accounts snapshot not available yet",
+                                )))
+                                .await;
+                            break 'outer;
+                        };
+                        let snapshot_filter = filter.clone();
+                        let updates = tokio::task::spawn_blocking(move || {
+                            Self::get_accounts_snapshot_updates(
+                                &snapshot_filter,
+                                provider.as_ref(),
+                            )
+                        })
+                        .await;
+                        let updates = match updates {
+                            Ok(updates) => updates,
+                            Err(err) => {
+                                error!(
+                                    "gRPC client #{id}: failed to take accounts snapshot: {:?}",
+                                    err
+                                );
+                                let _ = stream_tx
+                                    .send(Err(Status::internal(
+                                        "failed to take accounts snapshot",
+                                    )))
+                                    .await;
+                                break 'outer;
+                            }
+                        };
+                        for update in updates {
+                            if stream_tx.send(Ok(update)).await.is_err() {
+                                break 'outer;
+                            }
+                        }
+                    }
+                }
+                message = messages_rx.recv() => {
+                    let message = match message {
+                        Ok(message) => message,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            match slow_client_policy {
+                                ConfigGrpcSlowClientPolicy::Disconnect => {
+                                    warn!("gRPC client #{id}: lagged to receive updates");
+                                    Self::disconnect_slow_client(&stream_tx)
+                                        .await;
+                                    break 'outer;
+                                }
+                                ConfigGrpcSlowClientPolicy::DropOldest => {
+                                    metrics::inc_geyser_grpc_client_dropped(
+                                        &client, skipped,
+                                    );
+                                    continue;
+                                }
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            break 'outer;
+                        }
+                    };
+                    for update in Self::get_updates(&filter, &message) {
+                        match slow_client_policy {
+                            ConfigGrpcSlowClientPolicy::Disconnect => {
+                                match stream_tx.try_push(Ok(update)) {
+                                    Ok(()) => {}
+                                    Err(TryPushError::Full) => {
+                                        warn!("gRPC client #{id}: lagged to send updates");
+                                        Self::disconnect_slow_client(&stream_tx)
+                                            .await;
+                                        break 'outer;
+                                    }
+                                    Err(TryPushError::Closed) => break 'outer,
+                                }
+                            }
+                            ConfigGrpcSlowClientPolicy::DropOldest => {
+                                match stream_tx.push_dropping_oldest(Ok(update)) {
+                                    Ok(0) => {}
+                                    Ok(dropped) => {
+                                        metrics::inc_geyser_grpc_client_dropped(
+                                            &client, dropped,
+                                        );
+                                    }
+                                    Err(_) => break 'outer,
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            metrics::set_geyser_grpc_client_queue(&client, stream_tx.len());
+        }
+        metrics::remove_geyser_grpc_client(&client);
+        debug!("gRPC client #{id}: removed");
+    }
+
+    async fn disconnect_slow_client(stream_tx: &ClientQueueSender) {
+        metrics::inc_geyser_grpc_slow_client_disconnects();
+        let _ = stream_tx.send(Err(Status::internal("lagged"))).await;
+    }
+
+    fn check_unary_enabled(&self) -> TonicResult<()> {
+        if self.config.unary_disabled {
+            Err(Status::unimplemented("unary methods are disabled"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Geyser for GrpcService {
+    type SubscribeStream = ClientQueueStream;
+
+    async fn subscribe(
+        &self,
+        mut request: Request<Streaming<SubscribeRequest>>,
+    ) -> TonicResult<Response<Self::SubscribeStream>> {
+        let id = self.subscribe_id.fetch_add(1, Ordering::Relaxed);
+        let filter = Filter::new(
+            &Self::empty_subscribe_request(),
+            &self.config.filters,
+            self.config.normalize_commitment_level,
+        )
+        .expect("empty filter should always be valid");
+        let (stream_tx, stream_rx) = client_queue(self.config.channel_capacity);
+        let (filter_tx, filter_rx) = mpsc::unbounded_channel();
+
+        // Each request the client sends replaces the filter of the stream,
+        // invalid ones are reported without closing the stream
+        let filters_limit = self.config.filters.clone();
+        let normalize_commitment_level = self.config.normalize_commitment_level;
+        tokio::spawn(async move {
+            while let Ok(Some(request)) = request.get_mut().message().await {
+                let result = Filter::new(
+                    &request,
+                    &filters_limit,
+                    normalize_commitment_level,
+                )
+                .map_err(|err| {
+                    Status::invalid_argument(format!(
+                        "failed to create filter: {err}"
+                    ))
+                });
+                if filter_tx.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+
+        tokio::spawn(Self::client_loop(
+            id,
+            filter,
+            stream_tx,
+            filter_rx,
+            self.broadcast.clone(),
+            self.accounts_snapshot.clone(),
+            self.config.slow_client_policy,
+        ));
+
+        Ok(Response::new(stream_rx))
+    }
+
+    async fn ping(
+        &self,
+        request: Request<PingRequest>,
+    ) -> TonicResult<Response<PongResponse>> {
+        self.check_unary_enabled()?;
+        let count = request.get_ref().count;
+        Ok(Response::new(PongResponse { count }))
+    }
+
+    async fn get_latest_blockhash(
+        &self,
+        _request: Request<GetLatestBlockhashRequest>,
+    ) -> TonicResult<Response<GetLatestBlockhashResponse>> {
+        Err(Status::unimplemented(
+            "blockhashes are only available via the JSON RPC",
+        ))
+    }
+
+    async fn get_block_height(
+        &self,
+        _request: Request<GetBlockHeightRequest>,
+    ) -> TonicResult<Response<GetBlockHeightResponse>> {
+        Err(Status::unimplemented(
+            "block heights are only available via the JSON RPC",
+        ))
+    }
+
+    async fn get_slot(
+        &self,
+        _request: Request<GetSlotRequest>,
+    ) -> TonicResult<Response<GetSlotResponse>> {
+        self.check_unary_enabled()?;
+        let slot = self.latest_slot.load(Ordering::Relaxed);
+        Ok(Response::new(GetSlotResponse { slot }))
+    }
+
+    async fn is_blockhash_valid(
+        &self,
+        _request: Request<IsBlockhashValidRequest>,
+    ) -> TonicResult<Response<IsBlockhashValidResponse>> {
+        Err(Status::unimplemented(
+            "blockhashes are only available via the JSON RPC",
+        ))
+    }
+
+    async fn get_version(
+        &self,
+        _request: Request<GetVersionRequest>,
+    ) -> TonicResult<Response<GetVersionResponse>> {
+        self.check_unary_enabled()?;
+        Ok(Response::new(GetVersionResponse {
+            version: serde_json::to_string(&GrpcVersionInfo::default())
+                .map_err(|err| Status::internal(err.to_string()))?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use geyser_grpc_proto::prelude::{
+        SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots,
+        SubscribeRequestFilterTransactions,
+    };
+    use magicblock_transaction_status::{
+        InnerInstruction, InnerInstructions, TransactionStatusMeta,
+    };
+    use solana_sdk::{
+        account::{AccountSharedData, ReadableAccount},
+        hash::Hash,
+        instruction::CompiledInstruction,
+        message::Message as SolMessage,
+        pubkey::Pubkey,
+        signer::{keypair::Keypair, Signer},
+        transaction::{SanitizedTransaction, Transaction},
+        transaction_context::TransactionReturnData,
+    };
+
+    use super::*;
+    use crate::config::ConfigGrpcFilters;
+
+    struct AccountsSnapshotStub {
+        accounts: Vec<(Pubkey, AccountSharedData)>,
+    }
+
+    impl ProvidesAccountsSnapshot for AccountsSnapshotStub {
+        fn snapshot_slot(&self) -> u64 {
+            42
+        }
+
+        fn snapshot_account(
+            &self,
+            pubkey: &Pubkey,
+        ) -> Option<AccountSharedData> {
+            self.accounts
+                .iter()
+                .find(|(key, _)| key == pubkey)
+                .map(|(_, account)| account.clone())
+        }
+
+        fn snapshot_program_accounts(
+            &self,
+            program_id: &Pubkey,
+        ) -> Vec<(Pubkey, AccountSharedData)> {
+            self.accounts
+                .iter()
+                .filter(|(_, account)| account.owner() == program_id)
+                .cloned()
+                .collect()
+        }
+    }
+
+    fn slot_subscribe_request(
+        commitment: CommitmentLevel,
+        filter_by_commitment: bool,
+    ) -> SubscribeRequest {
+        let mut request = GrpcService::empty_subscribe_request();
+        request.slots.insert(
+            "slots".to_string(),
+            SubscribeRequestFilterSlots {
+                filter_by_commitment: Some(filter_by_commitment),
+            },
+        );
+        request.commitment = Some(commitment as i32);
+        request
+    }
+
+    fn slot_message() -> GeyserMessage {
+        Arc::new(Message::Slot(MessageSlot {
+            slot: 42,
+            parent: Some(41),
+            status: CommitmentLevel::Finalized,
+        }))
+    }
+
+    fn update_statuses(updates: &[SubscribeUpdate]) -> Vec<i32> {
+        updates
+            .iter()
+            .map(|update| match update.update_oneof {
+                Some(UpdateOneof::Slot(ref slot)) => slot.status,
+                _ => panic!("expected slot update"),
+            })
+            .collect()
+    }
+
+    fn request_with_x_token(token: Option<&str>) -> Request<()> {
+        let mut request = Request::new(());
+        if let Some(token) = token {
+            request
+                .metadata_mut()
+                .insert("x-token", token.parse().unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn test_x_token_not_required_if_none_configured() {
+        let x_tokens = HashSet::new();
+        assert!(GrpcService::check_x_token(
+            &x_tokens,
+            request_with_x_token(None)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_x_token_required_if_configured() {
+        let x_tokens = HashSet::from(["secret".to_string()]);
+        assert!(GrpcService::check_x_token(
+            &x_tokens,
+            request_with_x_token(Some("secret"))
+        )
+        .is_ok());
+        for token in [None, Some("wrong")] {
+            let status = GrpcService::check_x_token(
+                &x_tokens,
+                request_with_x_token(token),
+            )
+            .unwrap_err();
+            assert_eq!(status.code(), tonic::Code::Unauthenticated);
+        }
+    }
+
+    #[test]
+    fn test_slot_updates_for_all_commitment_levels() {
+        let request = slot_subscribe_request(CommitmentLevel::Processed, false);
+        let filter =
+            Filter::new(&request, &ConfigGrpcFilters::default(), false)
+                .unwrap();
+
+        let updates = GrpcService::get_updates(&filter, &slot_message());
+        assert_eq!(
+            update_statuses(&updates),
+            vec![
+                CommitmentLevel::Processed as i32,
+                CommitmentLevel::Confirmed as i32,
+                CommitmentLevel::Finalized as i32,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slot_updates_filtered_by_commitment() {
+        let request = slot_subscribe_request(CommitmentLevel::Confirmed, true);
+        let filter =
+            Filter::new(&request, &ConfigGrpcFilters::default(), false)
+                .unwrap();
+
+        let updates = GrpcService::get_updates(&filter, &slot_message());
+        assert_eq!(
+            update_statuses(&updates),
+            vec![CommitmentLevel::Confirmed as i32]
+        );
+    }
+
+    #[test]
+    fn test_transaction_updates_include_execution_details() {
+        let keypair = Keypair::new();
+        let transaction =
+            SanitizedTransaction::from_transaction_for_tests(Transaction::new(
+                &[&keypair],
+                SolMessage::new(&[], Some(&keypair.pubkey())),
+                Hash::default(),
+            ));
+        let return_data = TransactionReturnData {
+            program_id: Pubkey::new_unique(),
+            data: vec![1, 2, 3],
+        };
+        let meta = TransactionStatusMeta {
+            inner_instructions: Some(vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(
+                        0,
+                        vec![4, 5],
+                        vec![0],
+                    ),
+                    stack_height: Some(2),
+                }],
+            }]),
+            log_messages: Some(vec!["Program log: hello".to_string()]),
+            return_data: Some(return_data.clone()),
+            compute_units_consumed: Some(1_234),
+            ..Default::default()
+        };
+        let message = Arc::new(Message::Transaction(MessageTransaction {
+            transaction: MessageTransactionInfo {
+                signature: *transaction.signature(),
+                is_vote: false,
+                transaction,
+                meta,
+                index: 0,
+            },
+            slot: 42,
+        }));
+
+        let mut request = GrpcService::empty_subscribe_request();
+        request.transactions.insert(
+            "transactions".to_string(),
+            SubscribeRequestFilterTransactions {
+                account_include: vec![keypair.pubkey().to_string()],
+                ..Default::default()
+            },
+        );
+        let filter =
+            Filter::new(&request, &ConfigGrpcFilters::default(), false)
+                .unwrap();
+
+        let updates = GrpcService::get_updates(&filter, &message);
+        assert_eq!(updates.len(), 1);
+        let meta = match &updates[0].update_oneof {
+            Some(UpdateOneof::Transaction(update)) => {
+                update.transaction.as_ref().unwrap().meta.clone().unwrap()
+            }
+            _ => panic!("expected transaction update"),
+        };
+        assert!(!meta.inner_instructions_none);
+        assert_eq!(meta.inner_instructions.len(), 1);
+        let inner_instruction = &meta.inner_instructions[0].instructions[0];
+        assert_eq!(inner_instruction.data, vec![4, 5]);
+        assert_eq!(inner_instruction.stack_height, Some(2));
+        assert!(!meta.log_messages_none);
+        assert_eq!(meta.log_messages, vec!["Program log: hello".to_string()]);
+        assert!(!meta.return_data_none);
+        let proto_return_data = meta.return_data.unwrap();
+        assert_eq!(
+            proto_return_data.program_id,
+            return_data.program_id.to_bytes().to_vec()
+        );
+        assert_eq!(proto_return_data.data, return_data.data);
+        assert_eq!(meta.compute_units_consumed, Some(1_234));
+    }
+
+    #[test]
+    fn test_accounts_snapshot_updates() {
+        let program_id = Pubkey::new_unique();
+        let owned = Pubkey::new_unique();
+        let selected = Pubkey::new_unique();
+        let provider = AccountsSnapshotStub {
+            accounts: vec![
+                (owned, AccountSharedData::new(1, 0, &program_id)),
+                (selected, AccountSharedData::new(2, 0, &Pubkey::default())),
+                (
+                    Pubkey::new_unique(),
+                    AccountSharedData::new(3, 0, &Pubkey::default()),
+                ),
+            ],
+        };
+        let mut request = GrpcService::empty_subscribe_request();
+        request.accounts.insert(
+            "owner".to_string(),
+            SubscribeRequestFilterAccounts {
+                owner: vec![program_id.to_string()],
+                ..Default::default()
+            },
+        );
+        request.accounts.insert(
+            "account".to_string(),
+            SubscribeRequestFilterAccounts {
+                account: vec![selected.to_string()],
+                ..Default::default()
+            },
+        );
+        request.accounts_snapshot = Some(true);
+        let filter =
+            Filter::new(&request, &ConfigGrpcFilters::default(), false)
+                .unwrap();
+        assert!(filter.is_accounts_snapshot_requested());
+
+        let mut updates =
+            GrpcService::get_accounts_snapshot_updates(&filter, &provider);
+        assert!(matches!(
+            updates.pop().unwrap().update_oneof,
+            Some(UpdateOneof::AccountsSnapshotEnd(
+                SubscribeUpdateAccountsSnapshotEnd { slot: 42 }
+            ))
+        ));
+        let mut accounts = updates
+            .into_iter()
+            .map(|update| match update.update_oneof {
+                Some(UpdateOneof::Account(account)) => {
+                    assert!(account.is_startup);
+                    assert_eq!(account.slot, 42);
+                    (update.filters, account.account.unwrap().lamports)
+                }
+                _ => panic!("expected account update"),
+            })
+            .collect::<Vec<_>>();
+        accounts.sort_by_key(|(_, lamports)| *lamports);
+        assert_eq!(
+            accounts,
+            vec![
+                (vec!["owner".to_string()], 1),
+                (vec!["account".to_string()], 2),
+            ]
+        );
+    }
 }
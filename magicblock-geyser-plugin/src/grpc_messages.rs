@@ -15,8 +15,11 @@ use solana_geyser_plugin_interface::geyser_plugin_interface::{
     ReplicaTransactionInfoV2, SlotStatus,
 };
 use solana_sdk::{
-    account::ReadableAccount, clock::UnixTimestamp, pubkey::Pubkey,
-    signature::Signature, transaction::SanitizedTransaction,
+    account::{AccountSharedData, ReadableAccount},
+    clock::UnixTimestamp,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::SanitizedTransaction,
 };
 
 use crate::filters::FilterAccountsDataSlice;
@@ -112,6 +115,30 @@ impl<'a> From<(&'a ReplicaAccountInfoV3<'a>, u64, bool)> for MessageAccount {
     }
 }
 
+impl MessageAccount {
+    /// Account state taken from the accounts db instead of an update
+    pub fn from_snapshot(
+        pubkey: Pubkey,
+        account: &AccountSharedData,
+        slot: u64,
+    ) -> Self {
+        Self {
+            account: MessageAccountInfo {
+                pubkey,
+                lamports: account.lamports(),
+                owner: *account.owner(),
+                executable: account.executable(),
+                rent_epoch: account.rent_epoch(),
+                data: account.data().to_vec(),
+                write_version: 0,
+                txn_signature: None,
+            },
+            slot,
+            is_startup: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct MessageSlot {
     pub slot: u64,
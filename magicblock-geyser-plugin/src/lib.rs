@@ -1,9 +1,12 @@
+mod client_queue;
 pub mod config;
 pub mod filters;
 pub mod grpc;
 pub mod grpc_messages;
 pub mod plugin;
+mod replay;
 pub mod rpc;
+pub mod sink;
 pub mod types;
 mod utils;
 pub mod version;
@@ -78,6 +78,7 @@ impl GrpcGeyserPlugin {
         let (rpc_channel, rpc_shutdown, rpc_service) =
             GeyserRpcService::create(
                 config.grpc.clone(),
+                config.sink.clone(),
                 transactions_cache.as_ref().map(|x| x.shared_map()),
                 accounts_cache.as_ref().map(|x| x.shared_map()),
             )
@@ -279,16 +280,41 @@ impl GeyserPlugin for GrpcGeyserPlugin {
 
     fn notify_entry(
         &self,
-        _entry: ReplicaEntryInfoVersions,
+        entry: ReplicaEntryInfoVersions,
     ) -> PluginResult<()> {
-        Ok(())
+        self.with_inner(|inner| {
+            let entry =
+                match entry {
+                    ReplicaEntryInfoVersions::V0_0_2(info) => info,
+                    _ => return Err(GeyserPluginError::Custom(
+                        "only ReplicaEntryInfoVersions::V0_0_2 is supported"
+                            .into(),
+                    )),
+                };
+            let message = Arc::new(Message::Entry(entry.into()));
+            inner.send_message(&message);
+            Ok(())
+        })
     }
 
     fn notify_block_metadata(
         &self,
-        _blockinfo: ReplicaBlockInfoVersions,
+        blockinfo: ReplicaBlockInfoVersions,
     ) -> PluginResult<()> {
-        Ok(())
+        self.with_inner(|inner| {
+            let blockinfo =
+                match blockinfo {
+                    ReplicaBlockInfoVersions::V0_0_3(info) => info,
+                    _ => return Err(GeyserPluginError::Custom(
+                        "only ReplicaBlockInfoVersions::V0_0_3 is supported"
+                            .into(),
+                    )),
+                };
+            trace!("block: {} ({})", blockinfo.slot, blockinfo.blockhash);
+            let message = Arc::new(Message::BlockMeta(blockinfo.into()));
+            inner.send_message(&message);
+            Ok(())
+        })
     }
 
     fn account_data_notifications_enabled(&self) -> bool {
@@ -300,6 +326,6 @@ impl GeyserPlugin for GrpcGeyserPlugin {
     }
 
     fn entry_notifications_enabled(&self) -> bool {
-        false
+        self.config.enable_entry_notifications
     }
 }
@@ -0,0 +1,239 @@
+use std::{collections::VecDeque, mem, sync::Mutex};
+
+use solana_sdk::clock::Slot;
+use tokio::sync::broadcast;
+
+use crate::{grpc_messages::Message, types::GeyserMessage};
+
+/// Broadcasts messages to the gRPC clients and keeps the most recent ones
+/// around, so that clients can replay them after reconnecting.
+#[derive(Debug)]
+pub struct MessagesBroadcast {
+    tx: broadcast::Sender<GeyserMessage>,
+    replay: Mutex<ReplayBuffer>,
+}
+
+impl MessagesBroadcast {
+    /// Stores messages of up to `replay_stored_slots` slots, but no more
+    /// than `replay_max_bytes`, replay is disabled if no slots are stored
+    pub fn new(
+        capacity: usize,
+        replay_stored_slots: u64,
+        replay_max_bytes: usize,
+    ) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            replay: Mutex::new(ReplayBuffer::new(
+                replay_stored_slots,
+                replay_max_bytes,
+            )),
+        }
+    }
+
+    pub fn send(&self, message: GeyserMessage) {
+        let mut replay = self.replay.lock().expect("replay lock poisoned");
+        replay.push(message.clone());
+        // Only fails if no client is subscribed
+        let _ = self.tx.send(message);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<GeyserMessage> {
+        self.tx.subscribe()
+    }
+
+    /// Stored messages from the slot on, along with a receiver of the
+    /// messages sent afterwards, so that none are missed or duplicated
+    pub fn subscribe_from_slot(
+        &self,
+        from_slot: Slot,
+    ) -> Result<(Vec<GeyserMessage>, broadcast::Receiver<GeyserMessage>), String>
+    {
+        let replay = self.replay.lock().expect("replay lock poisoned");
+        let messages = replay.messages_from_slot(from_slot)?;
+        Ok((messages, self.tx.subscribe()))
+    }
+}
+
+#[derive(Debug)]
+struct ReplayBuffer {
+    messages: VecDeque<(GeyserMessage, usize)>,
+    bytes: usize,
+    max_slots: u64,
+    max_bytes: usize,
+    latest_slot: Slot,
+    /// Messages of this slot and older ones were dropped
+    evicted_slot: Option<Slot>,
+}
+
+impl ReplayBuffer {
+    fn new(max_slots: u64, max_bytes: usize) -> Self {
+        Self {
+            messages: VecDeque::new(),
+            bytes: 0,
+            max_slots,
+            max_bytes,
+            latest_slot: 0,
+            evicted_slot: None,
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_slots > 0
+    }
+
+    fn push(&mut self, message: GeyserMessage) {
+        if !self.is_enabled() {
+            return;
+        }
+        let size = message_size(&message);
+        self.latest_slot = self.latest_slot.max(message.get_slot());
+        self.bytes += size;
+        self.messages.push_back((message, size));
+
+        let min_slot = self.latest_slot.saturating_sub(self.max_slots - 1);
+        while let Some((oldest, size)) = self.messages.front() {
+            if oldest.get_slot() >= min_slot && self.bytes <= self.max_bytes {
+                break;
+            }
+            self.bytes -= size;
+            let evicted_slot = oldest.get_slot();
+            self.evicted_slot = Some(
+                self.evicted_slot
+                    .map_or(evicted_slot, |slot| slot.max(evicted_slot)),
+            );
+            self.messages.pop_front();
+        }
+    }
+
+    fn oldest_available_slot(&self) -> Slot {
+        self.evicted_slot.map_or(0, |slot| slot + 1)
+    }
+
+    fn messages_from_slot(
+        &self,
+        from_slot: Slot,
+    ) -> Result<Vec<GeyserMessage>, String> {
+        if !self.is_enabled() {
+            return Err("replay from slot is disabled".to_string());
+        }
+        let oldest_available_slot = self.oldest_available_slot();
+        if from_slot < oldest_available_slot {
+            return Err(format!(
+                "from_slot {} is not available anymore, oldest available slot is {}",
+                from_slot, oldest_available_slot
+            ));
+        }
+        Ok(self
+            .messages
+            .iter()
+            .filter(|(message, _)| message.get_slot() >= from_slot)
+            .map(|(message, _)| message.clone())
+            .collect())
+    }
+}
+
+/// Approximate memory held by the message
+fn message_size(message: &Message) -> usize {
+    let payload = match message {
+        Message::Account(account) => account.account.data.len(),
+        Message::Transaction(txn) => {
+            let meta = &txn.transaction.meta;
+            txn.transaction.transaction.message().account_keys().len() * 32
+                + meta
+                    .log_messages
+                    .as_ref()
+                    .map(|logs| logs.iter().map(String::len).sum())
+                    .unwrap_or_default()
+        }
+        Message::Block(block) => {
+            block.accounts.iter().map(|acc| acc.data.len()).sum()
+        }
+        Message::Slot(_) | Message::Entry(_) | Message::BlockMeta(_) => 0,
+    };
+    mem::size_of::<Message>() + payload
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use geyser_grpc_proto::prelude::CommitmentLevel;
+    use solana_sdk::pubkey::Pubkey;
+
+    use super::*;
+    use crate::grpc_messages::{MessageAccount, MessageSlot};
+
+    fn slot_message(slot: Slot) -> GeyserMessage {
+        Arc::new(Message::Slot(MessageSlot {
+            slot,
+            parent: slot.checked_sub(1),
+            status: CommitmentLevel::Finalized,
+        }))
+    }
+
+    fn account_message(slot: Slot, data_len: usize) -> GeyserMessage {
+        let mut account = MessageAccount::from_snapshot(
+            Pubkey::new_unique(),
+            &Default::default(),
+            slot,
+        );
+        account.account.data = vec![0; data_len];
+        account.is_startup = false;
+        Arc::new(Message::Account(account))
+    }
+
+    fn slots(messages: &[GeyserMessage]) -> Vec<Slot> {
+        messages.iter().map(|message| message.get_slot()).collect()
+    }
+
+    #[test]
+    fn test_replay_disabled() {
+        let broadcast = MessagesBroadcast::new(16, 0, usize::MAX);
+        broadcast.send(slot_message(1));
+        assert!(broadcast.subscribe_from_slot(1).is_err());
+    }
+
+    #[test]
+    fn test_replay_evicts_old_slots() {
+        let broadcast = MessagesBroadcast::new(16, 2, usize::MAX);
+        for slot in 1..=4 {
+            broadcast.send(slot_message(slot));
+        }
+
+        let (messages, _) = broadcast.subscribe_from_slot(3).unwrap();
+        assert_eq!(slots(&messages), vec![3, 4]);
+        let (messages, _) = broadcast.subscribe_from_slot(4).unwrap();
+        assert_eq!(slots(&messages), vec![4]);
+        assert!(broadcast.subscribe_from_slot(2).is_err());
+    }
+
+    #[test]
+    fn test_replay_evicts_when_exceeding_max_bytes() {
+        let message_size = message_size(&account_message(1, 100));
+        let broadcast = MessagesBroadcast::new(16, 100, 2 * message_size);
+        broadcast.send(account_message(1, 100));
+        broadcast.send(account_message(2, 100));
+        broadcast.send(account_message(2, 100));
+        broadcast.send(account_message(3, 100));
+
+        // Slot 2 is partially evicted, so only later slots are complete
+        assert!(broadcast.subscribe_from_slot(2).is_err());
+        let (messages, _) = broadcast.subscribe_from_slot(3).unwrap();
+        assert_eq!(slots(&messages), vec![3]);
+    }
+
+    #[test]
+    fn test_replay_continues_with_later_messages() {
+        let broadcast = MessagesBroadcast::new(16, 10, usize::MAX);
+        broadcast.send(slot_message(1));
+        broadcast.send(slot_message(2));
+
+        let (messages, mut rx) = broadcast.subscribe_from_slot(2).unwrap();
+        assert_eq!(slots(&messages), vec![2]);
+
+        broadcast.send(slot_message(3));
+        assert_eq!(rx.try_recv().unwrap().get_slot(), 3);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -2,15 +2,19 @@ use std::sync::{atomic::AtomicU64, Arc};
 
 use expiring_hashmap::SharedMap;
 use log::*;
+use magicblock_core::traits::ProvidesAccountsSnapshot;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use tokio::sync::{mpsc, Notify};
+use tokio::sync::Notify;
 
 use crate::{
-    config::ConfigGrpc,
+    config::{ConfigGrpc, ConfigSink},
     grpc::GrpcService,
+    replay::MessagesBroadcast,
+    sink::GeyserSink,
     types::{
-        geyser_message_channel, GeyserMessage, GeyserMessageSender,
-        LogsSubscribeKey, SubscriptionsDb,
+        geyser_message_channel, update_channel, AccountsSnapshotProvider,
+        GeyserMessage, GeyserMessageSender, LogsSubscribeKey, SubscriptionsDb,
+        UpdateReceiver,
     },
     utils::{short_signature, CacheState},
 };
@@ -21,6 +25,7 @@ pub struct GeyserRpcService {
     pub subscriptions_db: SubscriptionsDb,
     transactions_cache: Option<SharedMap<Signature, GeyserMessage>>,
     accounts_cache: Option<SharedMap<Pubkey, GeyserMessage>>,
+    accounts_snapshot: AccountsSnapshotProvider,
 }
 
 impl std::fmt::Debug for GeyserRpcService {
@@ -40,6 +45,7 @@ impl GeyserRpcService {
     #[allow(clippy::type_complexity)]
     pub fn create(
         config: ConfigGrpc,
+        sink_config: Option<ConfigSink>,
         transactions_cache: Option<SharedMap<Signature, GeyserMessage>>,
         accounts_cache: Option<SharedMap<Pubkey, GeyserMessage>>,
     ) -> Result<
@@ -52,21 +58,57 @@ impl GeyserRpcService {
             transactions_cache,
             accounts_cache,
             subscriptions_db: SubscriptionsDb::default(),
+            accounts_snapshot: AccountsSnapshotProvider::default(),
         };
 
+        // TODO: should Geyser handle shutdown or the piece that instantiates
+        // the RPC service?
+        let shutdown = Arc::new(Notify::new());
+
+        // Serve the Yellowstone gRPC protocol
+        let broadcast = Arc::new(MessagesBroadcast::new(
+            config.channel_capacity,
+            config.replay_stored_slots,
+            config.replay_max_bytes,
+        ));
+        let latest_slot = Arc::new(AtomicU64::new(0));
+        GrpcService::create(
+            config,
+            broadcast.clone(),
+            latest_slot.clone(),
+            rpc_service.accounts_snapshot.clone(),
+            shutdown.clone(),
+        )?;
+
+        // Publish updates to a message bus
+        if let Some(sink_config) = sink_config {
+            let sink = GeyserSink::new(sink_config)?;
+            tokio::spawn(sink.run(broadcast.subscribe()));
+        }
+
         // Run geyser message loop
         let (messages_tx, messages_rx) = geyser_message_channel();
         tokio::spawn(GrpcService::geyser_loop(
             messages_rx,
             rpc_service.subscriptions_db.clone(),
+            broadcast,
+            latest_slot,
         ));
 
-        // TODO: should Geyser handle shutdown or the piece that instantiates
-        // the RPC service?
-        let shutdown = Arc::new(Notify::new());
         Ok((messages_tx, shutdown, rpc_service))
     }
 
+    /// Allows gRPC clients to request a snapshot of the accounts they
+    /// subscribe to, only the first provider set is used
+    pub fn set_accounts_snapshot_provider(
+        &self,
+        provider: Arc<dyn ProvidesAccountsSnapshot>,
+    ) {
+        if self.accounts_snapshot.set(provider).is_err() {
+            warn!("Accounts snapshot provider was already set");
+        }
+    }
+
     // -----------------
     // Subscriptions
     // -----------------
@@ -74,9 +116,9 @@ impl GeyserRpcService {
         &self,
         subid: u64,
         pubkey: Pubkey,
-    ) -> mpsc::Receiver<GeyserMessage> {
+    ) -> UpdateReceiver {
         let (updates_tx, updates_rx) =
-            mpsc::channel(self.config.channel_capacity);
+            update_channel(self.config.channel_capacity);
         let msg = self
             .accounts_cache
             .as_ref()
@@ -97,9 +139,9 @@ impl GeyserRpcService {
         &self,
         subid: u64,
         pubkey: Pubkey,
-    ) -> mpsc::Receiver<GeyserMessage> {
+    ) -> UpdateReceiver {
         let (updates_tx, updates_rx) =
-            mpsc::channel(self.config.channel_capacity);
+            update_channel(self.config.channel_capacity);
         self.subscriptions_db
             .subscribe_to_program(pubkey, updates_tx, subid)
             .await;
@@ -111,9 +153,9 @@ impl GeyserRpcService {
         &self,
         subid: u64,
         signature: Signature,
-    ) -> mpsc::Receiver<GeyserMessage> {
+    ) -> UpdateReceiver {
         let (updates_tx, updates_rx) =
-            mpsc::channel(self.config.channel_capacity);
+            update_channel(self.config.channel_capacity);
         let msg = self
             .transactions_cache
             .as_ref()
@@ -132,12 +174,9 @@ impl GeyserRpcService {
         updates_rx
     }
 
-    pub async fn slot_subscribe(
-        &self,
-        subid: u64,
-    ) -> mpsc::Receiver<GeyserMessage> {
+    pub async fn slot_subscribe(&self, subid: u64) -> UpdateReceiver {
         let (updates_tx, updates_rx) =
-            mpsc::channel(self.config.channel_capacity);
+            update_channel(self.config.channel_capacity);
         self.subscriptions_db
             .subscribe_to_slot(updates_tx, subid)
             .await;
@@ -148,9 +187,9 @@ impl GeyserRpcService {
         &self,
         key: LogsSubscribeKey,
         subid: u64,
-    ) -> mpsc::Receiver<GeyserMessage> {
+    ) -> UpdateReceiver {
         let (updates_tx, updates_rx) =
-            mpsc::channel(self.config.channel_capacity);
+            update_channel(self.config.channel_capacity);
         self.subscriptions_db
             .subscribe_to_logs(key, updates_tx, subid)
             .await;
@@ -0,0 +1,409 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as base64_engine, Engine};
+use geyser_grpc_proto::{prelude::SubscribeUpdate, prost::Message as _};
+use log::*;
+use serde::Serialize;
+use solana_sdk::transaction::TransactionError;
+use tokio::sync::broadcast;
+
+use crate::{
+    config::{ConfigSink, ConfigSinkBus, ConfigSinkFormat},
+    grpc_messages::{Message, MessageAccount, MessageRef, MessageTransaction},
+    types::GeyserMessage,
+};
+
+pub type SinkResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+/// Message bus the [GeyserSink] publishes updates to
+#[async_trait]
+pub trait SinkPublisher: Send + Sync {
+    /// Publishes the payload to the topic, the key is the account pubkey or
+    /// transaction signature the update is about
+    async fn publish(
+        &self,
+        topic: &str,
+        key: Vec<u8>,
+        payload: Vec<u8>,
+    ) -> SinkResult<()>;
+}
+
+/// Publishes account updates and transaction notifications to a message bus
+/// for consumers that don't pull them via gRPC.
+/// Updates are dropped if the bus can't keep up, they are not retried.
+pub struct GeyserSink {
+    publisher: Box<dyn SinkPublisher>,
+    format: ConfigSinkFormat,
+    accounts_topic: Option<String>,
+    transactions_topic: Option<String>,
+}
+
+impl GeyserSink {
+    pub fn new(config: ConfigSink) -> SinkResult<Self> {
+        let publisher: Box<dyn SinkPublisher> = match config.bus {
+            #[cfg(feature = "kafka")]
+            ConfigSinkBus::Kafka { brokers } => {
+                Box::new(kafka::KafkaPublisher::new(&brokers)?)
+            }
+            #[cfg(not(feature = "kafka"))]
+            ConfigSinkBus::Kafka { .. } => {
+                return Err("Geyser sink to Kafka requires the validator to be built with the 'kafka' feature".into());
+            }
+            #[cfg(feature = "nats")]
+            ConfigSinkBus::Nats { url } => {
+                Box::new(nats::NatsPublisher::new(url))
+            }
+            #[cfg(not(feature = "nats"))]
+            ConfigSinkBus::Nats { .. } => {
+                return Err("Geyser sink to NATS requires the validator to be built with the 'nats' feature".into());
+            }
+        };
+        Ok(Self::with_publisher(publisher, config))
+    }
+
+    pub fn with_publisher(
+        publisher: Box<dyn SinkPublisher>,
+        config: ConfigSink,
+    ) -> Self {
+        Self {
+            publisher,
+            format: config.format,
+            accounts_topic: config.accounts_topic,
+            transactions_topic: config.transactions_topic,
+        }
+    }
+
+    pub async fn run(
+        self,
+        mut messages_rx: broadcast::Receiver<GeyserMessage>,
+    ) {
+        loop {
+            let message = match messages_rx.recv().await {
+                Ok(message) => message,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Geyser sink lagged, dropped {skipped} updates");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let Some((topic, key, payload)) = self.encode(&message) else {
+                continue;
+            };
+            if let Err(err) = self.publisher.publish(topic, key, payload).await
+            {
+                warn!("Failed to publish update to '{}': {:?}", topic, err);
+            }
+        }
+    }
+
+    /// Topic, key and payload of the message, `None` if the message is not
+    /// published
+    fn encode(
+        &self,
+        message: &GeyserMessage,
+    ) -> Option<(&str, Vec<u8>, Vec<u8>)> {
+        let encoded = match message.as_ref() {
+            Message::Account(account) => {
+                let topic = self.accounts_topic.as_deref()?;
+                let payload = match self.format {
+                    ConfigSinkFormat::Protobuf => {
+                        Self::encode_proto(MessageRef::Account(account))
+                    }
+                    ConfigSinkFormat::Json => {
+                        serde_json::to_vec(&JsonAccountUpdate::from(account))
+                            .expect("account update to serialize")
+                    }
+                };
+                (topic, account.account.pubkey.to_bytes().to_vec(), payload)
+            }
+            Message::Transaction(txn) => {
+                let topic = self.transactions_topic.as_deref()?;
+                let payload = match self.format {
+                    ConfigSinkFormat::Protobuf => {
+                        Self::encode_proto(MessageRef::Transaction(txn))
+                    }
+                    ConfigSinkFormat::Json => {
+                        serde_json::to_vec(&JsonTransactionUpdate::from(txn))
+                            .expect("transaction update to serialize")
+                    }
+                };
+                (topic, txn.transaction.signature.as_ref().to_vec(), payload)
+            }
+            Message::Slot(_)
+            | Message::Entry(_)
+            | Message::Block(_)
+            | Message::BlockMeta(_) => return None,
+        };
+        Some(encoded)
+    }
+
+    fn encode_proto(message: MessageRef) -> Vec<u8> {
+        SubscribeUpdate {
+            filters: vec![],
+            update_oneof: Some(message.to_proto(&[])),
+        }
+        .encode_to_vec()
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonAccountUpdate {
+    slot: u64,
+    pubkey: String,
+    owner: String,
+    lamports: u64,
+    executable: bool,
+    rent_epoch: u64,
+    /// Base64 encoded
+    data: String,
+    write_version: u64,
+    txn_signature: Option<String>,
+}
+
+impl From<&MessageAccount> for JsonAccountUpdate {
+    fn from(message: &MessageAccount) -> Self {
+        let account = &message.account;
+        Self {
+            slot: message.slot,
+            pubkey: account.pubkey.to_string(),
+            owner: account.owner.to_string(),
+            lamports: account.lamports,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: base64_engine.encode(&account.data),
+            write_version: account.write_version,
+            txn_signature: account.txn_signature.map(|sig| sig.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonTransactionUpdate<'a> {
+    slot: u64,
+    signature: String,
+    index: usize,
+    is_vote: bool,
+    err: Option<&'a TransactionError>,
+    fee: u64,
+    compute_units_consumed: Option<u64>,
+    log_messages: Option<&'a [String]>,
+}
+
+impl<'a> From<&'a MessageTransaction> for JsonTransactionUpdate<'a> {
+    fn from(message: &'a MessageTransaction) -> Self {
+        let txn = &message.transaction;
+        Self {
+            slot: message.slot,
+            signature: txn.signature.to_string(),
+            index: txn.index,
+            is_vote: txn.is_vote,
+            err: txn.meta.status.as_ref().err(),
+            fee: txn.meta.fee,
+            compute_units_consumed: txn.meta.compute_units_consumed,
+            log_messages: txn.meta.log_messages.as_deref(),
+        }
+    }
+}
+
+#[cfg(feature = "kafka")]
+mod kafka {
+    use async_trait::async_trait;
+    use rdkafka::producer::{
+        BaseRecord, DefaultProducerContext, ThreadedProducer,
+    };
+
+    use super::{SinkPublisher, SinkResult};
+
+    /// Enqueues each update to be produced by the background thread of
+    /// the producer without waiting for the broker to acknowledge it
+    pub(super) struct KafkaPublisher {
+        producer: ThreadedProducer<DefaultProducerContext>,
+    }
+
+    impl KafkaPublisher {
+        pub(super) fn new(brokers: &str) -> SinkResult<Self> {
+            let producer = rdkafka::ClientConfig::new()
+                .set("bootstrap.servers", brokers)
+                .create()?;
+            Ok(Self { producer })
+        }
+    }
+
+    #[async_trait]
+    impl SinkPublisher for KafkaPublisher {
+        async fn publish(
+            &self,
+            topic: &str,
+            key: Vec<u8>,
+            payload: Vec<u8>,
+        ) -> SinkResult<()> {
+            self.producer
+                .send(BaseRecord::to(topic).key(&key).payload(&payload))
+                .map_err(|(err, _)| err)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "nats")]
+mod nats {
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use log::*;
+    use tokio::sync::OnceCell;
+
+    use super::{SinkPublisher, SinkResult};
+
+    /// Connects to the server in the background once it is created, the
+    /// client reconnects by itself whenever the connection is lost.
+    /// Updates published before the client is set up are dropped.
+    pub(super) struct NatsPublisher {
+        client: Arc<OnceCell<async_nats::Client>>,
+    }
+
+    impl NatsPublisher {
+        /// Needs to be called inside a tokio runtime
+        pub(super) fn new(url: String) -> Self {
+            let client = Arc::new(OnceCell::new());
+            tokio::spawn({
+                let client = client.clone();
+                async move {
+                    // Retrying the initial connect resolves right away and
+                    // leaves connecting to the client's background task
+                    match async_nats::ConnectOptions::new()
+                        .retry_on_initial_connect()
+                        .connect(url.as_str())
+                        .await
+                    {
+                        Ok(connected) => {
+                            let _ = client.set(connected);
+                        }
+                        Err(err) => {
+                            error!(
+                                "Failed to set up NATS client for '{}': {:?}",
+                                url, err
+                            );
+                        }
+                    }
+                }
+            });
+            Self { client }
+        }
+    }
+
+    #[async_trait]
+    impl SinkPublisher for NatsPublisher {
+        async fn publish(
+            &self,
+            topic: &str,
+            _key: Vec<u8>,
+            payload: Vec<u8>,
+        ) -> SinkResult<()> {
+            let client =
+                self.client.get().ok_or("NATS client is not set up yet")?;
+            client.publish(topic.to_string(), payload.into()).await?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use geyser_grpc_proto::prelude::CommitmentLevel;
+    use solana_sdk::{account::AccountSharedData, pubkey::Pubkey};
+
+    use super::*;
+    use crate::grpc_messages::MessageSlot;
+
+    type Published = Arc<Mutex<Vec<(String, Vec<u8>, Vec<u8>)>>>;
+
+    struct PublisherStub {
+        published: Published,
+    }
+
+    #[async_trait]
+    impl SinkPublisher for PublisherStub {
+        async fn publish(
+            &self,
+            topic: &str,
+            key: Vec<u8>,
+            payload: Vec<u8>,
+        ) -> SinkResult<()> {
+            self.published.lock().unwrap().push((
+                topic.to_string(),
+                key,
+                payload,
+            ));
+            Ok(())
+        }
+    }
+
+    fn sink(format: ConfigSinkFormat) -> (GeyserSink, Published) {
+        let published = Published::default();
+        let sink = GeyserSink::with_publisher(
+            Box::new(PublisherStub {
+                published: published.clone(),
+            }),
+            ConfigSink {
+                bus: ConfigSinkBus::Nats {
+                    url: "nats://localhost:4222".to_string(),
+                },
+                format,
+                accounts_topic: Some("accounts".to_string()),
+                transactions_topic: None,
+            },
+        );
+        (sink, published)
+    }
+
+    fn account_message(pubkey: Pubkey) -> GeyserMessage {
+        Arc::new(Message::Account(MessageAccount::from_snapshot(
+            pubkey,
+            &AccountSharedData::new(42, 3, &Pubkey::default()),
+            7,
+        )))
+    }
+
+    #[tokio::test]
+    async fn test_sink_publishes_account_updates() {
+        let (sink, published) = sink(ConfigSinkFormat::Protobuf);
+        let (tx, rx) = broadcast::channel(16);
+        let pubkey = Pubkey::new_unique();
+        tx.send(account_message(pubkey)).unwrap();
+        tx.send(Arc::new(Message::Slot(MessageSlot {
+            slot: 7,
+            parent: Some(6),
+            status: CommitmentLevel::Finalized,
+        })))
+        .unwrap();
+        drop(tx);
+        sink.run(rx).await;
+
+        let published = published.lock().unwrap();
+        assert_eq!(published.len(), 1);
+        let (topic, key, payload) = &published[0];
+        assert_eq!(topic, "accounts");
+        assert_eq!(key, &pubkey.to_bytes().to_vec());
+        let update = SubscribeUpdate::decode(payload.as_slice()).unwrap();
+        assert!(update.update_oneof.is_some());
+    }
+
+    #[test]
+    fn test_sink_encodes_account_updates_as_json() {
+        let (sink, _) = sink(ConfigSinkFormat::Json);
+        let pubkey = Pubkey::new_unique();
+        let (_, _, payload) = sink.encode(&account_message(pubkey)).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(json["pubkey"], pubkey.to_string());
+        assert_eq!(json["lamports"], 42);
+        assert_eq!(json["slot"], 7);
+        assert_eq!(json["data"], "AAAA");
+    }
+}
@@ -1,9 +1,16 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, OnceLock,
+    },
+};
 
 use log::warn;
+use magicblock_core::traits::ProvidesAccountsSnapshot;
 use scc::hash_map::Entry;
 use solana_sdk::{pubkey::Pubkey, signature::Signature};
-use tokio::sync::mpsc;
+use tokio::sync::mpsc::{self, error::TrySendError};
 
 use crate::grpc_messages::{Message, MessageBlockMeta};
 
@@ -16,8 +23,11 @@ pub type SignatureSubscriptionsDb =
     Arc<scc::HashMap<Signature, UpdateSubscribers>>;
 pub type LogsSubscriptionsDb =
     Arc<scc::HashMap<LogsSubscribeKey, UpdateSubscribers>>;
-pub type SlotSubscriptionsDb =
-    Arc<scc::HashMap<u64, mpsc::Sender<GeyserMessage>>>;
+pub type SlotSubscriptionsDb = Arc<scc::HashMap<u64, UpdateSender>>;
+/// Set once the bank is created, which happens after the geyser service
+/// started
+pub type AccountsSnapshotProvider =
+    Arc<OnceLock<Arc<dyn ProvidesAccountsSnapshot>>>;
 
 #[derive(Clone, Default)]
 pub struct SubscriptionsDb {
@@ -67,7 +77,7 @@ impl SubscriptionsDb {
     pub async fn subscribe_to_account(
         &self,
         pubkey: Pubkey,
-        tx: mpsc::Sender<GeyserMessage>,
+        tx: UpdateSender,
         id: u64,
     ) {
         add_subscriber!(self, accounts, id, pubkey, tx);
@@ -88,7 +98,7 @@ impl SubscriptionsDb {
     pub async fn subscribe_to_program(
         &self,
         pubkey: Pubkey,
-        tx: mpsc::Sender<GeyserMessage>,
+        tx: UpdateSender,
         id: u64,
     ) {
         add_subscriber!(self, programs, id, pubkey, tx);
@@ -109,7 +119,7 @@ impl SubscriptionsDb {
     pub async fn subscribe_to_signature(
         &self,
         signature: Signature,
-        tx: mpsc::Sender<GeyserMessage>,
+        tx: UpdateSender,
         id: u64,
     ) {
         add_subscriber!(self, signatures, id, signature, tx);
@@ -134,7 +144,7 @@ impl SubscriptionsDb {
     pub async fn subscribe_to_logs(
         &self,
         key: LogsSubscribeKey,
-        tx: mpsc::Sender<GeyserMessage>,
+        tx: UpdateSender,
         id: u64,
     ) {
         add_subscriber!(self, logs, id, key, tx);
@@ -169,11 +179,7 @@ impl SubscriptionsDb {
             .await;
     }
 
-    pub async fn subscribe_to_slot(
-        &self,
-        tx: mpsc::Sender<GeyserMessage>,
-        id: u64,
-    ) {
+    pub async fn subscribe_to_slot(&self, tx: UpdateSender, id: u64) {
         let _ = self.slot.insert_async(id, tx).await;
     }
 
@@ -200,6 +206,65 @@ pub fn geyser_message_channel() -> (GeyserMessageSender, GeyserMessageReceiver)
     flume::unbounded()
 }
 
+/// Creates the channel updates are sent through to a single subscriber
+pub fn update_channel(capacity: usize) -> (UpdateSender, UpdateReceiver) {
+    let (tx, rx) = mpsc::channel(capacity);
+    let dropped = Arc::<AtomicU64>::default();
+    (
+        UpdateSender {
+            tx,
+            dropped: dropped.clone(),
+        },
+        UpdateReceiver { rx, dropped },
+    )
+}
+
+/// Sends updates to a subscriber, counting the ones dropped since the
+/// subscriber didn't keep up with them
+#[derive(Clone)]
+pub struct UpdateSender {
+    tx: mpsc::Sender<GeyserMessage>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl UpdateSender {
+    pub fn try_send(
+        &self,
+        msg: GeyserMessage,
+    ) -> Result<(), TrySendError<GeyserMessage>> {
+        let result = self.tx.try_send(msg);
+        if let Err(TrySendError::Full(_)) = result {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
+pub struct UpdateReceiver {
+    rx: mpsc::Receiver<GeyserMessage>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl UpdateReceiver {
+    pub async fn recv(&mut self) -> Option<GeyserMessage> {
+        self.rx.recv().await
+    }
+
+    /// The number of updates that were sent, but not received yet
+    pub fn len(&self) -> usize {
+        self.rx.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rx.is_empty()
+    }
+
+    /// Returns the number of updates dropped since the last call
+    pub fn take_dropped(&self) -> u64 {
+        self.dropped.swap(0, Ordering::Relaxed)
+    }
+}
+
 #[derive(Hash, PartialEq, Eq, Clone, Copy, Debug)]
 pub enum LogsSubscribeKey {
     All,
@@ -208,10 +273,7 @@ pub enum LogsSubscribeKey {
 
 /// Sender handles to subscribers for a given update
 pub enum UpdateSubscribers {
-    Single {
-        id: u64,
-        tx: mpsc::Sender<GeyserMessage>,
-    },
+    Single { id: u64, tx: UpdateSender },
     Multiple(HashMap<u64, Self>),
 }
 
@@ -259,3 +321,33 @@ impl UpdateSubscribers {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use geyser_grpc_proto::prelude::CommitmentLevel;
+
+    use super::*;
+    use crate::grpc_messages::MessageSlot;
+
+    fn slot_message(slot: u64) -> GeyserMessage {
+        Arc::new(Message::Slot(MessageSlot {
+            slot,
+            parent: slot.checked_sub(1),
+            status: CommitmentLevel::Processed,
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_update_channel_counts_dropped() {
+        let (tx, mut rx) = update_channel(2);
+        assert!(tx.try_send(slot_message(1)).is_ok());
+        assert!(tx.try_send(slot_message(2)).is_ok());
+        assert!(tx.try_send(slot_message(3)).is_err());
+        assert_eq!(rx.len(), 2);
+        assert_eq!(rx.take_dropped(), 1);
+        assert_eq!(rx.take_dropped(), 0);
+
+        assert_eq!(rx.recv().await.unwrap().get_slot(), 1);
+        assert_eq!(rx.len(), 1);
+    }
+}
@@ -0,0 +1,43 @@
+//! Encoding of blocks shared by ledger archives and the ledger tailer.
+//!
+//! A record is the little endian `u64` slot followed by the length delimited
+//! protobuf encoded [generated::ConfirmedBlock] of that slot. Records are
+//! self delimiting, thus a stream of them can simply be concatenated.
+
+use prost::Message;
+use solana_sdk::clock::Slot;
+use solana_storage_proto::convert::generated;
+use solana_transaction_status::{ConfirmedBlock, VersionedConfirmedBlock};
+
+use crate::errors::LedgerResult;
+
+/// Appends the record of the `block` at `slot` to `buf`
+pub fn encode_block_record(
+    slot: Slot,
+    block: VersionedConfirmedBlock,
+    buf: &mut Vec<u8>,
+) -> LedgerResult<()> {
+    buf.extend_from_slice(&slot.to_le_bytes());
+    generated::ConfirmedBlock::from(block).encode_length_delimited(buf)?;
+    Ok(())
+}
+
+/// Decodes a sequence of concatenated block records
+pub fn decode_block_records(
+    records: &[u8],
+) -> LedgerResult<Vec<(Slot, ConfirmedBlock)>> {
+    let mut buf = records;
+    let mut blocks = Vec::new();
+    while !buf.is_empty() {
+        let (slot, rest) =
+            buf.split_at(std::mem::size_of::<Slot>().min(buf.len()));
+        let slot = Slot::from_le_bytes(slot.try_into()?);
+        buf = rest;
+
+        let block =
+            generated::ConfirmedBlock::decode_length_delimited(&mut buf)?;
+        let block = ConfirmedBlock::try_from(block)?;
+        blocks.push((slot, block));
+    }
+    Ok(blocks)
+}
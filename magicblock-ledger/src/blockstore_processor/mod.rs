@@ -4,11 +4,15 @@ use log::{Level::Trace, *};
 use magicblock_bank::bank::Bank;
 use num_format::{Locale, ToFormattedString};
 use solana_sdk::{
+    account::AccountSharedData,
     clock::{Slot, UnixTimestamp},
     hash::Hash,
     message::SanitizedMessage,
+    pubkey::Pubkey,
+    signature::Signature,
     transaction::{
-        SanitizedTransaction, TransactionVerificationMode, VersionedTransaction,
+        Result as TransactionResult, SanitizedTransaction,
+        TransactionVerificationMode, VersionedTransaction,
     },
 };
 use solana_svm::{
@@ -38,6 +42,8 @@ struct IterBlocksParams<'a> {
     ledger: &'a Ledger,
     full_process_starting_slot: Slot,
     blockhashes_only_starting_slot: Slot,
+    /// First slot that is not processed anymore
+    end_slot: Slot,
 }
 
 fn iter_blocks(
@@ -48,6 +54,7 @@ fn iter_blocks(
         ledger,
         full_process_starting_slot,
         blockhashes_only_starting_slot,
+        end_slot,
     } = params;
     let mut slot: u64 = blockhashes_only_starting_slot;
 
@@ -60,7 +67,7 @@ fn iter_blocks(
         "N/A".to_string()
     };
     const PROGRESS_REPORT_INTERVAL: u64 = 100;
-    loop {
+    while slot < end_slot {
         let Ok(Some(block)) = ledger.get_block(slot) else {
             break;
         };
@@ -74,63 +81,75 @@ fn iter_blocks(
             );
         }
 
-        let VersionedConfirmedBlock {
-            blockhash,
-            previous_blockhash,
-            transactions,
-            block_time,
-            block_height,
-            ..
-        } = block;
-        if let Some(block_height) = block_height {
-            if slot != block_height {
-                return Err(LedgerError::BlockStoreProcessor(format!(
-                    "FATAL: block_height/slot mismatch: {} != {}",
-                    slot, block_height
-                )));
-            }
-        }
-
         // We skip all transactions until we reach the slot at which we should
         // start processing them. Up to that slot we only process blockhashes.
-        let successfull_txs = if slot >= full_process_starting_slot {
-            // We only re-run transactions that succeeded since errored transactions
-            // don't update any state
-            transactions
-                .into_iter()
-                .filter(|tx| tx.meta.status.is_ok())
-                .map(|tx| tx.transaction)
-                .collect::<Vec<_>>()
-        } else {
-            vec![]
-        };
-        let previous_blockhash =
-            Hash::from_str(&previous_blockhash).map_err(|err| {
-                LedgerError::BlockStoreProcessor(format!(
-                    "Failed to parse previous_blockhash: {:?}",
-                    err
-                ))
-            })?;
-        let blockhash = Hash::from_str(&blockhash).map_err(|err| {
-            LedgerError::BlockStoreProcessor(format!(
-                "Failed to parse blockhash: {:?}",
-                err
-            ))
-        })?;
-
-        prepared_block_handler(PreparedBlock {
+        prepared_block_handler(prepare_block(
             slot,
-            previous_blockhash,
-            blockhash,
-            block_time,
-            transactions: successfull_txs,
-        })?;
+            block,
+            slot >= full_process_starting_slot,
+        )?)?;
 
         slot += 1;
     }
     Ok(slot.max(1))
 }
 
+fn prepare_block(
+    slot: Slot,
+    block: VersionedConfirmedBlock,
+    replay_transactions: bool,
+) -> LedgerResult<PreparedBlock> {
+    let VersionedConfirmedBlock {
+        blockhash,
+        previous_blockhash,
+        transactions,
+        block_time,
+        block_height,
+        ..
+    } = block;
+    if let Some(block_height) = block_height {
+        if slot != block_height {
+            return Err(LedgerError::BlockStoreProcessor(format!(
+                "FATAL: block_height/slot mismatch: {} != {}",
+                slot, block_height
+            )));
+        }
+    }
+
+    let successfull_txs = if replay_transactions {
+        // We only re-run transactions that succeeded since errored transactions
+        // don't update any state
+        transactions
+            .into_iter()
+            .filter(|tx| tx.meta.status.is_ok())
+            .map(|tx| tx.transaction)
+            .collect::<Vec<_>>()
+    } else {
+        vec![]
+    };
+    let previous_blockhash =
+        Hash::from_str(&previous_blockhash).map_err(|err| {
+            LedgerError::BlockStoreProcessor(format!(
+                "Failed to parse previous_blockhash: {:?}",
+                err
+            ))
+        })?;
+    let blockhash = Hash::from_str(&blockhash).map_err(|err| {
+        LedgerError::BlockStoreProcessor(format!(
+            "Failed to parse blockhash: {:?}",
+            err
+        ))
+    })?;
+
+    Ok(PreparedBlock {
+        slot,
+        previous_blockhash,
+        blockhash,
+        block_time,
+        transactions: successfull_txs,
+    })
+}
+
 /// Processes the provided ledger updating the bank and returns the slot
 /// at which the validator should continue processing (last processed slot + 1).
 pub fn process_ledger(ledger: &Ledger, bank: &Arc<Bank>) -> LedgerResult<u64> {
@@ -138,16 +157,25 @@ pub fn process_ledger(ledger: &Ledger, bank: &Arc<Bank>) -> LedgerResult<u64> {
     // bank.adb was rolled back to max_slot (via ensure_at_most) in magicblock-bank/src/bank.rs
     // `Bank::new` method, so the returned slot here is guaranteed to be equal or less than the
     // slot from `ledger.get_max_blockhash`
-    let full_process_starting_slot = bank.accounts_db.slot();
+    let adb_slot = bank.accounts_db.slot();
 
     // Since transactions may refer to blockhashes that were present when they
     // ran initially we ensure that they are present during replay as well
-    let blockhashes_only_starting_slot =
-        if full_process_starting_slot > bank.max_age {
-            full_process_starting_slot - bank.max_age
-        } else {
-            0
-        };
+    let blockhashes_only_starting_slot = adb_slot.saturating_sub(bank.max_age);
+
+    // If the snapshot already includes all transactions of the ledger we only
+    // need to restore the blockhashes
+    let full_process_starting_slot = if can_skip_transaction_replay(
+        ledger, bank, adb_slot,
+    )? {
+        info!(
+            "Accounts snapshot at slot {} matches the ledger, skipping transaction replay",
+            adb_slot
+        );
+        Slot::MAX
+    } else {
+        adb_slot
+    };
     debug!(
         "Loaded accounts into bank from storage replaying blockhashes from {} and transactions from {}",
         blockhashes_only_starting_slot, full_process_starting_slot
@@ -157,94 +185,258 @@ pub fn process_ledger(ledger: &Ledger, bank: &Arc<Bank>) -> LedgerResult<u64> {
             ledger,
             full_process_starting_slot,
             blockhashes_only_starting_slot,
+            end_slot: Slot::MAX,
         },
-        |prepared_block| {
-            let mut block_txs = vec![];
-            let Some(timestamp) = prepared_block.block_time else {
+        |prepared_block| replay_block(bank, prepared_block),
+    )
+}
+
+/// Outcome of re-executing a transaction recorded in the ledger, see
+/// [replay_transaction]
+#[derive(Debug)]
+pub struct ReplayedTransaction {
+    pub slot: Slot,
+    pub transaction: SanitizedTransaction,
+    /// State of the accounts loaded by the transaction right before it was
+    /// executed, `None` for accounts that did not exist at that point
+    pub pre_accounts: Vec<(Pubkey, Option<AccountSharedData>)>,
+    /// Status recorded in the ledger when the transaction originally executed
+    pub recorded_status: TransactionResult<()>,
+    /// Compute units recorded in the ledger when the transaction originally
+    /// executed
+    pub recorded_compute_units: Option<u64>,
+    /// Result of re-executing the transaction, including all logs, inner
+    /// instructions and consumed compute units
+    pub result: TransactionCommitResult,
+}
+
+/// Reconstructs the state right before the transaction with the given
+/// signature executed and executes it again, recording everything.
+///
+/// The bank is expected to have been created with the accounts db rolled
+/// back to a snapshot at or before the slot of the transaction. Starting at
+/// that snapshot all blocks preceding the transaction's block are replayed,
+/// followed by the transactions in its block that executed before it.
+///
+/// NOTE: this modifies the accounts db of the bank, so it should only be
+/// used with a copy of the validator's accounts db.
+pub fn replay_transaction(
+    ledger: &Ledger,
+    bank: &Arc<Bank>,
+    signature: &Signature,
+) -> LedgerResult<ReplayedTransaction> {
+    let (slot, status_meta) = ledger
+        .get_transaction_status(*signature, Slot::MAX)?
+        .ok_or(LedgerError::TransactionStatusMetaNotFound)?;
+
+    let adb_slot = bank.accounts_db.slot();
+    if adb_slot > slot {
+        return Err(LedgerError::BlockStoreProcessor(format!(
+            "Accounts db at slot {} is newer than the transaction's slot {}",
+            adb_slot, slot
+        )));
+    }
+    debug!(
+        "Replaying transaction {} at slot {} starting at slot {}",
+        signature, slot, adb_slot
+    );
+    let next_slot = iter_blocks(
+        IterBlocksParams {
+            ledger,
+            full_process_starting_slot: adb_slot,
+            blockhashes_only_starting_slot: adb_slot
+                .saturating_sub(bank.max_age),
+            end_slot: slot,
+        },
+        |prepared_block| replay_block(bank, prepared_block),
+    )?;
+    if next_slot < slot {
+        return Err(LedgerError::BlockStoreProcessor(format!(
+            "Ledger is missing block {} preceding the transaction's slot {}",
+            next_slot, slot
+        )));
+    }
+
+    let mut block = ledger.get_block(slot)?.ok_or_else(|| {
+        LedgerError::BlockStoreProcessor(format!(
+            "Ledger is missing the transaction's block {}",
+            slot
+        ))
+    })?;
+    let index = block
+        .transactions
+        .iter()
+        .position(|tx| tx.transaction.signatures.first() == Some(signature))
+        .ok_or(LedgerError::TransactionNotFound)?;
+    // Transactions are stored ordered by most recent to latest, thus the ones
+    // which executed before the transaction follow it
+    let preceding_txs = block.transactions.split_off(index + 1);
+    let tx = block.transactions.swap_remove(index).transaction;
+    block.transactions = preceding_txs;
+    replay_block(bank, prepare_block(slot, block, true)?)?;
+
+    let tx = bank
+        .verify_transaction(tx, TransactionVerificationMode::HashOnly)
+        .map_err(|err| {
+            LedgerError::BlockStoreProcessor(format!(
+                "Error processing transaction: {:?}",
+                err
+            ))
+        })?;
+    log_sanitized_transaction(&tx);
+    let pre_accounts = tx
+        .message()
+        .account_keys()
+        .iter()
+        .map(|pubkey| (*pubkey, bank.get_account(pubkey)))
+        .collect();
+
+    let mut timings = ExecuteTimings::default();
+    let txs = [tx];
+    let batch = bank.prepare_sanitized_batch(&txs);
+    let (results, _) = bank.load_execute_and_commit_transactions(
+        &batch,
+        false,
+        ExecutionRecordingConfig::new_single_setting(true),
+        &mut timings,
+        None,
+    );
+    drop(batch);
+    log_execution_results(&results);
+    let result = results
+        .into_iter()
+        .next()
+        .expect("one result per executed transaction");
+    let [tx] = txs;
+
+    Ok(ReplayedTransaction {
+        slot,
+        transaction: tx,
+        pre_accounts,
+        recorded_status: status_meta.status,
+        recorded_compute_units: status_meta.compute_units_consumed,
+        result,
+    })
+}
+
+/// Replays the block, executing its transactions in the order in which they
+/// were originally executed
+fn replay_block(
+    bank: &Bank,
+    prepared_block: PreparedBlock,
+) -> LedgerResult<()> {
+    let mut block_txs = vec![];
+    let Some(timestamp) = prepared_block.block_time else {
+        return Err(LedgerError::BlockStoreProcessor(format!(
+            "Block has no timestamp, {:?}",
+            prepared_block
+        )));
+    };
+    blockhash_log::log_blockhash(
+        prepared_block.slot,
+        &prepared_block.blockhash,
+    );
+    bank.replay_slot(
+        prepared_block.slot,
+        &prepared_block.previous_blockhash,
+        &prepared_block.blockhash,
+        timestamp as u64,
+    );
+
+    // Transactions are stored in the ledger ordered by most recent to latest
+    // such to replay them in the order they executed we need to reverse them
+    for tx in prepared_block.transactions.into_iter().rev() {
+        match bank.verify_transaction(tx, TransactionVerificationMode::HashOnly)
+        {
+            Ok(tx) => block_txs.push(tx),
+            Err(err) => {
                 return Err(LedgerError::BlockStoreProcessor(format!(
-                    "Block has no timestamp, {:?}",
-                    prepared_block
+                    "Error processing transaction: {:?}",
+                    err
                 )));
-            };
-            blockhash_log::log_blockhash(
-                prepared_block.slot,
-                &prepared_block.blockhash,
-            );
-            bank.replay_slot(
-                prepared_block.slot,
-                &prepared_block.previous_blockhash,
-                &prepared_block.blockhash,
-                timestamp as u64,
-            );
-
-            // Transactions are stored in the ledger ordered by most recent to latest
-            // such to replay them in the order they executed we need to reverse them
-            for tx in prepared_block.transactions.into_iter().rev() {
-                match bank.verify_transaction(
-                    tx,
-                    TransactionVerificationMode::HashOnly,
-                ) {
-                    Ok(tx) => block_txs.push(tx),
-                    Err(err) => {
-                        return Err(LedgerError::BlockStoreProcessor(format!(
-                            "Error processing transaction: {:?}",
-                            err
-                        )));
-                    }
-                };
             }
-            if !block_txs.is_empty() {
-                // NOTE: ideally we would run all transactions in a single batch, but the
-                // flawed account lock mechanism prevents this currently.
-                // Until we revamp this transaction execution we execute each transaction
-                // in its own batch.
-                for tx in block_txs {
-                    log_sanitized_transaction(&tx);
+        };
+    }
+    if !block_txs.is_empty() {
+        // NOTE: ideally we would run all transactions in a single batch, but the
+        // flawed account lock mechanism prevents this currently.
+        // Until we revamp this transaction execution we execute each transaction
+        // in its own batch.
+        for tx in block_txs {
+            log_sanitized_transaction(&tx);
 
-                    let mut timings = ExecuteTimings::default();
-                    let signature = *tx.signature();
-                    let batch = [tx];
-                    let batch = bank.prepare_sanitized_batch(&batch);
-                    let (results, _) = bank
-                        .load_execute_and_commit_transactions(
-                            &batch,
-                            false,
-                            ExecutionRecordingConfig::new_single_setting(true),
-                            &mut timings,
-                            None,
-                        );
+            let mut timings = ExecuteTimings::default();
+            let signature = *tx.signature();
+            let batch = [tx];
+            let batch = bank.prepare_sanitized_batch(&batch);
+            let (results, _) = bank.load_execute_and_commit_transactions(
+                &batch,
+                false,
+                ExecutionRecordingConfig::new_single_setting(true),
+                &mut timings,
+                None,
+            );
 
-                    log_execution_results(&results);
-                    for result in results {
-                        if !result.was_executed_successfully() {
-                            // If we're on trace log level then we already logged this above
-                            if !log_enabled!(Trace) {
-                                debug!(
-                                    "Transactions: {:#?}",
-                                    batch.sanitized_transactions()
-                                );
-                                debug!("Result: {:#?}", result);
-                            }
-                            let err = match &result {
-                                Ok(tx) => match &tx.status {
-                                    Ok(_) => None,
-                                    Err(err) => Some(err),
-                                },
-                                Err(err) => Some(err),
-                            };
-                            return Err(LedgerError::BlockStoreProcessor(
-                                format!(
-                                    "Transaction '{}', {:?} could not be executed: {:?}",
-                                    signature, result, err
-                                ),
-                            ));
-                        }
+            log_execution_results(&results);
+            for result in results {
+                if !result.was_executed_successfully() {
+                    // If we're on trace log level then we already logged this above
+                    if !log_enabled!(Trace) {
+                        debug!(
+                            "Transactions: {:#?}",
+                            batch.sanitized_transactions()
+                        );
+                        debug!("Result: {:#?}", result);
                     }
+                    let err = match &result {
+                        Ok(tx) => match &tx.status {
+                            Ok(_) => None,
+                            Err(err) => Some(err),
+                        },
+                        Err(err) => Some(err),
+                    };
+                    return Err(LedgerError::BlockStoreProcessor(format!(
+                        "Transaction '{}', {:?} could not be executed: {:?}",
+                        signature, result, err
+                    )));
                 }
             }
-            Ok(())
-        },
-    )
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` if the accounts db state is the snapshot taken at
+/// `adb_slot`, no transactions were recorded in the ledger at or after that
+/// slot and the accounts hash stored in the ledger for that slot matches the
+/// state of the accounts db.
+/// Since the snapshot at a slot includes all transactions of previous slots
+/// there is nothing left to replay in that case.
+fn can_skip_transaction_replay(
+    ledger: &Ledger,
+    bank: &Bank,
+    adb_slot: Slot,
+) -> LedgerResult<bool> {
+    // Only captured if the accounts db was at its latest snapshot on startup
+    let Some(checksum) = bank.accounts_db.startup_checksum() else {
+        return Ok(false);
+    };
+    if ledger.has_transactions_since(adb_slot)? {
+        return Ok(false);
+    }
+    match ledger.read_accounts_hash(adb_slot)? {
+        Some(hash) if hash == Hash::new_from_array(checksum) => Ok(true),
+        Some(hash) => {
+            warn!(
+                "Accounts hash mismatch at slot {}, ledger: {}, accounts db: {}. Replaying transactions.",
+                adb_slot,
+                hash,
+                Hash::new_from_array(checksum)
+            );
+            Ok(false)
+        }
+        None => Ok(false),
+    }
 }
 
 fn log_sanitized_transaction(tx: &SanitizedTransaction) {
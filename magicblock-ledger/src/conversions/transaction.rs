@@ -101,6 +101,9 @@ fn versioned_tx_from_generated(
 fn versioned_message_from_generated(
     msg: generated::Message,
 ) -> VersionedMessage {
+    if !msg.versioned {
+        return VersionedMessage::Legacy(message_from_generated(msg));
+    }
     let account_keys = pubkeys_from_slices(msg.account_keys);
     let recent_blockhash =
         <[u8; HASH_BYTES]>::try_from(msg.recent_blockhash.as_slice())
@@ -184,15 +187,22 @@ fn signatures_from_slices(signatures: Vec<Vec<u8>>) -> Vec<Signature> {
 fn tx_meta_from_generated(
     meta: generated::TransactionStatusMeta,
 ) -> solana_transaction_status::TransactionStatusMeta {
-    let inner_instructions =
-        inner_instructions_from_generated(meta.inner_instructions);
+    // The *_none flags distinguish fields that weren't recorded from ones
+    // that were recorded but are empty
+    let inner_instructions = (!meta.inner_instructions_none)
+        .then(|| inner_instructions_from_generated(meta.inner_instructions));
+    let log_messages = (!meta.log_messages_none).then_some(meta.log_messages);
     let rewards = rewards_from_generated(meta.rewards);
     let pre_token_balances =
         token_balances_from_generated(meta.pre_token_balances);
     let post_token_balances =
         token_balances_from_generated(meta.post_token_balances);
     let status = status_from_generated(meta.err);
-    let return_data = return_data_from_generated(meta.return_data);
+    let return_data = if meta.return_data_none {
+        None
+    } else {
+        return_data_from_generated(meta.return_data)
+    };
     TransactionStatusMeta {
         status,
         compute_units_consumed: meta.compute_units_consumed,
@@ -203,8 +213,8 @@ fn tx_meta_from_generated(
         fee: meta.fee,
         pre_balances: meta.pre_balances,
         post_balances: meta.post_balances,
-        inner_instructions: Some(inner_instructions),
-        log_messages: Some(meta.log_messages),
+        inner_instructions,
+        log_messages,
         pre_token_balances: Some(pre_token_balances),
         post_token_balances: Some(post_token_balances),
         return_data,
@@ -243,16 +253,13 @@ fn inner_instructions_from_generated(
             instructions: inner_instructions
                 .instructions
                 .into_iter()
-                .map(|ix| {
-                    let stack_height = ix.stack_height();
-                    InnerInstruction {
-                        instruction: CompiledInstruction {
-                            program_id_index: ix.program_id_index as u8,
-                            accounts: ix.accounts,
-                            data: ix.data,
-                        },
-                        stack_height: Some(stack_height),
-                    }
+                .map(|ix| InnerInstruction {
+                    instruction: CompiledInstruction {
+                        program_id_index: ix.program_id_index as u8,
+                        accounts: ix.accounts,
+                        data: ix.data,
+                    },
+                    stack_height: ix.stack_height,
                 })
                 .collect(),
         })
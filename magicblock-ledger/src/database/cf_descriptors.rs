@@ -36,6 +36,10 @@ pub fn cf_descriptors(
         new_cf_descriptor::<TransactionMemos>(options),
         new_cf_descriptor::<PerfSamples>(options),
         new_cf_descriptor::<AccountModDatas>(options),
+        new_cf_descriptor::<AccountsHash>(options),
+        new_cf_descriptor::<StatusCache>(options),
+        new_cf_descriptor::<CommitRecords>(options),
+        new_cf_descriptor::<MutationAuditEntries>(options),
     ];
 
     // If the access type is Secondary, we don't need to open all of the
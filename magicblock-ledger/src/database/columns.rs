@@ -23,6 +23,14 @@ const TRANSACTION_MEMOS_CF: &str = "transaction_memos";
 const PERF_SAMPLES_CF: &str = "perf_samples";
 /// Column family for AccountModDatas
 const ACCOUNT_MOD_DATAS_CF: &str = "account_mod_datas";
+/// Column family for AccountsHash
+const ACCOUNTS_HASH_CF: &str = "accounts_hash";
+/// Column family for StatusCache
+const STATUS_CACHE_CF: &str = "status_cache";
+/// Column family for CommitRecords
+const COMMIT_RECORDS_CF: &str = "commit_records";
+/// Column family for MutationAuditEntries
+const MUTATION_AUDIT_ENTRIES_CF: &str = "mutation_audit_entries";
 
 #[derive(Debug)]
 /// The transaction status column
@@ -95,6 +103,39 @@ pub struct PerfSamples;
 /// * value type: [`crate::database::meta::AccountModData`]
 pub struct AccountModDatas;
 
+/// The accounts hash column, a consistency marker between the ledger and
+/// AccountsDb. It holds the checksum of the AccountsDb state at the start
+/// of a slot and is only written for slots at which a snapshot was taken.
+///
+/// * index type: `u64` (see [`SlotColumn`])
+/// * value type: [`solana_sdk::hash::Hash`]
+pub struct AccountsHash;
+
+/// The status cache column, holds the signatures and statuses of the
+/// transactions that executed in a slot as tracked by the bank status cache.
+/// It is written on shutdown in order to restore the status cache on startup.
+///
+/// * index type: `u64` (see [`SlotColumn`])
+/// * value type: [`crate::database::meta::SlotTransactionStatuses`]
+pub struct StatusCache;
+
+/// The commit records column, holds the accounts, base layer signatures and
+/// outcome of each scheduled commit.
+///
+/// * index type: `(`[`Slot`]`, u64)`
+/// *                 slot,  commit id
+/// * value type: [`magicblock_core::commit_record::CommitRecord`]
+pub struct CommitRecords;
+
+#[derive(Debug)]
+/// The mutation audit column, holds an entry for each modification of
+/// accounts by the validator authority outside of regular execution.
+///
+/// * index type: `(`[`Slot`]`, u64)`
+/// *                 slot,  sequence number
+/// * value type: [`magicblock_core::mutation_audit::MutationAuditEntry`]
+pub struct MutationAuditEntries;
+
 // When adding a new column ...
 // - Add struct below and implement `Column` and `ColumnName` traits
 // - Add descriptor in Rocks::cf_descriptors() and name in Rocks::columns()
@@ -113,6 +154,10 @@ pub fn columns() -> Vec<&'static str> {
         TransactionMemos::NAME,
         PerfSamples::NAME,
         AccountModDatas::NAME,
+        AccountsHash::NAME,
+        StatusCache::NAME,
+        CommitRecords::NAME,
+        MutationAuditEntries::NAME,
     ]
 }
 
@@ -482,6 +527,93 @@ impl TypedColumn for Blockhash {
     type Type = solana_sdk::hash::Hash;
 }
 
+// -----------------
+// AccountsHash
+// -----------------
+impl SlotColumn for AccountsHash {}
+impl ColumnName for AccountsHash {
+    const NAME: &'static str = ACCOUNTS_HASH_CF;
+}
+impl TypedColumn for AccountsHash {
+    type Type = solana_sdk::hash::Hash;
+}
+
+// -----------------
+// StatusCache
+// -----------------
+impl SlotColumn for StatusCache {}
+impl ColumnName for StatusCache {
+    const NAME: &'static str = STATUS_CACHE_CF;
+}
+impl TypedColumn for StatusCache {
+    type Type = meta::SlotTransactionStatuses;
+}
+
+// -----------------
+// CommitRecords
+// -----------------
+const COMMIT_RECORDS_INDEX_LEN: usize = 8 + 8;
+impl Column for CommitRecords {
+    type Index = (Slot, u64);
+
+    fn key((slot, commit_id): Self::Index) -> Vec<u8> {
+        let mut key = vec![0; COMMIT_RECORDS_INDEX_LEN];
+        BigEndian::write_u64(&mut key[0..8], slot);
+        BigEndian::write_u64(&mut key[8..16], commit_id);
+        key
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        let slot = BigEndian::read_u64(&key[0..8]);
+        let commit_id = BigEndian::read_u64(&key[8..16]);
+        (slot, commit_id)
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: u64) -> Self::Index {
+        (slot, 0)
+    }
+}
+impl ColumnName for CommitRecords {
+    const NAME: &'static str = COMMIT_RECORDS_CF;
+}
+impl TypedColumn for CommitRecords {
+    type Type = magicblock_core::commit_record::CommitRecord;
+}
+
+// -----------------
+// MutationAuditEntries
+// -----------------
+impl Column for MutationAuditEntries {
+    // Same key layout as CommitRecords
+    type Index = <CommitRecords as Column>::Index;
+
+    fn key(index: Self::Index) -> Vec<u8> {
+        <CommitRecords as Column>::key(index)
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        <CommitRecords as Column>::index(key)
+    }
+
+    fn slot(index: Self::Index) -> Slot {
+        index.0
+    }
+
+    fn as_index(slot: u64) -> Self::Index {
+        (slot, 0)
+    }
+}
+impl ColumnName for MutationAuditEntries {
+    const NAME: &'static str = MUTATION_AUDIT_ENTRIES_CF;
+}
+impl TypedColumn for MutationAuditEntries {
+    type Type = magicblock_core::mutation_audit::MutationAuditEntry;
+}
+
 // -----------------
 // Transaction
 // -----------------
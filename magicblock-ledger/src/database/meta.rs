@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use solana_sdk::{signature::Signature, transaction::Result};
 
 #[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 pub struct AddressSignatureMeta {
@@ -23,3 +24,9 @@ impl From<Vec<u8>> for AccountModData {
         Self { data }
     }
 }
+
+/// Statuses of the transactions that executed in a slot
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SlotTransactionStatuses {
+    pub statuses: Vec<(Signature, Result<()>)>,
+}
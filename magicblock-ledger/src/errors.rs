@@ -35,6 +35,9 @@ pub enum LedgerError {
     SlotCleanedUp,
     #[error("try from slice error: {0}")]
     TryFromSliceError(#[from] std::array::TryFromSliceError),
+    #[cfg(feature = "kafka")]
+    #[error("Kafka error: {0}")]
+    Kafka(#[from] rdkafka::error::KafkaError),
     #[error("BlockstoreProcessorError: {0}")]
     BlockStoreProcessor(String),
 }
@@ -0,0 +1,147 @@
+use std::{
+    cmp::min,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use log::*;
+use solana_sdk::clock::Slot;
+use solana_transaction_status::ConfirmedBlock;
+
+use crate::{
+    block_record::{decode_block_records, encode_block_record},
+    errors::LedgerResult,
+    Ledger,
+};
+
+/// Max number of slots stored in a single archive, bounds memory used while
+/// encoding and keeps single archives reasonably small
+const SLOTS_PER_ARCHIVE: u64 = 1_000;
+const ARCHIVE_EXTENSION: &str = "pb.zst";
+
+/// Destination for ledger archives.
+/// The [FileArchiveSink] writes into a local directory, other stores (i.e.
+/// object stores) can be supported by implementing this trait.
+pub trait ArchiveSink: Send + Sync {
+    /// Persists the archive with the provided `name`. Implementations must
+    /// only return once the archive has been durably stored, since the
+    /// archived slots are removed from the ledger right after.
+    fn store(&self, name: &str, archive: &[u8]) -> LedgerResult<()>;
+}
+
+/// Stores ledger archives as files inside a directory
+pub struct FileArchiveSink {
+    dir: PathBuf,
+}
+
+impl FileArchiveSink {
+    pub fn new(dir: impl Into<PathBuf>) -> LedgerResult<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl ArchiveSink for FileArchiveSink {
+    fn store(&self, name: &str, archive: &[u8]) -> LedgerResult<()> {
+        // Write to a temporary file first so that a crash never leaves
+        // a partially written archive behind
+        let path = self.dir.join(name);
+        let tmp_path = self.dir.join(format!("{name}.tmp"));
+        fs::write(&tmp_path, archive)?;
+        fs::File::open(&tmp_path)?.sync_all()?;
+        fs::rename(&tmp_path, &path)?;
+        Ok(())
+    }
+}
+
+/// Exports ranges of slots (blocks, transactions and their statuses) into
+/// compressed archives before they are truncated from the ledger.
+///
+/// Each archive is a zstd compressed sequence of block records, see
+/// [crate::block_record].
+pub struct LedgerArchiver {
+    sink: Arc<dyn ArchiveSink>,
+    compression_level: i32,
+}
+
+impl LedgerArchiver {
+    pub fn new(sink: Arc<dyn ArchiveSink>, compression_level: i32) -> Self {
+        Self {
+            sink,
+            compression_level,
+        }
+    }
+
+    /// Archives slots [from_slot; to_slot] inclusive range, split into
+    /// archives of at most [SLOTS_PER_ARCHIVE] slots
+    pub fn archive_slot_range(
+        &self,
+        ledger: &Ledger,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> LedgerResult<()> {
+        (from_slot..=to_slot)
+            .step_by(SLOTS_PER_ARCHIVE as usize)
+            .try_for_each(|cur_from_slot| {
+                let cur_to_slot =
+                    min(cur_from_slot + SLOTS_PER_ARCHIVE - 1, to_slot);
+                self.archive_chunk(ledger, cur_from_slot, cur_to_slot)
+            })
+    }
+
+    fn archive_chunk(
+        &self,
+        ledger: &Ledger,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> LedgerResult<()> {
+        let mut records = Vec::new();
+        let mut num_blocks = 0;
+        for slot in from_slot..=to_slot {
+            let Some(block) = ledger.get_block(slot)? else {
+                continue;
+            };
+            encode_block_record(slot, block, &mut records)?;
+            num_blocks += 1;
+        }
+        if num_blocks == 0 {
+            return Ok(());
+        }
+
+        let archive = zstd::stream::encode_all(
+            records.as_slice(),
+            self.compression_level,
+        )?;
+        let name = archive_name(from_slot, to_slot);
+        self.sink.store(&name, &archive)?;
+        info!(
+            "LedgerArchiver: archived {} blocks of slot range [{}; {}] into '{}' ({} bytes)",
+            num_blocks,
+            from_slot,
+            to_slot,
+            name,
+            archive.len()
+        );
+        Ok(())
+    }
+}
+
+/// Name of the archive containing slots [from_slot; to_slot], padded so that
+/// archives sort by slot
+pub fn archive_name(from_slot: Slot, to_slot: Slot) -> String {
+    format!("ledger-{from_slot:020}-{to_slot:020}.{ARCHIVE_EXTENSION}")
+}
+
+/// Decodes an archive created by the [LedgerArchiver] into its blocks
+pub fn read_archive(
+    archive: &[u8],
+) -> LedgerResult<Vec<(Slot, ConfirmedBlock)>> {
+    let records = zstd::stream::decode_all(archive)?;
+    decode_block_records(&records)
+}
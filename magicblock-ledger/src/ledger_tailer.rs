@@ -0,0 +1,242 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use log::*;
+use solana_sdk::clock::Slot;
+
+use crate::{block_record::encode_block_record, errors::LedgerResult, Ledger};
+
+pub const DEFAULT_TAIL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Destination of the blocks streamed by the [LedgerTailer].
+/// Each call receives a single block record, see [crate::block_record].
+pub trait TailSink: Send + Sync {
+    /// Delivers the record of the block at `slot`. The tailer retries the
+    /// same block until this succeeds, thus no block is skipped.
+    fn send(&self, slot: Slot, record: &[u8]) -> LedgerResult<()>;
+}
+
+/// Appends block records to a file
+pub struct FileTailSink {
+    file: Mutex<File>,
+}
+
+impl FileTailSink {
+    pub fn new(path: impl AsRef<Path>) -> LedgerResult<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl TailSink for FileTailSink {
+    fn send(&self, _slot: Slot, record: &[u8]) -> LedgerResult<()> {
+        let mut file = self.file.lock().expect("FileTailSink poisoned");
+        file.write_all(record)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+/// Writes block records to a Unix socket, reconnecting if the reader goes
+/// away. The socket has to be created by the reader.
+pub struct UnixSocketTailSink {
+    path: PathBuf,
+    stream: Mutex<Option<UnixStream>>,
+}
+
+impl UnixSocketTailSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            stream: Mutex::default(),
+        }
+    }
+}
+
+impl TailSink for UnixSocketTailSink {
+    fn send(&self, _slot: Slot, record: &[u8]) -> LedgerResult<()> {
+        let mut stream =
+            self.stream.lock().expect("UnixSocketTailSink poisoned");
+        let connected = match stream.as_mut() {
+            Some(connected) => connected,
+            None => stream.insert(UnixStream::connect(&self.path)?),
+        };
+        if let Err(err) = connected.write_all(record) {
+            // A partially written record cannot be completed, the reader
+            // needs to start from a new connection
+            stream.take();
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
+
+/// Produces each block record as a message to a Kafka topic, keyed by the
+/// big endian slot
+#[cfg(feature = "kafka")]
+pub struct KafkaTailSink {
+    producer: rdkafka::producer::BaseProducer,
+    topic: String,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaTailSink {
+    const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+    pub fn new(brokers: &str, topic: String) -> LedgerResult<Self> {
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .create()?;
+        Ok(Self { producer, topic })
+    }
+}
+
+#[cfg(feature = "kafka")]
+impl TailSink for KafkaTailSink {
+    fn send(&self, slot: Slot, record: &[u8]) -> LedgerResult<()> {
+        use rdkafka::producer::{BaseRecord, Producer};
+
+        let key = slot.to_be_bytes();
+        self.producer
+            .send(BaseRecord::to(&self.topic).key(&key).payload(record))
+            .map_err(|(err, _)| err)?;
+        // Only consider the block delivered once the broker acknowledged it
+        self.producer.flush(Self::FLUSH_TIMEOUT)?;
+        Ok(())
+    }
+}
+
+/// Streams blocks to a [TailSink] as soon as they are completed in the
+/// ledger, starting with the first block written after it was started.
+pub struct LedgerTailer {
+    ledger: Arc<Ledger>,
+    sink: Arc<dyn TailSink>,
+    poll_interval: Duration,
+    thread_hdl: Option<JoinHandle<()>>,
+}
+
+impl LedgerTailer {
+    pub fn new(
+        ledger: Arc<Ledger>,
+        sink: Arc<dyn TailSink>,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            ledger,
+            sink,
+            poll_interval,
+            thread_hdl: None,
+        }
+    }
+
+    pub fn start(&mut self, exit: Arc<AtomicBool>) -> LedgerResult<()> {
+        if self.thread_hdl.is_some() {
+            warn!("LedgerTailer already running, no need to start.");
+            return Ok(());
+        }
+
+        let next_slot = if self.ledger.count_blockhashes()? == 0 {
+            0
+        } else {
+            self.ledger.get_max_blockhash()?.0 + 1
+        };
+        let ledger = self.ledger.clone();
+        let sink = self.sink.clone();
+        let poll_interval = self.poll_interval;
+        let thread_hdl = thread::Builder::new()
+            .name("ledgerTailer".to_string())
+            .spawn(move || {
+                info!("LedgerTailer has started at slot {}", next_slot);
+                Self::run(
+                    &ledger,
+                    sink.as_ref(),
+                    next_slot,
+                    poll_interval,
+                    exit,
+                );
+                info!("LedgerTailer has stopped");
+            })?;
+        self.thread_hdl = Some(thread_hdl);
+        Ok(())
+    }
+
+    pub fn join(&mut self) {
+        if let Some(thread_hdl) = self.thread_hdl.take() {
+            if let Err(err) = thread_hdl.join() {
+                error!("Failed to join LedgerTailer: {:?}", err);
+            }
+        }
+    }
+
+    fn run(
+        ledger: &Ledger,
+        sink: &dyn TailSink,
+        mut next_slot: Slot,
+        poll_interval: Duration,
+        exit: Arc<AtomicBool>,
+    ) {
+        while !exit.load(Ordering::Relaxed) {
+            let lowest_cleanup_slot = ledger.get_lowest_cleanup_slot();
+            if lowest_cleanup_slot > 0 && next_slot <= lowest_cleanup_slot {
+                warn!(
+                    "LedgerTailer fell behind truncation, skipping slots [{}; {}]",
+                    next_slot, lowest_cleanup_slot
+                );
+                next_slot = lowest_cleanup_slot + 1;
+            }
+
+            let block = match ledger.get_block(next_slot) {
+                Ok(Some(block)) => block,
+                Ok(None) => {
+                    // The blockhash is written last, thus if a later block
+                    // exists this slot was never recorded
+                    match ledger.get_max_blockhash() {
+                        Ok((max_slot, _)) if max_slot > next_slot => {
+                            warn!(
+                                "LedgerTailer skipping unrecorded slot {}",
+                                next_slot
+                            );
+                            next_slot += 1;
+                        }
+                        _ => thread::sleep(poll_interval),
+                    }
+                    continue;
+                }
+                Err(err) => {
+                    error!(
+                        "LedgerTailer failed to read block {}: {:?}",
+                        next_slot, err
+                    );
+                    thread::sleep(poll_interval);
+                    continue;
+                }
+            };
+
+            let mut record = Vec::new();
+            let result = encode_block_record(next_slot, block, &mut record)
+                .and_then(|_| sink.send(next_slot, &record));
+            match result {
+                Ok(_) => next_slot += 1,
+                Err(err) => {
+                    error!(
+                        "LedgerTailer failed to send block {}: {:?}",
+                        next_slot, err
+                    );
+                    thread::sleep(poll_interval);
+                }
+            }
+        }
+    }
+}
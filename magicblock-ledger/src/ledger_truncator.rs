@@ -1,8 +1,14 @@
-use std::{cmp::min, sync::Arc, time::Duration};
+use std::{
+    cmp::min,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use log::{error, info, warn};
 use magicblock_core::traits::FinalityProvider;
+use solana_sdk::clock::{Slot, UnixTimestamp};
 use tokio::{
+    sync::Notify,
     task::{JoinError, JoinHandle, JoinSet},
     time::interval,
 };
@@ -14,6 +20,7 @@ use crate::{
         Transaction, TransactionMemos, TransactionStatus,
     },
     errors::LedgerResult,
+    ledger_archiver::LedgerArchiver,
     Ledger,
 };
 
@@ -21,11 +28,36 @@ pub const DEFAULT_TRUNCATION_TIME_INTERVAL: Duration =
     Duration::from_secs(2 * 60);
 const PERCENTAGE_TO_TRUNCATE: u8 = 10;
 
+/// Slot and age based limits on how much history the ledger keeps.
+/// These apply in addition to the size cap and, like it, never cause
+/// slots at or after the oldest AccountsDb snapshot to be removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LedgerRetention {
+    /// Maximum number of most recent slots to keep
+    pub max_slots: Option<u64>,
+    /// Maximum age of a slot, based on its block time
+    pub max_age: Option<Duration>,
+}
+
+/// Makes the truncator remove all slots that aren't needed to replay the
+/// ledger right away, i.e. when the disk is running full
+#[derive(Debug, Clone, Default)]
+pub struct EmergencyTruncation(Arc<Notify>);
+
+impl EmergencyTruncation {
+    pub fn trigger(&self) {
+        self.0.notify_one();
+    }
+}
+
 struct LedgerTrunctationWorker<T> {
     finality_provider: Arc<T>,
     ledger: Arc<Ledger>,
     truncation_time_interval: Duration,
     ledger_size: u64,
+    retention: LedgerRetention,
+    archiver: Option<Arc<LedgerArchiver>>,
+    emergency_truncation: EmergencyTruncation,
     cancellation_token: CancellationToken,
 }
 
@@ -35,6 +67,9 @@ impl<T: FinalityProvider> LedgerTrunctationWorker<T> {
         finality_provider: Arc<T>,
         truncation_time_interval: Duration,
         ledger_size: u64,
+        retention: LedgerRetention,
+        archiver: Option<Arc<LedgerArchiver>>,
+        emergency_truncation: EmergencyTruncation,
         cancellation_token: CancellationToken,
     ) -> Self {
         Self {
@@ -42,6 +77,9 @@ impl<T: FinalityProvider> LedgerTrunctationWorker<T> {
             finality_provider,
             truncation_time_interval,
             ledger_size,
+            retention,
+            archiver,
+            emergency_truncation,
             cancellation_token,
         }
     }
@@ -54,38 +92,48 @@ impl<T: FinalityProvider> LedgerTrunctationWorker<T> {
                     return;
                 }
                 _ = interval.tick() => {
-                    // Note: since we clean 10%, tomstones will take around 10% as well
-                    const FILLED_PERCENTAGE_LIMIT: u8 = 100 - PERCENTAGE_TO_TRUNCATE;
-
-                    let current_size = match self.ledger.storage_size() {
-                        Ok(value) => value,
-                        Err(err) => {
-                            error!("Failed to check truncation condition: {err}");
-                            continue;
+                    match self.estimate_truncation_range() {
+                        Ok(Some((from_slot, to_slot))) => {
+                            self.archive_and_truncate_slot_range(from_slot, to_slot).await
                         }
-                    };
-
-                    // Check if we should truncate
-                    if current_size < (self.ledger_size / 100) * FILLED_PERCENTAGE_LIMIT as u64 {
-                        continue;
-                    }
-
-                    info!("Ledger size: {current_size}");
-                    match self.estimate_truncation_range(current_size) {
-                        Ok(Some((from_slot, to_slot))) => Self::truncate_slot_range(&self.ledger, from_slot, to_slot).await,
-                        Ok(None) => warn!("Could not estimate truncation range"),
+                        Ok(None) => {}
                         Err(err) => error!("Failed to estimate truncation range: {:?}", err),
                     }
                 }
+                _ = self.emergency_truncation.0.notified() => {
+                    if let Some((from_slot, to_slot)) = self.available_truncation_range() {
+                        warn!("Emergency truncation of slots [{from_slot}; {to_slot}]");
+                        self.archive_and_truncate_slot_range(from_slot, to_slot).await
+                    }
+                }
             }
         }
     }
 
-    /// Returns range to truncate [from_slot, to_slot]
-    fn estimate_truncation_range(
+    async fn archive_and_truncate_slot_range(
         &self,
-        current_ledger_size: u64,
-    ) -> LedgerResult<Option<(u64, u64)>> {
+        from_slot: u64,
+        to_slot: u64,
+    ) {
+        // Slots that could not be archived are kept in the ledger
+        // and archiving is retried on the next tick
+        if let Err(err) = self.archive_slot_range(from_slot, to_slot) {
+            error!("Failed to archive slots [{from_slot}; {to_slot}], skipping truncation: {:?}", err);
+            return;
+        }
+        Self::truncate_slot_range(&self.ledger, from_slot, to_slot).await
+    }
+
+    /// Returns range to truncate [from_slot, to_slot]
+    /// The range covers whatever the size cap or the retention limits
+    /// require, clamped to the range that is safe to truncate.
+    fn estimate_truncation_range(&self) -> LedgerResult<Option<(u64, u64)>> {
+        let num_slots_by_size = self.size_truncation_num_slots()?;
+        let retention_to_slot = self.retention_truncation_slot()?;
+        if num_slots_by_size.is_none() && retention_to_slot.is_none() {
+            return Ok(None);
+        }
+
         let (from_slot, to_slot) =
             if let Some(val) = self.available_truncation_range() {
                 val
@@ -93,6 +141,36 @@ impl<T: FinalityProvider> LedgerTrunctationWorker<T> {
                 return Ok(None);
             };
 
+        // None orders below Some, so this picks the furthest requested slot
+        let size_to_slot = num_slots_by_size.map(|num| from_slot + num);
+        let Some(requested_to_slot) = size_to_slot.max(retention_to_slot)
+        else {
+            return Ok(None);
+        };
+        if requested_to_slot < from_slot {
+            return Ok(None);
+        }
+
+        // Slots at or after the oldest snapshot are required to replay
+        // the ledger on restart and are never truncated
+        Ok(Some((from_slot, min(requested_to_slot, to_slot))))
+    }
+
+    /// Returns the number of slots to truncate in order to keep the ledger
+    /// below its size cap, or `None` if there is still enough space
+    fn size_truncation_num_slots(&self) -> LedgerResult<Option<u64>> {
+        // Note: since we clean 10%, tomstones will take around 10% as well
+        const FILLED_PERCENTAGE_LIMIT: u8 = 100 - PERCENTAGE_TO_TRUNCATE;
+
+        let current_ledger_size = self.ledger.storage_size()?;
+        // Check if we should truncate
+        if current_ledger_size
+            < (self.ledger_size / 100) * FILLED_PERCENTAGE_LIMIT as u64
+        {
+            return Ok(None);
+        }
+        info!("Ledger size: {current_ledger_size}");
+
         let num_slots = self.ledger.count_blockhashes()?;
         if num_slots == 0 {
             info!("No slot were written yet. Nothing to truncate!");
@@ -102,10 +180,47 @@ impl<T: FinalityProvider> LedgerTrunctationWorker<T> {
         let slot_size = current_ledger_size / num_slots as u64;
         let size_to_truncate =
             (current_ledger_size / 100) * PERCENTAGE_TO_TRUNCATE as u64;
-        let num_slots_to_truncate = size_to_truncate / slot_size;
+        Ok(Some(size_to_truncate / slot_size.max(1)))
+    }
+
+    /// Returns the last slot that falls outside of the configured
+    /// retention limits, or `None` if all slots should be retained
+    fn retention_truncation_slot(&self) -> LedgerResult<Option<Slot>> {
+        let by_slots = match self.retention.max_slots {
+            Some(max_slots) => {
+                let (latest_slot, _) = self.ledger.get_max_blockhash()?;
+                latest_slot.checked_sub(max_slots)
+            }
+            None => None,
+        };
+        let by_age = match self.retention.max_age {
+            Some(max_age) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs() as UnixTimestamp;
+                let cutoff =
+                    now.saturating_sub(max_age.as_secs() as UnixTimestamp);
+                self.ledger.get_latest_slot_before_time(cutoff)?
+            }
+            None => None,
+        };
 
-        let to_slot = min(from_slot + num_slots_to_truncate, to_slot);
-        Ok(Some((from_slot, to_slot)))
+        Ok(by_slots.max(by_age))
+    }
+
+    /// Exports [from_slot, to_slot] range to cold storage if an archiver is configured
+    fn archive_slot_range(
+        &self,
+        from_slot: u64,
+        to_slot: u64,
+    ) -> LedgerResult<()> {
+        match &self.archiver {
+            Some(archiver) => {
+                archiver.archive_slot_range(&self.ledger, from_slot, to_slot)
+            }
+            None => Ok(()),
+        }
     }
 
     /// Returns [from_slot, to_slot] range that's safe to truncate
@@ -262,7 +377,10 @@ pub struct LedgerTruncator<T> {
     finality_provider: Arc<T>,
     ledger: Arc<Ledger>,
     ledger_size: u64,
+    retention: LedgerRetention,
+    archiver: Option<Arc<LedgerArchiver>>,
     truncation_time_interval: Duration,
+    emergency_truncation: EmergencyTruncation,
     state: ServiceState,
 }
 
@@ -272,16 +390,27 @@ impl<T: FinalityProvider> LedgerTruncator<T> {
         finality_provider: Arc<T>,
         truncation_time_interval: Duration,
         ledger_size: u64,
+        retention: LedgerRetention,
+        archiver: Option<Arc<LedgerArchiver>>,
     ) -> Self {
         Self {
             ledger,
             finality_provider,
             truncation_time_interval,
             ledger_size,
+            retention,
+            archiver,
+            emergency_truncation: EmergencyTruncation::default(),
             state: ServiceState::Created,
         }
     }
 
+    /// Allows triggering a truncation of all slots that aren't needed to
+    /// replay the ledger while the truncator is running
+    pub fn emergency_truncation(&self) -> EmergencyTruncation {
+        self.emergency_truncation.clone()
+    }
+
     pub fn start(&mut self) {
         if let ServiceState::Created = self.state {
             let cancellation_token = CancellationToken::new();
@@ -290,6 +419,9 @@ impl<T: FinalityProvider> LedgerTruncator<T> {
                 self.finality_provider.clone(),
                 self.truncation_time_interval,
                 self.ledger_size,
+                self.retention,
+                self.archiver.clone(),
+                self.emergency_truncation.clone(),
                 cancellation_token.clone(),
             );
             let worker_handle = tokio::spawn(worker.run());
@@ -1,10 +1,16 @@
+pub mod block_record;
 pub mod blockstore_processor;
 mod conversions;
 mod database;
 pub mod errors;
+pub mod ledger_archiver;
+pub mod ledger_tailer;
 pub mod ledger_truncator;
 mod metrics;
 mod store;
 
 pub use database::meta::PerfSample;
-pub use store::api::{Ledger, SignatureInfosForAddress};
+pub use store::{
+    api::{Ledger, SignatureInfosForAddress, TransactionToWrite},
+    verify::{LedgerAnomaly, LedgerVerifyReport},
+};
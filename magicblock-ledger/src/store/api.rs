@@ -10,14 +10,18 @@ use std::{
 
 use bincode::{deserialize, serialize};
 use log::*;
-use rocksdb::{Direction as IteratorDirection, FlushOptions};
+use rocksdb::{
+    checkpoint::Checkpoint, Direction as IteratorDirection, FlushOptions,
+};
 use solana_measure::measure::Measure;
 use solana_sdk::{
     clock::{Slot, UnixTimestamp},
     hash::{Hash, HASH_BYTES},
     pubkey::Pubkey,
     signature::Signature,
-    transaction::{SanitizedTransaction, VersionedTransaction},
+    transaction::{
+        Result as TransactionResult, SanitizedTransaction, VersionedTransaction,
+    },
 };
 use solana_storage_proto::convert::generated::{self, ConfirmedTransaction};
 use solana_transaction_status::{
@@ -26,6 +30,10 @@ use solana_transaction_status::{
     VersionedConfirmedBlock, VersionedTransactionWithStatusMeta,
 };
 
+use magicblock_core::{
+    commit_record::CommitRecord, mutation_audit::MutationAuditEntry,
+};
+
 use crate::{
     conversions::transaction,
     database::{
@@ -34,7 +42,10 @@ use crate::{
         db::Database,
         iterator::IteratorMode,
         ledger_column::{try_increase_entry_counter, LedgerColumn},
-        meta::{AccountModData, AddressSignatureMeta, PerfSample},
+        meta::{
+            AccountModData, AddressSignatureMeta, PerfSample,
+            SlotTransactionStatuses,
+        },
         options::LedgerOptions,
     },
     errors::{LedgerError, LedgerResult},
@@ -42,6 +53,16 @@ use crate::{
     store::utils::adjust_ulimit_nofile,
 };
 
+/// Transaction to be persisted via [Ledger::write_transactions]
+pub struct TransactionToWrite {
+    pub signature: Signature,
+    pub slot: Slot,
+    pub transaction: SanitizedTransaction,
+    pub status: TransactionStatusMeta,
+    pub transaction_slot_index: usize,
+    pub memos: Option<String>,
+}
+
 #[derive(Default, Debug)]
 pub struct SignatureInfosForAddress {
     pub infos: Vec<ConfirmedTransactionStatusWithSignature>,
@@ -51,22 +72,27 @@ pub struct SignatureInfosForAddress {
 
 pub struct Ledger {
     ledger_path: PathBuf,
-    db: Arc<Database>,
-
-    blocktime_cf: LedgerColumn<cf::Blocktime>,
-    blockhash_cf: LedgerColumn<cf::Blockhash>,
-    slot_signatures_cf: LedgerColumn<cf::SlotSignatures>,
-    address_signatures_cf: LedgerColumn<cf::AddressSignatures>,
-    transaction_status_cf: LedgerColumn<cf::TransactionStatus>,
-    transaction_cf: LedgerColumn<cf::Transaction>,
-    transaction_memos_cf: LedgerColumn<cf::TransactionMemos>,
-    perf_samples_cf: LedgerColumn<cf::PerfSamples>,
+    pub(super) db: Arc<Database>,
+
+    pub(super) blocktime_cf: LedgerColumn<cf::Blocktime>,
+    pub(super) blockhash_cf: LedgerColumn<cf::Blockhash>,
+    pub(super) slot_signatures_cf: LedgerColumn<cf::SlotSignatures>,
+    pub(super) address_signatures_cf: LedgerColumn<cf::AddressSignatures>,
+    pub(super) transaction_status_cf: LedgerColumn<cf::TransactionStatus>,
+    pub(super) transaction_cf: LedgerColumn<cf::Transaction>,
+    pub(super) transaction_memos_cf: LedgerColumn<cf::TransactionMemos>,
+    pub(super) perf_samples_cf: LedgerColumn<cf::PerfSamples>,
     account_mod_datas_cf: LedgerColumn<cf::AccountModDatas>,
+    pub(super) accounts_hash_cf: LedgerColumn<cf::AccountsHash>,
+    pub(super) status_cache_cf: LedgerColumn<cf::StatusCache>,
+    pub(super) commit_records_cf: LedgerColumn<cf::CommitRecords>,
+    pub(super) mutation_audit_entries_cf:
+        LedgerColumn<cf::MutationAuditEntries>,
 
-    transaction_successful_status_count: AtomicI64,
-    transaction_failed_status_count: AtomicI64,
+    pub(super) transaction_successful_status_count: AtomicI64,
+    pub(super) transaction_failed_status_count: AtomicI64,
 
-    lowest_cleanup_slot: RwLock<Slot>,
+    pub(super) lowest_cleanup_slot: RwLock<Slot>,
     rpc_api_metrics: LedgerRpcApiMetrics,
 }
 
@@ -77,7 +103,7 @@ impl fmt::Display for Ledger {
 }
 
 impl Ledger {
-    const LOWEST_CLEANUP_SLOT_POISONED: &'static str =
+    pub(super) const LOWEST_CLEANUP_SLOT_POISONED: &'static str =
         "lowest_cleanup_slot RwLock poisoned.";
 
     pub fn db(self) -> Arc<Database> {
@@ -96,6 +122,34 @@ impl Ledger {
         self.db.storage_size()
     }
 
+    /// Creates a consistent copy of the ledger inside `backup_path` while it
+    /// remains available for reads and writes.
+    /// Uses a RocksDB checkpoint which hard links the immutable data files if
+    /// `backup_path` is on the same filesystem as the ledger, thus it is cheap.
+    /// The backup can be opened via [Ledger::open] with `backup_path`.
+    pub fn create_backup(&self, backup_path: &Path) -> LedgerResult<()> {
+        let checkpoint_path = match self.ledger_path.file_name() {
+            Some(blockstore_directory) => {
+                backup_path.join(blockstore_directory)
+            }
+            None => backup_path.to_path_buf(),
+        };
+        if checkpoint_path.exists() {
+            return Err(LedgerError::Io(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("backup already exists at {:?}", checkpoint_path),
+            )));
+        }
+        fs::create_dir_all(backup_path)?;
+
+        let mut measure = Measure::start("ledger backup");
+        Checkpoint::new(&self.db.backend.db)?
+            .create_checkpoint(&checkpoint_path)?;
+        measure.stop();
+        info!("Created ledger backup at {:?}; {measure}", checkpoint_path);
+        Ok(())
+    }
+
     /// Opens a Ledger in directory, provides "infinite" window of shreds
     pub fn open(ledger_path: &Path) -> Result<Self, LedgerError> {
         Self::do_open(ledger_path, LedgerOptions::default())
@@ -136,6 +190,10 @@ impl Ledger {
         let perf_samples_cf = db.column();
 
         let account_mod_datas_cf = db.column();
+        let accounts_hash_cf = db.column();
+        let status_cache_cf = db.column();
+        let commit_records_cf = db.column();
+        let mutation_audit_entries_cf = db.column();
 
         let db = Arc::new(db);
 
@@ -157,6 +215,10 @@ impl Ledger {
             transaction_memos_cf,
             perf_samples_cf,
             account_mod_datas_cf,
+            accounts_hash_cf,
+            status_cache_cf,
+            commit_records_cf,
+            mutation_audit_entries_cf,
 
             transaction_successful_status_count: AtomicI64::new(DIRTY_COUNT),
             transaction_failed_status_count: AtomicI64::new(DIRTY_COUNT),
@@ -182,6 +244,10 @@ impl Ledger {
         self.transaction_memos_cf.submit_rocksdb_cf_metrics();
         self.perf_samples_cf.submit_rocksdb_cf_metrics();
         self.account_mod_datas_cf.submit_rocksdb_cf_metrics();
+        self.accounts_hash_cf.submit_rocksdb_cf_metrics();
+        self.status_cache_cf.submit_rocksdb_cf_metrics();
+        self.commit_records_cf.submit_rocksdb_cf_metrics();
+        self.mutation_audit_entries_cf.submit_rocksdb_cf_metrics();
     }
 
     // -----------------
@@ -216,7 +282,7 @@ impl Ledger {
     /// This function ensures a consistent result by using lowest_cleanup_slot
     /// as the lower bound for reading columns that do not employ strong read
     /// consistency with slot-based delete_range.
-    fn ensure_lowest_cleanup_slot(
+    pub(super) fn ensure_lowest_cleanup_slot(
         &self,
     ) -> (std::sync::RwLockReadGuard<Slot>, Slot) {
         let lowest_cleanup_slot = self
@@ -256,11 +322,45 @@ impl Ledger {
         self.blocktime_cf.count_column_using_cache()
     }
 
+    /// Returns the highest stored slot whose block time is strictly older
+    /// than the provided `timestamp`.
+    /// Block times increase monotonically with slots, so the search stops
+    /// at the first slot that is not older than `timestamp`.
+    pub fn get_latest_slot_before_time(
+        &self,
+        timestamp: UnixTimestamp,
+    ) -> LedgerResult<Option<Slot>> {
+        let (lowest_cleanup_slot, lowest_available_slot) =
+            self.ensure_lowest_cleanup_slot();
+        // Fresh ledgers haven't cleaned up anything, including slot 0
+        let from_slot = if *lowest_cleanup_slot == 0 {
+            0
+        } else {
+            lowest_available_slot
+        };
+
+        let mut latest_slot = None;
+        for (slot, value) in self
+            .blocktime_cf
+            .iter(IteratorMode::From(from_slot, IteratorDirection::Forward))?
+        {
+            let block_time: UnixTimestamp = deserialize(&value)?;
+            if block_time >= timestamp {
+                break;
+            }
+            latest_slot = Some(slot);
+        }
+        Ok(latest_slot)
+    }
+
     // -----------------
     // Blockhash
     // -----------------
 
-    fn get_block_hash(&self, slot: Slot) -> LedgerResult<Option<Hash>> {
+    pub(crate) fn get_block_hash(
+        &self,
+        slot: Slot,
+    ) -> LedgerResult<Option<Hash>> {
         let _lock = self.check_lowest_cleanup_slot(slot)?;
         self.blockhash_cf.get(slot)
     }
@@ -331,8 +431,10 @@ impl Ledger {
                 signatures.push(Signature::try_from(&*tx_signature)?);
             }
 
+            // Transactions are listed in the order they executed in the slot
             signatures
                 .into_iter()
+                .rev()
                 .map(|tx_signature| {
                     let transaction = self
                         .transaction_cf
@@ -345,7 +447,7 @@ impl Ledger {
                         .ok_or(LedgerError::TransactionStatusMetaNotFound)?;
                     Ok(VersionedTransactionWithStatusMeta {
                         transaction,
-                        meta: TransactionStatusMeta::try_from(meta).unwrap(),
+                        meta: TransactionStatusMeta::try_from(meta)?,
                     })
                 })
                 .collect::<LedgerResult<Vec<_>>>()
@@ -371,10 +473,192 @@ impl Ledger {
         Ok(Some(block))
     }
 
+    // -----------------
+    // AccountsHash
+    // -----------------
+
+    /// Records the hash of the AccountsDb state at the start of `slot`,
+    /// used to verify that a snapshot taken at that slot is consistent
+    /// with the ledger when restarting from it
+    pub fn write_accounts_hash(
+        &self,
+        slot: Slot,
+        accounts_hash: &Hash,
+    ) -> LedgerResult<()> {
+        self.accounts_hash_cf.put(slot, accounts_hash)
+    }
+
+    pub fn read_accounts_hash(&self, slot: Slot) -> LedgerResult<Option<Hash>> {
+        self.accounts_hash_cf.get(slot)
+    }
+
+    // -----------------
+    // StatusCache
+    // -----------------
+
+    /// Persists the transaction statuses tracked by the bank status cache
+    /// grouped by the slot in which the transactions executed
+    pub fn write_status_cache(
+        &self,
+        slot_statuses: Vec<(Slot, Vec<(Signature, TransactionResult<()>)>)>,
+    ) -> LedgerResult<()> {
+        let mut batch = self.db.batch();
+        for (slot, statuses) in slot_statuses {
+            batch.put::<cf::StatusCache>(
+                slot,
+                &SlotTransactionStatuses { statuses },
+            )?;
+        }
+        self.db.write(batch)
+    }
+
+    /// Reads the persisted transaction statuses of `from_slot` and the slots
+    /// following it, ordered by slot
+    pub fn read_status_cache(
+        &self,
+        from_slot: Slot,
+    ) -> LedgerResult<Vec<(Slot, Vec<(Signature, TransactionResult<()>)>)>>
+    {
+        self.status_cache_cf
+            .iter(IteratorMode::From(from_slot, IteratorDirection::Forward))?
+            .map(|(slot, value)| {
+                let SlotTransactionStatuses { statuses } = deserialize(&value)?;
+                Ok((slot, statuses))
+            })
+            .collect()
+    }
+
+    // -----------------
+    // CommitRecords
+    // -----------------
+
+    /// Stores the record of a scheduled commit, replacing an earlier record
+    /// of the same commit, i.e. once its outcome is known
+    pub fn write_commit_record(
+        &self,
+        record: &CommitRecord,
+    ) -> LedgerResult<()> {
+        self.commit_records_cf
+            .put((record.slot, record.commit_id), record)
+    }
+
+    /// Reads the records of the commits scheduled in the inclusive slot
+    /// range, ordered by slot and commit id
+    pub fn read_commit_records(
+        &self,
+        from_slot: Slot,
+        to_slot: Slot,
+    ) -> LedgerResult<Vec<CommitRecord>> {
+        let mut records = vec![];
+        for ((slot, _), value) in
+            self.commit_records_cf.iter(IteratorMode::From(
+                (from_slot, u64::MIN),
+                IteratorDirection::Forward,
+            ))?
+        {
+            if slot > to_slot {
+                break;
+            }
+            records.push(deserialize(&value)?);
+        }
+        Ok(records)
+    }
+
+    /// Reads the most recent record of the commit with the given id.
+    /// Commit ids restart when the validator restarts, thus the record of
+    /// an earlier commit with the same id is shadowed.
+    /// NOTE: this scans the records backwards until it finds the commit
+    pub fn read_commit_record(
+        &self,
+        commit_id: u64,
+    ) -> LedgerResult<Option<CommitRecord>> {
+        for ((_, id), value) in
+            self.commit_records_cf.iter(IteratorMode::End)?
+        {
+            if id == commit_id {
+                return Ok(Some(deserialize(&value)?));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads up to `limit` records of the most recent commits, newest first.
+    /// When `before` is provided only commits scheduled before that
+    /// `(slot, commit_id)` are included which allows paginating through them.
+    pub fn read_recent_commit_records(
+        &self,
+        before: Option<(Slot, u64)>,
+        limit: usize,
+    ) -> LedgerResult<Vec<CommitRecord>> {
+        let iterator_mode = match before {
+            Some(before) => {
+                IteratorMode::From(before, IteratorDirection::Reverse)
+            }
+            None => IteratorMode::End,
+        };
+        let mut records = vec![];
+        for (index, value) in self.commit_records_cf.iter(iterator_mode)? {
+            if records.len() >= limit {
+                break;
+            }
+            if Some(index) == before {
+                continue;
+            }
+            records.push(deserialize(&value)?);
+        }
+        Ok(records)
+    }
+
+    // -----------------
+    // MutationAuditEntries
+    // -----------------
+    pub fn write_mutation_audit_entry(
+        &self,
+        entry: &MutationAuditEntry,
+    ) -> LedgerResult<()> {
+        self.mutation_audit_entries_cf
+            .put((entry.slot, entry.seq), entry)
+    }
+
+    /// Reads up to `limit` audit entries of account modifications in the
+    /// inclusive slot range, ordered by slot and sequence number
+    pub fn read_mutation_audit_entries(
+        &self,
+        from_slot: Slot,
+        to_slot: Slot,
+        limit: usize,
+    ) -> LedgerResult<Vec<MutationAuditEntry>> {
+        let mut entries = vec![];
+        for ((slot, _), value) in
+            self.mutation_audit_entries_cf.iter(IteratorMode::From(
+                (from_slot, u64::MIN),
+                IteratorDirection::Forward,
+            ))?
+        {
+            if slot > to_slot || entries.len() >= limit {
+                break;
+            }
+            entries.push(deserialize(&value)?);
+        }
+        Ok(entries)
+    }
+
     pub fn count_slot_signatures(&self) -> LedgerResult<i64> {
         self.slot_signatures_cf.count_column_using_cache()
     }
 
+    /// Returns `true` if any transaction was recorded at `slot` or later
+    pub fn has_transactions_since(&self, slot: Slot) -> LedgerResult<bool> {
+        Ok(self
+            .slot_signatures_cf
+            .iter(IteratorMode::From(
+                (slot, u32::MIN),
+                IteratorDirection::Forward,
+            ))?
+            .next()
+            .is_some())
+    }
+
     // -----------------
     // Signatures
     // -----------------
@@ -810,6 +1094,104 @@ impl Ledger {
         Ok(())
     }
 
+    /// Writes all provided transactions together with their statuses and
+    /// memos in a single atomic batch, which is considerably cheaper than
+    /// writing each of them via [Ledger::write_transaction].
+    pub fn write_transactions(
+        &self,
+        transactions: Vec<TransactionToWrite>,
+    ) -> LedgerResult<()> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        let num_transactions = transactions.len() as u64;
+        let mut batch = self.db.batch();
+        let mut num_address_signatures = 0;
+        let mut num_memos = 0;
+        let mut num_successful = 0;
+        let mut num_failed = 0;
+        for TransactionToWrite {
+            signature,
+            slot,
+            transaction,
+            status,
+            transaction_slot_index,
+            memos,
+        } in transactions
+        {
+            let transaction_slot_index = u32::try_from(transaction_slot_index)
+                .map_err(|_| LedgerError::TransactionIndexOverflow)?;
+            let tx_account_locks = transaction.get_account_locks_unchecked();
+            let address_signatures = tx_account_locks
+                .writable
+                .into_iter()
+                .map(|address| (address, true))
+                .chain(
+                    tx_account_locks
+                        .readonly
+                        .into_iter()
+                        .map(|address| (address, false)),
+                );
+            for (address, writeable) in address_signatures {
+                batch.put::<cf::AddressSignatures>(
+                    (*address, slot, transaction_slot_index, signature),
+                    &AddressSignatureMeta { writeable },
+                )?;
+                num_address_signatures += 1;
+            }
+
+            batch.put::<cf::SlotSignatures>(
+                (slot, transaction_slot_index),
+                &signature,
+            )?;
+
+            let status: generated::TransactionStatusMeta = status.into();
+            if status.err.is_none() {
+                num_successful += 1;
+            } else {
+                num_failed += 1;
+            }
+            batch.put_protobuf::<cf::TransactionStatus>(
+                (signature, slot),
+                &status,
+            )?;
+
+            let versioned = transaction.to_versioned_transaction();
+            batch.put_protobuf::<cf::Transaction>(
+                (signature, slot),
+                &versioned.into(),
+            )?;
+
+            if let Some(memos) = memos {
+                batch.put::<cf::TransactionMemos>((signature, slot), &memos)?;
+                num_memos += 1;
+            }
+        }
+        self.db.write(batch)?;
+
+        self.address_signatures_cf
+            .try_increase_entry_counter(num_address_signatures);
+        self.slot_signatures_cf
+            .try_increase_entry_counter(num_transactions);
+        self.transaction_status_cf
+            .try_increase_entry_counter(num_transactions);
+        self.transaction_cf
+            .try_increase_entry_counter(num_transactions);
+        self.transaction_memos_cf
+            .try_increase_entry_counter(num_memos);
+        try_increase_entry_counter(
+            &self.transaction_successful_status_count,
+            num_successful,
+        );
+        try_increase_entry_counter(
+            &self.transaction_failed_status_count,
+            num_failed,
+        );
+
+        Ok(())
+    }
+
     pub fn read_transaction(
         &self,
         index: (Signature, Slot),
@@ -1156,6 +1538,26 @@ impl Ledger {
             from_slot,
             to_slot + 1,
         );
+        self.accounts_hash_cf.delete_range_in_batch(
+            &mut batch,
+            from_slot,
+            to_slot + 1,
+        );
+        self.status_cache_cf.delete_range_in_batch(
+            &mut batch,
+            from_slot,
+            to_slot + 1,
+        );
+        self.commit_records_cf.delete_range_in_batch(
+            &mut batch,
+            (from_slot, u64::MIN),
+            (to_slot + 1, u64::MIN),
+        );
+        self.mutation_audit_entries_cf.delete_range_in_batch(
+            &mut batch,
+            (from_slot, u64::MIN),
+            (to_slot + 1, u64::MIN),
+        );
 
         let mut slot_signatures_deleted = 0;
         let mut transaction_status_deleted = 0;
@@ -1255,6 +1657,10 @@ impl Ledger {
             self.transaction_memos_cf.handle(),
             self.perf_samples_cf.handle(),
             self.account_mod_datas_cf.handle(),
+            self.accounts_hash_cf.handle(),
+            self.status_cache_cf.handle(),
+            self.commit_records_cf.handle(),
+            self.mutation_audit_entries_cf.handle(),
         ];
 
         self.db
@@ -1276,6 +1682,9 @@ impl Ledger {
 // -----------------
 #[cfg(test)]
 mod tests {
+    use magicblock_core::{
+        commit_record::CommitOutcome, mutation_audit::MutationReason,
+    };
     use solana_sdk::{
         clock::UnixTimestamp,
         instruction::{CompiledInstruction, InstructionError},
@@ -1519,6 +1928,224 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_latest_slot_before_time() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        assert_eq!(store.get_latest_slot_before_time(100).unwrap(), None);
+
+        for slot in 0..10 {
+            store
+                .write_block(slot, 100 + slot as i64, Hash::new_unique())
+                .unwrap();
+        }
+
+        assert_eq!(store.get_latest_slot_before_time(100).unwrap(), None);
+        assert_eq!(store.get_latest_slot_before_time(101).unwrap(), Some(0));
+        assert_eq!(store.get_latest_slot_before_time(105).unwrap(), Some(4));
+        assert_eq!(store.get_latest_slot_before_time(500).unwrap(), Some(9));
+    }
+
+    #[test]
+    fn test_accounts_hash_and_transactions_since() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let accounts_hash = Hash::new_unique();
+        assert_eq!(store.read_accounts_hash(10).unwrap(), None);
+        store.write_accounts_hash(10, &accounts_hash).unwrap();
+        assert_eq!(store.read_accounts_hash(10).unwrap(), Some(accounts_hash));
+
+        assert!(!store.has_transactions_since(0).unwrap());
+
+        let (tx, sanitized) = create_confirmed_transaction(10, 5, None, None);
+        store
+            .write_transaction(
+                Signature::default(),
+                10,
+                sanitized,
+                tx.tx_with_meta.get_status_meta().unwrap(),
+                0,
+            )
+            .unwrap();
+
+        assert!(store.has_transactions_since(0).unwrap());
+        assert!(store.has_transactions_since(10).unwrap());
+        assert!(!store.has_transactions_since(11).unwrap());
+    }
+
+    #[test]
+    fn test_status_cache() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let slot_statuses = vec![
+            (3, vec![(Signature::new_unique(), Ok(()))]),
+            (
+                5,
+                vec![
+                    (Signature::new_unique(), Ok(())),
+                    (
+                        Signature::new_unique(),
+                        Err(TransactionError::AccountNotFound),
+                    ),
+                ],
+            ),
+        ];
+        store.write_status_cache(slot_statuses.clone()).unwrap();
+
+        assert_eq!(store.read_status_cache(0).unwrap(), slot_statuses);
+        assert_eq!(store.read_status_cache(4).unwrap(), slot_statuses[1..]);
+        assert!(store.read_status_cache(6).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_commit_records() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let commit_record = |commit_id: u64, slot: Slot| CommitRecord {
+            commit_id,
+            slot,
+            payer: Pubkey::new_unique(),
+            included_pubkeys: vec![Pubkey::new_unique()],
+            excluded_pubkeys: vec![],
+            requested_undelegation: false,
+            chain_signatures: vec![Signature::new_unique()],
+            outcome: CommitOutcome::Pending,
+        };
+        let mut record_uno = commit_record(2, 10);
+        let record_dos = commit_record(1, 10);
+        let record_tres = commit_record(3, 12);
+        for record in [&record_uno, &record_dos, &record_tres] {
+            store.write_commit_record(record).unwrap();
+        }
+
+        assert_eq!(
+            store.read_commit_records(10, 10).unwrap(),
+            vec![record_dos.clone(), record_uno.clone()]
+        );
+        assert_eq!(
+            store.read_commit_records(11, 20).unwrap(),
+            vec![record_tres.clone()]
+        );
+        assert!(store.read_commit_records(13, 20).unwrap().is_empty());
+
+        // Recording the outcome replaces the pending record
+        record_uno.outcome = CommitOutcome::Confirmed;
+        store.write_commit_record(&record_uno).unwrap();
+        assert_eq!(
+            store.read_commit_records(0, 12).unwrap(),
+            vec![record_dos, record_uno, record_tres]
+        );
+    }
+
+    #[test]
+    fn test_mutation_audit_entries() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let audit_entry = |seq: u64, slot: Slot| MutationAuditEntry {
+            seq,
+            slot,
+            authority: Pubkey::new_unique(),
+            reason: MutationReason::CloneAccount,
+            accounts: vec![Pubkey::new_unique()],
+        };
+        let entry_uno = audit_entry(0, 10);
+        let entry_dos = audit_entry(1, 10);
+        let entry_tres = audit_entry(2, 12);
+        for entry in [&entry_dos, &entry_tres, &entry_uno] {
+            store.write_mutation_audit_entry(entry).unwrap();
+        }
+
+        assert_eq!(
+            store.read_mutation_audit_entries(10, 10, 10).unwrap(),
+            vec![entry_uno.clone(), entry_dos.clone()]
+        );
+        assert_eq!(
+            store.read_mutation_audit_entries(0, 20, 10).unwrap(),
+            vec![entry_uno.clone(), entry_dos, entry_tres.clone()]
+        );
+        assert_eq!(
+            store.read_mutation_audit_entries(11, 20, 10).unwrap(),
+            vec![entry_tres]
+        );
+        assert_eq!(
+            store.read_mutation_audit_entries(0, 20, 1).unwrap(),
+            vec![entry_uno]
+        );
+        assert!(store
+            .read_mutation_audit_entries(13, 20, 10)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_recent_commit_records() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let commit_record = |commit_id: u64, slot: Slot| CommitRecord {
+            commit_id,
+            slot,
+            payer: Pubkey::new_unique(),
+            included_pubkeys: vec![Pubkey::new_unique()],
+            excluded_pubkeys: vec![],
+            requested_undelegation: false,
+            chain_signatures: vec![Signature::new_unique()],
+            outcome: CommitOutcome::Pending,
+        };
+        let record_uno = commit_record(1, 10);
+        let record_dos = commit_record(2, 10);
+        let record_tres = commit_record(3, 12);
+        // Commit ids restart with the validator
+        let record_cuatro = commit_record(1, 20);
+        for record in [&record_uno, &record_dos, &record_tres, &record_cuatro] {
+            store.write_commit_record(record).unwrap();
+        }
+
+        assert_eq!(
+            store.read_commit_record(3).unwrap(),
+            Some(record_tres.clone())
+        );
+        assert_eq!(
+            store.read_commit_record(1).unwrap(),
+            Some(record_cuatro.clone())
+        );
+        assert!(store.read_commit_record(4).unwrap().is_none());
+
+        assert_eq!(
+            store.read_recent_commit_records(None, 2).unwrap(),
+            vec![record_cuatro, record_tres.clone()]
+        );
+        assert_eq!(
+            store.read_recent_commit_records(Some((12, 3)), 2).unwrap(),
+            vec![record_dos.clone(), record_uno.clone()]
+        );
+        assert_eq!(
+            store.read_recent_commit_records(Some((11, 0)), 10).unwrap(),
+            vec![record_dos, record_uno]
+        );
+        assert!(store
+            .read_recent_commit_records(Some((10, 1)), 10)
+            .unwrap()
+            .is_empty());
+    }
+
     #[test]
     fn test_get_transaction_status_by_signature() {
         init_logger!();
@@ -1685,6 +2312,137 @@ mod tests {
         assert_eq!(tx, tx_dos);
     }
 
+    #[test]
+    fn test_write_transactions_batch() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let (slot, block_time, block_hash) = (10, 100, Hash::new_unique());
+        let (sig_uno, sig_dos) =
+            (Signature::from([1u8; 64]), Signature::from([2u8; 64]));
+        let (tx_uno, sanitized_uno) = create_confirmed_transaction(
+            slot,
+            5,
+            Some(block_time),
+            Some(vec![sig_uno, Signature::new_unique()]),
+        );
+        let (tx_dos, sanitized_dos) = create_confirmed_transaction(
+            slot,
+            9,
+            Some(block_time),
+            Some(vec![sig_dos, Signature::new_unique()]),
+        );
+
+        store
+            .write_transactions(vec![
+                TransactionToWrite {
+                    signature: sig_uno,
+                    slot,
+                    transaction: sanitized_uno,
+                    status: tx_uno.tx_with_meta.get_status_meta().unwrap(),
+                    transaction_slot_index: 0,
+                    memos: Some("memo uno".to_string()),
+                },
+                TransactionToWrite {
+                    signature: sig_dos,
+                    slot,
+                    transaction: sanitized_dos,
+                    status: tx_dos.tx_with_meta.get_status_meta().unwrap(),
+                    transaction_slot_index: 1,
+                    memos: None,
+                },
+            ])
+            .unwrap();
+        store.write_block(slot, block_time, block_hash).unwrap();
+
+        assert_eq!(
+            store
+                .get_complete_transaction(sig_uno, slot)
+                .unwrap()
+                .unwrap(),
+            tx_uno
+        );
+        assert_eq!(
+            store
+                .get_complete_transaction(sig_dos, slot)
+                .unwrap()
+                .unwrap(),
+            tx_dos
+        );
+        assert_eq!(
+            store.read_transaction_memos(sig_uno, slot).unwrap(),
+            Some("memo uno".to_string())
+        );
+        assert_eq!(store.read_transaction_memos(sig_dos, slot).unwrap(), None);
+
+        assert_eq!(store.count_transactions().unwrap(), 2);
+        assert_eq!(store.count_transaction_memos().unwrap(), 1);
+        assert!(store.verify().unwrap().is_consistent());
+    }
+
+    #[test]
+    fn test_get_block_with_status_meta() {
+        init_logger!();
+
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let store = Ledger::open(ledger_path.path()).unwrap();
+
+        let (slot, block_time, block_hash) = (10, 100, Hash::new_unique());
+        let (tx_uno, sanitized_uno) =
+            create_confirmed_transaction(slot, 5, Some(block_time), None);
+        let (tx_dos, sanitized_dos) =
+            create_confirmed_transaction(slot, 9, Some(block_time), None);
+        let meta_uno = tx_uno.tx_with_meta.get_status_meta().unwrap();
+        // Fields that weren't recorded need to be distinguishable from
+        // empty ones
+        let meta_dos = TransactionStatusMeta {
+            inner_instructions: None,
+            log_messages: None,
+            return_data: None,
+            ..tx_dos.tx_with_meta.get_status_meta().unwrap()
+        };
+
+        for (index, (sanitized, meta)) in [
+            (sanitized_uno.clone(), meta_uno.clone()),
+            (sanitized_dos.clone(), meta_dos.clone()),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            store
+                .write_transaction(
+                    *sanitized.signature(),
+                    slot,
+                    sanitized,
+                    meta,
+                    index,
+                )
+                .unwrap();
+        }
+        store.write_block(slot, block_time, block_hash).unwrap();
+
+        let block = store.get_block(slot).unwrap().unwrap();
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(
+            block.transactions[0].transaction.signatures[0],
+            *sanitized_uno.signature()
+        );
+        assert_eq!(block.transactions[0].meta, meta_uno);
+        assert_eq!(
+            block.transactions[1].transaction.signatures[0],
+            *sanitized_dos.signature()
+        );
+        assert_eq!(block.transactions[1].meta, meta_dos);
+
+        let tx = store
+            .get_complete_transaction(*sanitized_dos.signature(), slot)
+            .unwrap()
+            .unwrap();
+        assert_eq!(tx.tx_with_meta.get_status_meta().unwrap(), meta_dos);
+    }
+
     #[test]
     fn test_find_address_signatures_no_intra_slot_limits() {
         init_logger!();
@@ -0,0 +1,24 @@
+use std::error::Error;
+
+use log::*;
+use magicblock_core::{
+    commit_record::CommitRecord, traits::PersistsCommitRecords,
+};
+
+use crate::Ledger;
+
+impl PersistsCommitRecords for Ledger {
+    fn persist_commit_record(
+        &self,
+        record: &CommitRecord,
+    ) -> Result<(), Box<dyn Error>> {
+        trace!(
+            "Persisting commit record with id: {}, slot: {}, outcome: {:?}",
+            record.commit_id,
+            record.slot,
+            record.outcome
+        );
+        self.write_commit_record(record)?;
+        Ok(())
+    }
+}
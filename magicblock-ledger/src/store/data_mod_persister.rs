@@ -1,7 +1,9 @@
 use std::error::Error;
 
 use log::*;
-use magicblock_core::traits::PersistsAccountModData;
+use magicblock_core::{
+    mutation_audit::MutationAuditEntry, traits::PersistsAccountModData,
+};
 
 use crate::Ledger;
 
@@ -27,4 +29,18 @@ impl PersistsAccountModData for Ledger {
         }
         Ok(data)
     }
+
+    fn persist_mutation_audit_entry(
+        &self,
+        entry: &MutationAuditEntry,
+    ) -> Result<(), Box<dyn Error>> {
+        trace!(
+            "Persisting mutation audit entry with seq: {}, slot: {}, reason: {:?}",
+            entry.seq,
+            entry.slot,
+            entry.reason
+        );
+        self.write_mutation_audit_entry(entry)?;
+        Ok(())
+    }
 }
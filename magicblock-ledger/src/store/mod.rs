@@ -1,3 +1,5 @@
 pub mod api;
+pub mod commit_record_persister;
 pub mod data_mod_persister;
 mod utils;
+pub mod verify;
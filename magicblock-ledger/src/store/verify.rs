@@ -0,0 +1,364 @@
+use std::{fmt, iter::Peekable, sync::atomic::Ordering};
+
+use log::*;
+use rocksdb::Direction as IteratorDirection;
+use solana_sdk::{clock::Slot, pubkey::Pubkey, signature::Signature};
+
+use crate::{
+    database::{columns::DIRTY_COUNT, iterator::IteratorMode},
+    errors::LedgerResult,
+    Ledger,
+};
+
+/// Inconsistency between ledger columns found by [Ledger::verify]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerAnomaly {
+    /// A block time was recorded for the slot, but no blockhash
+    MissingBlockhash { slot: Slot },
+    /// A blockhash was recorded for the slot, but no block time
+    MissingBlocktime { slot: Slot },
+    /// No block was recorded for the slots in the inclusive range
+    MissingSlots { from_slot: Slot, to_slot: Slot },
+    /// The slot signature refers to a transaction that wasn't stored
+    MissingTransaction { slot: Slot, signature: Signature },
+    /// The slot signature refers to a transaction status that wasn't stored
+    MissingTransactionStatus { slot: Slot, signature: Signature },
+    /// The address signature doesn't resolve to a slot signature
+    UnresolvedAddressSignature {
+        address: Pubkey,
+        slot: Slot,
+        transaction_index: u32,
+        signature: Signature,
+    },
+}
+
+impl LedgerAnomaly {
+    /// The lowest slot affected by this anomaly
+    pub fn slot(&self) -> Slot {
+        use LedgerAnomaly::*;
+        match self {
+            MissingBlockhash { slot }
+            | MissingBlocktime { slot }
+            | MissingTransaction { slot, .. }
+            | MissingTransactionStatus { slot, .. }
+            | UnresolvedAddressSignature { slot, .. } => *slot,
+            MissingSlots { from_slot, .. } => *from_slot,
+        }
+    }
+}
+
+impl fmt::Display for LedgerAnomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use LedgerAnomaly::*;
+        match self {
+            MissingBlockhash { slot } => {
+                write!(f, "slot {slot}: block time without blockhash")
+            }
+            MissingBlocktime { slot } => {
+                write!(f, "slot {slot}: blockhash without block time")
+            }
+            MissingSlots { from_slot, to_slot } => {
+                write!(f, "slots {from_slot}..={to_slot}: no blocks recorded")
+            }
+            MissingTransaction { slot, signature } => {
+                write!(f, "slot {slot}: transaction {signature} not found")
+            }
+            MissingTransactionStatus { slot, signature } => write!(
+                f,
+                "slot {slot}: status of transaction {signature} not found"
+            ),
+            UnresolvedAddressSignature {
+                address,
+                slot,
+                transaction_index,
+                signature,
+            } => write!(
+                f,
+                "slot {slot}: signature {signature} of address {address} at index {transaction_index} doesn't resolve"
+            ),
+        }
+    }
+}
+
+/// Result of [Ledger::verify]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LedgerVerifyReport {
+    /// Lowest slot for which a block was recorded
+    pub first_slot: Option<Slot>,
+    /// Highest slot for which a block was recorded
+    pub last_slot: Option<Slot>,
+    pub num_blocks: u64,
+    pub num_transactions: u64,
+    /// Transactions recorded after the last block, they belong to the slot
+    /// that is still open and are not checked
+    pub num_open_slot_transactions: u64,
+    pub num_address_signatures: u64,
+    pub anomalies: Vec<LedgerAnomaly>,
+}
+
+impl LedgerVerifyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.anomalies.is_empty()
+    }
+
+    /// The lowest slot affected by any anomaly, dropping all slots starting
+    /// at this one via [Ledger::drop_slots_from] repairs the ledger
+    pub fn first_broken_slot(&self) -> Option<Slot> {
+        self.anomalies.iter().map(LedgerAnomaly::slot).min()
+    }
+}
+
+impl Ledger {
+    /// Checks the cross consistency of the ledger columns:
+    ///
+    /// - every slot has both a block time and a blockhash and there are no
+    ///   gaps between slots
+    /// - every transaction of a slot has been stored together with its status
+    /// - every address signature resolves to a transaction of its slot
+    ///
+    /// Data of slots that were already truncated is not checked, neither is
+    /// data of the slots after the last block, since transactions of the open
+    /// slot are written while the verification runs.
+    pub fn verify(&self) -> LedgerResult<LedgerVerifyReport> {
+        let (lowest_cleanup_slot, lowest_available_slot) =
+            self.ensure_lowest_cleanup_slot();
+        // Fresh ledgers haven't cleaned up anything, including slot 0
+        let from_slot = if *lowest_cleanup_slot == 0 {
+            0
+        } else {
+            lowest_available_slot
+        };
+        let mut report = LedgerVerifyReport::default();
+
+        // 1. Blocks
+        let mut blocktimes = self
+            .blocktime_cf
+            .iter(IteratorMode::From(from_slot, IteratorDirection::Forward))?
+            .map(|(slot, _)| slot)
+            .peekable();
+        let mut blockhashes = self
+            .blockhash_cf
+            .iter(IteratorMode::From(from_slot, IteratorDirection::Forward))?
+            .map(|(slot, _)| slot)
+            .peekable();
+        while let Some((slot, has_blocktime, has_blockhash)) =
+            next_block_slot(&mut blocktimes, &mut blockhashes)
+        {
+            match (has_blocktime, has_blockhash) {
+                (true, true) => report.num_blocks += 1,
+                (true, false) => report
+                    .anomalies
+                    .push(LedgerAnomaly::MissingBlockhash { slot }),
+                (false, _) => report
+                    .anomalies
+                    .push(LedgerAnomaly::MissingBlocktime { slot }),
+            }
+            match report.last_slot {
+                Some(last_slot) if slot > last_slot + 1 => {
+                    report.anomalies.push(LedgerAnomaly::MissingSlots {
+                        from_slot: last_slot + 1,
+                        to_slot: slot - 1,
+                    })
+                }
+                _ => {}
+            }
+            report.first_slot.get_or_insert(slot);
+            report.last_slot = Some(slot);
+        }
+
+        // 2. Transactions
+        for ((slot, _), raw_signature) in
+            self.slot_signatures_cf.iter(IteratorMode::From(
+                (from_slot, u32::MIN),
+                IteratorDirection::Forward,
+            ))?
+        {
+            if is_open_slot(&report, slot) {
+                report.num_open_slot_transactions += 1;
+                continue;
+            }
+            let signature = Signature::try_from(raw_signature.as_ref())?;
+            report.num_transactions += 1;
+
+            if self.transaction_cf.get_bytes((signature, slot))?.is_none() {
+                report.anomalies.push(LedgerAnomaly::MissingTransaction {
+                    slot,
+                    signature,
+                });
+            }
+            if self
+                .transaction_status_cf
+                .get_bytes((signature, slot))?
+                .is_none()
+            {
+                report.anomalies.push(
+                    LedgerAnomaly::MissingTransactionStatus { slot, signature },
+                );
+            }
+        }
+
+        // 3. Address signatures
+        for ((address, slot, transaction_index, signature), _) in
+            self.address_signatures_cf.iter(IteratorMode::Start)?
+        {
+            if slot < from_slot || is_open_slot(&report, slot) {
+                continue;
+            }
+            report.num_address_signatures += 1;
+
+            let slot_signature =
+                self.slot_signatures_cf.get((slot, transaction_index))?;
+            if slot_signature != Some(signature) {
+                report.anomalies.push(
+                    LedgerAnomaly::UnresolvedAddressSignature {
+                        address,
+                        slot,
+                        transaction_index,
+                        signature,
+                    },
+                );
+            }
+        }
+
+        report.anomalies.sort_by_key(LedgerAnomaly::slot);
+        Ok(report)
+    }
+
+    /// Removes all data of `from_slot` and the slots following it.
+    /// This is used to drop broken trailing slots, i.e. left behind after a
+    /// crash, see [LedgerVerifyReport::first_broken_slot].
+    /// Unlike [Ledger::delete_slot_range] it tolerates missing entries and
+    /// keeps the lowest cleanup slot unchanged.
+    pub fn drop_slots_from(&self, from_slot: Slot) -> LedgerResult<()> {
+        // Prevent concurrent reads and truncation while dropping
+        let _lock = self
+            .lowest_cleanup_slot
+            .write()
+            .expect(Self::LOWEST_CLEANUP_SLOT_POISONED);
+        let mut batch = self.db.batch();
+
+        self.blocktime_cf.delete_range_in_batch(
+            &mut batch,
+            from_slot,
+            Slot::MAX,
+        );
+        self.blockhash_cf.delete_range_in_batch(
+            &mut batch,
+            from_slot,
+            Slot::MAX,
+        );
+        self.perf_samples_cf.delete_range_in_batch(
+            &mut batch,
+            from_slot,
+            Slot::MAX,
+        );
+        self.accounts_hash_cf.delete_range_in_batch(
+            &mut batch,
+            from_slot,
+            Slot::MAX,
+        );
+        self.commit_records_cf.delete_range_in_batch(
+            &mut batch,
+            (from_slot, u64::MIN),
+            (Slot::MAX, u64::MIN),
+        );
+        self.mutation_audit_entries_cf.delete_range_in_batch(
+            &mut batch,
+            (from_slot, u64::MIN),
+            (Slot::MAX, u64::MIN),
+        );
+
+        let mut num_transactions = 0;
+        for ((slot, transaction_index), raw_signature) in
+            self.slot_signatures_cf.iter(IteratorMode::From(
+                (from_slot, u32::MIN),
+                IteratorDirection::Forward,
+            ))?
+        {
+            self.slot_signatures_cf
+                .delete_in_batch(&mut batch, (slot, transaction_index));
+
+            let signature = Signature::try_from(raw_signature.as_ref())?;
+            self.transaction_status_cf
+                .delete_in_batch(&mut batch, (signature, slot));
+            self.transaction_cf
+                .delete_in_batch(&mut batch, (signature, slot));
+            self.transaction_memos_cf
+                .delete_in_batch(&mut batch, (signature, slot));
+            num_transactions += 1;
+        }
+
+        // Address signatures are written before the transaction itself, so
+        // they need to be found without relying on the stored transaction
+        for (index, _) in
+            self.address_signatures_cf.iter(IteratorMode::Start)?
+        {
+            if index.1 >= from_slot {
+                self.address_signatures_cf
+                    .delete_in_batch(&mut batch, index);
+            }
+        }
+
+        self.db.write(batch)?;
+
+        // Dropped entries might not have been complete, thus we recount
+        self.blocktime_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.blockhash_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.perf_samples_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.slot_signatures_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.transaction_status_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.transaction_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.transaction_memos_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.address_signatures_cf
+            .entry_counter
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.transaction_successful_status_count
+            .store(DIRTY_COUNT, Ordering::Release);
+        self.transaction_failed_status_count
+            .store(DIRTY_COUNT, Ordering::Release);
+
+        info!(
+            "Dropped slots starting at {} including {} transactions",
+            from_slot, num_transactions
+        );
+        Ok(())
+    }
+}
+
+/// Slots after the last written block haven't been completed yet, their data
+/// is still being written
+fn is_open_slot(report: &LedgerVerifyReport, slot: Slot) -> bool {
+    report.last_slot.map_or(true, |last_slot| slot > last_slot)
+}
+
+/// Merges the slots of the block time and blockhash columns, returning the
+/// next slot and whether it is present in either of them
+fn next_block_slot(
+    blocktimes: &mut Peekable<impl Iterator<Item = Slot>>,
+    blockhashes: &mut Peekable<impl Iterator<Item = Slot>>,
+) -> Option<(Slot, bool, bool)> {
+    let slot = match (blocktimes.peek(), blockhashes.peek()) {
+        (Some(blocktime_slot), Some(blockhash_slot)) => {
+            *blocktime_slot.min(blockhash_slot)
+        }
+        (Some(slot), None) | (None, Some(slot)) => *slot,
+        (None, None) => return None,
+    };
+    let has_blocktime = blocktimes.next_if_eq(&slot).is_some();
+    let has_blockhash = blockhashes.next_if_eq(&slot).is_some();
+    Some((slot, has_blocktime, has_blockhash))
+}
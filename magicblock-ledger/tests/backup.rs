@@ -0,0 +1,36 @@
+mod common;
+
+use magicblock_ledger::Ledger;
+use solana_sdk::hash::Hash;
+use tempfile::TempDir;
+use test_tools_core::init_logger;
+
+use crate::common::{
+    get_block, get_block_transaction_hash, setup, write_dummy_transaction,
+};
+
+#[test]
+fn test_backup_while_writing() {
+    init_logger!();
+
+    let ledger = setup();
+    let (tx_hash, _) = write_dummy_transaction(&ledger, 0, 0);
+    let blockhash = Hash::new_unique();
+    ledger.write_block(0, 100, blockhash).unwrap();
+
+    let backup_dir = TempDir::new().unwrap();
+    ledger.create_backup(backup_dir.path()).unwrap();
+
+    // Writes after the backup was created are not included in it
+    ledger.write_block(1, 101, Hash::new_unique()).unwrap();
+
+    let backup = Ledger::open(backup_dir.path()).unwrap();
+    assert_eq!(backup.get_max_blockhash().unwrap(), (0, blockhash));
+    let block = get_block(&backup, 0);
+    assert_eq!(block.transactions.len(), 1);
+    assert_eq!(get_block_transaction_hash(&block, 0), tx_hash);
+    assert!(backup.get_block(1).unwrap().is_none());
+
+    // Backups are never overwritten
+    assert!(ledger.create_backup(backup_dir.path()).is_err());
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::{
+    fs,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use magicblock_ledger::{
+    block_record::decode_block_records,
+    ledger_tailer::{FileTailSink, LedgerTailer},
+};
+use solana_sdk::hash::Hash;
+use tempfile::TempDir;
+use test_tools_core::init_logger;
+
+use crate::common::{setup, write_dummy_transaction};
+
+#[test]
+fn test_tailer_streams_new_blocks_to_file() {
+    init_logger!();
+
+    let ledger = Arc::new(setup());
+    // Blocks written before the tailer started are not streamed
+    ledger.write_block(0, 100, Hash::new_unique()).unwrap();
+
+    let export_dir = TempDir::new().unwrap();
+    let export_path = export_dir.path().join("blocks");
+    let sink = Arc::new(FileTailSink::new(&export_path).unwrap());
+    let mut tailer =
+        LedgerTailer::new(ledger.clone(), sink, Duration::from_millis(10));
+    let exit = Arc::<AtomicBool>::default();
+    tailer.start(exit.clone()).unwrap();
+
+    write_dummy_transaction(&ledger, 1, 0);
+    let blockhash_1 = Hash::new_unique();
+    ledger.write_block(1, 101, blockhash_1).unwrap();
+    let blockhash_2 = Hash::new_unique();
+    ledger.write_block(2, 102, blockhash_2).unwrap();
+
+    let started = Instant::now();
+    let blocks = loop {
+        let records = fs::read(&export_path).unwrap();
+        // The tailer might be in the middle of appending a record
+        let blocks = decode_block_records(&records).unwrap_or_default();
+        if blocks.len() == 2 || started.elapsed() > Duration::from_secs(5) {
+            break blocks;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    exit.store(true, Ordering::Relaxed);
+    tailer.join();
+
+    assert_eq!(
+        blocks.iter().map(|(slot, _)| *slot).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    let (_, block_1) = &blocks[0];
+    assert_eq!(block_1.blockhash, blockhash_1.to_string());
+    assert_eq!(block_1.block_time, Some(101));
+    assert_eq!(block_1.transactions.len(), 1);
+    let (_, block_2) = &blocks[1];
+    assert_eq!(block_2.blockhash, blockhash_2.to_string());
+    assert!(block_2.transactions.is_empty());
+}
@@ -4,11 +4,15 @@ use std::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use magicblock_core::traits::FinalityProvider;
-use magicblock_ledger::{ledger_truncator::LedgerTruncator, Ledger};
+use magicblock_ledger::{
+    ledger_archiver::{read_archive, FileArchiveSink, LedgerArchiver},
+    ledger_truncator::{LedgerRetention, LedgerTruncator},
+    Ledger,
+};
 use solana_sdk::{hash::Hash, signature::Signature};
 
 use crate::common::{setup, write_dummy_transaction};
@@ -69,6 +73,8 @@ async fn test_truncator_not_purged_finality() {
         Arc::new(finality_provider),
         TEST_TRUNCATION_TIME_INTERVAL,
         0,
+        LedgerRetention::default(),
+        None,
     );
 
     for i in 0..SLOT_TRUNCATION_INTERVAL {
@@ -108,6 +114,8 @@ async fn test_truncator_not_purged_size() {
         Arc::new(finality_provider),
         TEST_TRUNCATION_TIME_INTERVAL,
         1 << 30, // 1 GB
+        LedgerRetention::default(),
+        None,
     );
 
     for i in 0..NUM_TRANSACTIONS {
@@ -155,6 +163,8 @@ async fn test_truncator_non_empty_ledger() {
         finality_provider,
         TEST_TRUNCATION_TIME_INTERVAL,
         0,
+        LedgerRetention::default(),
+        None,
     );
 
     ledger_truncator.start();
@@ -179,6 +189,202 @@ async fn test_truncator_non_empty_ledger() {
     );
 }
 
+fn write_slots(
+    ledger: &Ledger,
+    slots: std::ops::Range<u64>,
+    block_time: i64,
+) -> Vec<Signature> {
+    slots
+        .map(|i| {
+            let (_, signature) = write_dummy_transaction(ledger, i, 0);
+            ledger
+                .write_block(i, block_time, Hash::new_unique())
+                .unwrap();
+            signature
+        })
+        .collect()
+}
+
+fn now_in_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+// Tests that slots older than the retained slot count are truncated
+// even though the ledger is well below its size cap
+#[tokio::test]
+async fn test_truncator_retention_slots() {
+    const FINAL_SLOT: u64 = 80;
+    const RETAINED_SLOTS: u64 = 50;
+
+    let ledger = Arc::new(setup());
+    let signatures = write_slots(&ledger, 0..100, now_in_secs());
+
+    let finality_provider = Arc::new(TestFinalityProvider {
+        latest_final_slot: FINAL_SLOT.into(),
+    });
+    let mut ledger_truncator = LedgerTruncator::new(
+        ledger.clone(),
+        finality_provider,
+        TEST_TRUNCATION_TIME_INTERVAL,
+        1 << 30, // 1 GB
+        LedgerRetention {
+            max_slots: Some(RETAINED_SLOTS),
+            max_age: None,
+        },
+        None,
+    );
+
+    ledger_truncator.start();
+    tokio::time::sleep(TEST_TRUNCATION_TIME_INTERVAL).await;
+    ledger_truncator.stop();
+    assert!(ledger_truncator.join().await.is_ok());
+
+    // Latest slot is 99, so slots [0; 49] are outside of retention
+    assert_eq!(ledger.get_lowest_cleanup_slot(), 49);
+    verify_transactions_state(&ledger, 0, &signatures[..50], false);
+    verify_transactions_state(&ledger, 50, &signatures[50..], true);
+}
+
+// Tests that slots with block times older than the retention age are truncated
+#[tokio::test]
+async fn test_truncator_retention_age() {
+    const FINAL_SLOT: u64 = 80;
+
+    let ledger = Arc::new(setup());
+    let mut signatures = write_slots(&ledger, 0..40, 0);
+    signatures.extend(write_slots(&ledger, 40..100, now_in_secs()));
+
+    let finality_provider = Arc::new(TestFinalityProvider {
+        latest_final_slot: FINAL_SLOT.into(),
+    });
+    let mut ledger_truncator = LedgerTruncator::new(
+        ledger.clone(),
+        finality_provider,
+        TEST_TRUNCATION_TIME_INTERVAL,
+        1 << 30, // 1 GB
+        LedgerRetention {
+            max_slots: None,
+            max_age: Some(Duration::from_secs(60 * 60)),
+        },
+        None,
+    );
+
+    ledger_truncator.start();
+    tokio::time::sleep(TEST_TRUNCATION_TIME_INTERVAL).await;
+    ledger_truncator.stop();
+    assert!(ledger_truncator.join().await.is_ok());
+
+    assert_eq!(ledger.get_lowest_cleanup_slot(), 39);
+    verify_transactions_state(&ledger, 0, &signatures[..40], false);
+    verify_transactions_state(&ledger, 40, &signatures[40..], true);
+}
+
+// Tests that retention never truncates slots at or after the final slot,
+// i.e. the oldest retained snapshot
+#[tokio::test]
+async fn test_truncator_retention_respects_final_slot() {
+    const FINAL_SLOT: u64 = 30;
+
+    let ledger = Arc::new(setup());
+    let signatures = write_slots(&ledger, 0..100, 0);
+
+    let finality_provider = Arc::new(TestFinalityProvider {
+        latest_final_slot: FINAL_SLOT.into(),
+    });
+    let mut ledger_truncator = LedgerTruncator::new(
+        ledger.clone(),
+        finality_provider,
+        TEST_TRUNCATION_TIME_INTERVAL,
+        1 << 30, // 1 GB
+        LedgerRetention {
+            max_slots: Some(10),
+            max_age: Some(Duration::from_secs(1)),
+        },
+        None,
+    );
+
+    ledger_truncator.start();
+    tokio::time::sleep(TEST_TRUNCATION_TIME_INTERVAL).await;
+    ledger_truncator.stop();
+    assert!(ledger_truncator.join().await.is_ok());
+
+    assert_eq!(ledger.get_lowest_cleanup_slot(), FINAL_SLOT - 1);
+    verify_transactions_state(
+        &ledger,
+        0,
+        &signatures[..FINAL_SLOT as usize],
+        false,
+    );
+    verify_transactions_state(
+        &ledger,
+        FINAL_SLOT,
+        &signatures[FINAL_SLOT as usize..],
+        true,
+    );
+}
+
+// Tests that truncated slots are archived and can be reconstructed
+#[tokio::test]
+async fn test_truncator_archives_truncated_slots() {
+    const FINAL_SLOT: u64 = 80;
+
+    let ledger = Arc::new(setup());
+    let message_hashes = (0..100)
+        .map(|i| {
+            let (message_hash, _) = write_dummy_transaction(&ledger, i, 0);
+            ledger.write_block(i, 0, Hash::new_unique()).unwrap();
+            message_hash
+        })
+        .collect::<Vec<_>>();
+
+    let archive_dir = tempfile::tempdir().unwrap();
+    let sink = FileArchiveSink::new(archive_dir.path()).unwrap();
+    let archiver = LedgerArchiver::new(Arc::new(sink), 3);
+
+    let finality_provider = Arc::new(TestFinalityProvider {
+        latest_final_slot: FINAL_SLOT.into(),
+    });
+    let mut ledger_truncator = LedgerTruncator::new(
+        ledger.clone(),
+        finality_provider,
+        TEST_TRUNCATION_TIME_INTERVAL,
+        0,
+        LedgerRetention::default(),
+        Some(Arc::new(archiver)),
+    );
+
+    ledger_truncator.start();
+    tokio::time::sleep(TEST_TRUNCATION_TIME_INTERVAL).await;
+    ledger_truncator.stop();
+    assert!(ledger_truncator.join().await.is_ok());
+
+    let cleanup_slot = ledger.get_lowest_cleanup_slot();
+    assert_ne!(cleanup_slot, 0);
+
+    let mut archived = std::fs::read_dir(archive_dir.path())
+        .unwrap()
+        .flat_map(|entry| {
+            let archive = std::fs::read(entry.unwrap().path()).unwrap();
+            read_archive(&archive).unwrap()
+        })
+        .collect::<Vec<_>>();
+    archived.sort_by_key(|(slot, _)| *slot);
+
+    // Every truncated slot was archived with its transaction
+    assert_eq!(archived.len() as u64, cleanup_slot + 1);
+    for (expected_slot, (slot, block)) in archived.iter().enumerate() {
+        assert_eq!(*slot, expected_slot as u64);
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(
+            block.transactions[0].get_transaction().message.hash(),
+            message_hashes[expected_slot]
+        );
+    }
+}
+
 async fn transaction_spammer(
     ledger: Arc<Ledger>,
     finality_provider: Arc<TestFinalityProvider>,
@@ -217,6 +423,8 @@ async fn test_truncator_with_tx_spammer() {
         finality_provider.clone(),
         TEST_TRUNCATION_TIME_INTERVAL,
         0,
+        LedgerRetention::default(),
+        None,
     );
 
     ledger_truncator.start();
@@ -286,6 +494,8 @@ async fn test_with_1gb_db() {
         finality_provider.clone(),
         TEST_TRUNCATION_TIME_INTERVAL,
         DB_SIZE,
+        LedgerRetention::default(),
+        None,
     );
 
     ledger_truncator.start();
@@ -0,0 +1,98 @@
+mod common;
+
+use magicblock_ledger::LedgerAnomaly;
+use solana_sdk::hash::Hash;
+use test_tools_core::init_logger;
+
+use crate::common::{setup, write_dummy_transaction};
+
+#[test]
+fn test_verify_consistent_ledger() {
+    init_logger!();
+
+    let ledger = setup();
+    for slot in 0..10 {
+        write_dummy_transaction(&ledger, slot, 0);
+        write_dummy_transaction(&ledger, slot, 1);
+        ledger
+            .write_block(slot, slot as i64, Hash::new_unique())
+            .unwrap();
+    }
+
+    let report = ledger.verify().unwrap();
+    assert!(report.is_consistent(), "{:?}", report.anomalies);
+    assert_eq!(report.first_slot, Some(0));
+    assert_eq!(report.last_slot, Some(9));
+    assert_eq!(report.num_blocks, 10);
+    assert_eq!(report.num_transactions, 20);
+    assert_eq!(report.first_broken_slot(), None);
+}
+
+#[test]
+fn test_verify_detects_missing_slots() {
+    init_logger!();
+
+    let ledger = setup();
+    for slot in [0, 1, 5, 6] {
+        ledger
+            .write_block(slot, slot as i64, Hash::new_unique())
+            .unwrap();
+    }
+
+    let report = ledger.verify().unwrap();
+    assert_eq!(
+        report.anomalies,
+        vec![LedgerAnomaly::MissingSlots {
+            from_slot: 2,
+            to_slot: 4
+        }]
+    );
+    assert_eq!(report.first_broken_slot(), Some(2));
+}
+
+#[test]
+fn test_verify_ignores_open_slot() {
+    init_logger!();
+
+    let ledger = setup();
+    for slot in 0..5 {
+        write_dummy_transaction(&ledger, slot, 0);
+        ledger
+            .write_block(slot, slot as i64, Hash::new_unique())
+            .unwrap();
+    }
+    // Slot 5 is still open, its block wasn't written yet
+    write_dummy_transaction(&ledger, 5, 0);
+    write_dummy_transaction(&ledger, 5, 1);
+
+    let report = ledger.verify().unwrap();
+    assert!(report.is_consistent(), "{:?}", report.anomalies);
+    assert_eq!(report.last_slot, Some(4));
+    assert_eq!(report.num_transactions, 5);
+    assert_eq!(report.num_open_slot_transactions, 2);
+}
+
+#[test]
+fn test_verify_and_drop_broken_trailing_slots() {
+    init_logger!();
+
+    let ledger = setup();
+    for slot in [0, 1, 2, 5, 6] {
+        write_dummy_transaction(&ledger, slot, 0);
+        ledger
+            .write_block(slot, slot as i64, Hash::new_unique())
+            .unwrap();
+    }
+
+    let report = ledger.verify().unwrap();
+    assert_eq!(report.first_broken_slot(), Some(3));
+
+    ledger.drop_slots_from(3).unwrap();
+
+    let report = ledger.verify().unwrap();
+    assert!(report.is_consistent(), "{:?}", report.anomalies);
+    assert_eq!(report.last_slot, Some(2));
+    assert_eq!(report.num_transactions, 3);
+    assert_eq!(ledger.count_slot_signatures().unwrap(), 3);
+    assert!(ledger.get_block(2).unwrap().is_some());
+}
@@ -0,0 +1,53 @@
+use std::{collections::BTreeMap, sync::RwLock};
+
+/// Probes the health of a component when the readiness is requested
+pub type ReadinessProbe = Box<dyn Fn() -> Result<(), String> + Send + Sync>;
+
+enum ReadinessCheck {
+    /// Health last reported by the component
+    Reported(Result<(), String>),
+    Probe(ReadinessProbe),
+}
+
+lazy_static::lazy_static! {
+    /// Checks that decide if the validator is ready to serve traffic, each
+    /// one keyed by the name of the component it checks
+    static ref READINESS_CHECKS: RwLock<BTreeMap<&'static str, ReadinessCheck>> =
+        RwLock::new(BTreeMap::new());
+}
+
+/// Reports the health of the component, i.e. after an operation against a
+/// remote dependency failed or succeeded again
+pub fn set_readiness(component: &'static str, result: Result<(), String>) {
+    READINESS_CHECKS
+        .write()
+        .expect("RwLock READINESS_CHECKS poisoned")
+        .insert(component, ReadinessCheck::Reported(result));
+}
+
+/// Registers a probe that is run each time the readiness is requested
+pub fn register_readiness_probe(
+    component: &'static str,
+    probe: impl Fn() -> Result<(), String> + Send + Sync + 'static,
+) {
+    READINESS_CHECKS
+        .write()
+        .expect("RwLock READINESS_CHECKS poisoned")
+        .insert(component, ReadinessCheck::Probe(Box::new(probe)));
+}
+
+/// Runs all checks, returns the result of each one ordered by component
+pub fn check_readiness() -> Vec<(&'static str, Result<(), String>)> {
+    READINESS_CHECKS
+        .read()
+        .expect("RwLock READINESS_CHECKS poisoned")
+        .iter()
+        .map(|(component, check)| {
+            let result = match check {
+                ReadinessCheck::Reported(result) => result.clone(),
+                ReadinessCheck::Probe(probe) => probe(),
+            };
+            (*component, result)
+        })
+        .collect()
+}
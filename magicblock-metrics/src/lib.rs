@@ -1,4 +1,7 @@
+pub mod health;
 pub mod metrics;
+mod otlp;
 mod service;
 
+pub use otlp::OtlpTracing;
 pub use service::{try_start_metrics_service, MetricsService};
@@ -1,11 +1,11 @@
-use std::sync::Once;
+use std::{sync::Once, time::Duration};
 
 pub use prometheus::HistogramTimer;
 use prometheus::{
-    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
-    Opts, Registry,
+    exponential_buckets, Histogram, HistogramOpts, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
 };
-pub use types::{AccountClone, AccountCommit, Outcome};
+pub use types::{endpoint_label, AccountClone, AccountCommit, Outcome};
 mod types;
 
 // -----------------
@@ -55,11 +55,60 @@ lazy_static::lazy_static! {
         "fee_count", "Fee Count",
     ).unwrap();
 
+    static ref PROGRAM_CACHE_HITS_COUNT: IntCounter = IntCounter::new(
+        "program_cache_hits_count", "Count of invoked programs that were compiled already",
+    ).unwrap();
+
+    static ref PROGRAM_CACHE_MISSES_COUNT: IntCounter = IntCounter::new(
+        "program_cache_misses_count", "Count of invoked programs that had to be loaded and compiled",
+    ).unwrap();
+
+    static ref PROGRAM_CACHE_EVICTIONS_COUNT: IntCounter = IntCounter::new(
+        "program_cache_evictions_count", "Count of compiled programs evicted from the program cache",
+    ).unwrap();
+
+    static ref PROGRAM_EXECUTED_UNITS_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("program_executed_units", "Executed Units (CU) of transactions invoking specific programs")
+            .buckets(exponential_buckets(1_000.0, 2.0, 11).unwrap()),
+        &["program_id"],
+    ).unwrap();
+
+    static ref TRANSACTION_HEAP_SIZE_HISTOGRAM: Histogram = Histogram::with_opts(
+        HistogramOpts::new("transaction_heap_size", "Heap frame size in bytes available to each transaction")
+            .buckets(exponential_buckets(32.0 * 1024.0, 2.0, 4).unwrap()),
+    ).unwrap();
+
+    static ref TRANSACTION_LOADED_ACCOUNTS_DATA_SIZE_HISTOGRAM: Histogram = Histogram::with_opts(
+        HistogramOpts::new("transaction_loaded_accounts_data_size", "Size in bytes of the account data loaded by each transaction")
+            .buckets(exponential_buckets(1024.0, 4.0, 8).unwrap()),
+    ).unwrap();
+
     static ref ACCOUNT_CLONE_VEC_COUNT: IntCounterVec = IntCounterVec::new(
         Opts::new("account_clone_count", "Count clones performed for specific accounts"),
         &["kind", "pubkey", "owner"],
     ).unwrap();
 
+    static ref ACCOUNT_CLONE_REQUESTS_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("account_clone_requests_count", "Count of requests to clone an account"),
+        &["endpoint"],
+    ).unwrap();
+
+    static ref ACCOUNT_CLONE_CACHE_HITS_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("account_clone_cache_hits_count", "Count of clone requests served from the output of a previous clone"),
+        &["endpoint"],
+    ).unwrap();
+
+    static ref ACCOUNT_CLONE_TIME_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("account_clone_time", "Time spent fetching and cloning an account")
+            .buckets(
+                MILLIS_1_9.iter().chain(
+                MILLIS_10_90.iter()).chain(
+                MILLIS_100_900.iter()).chain(
+                SECONDS_1_9.iter()).cloned().collect()
+            ),
+        &["endpoint", "outcome"],
+    ).unwrap();
+
     static ref ACCOUNT_COMMIT_VEC_COUNT: IntCounterVec = IntCounterVec::new(
         Opts::new("account_commit_count", "Count commits performed for specific accounts"),
         &["kind", "pubkey", "outcome"],
@@ -75,6 +124,48 @@ lazy_static::lazy_static! {
             ),
     ).unwrap();
 
+    static ref ACCOUNT_COMMIT_CONFIRMATION_TIME_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("account_commit_confirmation_time", "Time from sending a commit transaction until it is confirmed or given up on")
+            .buckets(
+                MILLIS_10_90.iter().chain(
+                MILLIS_100_900.iter()).chain(
+                SECONDS_1_9.iter()).chain(
+                SECONDS_10_19.iter()).cloned().collect()
+            ),
+        &["endpoint", "outcome"],
+    ).unwrap();
+
+    static ref ACCOUNT_COMMIT_FAILURES_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("account_commit_failures_count", "Count of commit transactions that failed, by the reason they failed"),
+        &["endpoint", "reason"],
+    ).unwrap();
+
+    static ref ACCOUNT_COMMIT_RETRIES_COUNT: IntCounter = IntCounter::new(
+        "account_commit_retries_count", "Count of commit transactions that were sent again since they did not land in time",
+    ).unwrap();
+
+    static ref ACCOUNT_COMMITS_SKIPPED_COUNT: IntCounter = IntCounter::new(
+        "account_commits_skipped_count", "Count of scheduled account commits skipped since the account didn't change since its last commit",
+    ).unwrap();
+
+    static ref ACCOUNT_COMMIT_SIMULATION_FAILURES_COUNT: IntCounter = IntCounter::new(
+        "account_commit_simulation_failures_count", "Count of commit transactions that were not sent since their simulation on the remote cluster failed",
+    ).unwrap();
+
+    static ref COMMITTER_PAYER_BALANCE_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("committer_payer_balance", "Balance in lamports of the payer of commits on the remote cluster"),
+        &["endpoint"],
+    ).unwrap();
+
+    static ref COMMITTER_PAYER_LOW_BALANCE_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("committer_payer_low_balance", "1 while the balance of the payer of commits is below the configured minimum, otherwise 0"),
+        &["endpoint"],
+    ).unwrap();
+
+    static ref COMMITTER_PAYER_TOP_UPS_COUNT: IntCounter = IntCounter::new(
+        "committer_payer_top_ups_count", "Count of transfers from the treasury topping up the payer of commits",
+    ).unwrap();
+
     static ref CACHED_CLONE_OUTPUTS_COUNT: IntGauge = IntGauge::new(
         "magicblock_account_cloner_cached_outputs",
         "Number of cloned accounts in the RemoteAccountClonerWorker"
@@ -176,6 +267,18 @@ lazy_static::lazy_static! {
             ),
     ).unwrap();
 
+    static ref RESUBMITTED_TRANSACTIONS_COUNT: IntCounter = IntCounter::new(
+        "resubmitted_transactions_count", "Count of sent transactions that were processed before and thus got their original result",
+    ).unwrap();
+
+    static ref SLOW_TRANSACTIONS_COUNT: IntCounter = IntCounter::new(
+        "slow_transactions_count", "Count of transactions whose execution exceeded the configured threshold",
+    ).unwrap();
+
+    static ref TRANSACTIONS_BELOW_FEE_FLOOR_COUNT: IntCounter = IntCounter::new(
+        "transactions_below_fee_floor_count", "Count of sent transactions rejected for paying less than the configured fee floor",
+    ).unwrap();
+
     static ref FLUSH_ACCOUNTS_TIME_HISTOGRAM: Histogram = Histogram::with_opts(
         HistogramOpts::new("flush_accounts_time", "Time spent flushing accounts to disk")
             .buckets(
@@ -195,10 +298,217 @@ lazy_static::lazy_static! {
         &["shard"],
     ).unwrap();
 
+    static ref ACCOUNT_UPDATES_COUNT: IntCounter = IntCounter::new(
+        "account_updates_count", "Count of account updates received via websocket subscriptions",
+    ).unwrap();
+
+    static ref ACCOUNT_UPDATES_COALESCED_COUNT: IntCounter = IntCounter::new(
+        "account_updates_coalesced_count", "Count of account updates dropped since a more recent update of the same account arrived before they were applied",
+    ).unwrap();
+
     static ref EVICTED_ACCOUNTS_COUNT: IntGauge = IntGauge::new(
         "evicted_accounts", "number of accounts forcefully removed from monitored list and database",
     ).unwrap();
 
+    static ref REMOTE_SLOT_GAUGE: IntGauge = IntGauge::new(
+        "remote_slot", "Latest slot of the remote cluster observed via subscriptions",
+    ).unwrap();
+
+    static ref SUBSCRIPTION_LAG_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("subscription_lag", "Slots a subscription shard is behind the latest observed remote slot"),
+        &["shard"],
+    ).unwrap();
+
+    static ref PENDING_COMMITS_GAUGE: IntGauge = IntGauge::new(
+        "pending_commits", "Number of commits sent to the remote cluster that are not confirmed yet",
+    ).unwrap();
+
+    static ref SCHEDULED_COMMITS_GAUGE: IntGauge = IntGauge::new(
+        "scheduled_commits", "Number of commits that were scheduled, but not sent to the remote cluster yet",
+    ).unwrap();
+
+    static ref REVALIDATED_ACCOUNTS_COUNT: IntCounter = IntCounter::new(
+        "revalidated_accounts_count", "Count of cloned non-delegated accounts fetched again since they were cloned too long ago",
+    ).unwrap();
+
+    static ref INVALID_DELEGATIONS_COUNT: IntCounter = IntCounter::new(
+        "invalid_delegations_count", "Count of delegated accounts not cloned since their delegation failed verification",
+    ).unwrap();
+
+    static ref REVOKED_DELEGATIONS_COUNT: IntCounter = IntCounter::new(
+        "revoked_delegations_count", "Count of delegated accounts frozen since their delegation was revoked on chain",
+    ).unwrap();
+
+    // -----------------
+    // Remote Fetch
+    // -----------------
+    static ref REMOTE_FETCH_TIME_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("remote_fetch_time", "Time spent fetching accounts from remote endpoints")
+            .buckets(
+                MILLIS_1_9.iter().chain(
+                MILLIS_10_90.iter()).chain(
+                MILLIS_100_900.iter()).chain(
+                SECONDS_1_9.iter()).cloned().collect()
+            ),
+        &["endpoint", "outcome"],
+    ).unwrap();
+
+    static ref REMOTE_FETCH_FAILOVER_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("remote_fetch_failover_count", "Count of fetches that failed over to another endpoint"),
+        &["endpoint"],
+    ).unwrap();
+
+    static ref REMOTE_FETCH_THROTTLED_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("remote_fetch_throttled_count", "Count of fetches that were throttled by the remote endpoint"),
+        &["endpoint"],
+    ).unwrap();
+
+    static ref REMOTE_ENDPOINT_HEALTHY_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("remote_endpoint_healthy", "Whether the remote endpoint is considered healthy (1) or not (0) for its role, i.e. clone or commit"),
+        &["role", "endpoint"],
+    ).unwrap();
+
+    static ref REMOTE_ENDPOINT_FAILOVERS_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("remote_endpoint_failovers_count", "Count of requests that failed over to another remote endpoint of the same role"),
+        &["role"],
+    ).unwrap();
+
+    static ref REMOTE_FETCH_CIRCUIT_OPEN_GAUGE: IntGauge = IntGauge::new(
+        "remote_fetch_circuit_open", "Whether fetches fail fast (1) since all remote endpoints are down or not (0)",
+    ).unwrap();
+
+    static ref REMOTE_FETCH_SHORT_CIRCUITED_COUNT: IntCounter = IntCounter::new(
+        "remote_fetch_short_circuited_count", "Count of fetches that failed fast without reaching the remote since it is down",
+    ).unwrap();
+
+    // -----------------
+    // Remote Pubsub
+    // -----------------
+    static ref REMOTE_PUBSUB_REQUEST_TIME_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("remote_pubsub_request_time", "Time spent connecting and subscribing to remote websocket endpoints")
+            .buckets(
+                MILLIS_1_9.iter().chain(
+                MILLIS_10_90.iter()).chain(
+                MILLIS_100_900.iter()).chain(
+                SECONDS_1_9.iter()).cloned().collect()
+            ),
+        &["endpoint", "request", "outcome"],
+    ).unwrap();
+
+    // -----------------
+    // RPC
+    // -----------------
+    static ref RPC_REQUESTS_VEC_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("rpc_requests_count", "Count of RPC requests per method"),
+        &["method"],
+    ).unwrap();
+
+    static ref RPC_REQUEST_TIME_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("rpc_request_time", "Time spent handling RPC requests per method")
+            .buckets(
+                MICROS_10_90.iter().chain(
+                MICROS_100_900.iter()).chain(
+                MILLIS_1_9.iter()).chain(
+                MILLIS_10_90.iter()).chain(
+                MILLIS_100_900.iter()).chain(
+                SECONDS_1_9.iter()).cloned().collect()
+            ),
+        &["method"],
+    ).unwrap();
+
+    static ref RPC_REQUEST_SIZE_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("rpc_request_size", "Size of RPC request payloads per method in bytes")
+            // 64B .. 1MiB
+            .buckets(exponential_buckets(64.0, 4.0, 8).unwrap()),
+        &["method"],
+    ).unwrap();
+
+    static ref RPC_RESPONSE_SIZE_HISTOGRAM: HistogramVec = HistogramVec::new(
+        HistogramOpts::new("rpc_response_size", "Size of RPC response payloads per method in bytes")
+            // 64B .. 1MiB
+            .buckets(exponential_buckets(64.0, 4.0, 8).unwrap()),
+        &["method"],
+    ).unwrap();
+
+    static ref SLOW_RPC_REQUESTS_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("slow_rpc_requests_count", "Count of RPC requests whose handling exceeded the configured threshold"),
+        &["method"],
+    ).unwrap();
+
+    static ref RPC_ERRORS_VEC_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("rpc_errors_count", "Count of RPC errors per method and error code"),
+        &["method", "code"],
+    ).unwrap();
+
+    // -----------------
+    // Geyser gRPC
+    // -----------------
+    static ref GEYSER_GRPC_CLIENT_QUEUE_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("geyser_grpc_client_queue", "Updates queued for a gRPC subscriber that it didn't receive yet"),
+        &["client"],
+    ).unwrap();
+
+    static ref GEYSER_GRPC_CLIENT_DROPPED_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("geyser_grpc_client_dropped_count", "Count of updates dropped since the queue of a gRPC subscriber was full"),
+        &["client"],
+    ).unwrap();
+
+    static ref GEYSER_GRPC_SLOW_CLIENT_DISCONNECTS_COUNT: IntCounter = IntCounter::new(
+        "geyser_grpc_slow_client_disconnects_count", "Count of gRPC subscribers disconnected since they didn't keep up with the updates",
+    ).unwrap();
+
+    // -----------------
+    // Disk
+    // -----------------
+    static ref DISK_USAGE_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("disk_usage", "Bytes used on disk by the ledger, accountsdb and its snapshots"),
+        &["component"],
+    ).unwrap();
+
+    static ref DISK_BUDGET_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("disk_budget", "Bytes the ledger, accountsdb and its snapshots are allowed to use on disk"),
+        &["component"],
+    ).unwrap();
+
+    static ref DISK_FREE_GAUGE: IntGauge = IntGauge::new(
+        "disk_free", "Bytes available on the disk of the ledger",
+    ).unwrap();
+
+    static ref DISK_EMERGENCY_ACTIONS_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("disk_emergency_actions_count", "Count of emergency actions taken since the disk is running full"),
+        &["action"],
+    ).unwrap();
+
+    static ref READ_ONLY_GAUGE: IntGauge = IntGauge::new(
+        "read_only", "1 if the validator rejects transactions since it is running out of disk space",
+    ).unwrap();
+
+    // -----------------
+    // Pubsub
+    // -----------------
+    static ref PUBSUB_CONNECTIONS_GAUGE: IntGauge = IntGauge::new(
+        "pubsub_connections", "Number of open WebSocket connections",
+    ).unwrap();
+
+    static ref PUBSUB_SUBSCRIPTIONS_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("pubsub_subscriptions", "Number of active WebSocket subscriptions by type"),
+        &["subscription"],
+    ).unwrap();
+
+    static ref PUBSUB_NOTIFICATIONS_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("pubsub_notifications_count", "Count of notifications sent to WebSocket subscribers by subscription type"),
+        &["subscription"],
+    ).unwrap();
+
+    static ref PUBSUB_CONNECTION_QUEUE_GAUGE: IntGaugeVec = IntGaugeVec::new(
+        Opts::new("pubsub_connection_queue", "Notifications queued for the subscriptions of a WebSocket connection that weren't sent yet"),
+        &["connection"],
+    ).unwrap();
+
+    static ref PUBSUB_CONNECTION_DROPPED_COUNT: IntCounterVec = IntCounterVec::new(
+        Opts::new("pubsub_connection_dropped_count", "Count of notifications dropped since the subscriptions of a WebSocket connection didn't keep up"),
+        &["connection"],
+    ).unwrap();
 }
 
 pub(crate) fn register() {
@@ -216,9 +526,26 @@ pub(crate) fn register() {
         register!(FEE_PAYER_VEC_COUNT);
         register!(EXECUTED_UNITS_COUNT);
         register!(FEE_COUNT);
+        register!(PROGRAM_CACHE_HITS_COUNT);
+        register!(PROGRAM_CACHE_MISSES_COUNT);
+        register!(PROGRAM_CACHE_EVICTIONS_COUNT);
+        register!(PROGRAM_EXECUTED_UNITS_HISTOGRAM);
+        register!(TRANSACTION_HEAP_SIZE_HISTOGRAM);
+        register!(TRANSACTION_LOADED_ACCOUNTS_DATA_SIZE_HISTOGRAM);
         register!(ACCOUNT_CLONE_VEC_COUNT);
+        register!(ACCOUNT_CLONE_REQUESTS_COUNT);
+        register!(ACCOUNT_CLONE_CACHE_HITS_COUNT);
+        register!(ACCOUNT_CLONE_TIME_HISTOGRAM);
         register!(ACCOUNT_COMMIT_VEC_COUNT);
         register!(ACCOUNT_COMMIT_TIME_HISTOGRAM);
+        register!(ACCOUNT_COMMIT_CONFIRMATION_TIME_HISTOGRAM);
+        register!(ACCOUNT_COMMIT_FAILURES_COUNT);
+        register!(ACCOUNT_COMMIT_RETRIES_COUNT);
+        register!(ACCOUNT_COMMITS_SKIPPED_COUNT);
+        register!(ACCOUNT_COMMIT_SIMULATION_FAILURES_COUNT);
+        register!(COMMITTER_PAYER_BALANCE_GAUGE);
+        register!(COMMITTER_PAYER_LOW_BALANCE_GAUGE);
+        register!(COMMITTER_PAYER_TOP_UPS_COUNT);
         register!(CACHED_CLONE_OUTPUTS_COUNT);
         register!(LEDGER_SIZE_GAUGE);
         register!(LEDGER_BLOCK_TIMES_GAUGE);
@@ -241,10 +568,49 @@ pub(crate) fn register() {
         register!(SIGVERIFY_TIME_HISTOGRAM);
         register!(ENSURE_ACCOUNTS_TIME_HISTOGRAM);
         register!(TRANSACTION_EXECUTION_TIME_HISTORY);
+        register!(RESUBMITTED_TRANSACTIONS_COUNT);
+        register!(SLOW_TRANSACTIONS_COUNT);
+        register!(TRANSACTIONS_BELOW_FEE_FLOOR_COUNT);
         register!(FLUSH_ACCOUNTS_TIME_HISTOGRAM);
         register!(MONITORED_ACCOUNTS_GAUGE);
         register!(SUBSCRIPTIONS_COUNT_GAUGE);
+        register!(ACCOUNT_UPDATES_COUNT);
+        register!(ACCOUNT_UPDATES_COALESCED_COUNT);
         register!(EVICTED_ACCOUNTS_COUNT);
+        register!(REMOTE_SLOT_GAUGE);
+        register!(SUBSCRIPTION_LAG_GAUGE);
+        register!(PENDING_COMMITS_GAUGE);
+        register!(SCHEDULED_COMMITS_GAUGE);
+        register!(REVALIDATED_ACCOUNTS_COUNT);
+        register!(INVALID_DELEGATIONS_COUNT);
+        register!(REVOKED_DELEGATIONS_COUNT);
+        register!(REMOTE_FETCH_TIME_HISTOGRAM);
+        register!(REMOTE_FETCH_FAILOVER_COUNT);
+        register!(REMOTE_FETCH_THROTTLED_COUNT);
+        register!(REMOTE_ENDPOINT_HEALTHY_GAUGE);
+        register!(REMOTE_ENDPOINT_FAILOVERS_COUNT);
+        register!(REMOTE_FETCH_CIRCUIT_OPEN_GAUGE);
+        register!(REMOTE_FETCH_SHORT_CIRCUITED_COUNT);
+        register!(REMOTE_PUBSUB_REQUEST_TIME_HISTOGRAM);
+        register!(RPC_REQUESTS_VEC_COUNT);
+        register!(RPC_REQUEST_TIME_HISTOGRAM);
+        register!(RPC_REQUEST_SIZE_HISTOGRAM);
+        register!(RPC_RESPONSE_SIZE_HISTOGRAM);
+        register!(SLOW_RPC_REQUESTS_COUNT);
+        register!(RPC_ERRORS_VEC_COUNT);
+        register!(GEYSER_GRPC_CLIENT_QUEUE_GAUGE);
+        register!(GEYSER_GRPC_CLIENT_DROPPED_COUNT);
+        register!(GEYSER_GRPC_SLOW_CLIENT_DISCONNECTS_COUNT);
+        register!(DISK_USAGE_GAUGE);
+        register!(DISK_BUDGET_GAUGE);
+        register!(DISK_FREE_GAUGE);
+        register!(DISK_EMERGENCY_ACTIONS_COUNT);
+        register!(READ_ONLY_GAUGE);
+        register!(PUBSUB_CONNECTIONS_GAUGE);
+        register!(PUBSUB_SUBSCRIPTIONS_GAUGE);
+        register!(PUBSUB_NOTIFICATIONS_COUNT);
+        register!(PUBSUB_CONNECTION_QUEUE_GAUGE);
+        register!(PUBSUB_CONNECTION_DROPPED_COUNT);
     });
 }
 
@@ -268,6 +634,26 @@ pub fn inc_fee(fee: u64) {
     FEE_COUNT.inc_by(fee);
 }
 
+pub fn inc_program_cache_activity(hits: u64, misses: u64, evictions: u64) {
+    PROGRAM_CACHE_HITS_COUNT.inc_by(hits);
+    PROGRAM_CACHE_MISSES_COUNT.inc_by(misses);
+    PROGRAM_CACHE_EVICTIONS_COUNT.inc_by(evictions);
+}
+
+pub fn observe_program_executed_units(program_id: &str, executed_units: u64) {
+    PROGRAM_EXECUTED_UNITS_HISTOGRAM
+        .with_label_values(&[program_id])
+        .observe(executed_units as f64);
+}
+
+pub fn observe_transaction_heap_size(heap_size: u32) {
+    TRANSACTION_HEAP_SIZE_HISTOGRAM.observe(heap_size as f64);
+}
+
+pub fn observe_transaction_loaded_accounts_data_size(data_size: u32) {
+    TRANSACTION_LOADED_ACCOUNTS_DATA_SIZE_HISTOGRAM.observe(data_size as f64);
+}
+
 pub fn inc_account_clone(account_clone: AccountClone) {
     use AccountClone::*;
     match account_clone {
@@ -321,10 +707,76 @@ pub fn inc_account_commit(account_commit: AccountCommit) {
     }
 }
 
+pub fn inc_account_clone_requests(endpoint: &str) {
+    ACCOUNT_CLONE_REQUESTS_COUNT
+        .with_label_values(&[endpoint])
+        .inc();
+}
+
+pub fn inc_account_clone_cache_hits(endpoint: &str) {
+    ACCOUNT_CLONE_CACHE_HITS_COUNT
+        .with_label_values(&[endpoint])
+        .inc();
+}
+
+pub fn observe_account_clone_time(
+    endpoint: &str,
+    outcome: Outcome,
+    elapsed: Duration,
+) {
+    ACCOUNT_CLONE_TIME_HISTOGRAM
+        .with_label_values(&[endpoint, outcome.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
+
 pub fn account_commit_start() -> HistogramTimer {
     ACCOUNT_COMMIT_TIME_HISTOGRAM.start_timer()
 }
 
+pub fn observe_account_commit_confirmation_time(
+    endpoint: &str,
+    outcome: Outcome,
+    elapsed: Duration,
+) {
+    ACCOUNT_COMMIT_CONFIRMATION_TIME_HISTOGRAM
+        .with_label_values(&[endpoint, outcome.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn inc_account_commit_failures(endpoint: &str, reason: &str) {
+    ACCOUNT_COMMIT_FAILURES_COUNT
+        .with_label_values(&[endpoint, reason])
+        .inc();
+}
+
+pub fn inc_account_commit_retries() {
+    ACCOUNT_COMMIT_RETRIES_COUNT.inc();
+}
+
+pub fn inc_account_commits_skipped() {
+    ACCOUNT_COMMITS_SKIPPED_COUNT.inc();
+}
+
+pub fn inc_account_commit_simulation_failures() {
+    ACCOUNT_COMMIT_SIMULATION_FAILURES_COUNT.inc();
+}
+
+pub fn set_committer_payer_balance(endpoint: &str, lamports: u64) {
+    COMMITTER_PAYER_BALANCE_GAUGE
+        .with_label_values(&[endpoint])
+        .set(lamports as i64);
+}
+
+pub fn set_committer_payer_low_balance(endpoint: &str, is_low: bool) {
+    COMMITTER_PAYER_LOW_BALANCE_GAUGE
+        .with_label_values(&[endpoint])
+        .set(is_low as i64);
+}
+
+pub fn inc_committer_payer_top_ups() {
+    COMMITTER_PAYER_TOP_UPS_COUNT.inc();
+}
+
 pub fn set_cached_clone_outputs_count(count: usize) {
     CACHED_CLONE_OUTPUTS_COUNT.set(count as i64);
 }
@@ -339,6 +791,36 @@ pub fn set_subscriptions_count(count: usize, shard: &str) {
         .set(count as i64);
 }
 
+pub fn set_remote_slot(slot: u64) {
+    REMOTE_SLOT_GAUGE.set(slot as i64);
+}
+
+pub fn set_subscription_lag(shard: &str, lag: u64) {
+    SUBSCRIPTION_LAG_GAUGE
+        .with_label_values(&[shard])
+        .set(lag as i64);
+}
+
+pub fn remove_subscription_lag(shard: &str) {
+    let _ = SUBSCRIPTION_LAG_GAUGE.remove_label_values(&[shard]);
+}
+
+pub fn set_scheduled_commits_count(count: usize) {
+    SCHEDULED_COMMITS_GAUGE.set(count as i64);
+}
+
+pub fn set_pending_commits_count(count: usize) {
+    PENDING_COMMITS_GAUGE.set(count as i64);
+}
+
+pub fn inc_account_updates() {
+    ACCOUNT_UPDATES_COUNT.inc();
+}
+
+pub fn inc_account_updates_coalesced() {
+    ACCOUNT_UPDATES_COALESCED_COUNT.inc();
+}
+
 pub fn set_ledger_size(size: u64) {
     LEDGER_SIZE_GAUGE.set(size as i64);
 }
@@ -415,11 +897,12 @@ pub fn adjust_active_data_mods_size(delta: i64) {
     ACTIVE_DATA_MODS_SIZE_GAUGE.add(delta);
 }
 
-pub fn observe_sigverify_time<T, F>(f: F) -> T
-where
-    F: FnOnce() -> T,
-{
-    SIGVERIFY_TIME_HISTOGRAM.observe_closure_duration(f)
+pub fn sigverify_start() -> HistogramTimer {
+    SIGVERIFY_TIME_HISTOGRAM.start_timer()
+}
+
+pub fn sigverify_end(timer: HistogramTimer) {
+    timer.stop_and_record();
 }
 
 pub fn ensure_accounts_start() -> HistogramTimer {
@@ -430,11 +913,24 @@ pub fn ensure_accounts_end(timer: HistogramTimer) {
     timer.stop_and_record();
 }
 
-pub fn observe_transaction_execution_time<T, F>(f: F) -> T
-where
-    F: FnOnce() -> T,
-{
-    TRANSACTION_EXECUTION_TIME_HISTORY.observe_closure_duration(f)
+pub fn transaction_execution_start() -> HistogramTimer {
+    TRANSACTION_EXECUTION_TIME_HISTORY.start_timer()
+}
+
+pub fn transaction_execution_end(timer: HistogramTimer) {
+    timer.stop_and_record();
+}
+
+pub fn inc_resubmitted_transactions() {
+    RESUBMITTED_TRANSACTIONS_COUNT.inc();
+}
+
+pub fn inc_slow_transactions() {
+    SLOW_TRANSACTIONS_COUNT.inc();
+}
+
+pub fn inc_transactions_below_fee_floor() {
+    TRANSACTIONS_BELOW_FEE_FLOOR_COUNT.inc();
 }
 
 pub fn adjust_monitored_accounts_count(count: usize) {
@@ -444,9 +940,221 @@ pub fn inc_evicted_accounts_count() {
     EVICTED_ACCOUNTS_COUNT.inc();
 }
 
+pub fn inc_revalidated_accounts_count() {
+    REVALIDATED_ACCOUNTS_COUNT.inc();
+}
+
+pub fn inc_invalid_delegations_count() {
+    INVALID_DELEGATIONS_COUNT.inc();
+}
+
+pub fn inc_revoked_delegations_count() {
+    REVOKED_DELEGATIONS_COUNT.inc();
+}
+
 pub fn observe_flush_accounts_time<T, F>(f: F) -> T
 where
     F: FnOnce() -> T,
 {
     FLUSH_ACCOUNTS_TIME_HISTOGRAM.observe_closure_duration(f)
 }
+
+pub fn observe_remote_fetch_time(
+    endpoint: &str,
+    outcome: Outcome,
+    elapsed: Duration,
+) {
+    REMOTE_FETCH_TIME_HISTOGRAM
+        .with_label_values(&[endpoint, outcome.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn inc_remote_fetch_failover(endpoint: &str) {
+    REMOTE_FETCH_FAILOVER_COUNT
+        .with_label_values(&[endpoint])
+        .inc();
+}
+
+pub fn inc_remote_fetch_throttled(endpoint: &str) {
+    REMOTE_FETCH_THROTTLED_COUNT
+        .with_label_values(&[endpoint])
+        .inc();
+}
+
+pub fn set_remote_endpoint_healthy(role: &str, endpoint: &str, healthy: bool) {
+    REMOTE_ENDPOINT_HEALTHY_GAUGE
+        .with_label_values(&[role, endpoint])
+        .set(healthy as i64);
+}
+
+pub fn inc_remote_endpoint_failovers(role: &str) {
+    REMOTE_ENDPOINT_FAILOVERS_COUNT
+        .with_label_values(&[role])
+        .inc();
+}
+
+pub fn set_remote_fetch_circuit_open(open: bool) {
+    REMOTE_FETCH_CIRCUIT_OPEN_GAUGE.set(open as i64);
+}
+
+pub fn inc_remote_fetch_short_circuited() {
+    REMOTE_FETCH_SHORT_CIRCUITED_COUNT.inc();
+}
+
+pub fn observe_remote_pubsub_request_time(
+    endpoint: &str,
+    request: &str,
+    outcome: Outcome,
+    elapsed: Duration,
+) {
+    REMOTE_PUBSUB_REQUEST_TIME_HISTOGRAM
+        .with_label_values(&[endpoint, request, outcome.as_str()])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn inc_rpc_request(method: &str) {
+    RPC_REQUESTS_VEC_COUNT.with_label_values(&[method]).inc();
+}
+
+pub fn observe_rpc_request_time(method: &str, elapsed: Duration) {
+    RPC_REQUEST_TIME_HISTOGRAM
+        .with_label_values(&[method])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn observe_rpc_payload_sizes(
+    method: &str,
+    request_size: usize,
+    response_size: usize,
+) {
+    RPC_REQUEST_SIZE_HISTOGRAM
+        .with_label_values(&[method])
+        .observe(request_size as f64);
+    RPC_RESPONSE_SIZE_HISTOGRAM
+        .with_label_values(&[method])
+        .observe(response_size as f64);
+}
+
+pub fn inc_slow_rpc_requests(method: &str) {
+    SLOW_RPC_REQUESTS_COUNT.with_label_values(&[method]).inc();
+}
+
+pub fn inc_rpc_error(method: &str, code: i64) {
+    RPC_ERRORS_VEC_COUNT
+        .with_label_values(&[method, &code.to_string()])
+        .inc();
+}
+
+#[cfg(any(test, feature = "dev-context-only-utils"))]
+pub fn rpc_request_count(method: &str) -> u64 {
+    RPC_REQUESTS_VEC_COUNT.with_label_values(&[method]).get()
+}
+
+/// Sums of the request and response sizes recorded for `method` in bytes.
+#[cfg(any(test, feature = "dev-context-only-utils"))]
+pub fn rpc_payload_size_sums(method: &str) -> (f64, f64) {
+    (
+        RPC_REQUEST_SIZE_HISTOGRAM
+            .with_label_values(&[method])
+            .get_sample_sum(),
+        RPC_RESPONSE_SIZE_HISTOGRAM
+            .with_label_values(&[method])
+            .get_sample_sum(),
+    )
+}
+
+#[cfg(any(test, feature = "dev-context-only-utils"))]
+pub fn rpc_error_count(method: &str, code: i64) -> u64 {
+    RPC_ERRORS_VEC_COUNT
+        .with_label_values(&[method, &code.to_string()])
+        .get()
+}
+
+pub fn set_geyser_grpc_client_queue(client: &str, len: usize) {
+    GEYSER_GRPC_CLIENT_QUEUE_GAUGE
+        .with_label_values(&[client])
+        .set(len as i64);
+}
+
+pub fn inc_geyser_grpc_client_dropped(client: &str, count: u64) {
+    GEYSER_GRPC_CLIENT_DROPPED_COUNT
+        .with_label_values(&[client])
+        .inc_by(count);
+}
+
+pub fn inc_geyser_grpc_slow_client_disconnects() {
+    GEYSER_GRPC_SLOW_CLIENT_DISCONNECTS_COUNT.inc();
+}
+
+/// Removes the metrics of the gRPC subscriber once it disconnected
+pub fn remove_geyser_grpc_client(client: &str) {
+    let _ = GEYSER_GRPC_CLIENT_QUEUE_GAUGE.remove_label_values(&[client]);
+    let _ = GEYSER_GRPC_CLIENT_DROPPED_COUNT.remove_label_values(&[client]);
+}
+
+pub fn set_disk_usage(component: &str, bytes: u64) {
+    DISK_USAGE_GAUGE
+        .with_label_values(&[component])
+        .set(bytes as i64);
+}
+
+pub fn set_disk_budget(component: &str, bytes: u64) {
+    DISK_BUDGET_GAUGE
+        .with_label_values(&[component])
+        .set(bytes as i64);
+}
+
+pub fn set_disk_free(bytes: u64) {
+    DISK_FREE_GAUGE.set(bytes as i64);
+}
+
+pub fn inc_disk_emergency_actions(action: &str) {
+    DISK_EMERGENCY_ACTIONS_COUNT
+        .with_label_values(&[action])
+        .inc();
+}
+
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY_GAUGE.set(read_only as i64);
+}
+
+pub fn inc_pubsub_connections() {
+    PUBSUB_CONNECTIONS_GAUGE.inc();
+}
+
+/// Removes the metrics of the WebSocket connection once it closed
+pub fn remove_pubsub_connection(connection: &str) {
+    PUBSUB_CONNECTIONS_GAUGE.dec();
+    let _ = PUBSUB_CONNECTION_QUEUE_GAUGE.remove_label_values(&[connection]);
+    let _ = PUBSUB_CONNECTION_DROPPED_COUNT.remove_label_values(&[connection]);
+}
+
+pub fn inc_pubsub_subscriptions(subscription: &str) {
+    PUBSUB_SUBSCRIPTIONS_GAUGE
+        .with_label_values(&[subscription])
+        .inc();
+}
+
+pub fn dec_pubsub_subscriptions(subscription: &str) {
+    PUBSUB_SUBSCRIPTIONS_GAUGE
+        .with_label_values(&[subscription])
+        .dec();
+}
+
+pub fn inc_pubsub_notifications(subscription: &str) {
+    PUBSUB_NOTIFICATIONS_COUNT
+        .with_label_values(&[subscription])
+        .inc();
+}
+
+pub fn set_pubsub_connection_queue(connection: &str, len: i64) {
+    PUBSUB_CONNECTION_QUEUE_GAUGE
+        .with_label_values(&[connection])
+        .set(len);
+}
+
+pub fn inc_pubsub_connection_dropped(connection: &str, count: u64) {
+    PUBSUB_CONNECTION_DROPPED_COUNT
+        .with_label_values(&[connection])
+        .inc_by(count);
+}
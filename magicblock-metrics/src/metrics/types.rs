@@ -68,3 +68,39 @@ pub enum AccountCommit<'a> {
     CommitOnly { pubkey: &'a str, outcome: Outcome },
     CommitAndUndelegate { pubkey: &'a str, outcome: Outcome },
 }
+
+// -----------------
+// Endpoint
+// -----------------
+/// Reduces the endpoint url to its scheme and host since API keys are
+/// commonly passed as part of the path or query
+pub fn endpoint_label(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let host = rest.split(['/', '?']).next().unwrap_or_default();
+            format!("{scheme}://{host}")
+        }
+        None => url.split(['/', '?']).next().unwrap_or_default().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_label() {
+        assert_eq!(
+            endpoint_label("https://api.devnet.solana.com"),
+            "https://api.devnet.solana.com"
+        );
+        assert_eq!(
+            endpoint_label("https://rpc.provider.com/secret-token/"),
+            "https://rpc.provider.com"
+        );
+        assert_eq!(
+            endpoint_label("http://localhost:8899?api-key=secret"),
+            "http://localhost:8899"
+        );
+    }
+}
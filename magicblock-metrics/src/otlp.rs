@@ -0,0 +1,65 @@
+use std::error::Error;
+
+#[cfg(feature = "otlp")]
+use log::*;
+#[cfg(feature = "otlp")]
+use opentelemetry::{trace::TracerProvider as _, KeyValue};
+#[cfg(feature = "otlp")]
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+#[cfg(feature = "otlp")]
+use opentelemetry_sdk::{runtime, trace::TracerProvider, Resource};
+#[cfg(feature = "otlp")]
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Exports the `tracing` spans of the validator to an OTLP collector.
+/// Pending spans are flushed when it is dropped.
+pub struct OtlpTracing {
+    #[cfg(feature = "otlp")]
+    provider: TracerProvider,
+}
+
+impl OtlpTracing {
+    /// Installs the global `tracing` subscriber exporting spans to the gRPC
+    /// `endpoint` of the collector, needs to run inside a tokio runtime.
+    /// Logging is not affected, it keeps going through the `log` crate.
+    #[cfg(feature = "otlp")]
+    pub fn try_init(
+        endpoint: &str,
+        service_name: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let exporter = SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(endpoint)
+            .build()?;
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, runtime::Tokio)
+            .with_resource(Resource::new([KeyValue::new(
+                "service.name",
+                service_name.to_string(),
+            )]))
+            .build();
+        let tracer = provider.tracer("magicblock-validator");
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(tracer));
+        tracing::subscriber::set_global_default(subscriber)?;
+        info!("Exporting traces to '{}'", endpoint);
+        Ok(Self { provider })
+    }
+
+    #[cfg(not(feature = "otlp"))]
+    pub fn try_init(
+        _endpoint: &str,
+        _service_name: &str,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        Err("Exporting traces requires the validator to be built with the 'otlp' feature".into())
+    }
+}
+
+#[cfg(feature = "otlp")]
+impl Drop for OtlpTracing {
+    fn drop(&mut self) {
+        if let Err(err) = self.provider.shutdown() {
+            warn!("Failed to flush traces: {:?}", err);
+        }
+    }
+}
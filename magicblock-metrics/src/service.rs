@@ -11,7 +11,7 @@ use prometheus::TextEncoder;
 use tokio::{net::TcpListener, select};
 use tokio_util::sync::CancellationToken;
 
-use crate::metrics;
+use crate::{health, metrics};
 
 pub fn try_start_metrics_service(
     addr: SocketAddr,
@@ -122,6 +122,10 @@ async fn metrics_service_router(
                 });
             Ok(Response::new(full(metrics)))
         }
+        // Separate from the JSON RPC `getHealth` so orchestrators can probe
+        // the validator without going through the RPC service
+        (&Method::GET, "/livez") => Ok(Response::new(full("ok"))),
+        (&Method::GET, "/readyz") => Ok(readiness_response()),
         _ => {
             let mut not_found = Response::new(empty());
             *not_found.status_mut() = StatusCode::NOT_FOUND;
@@ -130,6 +134,33 @@ async fn metrics_service_router(
     }
 }
 
+/// Lists the result of each readiness check, responds with
+/// `503 Service Unavailable` if any of them failed
+fn readiness_response() -> Response<BoxBody<Bytes, hyper::Error>> {
+    let checks = health::check_readiness();
+    let ready = checks.iter().all(|(_, result)| result.is_ok());
+    let mut body = String::new();
+    for (component, result) in checks {
+        match result {
+            Ok(()) => body.push_str(&format!("[+]{component} ok\n")),
+            Err(reason) => {
+                body.push_str(&format!("[-]{component} failed: {reason}\n"))
+            }
+        }
+    }
+    body.push_str(if ready {
+        "readyz check passed\n"
+    } else {
+        "readyz check failed\n"
+    });
+
+    let mut response = Response::new(full(body));
+    if !ready {
+        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+    }
+    response
+}
+
 fn full<T: Into<Bytes>>(chunk: T) -> BoxBody<Bytes, hyper::Error> {
     Full::new(chunk.into())
         .map_err(|never| match never {})
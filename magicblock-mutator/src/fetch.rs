@@ -8,7 +8,10 @@ use solana_sdk::{
 
 use crate::{
     errors::{MutatorError, MutatorResult},
-    idl::fetch_program_idl_modification_from_cluster,
+    idl::{
+        fetch_program_idl_modification_from_cluster,
+        fetch_program_metadata_idl_modification_from_cluster,
+    },
     program::{create_program_modifications, ProgramModifications},
     transactions::{
         transaction_to_clone_program, transaction_to_clone_regular_account,
@@ -74,17 +77,26 @@ pub async fn transaction_to_clone_pubkey_from_cluster(
         slot,
     )
     .map_err(MutatorError::MutatorModificationError)?;
-    // Try to fetch the IDL if possible
-    let program_idl_modification =
+    // Try to fetch the IDLs if possible
+    let program_idl_modifications = [
         fetch_program_idl_modification_from_cluster(cluster, program_id_pubkey)
-            .await;
+            .await,
+        fetch_program_metadata_idl_modification_from_cluster(
+            cluster,
+            program_id_pubkey,
+        )
+        .await,
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
     // Done, generate the transaction as normal
     Ok(transaction_to_clone_program(
         needs_upgrade,
         program_id_modification,
         program_data_modification,
         program_buffer_modification,
-        program_idl_modification,
+        program_idl_modifications,
         recent_blockhash,
     ))
 }
@@ -1,11 +1,18 @@
 use magicblock_program::magicblock_instruction::AccountModification;
-use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{pubkey, pubkey::Pubkey};
 
 use crate::{fetch::fetch_account_from_cluster, Cluster};
 
 const ANCHOR_SEED: &str = "anchor:idl";
 const SHANK_SEED: &str = "shank:idl";
 
+/// Program which stores metadata of other programs, i.e. their IDL, in
+/// accounts derived from the program id
+pub const PROGRAM_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("ProgM6JCCvbYkfKqJYHePx4xxSUSqJp7rh8Lyv7nk7S");
+/// Metadata seeds are stored as fixed size, zero padded strings
+const PROGRAM_METADATA_IDL_SEED: [u8; 16] = *b"idl\0\0\0\0\0\0\0\0\0\0\0\0\0";
+
 pub fn get_pubkey_anchor_idl(program_id: &Pubkey) -> Option<Pubkey> {
     let (base, _) = Pubkey::find_program_address(&[], program_id);
     Pubkey::create_with_seed(&base, ANCHOR_SEED, program_id).ok()
@@ -16,6 +23,16 @@ pub fn get_pubkey_shank_idl(program_id: &Pubkey) -> Option<Pubkey> {
     Pubkey::create_with_seed(&base, SHANK_SEED, program_id).ok()
 }
 
+/// Address of the canonical IDL of the program in the program metadata
+/// program, i.e. the one uploaded by the program's upgrade authority
+pub fn get_pubkey_program_metadata_idl(program_id: &Pubkey) -> Pubkey {
+    let (pubkey, _) = Pubkey::find_program_address(
+        &[program_id.as_ref(), &PROGRAM_METADATA_IDL_SEED],
+        &PROGRAM_METADATA_PROGRAM_ID,
+    );
+    pubkey
+}
+
 pub async fn fetch_program_idl_modification_from_cluster(
     cluster: &Cluster,
     program_pubkey: &Pubkey,
@@ -44,6 +61,17 @@ pub async fn fetch_program_idl_modification_from_cluster(
     None
 }
 
+pub async fn fetch_program_metadata_idl_modification_from_cluster(
+    cluster: &Cluster,
+    program_pubkey: &Pubkey,
+) -> Option<AccountModification> {
+    try_fetch_program_idl_modification_from_cluster(
+        cluster,
+        Some(get_pubkey_program_metadata_idl(program_pubkey)),
+    )
+    .await
+}
+
 async fn try_fetch_program_idl_modification_from_cluster(
     cluster: &Cluster,
     pubkey: Option<Pubkey>,
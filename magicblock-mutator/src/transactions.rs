@@ -1,6 +1,7 @@
 use magicblock_program::{
     magicblock_instruction::{
         modify_accounts, modify_accounts_instruction, AccountModification,
+        MutationReason,
     },
     validator,
 };
@@ -35,7 +36,11 @@ pub fn transaction_to_clone_regular_account(
         }
     }
     // We only need a single transaction with a single mutation in this case
-    modify_accounts(vec![account_modification], recent_blockhash)
+    modify_accounts(
+        vec![account_modification],
+        MutationReason::CloneAccount,
+        recent_blockhash,
+    )
 }
 
 pub fn transaction_to_clone_program(
@@ -43,7 +48,7 @@ pub fn transaction_to_clone_program(
     program_id_modification: AccountModification,
     program_data_modification: AccountModification,
     program_buffer_modification: AccountModification,
-    program_idl_modification: Option<AccountModification>,
+    program_idl_modifications: Vec<AccountModification>,
     recent_blockhash: Hash,
 ) -> Transaction {
     // We'll need to run the upgrade IX based on those
@@ -55,16 +60,21 @@ pub fn transaction_to_clone_program(
         program_data_modification,
         program_buffer_modification,
     ];
-    if let Some(program_idl_modification) = program_idl_modification {
-        account_modifications.push(program_idl_modification)
-    }
+    account_modifications.extend(program_idl_modifications);
     // If the program does not exist yet, we just need to update it's data and don't
     // need to explicitly update using the BPF loader's Upgrade IX
     if !needs_upgrade {
-        return modify_accounts(account_modifications, recent_blockhash);
+        return modify_accounts(
+            account_modifications,
+            MutationReason::CloneProgram,
+            recent_blockhash,
+        );
     }
     // First dump the necessary set of account to our bank/ledger
-    let modify_ix = modify_accounts_instruction(account_modifications);
+    let modify_ix = modify_accounts_instruction(
+        account_modifications,
+        MutationReason::CloneProgram,
+    );
     // The validator is marked as the upgrade authority of all program accounts
     let validator_pubkey = &validator::validator_authority_id();
     // Then we run the official BPF upgrade IX to notify the system of the new program
@@ -9,7 +9,7 @@ use solana_sdk::{
     transaction::{Result, SanitizedTransaction, Transaction},
 };
 
-use crate::batch_processor::{execute_batch, TransactionBatchWithIndexes};
+use crate::scheduler::TRANSACTION_SCHEDULER;
 
 // NOTE: these don't exactly belong in the accounts crate
 //       they should go into a dedicated crate that also has access to
@@ -36,39 +36,76 @@ pub fn execute_sanitized_transaction(
     transaction_status_sender: Option<&TransactionStatusSender>,
 ) -> Result<Signature> {
     let signature = *sanitized_tx.signature();
-    let txs = &[sanitized_tx];
-
-    // Ensure that only one transaction is processed at a time even if it is initiated from
-    // multiple threads.
-    // TODO: This is a temporary solution until we have a transaction executor which schedules
-    // transactions to be executed in parallel without account lock conflicts.
-    // If we choose this as a long term solution we need to lock simulations/preflight with the
-    // same mutex once we enable them again
-    // Work tracked here: https://github.com/magicblock-labs/magicblock-validator/issues/181
-    //
-    // NOTE(bmuddha): this lock is also held in AccountsDB and
-    // during snapshotting it will acquire write guard, effectively
-    // halting all txn executions for the duration of lock
-    let _execution_guard = TRANSACTION_INDEX_LOCK.read();
-
-    let batch = bank.prepare_sanitized_batch(txs);
-
-    let batch_with_indexes = TransactionBatchWithIndexes {
-        batch,
-        // TODO: figure out how to properly derive transaction_indexes (index within the slot)
-        // - This is important for the ledger history of each slot
-        // - tracked: https://github.com/magicblock-labs/magicblock-validator/issues/201
-        //
-        // copied from agave/ledger/benches/blockstore_processor.rs:147
-        transaction_indexes: (0..txs.len()).collect(),
-    };
-    let mut timings = Default::default();
-    execute_batch(
-        &batch_with_indexes,
+    // Transactions that don't conflict with each other are executed in
+    // parallel, even when initiated from multiple threads
+    TRANSACTION_SCHEDULER.execute(
+        sanitized_tx,
         bank,
         transaction_status_sender,
-        &mut timings,
-        None,
     )?;
     Ok(signature)
 }
+
+/// Same as [execute_sanitized_transaction], but awaits the execution instead
+/// of blocking the calling thread, which is what async callers like the RPC
+/// service should use.
+pub async fn execute_sanitized_transaction_async(
+    sanitized_tx: SanitizedTransaction,
+    bank: &Arc<Bank>,
+    transaction_status_sender: Option<&TransactionStatusSender>,
+) -> Result<Signature> {
+    let signature = *sanitized_tx.signature();
+    TRANSACTION_SCHEDULER
+        .execute_async(sanitized_tx, bank, transaction_status_sender)
+        .await?;
+    Ok(signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use magicblock_bank::{
+        bank_dev_utils::transactions::create_system_transfer_transaction,
+        genesis_utils::create_genesis_config_with_leader_and_fees,
+    };
+    use solana_sdk::{native_token::LAMPORTS_PER_SOL, pubkey::Pubkey};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_executes_concurrently_awaited_transactions() {
+        let genesis_config_info = create_genesis_config_with_leader_and_fees(
+            u64::MAX,
+            &Pubkey::new_unique(),
+        );
+        let bank = Arc::new(
+            Bank::new_for_tests(
+                &genesis_config_info.genesis_config,
+                None,
+                None,
+            )
+            .unwrap(),
+        );
+        let (first, _, first_to) = create_system_transfer_transaction(
+            &bank,
+            LAMPORTS_PER_SOL,
+            LAMPORTS_PER_SOL / 5,
+        );
+        let (second, _, second_to) = create_system_transfer_transaction(
+            &bank,
+            LAMPORTS_PER_SOL,
+            LAMPORTS_PER_SOL / 4,
+        );
+        let (first_signature, second_signature) =
+            (*first.signature(), *second.signature());
+
+        let (first_result, second_result) = tokio::join!(
+            execute_sanitized_transaction_async(first, &bank, None),
+            execute_sanitized_transaction_async(second, &bank, None)
+        );
+
+        assert_eq!(first_result, Ok(first_signature));
+        assert_eq!(second_result, Ok(second_signature));
+        assert_eq!(bank.get_balance(&first_to), LAMPORTS_PER_SOL / 5);
+        assert_eq!(bank.get_balance(&second_to), LAMPORTS_PER_SOL / 4);
+    }
+}
@@ -1,5 +1,8 @@
 pub mod batch_processor;
 pub mod execute_transaction;
 mod metrics;
+mod read_write_account_set;
+pub mod scheduler;
+pub mod sigverify;
 pub mod token_balances;
 mod utils;
@@ -0,0 +1,147 @@
+// NOTE: adapted from core/src/banking_stage/read_write_account_set.rs
+
+use std::collections::HashSet;
+
+use solana_sdk::{message::SanitizedMessage, pubkey::Pubkey};
+
+/// Wrapper struct to accumulate locks for a batch of transactions.
+#[derive(Debug, Default)]
+pub struct ReadWriteAccountSet {
+    /// Set of accounts that are locked for read
+    read_set: HashSet<Pubkey>,
+    /// Set of accounts that are locked for write
+    write_set: HashSet<Pubkey>,
+}
+
+impl ReadWriteAccountSet {
+    /// Returns true if all account locks were available and false otherwise.
+    pub fn check_locks(&self, message: &SanitizedMessage) -> bool {
+        message
+            .account_keys()
+            .iter()
+            .enumerate()
+            .all(|(index, pubkey)| {
+                if message.is_writable(index) {
+                    self.can_write(pubkey)
+                } else {
+                    self.can_read(pubkey)
+                }
+            })
+    }
+
+    /// Add all account locks.
+    /// Returns true if all account locks were available and false otherwise.
+    pub fn take_locks(&mut self, message: &SanitizedMessage) -> bool {
+        if !self.check_locks(message) {
+            return false;
+        }
+        self.add_locks(message);
+        true
+    }
+
+    /// Add all account locks regardless of them being available.
+    pub fn add_locks(&mut self, message: &SanitizedMessage) {
+        for (index, pubkey) in message.account_keys().iter().enumerate() {
+            if message.is_writable(index) {
+                self.write_set.insert(*pubkey);
+            } else {
+                self.read_set.insert(*pubkey);
+            }
+        }
+    }
+
+    /// Clears the read and write sets
+    pub fn clear(&mut self) {
+        self.read_set.clear();
+        self.write_set.clear();
+    }
+
+    /// Check if an account can be read-locked
+    fn can_read(&self, pubkey: &Pubkey) -> bool {
+        !self.write_set.contains(pubkey)
+    }
+
+    /// Check if an account can be write-locked
+    fn can_write(&self, pubkey: &Pubkey) -> bool {
+        !self.write_set.contains(pubkey) && !self.read_set.contains(pubkey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        transaction::{SanitizedTransaction, Transaction},
+    };
+
+    use super::*;
+
+    fn message(writable: &[Pubkey], readonly: &[Pubkey]) -> SanitizedMessage {
+        let accounts = writable
+            .iter()
+            .map(|pubkey| AccountMeta::new(*pubkey, false))
+            .chain(
+                readonly
+                    .iter()
+                    .map(|pubkey| AccountMeta::new_readonly(*pubkey, false)),
+            )
+            .collect();
+        let instruction =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+        SanitizedTransaction::from_transaction_for_tests(
+            Transaction::new_unsigned(Message::new(
+                &[instruction],
+                Some(&Pubkey::new_unique()),
+            )),
+        )
+        .message()
+        .clone()
+    }
+
+    #[test]
+    fn test_read_locks_are_shared() {
+        let account = Pubkey::new_unique();
+        let mut locks = ReadWriteAccountSet::default();
+
+        assert!(locks.take_locks(&message(&[], &[account])));
+        assert!(locks.take_locks(&message(&[], &[account])));
+        assert!(!locks.check_locks(&message(&[account], &[])));
+    }
+
+    #[test]
+    fn test_write_lock_is_exclusive() {
+        let account = Pubkey::new_unique();
+        let mut locks = ReadWriteAccountSet::default();
+
+        assert!(locks.take_locks(&message(&[account], &[])));
+        assert!(!locks.take_locks(&message(&[account], &[])));
+        assert!(!locks.take_locks(&message(&[], &[account])));
+        assert!(locks.take_locks(&message(&[Pubkey::new_unique()], &[])));
+    }
+
+    #[test]
+    fn test_failed_take_locks_adds_no_locks() {
+        let taken = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut locks = ReadWriteAccountSet::default();
+
+        assert!(locks.take_locks(&message(&[taken], &[])));
+        assert!(!locks.take_locks(&message(&[other, taken], &[])));
+        assert!(locks.check_locks(&message(&[other], &[])));
+    }
+
+    #[test]
+    fn test_add_locks_and_clear() {
+        let account = Pubkey::new_unique();
+        let mut locks = ReadWriteAccountSet::default();
+
+        locks.add_locks(&message(&[account], &[]));
+        // Locks are added even if they conflict with existing ones
+        locks.add_locks(&message(&[], &[account]));
+        assert!(!locks.check_locks(&message(&[], &[account])));
+
+        locks.clear();
+        assert!(locks.check_locks(&message(&[account], &[])));
+    }
+}
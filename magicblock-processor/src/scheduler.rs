@@ -0,0 +1,573 @@
+use std::{
+    borrow::Borrow,
+    cmp::Reverse,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use lazy_static::lazy_static;
+use log::{debug, trace, warn};
+use magicblock_bank::{
+    bank::Bank, get_compute_budget_details::GetComputeBudgetDetails,
+};
+use magicblock_metrics::metrics;
+use magicblock_transaction_status::TransactionStatusSender;
+use rayon::prelude::*;
+use solana_sdk::transaction::{Result, SanitizedTransaction};
+use solana_timings::{ExecuteTimingType, ExecuteTimings};
+use tokio::sync::oneshot;
+
+use crate::{
+    batch_processor::{execute_batch, TransactionBatchWithIndexes},
+    execute_transaction::TRANSACTION_INDEX_LOCK,
+    read_write_account_set::ReadWriteAccountSet,
+    utils::PAR_THREAD_POOL,
+};
+
+/// Max number of queued transactions the scheduler considers at once when
+/// looking for ones that can be executed in parallel
+const MAX_PENDING_TRANSACTIONS: usize = 1024;
+
+lazy_static! {
+    pub static ref TRANSACTION_SCHEDULER: TransactionScheduler =
+        TransactionScheduler::spawn();
+}
+
+/// Order in which the scheduler picks queued transactions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransactionOrdering {
+    /// In the order they were scheduled in
+    #[default]
+    Fifo,
+    /// By compute unit price, highest first, the same way the base layer
+    /// does. Transactions with the same price keep the order they were
+    /// scheduled in.
+    FeePriority,
+}
+
+/// Delivers the result of a scheduled transaction to its caller
+enum ResultSender {
+    /// The caller blocks its thread until the result arrives
+    Blocking(Sender<Result<()>>),
+    /// The caller awaits the result without blocking the async runtime
+    Async(oneshot::Sender<Result<()>>),
+}
+
+impl ResultSender {
+    fn send(self, result: Result<()>) {
+        // The caller may have given up waiting for the result
+        match self {
+            Self::Blocking(sender) => {
+                let _ = sender.send(result);
+            }
+            Self::Async(sender) => {
+                let _ = sender.send(result);
+            }
+        }
+    }
+}
+
+struct ScheduledTransaction {
+    transaction: SanitizedTransaction,
+    bank: Arc<Bank>,
+    transaction_status_sender: Option<TransactionStatusSender>,
+    result_sender: ResultSender,
+    /// Span of the caller, execution is traced as part of it
+    span: tracing::Span,
+    scheduled_at: Instant,
+}
+
+impl Borrow<SanitizedTransaction> for ScheduledTransaction {
+    fn borrow(&self) -> &SanitizedTransaction {
+        &self.transaction
+    }
+}
+
+/// Executes transactions submitted from any thread on the [PAR_THREAD_POOL].
+///
+/// Transactions that don't lock the same accounts in conflicting ways
+/// (at least one of them writing) are executed concurrently. Conflicting
+/// transactions are executed one after the other in the order in which they
+/// were scheduled.
+/// Transactions queued while the scheduler is busy executing are ordered
+/// according to the configured [TransactionOrdering].
+pub struct TransactionScheduler {
+    sender: Sender<ScheduledTransaction>,
+    ordering: Arc<RwLock<TransactionOrdering>>,
+    /// Transactions taking longer than this to execute are logged
+    slow_threshold: Arc<RwLock<Option<Duration>>>,
+}
+
+impl TransactionScheduler {
+    fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let ordering = Arc::<RwLock<TransactionOrdering>>::default();
+        let slow_threshold = Arc::<RwLock<Option<Duration>>>::default();
+        thread::Builder::new()
+            .name("solTxScheduler".to_string())
+            .spawn({
+                let ordering = ordering.clone();
+                let slow_threshold = slow_threshold.clone();
+                move || Self::run(receiver, ordering, slow_threshold)
+            })
+            .expect("failed to spawn transaction scheduler thread");
+        Self {
+            sender,
+            ordering,
+            slow_threshold,
+        }
+    }
+
+    pub fn set_ordering(&self, ordering: TransactionOrdering) {
+        *self
+            .ordering
+            .write()
+            .expect("RwLock transaction ordering poisoned") = ordering;
+    }
+
+    /// Logs transactions taking longer than the threshold to execute, none
+    /// are logged if it is `None`
+    pub fn set_slow_transaction_threshold(&self, threshold: Option<Duration>) {
+        *self
+            .slow_threshold
+            .write()
+            .expect("RwLock slow transaction threshold poisoned") = threshold;
+    }
+
+    /// Schedules the transaction and blocks until it was executed and its
+    /// result committed to the bank.
+    /// Async callers should use [Self::execute_async] instead which doesn't
+    /// block the runtime while the transaction is queued.
+    pub fn execute(
+        &self,
+        transaction: SanitizedTransaction,
+        bank: &Arc<Bank>,
+        transaction_status_sender: Option<&TransactionStatusSender>,
+    ) -> Result<()> {
+        let (result_sender, result_receiver) = mpsc::channel();
+        self.schedule(
+            transaction,
+            bank,
+            transaction_status_sender,
+            ResultSender::Blocking(result_sender),
+        );
+        result_receiver
+            .recv()
+            .expect("transaction scheduler dropped a transaction")
+    }
+
+    /// Schedules the transaction and waits until it was executed and its
+    /// result committed to the bank without blocking the calling task.
+    pub async fn execute_async(
+        &self,
+        transaction: SanitizedTransaction,
+        bank: &Arc<Bank>,
+        transaction_status_sender: Option<&TransactionStatusSender>,
+    ) -> Result<()> {
+        let (result_sender, result_receiver) = oneshot::channel();
+        self.schedule(
+            transaction,
+            bank,
+            transaction_status_sender,
+            ResultSender::Async(result_sender),
+        );
+        result_receiver
+            .await
+            .expect("transaction scheduler dropped a transaction")
+    }
+
+    fn schedule(
+        &self,
+        transaction: SanitizedTransaction,
+        bank: &Arc<Bank>,
+        transaction_status_sender: Option<&TransactionStatusSender>,
+        result_sender: ResultSender,
+    ) {
+        self.sender
+            .send(ScheduledTransaction {
+                transaction,
+                bank: bank.clone(),
+                transaction_status_sender: transaction_status_sender.cloned(),
+                result_sender,
+                span: tracing::Span::current(),
+                scheduled_at: Instant::now(),
+            })
+            .expect("transaction scheduler stopped");
+    }
+
+    fn run(
+        receiver: Receiver<ScheduledTransaction>,
+        ordering: Arc<RwLock<TransactionOrdering>>,
+        slow_threshold: Arc<RwLock<Option<Duration>>>,
+    ) {
+        while let Ok(next) = receiver.recv() {
+            let mut pending = vec![next];
+            pending
+                .extend(receiver.try_iter().take(MAX_PENDING_TRANSACTIONS - 1));
+            let ordering = *ordering
+                .read()
+                .expect("RwLock transaction ordering poisoned");
+            let slow_threshold = *slow_threshold
+                .read()
+                .expect("RwLock slow transaction threshold poisoned");
+            Self::order_pending(&mut pending, ordering);
+            while !pending.is_empty() {
+                let (parallel, deferred) = Self::next_parallel_batch(pending);
+                Self::execute_parallel(parallel, slow_threshold);
+                pending = deferred;
+            }
+        }
+        debug!("Transaction scheduler stopped");
+    }
+
+    fn order_pending<T: Borrow<SanitizedTransaction>>(
+        pending: &mut [T],
+        ordering: TransactionOrdering,
+    ) {
+        if ordering == TransactionOrdering::FeePriority {
+            // The sort is stable, thus transactions with the same price keep
+            // the order they were scheduled in
+            pending.sort_by_cached_key(|scheduled| {
+                Reverse(
+                    scheduled
+                        .borrow()
+                        .get_compute_budget_details(false)
+                        .map(|details| details.compute_unit_price)
+                        .unwrap_or_default(),
+                )
+            });
+        }
+    }
+
+    /// Picks the transactions that can be executed in parallel and defers
+    /// the remaining ones.
+    /// A transaction is deferred if it conflicts with a picked transaction or
+    /// with one that was deferred before it, since conflicting transactions
+    /// need to execute in the order they were scheduled in.
+    fn next_parallel_batch<T: Borrow<SanitizedTransaction>>(
+        pending: Vec<T>,
+    ) -> (Vec<T>, Vec<T>) {
+        let mut parallel_locks = ReadWriteAccountSet::default();
+        let mut deferred_locks = ReadWriteAccountSet::default();
+        let mut parallel = Vec::new();
+        let mut deferred = Vec::new();
+        for scheduled in pending {
+            let message = scheduled.borrow().message();
+            if deferred_locks.check_locks(message)
+                && parallel_locks.take_locks(message)
+            {
+                parallel.push(scheduled);
+            } else {
+                deferred_locks.add_locks(message);
+                deferred.push(scheduled);
+            }
+        }
+        (parallel, deferred)
+    }
+
+    fn execute_parallel(
+        parallel: Vec<ScheduledTransaction>,
+        slow_threshold: Option<Duration>,
+    ) {
+        trace!("Executing {} transactions in parallel", parallel.len());
+
+        // NOTE(bmuddha): this lock is also held in AccountsDB and
+        // during snapshotting it will acquire write guard, effectively
+        // halting all txn executions for the duration of lock
+        let _execution_guard = TRANSACTION_INDEX_LOCK.read();
+
+        PAR_THREAD_POOL.install(|| {
+            parallel.into_par_iter().for_each(|scheduled| {
+                let ScheduledTransaction {
+                    transaction,
+                    bank,
+                    transaction_status_sender,
+                    result_sender,
+                    span,
+                    scheduled_at,
+                } = scheduled;
+                let _span = span.enter();
+                let txs = &[transaction];
+                let batch_with_indexes = TransactionBatchWithIndexes {
+                    batch: bank.prepare_sanitized_batch(txs),
+                    // TODO: figure out how to properly derive transaction_indexes (index within the slot)
+                    // - This is important for the ledger history of each slot
+                    // - tracked: https://github.com/magicblock-labs/magicblock-validator/issues/201
+                    //
+                    // copied from agave/ledger/benches/blockstore_processor.rs:147
+                    transaction_indexes: (0..txs.len()).collect(),
+                };
+                let mut timings = Default::default();
+                let started = Instant::now();
+                let result = execute_batch(
+                    &batch_with_indexes,
+                    &bank,
+                    transaction_status_sender.as_ref(),
+                    &mut timings,
+                    None,
+                );
+                let elapsed = started.elapsed();
+                if slow_threshold.is_some_and(|threshold| elapsed > threshold) {
+                    log_slow_transaction(
+                        &txs[0],
+                        started.duration_since(scheduled_at),
+                        elapsed,
+                        &timings,
+                    );
+                }
+                result_sender.send(result);
+            })
+        });
+    }
+}
+
+fn log_slow_transaction(
+    transaction: &SanitizedTransaction,
+    queued: Duration,
+    elapsed: Duration,
+    timings: &ExecuteTimings,
+) {
+    metrics::inc_slow_transactions();
+    let message = transaction.message();
+    let accounts = message.account_keys().len();
+    let writable_accounts = (0..accounts)
+        .filter(|&idx| message.is_writable(idx))
+        .count();
+    let timing_us = |timing: ExecuteTimingType| timings.metrics[timing];
+    warn!(
+        signature:% = transaction.signature();
+        "Slow transaction '{}' took {:?} to execute after being queued for {:?} (check: {}us, load: {}us, execute: {}us, store: {}us) with {} accounts ({} writable)",
+        transaction.signature(),
+        elapsed,
+        queued,
+        timing_us(ExecuteTimingType::CheckUs),
+        timing_us(ExecuteTimingType::LoadUs),
+        timing_us(ExecuteTimingType::ExecuteUs),
+        timing_us(ExecuteTimingType::StoreUs),
+        accounts,
+        writable_accounts,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        instruction::{AccountMeta, Instruction},
+        message::Message,
+        pubkey::Pubkey,
+        transaction::Transaction,
+    };
+
+    use super::*;
+
+    fn transaction(
+        writable: &[Pubkey],
+        readonly: &[Pubkey],
+    ) -> SanitizedTransaction {
+        let payer = Pubkey::new_unique();
+        let accounts = writable
+            .iter()
+            .map(|pubkey| AccountMeta::new(*pubkey, false))
+            .chain(
+                readonly
+                    .iter()
+                    .map(|pubkey| AccountMeta::new_readonly(*pubkey, false)),
+            )
+            .collect();
+        let instruction =
+            Instruction::new_with_bytes(Pubkey::new_unique(), &[], accounts);
+        SanitizedTransaction::from_transaction_for_tests(
+            Transaction::new_unsigned(Message::new(
+                &[instruction],
+                Some(&payer),
+            )),
+        )
+    }
+
+    fn priced_transaction(compute_unit_price: u64) -> SanitizedTransaction {
+        let payer = Pubkey::new_unique();
+        SanitizedTransaction::from_transaction_for_tests(
+            Transaction::new_unsigned(Message::new(
+                &[ComputeBudgetInstruction::set_compute_unit_price(
+                    compute_unit_price,
+                )],
+                Some(&payer),
+            )),
+        )
+    }
+
+    fn fee_payers(transactions: &[SanitizedTransaction]) -> Vec<Pubkey> {
+        transactions
+            .iter()
+            .map(|transaction| *transaction.message().fee_payer())
+            .collect()
+    }
+
+    /// Runs the batches the same way the scheduler does
+    fn batches(
+        mut pending: Vec<SanitizedTransaction>,
+    ) -> Vec<Vec<SanitizedTransaction>> {
+        let mut batches = vec![];
+        while !pending.is_empty() {
+            let (parallel, deferred) =
+                TransactionScheduler::next_parallel_batch(pending);
+            batches.push(parallel);
+            pending = deferred;
+        }
+        batches
+    }
+
+    #[test]
+    fn test_non_conflicting_transactions_execute_in_parallel() {
+        let account = Pubkey::new_unique();
+        let pending = vec![
+            transaction(&[Pubkey::new_unique()], &[account]),
+            transaction(&[Pubkey::new_unique()], &[account]),
+            transaction(&[Pubkey::new_unique()], &[]),
+        ];
+        let expected = fee_payers(&pending);
+
+        let (parallel, deferred) =
+            TransactionScheduler::next_parallel_batch(pending);
+        assert_eq!(fee_payers(&parallel), expected);
+        assert!(deferred.is_empty());
+    }
+
+    #[test]
+    fn test_deferred_transactions_execute_in_next_batch() {
+        let account = Pubkey::new_unique();
+        let pending = vec![
+            transaction(&[account], &[]),
+            transaction(&[account], &[]),
+            transaction(&[Pubkey::new_unique()], &[]),
+        ];
+        let payers = fee_payers(&pending);
+
+        let batches = batches(pending);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(fee_payers(&batches[0]), vec![payers[0], payers[2]]);
+        assert_eq!(fee_payers(&batches[1]), vec![payers[1]]);
+    }
+
+    #[test]
+    fn test_transaction_conflicting_with_deferred_one_is_deferred() {
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        // The last transaction doesn't conflict with the first one, but it
+        // has to execute after the second one which is deferred
+        let pending = vec![
+            transaction(&[first], &[]),
+            transaction(&[first], &[second]),
+            transaction(&[second], &[]),
+        ];
+        let payers = fee_payers(&pending);
+
+        let (parallel, deferred) =
+            TransactionScheduler::next_parallel_batch(pending);
+        assert_eq!(fee_payers(&parallel), vec![payers[0]]);
+        assert_eq!(fee_payers(&deferred), vec![payers[1], payers[2]]);
+
+        let batches = batches(deferred);
+        assert_eq!(batches.len(), 2);
+        assert_eq!(fee_payers(&batches[0]), vec![payers[1]]);
+        assert_eq!(fee_payers(&batches[1]), vec![payers[2]]);
+    }
+
+    #[test]
+    fn test_conflicting_read_and_write_never_execute_in_same_batch() {
+        let account = Pubkey::new_unique();
+        let pending = vec![
+            transaction(&[Pubkey::new_unique()], &[account]),
+            transaction(&[account], &[]),
+            transaction(&[Pubkey::new_unique()], &[account]),
+            transaction(&[account], &[]),
+            transaction(&[Pubkey::new_unique()], &[account]),
+        ];
+        let payers = fee_payers(&pending);
+
+        let batches = batches(pending);
+        for batch in &batches {
+            let mut locks = ReadWriteAccountSet::default();
+            assert!(batch
+                .iter()
+                .all(|transaction| locks.take_locks(transaction.message())));
+        }
+        // Conflicting transactions keep the order they were scheduled in
+        assert_eq!(
+            batches
+                .iter()
+                .map(|batch| fee_payers(batch))
+                .collect::<Vec<_>>(),
+            vec![
+                vec![payers[0]],
+                vec![payers[1]],
+                vec![payers[2]],
+                vec![payers[3]],
+                vec![payers[4]],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fee_priority_orders_by_compute_unit_price() {
+        let mut pending = vec![
+            transaction(&[Pubkey::new_unique()], &[]),
+            priced_transaction(10),
+            priced_transaction(1_000),
+            priced_transaction(100),
+        ];
+        let payers = fee_payers(&pending);
+
+        TransactionScheduler::order_pending(
+            &mut pending,
+            TransactionOrdering::FeePriority,
+        );
+        // Transactions without a price are ordered as if they paid nothing
+        assert_eq!(
+            fee_payers(&pending),
+            vec![payers[2], payers[3], payers[1], payers[0]]
+        );
+    }
+
+    #[test]
+    fn test_fee_priority_keeps_order_of_equal_prices() {
+        let mut pending = vec![
+            priced_transaction(10),
+            priced_transaction(100),
+            priced_transaction(10),
+            priced_transaction(100),
+            priced_transaction(10),
+        ];
+        let payers = fee_payers(&pending);
+
+        TransactionScheduler::order_pending(
+            &mut pending,
+            TransactionOrdering::FeePriority,
+        );
+        assert_eq!(
+            fee_payers(&pending),
+            vec![payers[1], payers[3], payers[0], payers[2], payers[4]]
+        );
+    }
+
+    #[test]
+    fn test_fifo_keeps_order_scheduled_in() {
+        let mut pending = vec![
+            priced_transaction(10),
+            priced_transaction(1_000),
+            priced_transaction(100),
+        ];
+        let payers = fee_payers(&pending);
+
+        TransactionScheduler::order_pending(
+            &mut pending,
+            TransactionOrdering::Fifo,
+        );
+        assert_eq!(fee_payers(&pending), payers);
+    }
+}
@@ -0,0 +1,289 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use ed25519_dalek::{verify_batch, PublicKey};
+use lazy_static::lazy_static;
+use log::{debug, trace};
+use rayon::{prelude::*, ThreadPool};
+use solana_rayon_threadlimit::get_max_thread_count;
+use solana_sdk::{
+    message::SanitizedMessage,
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{Result, SanitizedTransaction, TransactionError},
+};
+use tokio::sync::oneshot;
+
+/// Max number of queued transactions the verifier considers at once
+const MAX_PENDING_TRANSACTIONS: usize = 1024;
+
+/// Max number of transactions whose signatures are verified together
+const MAX_TRANSACTIONS_PER_BATCH: usize = 64;
+
+lazy_static! {
+    pub static ref SIGNATURE_VERIFIER: SignatureVerifier =
+        SignatureVerifier::spawn();
+    static ref SIGVERIFY_THREAD_POOL: ThreadPool =
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(get_max_thread_count())
+            .thread_name(|i| format!("solSigVerify{i:02}"))
+            .build()
+            .unwrap();
+}
+
+struct PendingVerification {
+    message: Vec<u8>,
+    signatures: Vec<Signature>,
+    signers: Vec<Pubkey>,
+    result_sender: oneshot::Sender<Result<()>>,
+}
+
+impl PendingVerification {
+    fn new(
+        transaction: &SanitizedTransaction,
+        result_sender: oneshot::Sender<Result<()>>,
+    ) -> Self {
+        let message = match transaction.message() {
+            SanitizedMessage::Legacy(legacy) => legacy.message.serialize(),
+            SanitizedMessage::V0(loaded) => loaded.message.serialize(),
+        };
+        Self {
+            message,
+            signatures: transaction.signatures().to_vec(),
+            signers: transaction
+                .message()
+                .account_keys()
+                .iter()
+                .take(transaction.signatures().len())
+                .copied()
+                .collect(),
+            result_sender,
+        }
+    }
+
+    fn signed(&self) -> impl Iterator<Item = (&Signature, &Pubkey)> {
+        self.signatures.iter().zip(self.signers.iter())
+    }
+
+    /// Verifies each signature strictly, the same way as
+    /// [SanitizedTransaction::verify]
+    fn verify_each(&self) -> bool {
+        self.signed().all(|(signature, signer)| {
+            signature.verify(signer.as_ref(), &self.message)
+        })
+    }
+}
+
+/// Verifies the signatures of transactions submitted from any thread in
+/// batches on a dedicated thread pool.
+///
+/// Transactions queued while the verifier is busy are split into batches
+/// which are verified in parallel. The signatures of a batch are verified at
+/// once via ed25519 batch verification which is considerably cheaper than
+/// verifying them one by one. Only if a batch fails, its transactions are
+/// verified one by one to find the invalid ones.
+///
+/// Batch verification accepts some signatures strict verification rejects
+/// if the nonce or public key has a torsion component, the signatures of
+/// such transactions are always verified strictly.
+pub struct SignatureVerifier {
+    sender: Sender<PendingVerification>,
+}
+
+impl SignatureVerifier {
+    fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("solSigVerifier".to_string())
+            .spawn(move || Self::run(receiver))
+            .expect("failed to spawn signature verifier thread");
+        Self { sender }
+    }
+
+    /// Queues the transaction and waits until its signatures were verified
+    /// without blocking the calling task.
+    pub async fn verify(
+        &self,
+        transaction: &SanitizedTransaction,
+    ) -> Result<()> {
+        let (result_sender, result_receiver) = oneshot::channel();
+        self.sender
+            .send(PendingVerification::new(transaction, result_sender))
+            .expect("signature verifier stopped");
+        result_receiver
+            .await
+            .expect("signature verifier dropped a transaction")
+    }
+
+    fn run(receiver: Receiver<PendingVerification>) {
+        while let Ok(next) = receiver.recv() {
+            let mut pending = vec![next];
+            pending
+                .extend(receiver.try_iter().take(MAX_PENDING_TRANSACTIONS - 1));
+            // Spread small amounts of transactions across all threads
+            let batch_size = pending
+                .len()
+                .div_ceil(SIGVERIFY_THREAD_POOL.current_num_threads())
+                .min(MAX_TRANSACTIONS_PER_BATCH);
+            trace!(
+                "Verifying signatures of {} transactions in batches of {}",
+                pending.len(),
+                batch_size
+            );
+            SIGVERIFY_THREAD_POOL.install(|| {
+                pending
+                    .into_par_iter()
+                    .chunks(batch_size)
+                    .for_each(Self::verify_batch)
+            });
+        }
+        debug!("Signature verifier stopped");
+    }
+
+    fn verify_batch(batch: Vec<PendingVerification>) {
+        let batch_verified = batch.len() > 1 && Self::batch_verify(&batch);
+        for pending in batch {
+            let result = if batch_verified || pending.verify_each() {
+                Ok(())
+            } else {
+                Err(TransactionError::SignatureFailure)
+            };
+            // The caller may have given up waiting for the result
+            let _ = pending.result_sender.send(result);
+        }
+    }
+
+    /// Returns true if all signatures of the batch are valid, false if any
+    /// of them is invalid or needs to be verified strictly
+    fn batch_verify(batch: &[PendingVerification]) -> bool {
+        let mut messages = Vec::new();
+        let mut signatures = Vec::new();
+        let mut public_keys = Vec::new();
+        for pending in batch {
+            for (signature, signer) in pending.signed() {
+                if has_torsion_component(signature, signer) {
+                    return false;
+                }
+                let (Ok(signature), Ok(public_key)) = (
+                    ed25519_dalek::Signature::from_bytes(signature.as_ref()),
+                    PublicKey::from_bytes(signer.as_ref()),
+                ) else {
+                    return false;
+                };
+                messages.push(pending.message.as_slice());
+                signatures.push(signature);
+                public_keys.push(public_key);
+            }
+        }
+        verify_batch(&messages, &signatures, &public_keys).is_ok()
+    }
+}
+
+/// Strict verification rejects signatures of small order public keys or
+/// nonces. Batch verification checks the cofactored equation which ignores
+/// the torsion component of points, hence any point which is not torsion
+/// free needs to be verified strictly.
+fn has_torsion_component(signature: &Signature, signer: &Pubkey) -> bool {
+    let has_torsion = |bytes: &[u8]| {
+        CompressedEdwardsY::from_slice(bytes)
+            .decompress()
+            .is_none_or(|point| {
+                point.is_small_order() || !point.is_torsion_free()
+            })
+    };
+    has_torsion(&signature.as_ref()[..32]) || has_torsion(signer.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use curve25519_dalek::constants::EIGHT_TORSION;
+    use solana_sdk::{
+        hash::Hash,
+        signature::{Keypair, Signer},
+        system_transaction,
+    };
+
+    use super::*;
+
+    /// Adds a point of order 8 to the compressed point
+    fn with_torsion(bytes: &[u8]) -> [u8; 32] {
+        let point = CompressedEdwardsY::from_slice(bytes).decompress().unwrap();
+        (point + EIGHT_TORSION[1]).compress().to_bytes()
+    }
+
+    #[test]
+    fn test_valid_signature_has_no_torsion_component() {
+        let signer = Keypair::new();
+        let signature = signer.sign_message(b"message");
+
+        assert!(!has_torsion_component(&signature, &signer.pubkey()));
+    }
+
+    #[test]
+    fn test_mixed_order_nonce_has_torsion_component() {
+        let signer = Keypair::new();
+        let signature = signer.sign_message(b"message");
+        let mut bytes = [0; 64];
+        bytes[..32].copy_from_slice(&with_torsion(&signature.as_ref()[..32]));
+        bytes[32..].copy_from_slice(&signature.as_ref()[32..]);
+
+        assert!(has_torsion_component(
+            &Signature::from(bytes),
+            &signer.pubkey()
+        ));
+    }
+
+    #[test]
+    fn test_mixed_order_public_key_has_torsion_component() {
+        let signer = Keypair::new();
+        let signature = signer.sign_message(b"message");
+        let public_key = Pubkey::from(with_torsion(signer.pubkey().as_ref()));
+
+        assert!(has_torsion_component(&signature, &public_key));
+    }
+
+    #[test]
+    fn test_small_order_public_key_has_torsion_component() {
+        let signer = Keypair::new();
+        let signature = signer.sign_message(b"message");
+        let public_key = Pubkey::from(EIGHT_TORSION[2].compress().to_bytes());
+
+        assert!(has_torsion_component(&signature, &public_key));
+    }
+
+    fn transfer(payer: &Keypair) -> SanitizedTransaction {
+        SanitizedTransaction::from_transaction_for_tests(
+            system_transaction::transfer(
+                payer,
+                &Pubkey::new_unique(),
+                1,
+                Hash::default(),
+            ),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_verifies_concurrently_queued_transactions() {
+        let valid = transfer(&Keypair::new());
+        let mut transaction = system_transaction::transfer(
+            &Keypair::new(),
+            &Pubkey::new_unique(),
+            1,
+            Hash::default(),
+        );
+        transaction.signatures[0] = Keypair::new().sign_message(b"message");
+        let invalid =
+            SanitizedTransaction::from_transaction_for_tests(transaction);
+
+        let (valid_result, invalid_result) = tokio::join!(
+            SIGNATURE_VERIFIER.verify(&valid),
+            SIGNATURE_VERIFIER.verify(&invalid)
+        );
+
+        assert_eq!(valid_result, Ok(()));
+        assert_eq!(invalid_result, Err(TransactionError::SignatureFailure));
+    }
+}
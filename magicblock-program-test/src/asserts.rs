@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use magicblock_program::ScheduledCommit;
+use solana_sdk::{clock::Slot, pubkey::Pubkey};
+
+/// Asserts that the commit includes exactly the provided accounts
+#[track_caller]
+pub fn assert_commit_accounts(commit: &ScheduledCommit, pubkeys: &[Pubkey]) {
+    let committed = commit
+        .accounts
+        .iter()
+        .map(|account| account.pubkey)
+        .collect::<HashSet<_>>();
+    let expected = pubkeys.iter().copied().collect::<HashSet<_>>();
+    assert_eq!(
+        committed, expected,
+        "commit {} includes other accounts than expected",
+        commit.id
+    );
+}
+
+/// Asserts that the commit includes exactly the provided accounts and that
+/// it undelegates them
+#[track_caller]
+pub fn assert_commit_and_undelegate_accounts(
+    commit: &ScheduledCommit,
+    pubkeys: &[Pubkey],
+) {
+    assert_commit_accounts(commit, pubkeys);
+    assert!(
+        commit.request_undelegation,
+        "commit {} does not request undelegation",
+        commit.id
+    );
+}
+
+/// Asserts that the commit was scheduled by the provided payer
+#[track_caller]
+pub fn assert_commit_payer(commit: &ScheduledCommit, payer: &Pubkey) {
+    assert_eq!(
+        &commit.payer, payer,
+        "commit {} was scheduled by another payer",
+        commit.id
+    );
+}
+
+/// Asserts that the commit was scheduled to be executed at the provided slot
+#[track_caller]
+pub fn assert_commit_executes_at_slot(commit: &ScheduledCommit, slot: Slot) {
+    assert_eq!(
+        commit.execute_at_slot,
+        Some(slot),
+        "commit {} is not executed at the expected slot",
+        commit.id
+    );
+}
+
+/// Asserts that no commit was realized by the committer
+#[track_caller]
+pub fn assert_no_commits(commits: &[ScheduledCommit]) {
+    assert!(
+        commits.is_empty(),
+        "expected no commits, but got {:?}",
+        commits.iter().map(|commit| commit.id).collect::<Vec<_>>()
+    );
+}
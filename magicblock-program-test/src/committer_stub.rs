@@ -0,0 +1,81 @@
+use std::{collections::HashSet, sync::Arc};
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use log::*;
+use magicblock_bank::bank::Bank;
+use magicblock_processor::execute_transaction::execute_legacy_transaction;
+use magicblock_program::{
+    register_scheduled_commit_sent, ScheduledCommit, SentCommit,
+};
+use solana_sdk::transaction;
+
+// -----------------
+// CommitterStub
+// -----------------
+/// Stands in for the committer of the validator.
+///
+/// It realizes scheduled commits on the ephemeral rollup only, i.e. it records
+/// that they were sent the same way the validator does, but sends nothing to
+/// a remote cluster. The commits it realized are provided via a channel.
+pub struct CommitterStub {
+    sender: Sender<ScheduledCommit>,
+    receiver: Receiver<ScheduledCommit>,
+}
+
+impl Default for CommitterStub {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded();
+        Self { sender, receiver }
+    }
+}
+
+impl CommitterStub {
+    /// Receives the commits realized by the stub in the order they were due
+    pub fn receiver(&self) -> &Receiver<ScheduledCommit> {
+        &self.receiver
+    }
+
+    /// Takes all commits realized since the last time they were taken
+    pub fn take_commits(&self) -> Vec<ScheduledCommit> {
+        self.receiver.try_iter().collect()
+    }
+
+    pub(crate) fn commit(
+        &self,
+        bank: &Arc<Bank>,
+        commit: ScheduledCommit,
+    ) -> transaction::Result<()> {
+        // All accounts are considered committed successfully
+        register_scheduled_commit_sent(SentCommit {
+            commit_id: commit.id,
+            slot: commit.slot,
+            blockhash: commit.blockhash,
+            payer: commit.payer,
+            chain_signatures: vec![],
+            included_pubkeys: commit
+                .accounts
+                .iter()
+                .map(|account| account.pubkey)
+                .collect(),
+            excluded_pubkeys: vec![],
+            feepayers: HashSet::new(),
+            requested_undelegation: commit.request_undelegation,
+            simulation_errors: vec![],
+            atomic: commit.atomic,
+            atomic_error: None,
+        });
+        let signature = execute_legacy_transaction(
+            commit.commit_sent_transaction.clone(),
+            bank,
+            None,
+        )?;
+        debug!(
+            "Committed scheduled commit {} with internal signature: {}",
+            commit.id, signature
+        );
+        self.sender
+            .send(commit)
+            .expect("receiver is owned by the stub");
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! Unit test harness for programs integrating the magic program.
+//!
+//! ```ignore
+//! let mut program_test = ProgramTest::default();
+//! program_test.add_program(program_id, "target/deploy/my_program.so");
+//! let ctx = program_test.start();
+//!
+//! ctx.process_instructions(&[schedule_commit_ix], &[])?;
+//! ctx.advance_slot();
+//!
+//! let commits = ctx.committer().take_commits();
+//! assert_commit_accounts(&commits[0], &[game_pda]);
+//! ```
+pub mod asserts;
+mod committer_stub;
+mod program_test;
+
+pub use committer_stub::CommitterStub;
+pub use program_test::{ProgramTest, ProgramTestContext};
@@ -0,0 +1,271 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use log::*;
+use magicblock_bank::{
+    bank::Bank,
+    genesis_utils::create_genesis_config_with_leader_and_fees,
+    program_loader::{add_loadables, LoadableProgram},
+};
+use magicblock_core::magic_program::{self, MAGIC_CONTEXT_PUBKEY};
+use magicblock_processor::execute_transaction::execute_legacy_transaction;
+use magicblock_program::{
+    magicblock_instruction::accept_scheduled_commits, MagicContext,
+    TransactionScheduler,
+};
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount},
+    clock::Slot,
+    hash::Hash,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature},
+    signer::Signer,
+    transaction::{self, Transaction},
+};
+use test_tools::{
+    account::fund_account, bank::bank_for_tests,
+    validator::init_started_validator,
+};
+
+use crate::committer_stub::CommitterStub;
+
+/// Lamports the payer of each [ProgramTestContext] starts out with
+const PAYER_LAMPORTS: u64 = LAMPORTS_PER_SOL * 1_000;
+
+/// The magic program keeps scheduled commits in process wide state, thus
+/// only one [ProgramTestContext] may be alive at a time
+static CONTEXT_LOCK: Mutex<()> = Mutex::new(());
+
+// -----------------
+// ProgramTest
+// -----------------
+/// Sets up a [ProgramTestContext] including the programs under test and the
+/// accounts they operate on.
+#[derive(Default)]
+pub struct ProgramTest {
+    programs: Vec<LoadableProgram>,
+    accounts: Vec<(Pubkey, AccountSharedData)>,
+}
+
+impl ProgramTest {
+    /// Adds the program compiled to the provided shared object, it is deployed
+    /// with the upgradeable loader
+    pub fn add_program(
+        &mut self,
+        program_id: Pubkey,
+        program_path: impl Into<String>,
+    ) -> &mut Self {
+        self.programs.push((program_id, program_path.into()).into());
+        self
+    }
+
+    /// Adds an account, i.e. a PDA that was delegated to the ephemeral rollup
+    pub fn add_account(
+        &mut self,
+        pubkey: Pubkey,
+        account: AccountSharedData,
+    ) -> &mut Self {
+        self.accounts.push((pubkey, account));
+        self
+    }
+
+    /// Starts a bank including the magic program as well as the added
+    /// programs and accounts.
+    /// Blocks until no other context is alive.
+    pub fn start(self) -> ProgramTestContext {
+        // A test panicking while its context is alive must not fail the others
+        let guard = CONTEXT_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let genesis_config = create_genesis_config_with_leader_and_fees(
+            u64::MAX,
+            &Pubkey::new_unique(),
+        )
+        .genesis_config;
+        let bank = Arc::new(
+            bank_for_tests(&genesis_config, None, None)
+                .expect("failed to initialize bank"),
+        );
+        init_started_validator(&bank);
+        // Commits left behind by a previous context don't concern this one
+        TransactionScheduler::default().clear_scheduled_commits();
+
+        // NOTE: the magic context is initialized with these properties at
+        // validator startup
+        bank.store_account(
+            MAGIC_CONTEXT_PUBKEY,
+            AccountSharedData::new(
+                u64::MAX / 2,
+                MagicContext::SIZE,
+                &magic_program::id(),
+            ),
+        );
+        add_loadables(&bank, &self.programs).expect("failed to load programs");
+        bank.store_accounts(self.accounts);
+
+        let payer = Keypair::new();
+        fund_account(&bank, &payer.pubkey(), PAYER_LAMPORTS);
+
+        ProgramTestContext {
+            bank,
+            payer,
+            committer: CommitterStub::default(),
+            _guard: guard,
+        }
+    }
+}
+
+// -----------------
+// ProgramTestContext
+// -----------------
+/// Bank including the magic program whose slots advance on demand.
+///
+/// Commits scheduled by programs are accepted whenever the slot advances and
+/// the ones that are due are handed to the [CommitterStub] right away, just
+/// as the validator does at the start of each slot.
+pub struct ProgramTestContext {
+    pub bank: Arc<Bank>,
+    /// Funded account paying for all transactions processed via
+    /// [ProgramTestContext::process_instructions]
+    pub payer: Keypair,
+    committer: CommitterStub,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl ProgramTestContext {
+    pub fn committer(&self) -> &CommitterStub {
+        &self.committer
+    }
+
+    pub fn last_blockhash(&self) -> Hash {
+        self.bank.last_blockhash()
+    }
+
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.bank.get_account(pubkey)
+    }
+
+    pub fn set_account(&self, pubkey: Pubkey, account: AccountSharedData) {
+        self.bank.store_account(pubkey, account);
+    }
+
+    pub fn process_transaction(
+        &self,
+        transaction: Transaction,
+    ) -> transaction::Result<Signature> {
+        execute_legacy_transaction(transaction, &self.bank, None)
+    }
+
+    /// Processes the instructions in a single transaction paid by the
+    /// [ProgramTestContext::payer] which signs it together with the provided
+    /// signers
+    pub fn process_instructions(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> transaction::Result<Signature> {
+        let signers = [&self.payer]
+            .into_iter()
+            .chain(signers.iter().copied())
+            .collect::<Vec<_>>();
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &signers,
+            self.last_blockhash(),
+        );
+        self.process_transaction(transaction)
+    }
+
+    /// Advances to the next slot, accepts the commits scheduled since the
+    /// last slot and realizes the ones that are due via the [CommitterStub].
+    pub fn advance_slot(&self) -> Slot {
+        let slot = self.bank.advance_slot();
+
+        let magic_context = self
+            .bank
+            .get_account(&MAGIC_CONTEXT_PUBKEY)
+            .expect("MagicContext account is created on start");
+        if MagicContext::has_scheduled_commits(magic_context.data())
+            || MagicContext::has_due_recurring_commits(
+                magic_context.data(),
+                slot,
+            )
+        {
+            let tx = accept_scheduled_commits(self.last_blockhash());
+            self.process_transaction(tx)
+                .expect("failed to accept scheduled commits");
+        }
+
+        for commit in
+            TransactionScheduler::default().take_due_scheduled_commits(slot)
+        {
+            let id = commit.id;
+            if let Err(err) = self.committer.commit(&self.bank, commit) {
+                error!("Failed to realize scheduled commit {}: {:?}", id, err);
+            }
+        }
+        slot
+    }
+
+    /// Advances the given number of slots, see [ProgramTestContext::advance_slot]
+    pub fn advance_slots(&self, count: u64) -> Slot {
+        (0..count).fold(self.bank.slot(), |_, _| self.advance_slot())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use magicblock_program::magicblock_instruction::{
+        schedule_commit, schedule_commit_at, CommitTarget,
+    };
+
+    use super::*;
+    use crate::asserts::{
+        assert_commit_accounts, assert_commit_executes_at_slot,
+        assert_commit_payer, assert_no_commits,
+    };
+
+    #[test]
+    fn test_commit_is_realized_once_slot_advances() {
+        let ctx = ProgramTest::default().start();
+        let payer = ctx.payer.pubkey();
+
+        // The payer can commit itself since it signs the transaction
+        let tx = schedule_commit(&ctx.payer, vec![payer], ctx.last_blockhash());
+        ctx.process_transaction(tx).unwrap();
+        assert_no_commits(&ctx.committer().take_commits());
+
+        ctx.advance_slot();
+        let commits = ctx.committer().take_commits();
+        assert_eq!(commits.len(), 1);
+        assert_commit_accounts(&commits[0], &[payer]);
+        assert_commit_payer(&commits[0], &payer);
+    }
+
+    #[test]
+    fn test_commit_scheduled_in_advance_is_realized_when_due() {
+        let ctx = ProgramTest::default().start();
+        let payer = ctx.payer.pubkey();
+
+        let tx = schedule_commit_at(
+            &ctx.payer,
+            vec![payer],
+            CommitTarget::Delay(3),
+            false,
+            ctx.last_blockhash(),
+        );
+        let scheduled_at = ctx.bank.slot();
+        ctx.process_transaction(tx).unwrap();
+
+        ctx.advance_slots(2);
+        assert_no_commits(&ctx.committer().take_commits());
+
+        ctx.advance_slot();
+        let commits = ctx.committer().take_commits();
+        assert_eq!(commits.len(), 1);
+        assert_commit_executes_at_slot(&commits[0], scheduled_at + 3);
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::{
+    atomic::{AtomicI64, AtomicUsize, Ordering},
+    Arc,
+};
+
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+use magicblock_geyser_plugin::types::UpdateReceiver;
+use magicblock_metrics::metrics;
+
+// -----------------
+// Connection
+// -----------------
+/// Metadata of a WebSocket connection, shared by all of its subscriptions
+#[derive(Clone)]
+pub struct Connection {
+    session: Arc<Session>,
+    metrics: Arc<ConnectionMetrics>,
+}
+
+impl Connection {
+    pub fn new(id: String, session: Session) -> Self {
+        Self {
+            session: Arc::new(session),
+            metrics: Arc::new(ConnectionMetrics::new(id)),
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<ConnectionMetrics> {
+        self.metrics.clone()
+    }
+}
+
+impl jsonrpc_core::Metadata for Connection {}
+
+impl PubSubMetadata for Connection {
+    fn session(&self) -> Option<Arc<Session>> {
+        Some(self.session.clone())
+    }
+}
+
+// -----------------
+// ConnectionMetrics
+// -----------------
+/// Tracks the notifications queued and dropped for the subscriptions of a
+/// connection, its metrics are removed once the connection closed and all
+/// of its subscriptions ended
+pub struct ConnectionMetrics {
+    id: String,
+    queued: AtomicI64,
+}
+
+impl ConnectionMetrics {
+    fn new(id: String) -> Self {
+        metrics::inc_pubsub_connections();
+        Self {
+            id,
+            queued: AtomicI64::new(0),
+        }
+    }
+
+    fn add_queued(&self, delta: i64) {
+        if delta == 0 {
+            return;
+        }
+        let queued = self.queued.fetch_add(delta, Ordering::Relaxed) + delta;
+        metrics::set_pubsub_connection_queue(&self.id, queued);
+    }
+
+    fn add_dropped(&self, count: u64) {
+        metrics::inc_pubsub_connection_dropped(&self.id, count);
+    }
+}
+
+impl Drop for ConnectionMetrics {
+    fn drop(&mut self) {
+        metrics::remove_pubsub_connection(&self.id);
+    }
+}
+
+// -----------------
+// SubscriptionMetrics
+// -----------------
+/// Counts a subscription as active for as long as it is alive and records
+/// the notifications sent through it
+pub struct SubscriptionMetrics {
+    kind: &'static str,
+    connection: Arc<ConnectionMetrics>,
+    queued: AtomicUsize,
+}
+
+impl SubscriptionMetrics {
+    pub fn new(kind: &'static str, connection: Arc<ConnectionMetrics>) -> Self {
+        metrics::inc_pubsub_subscriptions(kind);
+        Self {
+            kind,
+            connection,
+            queued: AtomicUsize::new(0),
+        }
+    }
+
+    /// Records a notification that was received from geyser, along with the
+    /// ones still queued and the ones dropped since the last notification
+    pub fn record_notification(&self, updates: &UpdateReceiver) {
+        metrics::inc_pubsub_notifications(self.kind);
+        self.set_queued(updates.len());
+        let dropped = updates.take_dropped();
+        if dropped > 0 {
+            self.connection.add_dropped(dropped);
+        }
+    }
+
+    fn set_queued(&self, queued: usize) {
+        let previous = self.queued.swap(queued, Ordering::Relaxed);
+        self.connection.add_queued(queued as i64 - previous as i64);
+    }
+}
+
+impl Drop for SubscriptionMetrics {
+    fn drop(&mut self) {
+        self.set_queued(0);
+        metrics::dec_pubsub_subscriptions(self.kind);
+    }
+}
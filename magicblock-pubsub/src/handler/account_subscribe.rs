@@ -5,7 +5,7 @@ use solana_sdk::pubkey::Pubkey;
 
 use super::common::UpdateHandler;
 use crate::{
-    errors::reject_internal_error,
+    connection::SubscriptionMetrics, errors::reject_internal_error,
     notification_builder::AccountNotificationBuilder, types::AccountParams,
 };
 
@@ -14,6 +14,7 @@ pub async fn handle_account_subscribe(
     subscriber: Subscriber,
     params: &AccountParams,
     geyser_service: &GeyserRpcService,
+    metrics: &SubscriptionMetrics,
 ) {
     let pubkey = match Pubkey::try_from(params.pubkey()) {
         Ok(pubkey) => pubkey,
@@ -40,6 +41,7 @@ pub async fn handle_account_subscribe(
         return;
     };
     while let Some(msg) = geyser_rx.recv().await {
+        metrics.record_notification(&geyser_rx);
         if !handler.handle(msg) {
             break;
         }
@@ -7,7 +7,7 @@ use solana_sdk::pubkey::Pubkey;
 
 use super::common::UpdateHandler;
 use crate::{
-    errors::reject_internal_error,
+    connection::SubscriptionMetrics, errors::reject_internal_error,
     notification_builder::LogsNotificationBuilder, types::LogsParams,
 };
 
@@ -16,6 +16,7 @@ pub async fn handle_logs_subscribe(
     subscriber: Subscriber,
     params: &LogsParams,
     geyser_service: &GeyserRpcService,
+    metrics: &SubscriptionMetrics,
 ) {
     let key = match params.filter() {
         RpcTransactionLogsFilter::All
@@ -47,6 +48,7 @@ pub async fn handle_logs_subscribe(
     };
 
     while let Some(msg) = geyser_rx.recv().await {
+        metrics.record_notification(&geyser_rx);
         if !handler.handle(msg) {
             break;
         }
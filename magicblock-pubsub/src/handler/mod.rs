@@ -1,9 +1,10 @@
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 use log::*;
 use tokio_util::sync::CancellationToken;
 
 use crate::{
+    connection::{ConnectionMetrics, SubscriptionMetrics},
     handler::{
         account_subscribe::handle_account_subscribe,
         logs_subscribe::handle_logs_subscribe,
@@ -25,8 +26,10 @@ pub async fn handle_subscription(
     subscription: SubscriptionRequest,
     subid: u64,
     unsubscriber: CancellationToken,
+    connection: Arc<ConnectionMetrics>,
 ) {
     use SubscriptionRequest::*;
+    let metrics = SubscriptionMetrics::new(subscription.kind(), connection);
     match subscription {
         Account {
             subscriber,
@@ -42,6 +45,7 @@ pub async fn handle_subscription(
                         subscriber,
                         &params,
                         &geyser_service,
+                        &metrics,
                     ) => {
                 },
             };
@@ -60,6 +64,7 @@ pub async fn handle_subscription(
                         subscriber,
                         &params,
                         &geyser_service,
+                        &metrics,
                     ) => {
                 },
             };
@@ -75,7 +80,8 @@ pub async fn handle_subscription(
                 _ = handle_slot_subscribe(
                         subid,
                         subscriber,
-                        &geyser_service) => {
+                        &geyser_service,
+                        &metrics) => {
                 },
             };
         }
@@ -95,7 +101,8 @@ pub async fn handle_subscription(
                         subscriber,
                         &params,
                         &geyser_service,
-                        &bank) => {
+                        &bank,
+                        &metrics) => {
                 },
             };
         }
@@ -114,6 +121,7 @@ pub async fn handle_subscription(
                         subscriber,
                         &params,
                         &geyser_service,
+                        &metrics,
                     ) => {
                 },
             };
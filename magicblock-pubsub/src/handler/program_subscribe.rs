@@ -5,6 +5,7 @@ use solana_sdk::pubkey::Pubkey;
 
 use super::common::UpdateHandler;
 use crate::{
+    connection::SubscriptionMetrics,
     errors::reject_internal_error,
     notification_builder::{ProgramFilters, ProgramNotificationBuilder},
     types::ProgramParams,
@@ -15,6 +16,7 @@ pub async fn handle_program_subscribe(
     subscriber: Subscriber,
     params: &ProgramParams,
     geyser_service: &GeyserRpcService,
+    metrics: &SubscriptionMetrics,
 ) {
     let address = params.program_id();
     let config = params.config().clone().unwrap_or_default();
@@ -47,6 +49,7 @@ pub async fn handle_program_subscribe(
         return;
     };
     while let Some(msg) = geyser_rx.recv().await {
+        metrics.record_notification(&geyser_rx);
         if !handler.handle(msg) {
             break;
         }
@@ -11,6 +11,7 @@ use solana_sdk::{signature::Signature, transaction::TransactionError};
 
 use super::common::UpdateHandler;
 use crate::{
+    connection::SubscriptionMetrics,
     errors::reject_internal_error,
     notification_builder::SignatureNotificationBuilder,
     subscription::assign_sub_id,
@@ -23,6 +24,7 @@ pub async fn handle_signature_subscribe(
     params: &SignatureParams,
     geyser_service: &GeyserRpcService,
     bank: &Bank,
+    metrics: &SubscriptionMetrics,
 ) {
     let sig = match Signature::from_str(params.signature()) {
         Ok(sig) => sig,
@@ -45,6 +47,7 @@ pub async fn handle_signature_subscribe(
             "Sending initial signature status from bank: {} {:?}",
             slot, res
         );
+        metrics.record_notification(&geyser_rx);
         sink_notify_transaction_result(&sink, slot, subid, res.err());
         subscriptions_db
             .unsubscribe_from_signature(&sig, subid)
@@ -66,6 +69,7 @@ pub async fn handle_signature_subscribe(
     let Ok(Some(msg)) = rx.await else {
         return;
     };
+    metrics.record_notification(&geyser_rx);
     handler.handle(msg);
 }
 
@@ -2,12 +2,16 @@ use jsonrpc_pubsub::Subscriber;
 use magicblock_geyser_plugin::rpc::GeyserRpcService;
 
 use super::common::UpdateHandler;
-use crate::notification_builder::SlotNotificationBuilder;
+use crate::{
+    connection::SubscriptionMetrics,
+    notification_builder::SlotNotificationBuilder,
+};
 
 pub async fn handle_slot_subscribe(
     subid: u64,
     subscriber: Subscriber,
     geyser_service: &GeyserRpcService,
+    metrics: &SubscriptionMetrics,
 ) {
     let mut geyser_rx = geyser_service.slot_subscribe(subid).await;
 
@@ -22,6 +26,7 @@ pub async fn handle_slot_subscribe(
         return;
     };
     while let Some(msg) = geyser_rx.recv().await {
+        metrics.record_notification(&geyser_rx);
         if !handler.handle_slot_update(msg) {
             break;
         }
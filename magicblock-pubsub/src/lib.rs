@@ -1,3 +1,4 @@
+mod connection;
 pub mod errors;
 mod handler;
 mod notification_builder;
@@ -6,6 +6,7 @@ use magicblock_geyser_plugin::rpc::GeyserRpcService;
 use tokio::sync::mpsc;
 
 use crate::{
+    connection::ConnectionMetrics,
     errors::{reject_internal_error, PubsubError, PubsubResult},
     handler::handle_subscription,
     subscription::SubscriptionRequest,
@@ -16,12 +17,18 @@ use crate::{
 // -----------------
 // SubscriptionsReceiver
 // -----------------
+/// A subscription requested through the connection it belongs to
+type ConnectionSubscriptionRequest =
+    (SubscriptionRequest, Arc<ConnectionMetrics>);
+
 struct SubscriptionsReceiver {
-    subscriptions: mpsc::Receiver<SubscriptionRequest>,
+    subscriptions: mpsc::Receiver<ConnectionSubscriptionRequest>,
 }
 
 impl SubscriptionsReceiver {
-    pub fn new(subscriptions: mpsc::Receiver<SubscriptionRequest>) -> Self {
+    pub fn new(
+        subscriptions: mpsc::Receiver<ConnectionSubscriptionRequest>,
+    ) -> Self {
         Self { subscriptions }
     }
 }
@@ -31,7 +38,7 @@ impl SubscriptionsReceiver {
 // -----------------
 #[derive(Clone)]
 pub struct PubsubApi {
-    subscribe: mpsc::Sender<SubscriptionRequest>,
+    subscribe: mpsc::Sender<ConnectionSubscriptionRequest>,
     unsubscribe_tokens: UnsubscribeTokens,
 }
 
@@ -45,7 +52,8 @@ impl PubsubApi {
                 let mut subid: u64 = 0;
                 let mut actor = SubscriptionsReceiver::new(subscribe_rx);
 
-                while let Some(subscription) = actor.subscriptions.recv().await
+                while let Some((subscription, connection)) =
+                    actor.subscriptions.recv().await
                 {
                     subid += 1;
                     let unsubscriber = unsubscribe_tokens.add(subid);
@@ -53,6 +61,7 @@ impl PubsubApi {
                         subscription,
                         subid,
                         unsubscriber,
+                        connection,
                     ));
                 }
             });
@@ -69,13 +78,17 @@ impl PubsubApi {
         subscriber: Subscriber,
         params: AccountParams,
         geyser_service: Arc<GeyserRpcService>,
+        connection: Arc<ConnectionMetrics>,
     ) -> PubsubResult<()> {
         self.subscribe
-            .blocking_send(SubscriptionRequest::Account {
-                subscriber,
-                params,
-                geyser_service,
-            })
+            .blocking_send((
+                SubscriptionRequest::Account {
+                    subscriber,
+                    params,
+                    geyser_service,
+                },
+                connection,
+            ))
             .map_err(map_send_error)?;
 
         Ok(())
@@ -86,13 +99,17 @@ impl PubsubApi {
         subscriber: Subscriber,
         params: ProgramParams,
         geyser_service: Arc<GeyserRpcService>,
+        connection: Arc<ConnectionMetrics>,
     ) -> PubsubResult<()> {
         self.subscribe
-            .blocking_send(SubscriptionRequest::Program {
-                subscriber,
-                params,
-                geyser_service,
-            })
+            .blocking_send((
+                SubscriptionRequest::Program {
+                    subscriber,
+                    params,
+                    geyser_service,
+                },
+                connection,
+            ))
             .map_err(map_send_error)?;
 
         Ok(())
@@ -102,12 +119,16 @@ impl PubsubApi {
         &self,
         subscriber: Subscriber,
         geyser_service: Arc<GeyserRpcService>,
+        connection: Arc<ConnectionMetrics>,
     ) -> PubsubResult<()> {
         self.subscribe
-            .blocking_send(SubscriptionRequest::Slot {
-                subscriber,
-                geyser_service,
-            })
+            .blocking_send((
+                SubscriptionRequest::Slot {
+                    subscriber,
+                    geyser_service,
+                },
+                connection,
+            ))
             .map_err(map_send_error)?;
 
         Ok(())
@@ -119,14 +140,18 @@ impl PubsubApi {
         params: SignatureParams,
         geyser_service: Arc<GeyserRpcService>,
         bank: Arc<Bank>,
+        connection: Arc<ConnectionMetrics>,
     ) -> PubsubResult<()> {
         self.subscribe
-            .blocking_send(SubscriptionRequest::Signature {
-                subscriber,
-                params,
-                geyser_service,
-                bank,
-            })
+            .blocking_send((
+                SubscriptionRequest::Signature {
+                    subscriber,
+                    params,
+                    geyser_service,
+                    bank,
+                },
+                connection,
+            ))
             .map_err(map_send_error)?;
 
         Ok(())
@@ -137,13 +162,17 @@ impl PubsubApi {
         subscriber: Subscriber,
         params: LogsParams,
         geyser_service: Arc<GeyserRpcService>,
+        connection: Arc<ConnectionMetrics>,
     ) -> PubsubResult<()> {
         self.subscribe
-            .blocking_send(SubscriptionRequest::Logs {
-                subscriber,
-                params,
-                geyser_service,
-            })
+            .blocking_send((
+                SubscriptionRequest::Logs {
+                    subscriber,
+                    params,
+                    geyser_service,
+                },
+                connection,
+            ))
             .map_err(map_send_error)?;
 
         Ok(())
@@ -155,10 +184,10 @@ impl PubsubApi {
 }
 
 fn map_send_error(
-    err: mpsc::error::SendError<SubscriptionRequest>,
+    err: mpsc::error::SendError<ConnectionSubscriptionRequest>,
 ) -> PubsubError {
     let err_msg = format!("{:?}", err);
-    let subscription = err.0;
+    let (subscription, _) = err.0;
     let subscriber = subscription.into_subscriber();
     reject_internal_error(
         subscriber,
@@ -8,7 +8,10 @@ use jsonrpc_core::{futures, BoxFuture, MetaIoHandler, Params};
 use jsonrpc_pubsub::{
     PubSubHandler, Session, Subscriber, SubscriptionId, UnsubscribeRpcMethod,
 };
-use jsonrpc_ws_server::{CloseHandle, RequestContext, Server, ServerBuilder};
+use jsonrpc_ws_server::{
+    CloseHandle, DomainsValidation, Origin, RequestContext, Server,
+    ServerBuilder,
+};
 use log::*;
 use magicblock_bank::bank::Bank;
 use magicblock_geyser_plugin::rpc::GeyserRpcService;
@@ -16,6 +19,7 @@ use serde_json::Value;
 use solana_sdk::rpc_port::DEFAULT_RPC_PUBSUB_PORT;
 
 use crate::{
+    connection::Connection,
     errors::{ensure_and_try_parse_params, ensure_empty_params, PubsubResult},
     pubsub_api::PubsubApi,
     types::{AccountParams, LogsParams, ProgramParams, SignatureParams},
@@ -28,6 +32,8 @@ use crate::{
 pub struct PubsubConfig {
     socket: SocketAddr,
     max_connections: usize,
+    /// Origins allowed to open a WebSocket connection, any if empty
+    allowed_origins: Vec<String>,
 }
 
 impl PubsubConfig {
@@ -35,10 +41,12 @@ impl PubsubConfig {
         rpc_addr: IpAddr,
         rpc_port: u16,
         max_connections: usize,
+        allowed_origins: Vec<String>,
     ) -> Self {
         Self {
             socket: SocketAddr::new(rpc_addr, rpc_port + 1),
             max_connections,
+            allowed_origins,
         }
     }
 }
@@ -48,6 +56,7 @@ impl Default for PubsubConfig {
         Self {
             socket: SocketAddr::from(([0, 0, 0, 0], DEFAULT_RPC_PUBSUB_PORT)),
             max_connections: 16384,
+            allowed_origins: vec![],
         }
     }
 }
@@ -56,6 +65,24 @@ impl PubsubConfig {
     pub fn socket(&self) -> &SocketAddr {
         &self.socket
     }
+
+    /// Origins checked during the WebSocket handshake.
+    /// Requests without an `Origin` header (i.e. non-browser clients) are
+    /// always accepted.
+    fn origins_validation(&self) -> DomainsValidation<Origin> {
+        if self.allowed_origins.is_empty()
+            || self.allowed_origins.iter().any(|origin| origin == "*")
+        {
+            DomainsValidation::Disabled
+        } else {
+            DomainsValidation::AllowOnly(
+                self.allowed_origins
+                    .iter()
+                    .map(|origin| Origin::from(origin.as_str()))
+                    .collect(),
+            )
+        }
+    }
 }
 
 pub type PubsubServiceCloseHandle = Arc<RwLock<Option<CloseHandle>>>;
@@ -63,7 +90,7 @@ pub struct PubsubService {
     api: PubsubApi,
     geyser_service: Arc<GeyserRpcService>,
     config: PubsubConfig,
-    io: PubSubHandler<Arc<Session>>,
+    io: PubSubHandler<Connection>,
     bank: Arc<Bank>,
 }
 
@@ -92,14 +119,19 @@ impl PubsubService {
 
     #[allow(clippy::result_large_err)]
     pub fn start(self) -> jsonrpc_ws_server::Result<Server> {
-        let extractor =
-            |context: &RequestContext| Arc::new(Session::new(context.sender()));
+        let extractor = |context: &RequestContext| {
+            Connection::new(
+                context.session_id.to_string(),
+                Session::new(context.sender()),
+            )
+        };
 
         ServerBuilder::with_meta_extractor(self.io, extractor)
             // NOTE: we just set the max number of allowed connections to a reasonably high value
             // to satisfy most of the use cases, however this number cannot be arbitrarily large
             // due to the preallocation involved, and a large value will trigger an OOM Kill
             .max_connections(self.config.max_connections)
+            .allowed_origins(self.config.origins_validation())
             .start(&self.config.socket)
     }
 
@@ -152,7 +184,9 @@ impl PubsubService {
         let subscribe = {
             let api = self.api.clone();
             let geyser_service = self.geyser_service.clone();
-            move |params: Params, _, subscriber: Subscriber| {
+            move |params: Params,
+                  connection: Connection,
+                  subscriber: Subscriber| {
                 let (subscriber, account_params): (Subscriber, AccountParams) =
                     match ensure_and_try_parse_params(subscriber, params) {
                         Some((subscriber, params)) => (subscriber, params),
@@ -167,6 +201,7 @@ impl PubsubService {
                     subscriber,
                     account_params,
                     geyser_service.clone(),
+                    connection.metrics(),
                 ) {
                     error!("Failed to handle account subscribe: {:?}", err);
                 };
@@ -188,7 +223,9 @@ impl PubsubService {
         let subscribe = {
             let api = self.api.clone();
             let geyser_service = self.geyser_service.clone();
-            move |params: Params, _, subscriber: Subscriber| {
+            move |params: Params,
+                  connection: Connection,
+                  subscriber: Subscriber| {
                 let (subscriber, program_params): (Subscriber, ProgramParams) =
                     match ensure_and_try_parse_params(subscriber, params) {
                         Some((subscriber, params)) => (subscriber, params),
@@ -203,6 +240,7 @@ impl PubsubService {
                     subscriber,
                     program_params,
                     geyser_service.clone(),
+                    connection.metrics(),
                 ) {
                     error!("Failed to handle program subscribe: {:?}", err);
                 };
@@ -224,16 +262,20 @@ impl PubsubService {
         let subscribe = {
             let api = self.api.clone();
             let geyser_service = self.geyser_service.clone();
-            move |params: Params, _, subscriber: Subscriber| {
+            move |params: Params,
+                  connection: Connection,
+                  subscriber: Subscriber| {
                 let subscriber =
                     match ensure_empty_params(subscriber, &params, true) {
                         Some(subscriber) => subscriber,
                         None => return,
                     };
 
-                if let Err(err) =
-                    api.slot_subscribe(subscriber, geyser_service.clone())
-                {
+                if let Err(err) = api.slot_subscribe(
+                    subscriber,
+                    geyser_service.clone(),
+                    connection.metrics(),
+                ) {
                     error!("Failed to handle slot subscribe: {:?}", err);
                 };
             }
@@ -255,7 +297,9 @@ impl PubsubService {
             let api = self.api.clone();
             let geyser_service = self.geyser_service.clone();
             let bank = self.bank.clone();
-            move |params: Params, _, subscriber: Subscriber| {
+            move |params: Params,
+                  connection: Connection,
+                  subscriber: Subscriber| {
                 let (subscriber, params): (Subscriber, SignatureParams) =
                     match ensure_and_try_parse_params(subscriber, params) {
                         Some((subscriber, params)) => (subscriber, params),
@@ -269,6 +313,7 @@ impl PubsubService {
                     params,
                     geyser_service.clone(),
                     bank.clone(),
+                    connection.metrics(),
                 ) {
                     error!("Failed to handle signature subscribe: {:?}", err);
                 };
@@ -290,7 +335,9 @@ impl PubsubService {
         let subscribe = {
             let api = self.api.clone();
             let geyser_service = self.geyser_service.clone();
-            move |params: Params, _, subscriber: Subscriber| {
+            move |params: Params,
+                  connection: Connection,
+                  subscriber: Subscriber| {
                 let (subscriber, logs_params): (Subscriber, LogsParams) =
                     match ensure_and_try_parse_params(subscriber, params) {
                         Some((subscriber, params)) => (subscriber, params),
@@ -305,6 +352,7 @@ impl PubsubService {
                     subscriber,
                     logs_params,
                     geyser_service.clone(),
+                    connection.metrics(),
                 ) {
                     error!("Failed to handle logs subscribe: {:?}", err);
                 };
@@ -322,10 +370,10 @@ impl PubsubService {
         self
     }
 
-    fn create_unsubscribe(&self) -> impl UnsubscribeRpcMethod<Arc<Session>> {
+    fn create_unsubscribe(&self) -> impl UnsubscribeRpcMethod<Connection> {
         let actor = self.api.clone();
         move |id: SubscriptionId,
-              _session: Option<Arc<Session>>|
+              _connection: Option<Connection>|
               -> BoxFuture<jsonrpc_core::Result<Value>> {
             match id {
                 SubscriptionId::Number(id) => {
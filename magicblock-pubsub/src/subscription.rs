@@ -36,6 +36,18 @@ pub enum SubscriptionRequest {
 }
 
 impl SubscriptionRequest {
+    /// The type of the subscription used to label its metrics
+    pub fn kind(&self) -> &'static str {
+        use SubscriptionRequest::*;
+        match self {
+            Account { .. } => "account",
+            Program { .. } => "program",
+            Slot { .. } => "slot",
+            Signature { .. } => "signature",
+            Logs { .. } => "logs",
+        }
+    }
+
     pub fn into_subscriber(self) -> Subscriber {
         use SubscriptionRequest::*;
         match self {
@@ -0,0 +1,23 @@
+use jsonrpc_http_server::{AccessControlAllowOrigin, DomainsValidation};
+
+/// Converts the configured `cors_allowed_origins` into the validation used
+/// by the HTTP server.
+/// An empty list or one containing `"*"` allows any origin which matches the
+/// behavior of the validator before origins were configurable.
+pub fn cors_domains(
+    allowed_origins: &[String],
+) -> DomainsValidation<AccessControlAllowOrigin> {
+    if allowed_origins.is_empty()
+        || allowed_origins.iter().any(|origin| origin == "*")
+    {
+        return DomainsValidation::AllowOnly(vec![
+            AccessControlAllowOrigin::Any,
+        ]);
+    }
+    DomainsValidation::AllowOnly(
+        allowed_origins
+            .iter()
+            .map(|origin| AccessControlAllowOrigin::from(origin.as_str()))
+            .collect(),
+    )
+}
@@ -0,0 +1,62 @@
+use jsonrpc_core::{futures::future, BoxFuture, Error, Result};
+use log::*;
+use solana_rpc_client_api::{
+    filter::RpcFilterType, request::MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+};
+
+use crate::{
+    filters::verify_filter,
+    json_rpc_request_processor::JsonRpcRequestProcessor,
+    traits::rpc_admin::{Admin, RpcClonedProgramAccounts, RpcLedgerBackup},
+    utils::verify_pubkey,
+};
+
+pub struct AdminImpl;
+impl Admin for AdminImpl {
+    type Metadata = JsonRpcRequestProcessor;
+
+    fn create_ledger_backup(
+        &self,
+        meta: Self::Metadata,
+        path: String,
+    ) -> Result<RpcLedgerBackup> {
+        info!("create_ledger_backup rpc request received: {}", path);
+        meta.create_ledger_backup(path)
+    }
+
+    fn clone_program_accounts(
+        &self,
+        meta: Self::Metadata,
+        program_id_str: String,
+        filters: Option<Vec<RpcFilterType>>,
+    ) -> BoxFuture<Result<RpcClonedProgramAccounts>> {
+        info!(
+            "clone_program_accounts rpc request received: {}",
+            program_id_str
+        );
+        let program_id = match verify_pubkey(&program_id_str) {
+            Ok(program_id) => program_id,
+            Err(err) => return Box::pin(future::err(err)),
+        };
+        let filters = filters.unwrap_or_default();
+        if filters.len() > MAX_GET_PROGRAM_ACCOUNT_FILTERS {
+            return Box::pin(future::err(Error::invalid_params(format!(
+                "Too many filters provided; max {MAX_GET_PROGRAM_ACCOUNT_FILTERS}"
+            ))));
+        }
+        for filter in &filters {
+            if let Err(err) = verify_filter(filter) {
+                return Box::pin(future::err(err));
+            }
+        }
+        Box::pin(async move {
+            meta.clone_program_accounts(&program_id, filters).await
+        })
+    }
+
+    fn shutdown(&self, meta: Self::Metadata) -> Result<()> {
+        info!("shutdown rpc request received");
+        meta.request_shutdown();
+        Ok(())
+    }
+}
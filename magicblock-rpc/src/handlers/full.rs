@@ -3,6 +3,7 @@ use std::{cmp::min, str::FromStr};
 // NOTE: from rpc/src/rpc.rs :3432
 use jsonrpc_core::{futures::future, BoxFuture, Error, Result};
 use log::*;
+use magicblock_metrics::metrics;
 use solana_rpc_client_api::{
     config::{
         RpcBlockConfig, RpcBlocksConfigWrapper, RpcContextConfig,
@@ -34,22 +35,29 @@ use solana_transaction_status::{
     TransactionBinaryEncoding, TransactionStatus, UiConfirmedBlock,
     UiTransactionEncoding,
 };
+use tracing::Instrument;
 
 use crate::{
     json_rpc_request_processor::JsonRpcRequestProcessor,
     perf::rpc_perf_sample_from,
-    traits::rpc_full::Full,
+    traits::rpc_full::{
+        Full, RpcCommitRecord, RpcMutationAuditConfig, RpcMutationAuditEntry,
+        RpcRecentCommitsConfig, RpcRemoteSyncStatus, RpcScheduledCommits,
+        RpcUndelegationStatus,
+    },
     transaction::{
-        decode_and_deserialize, sanitize_transaction, send_transaction,
-        SendTransactionConfig,
+        decode_and_deserialize, ensure_address_lookup_tables,
+        sanitize_transaction, send_transaction, SendTransactionConfig,
     },
     utils::{
         new_response, verify_and_parse_signatures_for_address_params,
-        verify_signature,
+        verify_pubkey, verify_signature,
     },
 };
 
 const PERFORMANCE_SAMPLES_LIMIT: usize = 720;
+const RECENT_COMMITS_LIMIT: usize = 1_000;
+const MUTATION_AUDIT_ENTRIES_LIMIT: usize = 1_000;
 
 pub struct FullImpl;
 
@@ -221,15 +229,23 @@ impl Full for FullImpl {
                 tx_encoding,
                 max_retries,
             )
+            .instrument(tracing::info_span!(
+                "transaction",
+                signature = tracing::field::Empty
+            ))
             .await
         })
     }
 
     fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot> {
         debug!("minimum_ledger_slot rpc request received");
-        // We always start the validator on slot 0 and never clear or snapshot the history
-        // There will be some related work here: https://github.com/magicblock-labs/magicblock-validator/issues/112
-        Ok(0)
+        // Slots up to and including the lowest cleanup slot were truncated
+        let lowest_cleanup_slot = meta.ledger.get_lowest_cleanup_slot();
+        if lowest_cleanup_slot == 0 {
+            Ok(0)
+        } else {
+            Ok(lowest_cleanup_slot + 1)
+        }
     }
 
     fn get_block(
@@ -422,6 +438,7 @@ impl Full for FullImpl {
         let (_, message) = decode_and_deserialize::<VersionedMessage>(
             data,
             TransactionBinaryEncoding::Base64,
+            meta.get_bank().max_transaction_size(),
         )?;
         let bank = &*meta.get_bank_with_config(config.unwrap_or_default())?;
         let sanitized_versioned_message =
@@ -463,6 +480,111 @@ impl Full for FullImpl {
             "Ephemeral validator does not support or require priority fees",
         ))
     }
+
+    fn get_commits_for_slot(
+        &self,
+        meta: Self::Metadata,
+        slot: Slot,
+    ) -> Result<Vec<RpcCommitRecord>> {
+        debug!("get_commits_for_slot rpc request received: {}", slot);
+        meta.get_commits_for_slot(slot)
+    }
+
+    fn get_commit_status(
+        &self,
+        meta: Self::Metadata,
+        commit_id: u64,
+    ) -> Result<Option<RpcCommitRecord>> {
+        debug!("get_commit_status rpc request received: {}", commit_id);
+        meta.get_commit_status(commit_id)
+    }
+
+    fn get_recent_commits(
+        &self,
+        meta: Self::Metadata,
+        config: Option<RpcRecentCommitsConfig>,
+    ) -> Result<Vec<RpcCommitRecord>> {
+        debug!("get_recent_commits rpc request received: {:?}", config);
+        let RpcRecentCommitsConfig {
+            limit,
+            before_slot,
+            before_commit_id,
+        } = config.unwrap_or_default();
+
+        let limit = limit.unwrap_or(RECENT_COMMITS_LIMIT);
+        if limit > RECENT_COMMITS_LIMIT {
+            return Err(Error::invalid_params(format!(
+                "Invalid limit; max {RECENT_COMMITS_LIMIT}"
+            )));
+        }
+        let before = match (before_slot, before_commit_id) {
+            (Some(slot), commit_id) => Some((slot, commit_id.unwrap_or(0))),
+            (None, Some(_)) => {
+                return Err(Error::invalid_params(
+                    "beforeCommitId requires beforeSlot",
+                ));
+            }
+            (None, None) => None,
+        };
+        meta.get_recent_commits(before, limit)
+    }
+
+    fn get_scheduled_commits(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<RpcScheduledCommits> {
+        debug!("get_scheduled_commits rpc request received");
+        meta.get_scheduled_commits()
+    }
+
+    fn get_mutation_audit_trail(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Slot,
+        config: Option<RpcMutationAuditConfig>,
+    ) -> Result<Vec<RpcMutationAuditEntry>> {
+        debug!(
+            "get_mutation_audit_trail rpc request received: {} {:?}",
+            start_slot, config
+        );
+        let RpcMutationAuditConfig { end_slot, limit } =
+            config.unwrap_or_default();
+
+        let end_slot = end_slot.unwrap_or(Slot::MAX);
+        if end_slot < start_slot {
+            return Err(Error::invalid_params(format!(
+                "Invalid slot range; endSlot {end_slot} < startSlot {start_slot}"
+            )));
+        }
+        let limit = limit.unwrap_or(MUTATION_AUDIT_ENTRIES_LIMIT);
+        if limit > MUTATION_AUDIT_ENTRIES_LIMIT {
+            return Err(Error::invalid_params(format!(
+                "Invalid limit; max {MUTATION_AUDIT_ENTRIES_LIMIT}"
+            )));
+        }
+        meta.get_mutation_audit_trail(start_slot, end_slot, limit)
+    }
+
+    fn get_undelegation_status(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+    ) -> Result<RpcUndelegationStatus> {
+        debug!(
+            "get_undelegation_status rpc request received: {:?}",
+            pubkey_str
+        );
+        let pubkey = verify_pubkey(&pubkey_str)?;
+        meta.get_undelegation_status(&pubkey)
+    }
+
+    fn get_remote_sync_status(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<RpcRemoteSyncStatus> {
+        debug!("get_remote_sync_status rpc request received");
+        Ok(meta.get_remote_sync_status())
+    }
 }
 
 async fn send_transaction_impl(
@@ -480,15 +602,30 @@ async fn send_transaction_impl(
         ))
     })?;
 
-    let (_wire_transaction, unsanitized_tx) =
-        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+    let (_wire_transaction, unsanitized_tx) = tracing::info_span!("decode")
+        .in_scope(|| {
+            decode_and_deserialize::<VersionedTransaction>(
+                data,
+                binary_encoding,
+                meta.get_bank().max_transaction_size(),
+            )
+        })?;
+
+    ensure_address_lookup_tables(meta, &unsanitized_tx).await?;
 
     let preflight_bank = &*meta.get_bank_with_config(RpcContextConfig {
         commitment: preflight_commitment,
         min_context_slot,
     })?;
-    let transaction = sanitize_transaction(unsanitized_tx, preflight_bank)?;
+    let transaction = tracing::info_span!("sanitize")
+        .in_scope(|| sanitize_transaction(unsanitized_tx, preflight_bank))?;
     let signature = *transaction.signature();
+    tracing::Span::current()
+        .record("signature", tracing::field::display(&signature));
+    meta.config
+        .transaction_admission
+        .check(preflight_bank, &transaction)
+        .inspect_err(|_| metrics::inc_transactions_below_fee_floor())?;
 
     let mut last_valid_block_height = preflight_bank
         .get_blockhash_last_valid_block_height(
@@ -543,8 +680,11 @@ async fn simulate_transaction_impl(
         ))
     })?;
 
-    let (_, unsanitized_tx) =
-        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+    let (_, unsanitized_tx) = decode_and_deserialize::<VersionedTransaction>(
+        data,
+        binary_encoding,
+        meta.get_bank().max_transaction_size(),
+    )?;
 
     meta.simulate_transaction(
         unsanitized_tx,
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap, net::SocketAddr, str::FromStr, sync::Arc,
+    collections::HashMap, net::SocketAddr, path::Path, str::FromStr, sync::Arc,
     time::Duration,
 };
 
@@ -9,7 +9,12 @@ use magicblock_accounts::AccountsManager;
 use magicblock_bank::{
     bank::Bank, transaction_simulation::TransactionSimulationResult,
 };
+use magicblock_core::traits::ProvidesRemoteSyncStatus;
 use magicblock_ledger::{Ledger, SignatureInfosForAddress};
+use magicblock_program::{
+    CommitEventLog, MagicContext, TransactionScheduler,
+    MAGIC_COMMIT_EVENTS_PUBKEY, MAGIC_CONTEXT_PUBKEY,
+};
 use magicblock_transaction_status::TransactionStatusSender;
 use solana_account_decoder::{UiAccount, UiAccountEncoding};
 use solana_accounts_db::accounts_index::AccountSecondaryIndexes;
@@ -28,6 +33,7 @@ use solana_rpc_client_api::{
     },
 };
 use solana_sdk::{
+    account::ReadableAccount,
     clock::{Slot, UnixTimestamp},
     epoch_schedule::EpochSchedule,
     hash::Hash,
@@ -42,15 +48,25 @@ use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta, TransactionConfirmationStatus,
     TransactionStatus, UiInnerInstructions, UiTransactionEncoding,
 };
+use tokio::sync::Notify;
 
 use crate::{
     account_resolver::{encode_account, get_encoded_account},
     filters::{get_filtered_program_accounts, optimize_filters},
     rpc_health::{RpcHealth, RpcHealthStatus},
+    traits::{
+        rpc_admin::{RpcClonedProgramAccounts, RpcLedgerBackup},
+        rpc_full::{
+            RpcCommitRecord, RpcMutationAuditEntry, RpcRecurringCommit,
+            RpcRemoteSyncStatus, RpcScheduledCommit, RpcScheduledCommits,
+            RpcUndelegationStatus,
+        },
+    },
     transaction::{
         airdrop_transaction, sanitize_transaction,
         sig_verify_transaction_and_check_precompiles,
     },
+    transaction_admission::TransactionAdmission,
     utils::{new_response, verify_pubkey},
     RpcCustomResult,
 };
@@ -86,6 +102,22 @@ pub struct JsonRpcConfig {
 
     /// Configures if to verify transaction signatures
     pub disable_sigverify: bool,
+
+    /// Origins allowed to make cross-origin requests, any if empty
+    pub cors_allowed_origins: Vec<String>,
+
+    /// Serves the admin methods, i.e. to create ledger backups, on a separate
+    /// endpoint at this address, they aren't exposed if `None`
+    pub admin_socket_addr: Option<SocketAddr>,
+
+    /// Requests taking longer than this to be handled are logged
+    pub slow_request_threshold: Option<Duration>,
+
+    /// Fee floor of sent transactions
+    pub transaction_admission: TransactionAdmission,
+
+    /// Notified when a shutdown is requested via the admin methods
+    pub shutdown_request: Arc<Notify>,
 }
 
 // NOTE: from rpc/src/rpc.rs :193
@@ -99,6 +131,7 @@ pub struct JsonRpcRequestProcessor {
     pub faucet_keypair: Arc<Keypair>,
 
     pub accounts_manager: Arc<AccountsManager>,
+    remote_sync_status: Arc<dyn ProvidesRemoteSyncStatus>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
 
@@ -110,6 +143,7 @@ impl JsonRpcRequestProcessor {
         faucet_keypair: Keypair,
         genesis_hash: Hash,
         accounts_manager: Arc<AccountsManager>,
+        remote_sync_status: Arc<dyn ProvidesRemoteSyncStatus>,
         config: JsonRpcConfig,
     ) -> Self {
         Self {
@@ -120,6 +154,7 @@ impl JsonRpcRequestProcessor {
             faucet_keypair: Arc::new(faucet_keypair),
             genesis_hash,
             accounts_manager,
+            remote_sync_status,
         }
     }
 
@@ -190,6 +225,163 @@ impl JsonRpcRequestProcessor {
         Ok(block.map(ConfirmedBlock::from))
     }
 
+    // -----------------
+    // Commits
+    // -----------------
+    pub fn get_commits_for_slot(
+        &self,
+        slot: Slot,
+    ) -> Result<Vec<RpcCommitRecord>> {
+        let records = self
+            .ledger
+            .read_commit_records(slot, slot)
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(records.into_iter().map(RpcCommitRecord::from).collect())
+    }
+
+    pub fn get_commit_status(
+        &self,
+        commit_id: u64,
+    ) -> Result<Option<RpcCommitRecord>> {
+        let record = self
+            .ledger
+            .read_commit_record(commit_id)
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(record.map(RpcCommitRecord::from))
+    }
+
+    pub fn get_recent_commits(
+        &self,
+        before: Option<(Slot, u64)>,
+        limit: usize,
+    ) -> Result<Vec<RpcCommitRecord>> {
+        let records = self
+            .ledger
+            .read_recent_commit_records(before, limit)
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(records.into_iter().map(RpcCommitRecord::from).collect())
+    }
+
+    /// Audit trail of the accounts modified by the validator authority in
+    /// the inclusive slot range
+    pub fn get_mutation_audit_trail(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<RpcMutationAuditEntry>> {
+        let entries = self
+            .ledger
+            .read_mutation_audit_entries(start_slot, end_slot, limit)
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(entries
+            .into_iter()
+            .map(RpcMutationAuditEntry::from)
+            .collect())
+    }
+
+    /// Commits held in the magic context followed by the ones the validator
+    /// accepted, but didn't execute yet
+    pub fn get_scheduled_commits(&self) -> Result<RpcScheduledCommits> {
+        let magic_context = match self.bank.get_account(&MAGIC_CONTEXT_PUBKEY) {
+            Some(account) => MagicContext::try_from_data(account.data())
+                .map_err(|err| Error {
+                    code: ErrorCode::InternalError,
+                    message: format!("Invalid magic context: {err}"),
+                    data: None,
+                })?,
+            None => MagicContext::default(),
+        };
+        let scheduled_commits = magic_context
+            .scheduled_commits
+            .iter()
+            .map(|commit| RpcScheduledCommit::new(commit, false))
+            .chain(
+                TransactionScheduler::default()
+                    .get_scheduled_commits()
+                    .iter()
+                    .map(|commit| RpcScheduledCommit::new(commit, true)),
+            )
+            .collect();
+        let recurring_commits = magic_context
+            .recurring_commits
+            .iter()
+            .map(RpcRecurringCommit::from)
+            .collect();
+        Ok(RpcScheduledCommits {
+            scheduled_commits,
+            recurring_commits,
+        })
+    }
+
+    /// Latest undelegation status of the account as recorded by the
+    /// committer in the commit events account
+    pub fn get_undelegation_status(
+        &self,
+        pubkey: &Pubkey,
+    ) -> Result<RpcUndelegationStatus> {
+        let commit_event_log =
+            match self.bank.get_account(&MAGIC_COMMIT_EVENTS_PUBKEY) {
+                Some(account) => CommitEventLog::try_from_data(account.data())
+                    .map_err(|err| Error {
+                        code: ErrorCode::InternalError,
+                        message: format!("Invalid commit event log: {err}"),
+                        data: None,
+                    })?,
+                None => CommitEventLog::default(),
+            };
+        Ok(commit_event_log.undelegation(pubkey).into())
+    }
+
+    // -----------------
+    // Remote Sync
+    // -----------------
+    pub fn get_remote_sync_status(&self) -> RpcRemoteSyncStatus {
+        self.remote_sync_status.remote_sync_status().into()
+    }
+
+    // -----------------
+    // Ledger Backup
+    // -----------------
+    pub fn create_ledger_backup(
+        &self,
+        path: String,
+    ) -> Result<RpcLedgerBackup> {
+        // The backup contains at least all slots up to this one
+        let (slot, _) = self
+            .ledger
+            .get_max_blockhash()
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        self.ledger
+            .create_backup(Path::new(&path))
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(RpcLedgerBackup { path, slot })
+    }
+
+    // -----------------
+    // Shutdown
+    // -----------------
+    pub fn request_shutdown(&self) {
+        self.config.shutdown_request.notify_one();
+    }
+
+    // -----------------
+    // Program Accounts Cloning
+    // -----------------
+    pub async fn clone_program_accounts(
+        &self,
+        program_id: &Pubkey,
+        mut filters: Vec<RpcFilterType>,
+    ) -> Result<RpcClonedProgramAccounts> {
+        optimize_filters(&mut filters);
+        let cloned = self
+            .accounts_manager
+            .clone_program_accounts(program_id, filters)
+            .await
+            .map_err(|err| Error::invalid_params(format!("{err}")))?;
+        Ok(cloned.into())
+    }
+
     // -----------------
     // Accounts
     // -----------------
@@ -660,6 +852,14 @@ impl JsonRpcRequestProcessor {
                 .message
                 .set_recent_blockhash(bank.last_blockhash());
         }
+        // Lookup tables that failed to clone are reported when sanitizing
+        if let Err(err) = self
+            .accounts_manager
+            .ensure_address_lookup_tables(&unsanitized_tx)
+            .await
+        {
+            trace!("ensure_address_lookup_tables failed: {:?}", err);
+        }
         let sanitized_transaction =
             sanitize_transaction(unsanitized_tx, &*bank)?;
         if sig_verify {
@@ -831,37 +1031,32 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&self.bank, statuses))
     }
 
+    /// Resolves the status of a transaction.
+    /// The in-memory status cache only covers recent slots. When
+    /// `search_transaction_history` is set we consult the ledger's
+    /// transaction status column for any slot, otherwise the ledger is only
+    /// used for the range of slots covered by the status cache, i.e. to
+    /// resolve recent transactions after the validator restarted.
     fn get_transaction_status(
         &self,
         signature: Signature,
-        _search_transaction_history: bool,
+        search_transaction_history: bool,
     ) -> Option<TransactionStatus> {
-        let bank_result = self.bank.get_recent_signature_status(
-            &signature,
-            Some(self.bank.slots_for_duration(Duration::from_secs(10))),
-        );
+        let recent_slots =
+            self.bank.slots_for_duration(Duration::from_secs(10));
+        let bank_result = self
+            .bank
+            .get_recent_signature_status(&signature, Some(recent_slots));
         let (slot, status) = if let Some(bank_result) = bank_result {
             bank_result
-        } else if self.config.enable_rpc_transaction_history
-        // NOTE: this is causing ledger replay tests to fail as
-        // transaction status cache contains too little history
-        //
-        // && search_transaction_history
-        {
-            match self
-                .ledger
-                .get_transaction_status(signature, self.bank.slot())
-            {
-                Ok(Some((slot, status))) => (slot, status.status),
-                Err(err) => {
-                    warn!(
-                        "Error loading signature {} from ledger: {:?}",
-                        signature, err
-                    );
-                    return None;
-                }
-                _ => return None,
-            }
+        } else if self.config.enable_rpc_transaction_history {
+            get_ledger_transaction_status(
+                &self.ledger,
+                signature,
+                self.bank.slot(),
+                recent_slots,
+                search_transaction_history,
+            )?
         } else {
             return None;
         };
@@ -875,3 +1070,141 @@ impl JsonRpcRequestProcessor {
         })
     }
 }
+
+/// Looks up the status of a transaction in the ledger's transaction status
+/// column. Unless `search_transaction_history` is set only transactions of
+/// the `recent_slots` before `current_slot` are considered.
+fn get_ledger_transaction_status(
+    ledger: &Ledger,
+    signature: Signature,
+    current_slot: Slot,
+    recent_slots: Slot,
+    search_transaction_history: bool,
+) -> Option<(Slot, solana_sdk::transaction::Result<()>)> {
+    let lowest_slot = if search_transaction_history {
+        0
+    } else {
+        current_slot.saturating_sub(recent_slots)
+    };
+    match ledger.get_transaction_status(signature, current_slot) {
+        Ok(Some((slot, status))) if slot >= lowest_slot => {
+            Some((slot, status.status))
+        }
+        Err(err) => {
+            warn!(
+                "Error loading signature {} from ledger: {:?}",
+                signature, err
+            );
+            None
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use solana_sdk::{
+        signer::Signer, system_instruction, transaction::Transaction,
+    };
+    use solana_transaction_status::TransactionStatusMeta;
+    use tempfile::NamedTempFile;
+
+    use super::*;
+
+    fn setup_ledger() -> Ledger {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.into_temp_path();
+        fs::remove_file(&path).unwrap();
+        Ledger::open(&path).unwrap()
+    }
+
+    fn write_transaction(ledger: &Ledger, slot: Slot) -> Signature {
+        let payer = Keypair::new();
+        let ix = system_instruction::transfer(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            1,
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            Hash::new_unique(),
+        );
+        let signature = tx.signatures[0];
+        ledger
+            .write_transaction(
+                signature,
+                slot,
+                SanitizedTransaction::from_transaction_for_tests(tx),
+                TransactionStatusMeta::default(),
+                0,
+            )
+            .unwrap();
+        signature
+    }
+
+    #[test]
+    fn test_ledger_transaction_status_of_recent_slot() {
+        let ledger = setup_ledger();
+        let signature = write_transaction(&ledger, 95);
+
+        for search_transaction_history in [false, true] {
+            assert_eq!(
+                get_ledger_transaction_status(
+                    &ledger,
+                    signature,
+                    100,
+                    10,
+                    search_transaction_history,
+                ),
+                Some((95, Ok(())))
+            );
+        }
+    }
+
+    #[test]
+    fn test_ledger_transaction_status_searches_history_when_requested() {
+        let ledger = setup_ledger();
+        let signature = write_transaction(&ledger, 5);
+
+        assert_eq!(
+            get_ledger_transaction_status(&ledger, signature, 100, 10, false),
+            None
+        );
+        assert_eq!(
+            get_ledger_transaction_status(&ledger, signature, 100, 10, true),
+            Some((5, Ok(())))
+        );
+    }
+
+    #[test]
+    fn test_ledger_transaction_status_ignores_later_slots() {
+        let ledger = setup_ledger();
+        let signature = write_transaction(&ledger, 120);
+
+        assert_eq!(
+            get_ledger_transaction_status(&ledger, signature, 100, 10, true),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ledger_transaction_status_of_unknown_signature() {
+        let ledger = setup_ledger();
+        write_transaction(&ledger, 95);
+
+        assert_eq!(
+            get_ledger_transaction_status(
+                &ledger,
+                Signature::new_unique(),
+                100,
+                10,
+                true,
+            ),
+            None
+        );
+    }
+}
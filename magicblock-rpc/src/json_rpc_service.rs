@@ -2,34 +2,38 @@ use std::{
     net::SocketAddr,
     sync::{atomic::AtomicBool, Arc, RwLock},
     thread::{self, JoinHandle},
+    time::Duration,
 };
 
 use jsonrpc_core::MetaIoHandler;
-use jsonrpc_http_server::{
-    hyper, AccessControlAllowOrigin, CloseHandle, DomainsValidation,
-    ServerBuilder,
-};
+use jsonrpc_http_server::{hyper, CloseHandle, Server, ServerBuilder};
 // NOTE: from rpc/src/rpc_service.rs
 use log::*;
 use magicblock_accounts::AccountsManager;
 use magicblock_bank::bank::Bank;
+use magicblock_core::traits::ProvidesRemoteSyncStatus;
 use magicblock_ledger::Ledger;
 use solana_perf::thread::renice_this_thread;
 use solana_sdk::{hash::Hash, signature::Keypair};
 use tokio::runtime::Runtime;
 
 use crate::{
+    cors::cors_domains,
     handlers::{
         accounts::AccountsDataImpl, accounts_scan::AccountsScanImpl,
-        bank_data::BankDataImpl, full::FullImpl, minimal::MinimalImpl,
+        admin::AdminImpl, bank_data::BankDataImpl, full::FullImpl,
+        minimal::MinimalImpl,
     },
     json_rpc_request_processor::{JsonRpcConfig, JsonRpcRequestProcessor},
     rpc_health::RpcHealth,
+    rpc_metrics_middleware::RpcMetricsMiddleware,
     rpc_request_middleware::RpcRequestMiddleware,
     traits::{
         rpc_accounts::AccountsData, rpc_accounts_scan::AccountsScan,
-        rpc_bank_data::BankData, rpc_full::Full, rpc_minimal::Minimal,
+        rpc_admin::Admin, rpc_bank_data::BankData, rpc_full::Full,
+        rpc_minimal::Minimal,
     },
+    transaction::max_base58_size,
     utils::MAX_REQUEST_BODY_SIZE,
 };
 
@@ -40,6 +44,9 @@ pub struct JsonRpcService {
     request_processor: JsonRpcRequestProcessor,
     startup_verification_complete: Arc<AtomicBool>,
     max_request_body_size: usize,
+    cors_allowed_origins: Vec<String>,
+    admin_addr: Option<SocketAddr>,
+    slow_request_threshold: Option<Duration>,
     rpc_thread_handle: RwLock<Option<JoinHandle<()>>>,
     close_handle: Arc<RwLock<Option<CloseHandle>>>,
 }
@@ -51,18 +58,28 @@ impl JsonRpcService {
         faucet_keypair: Keypair,
         genesis_hash: Hash,
         accounts_manager: Arc<AccountsManager>,
+        remote_sync_status: Arc<dyn ProvidesRemoteSyncStatus>,
         config: JsonRpcConfig,
     ) -> Result<Self, String> {
         let rpc_addr = config
             .rpc_socket_addr
             .ok_or_else(|| "JSON RPC socket required".to_string())?;
 
-        let max_request_body_size = config
-            .max_request_body_size
-            .unwrap_or(MAX_REQUEST_BODY_SIZE);
+        // By default the body fits the largest transaction the bank accepts
+        // with room to spare for the rest of the request
+        let max_request_body_size =
+            config.max_request_body_size.unwrap_or_else(|| {
+                MAX_REQUEST_BODY_SIZE.max(
+                    max_base58_size(bank.max_transaction_size())
+                        + MAX_REQUEST_BODY_SIZE / 2,
+                )
+            });
 
         let runtime = get_runtime(&config);
         let rpc_niceness_adj = config.rpc_niceness_adj;
+        let cors_allowed_origins = config.cors_allowed_origins.clone();
+        let admin_addr = config.admin_socket_addr;
+        let slow_request_threshold = config.slow_request_threshold;
 
         let startup_verification_complete =
             Arc::clone(bank.get_startup_verification_complete());
@@ -75,6 +92,7 @@ impl JsonRpcService {
             faucet_keypair,
             genesis_hash,
             accounts_manager,
+            remote_sync_status,
             config,
         );
 
@@ -82,6 +100,9 @@ impl JsonRpcService {
             rpc_addr,
             rpc_niceness_adj,
             max_request_body_size,
+            cors_allowed_origins,
+            admin_addr,
+            slow_request_threshold,
             runtime,
             request_processor,
             startup_verification_complete,
@@ -102,6 +123,9 @@ impl JsonRpcService {
         let rpc_addr = self.rpc_addr;
         let runtime = self.runtime.handle().clone();
         let max_request_body_size = self.max_request_body_size;
+        let cors = cors_domains(&self.cors_allowed_origins);
+        let admin_addr = self.admin_addr;
+        let slow_request_threshold = self.slow_request_threshold;
 
         let close_handle_rc = self.close_handle.clone();
         let thread_handle = thread::Builder::new()
@@ -109,13 +133,27 @@ impl JsonRpcService {
             .spawn(move || {
                 renice_this_thread(rpc_niceness_adj).unwrap();
 
-                let mut io = MetaIoHandler::default();
+                let mut methods = MetaIoHandler::default();
+                methods.extend_with(AccountsDataImpl.to_delegate());
+                methods.extend_with(AccountsScanImpl.to_delegate());
+                methods.extend_with(FullImpl.to_delegate());
+                methods.extend_with(BankDataImpl.to_delegate());
+                methods.extend_with(MinimalImpl.to_delegate());
+                let io = RpcMetricsMiddleware::io_handler(
+                    methods,
+                    slow_request_threshold,
+                );
 
-                io.extend_with(AccountsDataImpl.to_delegate());
-                io.extend_with(AccountsScanImpl.to_delegate());
-                io.extend_with(FullImpl.to_delegate());
-                io.extend_with(BankDataImpl.to_delegate());
-                io.extend_with(MinimalImpl.to_delegate());
+                // Kept alive until the public server stopped
+                let _admin_server = admin_addr.and_then(|admin_addr| {
+                    start_admin_server(
+                        admin_addr,
+                        request_processor.clone(),
+                        runtime.clone(),
+                        max_request_body_size,
+                        slow_request_threshold,
+                    )
+                });
 
                 let health = RpcHealth::new(startup_verification_complete);
                 let request_middleware = RpcRequestMiddleware::new(health);
@@ -128,9 +166,7 @@ impl JsonRpcService {
                 )
                     .event_loop_executor(runtime)
                     .threads(1)
-                    .cors(DomainsValidation::AllowOnly(vec![
-                        AccessControlAllowOrigin::Any,
-                    ]))
+                    .cors(cors)
                     .cors_max_age(86400)
                     .request_middleware(request_middleware)
                     .max_request_body_size(max_request_body_size)
@@ -187,6 +223,42 @@ impl JsonRpcService {
     }
 }
 
+/// Serves the admin methods separately from the public ones, so that they can
+/// only be reached from trusted hosts
+fn start_admin_server(
+    admin_addr: SocketAddr,
+    request_processor: JsonRpcRequestProcessor,
+    runtime: tokio::runtime::Handle,
+    max_request_body_size: usize,
+    slow_request_threshold: Option<Duration>,
+) -> Option<Server> {
+    let mut methods = MetaIoHandler::default();
+    methods.extend_with(AdminImpl.to_delegate());
+    let io = RpcMetricsMiddleware::io_handler(methods, slow_request_threshold);
+
+    let server = ServerBuilder::with_meta_extractor(
+        io,
+        move |_req: &hyper::Request<hyper::Body>| request_processor.clone(),
+    )
+    .event_loop_executor(runtime)
+    .threads(1)
+    .max_request_body_size(max_request_body_size)
+    .start_http(&admin_addr);
+    match server {
+        Ok(server) => {
+            info!("Launched JSON RPC admin service at {:?}", admin_addr);
+            Some(server)
+        }
+        Err(err) => {
+            error!(
+                "JSON RPC admin service unavailable at {:?}: {:?}",
+                admin_addr, err
+            );
+            None
+        }
+    }
+}
+
 fn get_runtime(config: &JsonRpcConfig) -> Arc<tokio::runtime::Runtime> {
     let rpc_threads = 1.max(config.rpc_threads);
     let rpc_niceness_adj = config.rpc_niceness_adj;
@@ -1,15 +1,18 @@
 use solana_rpc_client_api::custom_error::RpcCustomError;
 
 mod account_resolver;
+pub mod cors;
 mod filters;
 mod handlers;
 pub mod json_rpc_request_processor;
 pub mod json_rpc_service;
 mod perf;
 mod rpc_health;
+mod rpc_metrics_middleware;
 mod rpc_request_middleware;
 mod traits;
 mod transaction;
+pub mod transaction_admission;
 mod utils;
 
 pub(crate) type RpcCustomResult<T> = std::result::Result<T, RpcCustomError>;
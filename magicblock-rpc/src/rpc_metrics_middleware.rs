@@ -0,0 +1,265 @@
+use std::{
+    collections::HashSet,
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use jsonrpc_core::{
+    futures::{future::Either, FutureExt},
+    middleware::{Middleware, NoopCallFuture, NoopFuture},
+    Call, ErrorCode, MetaIoHandler, Metadata, Output,
+};
+use log::*;
+use magicblock_metrics::metrics;
+use serde::Serialize;
+use tracing::Instrument;
+
+/// Label used for methods that are not registered with the RPC service.
+/// Recording the name sent by the client would allow it to create an
+/// unbounded number of metrics.
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// Records per-method request counts, latencies, payload sizes and error codes
+/// for every RPC call.
+/// Each call runs inside an `rpc_call` tracing span carrying its method and id.
+/// Calls taking longer than the slow request threshold are logged as well.
+#[derive(Clone)]
+pub(crate) struct RpcMetricsMiddleware {
+    slow_request_threshold: Option<Duration>,
+    /// Names of the methods served by the handler, only these are used as
+    /// metric labels.
+    registered_methods: Arc<HashSet<String>>,
+}
+
+impl RpcMetricsMiddleware {
+    /// Creates a handler serving `methods` whose calls are recorded by this
+    /// middleware.
+    pub(crate) fn io_handler<M: Metadata>(
+        methods: MetaIoHandler<M>,
+        slow_request_threshold: Option<Duration>,
+    ) -> MetaIoHandler<M, Self> {
+        let registered_methods =
+            methods.iter().map(|(name, _)| name.clone()).collect();
+        let mut io = MetaIoHandler::with_middleware(Self {
+            slow_request_threshold,
+            registered_methods: Arc::new(registered_methods),
+        });
+        io.extend_with(methods);
+        io
+    }
+
+    fn method_label(&self, method: &str) -> String {
+        if self.registered_methods.contains(method) {
+            method.to_string()
+        } else {
+            UNKNOWN_METHOD.to_string()
+        }
+    }
+}
+
+/// Counts the bytes written to it without keeping them.
+#[derive(Default)]
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Size of the JSON encoding of `value`, which is what the server sends or
+/// received for it.
+/// The encoding is streamed into a [ByteCounter] so that large payloads
+/// like program accounts are never copied into a second buffer.
+fn serialized_len<T: Serialize>(value: &T) -> usize {
+    let mut counter = ByteCounter::default();
+    match serde_json::to_writer(&mut counter, value) {
+        Ok(()) => counter.0,
+        Err(_) => 0,
+    }
+}
+
+impl<M: Metadata> Middleware<M> for RpcMetricsMiddleware {
+    type Future = NoopFuture;
+    type CallFuture = NoopCallFuture;
+
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: M,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, M) -> X + Send + Sync,
+        X: std::future::Future<Output = Option<Output>> + Send + 'static,
+    {
+        let (method, id) = match &call {
+            Call::MethodCall(call) => {
+                (self.method_label(&call.method), format!("{:?}", call.id))
+            }
+            Call::Notification(notification) => {
+                (self.method_label(&notification.method), "none".to_string())
+            }
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let request_size = serialized_len(&call);
+
+        let span = tracing::info_span!("rpc_call", method = %method, id = %id);
+        let start = Instant::now();
+        let slow_request_threshold = self.slow_request_threshold;
+
+        let future = span.in_scope(|| next(call, meta)).map(move |output| {
+            let elapsed = start.elapsed();
+            let error_code = match &output {
+                Some(Output::Failure(failure)) => {
+                    Some(failure.error.code.clone())
+                }
+                _ => None,
+            };
+            let method = method.as_str();
+            let response_size =
+                output.as_ref().map(serialized_len).unwrap_or_default();
+
+            metrics::inc_rpc_request(method);
+            metrics::observe_rpc_request_time(method, elapsed);
+            metrics::observe_rpc_payload_sizes(
+                method,
+                request_size,
+                response_size,
+            );
+            if let Some(code) = &error_code {
+                metrics::inc_rpc_error(method, code.code());
+            }
+
+            if slow_request_threshold
+                .is_some_and(|threshold| elapsed > threshold)
+            {
+                metrics::inc_slow_rpc_requests(method);
+                warn!(
+                    method = method;
+                    "Slow rpc call: method={} id={} elapsed={:?} request_size={} response_size={} error={:?}",
+                    method,
+                    id,
+                    elapsed,
+                    request_size,
+                    response_size,
+                    error_code.as_ref().map(ErrorCode::code),
+                );
+            }
+            output
+        });
+        Either::Left(Box::pin(future.instrument(span)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpc_core::{Error, Params, Result, Value};
+
+    use super::*;
+
+    fn io_handler() -> MetaIoHandler<(), RpcMetricsMiddleware> {
+        let mut io = MetaIoHandler::default();
+        io.add_sync_method(
+            "metricsTestEcho",
+            |params: Params| -> Result<Value> {
+                let value: Value = params.parse()?;
+                Ok(value)
+            },
+        );
+        io.add_sync_method("metricsTestFail", |_: Params| -> Result<Value> {
+            Err(Error::invalid_params("always fails"))
+        });
+        io.add_notification("metricsTestNotify", |_: Params| {});
+        RpcMetricsMiddleware::io_handler(io, None)
+    }
+
+    #[test]
+    fn test_serialized_len() {
+        let value = serde_json::json!({ "data": "x".repeat(1_000) });
+        assert_eq!(
+            serialized_len(&value),
+            serde_json::to_vec(&value).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_records_request_metrics() {
+        let io = io_handler();
+        let method = "metricsTestEcho";
+        let requests = metrics::rpc_request_count(method);
+        let (request_bytes, response_bytes) =
+            metrics::rpc_payload_size_sums(method);
+
+        let request = r#"{"jsonrpc":"2.0","method":"metricsTestEcho","params":["abcdef"],"id":1}"#;
+        let response = io.handle_request_sync(request, ()).unwrap();
+
+        assert_eq!(metrics::rpc_request_count(method), requests + 1);
+        let (new_request_bytes, new_response_bytes) =
+            metrics::rpc_payload_size_sums(method);
+        assert_eq!(new_request_bytes - request_bytes, request.len() as f64);
+        assert_eq!(new_response_bytes - response_bytes, response.len() as f64);
+        assert_eq!(
+            metrics::rpc_error_count(method, ErrorCode::InvalidParams.code()),
+            0
+        );
+    }
+
+    #[test]
+    fn test_records_error_codes() {
+        let io = io_handler();
+        let method = "metricsTestFail";
+        let code = ErrorCode::InvalidParams.code();
+        let errors = metrics::rpc_error_count(method, code);
+
+        let request = r#"{"jsonrpc":"2.0","method":"metricsTestFail","params":[],"id":1}"#;
+        io.handle_request_sync(request, ()).unwrap();
+
+        assert_eq!(metrics::rpc_error_count(method, code), errors + 1);
+    }
+
+    #[test]
+    fn test_unknown_methods_share_one_label() {
+        let io = io_handler();
+        let code = ErrorCode::MethodNotFound.code();
+        let errors = metrics::rpc_error_count(UNKNOWN_METHOD, code);
+
+        let request =
+            r#"{"jsonrpc":"2.0","method":"metricsTestMissing","id":1}"#;
+        io.handle_request_sync(request, ()).unwrap();
+
+        assert_eq!(metrics::rpc_error_count(UNKNOWN_METHOD, code), errors + 1);
+        assert_eq!(metrics::rpc_request_count("metricsTestMissing"), 0);
+    }
+
+    #[test]
+    fn test_unregistered_notifications_share_one_label() {
+        let io = io_handler();
+        let requests = metrics::rpc_request_count(UNKNOWN_METHOD);
+
+        let notification =
+            r#"{"jsonrpc":"2.0","method":"metricsTestMissingNotify"}"#;
+        assert!(io.handle_request_sync(notification, ()).is_none());
+
+        assert!(metrics::rpc_request_count(UNKNOWN_METHOD) > requests);
+        assert_eq!(metrics::rpc_request_count("metricsTestMissingNotify"), 0);
+    }
+
+    #[test]
+    fn test_registered_notifications_use_their_name() {
+        let io = io_handler();
+        let method = "metricsTestNotify";
+        let requests = metrics::rpc_request_count(method);
+
+        let notification = r#"{"jsonrpc":"2.0","method":"metricsTestNotify"}"#;
+        assert!(io.handle_request_sync(notification, ()).is_none());
+
+        assert_eq!(metrics::rpc_request_count(method), requests + 1);
+    }
+}
@@ -1,5 +1,6 @@
 pub mod rpc_accounts;
 pub mod rpc_accounts_scan;
+pub mod rpc_admin;
 pub mod rpc_bank_data;
 pub mod rpc_full;
 pub mod rpc_minimal;
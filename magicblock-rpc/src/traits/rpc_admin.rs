@@ -0,0 +1,69 @@
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+use magicblock_accounts::ClonedProgramAccounts;
+use serde::{Deserialize, Serialize};
+use solana_rpc_client_api::filter::RpcFilterType;
+use solana_sdk::clock::Slot;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcLedgerBackup {
+    /// Directory the backup was created in
+    pub path: String,
+    /// All slots up to and including this one are part of the backup
+    pub slot: Slot,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcClonedProgramAccounts {
+    /// Number of accounts of the program found on the remote cluster
+    pub total: usize,
+    /// Number of accounts that were cloned or already up to date
+    pub cloned: usize,
+    /// Number of accounts that cannot be cloned into this validator
+    pub unclonable: usize,
+    /// Number of accounts that failed to be cloned
+    pub failed: usize,
+}
+
+impl From<ClonedProgramAccounts> for RpcClonedProgramAccounts {
+    fn from(cloned: ClonedProgramAccounts) -> Self {
+        Self {
+            total: cloned.total,
+            cloned: cloned.cloned,
+            unclonable: cloned.unclonable,
+            failed: cloned.failed,
+        }
+    }
+}
+
+/// Methods meant for operators of the validator, only exposed if enabled and
+/// served on their own endpoint, separate from the public one
+#[rpc]
+pub trait Admin {
+    type Metadata;
+
+    /// Creates a backup of the ledger inside the provided directory on the
+    /// validator host while the validator keeps running
+    #[rpc(meta, name = "createLedgerBackup")]
+    fn create_ledger_backup(
+        &self,
+        meta: Self::Metadata,
+        path: String,
+    ) -> Result<RpcLedgerBackup>;
+
+    /// Clones all accounts of the program from the remote cluster that match
+    /// the filters, instead of cloning each of them when first used
+    #[rpc(meta, name = "cloneProgramAccounts")]
+    fn clone_program_accounts(
+        &self,
+        meta: Self::Metadata,
+        program_id_str: String,
+        filters: Option<Vec<RpcFilterType>>,
+    ) -> BoxFuture<Result<RpcClonedProgramAccounts>>;
+
+    /// Shuts the validator down gracefully, the same as sending it SIGTERM
+    #[rpc(meta, name = "shutdown")]
+    fn shutdown(&self, meta: Self::Metadata) -> Result<()>;
+}
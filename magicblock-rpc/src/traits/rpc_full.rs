@@ -2,6 +2,16 @@
 //! The `rpc` module implements the Solana RPC interface.
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
+use magicblock_core::{
+    commit_record::{CommitOutcome, CommitRecord, UndelegationStatus},
+    mutation_audit::{MutationAuditEntry, MutationReason},
+    remote_sync_status::{RemoteSyncStatus, ShardSyncStatus},
+};
+use magicblock_program::{
+    magicblock_instruction::CommitPriority, RecurringCommit, ScheduledCommit,
+    UndelegationEntry,
+};
+use serde::{Deserialize, Serialize};
 use solana_rpc_client_api::{
     config::{
         RpcBlockConfig, RpcBlocksConfigWrapper, RpcContextConfig,
@@ -26,6 +36,281 @@ use solana_transaction_status::{
     UiConfirmedBlock,
 };
 
+/// Scheduled commit and its outcome on the base layer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCommitRecord {
+    pub commit_id: u64,
+    /// The slot at which the commit was scheduled
+    pub slot: Slot,
+    pub payer: String,
+    pub included_accounts: Vec<String>,
+    pub excluded_accounts: Vec<String>,
+    pub requested_undelegation: bool,
+    /// Signatures of the commit transactions on the base layer
+    pub chain_signatures: Vec<String>,
+    /// One of `notNeeded`, `pending`, `confirmed`, `finalized` or `failed`
+    pub status: String,
+    /// Why the commit failed if its status is `failed`
+    pub error: Option<String>,
+}
+
+impl From<CommitRecord> for RpcCommitRecord {
+    fn from(record: CommitRecord) -> Self {
+        let (status, error) = match record.outcome {
+            CommitOutcome::NotNeeded => ("notNeeded", None),
+            CommitOutcome::Pending => ("pending", None),
+            CommitOutcome::Confirmed => ("confirmed", None),
+            CommitOutcome::Failed(err) => ("failed", Some(err)),
+            CommitOutcome::Finalized => ("finalized", None),
+        };
+        Self {
+            commit_id: record.commit_id,
+            slot: record.slot,
+            payer: record.payer.to_string(),
+            included_accounts: record
+                .included_pubkeys
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+            excluded_accounts: record
+                .excluded_pubkeys
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+            requested_undelegation: record.requested_undelegation,
+            chain_signatures: record
+                .chain_signatures
+                .iter()
+                .map(|signature| signature.to_string())
+                .collect(),
+            status: status.to_string(),
+            error,
+        }
+    }
+}
+
+/// Paginates through the most recent commits, newest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRecentCommitsConfig {
+    pub limit: Option<usize>,
+    /// Only include commits scheduled before this slot, or before the commit
+    /// with [Self::before_commit_id] in this slot if provided as well
+    pub before_slot: Option<Slot>,
+    pub before_commit_id: Option<u64>,
+}
+
+/// Modification of accounts by the validator authority outside of regular
+/// transaction execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMutationAuditEntry {
+    pub seq: u64,
+    /// The slot at which the accounts were modified
+    pub slot: Slot,
+    pub authority: String,
+    /// One of `unspecified`, `cloneAccount`, `cloneProgram` or
+    /// `closeUndelegatedAccounts`
+    pub reason: String,
+    pub accounts: Vec<String>,
+}
+
+impl From<MutationAuditEntry> for RpcMutationAuditEntry {
+    fn from(entry: MutationAuditEntry) -> Self {
+        let reason = match entry.reason {
+            MutationReason::Unspecified => "unspecified",
+            MutationReason::CloneAccount => "cloneAccount",
+            MutationReason::CloneProgram => "cloneProgram",
+            MutationReason::CloseUndelegatedAccounts => {
+                "closeUndelegatedAccounts"
+            }
+        };
+        Self {
+            seq: entry.seq,
+            slot: entry.slot,
+            authority: entry.authority.to_string(),
+            reason: reason.to_string(),
+            accounts: entry
+                .accounts
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Selects the audit entries of account modifications, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcMutationAuditConfig {
+    /// Inclusive, defaults to the latest slot
+    pub end_slot: Option<Slot>,
+    pub limit: Option<usize>,
+}
+
+/// Commit that was scheduled, but not executed yet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcScheduledCommit {
+    pub commit_id: u64,
+    /// The slot at which the commit was scheduled
+    pub slot: Slot,
+    pub payer: String,
+    pub accounts: Vec<String>,
+    pub request_undelegation: bool,
+    /// The slot at which the commit is executed if it was scheduled in advance
+    pub execute_at_slot: Option<Slot>,
+    /// Whether all accounts are committed in the same base layer transaction
+    pub atomic: bool,
+    /// Memo included in the transactions committing the accounts
+    pub memo: Option<String>,
+    /// Commits with a higher priority are processed first
+    pub priority: CommitPriority,
+    /// Whether the validator accepted the commit already, otherwise it is
+    /// still held in the magic context account
+    pub accepted: bool,
+}
+
+impl RpcScheduledCommit {
+    pub fn new(commit: &ScheduledCommit, accepted: bool) -> Self {
+        Self {
+            commit_id: commit.id,
+            slot: commit.slot,
+            payer: commit.payer.to_string(),
+            accounts: commit
+                .accounts
+                .iter()
+                .map(|account| account.pubkey.to_string())
+                .collect(),
+            request_undelegation: commit.request_undelegation,
+            execute_at_slot: commit.execute_at_slot,
+            atomic: commit.atomic,
+            memo: commit.memo.clone(),
+            priority: commit.priority,
+            accepted,
+        }
+    }
+}
+
+/// Commit that is scheduled repeatedly at a fixed interval
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRecurringCommit {
+    pub commit_id: u64,
+    pub payer: String,
+    pub accounts: Vec<String>,
+    pub interval_slots: u64,
+    pub next_slot: Slot,
+    /// How many commits were scheduled so far
+    pub executed_commits: u64,
+}
+
+impl From<&RecurringCommit> for RpcRecurringCommit {
+    fn from(commit: &RecurringCommit) -> Self {
+        Self {
+            commit_id: commit.id,
+            payer: commit.payer.to_string(),
+            accounts: commit
+                .accounts
+                .iter()
+                .map(|account| account.pubkey.to_string())
+                .collect(),
+            interval_slots: commit.interval_slots,
+            next_slot: commit.next_slot,
+            executed_commits: commit.executed_commits,
+        }
+    }
+}
+
+/// Commits that are pending on the validator
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcScheduledCommits {
+    pub scheduled_commits: Vec<RpcScheduledCommit>,
+    pub recurring_commits: Vec<RpcRecurringCommit>,
+}
+
+/// Latest status of the undelegation requested for an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcUndelegationStatus {
+    /// One of `unknown`, `pending`, `confirmed`, `finalized` or `failed`,
+    /// only once it is `finalized` the account is undelegated for good
+    pub status: String,
+    /// The commit that requested the undelegation
+    pub commit_id: Option<u64>,
+    /// The slot at which the status was recorded
+    pub updated_at_slot: Option<Slot>,
+}
+
+impl From<Option<&UndelegationEntry>> for RpcUndelegationStatus {
+    fn from(entry: Option<&UndelegationEntry>) -> Self {
+        let status = match entry.map(|entry| entry.status) {
+            None | Some(UndelegationStatus::Unknown) => "unknown",
+            Some(UndelegationStatus::Pending) => "pending",
+            Some(UndelegationStatus::Confirmed) => "confirmed",
+            Some(UndelegationStatus::Finalized) => "finalized",
+            Some(UndelegationStatus::Failed) => "failed",
+        };
+        Self {
+            status: status.to_string(),
+            commit_id: entry.map(|entry| entry.commit_id),
+            updated_at_slot: entry.map(|entry| entry.updated_at_slot),
+        }
+    }
+}
+
+/// How far the validator is behind the remote cluster
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcRemoteSyncStatus {
+    /// Latest slot of the remote cluster observed via any subscription
+    pub remote_slot: Option<Slot>,
+    pub shards: Vec<RpcShardSyncStatus>,
+    /// Accounts requested to be cloned whose clone did not complete yet
+    pub pending_clones: usize,
+    /// Commits that were scheduled, but not sent to the remote cluster yet
+    pub scheduled_commits: usize,
+    /// Commits sent to the remote cluster that are not confirmed yet
+    pub pending_commits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcShardSyncStatus {
+    pub shard_id: String,
+    pub slot: Slot,
+    /// Slots the shard is behind the latest observed remote slot
+    pub lag: Slot,
+}
+
+impl From<RemoteSyncStatus> for RpcRemoteSyncStatus {
+    fn from(status: RemoteSyncStatus) -> Self {
+        Self {
+            remote_slot: status.remote_slot,
+            shards: status
+                .shards
+                .into_iter()
+                .map(RpcShardSyncStatus::from)
+                .collect(),
+            pending_clones: status.pending_clones,
+            scheduled_commits: status.scheduled_commits,
+            pending_commits: status.pending_commits,
+        }
+    }
+}
+
+impl From<ShardSyncStatus> for RpcShardSyncStatus {
+    fn from(status: ShardSyncStatus) -> Self {
+        Self {
+            shard_id: status.shard_id,
+            slot: status.slot,
+            lag: status.lag,
+        }
+    }
+}
+
 #[rpc]
 pub trait Full {
     type Metadata;
@@ -184,4 +469,52 @@ pub trait Full {
         meta: Self::Metadata,
         pubkey_strs: Option<Vec<String>>,
     ) -> Result<Vec<RpcPrioritizationFee>>;
+
+    #[rpc(meta, name = "getCommitsForSlot")]
+    fn get_commits_for_slot(
+        &self,
+        meta: Self::Metadata,
+        slot: Slot,
+    ) -> Result<Vec<RpcCommitRecord>>;
+
+    #[rpc(meta, name = "getCommitStatus")]
+    fn get_commit_status(
+        &self,
+        meta: Self::Metadata,
+        commit_id: u64,
+    ) -> Result<Option<RpcCommitRecord>>;
+
+    #[rpc(meta, name = "getRecentCommits")]
+    fn get_recent_commits(
+        &self,
+        meta: Self::Metadata,
+        config: Option<RpcRecentCommitsConfig>,
+    ) -> Result<Vec<RpcCommitRecord>>;
+
+    #[rpc(meta, name = "getScheduledCommits")]
+    fn get_scheduled_commits(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<RpcScheduledCommits>;
+
+    #[rpc(meta, name = "getMutationAuditTrail")]
+    fn get_mutation_audit_trail(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Slot,
+        config: Option<RpcMutationAuditConfig>,
+    ) -> Result<Vec<RpcMutationAuditEntry>>;
+
+    #[rpc(meta, name = "getUndelegationStatus")]
+    fn get_undelegation_status(
+        &self,
+        meta: Self::Metadata,
+        pubkey_str: String,
+    ) -> Result<RpcUndelegationStatus>;
+
+    #[rpc(meta, name = "getRemoteSyncStatus")]
+    fn get_remote_sync_status(
+        &self,
+        meta: Self::Metadata,
+    ) -> Result<RpcRemoteSyncStatus>;
 }
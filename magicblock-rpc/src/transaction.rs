@@ -6,29 +6,47 @@ use jsonrpc_core::{Error, ErrorCode, Result};
 use log::*;
 use magicblock_bank::bank::Bank;
 use magicblock_metrics::metrics;
-use magicblock_processor::execute_transaction::execute_sanitized_transaction;
+use magicblock_processor::{
+    execute_transaction::execute_sanitized_transaction_async,
+    sigverify::SIGNATURE_VERIFIER,
+};
+use magicblock_program::validator;
 use solana_metrics::inc_new_counter_info;
 use solana_rpc_client_api::custom_error::RpcCustomError;
 use solana_sdk::{
     feature_set,
     hash::Hash,
     message::AddressLoader,
-    packet::PACKET_DATA_SIZE,
     pubkey::Pubkey,
     signature::Signature,
     system_transaction,
-    transaction::{MessageHash, SanitizedTransaction, VersionedTransaction},
+    transaction::{
+        self, MessageHash, SanitizedTransaction, TransactionError,
+        VersionedTransaction,
+    },
 };
 use solana_transaction_status::TransactionBinaryEncoding;
+use tracing::Instrument;
 
 use crate::json_rpc_request_processor::JsonRpcRequestProcessor;
 
-const MAX_BASE58_SIZE: usize = 1683; // Golden, bump if PACKET_DATA_SIZE changes
-const MAX_BASE64_SIZE: usize = 1644; // Golden, bump if PACKET_DATA_SIZE changes
+/// Max length of the base58 encoding of `size` bytes, each byte takes
+/// log(256) / log(58) < 1.3658 characters
+pub(crate) fn max_base58_size(size: usize) -> usize {
+    (size * 13_658).div_ceil(10_000)
+}
+
+/// Max length of the base64 encoding of `size` bytes including padding
+pub(crate) fn max_base64_size(size: usize) -> usize {
+    size.div_ceil(3) * 4
+}
 
+/// Decodes a transaction or message whose wire format may take up to
+/// `max_size` bytes, the size limit the bank enforces
 pub(crate) fn decode_and_deserialize<T>(
     encoded: String,
     encoding: TransactionBinaryEncoding,
+    max_size: usize,
 ) -> Result<(Vec<u8>, T)>
 where
     T: serde::de::DeserializeOwned,
@@ -36,13 +54,14 @@ where
     let wire_output = match encoding {
         TransactionBinaryEncoding::Base58 => {
             inc_new_counter_info!("rpc-base58_encoded_tx", 1);
-            if encoded.len() > MAX_BASE58_SIZE {
+            let max_base58_size = max_base58_size(max_size);
+            if encoded.len() > max_base58_size {
                 return Err(Error::invalid_params(format!(
                     "base58 encoded {} too large: {} bytes (max: encoded/raw {}/{})",
                     type_name::<T>(),
                     encoded.len(),
-                    MAX_BASE58_SIZE,
-                    PACKET_DATA_SIZE,
+                    max_base58_size,
+                    max_size,
                 )));
             }
             bs58::decode(encoded).into_vec().map_err(|e| {
@@ -51,13 +70,14 @@ where
         }
         TransactionBinaryEncoding::Base64 => {
             inc_new_counter_info!("rpc-base64_encoded_tx", 1);
-            if encoded.len() > MAX_BASE64_SIZE {
+            let max_base64_size = max_base64_size(max_size);
+            if encoded.len() > max_base64_size {
                 return Err(Error::invalid_params(format!(
                     "base64 encoded {} too large: {} bytes (max: encoded/raw {}/{})",
                     type_name::<T>(),
                     encoded.len(),
-                    MAX_BASE64_SIZE,
-                    PACKET_DATA_SIZE,
+                    max_base64_size,
+                    max_size,
                 )));
             }
             BASE64_STANDARD.decode(encoded).map_err(|e| {
@@ -65,16 +85,16 @@ where
             })?
         }
     };
-    if wire_output.len() > PACKET_DATA_SIZE {
+    if wire_output.len() > max_size {
         return Err(Error::invalid_params(format!(
             "decoded {} too large: {} bytes (max: {} bytes)",
             type_name::<T>(),
             wire_output.len(),
-            PACKET_DATA_SIZE
+            max_size
         )));
     }
     bincode::options()
-        .with_limit(PACKET_DATA_SIZE as u64)
+        .with_limit(max_size as u64)
         .with_fixint_encoding()
         .allow_trailing_bytes()
         .deserialize_from(&wire_output[..])
@@ -102,6 +122,26 @@ pub(crate) fn sanitize_transaction(
     .map_err(|err| Error::invalid_params(format!("invalid transaction: {err}")))
 }
 
+/// Clones the address lookup tables a versioned transaction depends on,
+/// needs to run before it is sanitized
+pub(crate) async fn ensure_address_lookup_tables(
+    meta: &JsonRpcRequestProcessor,
+    transaction: &VersionedTransaction,
+) -> Result<()> {
+    meta.accounts_manager
+        .ensure_address_lookup_tables(transaction)
+        .await
+        .map_err(|err| {
+            trace!("ensure_address_lookup_tables failed: {:?}", err);
+            Error {
+                code: ErrorCode::InvalidRequest,
+                message: format!("{:?}", err),
+                data: None,
+            }
+        })?;
+    Ok(())
+}
+
 pub(crate) async fn airdrop_transaction(
     meta: &JsonRpcRequestProcessor,
     pubkey: Pubkey,
@@ -163,10 +203,41 @@ pub(crate) async fn send_transaction(
     let SendTransactionConfig { sigverify, .. } = config;
     let bank = &meta.get_bank();
 
+    if validator::is_shutting_down() {
+        return Err(Error {
+            code: ErrorCode::InvalidRequest,
+            message: "validator is shutting down".to_string(),
+            data: None,
+        });
+    }
+
+    if validator::is_read_only() {
+        return Err(Error {
+            code: ErrorCode::InvalidRequest,
+            message: "validator is in read-only mode since it is running out of disk space".to_string(),
+            data: None,
+        });
+    }
+
     if sigverify {
-        metrics::observe_sigverify_time(|| {
-            sig_verify_transaction(&sanitized_transaction)
-        })?;
+        let timer = metrics::sigverify_start();
+        SIGNATURE_VERIFIER
+            .verify(&sanitized_transaction)
+            .instrument(tracing::info_span!("sigverify"))
+            .await
+            .map_err(|_| {
+                Error::from(
+                    RpcCustomError::TransactionSignatureVerificationFailure,
+                )
+            })?;
+        metrics::sigverify_end(timer);
+    }
+
+    // Clients on flaky networks retry sending transactions they don't know
+    // the outcome of, which get the original result instead of an error
+    if let Some(result) = bank.get_processed_transaction_result(&signature) {
+        metrics::inc_resubmitted_transactions();
+        return into_send_transaction_result(signature, result);
     }
 
     // It is very important that we ensure accounts before simulating transactions
@@ -175,6 +246,7 @@ pub(crate) async fn send_transaction(
         let timer = metrics::ensure_accounts_start();
         meta.accounts_manager
             .ensure_accounts(&sanitized_transaction)
+            .instrument(tracing::info_span!("ensure_accounts"))
             .await
             .map_err(|err| {
                 trace!("ensure_accounts failed: {:?}", err);
@@ -189,34 +261,63 @@ pub(crate) async fn send_transaction(
     }
 
     if let Some(preflight_bank) = preflight_bank {
-        meta.transaction_preflight(preflight_bank, &sanitized_transaction)?;
+        tracing::info_span!("preflight").in_scope(|| {
+            meta.transaction_preflight(preflight_bank, &sanitized_transaction)
+        })?;
     }
 
-    metrics::observe_transaction_execution_time(|| {
-        execute_sanitized_transaction(
-            sanitized_transaction,
-            bank,
-            meta.transaction_status_sender(),
-        )
+    let timer = metrics::transaction_execution_start();
+    let result = execute_sanitized_transaction_async(
+        sanitized_transaction,
+        bank,
+        meta.transaction_status_sender(),
+    )
+    .await
+    .map(|_| ());
+    metrics::transaction_execution_end(timer);
+    // The same transaction may have been sent concurrently
+    let result = match result {
+        Err(TransactionError::AlreadyProcessed) => {
+            match bank.get_processed_transaction_result(&signature) {
+                Some(result) => {
+                    metrics::inc_resubmitted_transactions();
+                    result
+                }
+                None => Err(TransactionError::AlreadyProcessed),
+            }
+        }
+        result => result,
+    };
+    if let Err(err) = &result {
+        debug!(
+            signature:% = signature;
+            "Transaction {} failed: {}", signature, err
+        );
+    }
+
+    // debug!("{:#?}", tx_result);
+    // debug!("{:#?}", tx_balances_set);
+
+    into_send_transaction_result(signature, result)
+}
+
+fn into_send_transaction_result(
+    signature: Signature,
+    result: transaction::Result<()>,
+) -> Result<String> {
+    result
+        .map(|_| signature.to_string())
         .map_err(|err| jsonrpc_core::Error {
             code: jsonrpc_core::ErrorCode::InternalError,
             message: err.to_string(),
             data: None,
         })
-    })?;
-
-    // debug!("{:#?}", tx_result);
-    // debug!("{:#?}", tx_balances_set);
-
-    Ok(signature.to_string())
 }
 
-/// Verifies only the transaction signature and is used when sending a
-/// transaction to avoid the extra overhead of [sig_verify_transaction_and_check_precompiles]
-/// TODO(thlorenz): @@ sigverify takes upwards of 90µs which is 30%+ of
-/// the entire time it takes to execute a transaction.
-/// Therefore this an intermediate solution and we need to investigate verifying the
-/// wire_transaction instead (solana sigverify implementation is packet based)
+/// Verifies only the transaction signature.
+/// Sent transactions are verified in batches via the [SIGNATURE_VERIFIER]
+/// instead, since verifying them one by one takes upwards of 90µs which is
+/// 30%+ of the entire time it takes to execute a transaction.
 pub(crate) fn sig_verify_transaction(
     transaction: &SanitizedTransaction,
 ) -> Result<()> {
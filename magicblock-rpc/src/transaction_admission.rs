@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use jsonrpc_core::{Error, Result};
+use magicblock_bank::{
+    bank::Bank, get_compute_budget_details::GetComputeBudgetDetails,
+};
+use solana_sdk::{
+    compute_budget, pubkey::Pubkey, transaction::SanitizedTransaction,
+};
+
+/// Fee floor transactions sent via RPC need to pay in order to be executed,
+/// which keeps public validators from filling slots with free transactions.
+/// Transactions the validator executes itself are not affected.
+#[derive(Debug, Default, Clone)]
+pub struct TransactionAdmission {
+    /// Min price in micro-lamports per compute unit
+    pub min_compute_unit_price: u64,
+    /// Min fee in lamports, including the prioritization fee, paid for each
+    /// signature of the transaction
+    pub min_fee_per_signature: u64,
+    /// Transactions paid by these accounts are always admitted
+    pub exempt_fee_payers: HashSet<Pubkey>,
+    /// Transactions only invoking these programs are always admitted,
+    /// invoking the compute budget program is fine as well
+    pub exempt_programs: HashSet<Pubkey>,
+}
+
+impl TransactionAdmission {
+    pub(crate) fn check(
+        &self,
+        bank: &Bank,
+        transaction: &SanitizedTransaction,
+    ) -> Result<()> {
+        if self.is_exempt(transaction) {
+            return Ok(());
+        }
+        let compute_unit_price = transaction
+            .get_compute_budget_details(false)
+            .map(|details| details.compute_unit_price)
+            .unwrap_or_default();
+        if compute_unit_price < self.min_compute_unit_price {
+            return Err(Error::invalid_params(format!(
+                "compute unit price too low: {} micro-lamports (min: {})",
+                compute_unit_price, self.min_compute_unit_price
+            )));
+        }
+        if self.min_fee_per_signature > 0 {
+            let message = transaction.message();
+            // Unknown blockhashes are rejected when executing the transaction
+            let fee = bank.get_fee_for_message(message).unwrap_or_default();
+            let fee_per_signature =
+                fee / message.header().num_required_signatures.max(1) as u64;
+            if fee_per_signature < self.min_fee_per_signature {
+                return Err(Error::invalid_params(format!(
+                    "fee per signature too low: {} lamports (min: {})",
+                    fee_per_signature, self.min_fee_per_signature
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn is_exempt(&self, transaction: &SanitizedTransaction) -> bool {
+        let message = transaction.message();
+        if self.exempt_fee_payers.contains(message.fee_payer()) {
+            return true;
+        }
+        !self.exempt_programs.is_empty()
+            && message.program_instructions_iter().all(|(program_id, _)| {
+                compute_budget::check_id(program_id)
+                    || self.exempt_programs.contains(program_id)
+            })
+    }
+}
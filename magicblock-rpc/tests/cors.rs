@@ -0,0 +1,71 @@
+use std::{
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+};
+
+use jsonrpc_core::IoHandler;
+use jsonrpc_http_server::{Server, ServerBuilder};
+use magicblock_rpc::cors::cors_domains;
+
+fn start_server(allowed_origins: &[&str]) -> Server {
+    let allowed_origins = allowed_origins
+        .iter()
+        .map(|origin| origin.to_string())
+        .collect::<Vec<_>>();
+    ServerBuilder::new(IoHandler::default())
+        .cors(cors_domains(&allowed_origins))
+        .start_http(&"127.0.0.1:0".parse().unwrap())
+        .expect("failed to start http server")
+}
+
+fn send_preflight(addr: &SocketAddr, origin: &str) -> String {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    let request = format!(
+        "OPTIONS / HTTP/1.1\r\n\
+         Host: {addr}\r\n\
+         Origin: {origin}\r\n\
+         Access-Control-Request-Method: POST\r\n\
+         Access-Control-Request-Headers: content-type\r\n\
+         Connection: close\r\n\
+         \r\n"
+    );
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response.to_lowercase()
+}
+
+#[test]
+fn test_preflight_any_origin_by_default() {
+    let server = start_server(&[]);
+    let response = send_preflight(server.address(), "https://dapp.example");
+
+    assert!(response.starts_with("http/1.1 200"), "{response}");
+    assert!(
+        response.contains("access-control-allow-origin: https://dapp.example")
+    );
+    server.close();
+}
+
+#[test]
+fn test_preflight_allowed_origin() {
+    let server =
+        start_server(&["https://dapp.example", "http://localhost:3000"]);
+    let response = send_preflight(server.address(), "http://localhost:3000");
+
+    assert!(response.starts_with("http/1.1 200"), "{response}");
+    assert!(
+        response.contains("access-control-allow-origin: http://localhost:3000")
+    );
+    server.close();
+}
+
+#[test]
+fn test_preflight_rejected_origin() {
+    let server = start_server(&["https://dapp.example"]);
+    let response = send_preflight(server.address(), "https://evil.example");
+
+    assert!(response.starts_with("http/1.1 403"), "{response}");
+    assert!(!response.contains("access-control-allow-origin"));
+    server.close();
+}
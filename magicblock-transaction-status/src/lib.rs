@@ -1,4 +1,9 @@
-use crossbeam_channel::Sender;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use log::trace;
 use magicblock_bank::transaction_results::TransactionBalancesSet;
 use solana_sdk::{clock::Slot, transaction::SanitizedTransaction};
@@ -9,7 +14,9 @@ pub use solana_transaction_status::*;
 #[allow(clippy::large_enum_variant)]
 pub enum TransactionStatusMessage {
     Batch(TransactionStatusBatch),
-    Freeze(Slot),
+    /// No more transactions will be executed in the slot, the sender is
+    /// notified once its buffered transactions were written to the ledger
+    Freeze(Slot, Sender<()>),
 }
 
 // NOTE: copied from ledger/src/blockstore_processor.rs:2206
@@ -20,14 +27,40 @@ pub struct TransactionStatusBatch {
     pub balances: TransactionBalancesSet,
     pub token_balances: TransactionTokenBalancesSet,
     pub transaction_indexes: Vec<usize>,
+    /// Span the batch was executed in, writing it to the ledger and
+    /// notifying geyser about it is traced as part of it
+    pub span: tracing::Span,
+}
+
+/// Outcome of freezing the transaction statuses of a slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeOutcome {
+    /// The buffered transactions of the slot were written
+    Flushed,
+    /// The buffered transactions of the slot were not written within the
+    /// timeout, the writer is considered stalled from now on
+    TimedOut,
+    /// The writer has not caught up with an earlier freeze which timed out
+    /// yet, hence it was not waited for
+    Stalled,
 }
 
 #[derive(Clone, Debug)]
 pub struct TransactionStatusSender {
     pub sender: Sender<TransactionStatusMessage>,
+    /// Notified once the writer caught up after a freeze timed out, no
+    /// freeze waits for the writer until then
+    stalled_freeze: Arc<Mutex<Option<Receiver<()>>>>,
 }
 
 impl TransactionStatusSender {
+    pub fn new(sender: Sender<TransactionStatusMessage>) -> Self {
+        Self {
+            sender,
+            stalled_freeze: Default::default(),
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn send_transaction_status_batch(
         &self,
@@ -46,6 +79,7 @@ impl TransactionStatusSender {
                 balances,
                 token_balances,
                 transaction_indexes,
+                span: tracing::Span::current(),
             },
         )) {
             trace!(
@@ -55,4 +89,115 @@ impl TransactionStatusSender {
             );
         }
     }
+
+    /// Signals that no more transactions will be executed in `slot` and
+    /// waits until its buffered transactions were written to the ledger.
+    ///
+    /// If that does not happen within the timeout the writer is considered
+    /// stalled and later freezes don't wait for it until it caught up, so
+    /// that a stuck writer slows down at most one slot.
+    pub fn freeze_transaction_statuses(
+        &self,
+        slot: Slot,
+        timeout: Duration,
+    ) -> FreezeOutcome {
+        let mut stalled_freeze = self
+            .stalled_freeze
+            .lock()
+            .expect("Mutex of stalled freeze poisoned");
+        let (flushed_sender, flushed_receiver) = crossbeam_channel::bounded(1);
+        if let Err(e) = self
+            .sender
+            .send(TransactionStatusMessage::Freeze(slot, flushed_sender))
+        {
+            trace!(
+                "Slot {} transaction_status send freeze failed: {:?}",
+                slot,
+                e
+            );
+            return FreezeOutcome::TimedOut;
+        }
+        // Freezes are handled in order, the writer caught up once the stalled
+        // one was handled
+        let still_stalled = stalled_freeze.take().is_some_and(|stalled| {
+            stalled.try_recv() == Err(TryRecvError::Empty)
+        });
+        if still_stalled {
+            *stalled_freeze = Some(flushed_receiver);
+            return FreezeOutcome::Stalled;
+        }
+        match flushed_receiver.recv_timeout(timeout) {
+            Ok(()) => FreezeOutcome::Flushed,
+            Err(_) => {
+                *stalled_freeze = Some(flushed_receiver);
+                FreezeOutcome::TimedOut
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    fn acknowledge_freezes(receiver: &Receiver<TransactionStatusMessage>) {
+        for message in receiver.try_iter() {
+            if let TransactionStatusMessage::Freeze(_, flushed) = message {
+                // Nobody waits for freezes replaced by later ones anymore
+                let _ = flushed.send(());
+            }
+        }
+    }
+
+    #[test]
+    fn test_freeze_waits_for_flush() {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let sender = TransactionStatusSender::new(sender);
+        let writer = std::thread::spawn(move || {
+            let TransactionStatusMessage::Freeze(slot, flushed) =
+                receiver.recv().unwrap()
+            else {
+                panic!("expected freeze");
+            };
+            flushed.send(()).unwrap();
+            slot
+        });
+
+        assert_eq!(
+            sender.freeze_transaction_statuses(1, Duration::from_secs(5)),
+            FreezeOutcome::Flushed
+        );
+        assert_eq!(writer.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_stalled_writer_is_not_waited_for_until_it_caught_up() {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let sender = TransactionStatusSender::new(sender);
+
+        assert_eq!(
+            sender.freeze_transaction_statuses(1, TIMEOUT),
+            FreezeOutcome::TimedOut
+        );
+        // Would time out as well if it waited for the writer
+        assert_eq!(
+            sender.freeze_transaction_statuses(2, Duration::from_secs(60)),
+            FreezeOutcome::Stalled
+        );
+
+        acknowledge_freezes(&receiver);
+        let writer = std::thread::spawn(move || {
+            let message = receiver.recv().unwrap();
+            if let TransactionStatusMessage::Freeze(_, flushed) = message {
+                flushed.send(()).unwrap();
+            }
+        });
+        assert_eq!(
+            sender.freeze_transaction_statuses(3, Duration::from_secs(5)),
+            FreezeOutcome::Flushed
+        );
+        writer.join().unwrap();
+    }
 }
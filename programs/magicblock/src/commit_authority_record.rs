@@ -0,0 +1,95 @@
+use solana_sdk::pubkey::Pubkey;
+
+/// Permission of a program to schedule commits of an account it does not own.
+///
+/// The program owning the delegated account creates the record on the base
+/// layer when delegating the account. Since only the owner program can write
+/// to the accounts it owns, the record being owned by it proves that it
+/// granted the permission. The record is cloned into the ephemeral rollup like
+/// any other account.
+///
+/// The record is stored at the PDA derived from the owner program with the
+/// seeds [CommitAuthorityRecord::SEED], the delegated account and the
+/// authorized program. Its data has the following fixed layout:
+///
+/// - **0..8**   [CommitAuthorityRecord::DISCRIMINATOR]
+/// - **8..40**  delegated account
+/// - **40..72** program authorized to schedule commits of the account
+/// - **72**     bump of the PDA
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitAuthorityRecord {
+    pub delegated_account: Pubkey,
+    pub authorized_program: Pubkey,
+    pub bump: u8,
+}
+
+impl CommitAuthorityRecord {
+    pub const SEED: &'static [u8] = b"commit-authority";
+    pub const DISCRIMINATOR: [u8; 8] = *b"cmtauth1";
+    pub const SIZE: usize = 8 + 32 + 32 + 1;
+
+    /// Derives the address of the record authorizing the program to
+    /// schedule commits of the account owned by the owner program
+    pub fn find_address(
+        delegated_account: &Pubkey,
+        authorized_program: &Pubkey,
+        owner_program: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                Self::SEED,
+                delegated_account.as_ref(),
+                authorized_program.as_ref(),
+            ],
+            owner_program,
+        )
+    }
+
+    /// Parses the record from the account data, `None` if the data does not
+    /// hold a record
+    pub fn try_from_data(data: &[u8]) -> Option<Self> {
+        if data.len() < Self::SIZE || data[..8] != Self::DISCRIMINATOR {
+            return None;
+        }
+        Some(Self {
+            delegated_account: Pubkey::try_from(&data[8..40]).ok()?,
+            authorized_program: Pubkey::try_from(&data[40..72]).ok()?,
+            bump: data[72],
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::SIZE);
+        data.extend_from_slice(&Self::DISCRIMINATOR);
+        data.extend_from_slice(self.delegated_account.as_ref());
+        data.extend_from_slice(self.authorized_program.as_ref());
+        data.push(self.bump);
+        data
+    }
+
+    /// Checks that the record stored at the address was created by the owner
+    /// program and authorizes the program to schedule commits of the account
+    pub fn authorizes(
+        &self,
+        address: &Pubkey,
+        owner_program: &Pubkey,
+        delegated_account: &Pubkey,
+        authorized_program: &Pubkey,
+    ) -> bool {
+        if self.delegated_account.ne(delegated_account)
+            || self.authorized_program.ne(authorized_program)
+        {
+            return false;
+        }
+        Pubkey::create_program_address(
+            &[
+                Self::SEED,
+                delegated_account.as_ref(),
+                authorized_program.as_ref(),
+                &[self.bump],
+            ],
+            owner_program,
+        )
+        .is_ok_and(|expected| expected.eq(address))
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::VecDeque;
+
+use magicblock_core::{
+    commit_record::{CommitRecord, UndelegationStatus},
+    magic_program,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount, WritableAccount},
+    clock::Slot,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+};
+
+/// Outcome of a scheduled commit as recorded in the [CommitEventLog]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitEvent {
+    /// Increases with each recorded event, a gap between the events seen by a
+    /// client means that it missed some of them
+    pub seq: u64,
+    /// The slot of the ephemeral rollup at which the event was recorded
+    pub recorded_at_slot: Slot,
+    pub record: CommitRecord,
+}
+
+/// Latest undelegation status of an account as recorded in the
+/// [CommitEventLog]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndelegationEntry {
+    pub pubkey: Pubkey,
+    /// The commit that requested the undelegation
+    pub commit_id: u64,
+    pub status: UndelegationStatus,
+    /// The slot of the ephemeral rollup at which the status was recorded
+    pub updated_at_slot: Slot,
+}
+
+/// Outcomes of the most recent scheduled commits, an event is recorded each
+/// time the outcome of a commit changes, i.e. when its transactions were sent
+/// and again once they were confirmed on the base layer.
+///
+/// The commit events account holds this struct serialized with bincode's
+/// default options, the remainder of the account is zeroed:
+///
+/// - `next_seq`: `u64` sequence number of the next recorded event
+/// - `events`: `u64` length followed by each [CommitEvent], oldest first
+/// - `undelegations`: `u64` length followed by each [UndelegationEntry],
+///   least recently updated first
+///
+/// The oldest events are dropped once more than [CommitEventLog::MAX_EVENTS]
+/// are recorded or they don't fit into [CommitEventLog::SIZE] anymore.
+/// Likewise the least recently updated undelegations are dropped once more
+/// than [CommitEventLog::MAX_UNDELEGATIONS] are tracked.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CommitEventLog {
+    pub next_seq: u64,
+    pub events: VecDeque<CommitEvent>,
+    pub undelegations: VecDeque<UndelegationEntry>,
+}
+
+impl CommitEventLog {
+    pub const SIZE: usize = magic_program::MAGIC_COMMIT_EVENTS_SIZE;
+    pub const MAX_EVENTS: usize = 1024;
+    pub const MAX_UNDELEGATIONS: usize = 2048;
+
+    /// Reads the log from the data of the commit events account, zeroed data
+    /// results in an empty log
+    pub fn try_from_data(data: &[u8]) -> Result<Self, bincode::Error> {
+        if data.is_empty() {
+            Ok(Self::default())
+        } else {
+            bincode::deserialize(data)
+        }
+    }
+
+    /// The latest undelegation status recorded for the account, `None` if
+    /// no undelegation was requested for it recently
+    pub fn undelegation(&self, pubkey: &Pubkey) -> Option<&UndelegationEntry> {
+        self.undelegations
+            .iter()
+            .find(|entry| entry.pubkey.eq(pubkey))
+    }
+
+    pub fn undelegation_status(&self, pubkey: &Pubkey) -> UndelegationStatus {
+        self.undelegation(pubkey)
+            .map(|entry| entry.status)
+            .unwrap_or(UndelegationStatus::Unknown)
+    }
+
+    pub(crate) fn record(&mut self, slot: Slot, record: CommitRecord) -> u64 {
+        if record.requested_undelegation {
+            self.record_undelegations(slot, &record);
+        }
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        self.events.push_back(CommitEvent {
+            seq,
+            recorded_at_slot: slot,
+            record,
+        });
+        while self.events.len() > Self::MAX_EVENTS {
+            self.events.pop_front();
+        }
+        seq
+    }
+
+    fn record_undelegations(&mut self, slot: Slot, record: &CommitRecord) {
+        // Excluded accounts were not committed and thus not undelegated either
+        let included = UndelegationStatus::from_outcome(&record.outcome)
+            .into_iter()
+            .flat_map(move |status| {
+                record.included_pubkeys.iter().map(move |x| (x, status))
+            });
+        let excluded = record
+            .excluded_pubkeys
+            .iter()
+            .map(|x| (x, UndelegationStatus::Failed));
+        for (pubkey, status) in included.chain(excluded) {
+            self.undelegations.retain(|entry| entry.pubkey.ne(pubkey));
+            self.undelegations.push_back(UndelegationEntry {
+                pubkey: *pubkey,
+                commit_id: record.commit_id,
+                status,
+                updated_at_slot: slot,
+            });
+        }
+        while self.undelegations.len() > Self::MAX_UNDELEGATIONS {
+            self.undelegations.pop_front();
+        }
+    }
+
+    /// Serializes the log into the zeroed account after dropping the oldest
+    /// events and then the least recently updated undelegations that don't
+    /// fit into it
+    pub(crate) fn store(
+        &mut self,
+        data: &mut AccountSharedData,
+    ) -> Result<(), InstructionError> {
+        let max_size = data.data().len().min(Self::SIZE) as u64;
+        while bincode::serialized_size(self)
+            .map_err(|_| InstructionError::GenericError)?
+            > max_size
+        {
+            if self.events.pop_front().is_none()
+                && self.undelegations.pop_front().is_none()
+            {
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+        }
+        // Zero fill first to not leave parts of dropped events behind
+        data.data_as_mut_slice().fill(0);
+        data.serialize_data(self)
+            .map_err(|_| InstructionError::GenericError)
+    }
+}
@@ -5,4 +5,8 @@ pub mod custom_error_codes {
     pub const FAILED_TO_TRANSFER_SCHEDULE_COMMIT_COST: u32 = 10_000;
     pub const UNABLE_TO_UNLOCK_SENT_COMMITS: u32 = 10_001;
     pub const CANNOT_FIND_SCHEDULED_COMMIT: u32 = 10_002;
+    pub const MAGIC_CONTEXT_FULL: u32 = 10_003;
+    pub const PROGRAM_NOT_ALLOWED_TO_SCHEDULE_COMMITS: u32 = 10_004;
+    pub const SESSION_KEY_NOT_SPONSORED: u32 = 10_005;
+    pub const TOO_MANY_SESSION_KEYS: u32 = 10_006;
 }
@@ -1,8 +1,18 @@
+mod commit_authority_record;
+mod commit_event_log;
 pub mod errors;
 mod magic_context;
 mod mutate_accounts;
 mod schedule_transactions;
-pub use magic_context::{FeePayerAccount, MagicContext, ScheduledCommit};
+mod session_key_registry;
+mod session_keys;
+pub use commit_authority_record::CommitAuthorityRecord;
+pub use commit_event_log::{CommitEvent, CommitEventLog, UndelegationEntry};
+pub use magic_context::{
+    CommittedAccount, FeePayerAccount, MagicContext, PendingTransferIntent,
+    RecurringCommit, ScheduledCommit,
+};
+pub use session_key_registry::{SessionKey, SessionKeyRegistry};
 pub mod magicblock_instruction;
 pub mod magicblock_processor;
 #[cfg(test)]
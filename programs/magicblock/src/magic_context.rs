@@ -6,10 +6,18 @@ use solana_sdk::{
     account::{AccountSharedData, ReadableAccount},
     clock::Slot,
     hash::Hash,
+    instruction::InstructionError,
     pubkey::Pubkey,
     transaction::Transaction,
 };
 
+use crate::{
+    errors::custom_error_codes,
+    magicblock_instruction::{
+        BaseLayerAction, CommitPriority, RecurringCommitEnd, TransferIntent,
+    },
+};
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CommittedAccount {
     pub pubkey: Pubkey,
@@ -33,15 +41,118 @@ pub struct ScheduledCommit {
     pub payer: Pubkey,
     pub commit_sent_transaction: Transaction,
     pub request_undelegation: bool,
+    /// The slot at which the commit is executed if it was scheduled in advance,
+    /// otherwise it is executed right away
+    pub execute_at_slot: Option<Slot>,
+    /// Instruction to run on the base layer once the accounts are committed
+    pub base_layer_action: Option<BaseLayerAction>,
+    /// Compute unit price requested for the transaction committing the
+    /// accounts on the base layer
+    pub compute_unit_price: Option<u64>,
+    /// Whether the accounts are closed on the ephemeral rollup once their
+    /// undelegation was confirmed
+    pub close_accounts: bool,
+    /// Lamports the committed escrow pays out on the base layer
+    pub transfer_intent: Option<TransferIntent>,
+    /// Whether all accounts are committed in the same transaction on the base
+    /// layer, otherwise they may be split across multiple transactions of
+    /// which only some may land
+    pub atomic: bool,
+    /// Memo included in the transactions committing the accounts on the base
+    /// layer
+    pub memo: Option<String>,
+    /// Determines the order in which pending commits are processed and the
+    /// compute unit price their transactions offer
+    pub priority: CommitPriority,
+}
+
+impl ScheduledCommit {
+    /// Determines if the commit is to be executed at the given slot
+    pub fn is_due(&self, slot: Slot) -> bool {
+        self.execute_at_slot
+            .is_none_or(|execute_at_slot| execute_at_slot <= slot)
+    }
+}
+
+/// Lamports of a scheduled transfer intent held by the context account until
+/// it is known whether the recipient was paid on the base layer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTransferIntent {
+    pub commit_id: u64,
+    /// The escrow the lamports are refunded to if the payout failed
+    pub escrow: Pubkey,
+    pub lamports: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurringCommit {
+    pub id: u64,
+    pub accounts: Vec<CommittedAccount>,
+    pub payer: Pubkey,
+    pub interval_slots: u64,
+    /// The slot at which the next commit is scheduled
+    pub next_slot: Slot,
+    pub end: RecurringCommitEnd,
+    /// How many commits were scheduled so far
+    pub executed_commits: u64,
 }
 
+impl RecurringCommit {
+    fn is_finished(&self) -> bool {
+        match self.end {
+            RecurringCommitEnd::AfterCommits(commits) => {
+                self.executed_commits >= commits
+            }
+            RecurringCommitEnd::AfterSlot(slot) => self.next_slot > slot,
+        }
+    }
+}
+
+/// Commits scheduled by transactions of the current slot that weren't accepted
+/// by the validator yet, as well as the registered recurring commits.
+///
+/// The context account holds this struct serialized with bincode's default
+/// options, its layout is stable so that programs running on the ephemeral
+/// rollup can read it to check for pending commits:
+///
+/// - `next_recurring_commit_slot`: `u64` slot at which the next recurring
+///   commit is due or zero if none is registered, always at offset 0
+/// - `scheduled_commits`: `u64` length followed by each [ScheduledCommit]
+/// - `recurring_commits`: `u64` length followed by each [RecurringCommit]
+/// - `collected_fees`: `u64` lamports charged for scheduling commits that the
+///   validator authority claims when accepting them
+/// - `next_recurring_commit_id`: `u64` ID assigned to the next registered
+///   recurring commit
+/// - `pending_transfer_intents`: `u64` length followed by each
+///   [PendingTransferIntent] whose lamports the context holds
+///
+/// New fields are only ever appended to the end of the structs.
+/// The remainder of the account is zeroed.
+///
+/// The account is [MagicContext::SIZE] bytes long, but grows up to
+/// [MagicContext::MAX_SIZE] if more commits are scheduled within one slot
+/// than fit into it. It shrinks back once the commits were accepted.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct MagicContext {
+    /// Stored first so that it can be checked every slot without
+    /// deserializing the context, see [Self::has_due_recurring_commits]
+    pub next_recurring_commit_slot: Slot,
     pub scheduled_commits: Vec<ScheduledCommit>,
+    pub recurring_commits: Vec<RecurringCommit>,
+    /// The lamports of the fees are held by the context account until the
+    /// validator authority claims them
+    pub collected_fees: u64,
+    /// Persisted with the recurring commits, so their IDs stay unique when
+    /// the validator restarts
+    pub next_recurring_commit_id: u64,
+    /// The lamports of scheduled transfer intents are held by the context
+    /// account until the validator settles them
+    pub pending_transfer_intents: Vec<PendingTransferIntent>,
 }
 
 impl MagicContext {
     pub const SIZE: usize = magic_program::MAGIC_CONTEXT_SIZE;
+    pub const MAX_SIZE: usize = magic_program::MAX_MAGIC_CONTEXT_SIZE;
     pub const ZERO: [u8; Self::SIZE] = [0; Self::SIZE];
     pub(crate) fn deserialize(
         data: &AccountSharedData,
@@ -53,6 +164,39 @@ impl MagicContext {
         }
     }
 
+    /// Reads the context from the data of the context account, i.e. from
+    /// within a program running on the ephemeral rollup
+    pub fn try_from_data(data: &[u8]) -> Result<Self, bincode::Error> {
+        if data.is_empty() {
+            Ok(Self::default())
+        } else {
+            bincode::deserialize(data)
+        }
+    }
+
+    /// Serializes the context into the account, growing the account if the
+    /// context doesn't fit into it anymore.
+    /// Fails with [custom_error_codes::MAGIC_CONTEXT_FULL] if the context
+    /// exceeds [Self::MAX_SIZE] instead of dropping any of its commits.
+    pub(crate) fn store(
+        &self,
+        data: &mut AccountSharedData,
+    ) -> Result<(), InstructionError> {
+        let size = bincode::serialized_size(self)
+            .map_err(|_| InstructionError::GenericError)?
+            as usize;
+        if size > Self::MAX_SIZE {
+            return Err(InstructionError::Custom(
+                custom_error_codes::MAGIC_CONTEXT_FULL,
+            ));
+        }
+        if size > data.data().len() {
+            data.resize(size, 0);
+        }
+        data.serialize_data(self)
+            .map_err(|_| InstructionError::GenericError)
+    }
+
     pub(crate) fn add_scheduled_commit(&mut self, commit: ScheduledCommit) {
         self.scheduled_commits.push(commit);
     }
@@ -61,12 +205,134 @@ impl MagicContext {
         mem::take(&mut self.scheduled_commits)
     }
 
+    pub(crate) fn remove_scheduled_commit(
+        &mut self,
+        id: u64,
+    ) -> Option<ScheduledCommit> {
+        let idx = self
+            .scheduled_commits
+            .iter()
+            .position(|commit| commit.id == id)?;
+        Some(self.scheduled_commits.remove(idx))
+    }
+
+    pub(crate) fn collect_fee(&mut self, fee: u64) {
+        self.collected_fees = self.collected_fees.saturating_add(fee);
+    }
+
+    pub(crate) fn take_collected_fees(&mut self) -> u64 {
+        mem::take(&mut self.collected_fees)
+    }
+
+    pub(crate) fn hold_transfer_intent(
+        &mut self,
+        intent: PendingTransferIntent,
+    ) {
+        self.pending_transfer_intents.push(intent);
+    }
+
+    pub(crate) fn take_pending_transfer_intent(
+        &mut self,
+        commit_id: u64,
+    ) -> Option<PendingTransferIntent> {
+        let idx = self
+            .pending_transfer_intents
+            .iter()
+            .position(|intent| intent.commit_id == commit_id)?;
+        Some(self.pending_transfer_intents.remove(idx))
+    }
+
+    /// Adds the recurring commit assigning it the next ID, which is returned
+    pub(crate) fn add_recurring_commit(
+        &mut self,
+        mut commit: RecurringCommit,
+    ) -> u64 {
+        commit.id = self.next_recurring_commit_id;
+        self.next_recurring_commit_id += 1;
+        self.recurring_commits.push(commit);
+        self.update_next_recurring_commit_slot();
+        self.next_recurring_commit_id - 1
+    }
+
+    pub(crate) fn remove_recurring_commit(
+        &mut self,
+        id: u64,
+    ) -> Option<RecurringCommit> {
+        let idx = self
+            .recurring_commits
+            .iter()
+            .position(|commit| commit.id == id)?;
+        let commit = self.recurring_commits.remove(idx);
+        self.update_next_recurring_commit_slot();
+        Some(commit)
+    }
+
+    /// Stops committing the accounts once they are undelegated, recurring
+    /// commits without any accounts left are removed
+    pub(crate) fn remove_undelegated_accounts(
+        &mut self,
+        undelegated: &[CommittedAccount],
+    ) {
+        for commit in self.recurring_commits.iter_mut() {
+            commit.accounts.retain(|account| {
+                !undelegated
+                    .iter()
+                    .any(|undelegated| undelegated.pubkey == account.pubkey)
+            });
+        }
+        self.recurring_commits
+            .retain(|commit| !commit.accounts.is_empty());
+        self.update_next_recurring_commit_slot();
+    }
+
+    /// Returns the recurring commits that are due at the given slot and
+    /// advances them to their next slot, the ones that are finished afterwards
+    /// are removed
+    pub(crate) fn advance_due_recurring_commits(
+        &mut self,
+        slot: Slot,
+    ) -> Vec<RecurringCommit> {
+        let mut due = vec![];
+        for commit in self.recurring_commits.iter_mut() {
+            if commit.next_slot <= slot {
+                due.push(commit.clone());
+                commit.executed_commits += 1;
+                // Missed intervals are not caught up on
+                commit.next_slot = slot.saturating_add(commit.interval_slots);
+            }
+        }
+        self.recurring_commits
+            .retain(|commit| !commit.is_finished());
+        self.update_next_recurring_commit_slot();
+        due
+    }
+
+    fn update_next_recurring_commit_slot(&mut self) {
+        // Zero marks that no recurring commit is registered, a commit due at
+        // slot zero is due at any later slot as well
+        self.next_recurring_commit_slot = self
+            .recurring_commits
+            .iter()
+            .map(|commit| commit.next_slot.max(1))
+            .min()
+            .unwrap_or(0);
+    }
+
     pub fn has_scheduled_commits(data: &[u8]) -> bool {
-        // Currently we only store a vec of scheduled commits in the MagicContext
-        // The first 8 bytes contain the length of the vec
+        // The scheduled commits are stored right after the slot of the next
+        // recurring commit, the first 8 bytes contain the length of the vec
         // This works even if the length is actually stored as a u32
         // since we zero out the entire context whenever we update the vec
-        !is_zeroed(&data[0..8])
+        !is_zeroed(&data[8..16])
+    }
+
+    /// Determines if any recurring commit is due at the given slot, only
+    /// reads the slot stored at the start of the context
+    pub fn has_due_recurring_commits(data: &[u8], slot: Slot) -> bool {
+        data.get(0..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+            .is_some_and(|next_slot| next_slot != 0 && next_slot <= slot)
     }
 }
 
@@ -1,16 +1,26 @@
 use std::collections::HashMap;
 
-use magicblock_core::magic_program::MAGIC_CONTEXT_PUBKEY;
+pub use magicblock_core::mutation_audit::MutationReason;
+use magicblock_core::{
+    commit_record::CommitRecord,
+    magic_program::{
+        MAGIC_COMMIT_EVENTS_PUBKEY, MAGIC_CONTEXT_PUBKEY,
+        MAGIC_SESSION_KEYS_PUBKEY,
+    },
+};
 use num_derive::{FromPrimitive, ToPrimitive};
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
     account::Account,
+    clock::Slot,
     decode_error::DecodeError,
     hash::Hash,
-    instruction::{AccountMeta, Instruction},
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_utils::limited_deserialize,
     pubkey::Pubkey,
     signature::Keypair,
     signer::Signer,
+    system_instruction,
     transaction::Transaction,
 };
 use thiserror::Error;
@@ -56,6 +66,9 @@ pub enum MagicBlockProgramError {
 
     #[error("Encountered an error when persisting account modification data.")]
     FailedToPersistAccountModData,
+
+    #[error("Encountered an error when persisting the audit entry of an account modification.")]
+    FailedToPersistMutationAuditEntry,
 }
 
 impl<T> DecodeError<T> for MagicBlockProgramError {
@@ -98,15 +111,185 @@ pub(crate) struct AccountModificationForInstruction {
     pub rent_epoch: Option<u64>,
 }
 
+/// Determines when a commit scheduled in advance is executed
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum CommitTarget {
+    /// Execute the commit the given number of slots after it was scheduled
+    Delay(u64),
+    /// Execute the commit once the ephemeral rollup reaches the given slot
+    Slot(Slot),
+}
+
+/// Priority of a scheduled commit, commits with a higher priority are
+/// processed first when commits pile up and offer a higher compute unit price
+/// on the base layer
+#[derive(
+    Clone,
+    Copy,
+    Default,
+    Serialize,
+    Deserialize,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+)]
+pub enum CommitPriority {
+    /// Background syncs that are committed after all other pending commits
+    /// at the configured static compute unit price
+    Low,
+    #[default]
+    Normal,
+    /// Latency sensitive commits that are committed first at the highest
+    /// compute unit price scheduled commits may request
+    High,
+}
+
+/// Determines when a recurring commit stops
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum RecurringCommitEnd {
+    /// Stop after the given number of commits were executed
+    AfterCommits(u64),
+    /// Stop once the ephemeral rollup passed the given slot
+    AfterSlot(Slot),
+}
+
+/// An account passed to a [BaseLayerAction], it cannot be a signer
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct BaseLayerActionAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+/// Instruction invoking the owner program of the committed accounts on the
+/// base layer, it runs as part of the transaction committing the accounts
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct BaseLayerAction {
+    /// Has to be the program owning the committed accounts
+    pub program_id: Pubkey,
+    pub accounts: Vec<BaseLayerActionAccount>,
+    pub data: Vec<u8>,
+}
+
+impl BaseLayerAction {
+    pub fn to_instruction(&self) -> Instruction {
+        let account_metas = self
+            .accounts
+            .iter()
+            .map(|account| {
+                if account.is_writable {
+                    AccountMeta::new(account.pubkey, false)
+                } else {
+                    AccountMeta::new_readonly(account.pubkey, false)
+                }
+            })
+            .collect();
+        Instruction::new_with_bytes(self.program_id, &self.data, account_metas)
+    }
+}
+
+/// Lamports a delegated escrow account pays out to a recipient on the base
+/// layer, see [MagicBlockInstruction::ScheduleTransferIntent]
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct TransferIntent {
+    pub recipient: Pubkey,
+    pub lamports: u64,
+}
+
+impl TransferIntent {
+    /// The transfer is paid by the validator authority, which the lamports
+    /// removed from the escrow by the commit are released to once it landed
+    pub fn to_instruction(&self) -> Instruction {
+        system_instruction::transfer(
+            &validator_authority_id(),
+            &self.recipient,
+            self.lamports,
+        )
+    }
+}
+
+/// Program logging the memos attached to commit transactions on the base layer
+pub const MEMO_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Max length in bytes of a memo attached to a scheduled commit
+pub const MAX_COMMIT_MEMO_LEN: usize = 128;
+
+/// Creates the instruction adding the memo to a transaction committing
+/// accounts, see [MagicBlockInstruction::ScheduleCommitWithMemo]
+pub fn commit_memo_instruction(memo: &str) -> Instruction {
+    Instruction::new_with_bytes(MEMO_PROGRAM_ID, memo.as_bytes(), vec![])
+}
+
+/// Layout version of the arguments every instruction started out with.
+/// Instructions encoded with it are identical to their plain bincode encoding.
+pub const INITIAL_INSTRUCTION_VERSION: u8 = 0;
+
+/// Latest layout version of the arguments of each instruction, indexed by its
+/// [MagicBlockInstruction::index].
+///
+/// The version of an instruction is bumped whenever the shape of its
+/// arguments changes. Decoding of its previous layouts has to be kept in
+/// [MagicBlockInstruction::try_from_slice] since programs that are already
+/// deployed keep invoking it with them.
+const INSTRUCTION_VERSIONS: [u8; 21] = [
+    1,                           // ModifyAccounts: added reason
+    1,                           // ScheduleCommit: added priority
+    1,                           // ScheduleCommitAndUndelegate: added priority
+    INITIAL_INSTRUCTION_VERSION, // AcceptScheduleCommits
+    INITIAL_INSTRUCTION_VERSION, // ScheduledCommitSent
+    INITIAL_INSTRUCTION_VERSION, // ScheduleCommitAt
+    INITIAL_INSTRUCTION_VERSION, // RegisterRecurringCommit
+    INITIAL_INSTRUCTION_VERSION, // CancelScheduledCommit
+    INITIAL_INSTRUCTION_VERSION, // ScheduleCommitAndUndelegateWithAction
+    INITIAL_INSTRUCTION_VERSION, // ScheduleCommitWithComputeUnitPrice
+    INITIAL_INSTRUCTION_VERSION, // ScheduleCommitUndelegateAndClose
+    INITIAL_INSTRUCTION_VERSION, // ScheduleTransferIntent
+    INITIAL_INSTRUCTION_VERSION, // RecordCommitEvent
+    INITIAL_INSTRUCTION_VERSION, // ScheduleAtomicCommit
+    INITIAL_INSTRUCTION_VERSION, // ScheduleCommitWithMemo
+    INITIAL_INSTRUCTION_VERSION, // GetUndelegationStatus
+    INITIAL_INSTRUCTION_VERSION, // ScheduleCommitWithAuthorityRecords
+    INITIAL_INSTRUCTION_VERSION, // RegisterSessionKey
+    INITIAL_INSTRUCTION_VERSION, // RevokeSessionKey
+    INITIAL_INSTRUCTION_VERSION, // CancelRecurringCommit
+    INITIAL_INSTRUCTION_VERSION, // SettleTransferIntent
+];
+
+/// Instructions of the magic program.
+///
+/// # Encoding
+///
+/// Each instruction starts with a 4 byte discriminator followed by the bincode
+/// encoding of its arguments:
+/// - **0.**    [MagicBlockInstruction::index] which never changes for an
+///             instruction, new instructions are appended
+/// - **1..2.** always zero
+/// - **3.**    layout version of the arguments, see [INSTRUCTION_VERSIONS]
+///
+/// Thus instructions at their initial version are encoded exactly as bincode
+/// encodes this enum.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub(crate) enum MagicBlockInstruction {
     /// Modify one or more accounts
     ///
+    /// Each modification is recorded in the audit trail of the ledger
+    /// together with its reason.
+    ///
     /// # Account references
     ///  - **0.**    `[WRITE, SIGNER]` Validator Authority
     ///  - **1..n.** `[WRITE]` Accounts to modify
     ///  - **n+1**  `[SIGNER]` (Implicit NativeLoader)
-    ModifyAccounts(HashMap<Pubkey, AccountModificationForInstruction>),
+    ///
+    /// # Versions
+    /// - **0.** only `accounts`, the reason is [MutationReason::Unspecified]
+    /// - **1.** added `reason`
+    ModifyAccounts {
+        accounts: HashMap<Pubkey, AccountModificationForInstruction>,
+        reason: MutationReason,
+    },
 
     /// Schedules the accounts provided at end of accounts Vec to be committed.
     /// It should be invoked from the program whose PDA accounts are to be
@@ -121,7 +304,11 @@ pub(crate) enum MagicBlockInstruction {
     /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
     ///                              the scheduled commits
     /// - **2..n** `[]`              Accounts to be committed
-    ScheduleCommit,
+    ///
+    /// # Versions
+    /// - **0.** no arguments, the commit has [CommitPriority::Normal]
+    /// - **1.** added `priority`
+    ScheduleCommit { priority: CommitPriority },
 
     /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommit] except
     /// that the [ScheduledCommit] is flagged such that when accounts are committed, a request
@@ -138,17 +325,23 @@ pub(crate) enum MagicBlockInstruction {
     /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
     ///                              the scheduled commits
     /// - **2..n** `[]`              Accounts to be committed and undelegated
-    ScheduleCommitAndUndelegate,
+    ///
+    /// # Versions
+    /// - **0.** no arguments, the commit has [CommitPriority::Normal]
+    /// - **1.** added `priority`
+    ScheduleCommitAndUndelegate { priority: CommitPriority },
 
     /// Moves the scheduled commit from the MagicContext to the global scheduled commits
     /// map. This is the second part of scheduling a commit.
     ///
     /// It is run at the start of the slot to update the global scheduled commits map just
     /// in time for the validator to realize the commits right after.
+    /// The fees payers were charged for scheduling the commits are moved to the
+    /// validator authority at the same time.
     ///
     /// # Account references
-    /// - **0.**  `[SIGNER]` Validator Authority
-    /// - **1.**  `[WRITE]`  Magic Context Account containing the initially scheduled commits
+    /// - **0.**  `[WRITE, SIGNER]` Validator Authority
+    /// - **1.**  `[WRITE]`         Magic Context Account containing the initially scheduled commits
     AcceptScheduleCommits,
 
     /// Records the the attempt to realize a scheduled commit on chain.
@@ -160,6 +353,268 @@ pub(crate) enum MagicBlockInstruction {
     /// We implement it this way so we can log the signature of this transaction
     /// as part of the [MagicBlockInstruction::ScheduleCommit] instruction.
     ScheduledCommitSent(u64),
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommit],
+    /// respectively [MagicBlockInstruction::ScheduleCommitAndUndelegate] if
+    /// `request_undelegation` is set, except that the commit is only executed once the
+    /// [CommitTarget] is reached.
+    /// This allows programs to schedule a settlement in advance, i.e. at the end of a round.
+    /// The accounts are committed with the state they have at that point.
+    ///
+    /// NOTE: accounts to be undelegated are locked immediately and thus cannot be
+    /// written to anymore while the commit is pending.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed
+    ScheduleCommitAt {
+        target: CommitTarget,
+        request_undelegation: bool,
+    },
+
+    /// Registers a commit of the provided accounts that recurs every `interval_slots`
+    /// until the `end` condition is met.
+    /// It should be invoked from the program whose PDA accounts are to be committed.
+    ///
+    /// The recurring commit is stored in the MagicContext and a commit is scheduled
+    /// via [MagicBlockInstruction::AcceptScheduleCommits] whenever it is due.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the recurring commit
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the recurring commits
+    /// - **2..n** `[]`              Accounts to be committed
+    RegisterRecurringCommit {
+        interval_slots: u64,
+        end: RecurringCommitEnd,
+    },
+
+    /// Cancels a commit with the provided ID that is not processed yet, either
+    /// since it was scheduled in the current slot or for a later slot.
+    /// Only the payer that scheduled the commit can cancel it and commits that
+    /// request undelegation cannot be cancelled.
+    ///
+    /// # Account references
+    /// - **0.**   `[SIGNER]` Payer that scheduled the commit
+    /// - **1.**   `[WRITE]`  Magic Context Account containing the scheduled commits
+    CancelScheduledCommit(u64),
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommitAndUndelegate]
+    /// except that the provided [BaseLayerAction] is appended to the transaction
+    /// committing and undelegating the accounts on the base layer.
+    /// That way the program owning the accounts can settle i.e. a game once its
+    /// accounts are back on the base layer without an off-chain relayer.
+    ///
+    /// The action has to invoke the program owning the committed accounts and
+    /// cannot include the validator authority since it signs the transaction.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed and undelegated
+    ScheduleCommitAndUndelegateWithAction(BaseLayerAction),
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommit]
+    /// or [MagicBlockInstruction::ScheduleCommitAndUndelegate] except that the
+    /// transaction committing the accounts on the base layer offers the provided
+    /// compute unit price (in micro lamports).
+    /// This allows latency critical settlements to land faster than routine syncs.
+    ///
+    /// NOTE: the validator caps the price at its configured maximum and never
+    /// offers less than it would for any other commit.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed
+    ScheduleCommitWithComputeUnitPrice {
+        compute_unit_price: u64,
+        request_undelegation: bool,
+    },
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommitAndUndelegate]
+    /// except that the accounts are closed on the ephemeral rollup once their
+    /// undelegation was confirmed on the base layer, reclaiming their storage.
+    /// This suits short-lived accounts, i.e. per-match PDAs, which would otherwise
+    /// remain in the ephemeral rollup forever.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed, undelegated and closed
+    ScheduleCommitUndelegateAndClose,
+
+    /// Schedules a transfer of lamports from a delegated escrow account to a
+    /// recipient on the base layer.
+    /// It should be invoked from the program owning the escrow.
+    ///
+    /// The lamports are moved from the escrow to the magic context, which holds
+    /// them, and a commit of the escrow is scheduled. The transaction committing
+    /// it on the base layer also transfers the lamports from the validator
+    /// authority to the recipient. The delegation program moves the lamports
+    /// the commit removes from the escrow to the validator's fees vault, from
+    /// which the validator authority claims them.
+    ///
+    /// Once that transaction is confirmed the validator releases the held
+    /// lamports to the validator authority via
+    /// [MagicBlockInstruction::SettleTransferIntent]. If the escrow is not
+    /// delegated or the commit is not sent or fails, they are refunded to the
+    /// escrow instead. They stay held if the outcome is unknown since the
+    /// transaction could not be confirmed in time.
+    ///
+    /// NOTE: a scheduled transfer intent cannot be cancelled.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the transfer
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2.**   `[WRITE]`         Escrow account paying the lamports
+    ScheduleTransferIntent(TransferIntent),
+
+    /// Records the outcome of a scheduled commit in the commit events account
+    /// and logs it, so that clients can follow the outcome of their commits
+    /// via account or logs subscriptions.
+    /// This is executed by the validator whenever the outcome of a commit
+    /// changes.
+    ///
+    /// # Account references
+    /// - **0.**   `[SIGNER]` Validator Authority
+    /// - **1.**   `[WRITE]`  Commit Events Account
+    RecordCommitEvent(CommitRecord),
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommit]
+    /// or [MagicBlockInstruction::ScheduleCommitAndUndelegate] except that all
+    /// accounts are committed in the same transaction on the base layer.
+    /// Thus either all of them are committed or none of them, which programs
+    /// keeping invariants across multiple accounts rely on.
+    ///
+    /// NOTE: the commit fails as a whole if any of the accounts is unavailable or
+    /// the accounts don't fit into a single transaction.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed together
+    ScheduleAtomicCommit { request_undelegation: bool },
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommit]
+    /// or [MagicBlockInstruction::ScheduleCommitAndUndelegate] except that the
+    /// transactions committing the accounts on the base layer include the memo.
+    /// This makes them attributable to events on the ephemeral rollup, i.e. the
+    /// end of a match, for indexers of the base layer.
+    ///
+    /// NOTE: the memo may be at most [MAX_COMMIT_MEMO_LEN] bytes long.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed
+    ScheduleCommitWithMemo {
+        memo: String,
+        request_undelegation: bool,
+    },
+
+    /// Looks up the status of the latest undelegation requested for the
+    /// account, as recorded by the validator in the commit events account
+    /// whenever the outcome of a commit changes.
+    /// The status is provided as return data, a bincode serialized
+    /// [magicblock_core::commit_record::UndelegationStatus], so that programs
+    /// can invoke this via CPI and clients can simulate it.
+    ///
+    /// NOTE: only once the status is `Finalized` the account is owned by its
+    /// program on the base layer again, i.e. withdrawals should be unlocked.
+    ///
+    /// # Account references
+    /// - **0.**   `[]` Commit Events Account
+    GetUndelegationStatus(Pubkey),
+
+    /// This is the exact same instruction as [MagicBlockInstruction::ScheduleCommit]
+    /// or [MagicBlockInstruction::ScheduleCommitAndUndelegate] except that the
+    /// invoking program may also commit accounts it does not own, as long as
+    /// their owner program authorized it via a [crate::CommitAuthorityRecord].
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Payer requesting the commit to be scheduled
+    /// - **1.**   `[WRITE]`         Magic Context Account containing to which we store
+    ///                              the scheduled commits
+    /// - **2..n** `[]`              Accounts to be committed
+    /// - **n+1..2n-1** `[]`         Authority record of each account to be
+    ///                              committed in the same order
+    ScheduleCommitWithAuthorityRecords { request_undelegation: bool },
+
+    /// Registers a session key which may sign transactions invoking the
+    /// provided programs until the expiry slot.
+    /// If the validator sponsors session keys, transactions paid by a
+    /// registered session key that only invoke those programs are not
+    /// charged any fees.
+    /// Registering the same session key again replaces its expiry and
+    /// programs.
+    ///
+    /// The session key needs to be sponsored, either by the validator
+    /// authority signing the instruction or by one of the programs invoking
+    /// it via CPI, if the validator allows that program to sponsor session
+    /// keys.
+    /// The validator authority tops up the session key to the rent exempt
+    /// minimum, so that it can pay for transactions without holding lamports.
+    ///
+    /// NOTE: at most [crate::SessionKeyRegistry::MAX_PROGRAMS_PER_KEY]
+    /// programs may be provided and the magic program is not one of them.
+    /// The expiry may be at most
+    /// [crate::SessionKeyRegistry::MAX_SESSION_DURATION_SLOTS] ahead and an
+    /// authority may hold at most
+    /// [crate::SessionKeyRegistry::MAX_KEYS_PER_AUTHORITY] valid session keys.
+    ///
+    /// # Account references
+    /// - **0.**   `[SIGNER]`        Authority registering the session key
+    /// - **1.**   `[WRITE, SIGNER]` Session key
+    /// - **2.**   `[WRITE]`         Session Keys Account
+    /// - **3.**   `[WRITE, SIGNER]` Validator Authority, only needs to sign
+    ///                              unless invoked via CPI by one of the
+    ///                              programs
+    RegisterSessionKey {
+        expires_at_slot: Slot,
+        programs: Vec<Pubkey>,
+    },
+
+    /// Revokes the session key before it expires.
+    ///
+    /// # Account references
+    /// - **0.**   `[SIGNER]` Authority that registered the session key or the
+    ///                       session key itself
+    /// - **1.**   `[WRITE]`  Session Keys Account
+    RevokeSessionKey(Pubkey),
+
+    /// Cancels the recurring commit with the provided ID.
+    /// Commits that it scheduled already are not affected.
+    /// Only the payer that registered the recurring commit can cancel it and
+    /// the fees paid upfront for its remaining commits are not refunded.
+    ///
+    /// NOTE: recurring commits are removed once their accounts are
+    /// undelegated without having to cancel them.
+    ///
+    /// # Account references
+    /// - **0.**   `[SIGNER]` Payer that registered the recurring commit
+    /// - **1.**   `[WRITE]`  Magic Context Account containing the recurring commits
+    CancelRecurringCommit(u64),
+
+    /// Settles the transfer intent scheduled with the commit of the provided
+    /// ID once it is known whether the recipient was paid on the base layer.
+    /// If it was paid the lamports held by the magic context are released to
+    /// the validator authority, otherwise they are refunded to the escrow.
+    /// This is executed by the validator.
+    ///
+    /// # Account references
+    /// - **0.**   `[WRITE, SIGNER]` Validator Authority
+    /// - **1.**   `[WRITE]`         Magic Context Account holding the lamports
+    /// - **2.**   `[WRITE]`         Escrow account of the transfer intent
+    SettleTransferIntent { commit_id: u64, paid_out: bool },
 }
 
 #[allow(unused)]
@@ -167,21 +622,82 @@ impl MagicBlockInstruction {
     pub(crate) fn index(&self) -> u8 {
         use MagicBlockInstruction::*;
         match self {
-            ModifyAccounts(_) => 0,
-            ScheduleCommit => 1,
-            ScheduleCommitAndUndelegate => 2,
+            ModifyAccounts { .. } => 0,
+            ScheduleCommit { .. } => 1,
+            ScheduleCommitAndUndelegate { .. } => 2,
             AcceptScheduleCommits => 3,
             ScheduledCommitSent(_) => 4,
+            ScheduleCommitAt { .. } => 5,
+            RegisterRecurringCommit { .. } => 6,
+            CancelScheduledCommit(_) => 7,
+            ScheduleCommitAndUndelegateWithAction(_) => 8,
+            ScheduleCommitWithComputeUnitPrice { .. } => 9,
+            ScheduleCommitUndelegateAndClose => 10,
+            ScheduleTransferIntent(_) => 11,
+            RecordCommitEvent(_) => 12,
+            ScheduleAtomicCommit { .. } => 13,
+            ScheduleCommitWithMemo { .. } => 14,
+            GetUndelegationStatus(_) => 15,
+            ScheduleCommitWithAuthorityRecords { .. } => 16,
+            RegisterSessionKey { .. } => 17,
+            RevokeSessionKey(_) => 18,
+            CancelRecurringCommit(_) => 19,
+            SettleTransferIntent { .. } => 20,
         }
     }
 
+    /// Layout version of the arguments of the instruction
+    pub(crate) fn version(&self) -> u8 {
+        INSTRUCTION_VERSIONS[self.index() as usize]
+    }
+
     pub(crate) fn discriminant(&self) -> [u8; 4] {
         let idx = self.index();
-        [idx, 0, 0, 0]
+        [idx, 0, 0, self.version()]
     }
 
     pub(crate) fn try_to_vec(&self) -> Result<Vec<u8>, bincode::Error> {
-        bincode::serialize(self)
+        let mut data = bincode::serialize(self)?;
+        // bincode encodes the index as u32 LE, we only need to add the version
+        data[..4].copy_from_slice(&self.discriminant());
+        Ok(data)
+    }
+
+    /// Decodes an instruction encoded with any layout version of its
+    /// arguments that was released so far
+    pub(crate) fn try_from_slice(
+        data: &[u8],
+    ) -> Result<Self, InstructionError> {
+        let Some(&[index, 0, 0, version]) = data.first_chunk::<4>() else {
+            return Err(InstructionError::InvalidInstructionData);
+        };
+        let latest_version = INSTRUCTION_VERSIONS
+            .get(index as usize)
+            .copied()
+            .ok_or(InstructionError::InvalidInstructionData)?;
+        if version == latest_version {
+            if version == INITIAL_INSTRUCTION_VERSION {
+                return limited_deserialize(data);
+            }
+            // bincode expects the plain index as discriminator
+            let mut data = data.to_vec();
+            data[3] = 0;
+            return limited_deserialize(&data);
+        }
+        // Previous layouts are converted into the latest one
+        match (index, version) {
+            (0, 0) => Ok(Self::ModifyAccounts {
+                accounts: limited_deserialize(&data[4..])?,
+                reason: MutationReason::Unspecified,
+            }),
+            (1, 0) => Ok(Self::ScheduleCommit {
+                priority: CommitPriority::Normal,
+            }),
+            (2, 0) => Ok(Self::ScheduleCommitAndUndelegate {
+                priority: CommitPriority::Normal,
+            }),
+            _ => Err(InstructionError::InvalidInstructionData),
+        }
     }
 }
 
@@ -190,14 +706,16 @@ impl MagicBlockInstruction {
 // -----------------
 pub fn modify_accounts(
     account_modifications: Vec<AccountModification>,
+    reason: MutationReason,
     recent_blockhash: Hash,
 ) -> Transaction {
-    let ix = modify_accounts_instruction(account_modifications);
+    let ix = modify_accounts_instruction(account_modifications, reason);
     into_transaction(&validator_authority(), ix, recent_blockhash)
 }
 
 pub fn modify_accounts_instruction(
     account_modifications: Vec<AccountModification>,
+    reason: MutationReason,
 ) -> Instruction {
     let mut account_metas =
         vec![AccountMeta::new(validator_authority_id(), true)];
@@ -216,9 +734,11 @@ pub fn modify_accounts_instruction(
         account_mods
             .insert(account_modification.pubkey, account_mod_for_instruction);
     }
-    Instruction::new_with_bincode(
-        crate::id(),
-        &MagicBlockInstruction::ModifyAccounts(account_mods),
+    into_instruction(
+        &MagicBlockInstruction::ModifyAccounts {
+            accounts: account_mods,
+            reason,
+        },
         account_metas,
     )
 }
@@ -246,9 +766,10 @@ pub(crate) fn schedule_commit_instruction(
     for pubkey in &pdas {
         account_metas.push(AccountMeta::new_readonly(*pubkey, true));
     }
-    Instruction::new_with_bincode(
-        crate::id(),
-        &MagicBlockInstruction::ScheduleCommit,
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommit {
+            priority: CommitPriority::Normal,
+        },
         account_metas,
     )
 }
@@ -277,9 +798,478 @@ pub(crate) fn schedule_commit_and_undelegate_instruction(
     for pubkey in &pdas {
         account_metas.push(AccountMeta::new_readonly(*pubkey, true));
     }
-    Instruction::new_with_bincode(
-        crate::id(),
-        &MagicBlockInstruction::ScheduleCommitAndUndelegate,
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitAndUndelegate {
+            priority: CommitPriority::Normal,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Commit with Priority
+// -----------------
+pub fn schedule_commit_with_priority(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    priority: CommitPriority,
+    request_undelegation: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_with_priority_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        priority,
+        request_undelegation,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_with_priority_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    priority: CommitPriority,
+    request_undelegation: bool,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    let instruction = if request_undelegation {
+        MagicBlockInstruction::ScheduleCommitAndUndelegate { priority }
+    } else {
+        MagicBlockInstruction::ScheduleCommit { priority }
+    };
+    into_instruction(&instruction, account_metas)
+}
+
+// -----------------
+// Schedule Commit, Undelegate and Close
+// -----------------
+pub fn schedule_commit_undelegate_and_close(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_undelegate_and_close_instruction(
+        &payer.pubkey(),
+        pubkeys,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_undelegate_and_close_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitUndelegateAndClose,
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Transfer Intent
+// -----------------
+pub fn schedule_transfer_intent(
+    payer: &Keypair,
+    escrow: Pubkey,
+    intent: TransferIntent,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix =
+        schedule_transfer_intent_instruction(&payer.pubkey(), escrow, intent);
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_transfer_intent_instruction(
+    payer: &Pubkey,
+    escrow: Pubkey,
+    intent: TransferIntent,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+        AccountMeta::new(escrow, true),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::ScheduleTransferIntent(intent),
+        account_metas,
+    )
+}
+
+// -----------------
+// Settle Transfer Intent
+// -----------------
+pub fn settle_transfer_intent(
+    commit_id: u64,
+    escrow: Pubkey,
+    paid_out: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = settle_transfer_intent_instruction(commit_id, escrow, paid_out);
+    into_transaction(&validator_authority(), ix, recent_blockhash)
+}
+
+pub(crate) fn settle_transfer_intent_instruction(
+    commit_id: u64,
+    escrow: Pubkey,
+    paid_out: bool,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new(validator_authority_id(), true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+        AccountMeta::new(escrow, false),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::SettleTransferIntent {
+            commit_id,
+            paid_out,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Commit and Undelegate with Action
+// -----------------
+pub fn schedule_commit_and_undelegate_with_action(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    action: BaseLayerAction,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_and_undelegate_with_action_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        action,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_and_undelegate_with_action_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    action: BaseLayerAction,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitAndUndelegateWithAction(action),
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Commit with Compute Unit Price
+// -----------------
+pub fn schedule_commit_with_compute_unit_price(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    compute_unit_price: u64,
+    request_undelegation: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_with_compute_unit_price_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        compute_unit_price,
+        request_undelegation,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_with_compute_unit_price_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    compute_unit_price: u64,
+    request_undelegation: bool,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitWithComputeUnitPrice {
+            compute_unit_price,
+            request_undelegation,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Atomic Commit
+// -----------------
+pub fn schedule_atomic_commit(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    request_undelegation: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_atomic_commit_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        request_undelegation,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_atomic_commit_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    request_undelegation: bool,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleAtomicCommit {
+            request_undelegation,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Commit with Memo
+// -----------------
+pub fn schedule_commit_with_memo(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    memo: String,
+    request_undelegation: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_with_memo_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        memo,
+        request_undelegation,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_with_memo_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    memo: String,
+    request_undelegation: bool,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitWithMemo {
+            memo,
+            request_undelegation,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Commit With Authority Records
+// -----------------
+/// Each of the `pdas` is paired with the address of the authority record
+/// authorizing the invoking program to commit it
+pub fn schedule_commit_with_authority_records(
+    payer: &Keypair,
+    pdas: Vec<(Pubkey, Pubkey)>,
+    request_undelegation: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_with_authority_records_instruction(
+        &payer.pubkey(),
+        pdas,
+        request_undelegation,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_with_authority_records_instruction(
+    payer: &Pubkey,
+    pdas: Vec<(Pubkey, Pubkey)>,
+    request_undelegation: bool,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    // The invoking program cannot sign for accounts it does not own
+    for (pubkey, _) in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, false));
+    }
+    for (_, record) in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*record, false));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitWithAuthorityRecords {
+            request_undelegation,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Schedule Commit At
+// -----------------
+pub fn schedule_commit_at(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    target: CommitTarget,
+    request_undelegation: bool,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = schedule_commit_at_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        target,
+        request_undelegation,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn schedule_commit_at_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    target: CommitTarget,
+    request_undelegation: bool,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommitAt {
+            target,
+            request_undelegation,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Register Recurring Commit
+// -----------------
+pub fn register_recurring_commit(
+    payer: &Keypair,
+    pubkeys: Vec<Pubkey>,
+    interval_slots: u64,
+    end: RecurringCommitEnd,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = register_recurring_commit_instruction(
+        &payer.pubkey(),
+        pubkeys,
+        interval_slots,
+        end,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn register_recurring_commit_instruction(
+    payer: &Pubkey,
+    pdas: Vec<Pubkey>,
+    interval_slots: u64,
+    end: RecurringCommitEnd,
+) -> Instruction {
+    let mut account_metas = vec![
+        AccountMeta::new(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    for pubkey in &pdas {
+        account_metas.push(AccountMeta::new_readonly(*pubkey, true));
+    }
+    into_instruction(
+        &MagicBlockInstruction::RegisterRecurringCommit {
+            interval_slots,
+            end,
+        },
+        account_metas,
+    )
+}
+
+// -----------------
+// Cancel Scheduled Commit
+// -----------------
+pub fn cancel_scheduled_commit(
+    payer: &Keypair,
+    commit_id: u64,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = cancel_scheduled_commit_instruction(&payer.pubkey(), commit_id);
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn cancel_scheduled_commit_instruction(
+    payer: &Pubkey,
+    commit_id: u64,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new_readonly(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::CancelScheduledCommit(commit_id),
+        account_metas,
+    )
+}
+
+// -----------------
+// Cancel Recurring Commit
+// -----------------
+pub fn cancel_recurring_commit(
+    payer: &Keypair,
+    recurring_commit_id: u64,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = cancel_recurring_commit_instruction(
+        &payer.pubkey(),
+        recurring_commit_id,
+    );
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub(crate) fn cancel_recurring_commit_instruction(
+    payer: &Pubkey,
+    recurring_commit_id: u64,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new_readonly(*payer, true),
+        AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::CancelRecurringCommit(recurring_commit_id),
         account_metas,
     )
 }
@@ -294,11 +1284,10 @@ pub fn accept_scheduled_commits(recent_blockhash: Hash) -> Transaction {
 
 pub(crate) fn accept_scheduled_commits_instruction() -> Instruction {
     let account_metas = vec![
-        AccountMeta::new_readonly(validator_authority_id(), true),
+        AccountMeta::new(validator_authority_id(), true),
         AccountMeta::new(MAGIC_CONTEXT_PUBKEY, false),
     ];
-    Instruction::new_with_bincode(
-        crate::id(),
+    into_instruction(
         &MagicBlockInstruction::AcceptScheduleCommits,
         account_metas,
     )
@@ -328,9 +1317,122 @@ pub(crate) fn scheduled_commit_sent_instruction(
         AccountMeta::new_readonly(*magic_block_program, false),
         AccountMeta::new_readonly(*validator_authority, true),
     ];
-    Instruction::new_with_bincode(
-        *magic_block_program,
-        &MagicBlockInstruction::ScheduledCommitSent(scheduled_commit_id),
+    let data = MagicBlockInstruction::ScheduledCommitSent(scheduled_commit_id)
+        .try_to_vec()
+        .expect("magic program instructions are always serializable");
+    Instruction::new_with_bytes(*magic_block_program, &data, account_metas)
+}
+
+// -----------------
+// Record Commit Event
+// -----------------
+pub fn record_commit_event(
+    record: CommitRecord,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = record_commit_event_instruction(record);
+    into_transaction(&validator_authority(), ix, recent_blockhash)
+}
+
+pub(crate) fn record_commit_event_instruction(
+    record: CommitRecord,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new_readonly(validator_authority_id(), true),
+        AccountMeta::new(MAGIC_COMMIT_EVENTS_PUBKEY, false),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::RecordCommitEvent(record),
+        account_metas,
+    )
+}
+
+// -----------------
+// Get Undelegation Status
+// -----------------
+pub fn get_undelegation_status(
+    payer: &Keypair,
+    pubkey: Pubkey,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = get_undelegation_status_instruction(pubkey);
+    into_transaction(payer, ix, recent_blockhash)
+}
+
+pub fn get_undelegation_status_instruction(pubkey: Pubkey) -> Instruction {
+    let account_metas =
+        vec![AccountMeta::new_readonly(MAGIC_COMMIT_EVENTS_PUBKEY, false)];
+    into_instruction(
+        &MagicBlockInstruction::GetUndelegationStatus(pubkey),
+        account_metas,
+    )
+}
+
+// -----------------
+// Session Keys
+// -----------------
+pub fn register_session_key(
+    authority: &Keypair,
+    session_key: &Keypair,
+    expires_at_slot: Slot,
+    programs: Vec<Pubkey>,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = register_session_key_instruction(
+        &authority.pubkey(),
+        &session_key.pubkey(),
+        expires_at_slot,
+        programs,
+    );
+    Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&authority.pubkey()),
+        &[authority, session_key, &validator_authority()],
+        recent_blockhash,
+    )
+}
+
+/// Registers the session key sponsored by the validator authority
+pub fn register_session_key_instruction(
+    authority: &Pubkey,
+    session_key: &Pubkey,
+    expires_at_slot: Slot,
+    programs: Vec<Pubkey>,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*session_key, true),
+        AccountMeta::new(MAGIC_SESSION_KEYS_PUBKEY, false),
+        AccountMeta::new(validator_authority_id(), true),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::RegisterSessionKey {
+            expires_at_slot,
+            programs,
+        },
+        account_metas,
+    )
+}
+
+pub fn revoke_session_key(
+    signer: &Keypair,
+    session_key: Pubkey,
+    recent_blockhash: Hash,
+) -> Transaction {
+    let ix = revoke_session_key_instruction(&signer.pubkey(), session_key);
+    into_transaction(signer, ix, recent_blockhash)
+}
+
+pub fn revoke_session_key_instruction(
+    signer: &Pubkey,
+    session_key: Pubkey,
+) -> Instruction {
+    let account_metas = vec![
+        AccountMeta::new_readonly(*signer, true),
+        AccountMeta::new(MAGIC_SESSION_KEYS_PUBKEY, false),
+    ];
+    into_instruction(
+        &MagicBlockInstruction::RevokeSessionKey(session_key),
         account_metas,
     )
 }
@@ -338,6 +1440,16 @@ pub(crate) fn scheduled_commit_sent_instruction(
 // -----------------
 // Utils
 // -----------------
+pub(crate) fn into_instruction(
+    instruction: &MagicBlockInstruction,
+    account_metas: Vec<AccountMeta>,
+) -> Instruction {
+    let data = instruction
+        .try_to_vec()
+        .expect("magic program instructions are always serializable");
+    Instruction::new_with_bytes(crate::id(), &data, account_metas)
+}
+
 pub(crate) fn into_transaction(
     signer: &Keypair,
     instruction: Instruction,
@@ -351,3 +1463,268 @@ pub(crate) fn into_transaction(
         recent_blockhash,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use magicblock_core::commit_record::CommitOutcome;
+
+    use super::*;
+
+    /// One instance of each instruction together with the discriminator of
+    /// its latest layout, these must never change once released
+    fn released_instructions() -> Vec<(MagicBlockInstruction, [u8; 4])> {
+        use MagicBlockInstruction::*;
+        let record = CommitRecord {
+            commit_id: 1,
+            slot: 2,
+            payer: Pubkey::new_unique(),
+            included_pubkeys: vec![Pubkey::new_unique()],
+            excluded_pubkeys: vec![],
+            requested_undelegation: false,
+            chain_signatures: vec![],
+            outcome: CommitOutcome::Pending,
+        };
+        vec![
+            (
+                ModifyAccounts {
+                    accounts: HashMap::new(),
+                    reason: MutationReason::CloneAccount,
+                },
+                [0, 0, 0, 1],
+            ),
+            (
+                ScheduleCommit {
+                    priority: CommitPriority::High,
+                },
+                [1, 0, 0, 1],
+            ),
+            (
+                ScheduleCommitAndUndelegate {
+                    priority: CommitPriority::Low,
+                },
+                [2, 0, 0, 1],
+            ),
+            (AcceptScheduleCommits, [3, 0, 0, 0]),
+            (ScheduledCommitSent(1), [4, 0, 0, 0]),
+            (
+                ScheduleCommitAt {
+                    target: CommitTarget::Delay(10),
+                    request_undelegation: false,
+                },
+                [5, 0, 0, 0],
+            ),
+            (
+                RegisterRecurringCommit {
+                    interval_slots: 10,
+                    end: RecurringCommitEnd::AfterCommits(3),
+                },
+                [6, 0, 0, 0],
+            ),
+            (CancelScheduledCommit(1), [7, 0, 0, 0]),
+            (
+                ScheduleCommitAndUndelegateWithAction(BaseLayerAction {
+                    program_id: Pubkey::new_unique(),
+                    accounts: vec![BaseLayerActionAccount {
+                        pubkey: Pubkey::new_unique(),
+                        is_writable: true,
+                    }],
+                    data: vec![1, 2, 3],
+                }),
+                [8, 0, 0, 0],
+            ),
+            (
+                ScheduleCommitWithComputeUnitPrice {
+                    compute_unit_price: 1_000,
+                    request_undelegation: true,
+                },
+                [9, 0, 0, 0],
+            ),
+            (ScheduleCommitUndelegateAndClose, [10, 0, 0, 0]),
+            (
+                ScheduleTransferIntent(TransferIntent {
+                    recipient: Pubkey::new_unique(),
+                    lamports: 1_000,
+                }),
+                [11, 0, 0, 0],
+            ),
+            (RecordCommitEvent(record), [12, 0, 0, 0]),
+            (
+                ScheduleAtomicCommit {
+                    request_undelegation: false,
+                },
+                [13, 0, 0, 0],
+            ),
+            (
+                ScheduleCommitWithMemo {
+                    memo: "match-1".to_string(),
+                    request_undelegation: false,
+                },
+                [14, 0, 0, 0],
+            ),
+            (GetUndelegationStatus(Pubkey::new_unique()), [15, 0, 0, 0]),
+            (
+                ScheduleCommitWithAuthorityRecords {
+                    request_undelegation: true,
+                },
+                [16, 0, 0, 0],
+            ),
+            (
+                RegisterSessionKey {
+                    expires_at_slot: 1_000,
+                    programs: vec![Pubkey::new_unique()],
+                },
+                [17, 0, 0, 0],
+            ),
+            (RevokeSessionKey(Pubkey::new_unique()), [18, 0, 0, 0]),
+            (CancelRecurringCommit(1), [19, 0, 0, 0]),
+            (
+                SettleTransferIntent {
+                    commit_id: 1,
+                    paid_out: true,
+                },
+                [20, 0, 0, 0],
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_discriminators_are_stable() {
+        let instructions = released_instructions();
+        assert_eq!(instructions.len(), INSTRUCTION_VERSIONS.len());
+        for (ix, discriminator) in instructions {
+            let data = ix.try_to_vec().unwrap();
+            assert_eq!(data[..4], discriminator, "{:?}", ix);
+            assert_eq!(ix.discriminant(), discriminator, "{:?}", ix);
+        }
+    }
+
+    #[test]
+    fn test_instructions_roundtrip() {
+        for (ix, _) in released_instructions() {
+            let data = ix.try_to_vec().unwrap();
+            assert_eq!(
+                MagicBlockInstruction::try_from_slice(&data).unwrap(),
+                ix
+            );
+        }
+    }
+
+    #[test]
+    fn test_initial_layouts_match_plain_bincode() {
+        // Deployed programs encode the instructions with plain bincode
+        for (ix, _) in released_instructions()
+            .into_iter()
+            .filter(|(ix, _)| ix.version() == INITIAL_INSTRUCTION_VERSION)
+        {
+            let data = bincode::serialize(&ix).unwrap();
+            assert_eq!(ix.try_to_vec().unwrap(), data, "{:?}", ix);
+            assert_eq!(
+                MagicBlockInstruction::try_from_slice(&data).unwrap(),
+                ix
+            );
+        }
+    }
+
+    #[test]
+    fn test_decode_released_layouts() {
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[
+                5, 0, 0, 0, // ScheduleCommitAt
+                1, 0, 0, 0, // CommitTarget::Slot
+                42, 0, 0, 0, 0, 0, 0, 0, // slot
+                1, // request_undelegation
+            ])
+            .unwrap(),
+            MagicBlockInstruction::ScheduleCommitAt {
+                target: CommitTarget::Slot(42),
+                request_undelegation: true,
+            }
+        );
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[
+                9, 0, 0, 0, // ScheduleCommitWithComputeUnitPrice
+                232, 3, 0, 0, 0, 0, 0, 0, // compute_unit_price
+                0, // request_undelegation
+            ])
+            .unwrap(),
+            MagicBlockInstruction::ScheduleCommitWithComputeUnitPrice {
+                compute_unit_price: 1_000,
+                request_undelegation: false,
+            }
+        );
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[
+                14, 0, 0, 0, // ScheduleCommitWithMemo
+                2, 0, 0, 0, 0, 0, 0, 0, b'a', b'b', // memo
+                1,    // request_undelegation
+            ])
+            .unwrap(),
+            MagicBlockInstruction::ScheduleCommitWithMemo {
+                memo: "ab".to_string(),
+                request_undelegation: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_previous_layouts() {
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[
+                0, 0, 0, 0, // ModifyAccounts
+                0, 0, 0, 0, 0, 0, 0, 0, // no accounts
+            ])
+            .unwrap(),
+            MagicBlockInstruction::ModifyAccounts {
+                accounts: HashMap::new(),
+                reason: MutationReason::Unspecified,
+            }
+        );
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[1, 0, 0, 0]).unwrap(),
+            MagicBlockInstruction::ScheduleCommit {
+                priority: CommitPriority::Normal,
+            }
+        );
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[2, 0, 0, 0]).unwrap(),
+            MagicBlockInstruction::ScheduleCommitAndUndelegate {
+                priority: CommitPriority::Normal,
+            }
+        );
+        assert_eq!(
+            MagicBlockInstruction::try_from_slice(&[
+                1, 0, 0, 1, // ScheduleCommit v1
+                2, 0, 0, 0, // CommitPriority::High
+            ])
+            .unwrap(),
+            MagicBlockInstruction::ScheduleCommit {
+                priority: CommitPriority::High,
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_instructions_fails() {
+        for data in [
+            // Too short for the discriminator
+            vec![1, 0],
+            // Unknown instruction
+            vec![INSTRUCTION_VERSIONS.len() as u8, 0, 0, 0],
+            // Unreleased layout version
+            vec![1, 0, 0, 2],
+            // Missing arguments of the latest layout
+            vec![1, 0, 0, 1],
+            // Reserved discriminator bytes
+            vec![1, 1, 0, 0],
+            // Missing arguments
+            vec![7, 0, 0, 0, 1],
+        ] {
+            assert_eq!(
+                MagicBlockInstruction::try_from_slice(&data),
+                Err(InstructionError::InvalidInstructionData),
+                "{:?}",
+                data
+            );
+        }
+    }
+}
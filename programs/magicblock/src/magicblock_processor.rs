@@ -1,15 +1,17 @@
-use solana_program_runtime::declare_process_instruction;
-use solana_sdk::program_utils::limited_deserialize;
-
 use crate::{
-    magicblock_instruction::MagicBlockInstruction,
+    magicblock_instruction::{CommitPriority, MagicBlockInstruction},
     mutate_accounts::process_mutate_accounts,
     process_scheduled_commit_sent,
     schedule_transactions::{
-        process_accept_scheduled_commits, process_schedule_commit,
-        ProcessScheduleCommitOptions,
+        process_accept_scheduled_commits, process_cancel_recurring_commit,
+        process_cancel_scheduled_commit, process_get_undelegation_status,
+        process_record_commit_event, process_register_recurring_commit,
+        process_schedule_commit, process_schedule_transfer_intent,
+        process_settle_transfer_intent, ProcessScheduleCommitOptions,
     },
+    session_keys::{process_register_session_key, process_revoke_session_key},
 };
+use solana_program_runtime::declare_process_instruction;
 
 pub const DEFAULT_COMPUTE_UNITS: u64 = 150;
 
@@ -21,34 +23,199 @@ declare_process_instruction!(
         let instruction_context =
             transaction_context.get_current_instruction_context()?;
         let instruction_data = instruction_context.get_instruction_data();
-        let instruction = limited_deserialize(instruction_data)?;
+        let instruction =
+            MagicBlockInstruction::try_from_slice(instruction_data)?;
         let signers = instruction_context.get_signers(transaction_context)?;
 
         match instruction {
-            MagicBlockInstruction::ModifyAccounts(mut account_mods) => {
-                process_mutate_accounts(
+            MagicBlockInstruction::ModifyAccounts {
+                mut accounts,
+                reason,
+            } => process_mutate_accounts(
+                signers,
+                invoke_context,
+                transaction_context,
+                &mut accounts,
+                reason,
+            ),
+            MagicBlockInstruction::ScheduleCommit { priority } => {
+                process_schedule_commit(
                     signers,
                     invoke_context,
-                    transaction_context,
-                    &mut account_mods,
+                    ProcessScheduleCommitOptions {
+                        request_undelegation: false,
+                        target: None,
+                        base_layer_action: None,
+                        compute_unit_price: None,
+                        close_accounts: false,
+                        atomic: false,
+                        memo: None,
+                        priority,
+                        authority_records: false,
+                    },
                 )
             }
-            MagicBlockInstruction::ScheduleCommit => process_schedule_commit(
+            MagicBlockInstruction::ScheduleCommitAndUndelegate { priority } => {
+                process_schedule_commit(
+                    signers,
+                    invoke_context,
+                    ProcessScheduleCommitOptions {
+                        request_undelegation: true,
+                        target: None,
+                        base_layer_action: None,
+                        compute_unit_price: None,
+                        close_accounts: false,
+                        atomic: false,
+                        memo: None,
+                        priority,
+                        authority_records: false,
+                    },
+                )
+            }
+            MagicBlockInstruction::ScheduleCommitAt {
+                target,
+                request_undelegation,
+            } => process_schedule_commit(
+                signers,
+                invoke_context,
+                ProcessScheduleCommitOptions {
+                    request_undelegation,
+                    target: Some(target),
+                    base_layer_action: None,
+                    compute_unit_price: None,
+                    close_accounts: false,
+                    atomic: false,
+                    memo: None,
+                    priority: CommitPriority::Normal,
+                    authority_records: false,
+                },
+            ),
+            MagicBlockInstruction::ScheduleCommitAndUndelegateWithAction(
+                action,
+            ) => process_schedule_commit(
                 signers,
                 invoke_context,
                 ProcessScheduleCommitOptions {
-                    request_undelegation: false,
+                    request_undelegation: true,
+                    target: None,
+                    base_layer_action: Some(action),
+                    compute_unit_price: None,
+                    close_accounts: false,
+                    atomic: false,
+                    memo: None,
+                    priority: CommitPriority::Normal,
+                    authority_records: false,
                 },
             ),
-            MagicBlockInstruction::ScheduleCommitAndUndelegate => {
+            MagicBlockInstruction::ScheduleCommitWithComputeUnitPrice {
+                compute_unit_price,
+                request_undelegation,
+            } => process_schedule_commit(
+                signers,
+                invoke_context,
+                ProcessScheduleCommitOptions {
+                    request_undelegation,
+                    target: None,
+                    base_layer_action: None,
+                    compute_unit_price: Some(compute_unit_price),
+                    close_accounts: false,
+                    atomic: false,
+                    memo: None,
+                    priority: CommitPriority::Normal,
+                    authority_records: false,
+                },
+            ),
+            MagicBlockInstruction::ScheduleCommitUndelegateAndClose => {
                 process_schedule_commit(
                     signers,
                     invoke_context,
                     ProcessScheduleCommitOptions {
                         request_undelegation: true,
+                        target: None,
+                        base_layer_action: None,
+                        compute_unit_price: None,
+                        close_accounts: true,
+                        atomic: false,
+                        memo: None,
+                        priority: CommitPriority::Normal,
+                        authority_records: false,
                     },
                 )
             }
+            MagicBlockInstruction::ScheduleAtomicCommit {
+                request_undelegation,
+            } => process_schedule_commit(
+                signers,
+                invoke_context,
+                ProcessScheduleCommitOptions {
+                    request_undelegation,
+                    target: None,
+                    base_layer_action: None,
+                    compute_unit_price: None,
+                    close_accounts: false,
+                    atomic: true,
+                    memo: None,
+                    priority: CommitPriority::Normal,
+                    authority_records: false,
+                },
+            ),
+            MagicBlockInstruction::ScheduleCommitWithMemo {
+                memo,
+                request_undelegation,
+            } => process_schedule_commit(
+                signers,
+                invoke_context,
+                ProcessScheduleCommitOptions {
+                    request_undelegation,
+                    target: None,
+                    base_layer_action: None,
+                    compute_unit_price: None,
+                    close_accounts: false,
+                    atomic: false,
+                    memo: Some(memo),
+                    priority: CommitPriority::Normal,
+                    authority_records: false,
+                },
+            ),
+            MagicBlockInstruction::ScheduleCommitWithAuthorityRecords {
+                request_undelegation,
+            } => process_schedule_commit(
+                signers,
+                invoke_context,
+                ProcessScheduleCommitOptions {
+                    request_undelegation,
+                    target: None,
+                    base_layer_action: None,
+                    compute_unit_price: None,
+                    close_accounts: false,
+                    atomic: false,
+                    memo: None,
+                    priority: CommitPriority::Normal,
+                    authority_records: true,
+                },
+            ),
+            MagicBlockInstruction::ScheduleTransferIntent(intent) => {
+                process_schedule_transfer_intent(
+                    signers,
+                    invoke_context,
+                    intent,
+                )
+            }
+            MagicBlockInstruction::RegisterRecurringCommit {
+                interval_slots,
+                end,
+            } => process_register_recurring_commit(
+                signers,
+                invoke_context,
+                interval_slots,
+                end,
+            ),
+            MagicBlockInstruction::CancelScheduledCommit(id) => {
+                process_cancel_scheduled_commit(signers, invoke_context, id)
+            }
+            MagicBlockInstruction::CancelRecurringCommit(id) => {
+                process_cancel_recurring_commit(signers, invoke_context, id)
+            }
             MagicBlockInstruction::AcceptScheduleCommits => {
                 process_accept_scheduled_commits(signers, invoke_context)
             }
@@ -60,6 +227,33 @@ declare_process_instruction!(
                     id,
                 )
             }
+            MagicBlockInstruction::SettleTransferIntent {
+                commit_id,
+                paid_out,
+            } => process_settle_transfer_intent(
+                signers,
+                invoke_context,
+                commit_id,
+                paid_out,
+            ),
+            MagicBlockInstruction::RecordCommitEvent(record) => {
+                process_record_commit_event(signers, invoke_context, record)
+            }
+            MagicBlockInstruction::GetUndelegationStatus(pubkey) => {
+                process_get_undelegation_status(invoke_context, pubkey)
+            }
+            MagicBlockInstruction::RegisterSessionKey {
+                expires_at_slot,
+                programs,
+            } => process_register_session_key(
+                signers,
+                invoke_context,
+                expires_at_slot,
+                programs,
+            ),
+            MagicBlockInstruction::RevokeSessionKey(session_key) => {
+                process_revoke_session_key(signers, invoke_context, session_key)
+            }
         }
     }
 );
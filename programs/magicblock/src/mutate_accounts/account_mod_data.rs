@@ -8,7 +8,9 @@ use std::{
 };
 
 use lazy_static::lazy_static;
-use magicblock_core::traits::PersistsAccountModData;
+use magicblock_core::{
+    mutation_audit::MutationAuditEntry, traits::PersistsAccountModData,
+};
 use solana_log_collector::ic_msg;
 use solana_program_runtime::invoke_context::InvokeContext;
 
@@ -124,6 +126,17 @@ fn persist_data(
         .persist(id, data)
 }
 
+pub(super) fn persist_mutation_audit_entry(
+    entry: &MutationAuditEntry,
+) -> Result<(), Box<dyn std::error::Error>> {
+    PERSISTER
+        .read()
+        .expect("PERSISTER poisoned")
+        .as_ref()
+        .ok_or("AccounModPersister needs to be set on startup")?
+        .persist_mutation_audit_entry(entry)
+}
+
 /// The resolved data including an indication about how it was resolved.
 pub(super) enum ResolvedAccountModData {
     /// The data was resolved from memory while the validator was processing
@@ -1,5 +1,9 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicU64, Ordering},
+};
 
+use magicblock_core::mutation_audit::{MutationAuditEntry, MutationReason};
 use solana_log_collector::ic_msg;
 use solana_program_runtime::invoke_context::InvokeContext;
 use solana_sdk::{
@@ -14,15 +18,24 @@ use crate::{
     magicblock_instruction::{
         AccountModificationForInstruction, MagicBlockProgramError,
     },
-    mutate_accounts::account_mod_data::resolve_account_mod_data,
-    validator::validator_authority_id,
+    mutate_accounts::account_mod_data::{
+        persist_mutation_audit_entry, resolve_account_mod_data,
+    },
+    validator::{self, validator_authority_id},
 };
 
+/// Sequence number of the next audit entry, together with the slot it keys
+/// the entries in the ledger.
+/// It restarts with the validator, which is fine since the slots it records
+/// entries for afterwards are past the ones recorded before.
+static MUTATION_AUDIT_SEQ: AtomicU64 = AtomicU64::new(0);
+
 pub(crate) fn process_mutate_accounts(
     signers: HashSet<Pubkey>,
     invoke_context: &InvokeContext,
     transaction_context: &TransactionContext,
     account_mods: &mut HashMap<Pubkey, AccountModificationForInstruction>,
+    reason: MutationReason,
 ) -> Result<(), InstructionError> {
     let instruction_context =
         transaction_context.get_current_instruction_context()?;
@@ -102,6 +115,7 @@ pub(crate) fn process_mutate_accounts(
 
     // 2. Apply account modifications
     let mut memory_data_mods = Vec::new();
+    let mut modified_accounts = Vec::new();
     for idx in 0..account_mods_len {
         // NOTE: first account is the MagicBlock authority, account mods start at second account
         let account_idx = (idx + 1) as u16;
@@ -126,6 +140,7 @@ pub(crate) fn process_mutate_accounts(
             "MutateAccounts: modifying '{}'.",
             account_key,
         );
+        modified_accounts.push(*account_key);
 
         if let Some(lamports) = modification.lamports {
             ic_msg!(
@@ -261,17 +276,63 @@ pub(crate) fn process_mutate_accounts(
             })?;
     }
 
+    // Lastly record the modification in the audit trail.
+    // NOTE: during ledger replay the entries were recorded already
+    if !validator::is_starting_up() {
+        record_mutation_audit_entry(invoke_context, modified_accounts, reason)?;
+    }
+
     Ok(())
 }
 
+fn record_mutation_audit_entry(
+    invoke_context: &InvokeContext,
+    accounts: Vec<Pubkey>,
+    reason: MutationReason,
+) -> Result<(), InstructionError> {
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+    let entry = MutationAuditEntry {
+        seq: MUTATION_AUDIT_SEQ.fetch_add(1, Ordering::Relaxed),
+        slot: clock.slot,
+        authority: validator_authority_id(),
+        reason,
+        accounts,
+    };
+    ic_msg!(
+        invoke_context,
+        "MutateAccounts: recording audit entry {} with reason {:?}",
+        entry.seq,
+        entry.reason
+    );
+    persist_mutation_audit_entry(&entry).map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "MutateAccounts: failed to persist mutation audit entry: {}",
+            err.to_string()
+        );
+        MagicBlockProgramError::FailedToPersistMutationAuditEntry.into()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
     use assert_matches::assert_matches;
     use solana_sdk::{
-        account::{Account, AccountSharedData},
+        account::{
+            create_account_shared_data_for_test, Account, AccountSharedData,
+        },
+        clock,
         pubkey::Pubkey,
+        sysvar::SysvarId,
     };
     use test_tools_core::init_logger;
 
@@ -285,6 +346,13 @@ mod tests {
         },
     };
 
+    fn clock_sysvar_account() -> (Pubkey, AccountSharedData) {
+        (
+            clock::Clock::id(),
+            create_account_shared_data_for_test(&clock::Clock::default()),
+        )
+    }
+
     // -----------------
     // ModifyAccounts
     // -----------------
@@ -309,8 +377,11 @@ mod tests {
             data: Some(vec![1, 2, 3, 4, 5]),
             rent_epoch: Some(88),
         };
-        let ix = modify_accounts_instruction(vec![modification.clone()]);
-        let transaction_accounts = ix
+        let ix = modify_accounts_instruction(
+            vec![modification.clone()],
+            MutationReason::CloneAccount,
+        );
+        let mut transaction_accounts: Vec<_> = ix
             .accounts
             .iter()
             .flat_map(|acc| {
@@ -319,6 +390,8 @@ mod tests {
                     .map(|shared_data| (acc.pubkey, shared_data))
             })
             .collect();
+        // Needed to record the audit entry
+        transaction_accounts.push(clock_sysvar_account());
 
         let mut accounts = process_instruction(
             ix.data.as_slice(),
@@ -326,6 +399,8 @@ mod tests {
             ix.accounts,
             Ok(()),
         );
+        // Drop the clock sysvar
+        accounts.pop();
 
         assert_eq!(accounts.len(), 2);
 
@@ -376,19 +451,22 @@ mod tests {
         };
         ensure_started_validator(&mut account_data);
 
-        let ix = modify_accounts_instruction(vec![
-            AccountModification {
-                pubkey: mod_key1,
-                lamports: Some(300),
-                ..AccountModification::default()
-            },
-            AccountModification {
-                pubkey: mod_key2,
-                lamports: Some(400),
-                ..AccountModification::default()
-            },
-        ]);
-        let transaction_accounts = ix
+        let ix = modify_accounts_instruction(
+            vec![
+                AccountModification {
+                    pubkey: mod_key1,
+                    lamports: Some(300),
+                    ..AccountModification::default()
+                },
+                AccountModification {
+                    pubkey: mod_key2,
+                    lamports: Some(400),
+                    ..AccountModification::default()
+                },
+            ],
+            MutationReason::CloneAccount,
+        );
+        let mut transaction_accounts: Vec<_> = ix
             .accounts
             .iter()
             .flat_map(|acc| {
@@ -397,6 +475,8 @@ mod tests {
                     .map(|shared_data| (acc.pubkey, shared_data))
             })
             .collect();
+        // Needed to record the audit entry
+        transaction_accounts.push(clock_sysvar_account());
 
         let mut accounts = process_instruction(
             ix.data.as_slice(),
@@ -404,6 +484,8 @@ mod tests {
             ix.accounts,
             Ok(()),
         );
+        // Drop the clock sysvar
+        accounts.pop();
 
         assert_eq!(accounts.len(), 3);
 
@@ -473,35 +555,38 @@ mod tests {
         };
         ensure_started_validator(&mut account_data);
 
-        let ix = modify_accounts_instruction(vec![
-            AccountModification {
-                pubkey: mod_key1,
-                lamports: Some(1000),
-                data: Some(vec![1, 2, 3, 4, 5]),
-                ..Default::default()
-            },
-            AccountModification {
-                pubkey: mod_key2,
-                owner: Some(mod_2_owner),
-                ..Default::default()
-            },
-            AccountModification {
-                pubkey: mod_key3,
-                lamports: Some(3000),
-                rent_epoch: Some(90),
-                ..Default::default()
-            },
-            AccountModification {
-                pubkey: mod_key4,
-                lamports: Some(100),
-                executable: Some(true),
-                data: Some(vec![16, 17, 18, 19, 20]),
-                rent_epoch: Some(91),
-                ..Default::default()
-            },
-        ]);
-
-        let transaction_accounts = ix
+        let ix = modify_accounts_instruction(
+            vec![
+                AccountModification {
+                    pubkey: mod_key1,
+                    lamports: Some(1000),
+                    data: Some(vec![1, 2, 3, 4, 5]),
+                    ..Default::default()
+                },
+                AccountModification {
+                    pubkey: mod_key2,
+                    owner: Some(mod_2_owner),
+                    ..Default::default()
+                },
+                AccountModification {
+                    pubkey: mod_key3,
+                    lamports: Some(3000),
+                    rent_epoch: Some(90),
+                    ..Default::default()
+                },
+                AccountModification {
+                    pubkey: mod_key4,
+                    lamports: Some(100),
+                    executable: Some(true),
+                    data: Some(vec![16, 17, 18, 19, 20]),
+                    rent_epoch: Some(91),
+                    ..Default::default()
+                },
+            ],
+            MutationReason::CloneAccount,
+        );
+
+        let mut transaction_accounts: Vec<_> = ix
             .accounts
             .iter()
             .flat_map(|acc| {
@@ -510,6 +595,8 @@ mod tests {
                     .map(|shared_data| (acc.pubkey, shared_data))
             })
             .collect();
+        // Needed to record the audit entry
+        transaction_accounts.push(clock_sysvar_account());
 
         let mut accounts = process_instruction(
             ix.data.as_slice(),
@@ -517,6 +604,8 @@ mod tests {
             ix.accounts,
             Ok(()),
         );
+        // Drop the clock sysvar
+        accounts.pop();
 
         let account_authority: Account =
             accounts.drain(0..1).next().unwrap().into();
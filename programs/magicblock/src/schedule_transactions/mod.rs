@@ -1,10 +1,20 @@
+mod process_cancel_recurring_commit;
+mod process_cancel_scheduled_commit;
+mod process_get_undelegation_status;
+mod process_record_commit_event;
 mod process_schedule_commit;
 mod process_scheduled_commit_sent;
+mod process_settle_transfer_intent;
 pub(crate) mod transaction_scheduler;
+pub(crate) use process_cancel_recurring_commit::*;
+pub(crate) use process_cancel_scheduled_commit::*;
+pub(crate) use process_get_undelegation_status::*;
+pub(crate) use process_record_commit_event::*;
 pub(crate) use process_schedule_commit::*;
 pub use process_scheduled_commit_sent::{
     process_scheduled_commit_sent, register_scheduled_commit_sent, SentCommit,
 };
+pub(crate) use process_settle_transfer_intent::*;
 
 #[cfg(test)]
 mod process_schedule_commit_tests;
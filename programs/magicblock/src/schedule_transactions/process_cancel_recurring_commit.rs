@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{instruction::InstructionError, pubkey::Pubkey};
+
+use crate::{
+    magic_context::MagicContext,
+    schedule_transactions::check_magic_context_id,
+    utils::accounts::{
+        get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
+    },
+};
+
+pub(crate) fn process_cancel_recurring_commit(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    recurring_commit_id: u64,
+) -> Result<(), InstructionError> {
+    const PAYER_IDX: u16 = 0;
+    const MAGIC_CONTEXT_IDX: u16 = PAYER_IDX + 1;
+
+    check_magic_context_id(invoke_context, MAGIC_CONTEXT_IDX)?;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // Assert Payer is signer
+    let payer_pubkey =
+        get_instruction_pubkey_with_idx(transaction_context, PAYER_IDX)?;
+    if !signers.contains(payer_pubkey) {
+        ic_msg!(
+            invoke_context,
+            "CancelRecurringCommit ERR: payer pubkey {} not in signers",
+            payer_pubkey
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+
+    let magic_context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    let mut magic_context = MagicContext::deserialize(
+        &magic_context_acc.borrow(),
+    )
+    .map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "Failed to deserialize MagicContext: {}",
+            err
+        );
+        InstructionError::InvalidAccountData
+    })?;
+
+    let Some(recurring_commit) = magic_context
+        .recurring_commits
+        .iter()
+        .find(|commit| commit.id == recurring_commit_id)
+    else {
+        ic_msg!(
+            invoke_context,
+            "CancelRecurringCommit ERR: no recurring commit with ID {}",
+            recurring_commit_id
+        );
+        return Err(InstructionError::InvalidArgument);
+    };
+    if !recurring_commit.payer.eq(payer_pubkey) {
+        ic_msg!(
+            invoke_context,
+            "CancelRecurringCommit ERR: recurring commit {} was registered by {} and can only be cancelled by it",
+            recurring_commit_id,
+            recurring_commit.payer
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+
+    magic_context.remove_recurring_commit(recurring_commit_id);
+    // Zero fill account before updating data, the context shrinks and we
+    // don't want to leave the removed commit behind
+    magic_context_acc
+        .borrow_mut()
+        .set_data_from_slice(&MagicContext::ZERO);
+    magic_context
+        .store(&mut magic_context_acc.borrow_mut())
+        .map_err(|err| {
+            ic_msg!(
+                invoke_context,
+                "Failed to serialize MagicContext: {:?}",
+                err
+            );
+            err
+        })?;
+    ic_msg!(
+        invoke_context,
+        "Cancelled recurring commit with ID: {}",
+        recurring_commit_id
+    );
+
+    Ok(())
+}
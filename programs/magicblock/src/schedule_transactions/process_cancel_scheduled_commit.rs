@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{instruction::InstructionError, pubkey::Pubkey};
+
+use crate::{
+    magic_context::{MagicContext, ScheduledCommit},
+    schedule_transactions::{
+        check_magic_context_id, transaction_scheduler::TransactionScheduler,
+    },
+    utils::accounts::{
+        get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
+    },
+};
+
+pub(crate) fn process_cancel_scheduled_commit(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    commit_id: u64,
+) -> Result<(), InstructionError> {
+    const PAYER_IDX: u16 = 0;
+    const MAGIC_CONTEXT_IDX: u16 = PAYER_IDX + 1;
+
+    check_magic_context_id(invoke_context, MAGIC_CONTEXT_IDX)?;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // Assert Payer is signer
+    let payer_pubkey =
+        get_instruction_pubkey_with_idx(transaction_context, PAYER_IDX)?;
+    if !signers.contains(payer_pubkey) {
+        ic_msg!(
+            invoke_context,
+            "CancelScheduledCommit ERR: payer pubkey {} not in signers",
+            payer_pubkey
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+
+    let magic_context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    let mut magic_context = MagicContext::deserialize(
+        &magic_context_acc.borrow(),
+    )
+    .map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "Failed to deserialize MagicContext: {}",
+            err
+        );
+        InstructionError::InvalidAccountData
+    })?;
+
+    // The commit is either still in the MagicContext or it was accepted
+    // already, but is scheduled for a later slot
+    let transaction_scheduler = TransactionScheduler::default();
+    let in_magic_context = magic_context
+        .scheduled_commits
+        .iter()
+        .find(|commit| commit.id == commit_id)
+        .cloned();
+    let commit = match in_magic_context {
+        Some(commit) => commit,
+        None => transaction_scheduler
+            .get_scheduled_commit(commit_id)
+            .filter(|commit| commit.execute_at_slot.is_some())
+            .ok_or_else(|| {
+                ic_msg!(
+                    invoke_context,
+                    "CancelScheduledCommit ERR: no pending commit with ID {}",
+                    commit_id
+                );
+                InstructionError::InvalidArgument
+            })?,
+    };
+    check_can_cancel(invoke_context, &commit, payer_pubkey)?;
+
+    if magic_context.remove_scheduled_commit(commit_id).is_some() {
+        // Zero fill account before updating data, the context shrinks and we
+        // don't want to leave removed commits behind
+        magic_context_acc
+            .borrow_mut()
+            .set_data_from_slice(&MagicContext::ZERO);
+        magic_context
+            .store(&mut magic_context_acc.borrow_mut())
+            .map_err(|err| {
+                ic_msg!(
+                    invoke_context,
+                    "Failed to serialize MagicContext: {:?}",
+                    err
+                );
+                err
+            })?;
+    } else {
+        transaction_scheduler.remove_scheduled_commit(commit_id);
+    }
+    ic_msg!(
+        invoke_context,
+        "Cancelled scheduled commit with ID: {}",
+        commit_id
+    );
+
+    Ok(())
+}
+
+fn check_can_cancel(
+    invoke_context: &InvokeContext,
+    commit: &ScheduledCommit,
+    payer: &Pubkey,
+) -> Result<(), InstructionError> {
+    if !commit.payer.eq(payer) {
+        ic_msg!(
+            invoke_context,
+            "CancelScheduledCommit ERR: commit {} was scheduled by {} and can only be cancelled by it",
+            commit.id,
+            commit.payer
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    // The accounts were locked to the delegation program when the commit was
+    // scheduled, thus they cannot be used anymore without undelegating them
+    if commit.request_undelegation {
+        ic_msg!(
+            invoke_context,
+            "CancelScheduledCommit ERR: commit {} requests undelegation and cannot be cancelled",
+            commit.id
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    // The escrow's lamports are held until the outcome of the payout is known
+    if commit.transfer_intent.is_some() {
+        ic_msg!(
+            invoke_context,
+            "CancelScheduledCommit ERR: commit {} pays out a transfer intent and cannot be cancelled",
+            commit.id
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use magicblock_core::magic_program::MAGIC_COMMIT_EVENTS_PUBKEY;
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{
+    account::ReadableAccount, instruction::InstructionError, pubkey::Pubkey,
+};
+
+use crate::{
+    commit_event_log::CommitEventLog,
+    utils::accounts::{
+        get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
+    },
+};
+
+pub(crate) fn process_get_undelegation_status(
+    invoke_context: &mut InvokeContext,
+    pubkey: Pubkey,
+) -> Result<(), InstructionError> {
+    const COMMIT_EVENTS_IDX: u16 = 0;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // 1. Check that the commit events account is correct
+    let provided_commit_events = get_instruction_pubkey_with_idx(
+        transaction_context,
+        COMMIT_EVENTS_IDX,
+    )?;
+    if !provided_commit_events.eq(&MAGIC_COMMIT_EVENTS_PUBKEY) {
+        ic_msg!(
+            invoke_context,
+            "GetUndelegationStatus ERR: invalid commit events account {}",
+            provided_commit_events
+        );
+        return Err(InstructionError::MissingAccount);
+    }
+
+    // 2. Look up the latest undelegation status recorded for the account
+    let commit_events_acc = get_instruction_account_with_idx(
+        transaction_context,
+        COMMIT_EVENTS_IDX,
+    )?;
+    let status =
+        CommitEventLog::try_from_data(commit_events_acc.borrow().data())
+            .map_err(|err| {
+                ic_msg!(
+                    invoke_context,
+                    "Failed to deserialize CommitEventLog: {}",
+                    err
+                );
+                InstructionError::InvalidAccountData
+            })?
+            .undelegation_status(&pubkey);
+
+    // 3. Provide it as return data to the caller
+    ic_msg!(
+        invoke_context,
+        "UndelegationStatus: pubkey={}, status={:?}",
+        pubkey,
+        status
+    );
+    let data = bincode::serialize(&status)
+        .map_err(|_| InstructionError::GenericError)?;
+    invoke_context
+        .transaction_context
+        .set_return_data(crate::id(), data)
+}
@@ -0,0 +1,116 @@
+use std::collections::HashSet;
+
+use magicblock_core::{
+    commit_record::CommitRecord, magic_program::MAGIC_COMMIT_EVENTS_PUBKEY,
+};
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{
+    account::ReadableAccount, instruction::InstructionError, pubkey::Pubkey,
+};
+
+use crate::{
+    commit_event_log::CommitEventLog,
+    utils::accounts::{
+        get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
+    },
+    validator::validator_authority_id,
+};
+
+pub(crate) fn process_record_commit_event(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    record: CommitRecord,
+) -> Result<(), InstructionError> {
+    const VALIDATOR_AUTHORITY_IDX: u16 = 0;
+    const COMMIT_EVENTS_IDX: u16 = VALIDATOR_AUTHORITY_IDX + 1;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // 1. Check that the validator authority (first account) is correct and signer
+    let provided_validator_auth = get_instruction_pubkey_with_idx(
+        transaction_context,
+        VALIDATOR_AUTHORITY_IDX,
+    )?;
+    let validator_auth = validator_authority_id();
+    if !provided_validator_auth.eq(&validator_auth) {
+        ic_msg!(
+            invoke_context,
+            "RecordCommitEvent ERR: invalid validator authority {}, should be {}",
+            provided_validator_auth,
+            validator_auth
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    if !signers.contains(&validator_auth) {
+        ic_msg!(
+            invoke_context,
+            "RecordCommitEvent ERR: validator authority pubkey {} not in signers",
+            validator_auth
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+
+    // 2. Check that the commit events account is correct
+    let provided_commit_events = get_instruction_pubkey_with_idx(
+        transaction_context,
+        COMMIT_EVENTS_IDX,
+    )?;
+    if !provided_commit_events.eq(&MAGIC_COMMIT_EVENTS_PUBKEY) {
+        ic_msg!(
+            invoke_context,
+            "RecordCommitEvent ERR: invalid commit events account {}",
+            provided_commit_events
+        );
+        return Err(InstructionError::MissingAccount);
+    }
+
+    // 3. Append the event to the log and store it
+    let commit_events_acc = get_instruction_account_with_idx(
+        transaction_context,
+        COMMIT_EVENTS_IDX,
+    )?;
+    let mut commit_event_log =
+        CommitEventLog::try_from_data(commit_events_acc.borrow().data())
+            .map_err(|err| {
+                ic_msg!(
+                    invoke_context,
+                    "Failed to deserialize CommitEventLog: {}",
+                    err
+                );
+                InstructionError::InvalidAccountData
+            })?;
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+
+    // Logged in a stable format so that clients can follow the outcomes
+    // via logsSubscribe as well
+    ic_msg!(
+        invoke_context,
+        "CommitEvent: commit_id={}, outcome={:?}, included={:?}, excluded={:?}, chain_signatures={:?}",
+        record.commit_id,
+        record.outcome,
+        record.included_pubkeys,
+        record.excluded_pubkeys,
+        record.chain_signatures
+    );
+    commit_event_log.record(clock.slot, record);
+    commit_event_log
+        .store(&mut commit_events_acc.borrow_mut())
+        .map_err(|err| {
+            ic_msg!(
+                invoke_context,
+                "Failed to serialize CommitEventLog: {:?}",
+                err
+            );
+            err
+        })?;
+
+    Ok(())
+}
@@ -7,12 +7,22 @@ use magicblock_core::magic_program::MAGIC_CONTEXT_PUBKEY;
 use solana_log_collector::ic_msg;
 use solana_program_runtime::invoke_context::InvokeContext;
 use solana_sdk::{
-    account::ReadableAccount, instruction::InstructionError, pubkey::Pubkey,
+    account::{ReadableAccount, WritableAccount},
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    transaction_context::TransactionContext,
 };
 
 use crate::{
-    magic_context::{CommittedAccount, MagicContext, ScheduledCommit},
-    magicblock_instruction::scheduled_commit_sent,
+    commit_authority_record::CommitAuthorityRecord,
+    errors::custom_error_codes,
+    magic_context::{
+        CommittedAccount, MagicContext, RecurringCommit, ScheduledCommit,
+    },
+    magicblock_instruction::{
+        scheduled_commit_sent, BaseLayerAction, CommitPriority, CommitTarget,
+        RecurringCommitEnd, TransferIntent, MAX_COMMIT_MEMO_LEN,
+    },
     schedule_transactions::transaction_scheduler::TransactionScheduler,
     utils::{
         account_actions::set_account_owner_to_delegation_program,
@@ -20,30 +30,535 @@ use crate::{
             get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
         },
     },
-    validator::validator_authority_id,
+    validator::{
+        is_allowed_to_schedule_commits, schedule_commit_fees,
+        validator_authority_id,
+    },
 };
 
 #[derive(Default)]
 pub(crate) struct ProcessScheduleCommitOptions {
     pub request_undelegation: bool,
+    /// If set the commit is executed once the target is reached instead of right away
+    pub target: Option<CommitTarget>,
+    /// Instruction appended to the transaction committing the accounts on chain
+    pub base_layer_action: Option<BaseLayerAction>,
+    /// Compute unit price offered by the transaction committing the accounts
+    pub compute_unit_price: Option<u64>,
+    /// Close the accounts on the ephemeral rollup once they were undelegated
+    pub close_accounts: bool,
+    /// Commit all accounts in the same transaction on the base layer
+    pub atomic: bool,
+    /// Memo included in the transactions committing the accounts on chain
+    pub memo: Option<String>,
+    /// Order in which the commit is processed among pending commits
+    pub priority: CommitPriority,
+    /// The accounts to be committed are followed by a [CommitAuthorityRecord]
+    /// for each of them
+    pub authority_records: bool,
 }
 
+/// Provides the ids of scheduled commits, including the ones realizing
+/// recurring commits
+pub(crate) fn next_commit_id() -> u64 {
+    static COMMIT_ID: AtomicU64 = AtomicU64::new(0);
+    COMMIT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+const PAYER_IDX: u16 = 0;
+const MAGIC_CONTEXT_IDX: u16 = PAYER_IDX + 1;
+const COMMITTEES_START: usize = MAGIC_CONTEXT_IDX as usize + 1;
+
 pub(crate) fn process_schedule_commit(
     signers: HashSet<Pubkey>,
     invoke_context: &mut InvokeContext,
     opts: ProcessScheduleCommitOptions,
 ) -> Result<(), InstructionError> {
-    static COMMIT_ID: AtomicU64 = AtomicU64::new(0);
+    check_magic_context_id(invoke_context, MAGIC_CONTEXT_IDX)?;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+    let (payer_pubkey, pubkeys) = get_committed_accounts(
+        &signers,
+        invoke_context,
+        transaction_context,
+        COMMITTEES_START,
+        opts.authority_records,
+    )?;
+
+    if let Some(action) = &opts.base_layer_action {
+        check_base_layer_action(invoke_context, action, &pubkeys)?;
+    }
+    if let Some(memo) = &opts.memo {
+        if memo.len() > MAX_COMMIT_MEMO_LEN {
+            ic_msg!(
+                invoke_context,
+                "ScheduleCommit ERR: memo of {} bytes exceeds the max of {} bytes",
+                memo.len(),
+                MAX_COMMIT_MEMO_LEN
+            );
+            return Err(InstructionError::InvalidArgument);
+        }
+    }
+
+    if opts.request_undelegation {
+        for (idx, committed_account) in (COMMITTEES_START..).zip(&pubkeys) {
+            let acc = get_instruction_account_with_idx(
+                transaction_context,
+                idx as u16,
+            )?;
+            // If the account is scheduled to be undelegated then we need to lock it
+            // immediately in order to prevent the following actions:
+            // - writes to the account
+            // - scheduling further commits for this account
+            //
+            // Setting the owner will prevent both, since in both cases the _actual_
+            // owner program needs to sign for the account which is not possible at
+            // that point
+            // NOTE: this owner change only takes effect if the transaction which
+            // includes this instruction succeeds.
+            set_account_owner_to_delegation_program(acc);
+            ic_msg!(
+                invoke_context,
+                "ScheduleCommit: account {} owner set to delegation program",
+                committed_account.pubkey
+            );
+        }
+    }
 
-    const PAYER_IDX: u16 = 0;
-    const MAGIC_CONTEXT_IDX: u16 = PAYER_IDX + 1;
+    // Determine id and slot
+    let commit_id = next_commit_id();
+
+    // It appears that in builtin programs `Clock::get` doesn't work as expected, thus
+    // we have to get it directly from the sysvar cache.
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+
+    let execute_at_slot = match opts.target {
+        None => None,
+        Some(CommitTarget::Delay(delay)) => {
+            Some(clock.slot.saturating_add(delay))
+        }
+        Some(CommitTarget::Slot(slot)) if slot < clock.slot => {
+            ic_msg!(
+                invoke_context,
+                "ScheduleCommit ERR: target slot {} already passed, current slot is {}",
+                slot,
+                clock.slot
+            );
+            return Err(InstructionError::InvalidArgument);
+        }
+        Some(CommitTarget::Slot(slot)) => Some(slot),
+    };
+
+    let blockhash = invoke_context.environment_config.blockhash;
+    let commit_sent_transaction = scheduled_commit_sent(commit_id, blockhash);
+
+    let commit_sent_sig = commit_sent_transaction.signatures[0];
+
+    let fee = charge_schedule_commit_fee(
+        invoke_context,
+        transaction_context,
+        1,
+        pubkeys.len(),
+    )?;
+
+    let scheduled_commit = ScheduledCommit {
+        id: commit_id,
+        slot: clock.slot,
+        blockhash,
+        accounts: pubkeys,
+        payer: payer_pubkey,
+        commit_sent_transaction,
+        request_undelegation: opts.request_undelegation,
+        execute_at_slot,
+        base_layer_action: opts.base_layer_action,
+        compute_unit_price: opts.compute_unit_price,
+        close_accounts: opts.close_accounts,
+        transfer_intent: None,
+        atomic: opts.atomic,
+        memo: opts.memo,
+        priority: opts.priority,
+    };
+
+    // NOTE: this is only protected by all the above checks however if the
+    // instruction fails for other reasons detected afterward then the commit
+    // stays scheduled
+    let context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    TransactionScheduler::schedule_commit(
+        invoke_context,
+        context_acc,
+        scheduled_commit,
+        fee,
+    )
+    .map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "ScheduleCommit ERR: failed to schedule commit: {}",
+            err
+        );
+        InstructionError::GenericError
+    })?;
+    ic_msg!(invoke_context, "Scheduled commit with ID: {}", commit_id,);
+    match execute_at_slot {
+        // The transaction recording the commit is created once it is due, since
+        // the blockhash it uses may have expired by then
+        Some(slot) => {
+            ic_msg!(
+                invoke_context,
+                "Scheduled commit executes at slot: {}",
+                slot
+            )
+        }
+        None => ic_msg!(
+            invoke_context,
+            "ScheduledCommitSent signature: {}",
+            commit_sent_sig,
+        ),
+    }
+
+    Ok(())
+}
+
+pub(crate) fn process_register_recurring_commit(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    interval_slots: u64,
+    end: RecurringCommitEnd,
+) -> Result<(), InstructionError> {
+    check_magic_context_id(invoke_context, MAGIC_CONTEXT_IDX)?;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+    let (payer_pubkey, pubkeys) = get_committed_accounts(
+        &signers,
+        invoke_context,
+        transaction_context,
+        COMMITTEES_START,
+        false,
+    )?;
+
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+
+    if interval_slots == 0 {
+        ic_msg!(
+            invoke_context,
+            "RegisterRecurringCommit ERR: interval needs to be at least one slot"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let next_slot = clock.slot.saturating_add(interval_slots);
+    let ends_before_first_commit = match end {
+        RecurringCommitEnd::AfterCommits(commits) => commits == 0,
+        RecurringCommitEnd::AfterSlot(slot) => slot < next_slot,
+    };
+    if ends_before_first_commit {
+        ic_msg!(
+            invoke_context,
+            "RegisterRecurringCommit ERR: {:?} ends before the first commit at slot {}",
+            end,
+            next_slot
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+
+    // All commits of the recurring commit are paid for upfront
+    let commits = match end {
+        RecurringCommitEnd::AfterCommits(commits) => commits,
+        RecurringCommitEnd::AfterSlot(slot) => {
+            (slot - next_slot) / interval_slots + 1
+        }
+    };
+    let fee = charge_schedule_commit_fee(
+        invoke_context,
+        transaction_context,
+        commits,
+        pubkeys.len(),
+    )?;
+
+    let recurring_commit = RecurringCommit {
+        // Assigned once it is added to the MagicContext
+        id: 0,
+        accounts: pubkeys,
+        payer: payer_pubkey,
+        interval_slots,
+        next_slot,
+        end,
+        executed_commits: 0,
+    };
+
+    let context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    let recurring_commit_id = TransactionScheduler::register_recurring_commit(
+        invoke_context,
+        context_acc,
+        recurring_commit,
+        fee,
+    )
+    .map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "RegisterRecurringCommit ERR: failed to register recurring commit: {}",
+            err
+        );
+        InstructionError::GenericError
+    })?;
+    ic_msg!(
+        invoke_context,
+        "Registered recurring commit with ID: {}, first commit at slot: {}",
+        recurring_commit_id,
+        next_slot
+    );
+
+    Ok(())
+}
+
+/// Moves the lamports of the intent from the escrow to the magic context, which
+/// holds them until the validator settles the intent, and schedules a commit
+/// of the escrow which pays them out to the recipient on the base layer
+pub(crate) fn process_schedule_transfer_intent(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    intent: TransferIntent,
+) -> Result<(), InstructionError> {
+    const ESCROW_IDX: u16 = MAGIC_CONTEXT_IDX + 1;
 
     check_magic_context_id(invoke_context, MAGIC_CONTEXT_IDX)?;
 
     let transaction_context = &invoke_context.transaction_context.clone();
+    let (payer_pubkey, accounts) = get_committed_accounts(
+        &signers,
+        invoke_context,
+        transaction_context,
+        ESCROW_IDX as usize,
+        false,
+    )?;
+    if accounts.len() != 1 {
+        ic_msg!(
+            invoke_context,
+            "ScheduleTransferIntent ERR: expected exactly one escrow account, got {}",
+            accounts.len()
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    if intent.lamports == 0 {
+        ic_msg!(
+            invoke_context,
+            "ScheduleTransferIntent ERR: cannot transfer zero lamports"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+
+    // The validator authority pays the recipient on the base layer, the
+    // escrow's lamports are refunded from the context if that fails
+    let escrow_acc =
+        get_instruction_account_with_idx(transaction_context, ESCROW_IDX)?;
+    let escrow_lamports = escrow_acc
+        .borrow()
+        .lamports()
+        .checked_sub(intent.lamports)
+        .ok_or_else(|| {
+            ic_msg!(
+                invoke_context,
+                "ScheduleTransferIntent ERR: escrow {} cannot pay {} lamports",
+                accounts[0].pubkey,
+                intent.lamports
+            );
+            InstructionError::InsufficientFunds
+        })?;
+    let context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    let context_lamports = context_acc
+        .borrow()
+        .lamports()
+        .checked_add(intent.lamports)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    escrow_acc.borrow_mut().set_lamports(escrow_lamports);
+    context_acc.borrow_mut().set_lamports(context_lamports);
+
+    let fee = charge_schedule_commit_fee(
+        invoke_context,
+        transaction_context,
+        1,
+        accounts.len(),
+    )?;
+
+    let commit_id = next_commit_id();
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+    let blockhash = invoke_context.environment_config.blockhash;
+    let commit_sent_transaction = scheduled_commit_sent(commit_id, blockhash);
+    let commit_sent_sig = commit_sent_transaction.signatures[0];
+
+    let scheduled_commit = ScheduledCommit {
+        id: commit_id,
+        slot: clock.slot,
+        blockhash,
+        accounts,
+        payer: payer_pubkey,
+        commit_sent_transaction,
+        request_undelegation: false,
+        execute_at_slot: None,
+        base_layer_action: None,
+        compute_unit_price: None,
+        close_accounts: false,
+        transfer_intent: Some(intent),
+        atomic: false,
+        memo: None,
+        priority: CommitPriority::Normal,
+    };
+
+    TransactionScheduler::schedule_commit(
+        invoke_context,
+        context_acc,
+        scheduled_commit,
+        fee,
+    )
+    .map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "ScheduleTransferIntent ERR: failed to schedule commit: {}",
+            err
+        );
+        InstructionError::GenericError
+    })?;
+    ic_msg!(
+        invoke_context,
+        "Scheduled transfer intent with commit ID: {}",
+        commit_id
+    );
+    ic_msg!(
+        invoke_context,
+        "ScheduledCommitSent signature: {}",
+        commit_sent_sig,
+    );
+
+    Ok(())
+}
+
+/// Moves the configured fee for scheduling the commits of the accounts from the
+/// payer to the MagicContext, which holds it until the validator authority
+/// claims it when accepting the scheduled commits.
+/// Returns the charged fee.
+fn charge_schedule_commit_fee(
+    invoke_context: &InvokeContext,
+    transaction_context: &TransactionContext,
+    commits: u64,
+    accounts: usize,
+) -> Result<u64, InstructionError> {
+    let fee = schedule_commit_fees()
+        .fee(commits, accounts)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    if fee == 0 {
+        return Ok(0);
+    }
+
+    let payer_acc =
+        get_instruction_account_with_idx(transaction_context, PAYER_IDX)?;
+    let payer_lamports =
+        payer_acc.borrow().lamports().checked_sub(fee).ok_or_else(|| {
+            ic_msg!(
+                invoke_context,
+                "ScheduleCommit ERR: payer cannot pay the fee of {} lamports for scheduling the commit",
+                fee
+            );
+            InstructionError::Custom(
+                custom_error_codes::FAILED_TO_TRANSFER_SCHEDULE_COMMIT_COST,
+            )
+        })?;
+    let context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    let context_lamports = context_acc
+        .borrow()
+        .lamports()
+        .checked_add(fee)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    payer_acc.borrow_mut().set_lamports(payer_lamports);
+    context_acc.borrow_mut().set_lamports(context_lamports);
+    ic_msg!(
+        invoke_context,
+        "ScheduleCommit: charged fee of {} lamports",
+        fee
+    );
+
+    Ok(fee)
+}
+
+/// Ensures that the action only invokes the program owning the committed
+/// accounts and cannot make use of the signature of the validator authority
+fn check_base_layer_action(
+    invoke_context: &InvokeContext,
+    action: &BaseLayerAction,
+    committed_accounts: &[CommittedAccount],
+) -> Result<(), InstructionError> {
+    if let Some(committed_account) = committed_accounts
+        .iter()
+        .find(|account| account.owner != action.program_id)
+    {
+        ic_msg!(
+            invoke_context,
+            "ScheduleCommit ERR: action program {} needs to own the committed account {}, but it is owned by {}",
+            action.program_id,
+            committed_account.pubkey,
+            committed_account.owner
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let validator_auth = validator_authority_id();
+    if action
+        .accounts
+        .iter()
+        .any(|account| account.pubkey == validator_auth)
+    {
+        ic_msg!(
+            invoke_context,
+            "ScheduleCommit ERR: action cannot include the validator authority {}",
+            validator_auth
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    Ok(())
+}
+
+/// Validates the payer and the accounts to be committed provided to an
+/// instruction scheduling commits and returns them.
+/// With `authority_records` the accounts to be committed are followed by the
+/// [CommitAuthorityRecord] of each of them in the same order.
+pub(crate) fn get_committed_accounts(
+    signers: &HashSet<Pubkey>,
+    invoke_context: &InvokeContext,
+    transaction_context: &TransactionContext,
+    committees_start: usize,
+    authority_records: bool,
+) -> Result<(Pubkey, Vec<CommittedAccount>), InstructionError> {
     let ix_ctx = transaction_context.get_current_instruction_context()?;
     let ix_accs_len = ix_ctx.get_number_of_instruction_accounts() as usize;
-    const COMMITTEES_START: usize = MAGIC_CONTEXT_IDX as usize + 1;
 
     // Assert MagicBlock program
     ix_ctx
@@ -57,7 +572,7 @@ pub(crate) fn process_schedule_commit(
         })?;
 
     // Assert enough accounts
-    if ix_accs_len <= COMMITTEES_START {
+    if ix_accs_len <= committees_start {
         ic_msg!(
             invoke_context,
             "ScheduleCommit ERR: not enough accounts to schedule commit ({}), need payer, signing program an account for each pubkey to be committed",
@@ -93,7 +608,7 @@ pub(crate) fn process_schedule_commit(
     let first_committee_owner = {
         *get_instruction_account_with_idx(
             transaction_context,
-            COMMITTEES_START as u16,
+            committees_start as u16,
         )?
         .borrow()
         .owner()
@@ -117,12 +632,39 @@ pub(crate) fn process_schedule_commit(
     #[cfg(test)]
     let parent_program_id = Some(&first_committee_owner);
 
-    // Assert all accounts are owned by invoking program OR are signers
+    if !is_allowed_to_schedule_commits(parent_program_id) {
+        ic_msg!(
+            invoke_context,
+            "ScheduleCommit ERR: invoking program {} is not allowed to schedule commits",
+            parent_program_id
+                .map_or_else(|| "None".to_string(), |id| id.to_string())
+        );
+        return Err(InstructionError::Custom(
+            custom_error_codes::PROGRAM_NOT_ALLOWED_TO_SCHEDULE_COMMITS,
+        ));
+    }
+
+    let committees_end = if authority_records {
+        let accounts_len = ix_accs_len - committees_start;
+        if accounts_len % 2 != 0 {
+            ic_msg!(
+                invoke_context,
+                "ScheduleCommit ERR: each account to be committed needs to be followed by its authority record"
+            );
+            return Err(InstructionError::NotEnoughAccountKeys);
+        }
+        committees_start + accounts_len / 2
+    } else {
+        ix_accs_len
+    };
+
+    // Assert all accounts are owned by invoking program OR are signers OR
+    // their owner authorized the invoking program via an authority record
     // NOTE: we don't require PDAs to be signers as in our case verifying that the
     // program owning the PDAs invoked us via CPI is sufficient
     // Thus we can be `invoke`d unsigned and no seeds need to be provided
     let mut pubkeys: Vec<CommittedAccount> = Vec::new();
-    for idx in COMMITTEES_START..ix_accs_len {
+    for idx in committees_start..committees_end {
         let acc_pubkey =
             get_instruction_pubkey_with_idx(transaction_context, idx as u16)?;
         let acc =
@@ -130,9 +672,29 @@ pub(crate) fn process_schedule_commit(
 
         {
             let acc_owner = *acc.borrow().owner();
-            if parent_program_id != Some(&acc_owner)
-                && !signers.contains(acc_pubkey)
+            let is_owned_or_signer = parent_program_id == Some(&acc_owner)
+                || signers.contains(acc_pubkey);
+            let record_idx = idx + committees_end - committees_start;
+            if !is_owned_or_signer
+                && authority_records
+                && parent_program_id.is_some_and(|parent_id| {
+                    is_authorized_by_record(
+                        invoke_context,
+                        transaction_context,
+                        record_idx as u16,
+                        acc_pubkey,
+                        &acc_owner,
+                        parent_id,
+                    )
+                })
             {
+                pubkeys.push(CommittedAccount {
+                    pubkey: *acc_pubkey,
+                    owner: acc_owner,
+                });
+                continue;
+            }
+            if !is_owned_or_signer {
                 return match parent_program_id {
                     None => {
                         ic_msg!(
@@ -157,84 +719,65 @@ pub(crate) fn process_schedule_commit(
                 owner: *parent_program_id.unwrap_or(&acc_owner),
             });
         }
-
-        if opts.request_undelegation {
-            // If the account is scheduled to be undelegated then we need to lock it
-            // immediately in order to prevent the following actions:
-            // - writes to the account
-            // - scheduling further commits for this account
-            //
-            // Setting the owner will prevent both, since in both cases the _actual_
-            // owner program needs to sign for the account which is not possible at
-            // that point
-            // NOTE: this owner change only takes effect if the transaction which
-            // includes this instruction succeeds.
-            set_account_owner_to_delegation_program(acc);
-            ic_msg!(
-                invoke_context,
-                "ScheduleCommit: account {} owner set to delegation program",
-                acc_pubkey
-            );
-        }
     }
 
-    // Determine id and slot
-    let commit_id = COMMIT_ID.fetch_add(1, Ordering::Relaxed);
-
-    // It appears that in builtin programs `Clock::get` doesn't work as expected, thus
-    // we have to get it directly from the sysvar cache.
-    let clock =
-        invoke_context
-            .get_sysvar_cache()
-            .get_clock()
-            .map_err(|err| {
-                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
-                InstructionError::UnsupportedSysvar
-            })?;
-
-    let blockhash = invoke_context.environment_config.blockhash;
-    let commit_sent_transaction = scheduled_commit_sent(commit_id, blockhash);
-
-    let commit_sent_sig = commit_sent_transaction.signatures[0];
+    Ok((*payer_pubkey, pubkeys))
+}
 
-    let scheduled_commit = ScheduledCommit {
-        id: commit_id,
-        slot: clock.slot,
-        blockhash,
-        accounts: pubkeys,
-        payer: *payer_pubkey,
-        commit_sent_transaction,
-        request_undelegation: opts.request_undelegation,
+/// Determines if the authority record at the index was created by the owner of
+/// the account and authorizes the invoking program to schedule its commit
+fn is_authorized_by_record(
+    invoke_context: &InvokeContext,
+    transaction_context: &TransactionContext,
+    record_idx: u16,
+    acc_pubkey: &Pubkey,
+    acc_owner: &Pubkey,
+    parent_program_id: &Pubkey,
+) -> bool {
+    let (Ok(record_pubkey), Ok(record_acc)) = (
+        get_instruction_pubkey_with_idx(transaction_context, record_idx),
+        get_instruction_account_with_idx(transaction_context, record_idx),
+    ) else {
+        return false;
     };
-
-    // NOTE: this is only protected by all the above checks however if the
-    // instruction fails for other reasons detected afterward then the commit
-    // stays scheduled
-    let context_acc = get_instruction_account_with_idx(
-        transaction_context,
-        MAGIC_CONTEXT_IDX,
-    )?;
-    TransactionScheduler::schedule_commit(
-        invoke_context,
-        context_acc,
-        scheduled_commit,
-    )
-    .map_err(|err| {
+    let record_acc = record_acc.borrow();
+    // Only the owner program can create accounts owned by it
+    if record_acc.owner().ne(acc_owner) {
         ic_msg!(
             invoke_context,
-            "ScheduleCommit ERR: failed to schedule commit: {}",
-            err
+            "ScheduleCommit: authority record {} of account {} is not owned by {}",
+            record_pubkey,
+            acc_pubkey,
+            acc_owner
         );
-        InstructionError::GenericError
-    })?;
-    ic_msg!(invoke_context, "Scheduled commit with ID: {}", commit_id,);
-    ic_msg!(
-        invoke_context,
-        "ScheduledCommitSent signature: {}",
-        commit_sent_sig,
-    );
-
-    Ok(())
+        return false;
+    }
+    let authorized = CommitAuthorityRecord::try_from_data(record_acc.data())
+        .is_some_and(|record| {
+            record.authorizes(
+                record_pubkey,
+                acc_owner,
+                acc_pubkey,
+                parent_program_id,
+            )
+        });
+    if authorized {
+        ic_msg!(
+            invoke_context,
+            "ScheduleCommit: account {} authorized by record {}",
+            acc_pubkey,
+            record_pubkey
+        );
+    } else {
+        ic_msg!(
+            invoke_context,
+            "ScheduleCommit: authority record {} does not authorize {} to commit account {}",
+            record_pubkey,
+            parent_program_id,
+            acc_pubkey
+        );
+    }
+    authorized
 }
 
 pub fn process_accept_scheduled_commits(
@@ -264,7 +807,20 @@ pub fn process_accept_scheduled_commits(
                 );
                 InstructionError::InvalidAccountData
             })?;
-    if magic_context.scheduled_commits.is_empty() {
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+    let has_due_recurring_commits = magic_context
+        .recurring_commits
+        .iter()
+        .any(|commit| commit.next_slot <= clock.slot);
+    if magic_context.scheduled_commits.is_empty() && !has_due_recurring_commits
+    {
         ic_msg!(
             invoke_context,
             "AcceptScheduledCommits: no scheduled commits to accept"
@@ -297,8 +853,71 @@ pub fn process_accept_scheduled_commits(
         return Err(InstructionError::MissingRequiredSignature);
     }
 
-    // 3. Move scheduled commits (without copying)
-    let scheduled_commits = magic_context.take_scheduled_commits();
+    // 3. Claim the fees payers were charged for scheduling the commits
+    let collected_fees = magic_context.take_collected_fees();
+    if collected_fees > 0 {
+        let validator_authority_acc = get_instruction_account_with_idx(
+            transaction_context,
+            VALIDATOR_AUTHORITY_IDX,
+        )?;
+        let validator_authority_lamports = validator_authority_acc
+            .borrow()
+            .lamports()
+            .checked_add(collected_fees)
+            .ok_or(InstructionError::ArithmeticOverflow)?;
+        let magic_context_lamports = magic_context_acc
+            .borrow()
+            .lamports()
+            .checked_sub(collected_fees)
+            .ok_or(InstructionError::InsufficientFunds)?;
+        magic_context_acc
+            .borrow_mut()
+            .set_lamports(magic_context_lamports);
+        validator_authority_acc
+            .borrow_mut()
+            .set_lamports(validator_authority_lamports);
+        ic_msg!(
+            invoke_context,
+            "AcceptScheduledCommits: claimed {} lamports of fees",
+            collected_fees
+        );
+    }
+
+    // 4. Move scheduled commits (without copying)
+    let mut scheduled_commits = magic_context.take_scheduled_commits();
+
+    // Recurring commits are scheduled whenever they are due
+    let blockhash = invoke_context.environment_config.blockhash;
+    for recurring_commit in
+        magic_context.advance_due_recurring_commits(clock.slot)
+    {
+        let commit_id = next_commit_id();
+        ic_msg!(
+            invoke_context,
+            "AcceptScheduledCommits: scheduled commit {} for recurring commit {}",
+            commit_id,
+            recurring_commit.id
+        );
+        scheduled_commits.push(ScheduledCommit {
+            id: commit_id,
+            slot: clock.slot,
+            blockhash,
+            accounts: recurring_commit.accounts,
+            payer: recurring_commit.payer,
+            commit_sent_transaction: scheduled_commit_sent(
+                commit_id, blockhash,
+            ),
+            request_undelegation: false,
+            execute_at_slot: None,
+            base_layer_action: None,
+            compute_unit_price: None,
+            close_accounts: false,
+            transfer_intent: None,
+            atomic: false,
+            memo: None,
+            priority: CommitPriority::Normal,
+        });
+    }
     ic_msg!(
         invoke_context,
         "AcceptScheduledCommits: accepted {} scheduled commit(s)",
@@ -306,30 +925,30 @@ pub fn process_accept_scheduled_commits(
     );
     TransactionScheduler::default().accept_scheduled_commits(scheduled_commits);
 
-    // 4. Serialize and store the updated `MagicContext` account
-    // Zero fill account before updating data
+    // 5. Serialize and store the updated `MagicContext` account
+    // Zero fill account before updating data, this also shrinks it back to
+    // its regular size in case it grew due to a burst of scheduled commits
     // NOTE: this may become expensive, but is a security measure and also prevents
     // accidentally interpreting old data when deserializing
     magic_context_acc
         .borrow_mut()
         .set_data_from_slice(&MagicContext::ZERO);
 
-    magic_context_acc
-        .borrow_mut()
-        .serialize_data(&magic_context)
+    magic_context
+        .store(&mut magic_context_acc.borrow_mut())
         .map_err(|err| {
             ic_msg!(
                 invoke_context,
-                "Failed to serialize MagicContext: {}",
+                "Failed to serialize MagicContext: {:?}",
                 err
             );
-            InstructionError::GenericError
+            err
         })?;
 
     Ok(())
 }
 
-fn check_magic_context_id(
+pub(crate) fn check_magic_context_id(
     invoke_context: &InvokeContext,
     idx: u16,
 ) -> Result<(), InstructionError> {
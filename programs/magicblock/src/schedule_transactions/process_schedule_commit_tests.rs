@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
 use assert_matches::assert_matches;
-use magicblock_core::magic_program::MAGIC_CONTEXT_PUBKEY;
+use magicblock_core::{
+    commit_record::{CommitOutcome, CommitRecord, UndelegationStatus},
+    magic_program::{MAGIC_COMMIT_EVENTS_PUBKEY, MAGIC_CONTEXT_PUBKEY},
+};
 use solana_sdk::{
     account::{
         create_account_shared_data_for_test, AccountSharedData, ReadableAccount,
@@ -18,16 +21,38 @@ use solana_sdk::{
 use test_tools_core::init_logger;
 
 use crate::{
-    magic_context::MagicContext,
+    commit_event_log::CommitEventLog,
+    magic_context::{
+        CommittedAccount, MagicContext, PendingTransferIntent, RecurringCommit,
+    },
     magicblock_instruction::{
         accept_scheduled_commits_instruction,
+        cancel_recurring_commit_instruction,
+        cancel_scheduled_commit_instruction,
+        get_undelegation_status_instruction, into_instruction,
+        record_commit_event_instruction, register_recurring_commit_instruction,
+        schedule_atomic_commit_instruction,
         schedule_commit_and_undelegate_instruction,
-        schedule_commit_instruction, MagicBlockInstruction,
+        schedule_commit_and_undelegate_with_action_instruction,
+        schedule_commit_at_instruction, schedule_commit_instruction,
+        schedule_commit_undelegate_and_close_instruction,
+        schedule_commit_with_authority_records_instruction,
+        schedule_commit_with_compute_unit_price_instruction,
+        schedule_commit_with_memo_instruction,
+        schedule_commit_with_priority_instruction,
+        schedule_transfer_intent_instruction,
+        settle_transfer_intent_instruction, BaseLayerAction,
+        BaseLayerActionAccount, CommitPriority, CommitTarget,
+        MagicBlockInstruction, RecurringCommitEnd, TransferIntent,
+        MAX_COMMIT_MEMO_LEN,
     },
     schedule_transactions::transaction_scheduler::TransactionScheduler,
-    test_utils::{ensure_started_validator, process_instruction},
+    test_utils::{
+        ensure_started_validator, process_instruction, AUTHORITY_BALANCE,
+    },
     utils::DELEGATION_PROGRAM_ID,
-    ScheduledCommit,
+    validator::{validator_authority_id, ScheduleCommitFees},
+    CommitAuthorityRecord, ScheduledCommit,
 };
 
 // For the scheduling itself and the debit to fund the scheduled transaction
@@ -225,6 +250,14 @@ fn assert_first_commit(
             blockhash: _,
             commit_sent_transaction,
             request_undelegation,
+            execute_at_slot,
+            base_layer_action,
+            compute_unit_price,
+            close_accounts,
+            transfer_intent,
+            atomic,
+            memo,
+            priority,
         } => {
             assert!(id >= &0);
             assert_eq!(slot, &test_clock.slot);
@@ -233,6 +266,14 @@ fn assert_first_commit(
             let instruction = MagicBlockInstruction::ScheduledCommitSent(*id);
             assert_eq!(commit_sent_transaction.data(0), instruction.try_to_vec().unwrap());
             assert_eq!(*request_undelegation, expected_request_undelegation);
+            assert_eq!(execute_at_slot, &None);
+            assert_eq!(base_layer_action, &None);
+            assert_eq!(compute_unit_price, &None);
+            assert!(!close_accounts);
+            assert_eq!(transfer_intent, &None);
+            assert!(!atomic);
+            assert_eq!(memo, &None);
+            assert_eq!(priority, &CommitPriority::Normal);
         }
     );
 }
@@ -634,9 +675,10 @@ fn account_metas_last_committee_not_signer(
 fn instruction_from_account_metas(
     account_metas: Vec<AccountMeta>,
 ) -> solana_sdk::instruction::Instruction {
-    Instruction::new_with_bincode(
-        crate::id(),
-        &MagicBlockInstruction::ScheduleCommit,
+    into_instruction(
+        &MagicBlockInstruction::ScheduleCommit {
+            priority: CommitPriority::Normal,
+        },
         account_metas,
     )
 }
@@ -713,3 +755,1701 @@ fn test_schedule_commit_three_accounts_second_not_owned_by_program_and_not_signe
         Err(InstructionError::InvalidAccountOwner),
     );
 }
+
+#[test]
+fn test_schedule_commit_single_account_with_delay_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_single_account_with_delay")
+            .unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    // 1. We run the transaction that registers the intent to schedule a commit
+    let (processed_scheduled, magic_context_acc) = {
+        let (mut account_data, mut transaction_accounts) =
+            prepare_transaction_with_single_committee(
+                &payer, program, committee,
+            );
+
+        let ix = schedule_commit_at_instruction(
+            &payer.pubkey(),
+            vec![committee],
+            CommitTarget::Delay(5),
+            false,
+        );
+
+        extend_transaction_accounts_from_ix(
+            &ix,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+
+        let processed_scheduled = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts.clone(),
+            ix.accounts,
+            Ok(()),
+        );
+
+        let magic_context_acc = assert_non_accepted_commits(
+            &processed_scheduled,
+            &payer.pubkey(),
+            1,
+        );
+
+        (processed_scheduled.clone(), magic_context_acc.clone())
+    };
+
+    // 2. We run the transaction that accepts the scheduled commit
+    {
+        let (mut account_data, mut transaction_accounts) =
+            prepare_transaction_with_single_committee(
+                &payer, program, committee,
+            );
+
+        let ix = accept_scheduled_commits_instruction();
+        extend_transaction_accounts_from_ix_adding_magic_context(
+            &ix,
+            &magic_context_acc,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+
+        let processed_accepted = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+
+        // The commit is accepted, but only due once the delay passed
+        let scheduled_commits =
+            assert_accepted_commits(&processed_accepted, &payer.pubkey(), 1);
+        let commit = &scheduled_commits[0];
+        let test_clock = get_clock();
+        assert_eq!(commit.execute_at_slot, Some(test_clock.slot + 5));
+        assert!(!commit.is_due(test_clock.slot + 4));
+        assert!(commit.is_due(test_clock.slot + 5));
+    }
+    // Accounts are not locked when only committing
+    let committed_account = processed_scheduled.last().unwrap();
+    assert_eq!(*committed_account.owner(), program);
+}
+
+#[test]
+fn test_schedule_commit_with_compute_unit_price_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_with_compute_unit_price").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_with_compute_unit_price_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        5_000_000,
+        false,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let commit = &magic_context.scheduled_commits[0];
+    assert_eq!(commit.compute_unit_price, Some(5_000_000));
+    assert!(!commit.request_undelegation);
+}
+
+#[test]
+fn test_schedule_atomic_commit_three_accounts_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_atomic_commit_three_accounts").unwrap();
+    let PreparedTransactionThreeCommittees {
+        mut accounts_data,
+        committee_uno,
+        committee_dos,
+        committee_tres,
+        mut transaction_accounts,
+        ..
+    } = prepare_transaction_with_three_committees(&payer, None);
+
+    let ix = schedule_atomic_commit_instruction(
+        &payer.pubkey(),
+        vec![committee_uno, committee_dos, committee_tres],
+        false,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut accounts_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let commit = &magic_context.scheduled_commits[0];
+    assert!(commit.atomic);
+    assert!(!commit.request_undelegation);
+    assert_eq!(
+        commit
+            .accounts
+            .iter()
+            .map(|account| account.pubkey)
+            .collect::<Vec<_>>(),
+        vec![committee_uno, committee_dos, committee_tres]
+    );
+}
+
+#[test]
+fn test_schedule_commit_with_memo_success() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"schedule_commit_with_memo").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_with_memo_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        "match:42".to_string(),
+        false,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    assert_eq!(
+        magic_context.scheduled_commits[0].memo,
+        Some("match:42".to_string())
+    );
+}
+
+#[test]
+fn test_schedule_commit_with_too_long_memo_fails() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"schedule_commit_with_long_memo").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_with_memo_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        "x".repeat(MAX_COMMIT_MEMO_LEN + 1),
+        false,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+/// Prepares committing an account owned by the invoking program together with
+/// one owned by another program which created an authority record for it.
+/// The record authorizes the invoking program unless `authorized_program` is
+/// provided.
+/// Returns the committees paired with their authority records
+fn prepare_transaction_with_authority_record(
+    payer: &Keypair,
+    authorized_program: Option<Pubkey>,
+) -> (
+    HashMap<Pubkey, AccountSharedData>,
+    Vec<(Pubkey, AccountSharedData)>,
+    Vec<(Pubkey, Pubkey)>,
+) {
+    let program = Pubkey::new_unique();
+    let foreign_program = Pubkey::new_unique();
+    let owned_committee = Pubkey::new_unique();
+    let foreign_committee = Pubkey::new_unique();
+
+    let (mut account_data, transaction_accounts) =
+        prepare_transaction_with_single_committee(
+            payer,
+            program,
+            owned_committee,
+        );
+    account_data.insert(
+        foreign_committee,
+        AccountSharedData::new(0, 0, &foreign_program),
+    );
+
+    // The account owned by the invoking program does not need a record
+    let unused_record = Pubkey::new_unique();
+    account_data.insert(
+        unused_record,
+        AccountSharedData::new(0, 0, &system_program::id()),
+    );
+
+    let authorized_program = authorized_program.unwrap_or(program);
+    let (record_pubkey, bump) = CommitAuthorityRecord::find_address(
+        &foreign_committee,
+        &authorized_program,
+        &foreign_program,
+    );
+    let record = CommitAuthorityRecord {
+        delegated_account: foreign_committee,
+        authorized_program,
+        bump,
+    };
+    let mut record_acc = AccountSharedData::new(
+        0,
+        CommitAuthorityRecord::SIZE,
+        &foreign_program,
+    );
+    record_acc.set_data_from_slice(&record.to_bytes());
+    account_data.insert(record_pubkey, record_acc);
+
+    (
+        account_data,
+        transaction_accounts,
+        vec![
+            (owned_committee, unused_record),
+            (foreign_committee, record_pubkey),
+        ],
+    )
+}
+
+#[test]
+fn test_schedule_commit_with_authority_records_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_with_authority_records").unwrap();
+    let (mut account_data, mut transaction_accounts, pdas) =
+        prepare_transaction_with_authority_record(&payer, None);
+    let foreign_owner = *account_data[&pdas[1].0].owner();
+
+    let ix = schedule_commit_with_authority_records_instruction(
+        &payer.pubkey(),
+        pdas.clone(),
+        false,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let accounts = &magic_context.scheduled_commits[0].accounts;
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0].pubkey, pdas[0].0);
+    // The account is committed on behalf of its actual owner
+    assert_eq!(
+        accounts[1],
+        CommittedAccount {
+            pubkey: pdas[1].0,
+            owner: foreign_owner,
+        }
+    );
+}
+
+#[test]
+fn test_schedule_commit_with_record_for_other_program_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_with_other_authority").unwrap();
+    let (mut account_data, mut transaction_accounts, pdas) =
+        prepare_transaction_with_authority_record(
+            &payer,
+            Some(Pubkey::new_unique()),
+        );
+
+    let ix = schedule_commit_with_authority_records_instruction(
+        &payer.pubkey(),
+        pdas,
+        false,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InvalidAccountOwner),
+    );
+}
+
+#[test]
+fn test_schedule_commit_with_missing_authority_record_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_with_missing_record").unwrap();
+    let (mut account_data, mut transaction_accounts, pdas) =
+        prepare_transaction_with_authority_record(&payer, None);
+
+    let mut ix = schedule_commit_with_authority_records_instruction(
+        &payer.pubkey(),
+        pdas,
+        false,
+    );
+    // Drop the record of the foreign account
+    ix.accounts.pop();
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::NotEnoughAccountKeys),
+    );
+}
+
+#[test]
+fn test_schedule_commit_with_priority_success() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"schedule_commit_with_priority").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_with_priority_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        CommitPriority::High,
+        true,
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let commit = &magic_context.scheduled_commits[0];
+    assert_eq!(commit.priority, CommitPriority::High);
+    assert!(commit.request_undelegation);
+}
+
+#[test]
+fn test_schedule_commit_single_account_at_passed_slot_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_at_passed_slot_fails").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_at_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        CommitTarget::Slot(get_clock().slot - 1),
+        false,
+    );
+
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+#[test]
+fn test_register_recurring_commit_schedules_commits_when_due() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"register_recurring_commit_when_due").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    // 1. We run the transaction that registers the recurring commit
+    let magic_context_acc = {
+        let (mut account_data, mut transaction_accounts) =
+            prepare_transaction_with_single_committee(
+                &payer, program, committee,
+            );
+
+        let ix = register_recurring_commit_instruction(
+            &payer.pubkey(),
+            vec![committee],
+            10,
+            RecurringCommitEnd::AfterCommits(1),
+        );
+        extend_transaction_accounts_from_ix(
+            &ix,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+
+        let processed_registered = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+
+        // The recurring commit is stored, but no commit is scheduled yet
+        let magic_context_acc = assert_non_accepted_commits(
+            &processed_registered,
+            &payer.pubkey(),
+            0,
+        );
+        let magic_context =
+            bincode::deserialize::<MagicContext>(magic_context_acc.data())
+                .unwrap();
+        assert_eq!(magic_context.recurring_commits.len(), 1);
+        assert_eq!(
+            magic_context.recurring_commits[0].next_slot,
+            get_clock().slot + 10
+        );
+        assert_eq!(
+            magic_context.next_recurring_commit_slot,
+            get_clock().slot + 10
+        );
+        assert!(!MagicContext::has_due_recurring_commits(
+            magic_context_acc.data(),
+            get_clock().slot + 9
+        ));
+        assert!(MagicContext::has_due_recurring_commits(
+            magic_context_acc.data(),
+            get_clock().slot + 10
+        ));
+        magic_context_acc.clone()
+    };
+
+    // 2. We accept commits once the recurring commit is due
+    {
+        let (mut account_data, mut transaction_accounts) =
+            prepare_transaction_with_single_committee(
+                &payer, program, committee,
+            );
+        transaction_accounts[0] = (
+            clock::Clock::id(),
+            create_account_shared_data_for_test(&clock::Clock {
+                slot: get_clock().slot + 10,
+                ..get_clock()
+            }),
+        );
+
+        let ix = accept_scheduled_commits_instruction();
+        extend_transaction_accounts_from_ix_adding_magic_context(
+            &ix,
+            &magic_context_acc,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+
+        let processed_accepted = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+
+        let scheduled_commits =
+            assert_accepted_commits(&processed_accepted, &payer.pubkey(), 1);
+        assert_eq!(
+            scheduled_commits[0]
+                .accounts
+                .iter()
+                .map(|ca| ca.pubkey)
+                .collect::<Vec<_>>(),
+            vec![committee]
+        );
+
+        // The recurring commit ended after its only commit
+        let magic_context_acc =
+            find_magic_context_account(&processed_accepted).unwrap();
+        let magic_context =
+            bincode::deserialize::<MagicContext>(magic_context_acc.data())
+                .unwrap();
+        assert!(magic_context.recurring_commits.is_empty());
+        assert_eq!(magic_context.next_recurring_commit_slot, 0);
+        assert!(!MagicContext::has_due_recurring_commits(
+            magic_context_acc.data(),
+            u64::MAX
+        ));
+    }
+}
+
+#[test]
+fn test_register_recurring_commit_without_interval_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"register_recurring_commit_no_interval").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = register_recurring_commit_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        0,
+        RecurringCommitEnd::AfterCommits(3),
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+fn register_recurring_commit(
+    payer: &Keypair,
+    program: Pubkey,
+    committee: Pubkey,
+    magic_context_acc: Option<&AccountSharedData>,
+) -> AccountSharedData {
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(payer, program, committee);
+
+    let ix = register_recurring_commit_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        10,
+        RecurringCommitEnd::AfterCommits(3),
+    );
+    match magic_context_acc {
+        Some(magic_context_acc) => {
+            extend_transaction_accounts_from_ix_adding_magic_context(
+                &ix,
+                magic_context_acc,
+                &mut account_data,
+                &mut transaction_accounts,
+            )
+        }
+        None => extend_transaction_accounts_from_ix(
+            &ix,
+            &mut account_data,
+            &mut transaction_accounts,
+        ),
+    }
+
+    let processed_registered = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+    find_magic_context_account(&processed_registered)
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn test_register_recurring_commits_assigns_ids_from_magic_context() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"register_recurring_commits_assigns_ids").unwrap();
+    let program = Pubkey::new_unique();
+
+    let magic_context_acc =
+        register_recurring_commit(&payer, program, Pubkey::new_unique(), None);
+    let magic_context_acc = register_recurring_commit(
+        &payer,
+        program,
+        Pubkey::new_unique(),
+        Some(&magic_context_acc),
+    );
+
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    assert_eq!(
+        magic_context
+            .recurring_commits
+            .iter()
+            .map(|commit| commit.id)
+            .collect::<Vec<_>>(),
+        vec![0, 1]
+    );
+    assert_eq!(magic_context.next_recurring_commit_id, 2);
+}
+
+#[test]
+fn test_cancel_recurring_commit_success() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"cancel_recurring_commit_success").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let magic_context_acc =
+        register_recurring_commit(&payer, program, committee, None);
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+    let ix = cancel_recurring_commit_instruction(&payer.pubkey(), 0);
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_cancelled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+    let magic_context_acc =
+        find_magic_context_account(&processed_cancelled).unwrap();
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    assert!(magic_context.recurring_commits.is_empty());
+    assert_eq!(magic_context.next_recurring_commit_id, 1);
+    assert!(!MagicContext::has_due_recurring_commits(
+        magic_context_acc.data(),
+        u64::MAX
+    ));
+}
+
+#[test]
+fn test_cancel_recurring_commit_of_other_payer_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"cancel_recurring_commit_other_payer").unwrap();
+    let other_payer = Keypair::new();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let magic_context_acc =
+        register_recurring_commit(&payer, program, committee, None);
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(
+            &other_payer,
+            program,
+            committee,
+        );
+    let ix = cancel_recurring_commit_instruction(&other_payer.pubkey(), 0);
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::MissingRequiredSignature),
+    );
+}
+
+#[test]
+fn test_schedule_commit_and_undelegate_removes_recurring_commit() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"undelegate_removes_recurring_commit").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let magic_context_acc =
+        register_recurring_commit(&payer, program, committee, None);
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+    let ix = schedule_commit_and_undelegate_instruction(
+        &payer.pubkey(),
+        vec![committee],
+    );
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    assert!(magic_context.recurring_commits.is_empty());
+    assert_eq!(magic_context.next_recurring_commit_slot, 0);
+}
+
+fn schedule_single_commit(
+    payer: &Keypair,
+    program: Pubkey,
+    committee: Pubkey,
+) -> (u64, AccountSharedData) {
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(payer, program, committee);
+
+    let ix = schedule_commit_instruction(&payer.pubkey(), vec![committee]);
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    (
+        magic_context.scheduled_commits[0].id,
+        magic_context_acc.clone(),
+    )
+}
+
+#[test]
+fn test_schedule_commit_fees() {
+    let fees = ScheduleCommitFees {
+        flat: 5_000,
+        per_account: 1_000,
+    };
+    assert_eq!(fees.fee(1, 3), Some(8_000));
+    assert_eq!(fees.fee(4, 1), Some(24_000));
+    assert_eq!(fees.fee(u64::MAX, 1), None);
+    assert_eq!(ScheduleCommitFees::default().fee(10, 10), Some(0));
+}
+
+#[test]
+fn test_accept_scheduled_commits_claims_collected_fees() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"accept_scheduled_commits_claims_fees").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+    const FEES: u64 = 7_000;
+
+    // Fees charged by the scheduling transaction are held by the context
+    let (_, mut magic_context_acc) =
+        schedule_single_commit(&payer, program, committee);
+    let mut magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    magic_context.collect_fee(FEES);
+    magic_context_acc.serialize_data(&magic_context).unwrap();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+    let ix = accept_scheduled_commits_instruction();
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+    let processed_accepted = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let validator_authority_acc = &processed_accepted[1];
+    let magic_context_acc = &processed_accepted[2];
+    assert_eq!(validator_authority_acc.lamports(), AUTHORITY_BALANCE + FEES);
+    assert_eq!(magic_context_acc.lamports(), u64::MAX - FEES);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    assert_eq!(magic_context.collected_fees, 0);
+    assert!(magic_context.scheduled_commits.is_empty());
+}
+
+#[test]
+fn test_cancel_scheduled_commit_success() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"cancel_scheduled_commit_success").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (commit_id, magic_context_acc) =
+        schedule_single_commit(&payer, program, committee);
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+    let ix = cancel_scheduled_commit_instruction(&payer.pubkey(), commit_id);
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_cancelled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+    assert_non_accepted_commits(&processed_cancelled, &payer.pubkey(), 0);
+}
+
+#[test]
+fn test_cancel_scheduled_commit_of_other_payer_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"cancel_scheduled_commit_other_payer").unwrap();
+    let other_payer = Keypair::new();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (commit_id, magic_context_acc) =
+        schedule_single_commit(&payer, program, committee);
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(
+            &other_payer,
+            program,
+            committee,
+        );
+    let ix =
+        cancel_scheduled_commit_instruction(&other_payer.pubkey(), commit_id);
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::MissingRequiredSignature),
+    );
+}
+
+fn settle_action(program: Pubkey, committee: Pubkey) -> BaseLayerAction {
+    BaseLayerAction {
+        program_id: program,
+        accounts: vec![BaseLayerActionAccount {
+            pubkey: committee,
+            is_writable: true,
+        }],
+        data: vec![1, 2, 3],
+    }
+}
+
+#[test]
+fn test_schedule_commit_and_undelegate_with_action_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_undelegate_with_action").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_and_undelegate_with_action_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        settle_action(program, committee),
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let commit = &magic_context.scheduled_commits[0];
+    assert!(commit.request_undelegation);
+    assert_eq!(
+        commit.base_layer_action,
+        Some(settle_action(program, committee))
+    );
+    let committed_account = processed_scheduled.last().unwrap();
+    assert_eq!(*committed_account.owner(), DELEGATION_PROGRAM_ID);
+}
+
+#[test]
+fn test_schedule_commit_and_undelegate_with_action_of_other_program_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"commit_undelegate_with_foreign_action").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_and_undelegate_with_action_instruction(
+        &payer.pubkey(),
+        vec![committee],
+        settle_action(Pubkey::new_unique(), committee),
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+#[test]
+fn test_schedule_commit_into_full_magic_context_grows_it() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_into_full_magic_context").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    // A recurring commit that leaves no room for another scheduled commit
+    let full_magic_context_acc = {
+        let committed_account = CommittedAccount {
+            pubkey: Pubkey::new_unique(),
+            owner: program,
+        };
+        let magic_context = MagicContext {
+            next_recurring_commit_slot: u64::MAX,
+            scheduled_commits: vec![],
+            recurring_commits: vec![RecurringCommit {
+                id: u64::MAX,
+                accounts: vec![
+                    committed_account;
+                    (MagicContext::SIZE - 256) / 64
+                ],
+                payer: payer.pubkey(),
+                interval_slots: 1,
+                next_slot: u64::MAX,
+                end: RecurringCommitEnd::AfterCommits(1),
+                executed_commits: 0,
+            }],
+            collected_fees: 0,
+            next_recurring_commit_id: 1,
+            pending_transfer_intents: vec![],
+        };
+        let mut acc =
+            AccountSharedData::new(u64::MAX, MagicContext::SIZE, &crate::id());
+        acc.serialize_data(&magic_context).unwrap();
+        acc
+    };
+
+    // 1. Scheduling the commit grows the magic context account
+    let magic_context_acc = {
+        let (mut account_data, mut transaction_accounts) =
+            prepare_transaction_with_single_committee(
+                &payer, program, committee,
+            );
+        let ix = schedule_commit_instruction(&payer.pubkey(), vec![committee]);
+        extend_transaction_accounts_from_ix_adding_magic_context(
+            &ix,
+            &full_magic_context_acc,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+
+        let processed_scheduled = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+        let magic_context_acc = assert_non_accepted_commits(
+            &processed_scheduled,
+            &payer.pubkey(),
+            1,
+        );
+        assert!(magic_context_acc.data().len() > MagicContext::SIZE);
+        magic_context_acc.clone()
+    };
+
+    // 2. Accepting the commit shrinks it back to its regular size
+    {
+        let (mut account_data, mut transaction_accounts) =
+            prepare_transaction_with_single_committee(
+                &payer, program, committee,
+            );
+        let ix = accept_scheduled_commits_instruction();
+        extend_transaction_accounts_from_ix_adding_magic_context(
+            &ix,
+            &magic_context_acc,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+
+        let processed_accepted = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+        let scheduled_commits =
+            assert_accepted_commits(&processed_accepted, &payer.pubkey(), 1);
+        assert_first_commit(
+            &scheduled_commits,
+            &payer.pubkey(),
+            &[committee],
+            false,
+        );
+        let magic_context_acc =
+            find_magic_context_account(&processed_accepted).unwrap();
+        assert_eq!(magic_context_acc.data().len(), MagicContext::SIZE);
+    }
+}
+
+#[test]
+fn test_schedule_commit_undelegate_and_close_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_commit_undelegate_and_close").unwrap();
+    let program = Pubkey::new_unique();
+    let committee = Pubkey::new_unique();
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(&payer, program, committee);
+
+    let ix = schedule_commit_undelegate_and_close_instruction(
+        &payer.pubkey(),
+        vec![committee],
+    );
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let commit = &magic_context.scheduled_commits[0];
+    assert!(commit.request_undelegation);
+    assert!(commit.close_accounts);
+
+    // The account is locked until it is undelegated and closed
+    let committed_account = processed_scheduled.last().unwrap();
+    assert_eq!(*committed_account.owner(), DELEGATION_PROGRAM_ID);
+}
+
+// Leaves room for the lamports the context holds, see `fund_magic_context`
+const MAGIC_CONTEXT_LAMPORTS: u64 = u64::MAX / 2;
+
+fn prepare_transfer_intent(
+    payer: &Keypair,
+    escrow_lamports: u64,
+    intent: TransferIntent,
+) -> (Instruction, Vec<(Pubkey, AccountSharedData)>) {
+    let program = Pubkey::new_unique();
+    let escrow = Pubkey::new_unique();
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(payer, program, escrow);
+    account_data
+        .insert(escrow, AccountSharedData::new(escrow_lamports, 0, &program));
+    account_data.insert(
+        MAGIC_CONTEXT_PUBKEY,
+        AccountSharedData::new(
+            MAGIC_CONTEXT_LAMPORTS,
+            MagicContext::SIZE,
+            &crate::id(),
+        ),
+    );
+
+    let ix =
+        schedule_transfer_intent_instruction(&payer.pubkey(), escrow, intent);
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+    (ix, transaction_accounts)
+}
+
+/// Schedules the transfer intent from an escrow holding 1_000 lamports,
+/// returns the ID of the commit paying it out, the escrow and the updated
+/// magic context
+fn schedule_transfer_intent(
+    payer: &Keypair,
+    intent: TransferIntent,
+) -> (u64, Pubkey, AccountSharedData) {
+    let (ix, transaction_accounts) =
+        prepare_transfer_intent(payer, 1_000, intent);
+    let escrow = ix.accounts[2].pubkey;
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1)
+            .clone();
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    (
+        magic_context.scheduled_commits[0].id,
+        escrow,
+        magic_context_acc,
+    )
+}
+
+/// Processes the settlement of the transfer intent held by the magic context
+/// with the escrow holding 600 lamports, returns the updated accounts in the
+/// order validator authority, magic context and escrow
+fn process_settle_transfer_intent(
+    ix: Instruction,
+    magic_context_acc: AccountSharedData,
+    expected_result: Result<(), InstructionError>,
+) -> Vec<AccountSharedData> {
+    let mut account_data = HashMap::new();
+    ensure_started_validator(&mut account_data);
+    account_data.insert(MAGIC_CONTEXT_PUBKEY, magic_context_acc);
+    account_data.insert(
+        ix.accounts[2].pubkey,
+        AccountSharedData::new(600, 0, &Pubkey::new_unique()),
+    );
+    let mut transaction_accounts = vec![];
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        expected_result,
+    )
+}
+
+#[test]
+fn test_schedule_transfer_intent_success() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_transfer_intent_success").unwrap();
+    let intent = TransferIntent {
+        recipient: Pubkey::new_unique(),
+        lamports: 400,
+    };
+    let (ix, transaction_accounts) =
+        prepare_transfer_intent(&payer, 1_000, intent.clone());
+    let escrow = ix.accounts[2].pubkey;
+
+    let processed_scheduled = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+
+    let magic_context_acc =
+        assert_non_accepted_commits(&processed_scheduled, &payer.pubkey(), 1);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    let commit = &magic_context.scheduled_commits[0];
+    assert_eq!(commit.transfer_intent, Some(intent));
+    assert!(!commit.request_undelegation);
+
+    // The lamports are held by the context until the intent is settled
+    let escrow_acc = processed_scheduled.last().unwrap();
+    assert_eq!(escrow_acc.lamports(), 600);
+    assert_eq!(
+        magic_context.pending_transfer_intents,
+        vec![PendingTransferIntent {
+            commit_id: commit.id,
+            escrow,
+            lamports: 400,
+        }]
+    );
+    assert_eq!(
+        magic_context_acc.lamports(),
+        MAGIC_CONTEXT_LAMPORTS + 400 + magic_context.collected_fees
+    );
+}
+
+#[test]
+fn test_schedule_transfer_intent_exceeding_escrow_fails() {
+    init_logger!();
+    let payer =
+        Keypair::from_seed(b"schedule_transfer_intent_exceeding").unwrap();
+    let intent = TransferIntent {
+        recipient: Pubkey::new_unique(),
+        lamports: 1_001,
+    };
+    let (ix, transaction_accounts) =
+        prepare_transfer_intent(&payer, 1_000, intent);
+
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InsufficientFunds),
+    );
+}
+
+#[test]
+fn test_settle_failed_transfer_intent_refunds_escrow() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"settle_failed_transfer_intent").unwrap();
+    let intent = TransferIntent {
+        recipient: Pubkey::new_unique(),
+        lamports: 400,
+    };
+    let (commit_id, escrow, magic_context_acc) =
+        schedule_transfer_intent(&payer, intent);
+    let magic_context_lamports = magic_context_acc.lamports();
+
+    let ix = settle_transfer_intent_instruction(commit_id, escrow, false);
+    let processed =
+        process_settle_transfer_intent(ix.clone(), magic_context_acc, Ok(()));
+    let [validator_authority_acc, magic_context_acc, escrow_acc] =
+        processed.as_slice()
+    else {
+        panic!("unexpected accounts: {:?}", processed);
+    };
+    assert_eq!(escrow_acc.lamports(), 1_000);
+    assert_eq!(validator_authority_acc.lamports(), AUTHORITY_BALANCE);
+    assert_eq!(magic_context_acc.lamports(), magic_context_lamports - 400);
+    let magic_context =
+        bincode::deserialize::<MagicContext>(magic_context_acc.data()).unwrap();
+    assert!(magic_context.pending_transfer_intents.is_empty());
+    // The scheduled commit is not affected
+    assert_eq!(magic_context.scheduled_commits.len(), 1);
+
+    // The intent is settled only once
+    process_settle_transfer_intent(
+        ix,
+        magic_context_acc.clone(),
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+#[test]
+fn test_settle_paid_out_transfer_intent_releases_lamports() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"settle_paid_out_transfer_intent").unwrap();
+    let intent = TransferIntent {
+        recipient: Pubkey::new_unique(),
+        lamports: 400,
+    };
+    let (commit_id, escrow, magic_context_acc) =
+        schedule_transfer_intent(&payer, intent);
+    let magic_context_lamports = magic_context_acc.lamports();
+
+    let processed = process_settle_transfer_intent(
+        settle_transfer_intent_instruction(commit_id, escrow, true),
+        magic_context_acc,
+        Ok(()),
+    );
+    let [validator_authority_acc, magic_context_acc, escrow_acc] =
+        processed.as_slice()
+    else {
+        panic!("unexpected accounts: {:?}", processed);
+    };
+    assert_eq!(escrow_acc.lamports(), 600);
+    assert_eq!(validator_authority_acc.lamports(), AUTHORITY_BALANCE + 400);
+    assert_eq!(magic_context_acc.lamports(), magic_context_lamports - 400);
+}
+
+#[test]
+fn test_settle_transfer_intent_with_invalid_accounts_fails() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"settle_transfer_intent_invalid").unwrap();
+    let intent = TransferIntent {
+        recipient: Pubkey::new_unique(),
+        lamports: 400,
+    };
+    let (commit_id, escrow, magic_context_acc) =
+        schedule_transfer_intent(&payer, intent);
+
+    // Refunding to another account than the escrow
+    process_settle_transfer_intent(
+        settle_transfer_intent_instruction(
+            commit_id,
+            Pubkey::new_unique(),
+            false,
+        ),
+        magic_context_acc.clone(),
+        Err(InstructionError::InvalidArgument),
+    );
+    // Settling a commit without transfer intent
+    process_settle_transfer_intent(
+        settle_transfer_intent_instruction(commit_id + 1, escrow, false),
+        magic_context_acc.clone(),
+        Err(InstructionError::InvalidArgument),
+    );
+    // Settling without the validator authority's signature
+    let mut ix = settle_transfer_intent_instruction(commit_id, escrow, true);
+    ix.accounts[0].is_signer = false;
+    process_settle_transfer_intent(
+        ix,
+        magic_context_acc,
+        Err(InstructionError::MissingRequiredSignature),
+    );
+}
+
+#[test]
+fn test_cancel_transfer_intent_fails() {
+    init_logger!();
+    let payer = Keypair::from_seed(b"cancel_transfer_intent").unwrap();
+    let intent = TransferIntent {
+        recipient: Pubkey::new_unique(),
+        lamports: 400,
+    };
+    let (commit_id, _, magic_context_acc) =
+        schedule_transfer_intent(&payer, intent);
+
+    let (mut account_data, mut transaction_accounts) =
+        prepare_transaction_with_single_committee(
+            &payer,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+    let ix = cancel_scheduled_commit_instruction(&payer.pubkey(), commit_id);
+    extend_transaction_accounts_from_ix_adding_magic_context(
+        &ix,
+        &magic_context_acc,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+#[test]
+fn test_record_commit_event_appends_to_log() {
+    init_logger!();
+    let mut account_data = {
+        let mut map = HashMap::new();
+        map.insert(
+            MAGIC_COMMIT_EVENTS_PUBKEY,
+            AccountSharedData::new(
+                u64::MAX,
+                CommitEventLog::SIZE,
+                &crate::id(),
+            ),
+        );
+        map
+    };
+    ensure_started_validator(&mut account_data);
+
+    let record = CommitRecord {
+        commit_id: 42,
+        slot: 90,
+        payer: Pubkey::new_unique(),
+        included_pubkeys: vec![Pubkey::new_unique()],
+        excluded_pubkeys: vec![],
+        requested_undelegation: false,
+        chain_signatures: vec![],
+        outcome: CommitOutcome::Pending,
+    };
+
+    let mut commit_events_acc = None;
+    for outcome in [CommitOutcome::Pending, CommitOutcome::Confirmed] {
+        let ix = record_commit_event_instruction(CommitRecord {
+            outcome,
+            ..record.clone()
+        });
+        let mut transaction_accounts = vec![(
+            clock::Clock::id(),
+            create_account_shared_data_for_test(&get_clock()),
+        )];
+        extend_transaction_accounts_from_ix(
+            &ix,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+        let processed = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+        let acc = processed.last().unwrap().clone();
+        // Provide the updated account to the next instruction
+        account_data.insert(MAGIC_COMMIT_EVENTS_PUBKEY, acc.clone());
+        account_data.insert(validator_authority_id(), processed[1].clone());
+        commit_events_acc = Some(acc);
+    }
+
+    let commit_events_acc = commit_events_acc.unwrap();
+    assert_eq!(commit_events_acc.data().len(), CommitEventLog::SIZE);
+    let log = CommitEventLog::try_from_data(commit_events_acc.data()).unwrap();
+    assert_eq!(log.next_seq, 2);
+    assert_eq!(log.events.len(), 2);
+    assert_eq!(log.events[0].seq, 0);
+    assert_eq!(log.events[0].record, record);
+    assert_eq!(log.events[1].seq, 1);
+    assert_eq!(log.events[1].recorded_at_slot, get_clock().slot);
+    assert_eq!(log.events[1].record.outcome, CommitOutcome::Confirmed);
+}
+
+#[test]
+fn test_record_commit_event_without_validator_signature_fails() {
+    init_logger!();
+    let mut account_data = HashMap::new();
+    ensure_started_validator(&mut account_data);
+
+    let mut ix = record_commit_event_instruction(CommitRecord {
+        commit_id: 1,
+        slot: 90,
+        payer: Pubkey::new_unique(),
+        included_pubkeys: vec![],
+        excluded_pubkeys: vec![],
+        requested_undelegation: false,
+        chain_signatures: vec![],
+        outcome: CommitOutcome::NotNeeded,
+    });
+    ix.accounts[0].is_signer = false;
+    let transaction_accounts = vec![
+        (
+            clock::Clock::id(),
+            create_account_shared_data_for_test(&get_clock()),
+        ),
+        (
+            validator_authority_id(),
+            account_data.remove(&validator_authority_id()).unwrap(),
+        ),
+        (
+            MAGIC_COMMIT_EVENTS_PUBKEY,
+            AccountSharedData::new(
+                u64::MAX,
+                CommitEventLog::SIZE,
+                &crate::id(),
+            ),
+        ),
+    ];
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::MissingRequiredSignature),
+    );
+}
+
+#[test]
+fn test_record_commit_event_tracks_undelegation_status() {
+    init_logger!();
+    let mut account_data = {
+        let mut map = HashMap::new();
+        map.insert(
+            MAGIC_COMMIT_EVENTS_PUBKEY,
+            AccountSharedData::new(
+                u64::MAX,
+                CommitEventLog::SIZE,
+                &crate::id(),
+            ),
+        );
+        map
+    };
+    ensure_started_validator(&mut account_data);
+
+    let undelegated = Pubkey::new_unique();
+    let excluded = Pubkey::new_unique();
+    let record = CommitRecord {
+        commit_id: 7,
+        slot: 90,
+        payer: Pubkey::new_unique(),
+        included_pubkeys: vec![undelegated],
+        excluded_pubkeys: vec![excluded],
+        requested_undelegation: true,
+        chain_signatures: vec![],
+        outcome: CommitOutcome::Pending,
+    };
+
+    let mut statuses = vec![];
+    for outcome in [CommitOutcome::Pending, CommitOutcome::Finalized] {
+        let ix = record_commit_event_instruction(CommitRecord {
+            outcome,
+            ..record.clone()
+        });
+        let mut transaction_accounts = vec![(
+            clock::Clock::id(),
+            create_account_shared_data_for_test(&get_clock()),
+        )];
+        extend_transaction_accounts_from_ix(
+            &ix,
+            &mut account_data,
+            &mut transaction_accounts,
+        );
+        let processed = process_instruction(
+            ix.data.as_slice(),
+            transaction_accounts,
+            ix.accounts,
+            Ok(()),
+        );
+        let acc = processed.last().unwrap().clone();
+        let log = CommitEventLog::try_from_data(acc.data()).unwrap();
+        statuses.push((
+            log.undelegation_status(&undelegated),
+            log.undelegation_status(&excluded),
+        ));
+        // Provide the updated account to the next instruction
+        account_data.insert(MAGIC_COMMIT_EVENTS_PUBKEY, acc);
+        account_data.insert(validator_authority_id(), processed[1].clone());
+    }
+    assert_eq!(
+        statuses,
+        vec![
+            (UndelegationStatus::Pending, UndelegationStatus::Failed),
+            (UndelegationStatus::Finalized, UndelegationStatus::Failed),
+        ]
+    );
+
+    let log = CommitEventLog::try_from_data(
+        account_data[&MAGIC_COMMIT_EVENTS_PUBKEY].data(),
+    )
+    .unwrap();
+    assert_eq!(log.undelegations.len(), 2);
+    let entry = log.undelegation(&undelegated).unwrap();
+    assert_eq!(entry.commit_id, 7);
+    assert_eq!(entry.updated_at_slot, get_clock().slot);
+    assert_eq!(
+        log.undelegation_status(&Pubkey::new_unique()),
+        UndelegationStatus::Unknown
+    );
+
+    // The status can be queried via the instruction as well
+    let ix = get_undelegation_status_instruction(undelegated);
+    let mut transaction_accounts = vec![];
+    extend_transaction_accounts_from_ix(
+        &ix,
+        &mut account_data,
+        &mut transaction_accounts,
+    );
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Ok(()),
+    );
+}
+
+#[test]
+fn test_get_undelegation_status_with_invalid_account_fails() {
+    init_logger!();
+    let mut ix = get_undelegation_status_instruction(Pubkey::new_unique());
+    let fake_commit_events = Pubkey::new_unique();
+    ix.accounts[0].pubkey = fake_commit_events;
+    let transaction_accounts = vec![(
+        fake_commit_events,
+        AccountSharedData::new(u64::MAX, CommitEventLog::SIZE, &crate::id()),
+    )];
+    process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        Err(InstructionError::MissingAccount),
+    );
+}
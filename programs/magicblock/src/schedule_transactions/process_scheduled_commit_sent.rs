@@ -28,6 +28,13 @@ pub struct SentCommit {
     pub excluded_pubkeys: Vec<Pubkey>,
     pub feepayers: HashSet<FeePayerAccount>,
     pub requested_undelegation: bool,
+    /// Why simulating commit transactions failed, those were not sent
+    pub simulation_errors: Vec<String>,
+    /// Whether all accounts were committed in the same transaction
+    pub atomic: bool,
+    /// Why the accounts of an atomic commit could not be committed together,
+    /// in that case none of them were committed
+    pub atomic_error: Option<String>,
 }
 
 /// This is a printable version of the SentCommit struct.
@@ -43,6 +50,9 @@ struct SentCommitPrintable {
     excluded_pubkeys: String,
     feepayers: String,
     requested_undelegation: bool,
+    simulation_errors: Vec<String>,
+    atomic: bool,
+    atomic_error: Option<String>,
 }
 
 impl From<SentCommit> for SentCommitPrintable {
@@ -76,6 +86,9 @@ impl From<SentCommit> for SentCommitPrintable {
                 .collect::<Vec<_>>()
                 .join(", "),
             requested_undelegation: commit.requested_undelegation,
+            simulation_errors: commit.simulation_errors,
+            atomic: commit.atomic,
+            atomic_error: commit.atomic_error,
         }
     }
 }
@@ -220,10 +233,30 @@ pub fn process_scheduled_commit_sent(
         );
     }
 
+    for (idx, err) in commit.simulation_errors.iter().enumerate() {
+        ic_msg!(
+            invoke_context,
+            "ScheduledCommitSent simulation error[{}]: {}",
+            idx,
+            err
+        );
+    }
+
     if commit.requested_undelegation {
         ic_msg!(invoke_context, "ScheduledCommitSent requested undelegation",);
     }
 
+    if commit.atomic {
+        ic_msg!(invoke_context, "ScheduledCommitSent atomic");
+    }
+    if let Some(err) = &commit.atomic_error {
+        ic_msg!(
+            invoke_context,
+            "ScheduledCommitSent atomic commit failed: {}",
+            err
+        );
+    }
+
     Ok(())
 }
 
@@ -260,6 +293,9 @@ mod tests {
             excluded_pubkeys: Default::default(),
             feepayers: Default::default(),
             requested_undelegation: false,
+            simulation_errors: Default::default(),
+            atomic: false,
+            atomic_error: None,
         }
     }
 
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{
+    account::{ReadableAccount, WritableAccount},
+    instruction::InstructionError,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    magic_context::MagicContext,
+    schedule_transactions::check_magic_context_id,
+    utils::accounts::{
+        get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
+    },
+    validator::validator_authority_id,
+};
+
+pub(crate) fn process_settle_transfer_intent(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    commit_id: u64,
+    paid_out: bool,
+) -> Result<(), InstructionError> {
+    const VALIDATOR_AUTHORITY_IDX: u16 = 0;
+    const MAGIC_CONTEXT_IDX: u16 = VALIDATOR_AUTHORITY_IDX + 1;
+    const ESCROW_IDX: u16 = MAGIC_CONTEXT_IDX + 1;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // 1. Check that the validator authority (first account) is correct and signer
+    let provided_validator_auth = get_instruction_pubkey_with_idx(
+        transaction_context,
+        VALIDATOR_AUTHORITY_IDX,
+    )?;
+    let validator_auth = validator_authority_id();
+    if !provided_validator_auth.eq(&validator_auth) {
+        ic_msg!(
+            invoke_context,
+            "SettleTransferIntent ERR: invalid validator authority {}, should be {}",
+            provided_validator_auth,
+            validator_auth
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    if !signers.contains(&validator_auth) {
+        ic_msg!(
+            invoke_context,
+            "SettleTransferIntent ERR: validator authority pubkey {} not in signers",
+            validator_auth
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+    check_magic_context_id(invoke_context, MAGIC_CONTEXT_IDX)?;
+
+    // 2. Take the intent held for the commit, which also ensures that it is
+    //    settled only once
+    let magic_context_acc = get_instruction_account_with_idx(
+        transaction_context,
+        MAGIC_CONTEXT_IDX,
+    )?;
+    let mut magic_context = MagicContext::deserialize(
+        &magic_context_acc.borrow(),
+    )
+    .map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "Failed to deserialize MagicContext: {}",
+            err
+        );
+        InstructionError::InvalidAccountData
+    })?;
+    let intent = magic_context
+        .take_pending_transfer_intent(commit_id)
+        .ok_or_else(|| {
+            ic_msg!(
+                invoke_context,
+                "SettleTransferIntent ERR: no pending transfer intent for commit {}",
+                commit_id
+            );
+            InstructionError::InvalidArgument
+        })?;
+    let escrow_pubkey =
+        get_instruction_pubkey_with_idx(transaction_context, ESCROW_IDX)?;
+    if !escrow_pubkey.eq(&intent.escrow) {
+        ic_msg!(
+            invoke_context,
+            "SettleTransferIntent ERR: invalid escrow {}, should be {}",
+            escrow_pubkey,
+            intent.escrow
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+
+    // 3. Release the held lamports to the validator authority which paid the
+    //    recipient on the base layer, otherwise refund them to the escrow
+    let beneficiary_idx = if paid_out {
+        VALIDATOR_AUTHORITY_IDX
+    } else {
+        ESCROW_IDX
+    };
+    let beneficiary_acc =
+        get_instruction_account_with_idx(transaction_context, beneficiary_idx)?;
+    let beneficiary_lamports = beneficiary_acc
+        .borrow()
+        .lamports()
+        .checked_add(intent.lamports)
+        .ok_or(InstructionError::ArithmeticOverflow)?;
+    let magic_context_lamports = magic_context_acc
+        .borrow()
+        .lamports()
+        .checked_sub(intent.lamports)
+        .ok_or(InstructionError::InsufficientFunds)?;
+    magic_context_acc
+        .borrow_mut()
+        .set_lamports(magic_context_lamports);
+    beneficiary_acc
+        .borrow_mut()
+        .set_lamports(beneficiary_lamports);
+
+    // 4. Store the context without the settled intent
+    // Zero fill account before updating data, the context shrinks and we
+    // don't want to leave the settled intent behind
+    magic_context_acc
+        .borrow_mut()
+        .set_data_from_slice(&MagicContext::ZERO);
+    magic_context
+        .store(&mut magic_context_acc.borrow_mut())
+        .map_err(|err| {
+            ic_msg!(
+                invoke_context,
+                "Failed to serialize MagicContext: {:?}",
+                err
+            );
+            err
+        })?;
+    ic_msg!(
+        invoke_context,
+        "SettleTransferIntent: {} {} lamports of commit {}",
+        if paid_out { "released" } else { "refunded" },
+        intent.lamports,
+        commit_id
+    );
+
+    Ok(())
+}
@@ -8,11 +8,13 @@ use lazy_static::lazy_static;
 use solana_log_collector::ic_msg;
 use solana_program_runtime::invoke_context::InvokeContext;
 use solana_sdk::{
-    account::AccountSharedData, account_utils::StateMut,
-    instruction::InstructionError, pubkey::Pubkey,
+    account::AccountSharedData, clock::Slot, instruction::InstructionError,
+    pubkey::Pubkey,
 };
 
-use crate::magic_context::{MagicContext, ScheduledCommit};
+use crate::magic_context::{
+    MagicContext, PendingTransferIntent, RecurringCommit, ScheduledCommit,
+};
 
 #[derive(Clone)]
 pub struct TransactionScheduler {
@@ -39,6 +41,7 @@ impl TransactionScheduler {
         invoke_context: &InvokeContext,
         context_account: &RefCell<AccountSharedData>,
         commit: ScheduledCommit,
+        fee: u64,
     ) -> Result<(), InstructionError> {
         let context_data = &mut context_account.borrow_mut();
         let mut context =
@@ -50,9 +53,46 @@ impl TransactionScheduler {
                 );
                 InstructionError::GenericError
             })?;
+        // The validator no longer owns accounts once they are undelegated
+        if commit.request_undelegation {
+            context.remove_undelegated_accounts(&commit.accounts);
+        }
+        // The escrow of a transfer intent is its only committed account
+        if let (Some(intent), [escrow]) =
+            (&commit.transfer_intent, commit.accounts.as_slice())
+        {
+            context.hold_transfer_intent(PendingTransferIntent {
+                commit_id: commit.id,
+                escrow: escrow.pubkey,
+                lamports: intent.lamports,
+            });
+        }
         context.add_scheduled_commit(commit);
-        context_data.set_state(&context)?;
-        Ok(())
+        context.collect_fee(fee);
+        store_context(invoke_context, context_data, &context)
+    }
+
+    /// Registers the recurring commit and returns the ID it was assigned
+    pub fn register_recurring_commit(
+        invoke_context: &InvokeContext,
+        context_account: &RefCell<AccountSharedData>,
+        commit: RecurringCommit,
+        fee: u64,
+    ) -> Result<u64, InstructionError> {
+        let context_data = &mut context_account.borrow_mut();
+        let mut context =
+            MagicContext::deserialize(context_data).map_err(|err| {
+                ic_msg!(
+                    invoke_context,
+                    "Failed to deserialize MagicContext: {}",
+                    err
+                );
+                InstructionError::GenericError
+            })?;
+        let id = context.add_recurring_commit(commit);
+        context.collect_fee(fee);
+        store_context(invoke_context, context_data, &context)?;
+        Ok(id)
     }
 
     pub fn accept_scheduled_commits(&self, commits: Vec<ScheduledCommit>) {
@@ -62,6 +102,13 @@ impl TransactionScheduler {
             .extend(commits);
     }
 
+    pub fn get_scheduled_commits(&self) -> Vec<ScheduledCommit> {
+        self.scheduled_commits
+            .read()
+            .expect("scheduled_commits lock poisoned")
+            .clone()
+    }
+
     pub fn get_scheduled_commits_by_payer(
         &self,
         payer: &Pubkey,
@@ -78,6 +125,24 @@ impl TransactionScheduler {
             .collect::<Vec<_>>()
     }
 
+    pub fn get_scheduled_commit(&self, id: u64) -> Option<ScheduledCommit> {
+        self.scheduled_commits
+            .read()
+            .expect("scheduled_commits lock poisoned")
+            .iter()
+            .find(|commit| commit.id == id)
+            .cloned()
+    }
+
+    pub fn remove_scheduled_commit(&self, id: u64) -> Option<ScheduledCommit> {
+        let mut lock = self
+            .scheduled_commits
+            .write()
+            .expect("scheduled_commits lock poisoned");
+        let idx = lock.iter().position(|commit| commit.id == id)?;
+        Some(lock.remove(idx))
+    }
+
     pub fn take_scheduled_commits(&self) -> Vec<ScheduledCommit> {
         let mut lock = self
             .scheduled_commits
@@ -86,6 +151,23 @@ impl TransactionScheduler {
         mem::take(&mut *lock)
     }
 
+    /// Takes the commits that are due at the given slot, commits scheduled
+    /// for a later slot remain
+    pub fn take_due_scheduled_commits(
+        &self,
+        slot: Slot,
+    ) -> Vec<ScheduledCommit> {
+        let mut lock = self
+            .scheduled_commits
+            .write()
+            .expect("scheduled_commits lock poisoned");
+        let (due, pending) = mem::take(&mut *lock)
+            .into_iter()
+            .partition(|commit| commit.is_due(slot));
+        *lock = pending;
+        due
+    }
+
     pub fn scheduled_commits_len(&self) -> usize {
         let lock = self
             .scheduled_commits
@@ -103,3 +185,19 @@ impl TransactionScheduler {
         lock.clear();
     }
 }
+
+fn store_context(
+    invoke_context: &InvokeContext,
+    context_data: &mut AccountSharedData,
+    context: &MagicContext,
+) -> Result<(), InstructionError> {
+    context.store(context_data).map_err(|err| {
+        ic_msg!(
+            invoke_context,
+            "Failed to store MagicContext of {} scheduled commit(s): {:?}",
+            context.scheduled_commits.len(),
+            err
+        );
+        err
+    })
+}
@@ -0,0 +1,141 @@
+use magicblock_core::magic_program;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    account::{AccountSharedData, ReadableAccount, WritableAccount},
+    clock::Slot,
+    compute_budget,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+};
+
+/// A key the authority delegated signing transactions to, usually a
+/// throwaway keypair held by a game client so that the user does not need
+/// to approve every transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionKey {
+    pub session_key: Pubkey,
+    /// The account that registered the session key and may revoke it
+    pub authority: Pubkey,
+    /// The session key is valid up to and including this slot
+    pub expires_at_slot: Slot,
+    /// Programs that transactions paid by the session key may invoke
+    pub programs: Vec<Pubkey>,
+}
+
+impl SessionKey {
+    pub fn is_expired(&self, slot: Slot) -> bool {
+        slot > self.expires_at_slot
+    }
+
+    /// Whether the session key may pay for invoking the program, the compute
+    /// budget program is always allowed in order to set compute limits
+    pub fn allows_program(&self, program_id: &Pubkey) -> bool {
+        compute_budget::check_id(program_id)
+            || self.programs.contains(program_id)
+    }
+}
+
+/// Session keys registered via the magic program.
+///
+/// The session keys account holds this struct serialized with bincode's
+/// default options, the remainder of the account is zeroed:
+///
+/// - `keys`: `u64` length followed by each [SessionKey], oldest first
+///
+/// Expired keys are pruned whenever a key is registered or revoked.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SessionKeyRegistry {
+    pub keys: Vec<SessionKey>,
+}
+
+impl SessionKeyRegistry {
+    pub const SIZE: usize = magic_program::MAGIC_SESSION_KEYS_SIZE;
+    pub const MAX_PROGRAMS_PER_KEY: usize = 16;
+    /// Session keys of an authority that may be valid at the same time
+    pub const MAX_KEYS_PER_AUTHORITY: usize = 8;
+    /// How far ahead the expiry of a session key may be, about one day at
+    /// the default slot duration of 50ms
+    pub const MAX_SESSION_DURATION_SLOTS: Slot = 1_728_000;
+
+    /// Reads the registry from the data of the session keys account, zeroed
+    /// data results in an empty registry
+    pub fn try_from_data(data: &[u8]) -> Result<Self, bincode::Error> {
+        if data.is_empty() {
+            Ok(Self::default())
+        } else {
+            bincode::deserialize(data)
+        }
+    }
+
+    pub fn get(&self, session_key: &Pubkey) -> Option<&SessionKey> {
+        self.keys.iter().find(|key| key.session_key.eq(session_key))
+    }
+
+    /// The session key if it is registered and did not expire at the slot
+    pub fn get_valid(
+        &self,
+        session_key: &Pubkey,
+        slot: Slot,
+    ) -> Option<&SessionKey> {
+        self.get(session_key).filter(|key| !key.is_expired(slot))
+    }
+
+    /// The session keys registered by the authority that did not expire at
+    /// the slot
+    pub fn valid_keys_of_authority<'a>(
+        &'a self,
+        authority: &'a Pubkey,
+        slot: Slot,
+    ) -> impl Iterator<Item = &'a SessionKey> {
+        self.keys.iter().filter(move |key| {
+            key.authority.eq(authority) && !key.is_expired(slot)
+        })
+    }
+
+    /// Registers the key replacing the one previously registered for the
+    /// same session key, after pruning the keys that expired at the slot
+    pub(crate) fn register(&mut self, slot: Slot, key: SessionKey) {
+        self.prune_expired(slot);
+        self.keys
+            .retain(|existing| existing.session_key.ne(&key.session_key));
+        self.keys.push(key);
+    }
+
+    fn prune_expired(&mut self, slot: Slot) {
+        self.keys.retain(|key| !key.is_expired(slot));
+    }
+
+    /// Removes the session key along with the keys that expired at the slot,
+    /// returns it if it was registered
+    pub(crate) fn revoke(
+        &mut self,
+        slot: Slot,
+        session_key: &Pubkey,
+    ) -> Option<SessionKey> {
+        self.prune_expired(slot);
+        let idx = self
+            .keys
+            .iter()
+            .position(|key| key.session_key.eq(session_key))?;
+        Some(self.keys.remove(idx))
+    }
+
+    /// Serializes the registry into the zeroed account, fails if it does not
+    /// fit into it
+    pub(crate) fn store(
+        &self,
+        data: &mut AccountSharedData,
+    ) -> Result<(), InstructionError> {
+        let max_size = data.data().len().min(Self::SIZE) as u64;
+        if bincode::serialized_size(self)
+            .map_err(|_| InstructionError::GenericError)?
+            > max_size
+        {
+            return Err(InstructionError::AccountDataTooSmall);
+        }
+        // Zero fill first to not leave parts of removed keys behind
+        data.data_as_mut_slice().fill(0);
+        data.serialize_data(self)
+            .map_err(|_| InstructionError::GenericError)
+    }
+}
@@ -0,0 +1,72 @@
+mod process_register_session_key;
+mod process_revoke_session_key;
+pub(crate) use process_register_session_key::*;
+pub(crate) use process_revoke_session_key::*;
+
+use magicblock_core::magic_program::MAGIC_SESSION_KEYS_PUBKEY;
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{account::ReadableAccount, instruction::InstructionError};
+
+use crate::{
+    session_key_registry::SessionKeyRegistry,
+    utils::accounts::{
+        get_instruction_account_with_idx, get_instruction_pubkey_with_idx,
+    },
+};
+
+/// Updates the registry stored in the session keys account at the index
+/// after checking that the correct account was provided
+pub(crate) fn update_session_key_registry<F>(
+    invoke_context: &mut InvokeContext,
+    session_keys_idx: u16,
+    update: F,
+) -> Result<(), InstructionError>
+where
+    F: FnOnce(
+        &mut InvokeContext,
+        &mut SessionKeyRegistry,
+    ) -> Result<(), InstructionError>,
+{
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    let provided_session_keys =
+        get_instruction_pubkey_with_idx(transaction_context, session_keys_idx)?;
+    if !provided_session_keys.eq(&MAGIC_SESSION_KEYS_PUBKEY) {
+        ic_msg!(
+            invoke_context,
+            "ERR: invalid session keys account {}",
+            provided_session_keys
+        );
+        return Err(InstructionError::MissingAccount);
+    }
+
+    let session_keys_acc = get_instruction_account_with_idx(
+        transaction_context,
+        session_keys_idx,
+    )?;
+    let mut registry =
+        SessionKeyRegistry::try_from_data(session_keys_acc.borrow().data())
+            .map_err(|err| {
+                ic_msg!(
+                    invoke_context,
+                    "Failed to deserialize SessionKeyRegistry: {}",
+                    err
+                );
+                InstructionError::InvalidAccountData
+            })?;
+    update(invoke_context, &mut registry)?;
+    registry
+        .store(&mut session_keys_acc.borrow_mut())
+        .map_err(|err| {
+            ic_msg!(
+                invoke_context,
+                "Failed to serialize SessionKeyRegistry: {:?}",
+                err
+            );
+            err
+        })
+}
+
+#[cfg(test)]
+mod process_session_keys_tests;
@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{
+    account::ReadableAccount, clock::Slot, instruction::InstructionError,
+    pubkey::Pubkey,
+};
+
+#[cfg(not(test))]
+use crate::utils::instruction_context_frames::InstructionContextFrames;
+use crate::{
+    errors::custom_error_codes,
+    session_key_registry::{SessionKey, SessionKeyRegistry},
+    session_keys::update_session_key_registry,
+    utils::accounts::{
+        credit_instruction_account_at_index,
+        debit_instruction_account_at_index, get_instruction_account_with_idx,
+        get_instruction_pubkey_with_idx,
+    },
+    validator::{is_session_key_sponsor_program, validator_authority_id},
+};
+
+pub(crate) fn process_register_session_key(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    expires_at_slot: Slot,
+    programs: Vec<Pubkey>,
+) -> Result<(), InstructionError> {
+    const AUTHORITY_IDX: u16 = 0;
+    const SESSION_KEY_IDX: u16 = AUTHORITY_IDX + 1;
+    const SESSION_KEYS_IDX: u16 = SESSION_KEY_IDX + 1;
+    const VALIDATOR_AUTHORITY_IDX: u16 = SESSION_KEYS_IDX + 1;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // 1. Check that both the authority and the session key signed
+    let authority =
+        *get_instruction_pubkey_with_idx(transaction_context, AUTHORITY_IDX)?;
+    let session_key =
+        *get_instruction_pubkey_with_idx(transaction_context, SESSION_KEY_IDX)?;
+    for pubkey in [&authority, &session_key] {
+        if !signers.contains(pubkey) {
+            ic_msg!(
+                invoke_context,
+                "RegisterSessionKey ERR: pubkey {} not in signers",
+                pubkey
+            );
+            return Err(InstructionError::MissingRequiredSignature);
+        }
+    }
+
+    // 2. Check that the validator or one of the programs in scope which the
+    //    validator allows to sponsor session keys sponsors the session key,
+    //    otherwise anyone could have their fees sponsored
+    let validator_authority = *get_instruction_pubkey_with_idx(
+        transaction_context,
+        VALIDATOR_AUTHORITY_IDX,
+    )?;
+    if validator_authority.ne(&validator_authority_id()) {
+        ic_msg!(
+            invoke_context,
+            "RegisterSessionKey ERR: invalid validator authority {}",
+            validator_authority
+        );
+        return Err(InstructionError::MissingAccount);
+    }
+    let sponsored_by_validator = signers.contains(&validator_authority);
+
+    // We cannot easily simulate the instruction being invoked via CPI during
+    // unit tests, the integration tests ensure that this works as expected
+    #[cfg(not(test))]
+    let parent_program_id = {
+        let frames = InstructionContextFrames::try_from(transaction_context)?;
+        frames
+            .find_program_id_of_parent_of_current_instruction()
+            .copied()
+    };
+    #[cfg(test)]
+    let parent_program_id: Option<Pubkey> = None;
+
+    if !sponsored_by_validator
+        && !is_sponsored_by_program(parent_program_id.as_ref(), &programs)
+    {
+        ic_msg!(
+            invoke_context,
+            "RegisterSessionKey ERR: needs to be signed by the validator authority or invoked by one of the programs allowed to sponsor session keys, invoked by {}",
+            parent_program_id
+                .map_or_else(|| "None".to_string(), |id| id.to_string())
+        );
+        return Err(InstructionError::Custom(
+            custom_error_codes::SESSION_KEY_NOT_SPONSORED,
+        ));
+    }
+
+    // 3. Check the scope and expiry of the session key
+    if programs.is_empty()
+        || programs.len() > SessionKeyRegistry::MAX_PROGRAMS_PER_KEY
+    {
+        ic_msg!(
+            invoke_context,
+            "RegisterSessionKey ERR: needs between 1 and {} programs, got {}",
+            SessionKeyRegistry::MAX_PROGRAMS_PER_KEY,
+            programs.len()
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    // Otherwise the session key could register further session keys
+    if programs.contains(&crate::id()) {
+        ic_msg!(
+            invoke_context,
+            "RegisterSessionKey ERR: session keys may not invoke the magic program"
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+    if expires_at_slot <= clock.slot
+        || expires_at_slot - clock.slot
+            > SessionKeyRegistry::MAX_SESSION_DURATION_SLOTS
+    {
+        ic_msg!(
+            invoke_context,
+            "RegisterSessionKey ERR: expiry slot {} needs to be after current slot {} and at most {} slots ahead",
+            expires_at_slot,
+            clock.slot,
+            SessionKeyRegistry::MAX_SESSION_DURATION_SLOTS
+        );
+        return Err(InstructionError::InvalidArgument);
+    }
+
+    // 4. Register the session key unless another authority holds it or the
+    //    authority registered too many session keys already
+    update_session_key_registry(
+        invoke_context,
+        SESSION_KEYS_IDX,
+        |invoke_context, registry| {
+            if let Some(existing) = registry.get_valid(&session_key, clock.slot)
+            {
+                if existing.authority.ne(&authority) {
+                    ic_msg!(
+                        invoke_context,
+                        "RegisterSessionKey ERR: session key {} is registered by {}",
+                        session_key,
+                        existing.authority
+                    );
+                    return Err(InstructionError::AccountAlreadyInitialized);
+                }
+            }
+            let registered = registry
+                .valid_keys_of_authority(&authority, clock.slot)
+                .filter(|key| key.session_key.ne(&session_key))
+                .count();
+            if registered >= SessionKeyRegistry::MAX_KEYS_PER_AUTHORITY {
+                ic_msg!(
+                    invoke_context,
+                    "RegisterSessionKey ERR: authority {} registered {} session keys already",
+                    authority,
+                    registered
+                );
+                return Err(InstructionError::Custom(
+                    custom_error_codes::TOO_MANY_SESSION_KEYS,
+                ));
+            }
+            ic_msg!(
+                invoke_context,
+                "RegisterSessionKey: session_key={}, authority={}, expires_at_slot={}, programs={:?}",
+                session_key,
+                authority,
+                expires_at_slot,
+                programs
+            );
+            registry.register(
+                clock.slot,
+                SessionKey {
+                    session_key,
+                    authority,
+                    expires_at_slot,
+                    programs,
+                },
+            );
+            Ok(())
+        },
+    )?;
+
+    // 5. Fund the session key from the validator authority, the fees of
+    //    sponsored transactions are waived, but their fee payer still needs
+    //    to exist
+    fund_session_key(invoke_context, SESSION_KEY_IDX, VALIDATOR_AUTHORITY_IDX)
+}
+
+/// Whether the program invoking the instruction, `None` if it was invoked
+/// directly by a transaction, may sponsor a session key for the programs.
+/// Programs need to be allowed by the validator, otherwise any program could
+/// sponsor session keys by listing itself.
+pub(super) fn is_sponsored_by_program(
+    parent_program_id: Option<&Pubkey>,
+    programs: &[Pubkey],
+) -> bool {
+    parent_program_id.is_some_and(|id| {
+        is_session_key_sponsor_program(id) && programs.contains(id)
+    })
+}
+
+/// Tops up the session key to the rent exempt minimum of an account without
+/// data, which makes it a valid fee payer even though it holds no lamports.
+fn fund_session_key(
+    invoke_context: &InvokeContext,
+    session_key_idx: u16,
+    validator_authority_idx: u16,
+) -> Result<(), InstructionError> {
+    let rent = invoke_context
+        .get_sysvar_cache()
+        .get_rent()
+        .map_err(|err| {
+            ic_msg!(invoke_context, "Failed to get rent sysvar: {}", err);
+            InstructionError::UnsupportedSysvar
+        })?;
+    let transaction_context = &invoke_context.transaction_context;
+    let session_key_lamports =
+        get_instruction_account_with_idx(transaction_context, session_key_idx)?
+            .borrow()
+            .lamports();
+    let shortfall =
+        rent.minimum_balance(0).saturating_sub(session_key_lamports);
+    if shortfall == 0 {
+        return Ok(());
+    }
+    // Lamports of readonly accounts would not be persisted
+    let ix_ctx = transaction_context.get_current_instruction_context()?;
+    for idx in [session_key_idx, validator_authority_idx] {
+        if !ix_ctx.is_instruction_account_writable(idx)? {
+            ic_msg!(
+                invoke_context,
+                "RegisterSessionKey ERR: account at index {} needs to be writable to fund the session key",
+                idx
+            );
+            return Err(InstructionError::ReadonlyLamportChange);
+        }
+    }
+    debit_instruction_account_at_index(
+        transaction_context,
+        validator_authority_idx,
+        shortfall,
+    )
+    .inspect_err(|_| {
+        ic_msg!(
+            invoke_context,
+            "RegisterSessionKey ERR: validator authority cannot fund session key with {} lamports",
+            shortfall
+        );
+    })?;
+    credit_instruction_account_at_index(
+        transaction_context,
+        session_key_idx,
+        shortfall,
+    )?;
+    ic_msg!(
+        invoke_context,
+        "RegisterSessionKey: funded session key with {} lamports",
+        shortfall
+    );
+    Ok(())
+}
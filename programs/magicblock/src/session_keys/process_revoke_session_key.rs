@@ -0,0 +1,73 @@
+use std::collections::HashSet;
+
+use solana_log_collector::ic_msg;
+use solana_program_runtime::invoke_context::InvokeContext;
+use solana_sdk::{instruction::InstructionError, pubkey::Pubkey};
+
+use crate::{
+    session_keys::update_session_key_registry,
+    utils::accounts::get_instruction_pubkey_with_idx,
+};
+
+pub(crate) fn process_revoke_session_key(
+    signers: HashSet<Pubkey>,
+    invoke_context: &mut InvokeContext,
+    session_key: Pubkey,
+) -> Result<(), InstructionError> {
+    const SIGNER_IDX: u16 = 0;
+    const SESSION_KEYS_IDX: u16 = SIGNER_IDX + 1;
+
+    let transaction_context = &invoke_context.transaction_context.clone();
+
+    // 1. Check that the signer is either the authority or the session key
+    let signer =
+        *get_instruction_pubkey_with_idx(transaction_context, SIGNER_IDX)?;
+    if !signers.contains(&signer) {
+        ic_msg!(
+            invoke_context,
+            "RevokeSessionKey ERR: pubkey {} not in signers",
+            signer
+        );
+        return Err(InstructionError::MissingRequiredSignature);
+    }
+
+    // 2. Remove the session key from the registry
+    let clock =
+        invoke_context
+            .get_sysvar_cache()
+            .get_clock()
+            .map_err(|err| {
+                ic_msg!(invoke_context, "Failed to get clock sysvar: {}", err);
+                InstructionError::UnsupportedSysvar
+            })?;
+    update_session_key_registry(
+        invoke_context,
+        SESSION_KEYS_IDX,
+        |invoke_context, registry| {
+            let Some(key) = registry.get(&session_key) else {
+                ic_msg!(
+                    invoke_context,
+                    "RevokeSessionKey ERR: session key {} is not registered",
+                    session_key
+                );
+                return Err(InstructionError::InvalidArgument);
+            };
+            if key.authority.ne(&signer) && key.session_key.ne(&signer) {
+                ic_msg!(
+                    invoke_context,
+                    "RevokeSessionKey ERR: {} may not revoke session key {}",
+                    signer,
+                    session_key
+                );
+                return Err(InstructionError::IncorrectAuthority);
+            }
+            registry.revoke(clock.slot, &session_key);
+            ic_msg!(
+                invoke_context,
+                "RevokeSessionKey: session_key={}",
+                session_key
+            );
+            Ok(())
+        },
+    )
+}
@@ -0,0 +1,516 @@
+use magicblock_core::magic_program::MAGIC_SESSION_KEYS_PUBKEY;
+use solana_sdk::{
+    account::{
+        create_account_shared_data_for_test, AccountSharedData, ReadableAccount,
+    },
+    clock::{self, Slot},
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::SysvarId,
+};
+use test_tools_core::init_logger;
+
+use crate::{
+    errors::custom_error_codes,
+    magicblock_instruction::{
+        register_session_key_instruction, revoke_session_key_instruction,
+    },
+    session_key_registry::{SessionKey, SessionKeyRegistry},
+    session_keys::process_register_session_key::is_sponsored_by_program,
+    test_utils::process_instruction,
+    validator::{
+        generate_validator_authority_if_needed,
+        init_session_key_sponsor_programs, validator_authority_id,
+    },
+};
+
+const CURRENT_SLOT: Slot = 100;
+const FUNDED_LAMPORTS: u64 = 1_000_000;
+
+fn get_clock() -> clock::Clock {
+    clock::Clock {
+        slot: CURRENT_SLOT,
+        ..Default::default()
+    }
+}
+
+fn empty_session_keys() -> AccountSharedData {
+    AccountSharedData::new(u64::MAX, SessionKeyRegistry::SIZE, &crate::id())
+}
+
+/// Processes the instruction with funded signers and the provided session
+/// keys account, returns the updated session keys account
+fn process_session_keys_instruction(
+    ix: Instruction,
+    session_keys: AccountSharedData,
+    expected_result: Result<(), InstructionError>,
+) -> AccountSharedData {
+    let accounts = process_session_keys_instruction_with_accounts(
+        ix,
+        session_keys,
+        |_| FUNDED_LAMPORTS,
+        expected_result,
+    );
+    account_of(&accounts, &MAGIC_SESSION_KEYS_PUBKEY)
+}
+
+/// Processes the instruction with signers holding the lamports returned by
+/// `lamports_of` and the provided session keys account, returns all updated
+/// accounts
+fn process_session_keys_instruction_with_accounts(
+    ix: Instruction,
+    session_keys: AccountSharedData,
+    lamports_of: impl Fn(&Pubkey) -> u64,
+    expected_result: Result<(), InstructionError>,
+) -> Vec<(Pubkey, AccountSharedData)> {
+    let mut transaction_accounts = vec![
+        (
+            clock::Clock::id(),
+            create_account_shared_data_for_test(&get_clock()),
+        ),
+        (
+            Rent::id(),
+            create_account_shared_data_for_test(&Rent::default()),
+        ),
+    ];
+    transaction_accounts.extend(ix.accounts.iter().map(|meta| {
+        let account = if meta.pubkey == MAGIC_SESSION_KEYS_PUBKEY {
+            session_keys.clone()
+        } else {
+            AccountSharedData::new(
+                lamports_of(&meta.pubkey),
+                0,
+                &system_program::id(),
+            )
+        };
+        (meta.pubkey, account)
+    }));
+    let pubkeys = transaction_accounts
+        .iter()
+        .map(|(pubkey, _)| *pubkey)
+        .collect::<Vec<_>>();
+    let processed = process_instruction(
+        ix.data.as_slice(),
+        transaction_accounts,
+        ix.accounts,
+        expected_result,
+    );
+    pubkeys.into_iter().zip(processed).collect()
+}
+
+fn account_of(
+    accounts: &[(Pubkey, AccountSharedData)],
+    pubkey: &Pubkey,
+) -> AccountSharedData {
+    accounts
+        .iter()
+        .find(|(key, _)| key == pubkey)
+        .map(|(_, account)| account.clone())
+        .unwrap()
+}
+
+/// Instruction registering the session key sponsored by the validator
+fn register_instruction(
+    authority: &Pubkey,
+    session_key: &Pubkey,
+    expires_at_slot: Slot,
+    programs: Vec<Pubkey>,
+) -> Instruction {
+    generate_validator_authority_if_needed();
+    register_session_key_instruction(
+        authority,
+        session_key,
+        expires_at_slot,
+        programs,
+    )
+}
+
+fn registry_of(session_keys: &AccountSharedData) -> SessionKeyRegistry {
+    SessionKeyRegistry::try_from_data(session_keys.data()).unwrap()
+}
+
+#[test]
+fn test_register_and_revoke_session_key() {
+    init_logger!();
+    let authority = Pubkey::new_unique();
+    let session_key = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+
+    let session_keys = process_session_keys_instruction(
+        register_instruction(
+            &authority,
+            &session_key,
+            CURRENT_SLOT + 10,
+            vec![program],
+        ),
+        empty_session_keys(),
+        Ok(()),
+    );
+    let registry = registry_of(&session_keys);
+    assert_eq!(
+        registry.get_valid(&session_key, CURRENT_SLOT + 10),
+        Some(&SessionKey {
+            session_key,
+            authority,
+            expires_at_slot: CURRENT_SLOT + 10,
+            programs: vec![program],
+        })
+    );
+    assert!(registry
+        .get_valid(&session_key, CURRENT_SLOT + 11)
+        .is_none());
+
+    // Registering again extends the session
+    let session_keys = process_session_keys_instruction(
+        register_instruction(
+            &authority,
+            &session_key,
+            CURRENT_SLOT + 20,
+            vec![program],
+        ),
+        session_keys,
+        Ok(()),
+    );
+    let registry = registry_of(&session_keys);
+    assert_eq!(registry.keys.len(), 1);
+    assert_eq!(registry.keys[0].expires_at_slot, CURRENT_SLOT + 20);
+
+    // Only the authority or the session key may revoke it
+    let session_keys = process_session_keys_instruction(
+        revoke_session_key_instruction(&Pubkey::new_unique(), session_key),
+        session_keys,
+        Err(InstructionError::IncorrectAuthority),
+    );
+    let session_keys = process_session_keys_instruction(
+        revoke_session_key_instruction(&authority, session_key),
+        session_keys,
+        Ok(()),
+    );
+    assert!(registry_of(&session_keys).keys.is_empty());
+    process_session_keys_instruction(
+        revoke_session_key_instruction(&session_key, session_key),
+        session_keys,
+        Err(InstructionError::InvalidArgument),
+    );
+}
+
+#[test]
+fn test_register_session_key_prunes_expired_keys() {
+    init_logger!();
+    let mut session_keys = empty_session_keys();
+    let expired = SessionKey {
+        session_key: Pubkey::new_unique(),
+        authority: Pubkey::new_unique(),
+        expires_at_slot: CURRENT_SLOT - 1,
+        programs: vec![Pubkey::new_unique()],
+    };
+    SessionKeyRegistry {
+        keys: vec![expired.clone()],
+    }
+    .store(&mut session_keys)
+    .unwrap();
+
+    // An expired session key may be registered by another authority
+    let authority = Pubkey::new_unique();
+    let session_keys = process_session_keys_instruction(
+        register_instruction(
+            &authority,
+            &expired.session_key,
+            CURRENT_SLOT + 1,
+            vec![Pubkey::new_unique()],
+        ),
+        session_keys,
+        Ok(()),
+    );
+    let registry = registry_of(&session_keys);
+    assert_eq!(registry.keys.len(), 1);
+    assert_eq!(registry.keys[0].authority, authority);
+}
+
+#[test]
+fn test_register_session_key_of_other_authority_fails() {
+    init_logger!();
+    let session_key = Pubkey::new_unique();
+    let session_keys = process_session_keys_instruction(
+        register_instruction(
+            &Pubkey::new_unique(),
+            &session_key,
+            CURRENT_SLOT + 10,
+            vec![Pubkey::new_unique()],
+        ),
+        empty_session_keys(),
+        Ok(()),
+    );
+    process_session_keys_instruction(
+        register_instruction(
+            &Pubkey::new_unique(),
+            &session_key,
+            CURRENT_SLOT + 10,
+            vec![Pubkey::new_unique()],
+        ),
+        session_keys,
+        Err(InstructionError::AccountAlreadyInitialized),
+    );
+}
+
+#[test]
+fn test_register_session_key_with_invalid_arguments_fails() {
+    init_logger!();
+    let programs =
+        |num: usize| (0..num).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+    for (expires_at_slot, programs) in [
+        // Already expired
+        (CURRENT_SLOT, programs(1)),
+        // Expires too far ahead
+        (
+            CURRENT_SLOT + SessionKeyRegistry::MAX_SESSION_DURATION_SLOTS + 1,
+            programs(1),
+        ),
+        // Without programs
+        (CURRENT_SLOT + 1, vec![]),
+        // Too many programs
+        (
+            CURRENT_SLOT + 1,
+            programs(SessionKeyRegistry::MAX_PROGRAMS_PER_KEY + 1),
+        ),
+        // Session keys may not register further session keys
+        (CURRENT_SLOT + 1, vec![crate::id()]),
+    ] {
+        process_session_keys_instruction(
+            register_instruction(
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                expires_at_slot,
+                programs,
+            ),
+            empty_session_keys(),
+            Err(InstructionError::InvalidArgument),
+        );
+    }
+}
+
+#[test]
+fn test_register_session_key_without_session_key_signature_fails() {
+    init_logger!();
+    let mut ix = register_instruction(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        CURRENT_SLOT + 1,
+        vec![Pubkey::new_unique()],
+    );
+    ix.accounts[1].is_signer = false;
+    process_session_keys_instruction(
+        ix,
+        empty_session_keys(),
+        Err(InstructionError::MissingRequiredSignature),
+    );
+}
+
+#[test]
+fn test_register_session_key_with_invalid_account_fails() {
+    init_logger!();
+    let mut ix = register_instruction(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        CURRENT_SLOT + 1,
+        vec![Pubkey::new_unique()],
+    );
+    ix.accounts[2].pubkey = Pubkey::new_unique();
+    process_session_keys_instruction(
+        ix,
+        empty_session_keys(),
+        Err(InstructionError::MissingAccount),
+    );
+}
+
+#[test]
+fn test_register_session_key_without_sponsor_fails() {
+    init_logger!();
+    let mut ix = register_instruction(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        CURRENT_SLOT + 1,
+        vec![Pubkey::new_unique()],
+    );
+    ix.accounts[3].is_signer = false;
+    process_session_keys_instruction(
+        ix,
+        empty_session_keys(),
+        Err(InstructionError::Custom(
+            custom_error_codes::SESSION_KEY_NOT_SPONSORED,
+        )),
+    );
+}
+
+#[test]
+fn test_register_too_many_session_keys_fails() {
+    init_logger!();
+    let authority = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let mut session_keys = empty_session_keys();
+    let registered = (0..SessionKeyRegistry::MAX_KEYS_PER_AUTHORITY)
+        .map(|_| Pubkey::new_unique())
+        .collect::<Vec<_>>();
+    for session_key in &registered {
+        session_keys = process_session_keys_instruction(
+            register_instruction(
+                &authority,
+                session_key,
+                CURRENT_SLOT + 10,
+                vec![program],
+            ),
+            session_keys,
+            Ok(()),
+        );
+    }
+
+    let session_keys = process_session_keys_instruction(
+        register_instruction(
+            &authority,
+            &Pubkey::new_unique(),
+            CURRENT_SLOT + 10,
+            vec![program],
+        ),
+        session_keys,
+        Err(InstructionError::Custom(
+            custom_error_codes::TOO_MANY_SESSION_KEYS,
+        )),
+    );
+    // Registered session keys may still be extended
+    process_session_keys_instruction(
+        register_instruction(
+            &authority,
+            &registered[0],
+            CURRENT_SLOT + 20,
+            vec![program],
+        ),
+        session_keys,
+        Ok(()),
+    );
+}
+
+#[test]
+fn test_register_session_key_funds_unfunded_session_key() {
+    init_logger!();
+    let session_key = Pubkey::new_unique();
+    let ix = register_instruction(
+        &Pubkey::new_unique(),
+        &session_key,
+        CURRENT_SLOT + 10,
+        vec![Pubkey::new_unique()],
+    );
+    let accounts = process_session_keys_instruction_with_accounts(
+        ix,
+        empty_session_keys(),
+        |pubkey| {
+            if pubkey == &session_key {
+                0
+            } else {
+                FUNDED_LAMPORTS
+            }
+        },
+        Ok(()),
+    );
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+    assert_eq!(
+        account_of(&accounts, &session_key).lamports(),
+        rent_exempt_minimum
+    );
+    assert_eq!(
+        account_of(&accounts, &validator_authority_id()).lamports(),
+        FUNDED_LAMPORTS - rent_exempt_minimum
+    );
+}
+
+#[test]
+fn test_register_session_key_funds_session_key_shortfall_only() {
+    init_logger!();
+    let session_key = Pubkey::new_unique();
+    let rent_exempt_minimum = Rent::default().minimum_balance(0);
+    let ix = register_instruction(
+        &Pubkey::new_unique(),
+        &session_key,
+        CURRENT_SLOT + 10,
+        vec![Pubkey::new_unique()],
+    );
+    let accounts = process_session_keys_instruction_with_accounts(
+        ix,
+        empty_session_keys(),
+        |pubkey| {
+            if pubkey == &session_key {
+                rent_exempt_minimum - 1
+            } else {
+                FUNDED_LAMPORTS
+            }
+        },
+        Ok(()),
+    );
+    assert_eq!(
+        account_of(&accounts, &session_key).lamports(),
+        rent_exempt_minimum
+    );
+    assert_eq!(
+        account_of(&accounts, &validator_authority_id()).lamports(),
+        FUNDED_LAMPORTS - 1
+    );
+}
+
+#[test]
+fn test_register_session_key_without_funds_of_validator_authority_fails() {
+    init_logger!();
+    let session_key = Pubkey::new_unique();
+    let ix = register_instruction(
+        &Pubkey::new_unique(),
+        &session_key,
+        CURRENT_SLOT + 10,
+        vec![Pubkey::new_unique()],
+    );
+    let validator_authority = validator_authority_id();
+    process_session_keys_instruction_with_accounts(
+        ix,
+        empty_session_keys(),
+        |pubkey| {
+            if pubkey == &session_key || pubkey == &validator_authority {
+                0
+            } else {
+                FUNDED_LAMPORTS
+            }
+        },
+        Err(InstructionError::InsufficientFunds),
+    );
+}
+
+#[test]
+fn test_register_session_key_with_invalid_validator_authority_fails() {
+    init_logger!();
+    let mut ix = register_instruction(
+        &Pubkey::new_unique(),
+        &Pubkey::new_unique(),
+        CURRENT_SLOT + 1,
+        vec![Pubkey::new_unique()],
+    );
+    ix.accounts[3].pubkey = Pubkey::new_unique();
+    process_session_keys_instruction(
+        ix,
+        empty_session_keys(),
+        Err(InstructionError::MissingAccount),
+    );
+}
+
+#[test]
+fn test_session_key_sponsored_only_by_allowed_programs_in_scope() {
+    let sponsor = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+
+    // Listing itself in scope does not make a program a sponsor
+    assert!(!is_sponsored_by_program(Some(&sponsor), &[sponsor]));
+    assert!(!is_sponsored_by_program(None, &[sponsor]));
+
+    init_session_key_sponsor_programs([sponsor].into_iter().collect());
+    assert!(is_sponsored_by_program(Some(&sponsor), &[other, sponsor]));
+    // Sponsors may only sponsor session keys within their own scope
+    assert!(!is_sponsored_by_program(Some(&sponsor), &[other]));
+    assert!(!is_sponsored_by_program(Some(&other), &[other]));
+}
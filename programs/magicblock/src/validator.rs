@@ -1,6 +1,9 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    RwLock,
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
 };
 
 use lazy_static::lazy_static;
@@ -9,6 +12,19 @@ use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
 lazy_static! {
     static ref VALIDATOR_AUTHORITY: RwLock<Option<Keypair>> = RwLock::new(None);
 
+    /// Programs that may invoke the magic program to schedule commits.
+    /// If not set any program may do so.
+    static ref MAGIC_PROGRAM_ALLOWLIST: RwLock<Option<HashSet<Pubkey>>> =
+        RwLock::new(None);
+
+    /// Programs that may sponsor session keys by registering them via CPI,
+    /// none by default.
+    static ref SESSION_KEY_SPONSOR_PROGRAMS: RwLock<HashSet<Pubkey>> =
+        RwLock::new(HashSet::new());
+
+    static ref SCHEDULE_COMMIT_FEES: RwLock<ScheduleCommitFees> =
+        RwLock::new(ScheduleCommitFees::default());
+
     /// Flag to indicate if the validator is starting up which includes
     /// processing the ledger.
     /// Certain transactions behave slightly different during that phase
@@ -21,6 +37,14 @@ lazy_static! {
         #[cfg(test)]
         false,
     );
+
+    /// Flag to indicate if the validator rejects transactions, i.e. since
+    /// it is running out of disk space
+    static ref READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+    /// Flag to indicate if the validator is shutting down, it no longer
+    /// accepts transactions while the scheduled commits are sent
+    static ref SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
 }
 
 pub fn validator_authority() -> Keypair {
@@ -61,6 +85,73 @@ pub fn generate_validator_authority_if_needed() {
     validator_authority_lock.replace(Keypair::new());
 }
 
+/// Restricts scheduling commits to invocations from the provided programs.
+/// Protects shared ephemeral rollups from programs spamming scheduled commits.
+pub fn init_magic_program_allowlist(program_ids: HashSet<Pubkey>) {
+    MAGIC_PROGRAM_ALLOWLIST
+        .write()
+        .expect("RwLock MAGIC_PROGRAM_ALLOWLIST poisoned")
+        .replace(program_ids);
+}
+
+/// Returns `true` if the program invoking the magic program, `None` if
+/// it was invoked directly by a transaction, may schedule commits.
+pub fn is_allowed_to_schedule_commits(program_id: Option<&Pubkey>) -> bool {
+    MAGIC_PROGRAM_ALLOWLIST
+        .read()
+        .expect("RwLock MAGIC_PROGRAM_ALLOWLIST poisoned")
+        .as_ref()
+        .is_none_or(|allowlist| {
+            program_id.is_some_and(|id| allowlist.contains(id))
+        })
+}
+
+/// Allows the provided programs to sponsor session keys they are in scope of
+/// without the validator authority signing the registration.
+pub fn init_session_key_sponsor_programs(program_ids: HashSet<Pubkey>) {
+    *SESSION_KEY_SPONSOR_PROGRAMS
+        .write()
+        .expect("RwLock SESSION_KEY_SPONSOR_PROGRAMS poisoned") = program_ids;
+}
+
+/// Returns `true` if the program may sponsor session keys.
+pub fn is_session_key_sponsor_program(program_id: &Pubkey) -> bool {
+    SESSION_KEY_SPONSOR_PROGRAMS
+        .read()
+        .expect("RwLock SESSION_KEY_SPONSOR_PROGRAMS poisoned")
+        .contains(program_id)
+}
+
+/// Lamports payers are charged for scheduling commits, by default nothing
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleCommitFees {
+    pub flat: u64,
+    pub per_account: u64,
+}
+
+impl ScheduleCommitFees {
+    /// The fee for scheduling the given number of commits of the accounts,
+    /// `None` if it overflows
+    pub fn fee(&self, commits: u64, accounts: usize) -> Option<u64> {
+        self.per_account
+            .checked_mul(accounts as u64)?
+            .checked_add(self.flat)?
+            .checked_mul(commits)
+    }
+}
+
+pub fn init_schedule_commit_fees(fees: ScheduleCommitFees) {
+    *SCHEDULE_COMMIT_FEES
+        .write()
+        .expect("RwLock SCHEDULE_COMMIT_FEES poisoned") = fees;
+}
+
+pub fn schedule_commit_fees() -> ScheduleCommitFees {
+    *SCHEDULE_COMMIT_FEES
+        .read()
+        .expect("RwLock SCHEDULE_COMMIT_FEES poisoned")
+}
+
 /// Returns `true` if the validator is starting up which is the initial
 /// state.
 pub fn is_starting_up() -> bool {
@@ -87,3 +178,24 @@ pub fn finished_starting_up() {
         "validator::finished_starting_up should only be called once"
     );
 }
+
+/// Returns `true` if the validator currently rejects transactions
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Switches the validator into or out of read-only mode, returns `true` if
+/// the mode changed
+pub fn set_read_only(read_only: bool) -> bool {
+    READ_ONLY.swap(read_only, Ordering::Relaxed) != read_only
+}
+
+/// Returns `true` once the validator started shutting down
+pub fn is_shutting_down() -> bool {
+    SHUTTING_DOWN.load(Ordering::Relaxed)
+}
+
+/// Marks the validator as shutting down, this cannot be undone
+pub fn start_shutting_down() {
+    SHUTTING_DOWN.store(true, Ordering::Relaxed);
+}
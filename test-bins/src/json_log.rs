@@ -0,0 +1,47 @@
+use std::io::Write;
+
+use env_logger::Builder;
+use log::kv::{self, Key, Value, VisitSource};
+use serde_json::{Map, Value as JsonValue};
+
+/// Formats each record as a single line JSON object, i.e.
+/// `{"timestamp":"..","level":"INFO","target":"..","message":"..","slot":42}`.
+/// Key-values attached to a record, like the `slot`, `signature` or `pubkey`
+/// it refers to, are included as keys so log indexers don't need to parse
+/// them out of the message.
+pub fn format_json(builder: &mut Builder) {
+    builder.format(|buf, record| {
+        let mut object = Map::new();
+        object.insert(
+            "timestamp".to_string(),
+            buf.timestamp_micros().to_string().into(),
+        );
+        object.insert("level".to_string(), record.level().as_str().into());
+        object.insert("target".to_string(), record.target().into());
+        object.insert("message".to_string(), record.args().to_string().into());
+        let _ = record.key_values().visit(&mut JsonFields(&mut object));
+        writeln!(buf, "{}", JsonValue::Object(object))
+    });
+}
+
+struct JsonFields<'a>(&'a mut Map<String, JsonValue>);
+
+impl<'kvs> VisitSource<'kvs> for JsonFields<'_> {
+    fn visit_pair(
+        &mut self,
+        key: Key<'kvs>,
+        value: Value<'kvs>,
+    ) -> Result<(), kv::Error> {
+        let value = if let Some(value) = value.to_u64() {
+            value.into()
+        } else if let Some(value) = value.to_i64() {
+            value.into()
+        } else if let Some(value) = value.to_bool() {
+            value.into()
+        } else {
+            value.to_string().into()
+        };
+        self.0.insert(key.as_str().to_string(), value);
+        Ok(())
+    }
+}
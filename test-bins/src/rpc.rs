@@ -1,12 +1,16 @@
+use json_log::format_json;
 use log::*;
 use magicblock_api::{
+    diagnostics::{abort_with_diagnostics, RecordingLogger},
     ledger,
     magic_validator::{MagicValidator, MagicValidatorConfig},
     InitGeyserServiceConfig,
 };
-use magicblock_config::{EphemeralConfig, GeyserGrpcConfig};
+use magicblock_config::{EphemeralConfig, GeyserGrpcConfig, LogFormat};
 use solana_sdk::signature::{Keypair, Signer};
-use test_tools::init_logger;
+use tokio::signal::unix::{signal, SignalKind};
+
+mod json_log;
 
 // mAGicPQYBMvcYveUZA5F5UNNwyHvfYh5xkLS2Fr1mev
 const TEST_KEYPAIR_BYTES: [u8; 64] = [
@@ -18,11 +22,13 @@ const TEST_KEYPAIR_BYTES: [u8; 64] = [
 
 const GIT_VERSION: &str = git_version::git_version!();
 
-fn init_logger() {
-    if let Ok(style) = std::env::var("RUST_LOG_STYLE") {
+fn init_logger(format: LogFormat) {
+    let mut builder = env_logger::builder();
+    builder.format_timestamp_micros().is_test(false);
+    if format == LogFormat::Json {
+        format_json(&mut builder);
+    } else if let Ok(style) = std::env::var("RUST_LOG_STYLE") {
         use std::io::Write;
-        let mut builder = env_logger::builder();
-        builder.format_timestamp_micros().is_test(false);
         match style.as_str() {
             "EPHEM" => {
                 builder.format(|buf, record| {
@@ -50,20 +56,34 @@ fn init_logger() {
             }
             _ => {}
         }
-        let _ = builder.try_init();
     } else {
-        init_logger!();
+        // Include the logs of this binary unless RUST_LOG is configured
+        // explicitly, same as `test_tools::init_logger!` does
+        let rust_log =
+            std::env::var(env_logger::DEFAULT_FILTER_ENV).unwrap_or_default();
+        if rust_log.is_empty() || rust_log.ends_with(',') {
+            builder.filter_module("rpc", LevelFilter::Info);
+        }
+    }
+
+    // The most recent log lines are kept to include them in the diagnostics
+    // written when the validator crashes
+    let logger = builder.build();
+    let max_level = logger.filter();
+    if log::set_boxed_logger(Box::new(RecordingLogger::new(logger))).is_ok() {
+        log::set_max_level(max_level);
     }
 }
 
 #[tokio::main]
 async fn main() {
-    init_logger();
+    let (file, config) = load_config_from_arg();
+    let config = config.override_from_envs();
+
+    init_logger(config.logging.format);
     #[cfg(feature = "tokio-console")]
     console_subscriber::init();
 
-    let (file, config) = load_config_from_arg();
-    let config = config.override_from_envs();
     match file {
         Some(file) => info!("Loading config from '{}'.", file),
         None => info!("Using default config. Override it by passing the path to a config file."),
@@ -74,6 +94,7 @@ async fn main() {
     let rpc_port = config.rpc.port;
     let ws_port = rpc_port + WS_PORT_OFFSET; // WebSocket port is typically RPC port + 1
     let rpc_host = config.rpc.addr;
+    let admin_addr = config.rpc.admin_socket_addr();
 
     let validator_keypair = validator_keypair();
 
@@ -83,6 +104,7 @@ async fn main() {
     let config = MagicValidatorConfig {
         validator_config: config,
         init_geyser_service_config: init_geyser_config(geyser_grpc_config),
+        ..Default::default()
     };
 
     debug!("{:#?}", config);
@@ -97,7 +119,9 @@ async fn main() {
     let _ledger_write_guard =
         ledger::lock_ledger(api.ledger().ledger_path(), &mut ledger_lock);
 
-    api.start().await.expect("Failed to start validator");
+    if let Err(err) = api.start().await {
+        abort_with_diagnostics(&format!("Failed to start validator: {err}"));
+    }
 
     info!("");
     info!("🧙 Magicblock Validator is running!");
@@ -109,24 +133,45 @@ async fn main() {
     info!("-----------------------------------");
     info!("📡 RPC endpoint:       http://{}:{}", rpc_host, rpc_port);
     info!("🔌 WebSocket endpoint: ws://{}:{}", rpc_host, ws_port);
+    if let Some(admin_addr) = admin_addr {
+        info!("🔐 Admin endpoint:     http://{}", admin_addr);
+    }
     info!("-----------------------------------");
     info!("Ready for connections!");
     info!("");
 
     // validator is supposed to run forever, so we wait for
     // termination signal to initiate a graceful shutdown
-    let _ = tokio::signal::ctrl_c().await;
-
-    info!("SIGTERM has been received, initiating graceful shutdown");
+    let mut sigterm = signal(SignalKind::terminate())
+        .expect("Failed to install SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {
+            info!("SIGINT has been received, initiating graceful shutdown");
+        }
+        _ = sigterm.recv() => {
+            info!("SIGTERM has been received, initiating graceful shutdown");
+        }
+        _ = api.shutdown_requested() => {
+            info!("Shutdown was requested via RPC, initiating graceful shutdown");
+        }
+    }
     // weird panic behavior in json rpc http server, which panics when stopped from
     // within async context, so we just move it to a different thread for shutdown
     //
     // TODO: once we move rpc out of the validator, this hack will be gone
-    let _ = std::thread::spawn(move || {
-        api.stop();
+    let commits_landed = std::thread::spawn(move || {
+        let commits_landed = api.stop();
         api.join();
+        commits_landed
     })
-    .join();
+    .join()
+    .unwrap_or(false);
+
+    if !commits_landed {
+        error!("Not all scheduled commits landed before shutting down");
+        std::process::exit(1);
+    }
+    info!("Validator shut down gracefully");
 }
 
 fn validator_keypair() -> Keypair {
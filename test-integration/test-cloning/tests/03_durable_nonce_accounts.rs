@@ -0,0 +1,68 @@
+use integration_test_tools::IntegrationTestContext;
+use solana_sdk::{
+    account_utils::StateMut,
+    native_token::LAMPORTS_PER_SOL,
+    nonce::state::{State, Versions},
+    signature::Keypair,
+    signer::Signer,
+    system_instruction,
+    transaction::Transaction,
+};
+
+#[test]
+fn test_durable_nonce_transaction_with_chain_nonce_account_is_rejected() {
+    let ctx = IntegrationTestContext::try_new().unwrap();
+    let payer = Keypair::new();
+    ctx.airdrop_chain(&payer.pubkey(), LAMPORTS_PER_SOL)
+        .expect("failed to airdrop to on-chain account");
+
+    // Create a nonce account on chain
+    let nonce = Keypair::new();
+    let rent = ctx
+        .try_chain_client()
+        .unwrap()
+        .get_minimum_balance_for_rent_exemption(State::size())
+        .unwrap();
+    let mut create_nonce_tx = Transaction::new_with_payer(
+        &system_instruction::create_nonce_account(
+            &payer.pubkey(),
+            &nonce.pubkey(),
+            &payer.pubkey(),
+            rent,
+        ),
+        Some(&payer.pubkey()),
+    );
+    ctx.send_and_confirm_transaction_chain(
+        &mut create_nonce_tx,
+        &[&payer, &nonce],
+    )
+    .expect("failed to create nonce account on chain");
+    let nonce_account = ctx.fetch_chain_account(nonce.pubkey()).unwrap();
+    let versions: Versions = nonce_account.state().unwrap();
+    let State::Initialized(nonce_data) = versions.state() else {
+        panic!("nonce account is not initialized");
+    };
+
+    // Using it on the ephemeral fails since it cannot be delegated and hence
+    // cannot be advanced there
+    let mut tx = Transaction::new_with_nonce(
+        vec![system_instruction::transfer(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            0,
+        )],
+        Some(&payer.pubkey()),
+        &nonce.pubkey(),
+        &payer.pubkey(),
+    );
+    tx.sign(&[&payer], nonce_data.blockhash());
+    let err = ctx
+        .try_ephem_client()
+        .unwrap()
+        .send_transaction(&tx)
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("NonceAccountNotLocalToEphemeral"),
+        "unexpected error: {err}"
+    );
+}
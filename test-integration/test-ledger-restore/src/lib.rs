@@ -11,7 +11,10 @@ use integration_test_tools::{
     workspace_paths::path_relative_to_workspace,
     IntegrationTestContext,
 };
-use magicblock_config::{AccountsConfig, EphemeralConfig, LedgerConfig, LifecycleMode, ProgramConfig, RemoteConfig, ValidatorConfig, DEFAULT_LEDGER_SIZE_BYTES};
+use magicblock_config::{
+    AccountsConfig, EphemeralConfig, LedgerConfig, LifecycleMode,
+    ProgramConfig, RemoteConfig, ValidatorConfig, DEFAULT_LEDGER_SIZE_BYTES,
+};
 use program_flexi_counter::state::FlexiCounter;
 use solana_sdk::{
     clock::Slot,
@@ -84,6 +87,26 @@ pub fn setup_offline_validator(
     programs: Option<Vec<ProgramConfig>>,
     millis_per_slot: Option<u64>,
     reset: bool,
+) -> (TempDir, Child, IntegrationTestContext) {
+    let ledger_config = LedgerConfig {
+        reset,
+        path: Some(ledger_path.display().to_string()),
+        size: DEFAULT_LEDGER_SIZE_BYTES,
+        ..Default::default()
+    };
+    setup_offline_validator_with_ledger_config(
+        ledger_config,
+        programs,
+        millis_per_slot,
+    )
+}
+
+/// Same as [setup_offline_validator], but allows to customize the full
+/// ledger config, i.e. its truncation settings.
+pub fn setup_offline_validator_with_ledger_config(
+    ledger_config: LedgerConfig,
+    programs: Option<Vec<ProgramConfig>>,
+    millis_per_slot: Option<u64>,
 ) -> (TempDir, Child, IntegrationTestContext) {
     let mut accounts_config = AccountsConfig {
         lifecycle: LifecycleMode::Offline,
@@ -101,11 +124,7 @@ pub fn setup_offline_validator(
     let programs = resolve_programs(programs);
 
     let config = EphemeralConfig {
-        ledger: LedgerConfig {
-            reset,
-            path: Some(ledger_path.display().to_string()),
-            size: DEFAULT_LEDGER_SIZE_BYTES
-        },
+        ledger: ledger_config,
         accounts: accounts_config.clone(),
         programs,
         validator: validator_config,
@@ -146,6 +165,7 @@ pub fn setup_validator_with_local_remote(
             reset,
             path: Some(ledger_path.display().to_string()),
             size: DEFAULT_LEDGER_SIZE_BYTES,
+            ..Default::default()
         },
         accounts: accounts_config.clone(),
         programs,
@@ -0,0 +1,105 @@
+use cleanass::{assert, assert_eq};
+use std::{path::Path, process::Child};
+
+use integration_test_tools::{expect, tmpdir::resolve_tmp_dir};
+use magicblock_config::{LedgerConfig, DEFAULT_LEDGER_SIZE_BYTES};
+use solana_sdk::{clock::Slot, pubkey::Pubkey};
+use test_ledger_restore::{
+    cleanup, setup_offline_validator,
+    setup_offline_validator_with_ledger_config, wait_for_ledger_persist,
+    TMP_DIR_LEDGER,
+};
+
+// In this test we ensure that ledger truncation based on slot retention
+// never removes slots that are needed to restore from the oldest retained
+// accounts snapshot.
+// First we airdrop to an account and advance enough slots for multiple
+// snapshots to be taken, then airdrop again.
+// On restart we configure a retention that is much smaller than the range
+// of slots covered by the snapshots.
+// The ledger is truncated, but only up to the oldest snapshot, which we
+// verify by restarting again and checking that the state restores properly.
+
+const RETENTION_SLOTS: u64 = 5;
+const SLOTS_TO_ADVANCE: u64 = 100;
+
+#[test]
+fn restore_ledger_after_truncation_by_retention_slots() {
+    let (_, ledger_path) = resolve_tmp_dir(TMP_DIR_LEDGER);
+
+    let pubkey = Pubkey::new_unique();
+
+    let (mut validator, slot) = write(&ledger_path, &pubkey);
+    validator.kill().unwrap();
+
+    assert!(slot > SLOTS_TO_ADVANCE);
+
+    let mut validator = truncate(&ledger_path, &pubkey);
+    validator.kill().unwrap();
+
+    let mut validator = read(&ledger_path, &pubkey);
+    validator.kill().unwrap();
+}
+
+fn write(ledger_path: &Path, pubkey: &Pubkey) -> (Child, Slot) {
+    let (_, mut validator, ctx) =
+        setup_offline_validator(ledger_path, None, None, true);
+
+    expect!(ctx.airdrop_ephem(pubkey, 1_111_111), validator);
+    expect!(ctx.wait_for_delta_slot_ephem(SLOTS_TO_ADVANCE), validator);
+    expect!(ctx.airdrop_ephem(pubkey, 2_222_222), validator);
+
+    let lamports = expect!(ctx.fetch_ephem_account_balance(pubkey), validator);
+    assert_eq!(lamports, 3_333_333, cleanup(&mut validator));
+
+    let slot = wait_for_ledger_persist(&mut validator);
+    (validator, slot)
+}
+
+fn truncate(ledger_path: &Path, pubkey: &Pubkey) -> Child {
+    let ledger_config = LedgerConfig {
+        reset: false,
+        path: Some(ledger_path.display().to_string()),
+        size: DEFAULT_LEDGER_SIZE_BYTES,
+        retention_slots: Some(RETENTION_SLOTS),
+        ..Default::default()
+    };
+    let (_, mut validator, ctx) =
+        setup_offline_validator_with_ledger_config(ledger_config, None, None);
+
+    let lamports = expect!(ctx.fetch_ephem_account_balance(pubkey), validator);
+    assert_eq!(lamports, 3_333_333, cleanup(&mut validator));
+
+    // The truncator runs right after the validator starts, but might not
+    // have finished by the time the RPC service is available
+    let ephem_client = expect!(ctx.try_ephem_client(), validator);
+    let mut first_available_slot = 0;
+    for _ in 0..20 {
+        first_available_slot =
+            expect!(ephem_client.get_first_available_block(), validator);
+        if first_available_slot > 0 {
+            break;
+        }
+        expect!(ctx.wait_for_next_slot_ephem(), validator);
+    }
+    let current_slot = expect!(ephem_client.get_slot(), validator);
+
+    // Slots were truncated, but not down to the retention limit since
+    // slots after the oldest snapshot have to be kept
+    assert!(first_available_slot > 0, cleanup(&mut validator));
+    assert!(
+        first_available_slot + RETENTION_SLOTS < current_slot,
+        cleanup(&mut validator)
+    );
+
+    validator
+}
+
+fn read(ledger_path: &Path, pubkey: &Pubkey) -> Child {
+    let (_, mut validator, ctx) =
+        setup_offline_validator(ledger_path, None, None, false);
+
+    let lamports = expect!(ctx.fetch_ephem_account_balance(pubkey), validator);
+    assert_eq!(lamports, 3_333_333, cleanup(&mut validator));
+    validator
+}
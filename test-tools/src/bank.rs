@@ -4,7 +4,8 @@ use magicblock_accounts_db::{
     config::AccountsDbConfig, error::AccountsDbError, StWLock,
 };
 use magicblock_bank::{
-    bank::Bank, geyser::AccountsUpdateNotifier,
+    bank::{Bank, FeatureOverrides},
+    geyser::AccountsUpdateNotifier,
     transaction_logs::TransactionLogCollectorFilter,
     EPHEM_DEFAULT_MILLIS_PER_SLOT,
 };
@@ -47,6 +48,7 @@ pub fn bank_for_tests_with_identity(
         StWLock::default(),
         &adb_path,
         adb_init_slot,
+        &FeatureOverrides::default(),
     )?;
     bank.transaction_log_collector_config
         .write()
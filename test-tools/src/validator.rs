@@ -9,7 +9,9 @@ use std::{
 
 use log::*;
 use magicblock_bank::bank::Bank;
-use magicblock_core::traits::PersistsAccountModData;
+use magicblock_core::{
+    mutation_audit::MutationAuditEntry, traits::PersistsAccountModData,
+};
 use magicblock_program::{init_persister, validator};
 use solana_sdk::native_token::LAMPORTS_PER_SOL;
 
@@ -56,6 +58,17 @@ impl PersistsAccountModData for PersisterStub {
     fn load(&self, _id: u64) -> Result<Option<Vec<u8>>, Box<dyn Error>> {
         Err("Loading from ledger not supported in tests".into())
     }
+
+    fn persist_mutation_audit_entry(
+        &self,
+        entry: &MutationAuditEntry,
+    ) -> Result<(), Box<dyn Error>> {
+        debug!(
+            "Persisting mutation audit entry {} for {:?}",
+            entry.seq, entry.accounts
+        );
+        Ok(())
+    }
 }
 
 pub fn init_started_validator(bank: &Bank) {
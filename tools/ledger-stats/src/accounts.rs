@@ -2,10 +2,13 @@ use std::ffi::OsStr;
 
 use magicblock_accounts_db::AccountsDb;
 use num_format::{Locale, ToFormattedString};
+use serde_json::json;
 use solana_sdk::{account::ReadableAccount, clock::Epoch, pubkey::Pubkey};
 use structopt::StructOpt;
 use tabular::{Row, Table};
 
+use crate::output::{OutputFormat, Records};
+
 // -----------------
 // SortAccounts
 // -----------------
@@ -111,6 +114,7 @@ pub fn print_accounts(
     filters: &[FilterAccounts],
     print_rent_epoch: bool,
     count: bool,
+    format: OutputFormat,
 ) {
     let mut accounts = {
         let iter = adb.iter_all();
@@ -165,6 +169,10 @@ pub fn print_accounts(
     });
 
     let slot = adb.slot();
+    if format != OutputFormat::Table {
+        print_accounts_records(slot, owner, accounts, count, format);
+        return;
+    }
     if count {
         if let Some(owner) = owner {
             println!(
@@ -219,3 +227,47 @@ pub fn print_accounts(
     println!("Accounts at slot {}", slot);
     println!("{}", table);
 }
+
+fn print_accounts_records(
+    slot: u64,
+    owner: Option<Pubkey>,
+    accounts: Vec<AccountInfo>,
+    count: bool,
+    format: OutputFormat,
+) {
+    if count {
+        let mut records = Records::new(vec!["slot", "owner", "count"]);
+        records.push(vec![
+            json!(slot),
+            json!(owner.map(|owner| owner.to_string())),
+            json!(accounts.len()),
+        ]);
+        records.print_single(format);
+        return;
+    }
+
+    // Rent epochs are always included to keep the columns stable
+    let mut records = Records::new(vec![
+        "slot",
+        "pubkey",
+        "owner",
+        "lamports",
+        "executable",
+        "data_len",
+        "on_curve",
+        "rent_epoch",
+    ]);
+    for acc in accounts {
+        records.push(vec![
+            json!(slot),
+            json!(acc.pubkey.to_string()),
+            json!(acc.owner.to_string()),
+            json!(acc.lamports),
+            json!(acc.executable),
+            json!(acc.data.len()),
+            json!(acc.pubkey.is_on_curve()),
+            json!(acc.rent_epoch),
+        ]);
+    }
+    records.print(format);
+}
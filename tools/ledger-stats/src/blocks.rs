@@ -0,0 +1,207 @@
+use magicblock_ledger::Ledger;
+use num_format::{Locale, ToFormattedString};
+use solana_sdk::clock::Slot;
+use solana_transaction_status::{
+    TransactionStatusMeta, VersionedConfirmedBlock,
+};
+use tabular::{Row, Table};
+
+pub(crate) fn print_block(ledger: &Ledger, slot: Slot) {
+    let Some(block) = ledger.get_block(slot).expect("Failed to get block")
+    else {
+        eprintln!("Block {} not found", slot);
+        return;
+    };
+    print_block_details(slot, &block);
+}
+
+pub(crate) fn print_slot_range(
+    ledger: &Ledger,
+    start_slot: Slot,
+    end_slot: Slot,
+    summary: bool,
+) {
+    if summary {
+        print_slot_range_summary(ledger, start_slot, end_slot);
+        return;
+    }
+    for slot in start_slot..=end_slot {
+        if let Some(block) =
+            ledger.get_block(slot).expect("Failed to get block")
+        {
+            print_block_details(slot, &block);
+        }
+    }
+}
+
+fn print_block_details(slot: Slot, block: &VersionedConfirmedBlock) {
+    let table = Table::new("{:<}  {:>}")
+        .with_heading(format!("\n++++ Block {} ++++\n", slot))
+        .with_row(
+            Row::new()
+                .with_cell("Blockhash")
+                .with_cell(&block.blockhash),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Previous Blockhash")
+                .with_cell(&block.previous_blockhash),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Parent Slot")
+                .with_cell(block.parent_slot.to_formatted_string(&Locale::en)),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Block Time")
+                .with_cell(block.block_time.unwrap_or_default()),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Transactions")
+                .with_cell(block.transactions.len()),
+        );
+    println!("{}", table);
+
+    if block.transactions.is_empty() {
+        return;
+    }
+
+    let mut table = Table::new("{:>}  {:<}  {:<}  {:>}  {:>}  {:>}  {:>}")
+        .with_row(
+            Row::new()
+                .with_cell("#")
+                .with_cell("Signature")
+                .with_cell("Status")
+                .with_cell("Fee")
+                .with_cell("CUs")
+                .with_cell("Ixs")
+                .with_cell("Inner Ixs"),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("==")
+                .with_cell("=========")
+                .with_cell("======")
+                .with_cell("===")
+                .with_cell("===")
+                .with_cell("===")
+                .with_cell("========="),
+        );
+    for (idx, tx) in block.transactions.iter().enumerate() {
+        let meta = &tx.meta;
+        let status = match &meta.status {
+            Ok(_) => "Ok".to_string(),
+            Err(err) => format!("{:?}", err),
+        };
+        let inner_instructions =
+            meta.inner_instructions.as_ref().map_or(0, |inner| {
+                inner.iter().map(|i| i.instructions.len()).sum()
+            });
+        table.add_row(
+            Row::new()
+                .with_cell(idx)
+                .with_cell(
+                    tx.transaction
+                        .signatures
+                        .first()
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                )
+                .with_cell(status)
+                .with_cell(meta.fee.to_formatted_string(&Locale::en))
+                .with_cell(compute_units(meta).to_formatted_string(&Locale::en))
+                .with_cell(tx.transaction.message.instructions().len())
+                .with_cell(inner_instructions),
+        );
+    }
+    println!("{}", table);
+}
+
+#[derive(Default)]
+struct SlotSummary {
+    transactions: u64,
+    failed: u64,
+    compute_units: u64,
+    fees: u64,
+}
+
+impl SlotSummary {
+    fn from_block(block: &VersionedConfirmedBlock) -> Self {
+        let mut summary = Self::default();
+        for tx in &block.transactions {
+            summary.add_transaction(&tx.meta);
+        }
+        summary
+    }
+
+    fn add_transaction(&mut self, meta: &TransactionStatusMeta) {
+        self.transactions += 1;
+        if meta.status.is_err() {
+            self.failed += 1;
+        }
+        self.compute_units += compute_units(meta);
+        self.fees += meta.fee;
+    }
+
+    fn add(&mut self, other: &SlotSummary) {
+        self.transactions += other.transactions;
+        self.failed += other.failed;
+        self.compute_units += other.compute_units;
+        self.fees += other.fees;
+    }
+
+    fn row(&self, label: String) -> Row {
+        Row::new()
+            .with_cell(label)
+            .with_cell(self.transactions.to_formatted_string(&Locale::en))
+            .with_cell(self.failed.to_formatted_string(&Locale::en))
+            .with_cell(self.compute_units.to_formatted_string(&Locale::en))
+            .with_cell(self.fees.to_formatted_string(&Locale::en))
+    }
+}
+
+fn print_slot_range_summary(ledger: &Ledger, start_slot: Slot, end_slot: Slot) {
+    let mut table = Table::new("{:>}  {:>}  {:>}  {:>}  {:>}")
+        .with_row(
+            Row::new()
+                .with_cell("Slot")
+                .with_cell("Transactions")
+                .with_cell("Failed")
+                .with_cell("CUs")
+                .with_cell("Fees"),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("====")
+                .with_cell("============")
+                .with_cell("======")
+                .with_cell("===")
+                .with_cell("===="),
+        );
+
+    let mut total = SlotSummary::default();
+    let mut num_blocks = 0;
+    for slot in start_slot..=end_slot {
+        let Some(block) = ledger.get_block(slot).expect("Failed to get block")
+        else {
+            continue;
+        };
+        let summary = SlotSummary::from_block(&block);
+        table.add_row(summary.row(slot.to_formatted_string(&Locale::en)));
+        total.add(&summary);
+        num_blocks += 1;
+    }
+    table.add_row(total.row("Total".to_string()));
+
+    println!("{}", table);
+    println!(
+        "Blocks found: {}",
+        num_blocks.to_formatted_string(&Locale::en)
+    );
+}
+
+fn compute_units(meta: &TransactionStatusMeta) -> u64 {
+    meta.compute_units_consumed.unwrap_or_default()
+}
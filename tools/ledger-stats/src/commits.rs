@@ -0,0 +1,54 @@
+use magicblock_core::commit_record::{CommitOutcome, CommitRecord};
+use magicblock_ledger::Ledger;
+use num_format::{Locale, ToFormattedString};
+use solana_sdk::clock::Slot;
+
+pub(crate) fn print_commits(
+    ledger: &Ledger,
+    start_slot: Option<Slot>,
+    end_slot: Option<Slot>,
+) {
+    let start_slot = start_slot.unwrap_or(0);
+    let end_slot = end_slot.unwrap_or(Slot::MAX);
+    let records = ledger
+        .read_commit_records(start_slot, end_slot)
+        .expect("Failed to read commit records");
+    if records.is_empty() {
+        println!("No commits recorded");
+        return;
+    }
+
+    for record in &records {
+        print_commit(record);
+    }
+    println!("Total commits: {}", records.len());
+}
+
+fn print_commit(record: &CommitRecord) {
+    let outcome = match &record.outcome {
+        CommitOutcome::NotNeeded => "not needed".to_string(),
+        CommitOutcome::Pending => "pending".to_string(),
+        CommitOutcome::Confirmed => "confirmed".to_string(),
+        CommitOutcome::Failed(err) => format!("failed ({err})"),
+        CommitOutcome::Finalized => "finalized".to_string(),
+    };
+    println!(
+        "Commit {} at slot {}: {}",
+        record.commit_id,
+        record.slot.to_formatted_string(&Locale::en),
+        outcome
+    );
+    println!("  Payer:        {}", record.payer);
+    println!("  Undelegation: {}", record.requested_undelegation);
+    println!("  Included:     {}", join(&record.included_pubkeys));
+    println!("  Excluded:     {}", join(&record.excluded_pubkeys));
+    println!("  Signatures:   {}", join(&record.chain_signatures));
+}
+
+fn join(items: &[impl ToString]) -> String {
+    items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -4,15 +4,20 @@ use magicblock_accounts_db::AccountsDb;
 use solana_sdk::pubkey::Pubkey;
 use structopt::StructOpt;
 
-use crate::utils::open_ledger;
+use crate::{output::OutputFormat, utils::open_ledger};
 
 mod account;
 mod accounts;
 mod blockhash;
+mod blocks;
+mod commits;
 mod counts;
+mod output;
+mod replay_tx;
 mod transaction_details;
 mod transaction_logs;
 mod utils;
+mod verify;
 
 #[derive(Debug, StructOpt)]
 enum Command {
@@ -20,6 +25,12 @@ enum Command {
     Count {
         #[structopt(parse(from_os_str))]
         ledger_path: PathBuf,
+        #[structopt(
+            long,
+            default_value = "table",
+            help = "Output format: table, json or csv"
+        )]
+        format: OutputFormat,
     },
     #[structopt(name = "log", about = "Transaction logs")]
     Log {
@@ -45,6 +56,12 @@ enum Command {
             help = "Accounts in transaction"
         )]
         accounts: Option<Vec<String>>,
+        #[structopt(
+            long,
+            default_value = "table",
+            help = "Output format: table, json or csv"
+        )]
+        format: OutputFormat,
     },
     #[structopt(name = "sig", about = "Transaction details for signature")]
     Sig {
@@ -59,6 +76,12 @@ enum Command {
             parse(from_flag)
         )]
         ascii: bool,
+        #[structopt(
+            long,
+            default_value = "table",
+            help = "Output format: table, json or csv"
+        )]
+        format: OutputFormat,
     },
     #[structopt(name = "accounts", about = "Account details")]
     Accounts {
@@ -92,6 +115,12 @@ enum Command {
             parse(from_flag)
         )]
         count: bool,
+        #[structopt(
+            long,
+            default_value = "table",
+            help = "Output format: table, json or csv"
+        )]
+        format: OutputFormat,
     },
     #[structopt(
         name = "account",
@@ -113,6 +142,64 @@ enum Command {
         )]
         query: blockhash::BlockhashQuery,
     },
+    #[structopt(name = "block", about = "Block details with its transactions")]
+    Block {
+        #[structopt(parse(from_os_str))]
+        ledger_path: PathBuf,
+        #[structopt(help = "Slot of the block")]
+        slot: u64,
+    },
+    #[structopt(name = "range", about = "Blocks of a slot range")]
+    Range {
+        #[structopt(parse(from_os_str))]
+        ledger_path: PathBuf,
+        #[structopt(help = "Start slot")]
+        start: u64,
+        #[structopt(help = "End slot (inclusive)")]
+        end: u64,
+        #[structopt(
+            long,
+            help = "Only print transaction counts, compute units and fees per slot",
+            parse(from_flag)
+        )]
+        summary: bool,
+    },
+    #[structopt(
+        name = "commits",
+        about = "Scheduled commits and their outcome"
+    )]
+    Commits {
+        #[structopt(parse(from_os_str))]
+        ledger_path: PathBuf,
+        #[structopt(long, short, help = "Start slot")]
+        start: Option<u64>,
+        #[structopt(long, short, help = "End slot")]
+        end: Option<u64>,
+    },
+    #[structopt(
+        name = "replay-tx",
+        about = "Re-executes a transaction on the state right before it originally executed, printing all logs and compute units"
+    )]
+    ReplayTx {
+        #[structopt(parse(from_os_str))]
+        ledger_path: PathBuf,
+        #[structopt(help = "Signature")]
+        sig: String,
+    },
+    #[structopt(
+        name = "verify",
+        about = "Verifies the consistency of the ledger columns"
+    )]
+    Verify {
+        #[structopt(parse(from_os_str))]
+        ledger_path: PathBuf,
+        #[structopt(
+            long,
+            help = "Drop all slots starting at the first broken slot",
+            parse(from_flag)
+        )]
+        repair: bool,
+    },
 }
 
 #[derive(StructOpt)]
@@ -126,15 +213,17 @@ fn main() {
 
     use Command::*;
     match args.command {
-        Count { ledger_path } => {
-            counts::print_counts(&open_ledger(&ledger_path))
-        }
+        Count {
+            ledger_path,
+            format,
+        } => counts::print_counts(&open_ledger(&ledger_path), format),
         Log {
             ledger_path,
             success,
             start,
             end,
             accounts,
+            format,
         } => {
             let accounts = accounts.map(|accounts| {
                 accounts
@@ -151,16 +240,18 @@ fn main() {
                 end,
                 accounts,
                 success,
+                format,
             );
         }
         Sig {
             ledger_path,
             sig,
             ascii,
+            format,
         } => {
             let ledger = open_ledger(&ledger_path);
             transaction_details::print_transaction_details(
-                &ledger, &sig, ascii,
+                &ledger, &sig, ascii, format,
             );
         }
         Accounts {
@@ -170,6 +261,7 @@ fn main() {
             owner,
             filter,
             count,
+            format,
         } => {
             let owner = owner.map(|owner| {
                 Pubkey::from_str(&owner).expect("Invalid owner filter pubkey")
@@ -183,6 +275,7 @@ fn main() {
                 &filters,
                 rent_epoch,
                 count,
+                format,
             );
         }
         Account {
@@ -200,5 +293,41 @@ fn main() {
                 query,
             );
         }
+        Block { ledger_path, slot } => {
+            blocks::print_block(&open_ledger(&ledger_path), slot);
+        }
+        Range {
+            ledger_path,
+            start,
+            end,
+            summary,
+        } => {
+            blocks::print_slot_range(
+                &open_ledger(&ledger_path),
+                start,
+                end,
+                summary,
+            );
+        }
+        Commits {
+            ledger_path,
+            start,
+            end,
+        } => {
+            commits::print_commits(&open_ledger(&ledger_path), start, end);
+        }
+        ReplayTx { ledger_path, sig } => {
+            replay_tx::print_replayed_transaction(
+                &open_ledger(&ledger_path),
+                &ledger_path,
+                &sig,
+            );
+        }
+        Verify {
+            ledger_path,
+            repair,
+        } => {
+            verify::verify_ledger(&open_ledger(&ledger_path), repair);
+        }
     }
 }
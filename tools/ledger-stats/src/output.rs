@@ -0,0 +1,121 @@
+use std::str::FromStr;
+
+use serde_json::{Map, Value};
+
+// -----------------
+// OutputFormat
+// -----------------
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human readable tables
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use OutputFormat::*;
+        match s.to_lowercase().as_str() {
+            "table" => Ok(Table),
+            "json" => Ok(Json),
+            "csv" => Ok(Csv),
+            _ => Err(format!(
+                "Invalid format '{s}', expected one of: table, json, csv"
+            )),
+        }
+    }
+}
+
+// -----------------
+// Records
+// -----------------
+/// Rows of named values rendered as machine readable output.
+/// In JSON each row becomes an object, in CSV the column names are printed
+/// as header and nested values (i.e. lists) are encoded as JSON.
+pub(crate) struct Records {
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Records {
+    pub(crate) fn new(columns: Vec<&'static str>) -> Self {
+        Self {
+            columns,
+            rows: vec![],
+        }
+    }
+
+    pub(crate) fn push(&mut self, row: Vec<Value>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    /// Prints all rows as a JSON array
+    pub(crate) fn print(&self, format: OutputFormat) {
+        match format {
+            OutputFormat::Json => {
+                let rows = self
+                    .rows
+                    .iter()
+                    .map(|row| Value::Object(self.to_object(row)))
+                    .collect::<Vec<_>>();
+                print_json(&Value::Array(rows));
+            }
+            OutputFormat::Csv => self.print_csv(),
+            OutputFormat::Table => {
+                unreachable!("Tables are rendered by each subcommand")
+            }
+        }
+    }
+
+    /// Prints a single row as a JSON object, used when the output consists
+    /// of exactly one item
+    pub(crate) fn print_single(&self, format: OutputFormat) {
+        match (format, self.rows.as_slice()) {
+            (OutputFormat::Json, [row]) => {
+                print_json(&Value::Object(self.to_object(row)))
+            }
+            _ => self.print(format),
+        }
+    }
+
+    fn to_object(&self, row: &[Value]) -> Map<String, Value> {
+        self.columns
+            .iter()
+            .map(|column| column.to_string())
+            .zip(row.iter().cloned())
+            .collect()
+    }
+
+    fn print_csv(&self) {
+        println!("{}", self.columns.join(","));
+        for row in &self.rows {
+            let cells = row.iter().map(csv_cell).collect::<Vec<_>>();
+            println!("{}", cells.join(","));
+        }
+    }
+}
+
+fn print_json(value: &Value) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(value).expect("Failed to encode JSON")
+    );
+}
+
+fn csv_cell(value: &Value) -> String {
+    let cell = match value {
+        Value::Null => return String::new(),
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    };
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell
+    }
+}
@@ -0,0 +1,192 @@
+use std::{fs, io, path::Path, str::FromStr, sync::Arc};
+
+use magicblock_accounts_db::{config::AccountsDbConfig, StWLock};
+use magicblock_bank::{
+    bank::{Bank, FeatureOverrides},
+    genesis_utils::create_genesis_config_with_leader,
+    EPHEM_DEFAULT_MILLIS_PER_SLOT,
+};
+use magicblock_ledger::{
+    blockstore_processor::{replay_transaction, ReplayedTransaction},
+    Ledger,
+};
+use num_format::{Locale, ToFormattedString};
+use solana_sdk::{
+    account::ReadableAccount,
+    clock::Slot,
+    signature::{read_keypair_file, Signature, Signer},
+};
+use tabular::{Row, Table};
+
+use crate::utils::render_logs;
+
+pub(crate) fn print_replayed_transaction(
+    ledger: &Ledger,
+    ledger_path: &Path,
+    sig: &str,
+) {
+    let sig = Signature::from_str(sig).expect("Invalid signature");
+    let Some((slot, _)) = ledger
+        .get_transaction_status(sig, Slot::MAX)
+        .expect("Failed to get transaction status")
+    else {
+        eprintln!("Transaction status not found");
+        return;
+    };
+
+    // Replaying rolls the accounts db back to a snapshot and modifies it,
+    // thus we work on a copy in order to leave the original untouched
+    let workdir = std::env::temp_dir()
+        .join(format!("ledger-stats-replay-{}", std::process::id()));
+    copy_dir(&ledger_path.join("accountsdb"), &workdir.join("accountsdb"))
+        .expect("Failed to copy accounts db");
+
+    let bank = open_bank(ledger_path, &workdir, slot);
+    let replayed = replay_transaction(ledger, &bank, &sig);
+    drop(bank);
+    let _ = fs::remove_dir_all(&workdir);
+
+    match replayed {
+        Ok(replayed) => print_replayed(&sig, &replayed),
+        Err(err) => eprintln!("Failed to replay transaction: {}", err),
+    }
+}
+
+fn open_bank(ledger_path: &Path, adb_path: &Path, slot: Slot) -> Arc<Bank> {
+    let identity =
+        read_keypair_file(ledger_path.join("validator-keypair.json"))
+            .expect("Failed to read validator keypair from ledger")
+            .pubkey();
+    let genesis_config =
+        create_genesis_config_with_leader(u64::MAX, &identity).genesis_config;
+    let accountsdb_config = AccountsDbConfig {
+        snapshot_frequency: u64::MAX,
+        ..Default::default()
+    };
+    let bank = Bank::new(
+        &genesis_config,
+        Default::default(),
+        &accountsdb_config,
+        None,
+        None,
+        false,
+        None,
+        None,
+        EPHEM_DEFAULT_MILLIS_PER_SLOT,
+        identity,
+        StWLock::default(),
+        adb_path,
+        // Rolls the accounts db back to a snapshot taken before the slot
+        slot.saturating_sub(1),
+        &FeatureOverrides::default(),
+    )
+    .expect("Failed to create bank");
+    Arc::new(bank)
+}
+
+fn print_replayed(sig: &Signature, replayed: &ReplayedTransaction) {
+    println!(
+        "Replayed transaction {} at slot {}",
+        sig,
+        replayed.slot.to_formatted_string(&Locale::en)
+    );
+
+    let message = replayed.transaction.message();
+    let mut accounts = Table::new("{:<}  {:<}  {:>}  {:>}  {:<}")
+        .with_heading("\n++++ Accounts before execution ++++\n")
+        .with_row(
+            Row::new()
+                .with_cell("Pubkey")
+                .with_cell("Owner")
+                .with_cell("Lamports")
+                .with_cell("Data (Bytes)")
+                .with_cell("Writable"),
+        );
+    for (idx, (pubkey, account)) in replayed.pre_accounts.iter().enumerate() {
+        let row = Row::new().with_cell(pubkey.to_string());
+        let row = match account {
+            Some(account) => row
+                .with_cell(account.owner().to_string())
+                .with_cell(account.lamports().to_formatted_string(&Locale::en))
+                .with_cell(
+                    account.data().len().to_formatted_string(&Locale::en),
+                ),
+            None => row.with_cell("None").with_cell("-").with_cell("-"),
+        };
+        accounts.add_row(row.with_cell(message.is_writable(idx)));
+    }
+    println!("{}", accounts);
+
+    let (status, executed_units, loaded_accounts_data_size, logs) =
+        match &replayed.result {
+            Ok(committed) => (
+                committed.status.clone(),
+                Some(committed.executed_units),
+                Some(committed.loaded_account_stats.loaded_accounts_data_size),
+                committed.log_messages.clone(),
+            ),
+            Err(err) => (Err(err.clone()), None, None, None),
+        };
+    let format_status = |status: &Result<(), _>| match status {
+        Ok(_) => "Ok".to_string(),
+        Err(err) => format!("{:?}", err),
+    };
+    let format_units = |units: Option<u64>| {
+        units.map_or("None".to_string(), |units| {
+            units.to_formatted_string(&Locale::en)
+        })
+    };
+    let table = Table::new("{:<}  {:>}  {:>}")
+        .with_heading("\n++++ Execution ++++\n")
+        .with_row(
+            Row::new()
+                .with_cell("Field")
+                .with_cell("Recorded")
+                .with_cell("Replayed"),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Status")
+                .with_cell(format_status(&replayed.recorded_status))
+                .with_cell(format_status(&status)),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Compute Units")
+                .with_cell(format_units(replayed.recorded_compute_units))
+                .with_cell(format_units(executed_units)),
+        )
+        .with_row(
+            Row::new()
+                .with_cell("Loaded Accounts Data (Bytes)")
+                .with_cell("-")
+                .with_cell(format_units(
+                    loaded_accounts_data_size.map(u64::from),
+                )),
+        );
+    println!("{}", table);
+    if replayed.recorded_status != status
+        || replayed.recorded_compute_units != executed_units
+    {
+        println!("WARNING: the replay diverged from the recorded execution\n");
+    }
+
+    match logs {
+        Some(logs) => println!("Logs:\n{}", render_logs(&logs, "  ")),
+        None => println!("Logs: None"),
+    }
+}
+
+fn copy_dir(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let dst = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dst)?;
+        } else {
+            fs::copy(entry.path(), dst)?;
+        }
+    }
+    Ok(())
+}
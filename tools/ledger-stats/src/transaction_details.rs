@@ -3,19 +3,29 @@ use std::str::FromStr;
 use magicblock_ledger::Ledger;
 use num_format::{Locale, ToFormattedString};
 use pretty_hex::*;
-use solana_sdk::{message::VersionedMessage, signature::Signature};
-use solana_transaction_status::ConfirmedTransactionWithStatusMeta;
+use serde_json::json;
+use solana_sdk::{
+    clock::Slot, message::VersionedMessage, signature::Signature,
+};
+use solana_transaction_status::{
+    ConfirmedTransactionWithStatusMeta, TransactionStatusMeta,
+    TransactionTokenBalance,
+};
 use tabular::{Row, Table};
 
-use crate::utils::render_logs;
+use crate::{
+    output::{OutputFormat, Records},
+    utils::render_logs,
+};
 
 pub(crate) fn print_transaction_details(
     ledger: &Ledger,
     sig: &str,
     ix_data_ascii: bool,
+    format: OutputFormat,
 ) {
     let sig = Signature::from_str(sig).expect("Invalid signature");
-    let (_slot, status_meta) = match ledger
+    let (slot, status_meta) = match ledger
         .get_transaction_status(sig, u64::MAX)
         .expect("Failed to get transaction status")
     {
@@ -26,6 +36,17 @@ pub(crate) fn print_transaction_details(
         }
     };
 
+    if format != OutputFormat::Table {
+        print_transaction_details_record(
+            ledger,
+            sig,
+            slot,
+            status_meta,
+            format,
+        );
+        return;
+    }
+
     let status = match &status_meta.status {
         Ok(_) => "Ok".to_string(),
         Err(err) => format!("{:?}", err),
@@ -228,3 +249,106 @@ pub(crate) fn print_transaction_details(
         }
     }
 }
+
+fn print_transaction_details_record(
+    ledger: &Ledger,
+    sig: Signature,
+    slot: Slot,
+    status_meta: TransactionStatusMeta,
+    format: OutputFormat,
+) {
+    let tx = ledger
+        .get_complete_transaction(sig, u64::MAX)
+        .expect("Failed to get transaction");
+    let block_time = tx.as_ref().and_then(|tx| tx.block_time);
+    let (account_keys, instructions) = tx
+        .map(|tx| {
+            let message = tx.tx_with_meta.get_transaction().message;
+            let account_keys = message
+                .static_account_keys()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            let instructions = message
+                .instructions()
+                .iter()
+                .map(|ix| {
+                    json!({
+                        "program_id": account_keys
+                            .get(ix.program_id_index as usize),
+                        "accounts": ix
+                            .accounts
+                            .iter()
+                            .map(|idx| account_keys.get(*idx as usize))
+                            .collect::<Vec<_>>(),
+                        "data": hex_string(&ix.data),
+                    })
+                })
+                .collect::<Vec<_>>();
+            (account_keys, instructions)
+        })
+        .unwrap_or_default();
+
+    let mut records = Records::new(vec![
+        "signature",
+        "slot",
+        "block_time",
+        "status",
+        "fee",
+        "pre_balances",
+        "post_balances",
+        "inner_instructions",
+        "pre_token_balances",
+        "post_token_balances",
+        "rewards",
+        "loaded_writable_addresses",
+        "loaded_readonly_addresses",
+        "return_data_len",
+        "compute_units_consumed",
+        "log_messages",
+        "account_keys",
+        "instructions",
+    ]);
+    records.push(vec![
+        json!(sig.to_string()),
+        json!(slot),
+        json!(block_time),
+        json!(match &status_meta.status {
+            Ok(_) => "Ok".to_string(),
+            Err(err) => format!("{:?}", err),
+        }),
+        json!(status_meta.fee),
+        json!(status_meta.pre_balances),
+        json!(status_meta.post_balances),
+        json!(status_meta.inner_instructions.as_ref().map(|i| i.len())),
+        json!(token_amounts(&status_meta.pre_token_balances)),
+        json!(token_amounts(&status_meta.post_token_balances)),
+        json!(status_meta.rewards.as_ref().map(|rewards| rewards
+            .iter()
+            .map(|r| r.lamports)
+            .collect::<Vec<_>>())),
+        json!(status_meta.loaded_addresses.writable.len()),
+        json!(status_meta.loaded_addresses.readonly.len()),
+        json!(status_meta.return_data.as_ref().map(|d| d.data.len())),
+        json!(status_meta.compute_units_consumed),
+        json!(status_meta.log_messages),
+        json!(account_keys),
+        json!(instructions),
+    ]);
+    records.print_single(format);
+}
+
+fn hex_string(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn token_amounts(
+    balances: &Option<Vec<TransactionTokenBalance>>,
+) -> Option<Vec<String>> {
+    balances.as_ref().map(|balances| {
+        balances
+            .iter()
+            .map(|b| b.ui_token_amount.amount.clone())
+            .collect()
+    })
+}
@@ -1,9 +1,13 @@
 use std::collections::HashSet;
 
 use magicblock_ledger::Ledger;
+use serde_json::json;
 use solana_sdk::pubkey::Pubkey;
 
-use crate::utils::render_logs;
+use crate::{
+    output::{OutputFormat, Records},
+    utils::render_logs,
+};
 
 pub(crate) fn print_transaction_logs(
     ledger: &Ledger,
@@ -11,6 +15,7 @@ pub(crate) fn print_transaction_logs(
     end_slot: Option<u64>,
     accounts: Option<HashSet<Pubkey>>,
     success: bool,
+    format: OutputFormat,
 ) {
     let start_slot = start_slot.unwrap_or(0);
     let end_slot = end_slot.unwrap_or(u64::MAX);
@@ -59,6 +64,28 @@ pub(crate) fn print_transaction_logs(
         vec.sort_by_key(|(slot, _, _, _)| *slot);
         vec
     };
+    if format != OutputFormat::Table {
+        let mut records = Records::new(vec![
+            "slot",
+            "signature",
+            "matched_accounts",
+            "log_messages",
+        ]);
+        for (slot, sig, status, acc) in sorted {
+            let matched_accounts = acc.map(|acc| {
+                acc.iter().map(ToString::to_string).collect::<Vec<_>>()
+            });
+            records.push(vec![
+                json!(slot),
+                json!(sig.to_string()),
+                json!(matched_accounts),
+                json!(status.log_messages),
+            ]);
+        }
+        records.print(format);
+        return;
+    }
+
     for (slot, sig, status, acc) in sorted {
         println!("\n ------------------------------------");
         if let Some(x) = acc {
@@ -0,0 +1,56 @@
+use magicblock_ledger::Ledger;
+use num_format::{Locale, ToFormattedString};
+
+pub(crate) fn verify_ledger(ledger: &Ledger, repair: bool) {
+    let report = ledger.verify().expect("Failed to verify ledger");
+
+    let slot_range = match (report.first_slot, report.last_slot) {
+        (Some(first_slot), Some(last_slot)) => format!(
+            "{}..={}",
+            first_slot.to_formatted_string(&Locale::en),
+            last_slot.to_formatted_string(&Locale::en)
+        ),
+        _ => "none".to_string(),
+    };
+    println!(
+        "Verified slots {}: {} blocks, {} transactions, {} address signatures",
+        slot_range,
+        report.num_blocks.to_formatted_string(&Locale::en),
+        report.num_transactions.to_formatted_string(&Locale::en),
+        report
+            .num_address_signatures
+            .to_formatted_string(&Locale::en),
+    );
+    if report.num_open_slot_transactions > 0 {
+        println!(
+            "Skipped {} transactions of the open slot",
+            report
+                .num_open_slot_transactions
+                .to_formatted_string(&Locale::en),
+        );
+    }
+
+    let Some(first_broken_slot) = report.first_broken_slot() else {
+        println!("Ledger is consistent");
+        return;
+    };
+    println!("Found {} anomalies:", report.anomalies.len());
+    for anomaly in &report.anomalies {
+        println!("  {anomaly}");
+    }
+
+    if !repair {
+        println!(
+            "Run with --repair to drop all slots starting at {}",
+            first_broken_slot.to_formatted_string(&Locale::en)
+        );
+        return;
+    }
+    ledger
+        .drop_slots_from(first_broken_slot)
+        .expect("Failed to drop broken slots");
+    println!(
+        "Dropped all slots starting at {}",
+        first_broken_slot.to_formatted_string(&Locale::en)
+    );
+}
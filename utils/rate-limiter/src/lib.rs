@@ -0,0 +1,272 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use tokio::time::sleep;
+
+/// Fraction of the configured rate that is recovered with each successful
+/// request after the rate was lowered due to throttling
+const RATE_RECOVERY_STEP: f64 = 0.05;
+/// The rate is never lowered below this many requests per second
+const MIN_REQUESTS_PER_SECOND: f64 = 1.0;
+
+// -----------------
+// RateLimiterConfig
+// -----------------
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimiterConfig {
+    /// Maximum sustained requests per second, `0` disables rate limiting
+    /// while still backing off when throttled
+    pub requests_per_second: u32,
+    /// Maximum number of requests that can be sent at once after an idle
+    /// period
+    pub burst: u32,
+    /// Backoff applied after the first throttled request
+    pub initial_backoff: Duration,
+    /// Upper bound for the backoff which doubles with each throttled request
+    pub max_backoff: Duration,
+}
+
+impl Default for RateLimiterConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 50,
+            burst: 100,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+// -----------------
+// RateLimiter
+// -----------------
+#[derive(Debug)]
+struct RateLimiterState {
+    /// Tokens currently available, each request consumes one
+    tokens: f64,
+    /// Current rate at which tokens are refilled, lowered whenever we are
+    /// throttled and slowly recovering to the configured rate afterwards
+    requests_per_second: f64,
+    last_refill: Instant,
+    /// Backoff applied for the last throttled request, zero once a request
+    /// succeeded again
+    backoff: Duration,
+    backoff_until: Option<Instant>,
+}
+
+/// Token bucket rate limiter for requests to a single remote endpoint.
+///
+/// Callers wait via [Self::acquire] before each request and report its
+/// outcome via [Self::on_success] or [Self::on_throttled].
+/// When throttled all requests are paused for an exponentially growing
+/// backoff and the rate is halved (AIMD), recovering additively with each
+/// successful request.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimiterConfig,
+    state: Mutex<RateLimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimiterConfig) -> Self {
+        let state = RateLimiterState {
+            tokens: config.burst.max(1) as f64,
+            requests_per_second: config.requests_per_second as f64,
+            last_refill: Instant::now(),
+            backoff: Duration::ZERO,
+            backoff_until: None,
+        };
+        Self {
+            config,
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn config(&self) -> &RateLimiterConfig {
+        &self.config
+    }
+
+    /// Waits until a request may be sent
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire_at(Instant::now()) {
+            sleep(wait).await;
+        }
+    }
+
+    /// Consumes a token if a request may be sent at the given time,
+    /// otherwise returns how long to wait before trying again
+    fn try_acquire_at(&self, now: Instant) -> Option<Duration> {
+        let mut state = self.state.lock().expect("RateLimiter poisoned");
+        if let Some(backoff_until) = state.backoff_until {
+            if now < backoff_until {
+                return Some(backoff_until - now);
+            }
+            state.backoff_until = None;
+        }
+        if self.config.requests_per_second == 0 {
+            return None;
+        }
+
+        let capacity = self.config.burst.max(1) as f64;
+        let elapsed = now.saturating_duration_since(state.last_refill);
+        state.tokens = (state.tokens
+            + elapsed.as_secs_f64() * state.requests_per_second)
+            .min(capacity);
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - state.tokens) / state.requests_per_second,
+            ))
+        }
+    }
+
+    /// Reports a request that was not throttled
+    pub fn on_success(&self) {
+        let mut state = self.state.lock().expect("RateLimiter poisoned");
+        state.backoff = Duration::ZERO;
+        let max_requests_per_second = self.config.requests_per_second as f64;
+        state.requests_per_second = (state.requests_per_second
+            + max_requests_per_second * RATE_RECOVERY_STEP)
+            .min(max_requests_per_second);
+    }
+
+    /// Reports a request that was throttled by the remote and returns the
+    /// backoff that is applied before the next request
+    pub fn on_throttled(&self) -> Duration {
+        self.on_throttled_at(Instant::now())
+    }
+
+    fn on_throttled_at(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().expect("RateLimiter poisoned");
+        // Requests that were sent before we started backing off are
+        // likely to be throttled as well and shouldn't extend the backoff
+        if let Some(backoff_until) = state.backoff_until {
+            if now < backoff_until {
+                return backoff_until - now;
+            }
+        }
+        state.backoff = if state.backoff.is_zero() {
+            self.config.initial_backoff
+        } else {
+            state.backoff.saturating_mul(2)
+        }
+        .min(self.config.max_backoff);
+        state.backoff_until = Some(now + state.backoff);
+
+        if self.config.requests_per_second > 0 {
+            state.requests_per_second =
+                (state.requests_per_second / 2.0).max(MIN_REQUESTS_PER_SECOND);
+            state.tokens = 0.0;
+            state.last_refill = now + state.backoff;
+        }
+        state.backoff
+    }
+}
+
+/// Determines from the message of a failed request if the remote is
+/// throttling us, either explicitly via a 429 response or by timing out
+pub fn is_throttling_error(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("too many requests")
+        || message.contains("timed out")
+        || message.contains("timeout")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> RateLimiterConfig {
+        RateLimiterConfig {
+            requests_per_second: 10,
+            burst: 2,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        }
+    }
+
+    #[test]
+    fn test_burst_then_rate() {
+        let limiter = RateLimiter::new(config());
+        let now = Instant::now();
+
+        assert_eq!(limiter.try_acquire_at(now), None);
+        assert_eq!(limiter.try_acquire_at(now), None);
+        let wait = limiter.try_acquire_at(now).unwrap();
+        assert!(wait <= Duration::from_millis(100));
+
+        let later = now + Duration::from_millis(100);
+        assert_eq!(limiter.try_acquire_at(later), None);
+        assert!(limiter.try_acquire_at(later).is_some());
+    }
+
+    #[test]
+    fn test_unlimited() {
+        let limiter = RateLimiter::new(RateLimiterConfig {
+            requests_per_second: 0,
+            ..config()
+        });
+        let now = Instant::now();
+        for _ in 0..1_000 {
+            assert_eq!(limiter.try_acquire_at(now), None);
+        }
+    }
+
+    #[test]
+    fn test_exponential_backoff() {
+        let limiter = RateLimiter::new(config());
+        let now = Instant::now();
+
+        let backoff = limiter.on_throttled_at(now);
+        assert_eq!(backoff, Duration::from_millis(100));
+        assert_eq!(
+            limiter.try_acquire_at(now),
+            Some(Duration::from_millis(100))
+        );
+        // Throttled while backing off does not extend the backoff
+        assert_eq!(
+            limiter.on_throttled_at(now + Duration::from_millis(50)),
+            Duration::from_millis(50)
+        );
+
+        let now = now + backoff;
+        assert_eq!(limiter.on_throttled_at(now), Duration::from_millis(200));
+        let now = now + Duration::from_millis(200);
+        assert_eq!(limiter.on_throttled_at(now), Duration::from_millis(300));
+
+        limiter.on_success();
+        let now = now + Duration::from_millis(300);
+        assert_eq!(limiter.on_throttled_at(now), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_rate_halved_when_throttled() {
+        let limiter = RateLimiter::new(config());
+        let now = Instant::now();
+        let backoff = limiter.on_throttled_at(now);
+
+        // After the backoff tokens refill at half the rate
+        let now = now + backoff;
+        assert_eq!(
+            limiter.try_acquire_at(now),
+            Some(Duration::from_millis(200))
+        );
+        let now = now + Duration::from_millis(200);
+        assert_eq!(limiter.try_acquire_at(now), None);
+    }
+
+    #[test]
+    fn test_is_throttling_error() {
+        assert!(is_throttling_error(
+            "HTTP status client error (429 Too Many Requests) for url"
+        ));
+        assert!(is_throttling_error("operation timed out"));
+        assert!(!is_throttling_error("AccountNotFound"));
+    }
+}